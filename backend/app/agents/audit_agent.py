@@ -2,21 +2,25 @@
 IRS Audit Defense AI Agent
 Handles audit representation, response generation, and strategy
 """
-import anthropic
 from typing import Dict, List, Any, Optional
 import os
 import json
 
+from app.agents.claude_client import ClaudeClient
+from app.agents.prompts import build_tax_system_prompt, find_referral, scoped_reply
+from app.agents.redaction import PIIRedactor
+
 class AuditDefenseAgent:
     """AI agent for IRS audit defense and representation"""
     
     def __init__(self):
-        self.client = anthropic.Anthropic(api_key=os.getenv("ANTHROPIC_API_KEY", ""))
+        self.client = ClaudeClient()
         self.model = "claude-sonnet-4-20250514"
     
     async def analyze_audit_notice(self, notice_text: str, client_documents: Dict) -> Dict[str, Any]:
         """Analyze IRS audit notice and generate defense strategy"""
         
+        redactor = PIIRedactor()
         prompt = f"""You are an expert CPA specializing in IRS audit defense. 
 
 Analyze this IRS audit notice and provide a comprehensive defense strategy:
@@ -25,7 +29,7 @@ AUDIT NOTICE:
 {notice_text}
 
 CLIENT DOCUMENTS AVAILABLE:
-{json.dumps(client_documents, indent=2)}
+{json.dumps(redactor.redact_data(client_documents), indent=2)}
 
 Provide:
 1. Summary of what the IRS is questioning
@@ -38,13 +42,18 @@ Provide:
 
 Format as JSON with these exact keys."""
 
-        response = self.client.messages.create(
+        response = await self.client.create_message(
             model=self.model,
             max_tokens=4000,
-            messages=[{"role": "user", "content": prompt}]
+            system=build_tax_system_prompt(),
+            messages=redactor.redact_messages([{"role": "user", "content": prompt}])
         )
         
-        return self._parse_defense_strategy(response.content[0].text)
+        text = redactor.restore(response.content[0].text)
+        referral = find_referral(text)
+        if referral:
+            return {"analysis": referral["text"], "response": referral}
+        return self._parse_defense_strategy(text)
     
     async def prepare_audit_response(
         self, 
@@ -54,6 +63,7 @@ Format as JSON with these exact keys."""
     ) -> Dict[str, Any]:
         """Generate professional audit response letter"""
         
+        redactor = PIIRedactor()
         prompt = f"""As a CPA representing a client before the IRS, draft a professional audit response letter.
 
 AUDIT ISSUE: {audit_issue}
@@ -70,21 +80,26 @@ Draft a formal response that:
 
 Use proper formatting for IRS correspondence."""
 
-        response = self.client.messages.create(
+        response = await self.client.create_message(
             model=self.model,
             max_tokens=3000,
-            messages=[{"role": "user", "content": prompt}]
+            system=build_tax_system_prompt(),
+            messages=redactor.redact_messages([{"role": "user", "content": prompt}])
         )
         
+        # The letter goes to the IRS, so it doesn't get the disclaimer
+        reply = scoped_reply(redactor.restore(response.content[0].text), disclaimer=False)
         return {
-            "response_letter": response.content[0].text,
+            "response_letter": reply["text"],
             "confidence_level": "high",
-            "estimated_success_rate": 0.75
+            "estimated_success_rate": 0.75,
+            "response": reply,
         }
     
     async def research_tax_position(self, tax_issue: str) -> Dict[str, Any]:
         """Research tax law to support audit defense"""
         
+        redactor = PIIRedactor()
         prompt = f"""Research this tax issue and provide authority:
 
 TAX ISSUE: {tax_issue}
@@ -99,15 +114,18 @@ Find and cite:
 Explain how each authority supports the taxpayer position.
 Identify any contrary authority and distinguish it."""
 
-        response = self.client.messages.create(
+        response = await self.client.create_message(
             model=self.model,
             max_tokens=3000,
-            messages=[{"role": "user", "content": prompt}]
+            system=build_tax_system_prompt(),
+            messages=redactor.redact_messages([{"role": "user", "content": prompt}])
         )
         
+        reply = scoped_reply(redactor.restore(response.content[0].text))
         return {
-            "research_memo": response.content[0].text,
-            "authorities_found": []
+            "research_memo": reply["text"],
+            "authorities_found": [],
+            "response": reply,
         }
     
     def _parse_defense_strategy(self, response_text: str) -> Dict[str, Any]:
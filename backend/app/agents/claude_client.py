@@ -0,0 +1,150 @@
+"""
+Claude API Client
+Shared Anthropic client with client-side rate limiting and request queueing
+"""
+import anthropic
+from typing import Dict, Any, Callable, Optional
+from collections import deque
+import asyncio
+import os
+import threading
+import time
+
+from app.utils.secret_store import get_api_key
+from app.utils.settings_store import AppSettings, SettingsStore
+
+
+class RequestScheduler:
+    """
+    Queue for outbound Claude calls
+
+    Caps the number of in-flight requests and the number of requests started
+    per rolling window. Excess calls wait in line instead of tripping the
+    provider's rate limits.
+    """
+
+    def __init__(
+        self,
+        max_concurrency: int = 4,
+        requests_per_minute: int = 50,
+        window_seconds: float = 60.0,
+    ):
+        if max_concurrency < 1 or requests_per_minute < 1:
+            raise ValueError("Concurrency and requests per minute must be at least 1")
+
+        self.max_concurrency = max_concurrency
+        self.requests_per_minute = requests_per_minute
+        self.window_seconds = window_seconds
+        self._started: deque = deque()
+        self._lock = threading.Lock()
+        # asyncio primitives belong to one event loop, so keep one semaphore per loop
+        self._semaphores: Dict[int, asyncio.Semaphore] = {}
+        self.in_flight = 0
+        self.queued = 0
+
+    def _semaphore(self) -> asyncio.Semaphore:
+        """Get the concurrency semaphore for the running event loop"""
+        loop_id = id(asyncio.get_running_loop())
+        if loop_id not in self._semaphores:
+            self._semaphores[loop_id] = asyncio.Semaphore(self.max_concurrency)
+        return self._semaphores[loop_id]
+
+    async def _wait_for_window(self) -> None:
+        """Block until starting another request stays within the per-minute budget"""
+        while True:
+            with self._lock:
+                now = time.monotonic()
+                while self._started and self._started[0] <= now - self.window_seconds:
+                    self._started.popleft()
+                if len(self._started) < self.requests_per_minute:
+                    self._started.append(now)
+                    return
+                wait = self._started[0] + self.window_seconds - now
+            await asyncio.sleep(wait)
+
+    async def run(self, func: Callable[..., Any], *args: Any, **kwargs: Any) -> Any:
+        """
+        Run a blocking API call once a slot is free
+
+        Args:
+            func: Blocking function (e.g. client.messages.create)
+            *args, **kwargs: Passed through to func
+
+        Returns:
+            Whatever func returns
+        """
+        self.queued += 1
+        started = False
+        try:
+            async with self._semaphore():
+                await self._wait_for_window()
+                self.queued -= 1
+                started = True
+                self.in_flight += 1
+                try:
+                    return await asyncio.to_thread(func, *args, **kwargs)
+                finally:
+                    self.in_flight -= 1
+        finally:
+            # Cancelled while still waiting in line
+            if not started:
+                self.queued -= 1
+
+    def get_stats(self) -> Dict[str, Any]:
+        """Current scheduler load"""
+        return {
+            "max_concurrency": self.max_concurrency,
+            "requests_per_minute": self.requests_per_minute,
+            "in_flight": self.in_flight,
+            "queued": self.queued,
+        }
+
+
+_scheduler: Optional[RequestScheduler] = None
+
+
+def get_scheduler() -> RequestScheduler:
+    """
+    Get the process-wide scheduler shared by every agent
+
+    Limits come from CLAUDE_MAX_CONCURRENCY and CLAUDE_REQUESTS_PER_MINUTE.
+    """
+    global _scheduler
+    if _scheduler is None:
+        _scheduler = RequestScheduler(
+            max_concurrency=int(os.getenv("CLAUDE_MAX_CONCURRENCY", "4")),
+            requests_per_minute=int(os.getenv("CLAUDE_REQUESTS_PER_MINUTE", "50")),
+        )
+    return _scheduler
+
+
+def client_options(settings: AppSettings) -> Dict[str, Any]:
+    """
+    Anthropic client arguments for the endpoint and proxy settings
+
+    Without a proxy setting the SDK still honors HTTPS_PROXY/NO_PROXY from the environment.
+    """
+    options: Dict[str, Any] = {}
+    if settings.ai_base_url:
+        options["base_url"] = settings.ai_base_url
+    if settings.ai_proxy_url:
+        options["http_client"] = anthropic.DefaultHttpxClient(proxy=settings.ai_proxy_url)
+    return options
+
+
+class ClaudeClient:
+    """Anthropic client whose calls all go through the shared request scheduler"""
+
+    def __init__(
+        self,
+        scheduler: Optional[RequestScheduler] = None,
+        api_key: Optional[str] = None,
+        settings: Optional[AppSettings] = None,
+    ):
+        options = client_options(settings or SettingsStore().get_settings())
+        self._client = anthropic.Anthropic(api_key=api_key or get_api_key(), **options)
+        self.scheduler = scheduler or get_scheduler()
+
+    async def create_message(self, **kwargs: Any) -> Any:
+        """Queue a messages.create call (same arguments as the Anthropic SDK)"""
+        return await self.scheduler.run(self._client.messages.create, **kwargs)
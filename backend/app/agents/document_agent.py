@@ -2,17 +2,20 @@
 Document Analysis AI Agent
 Processes tax documents (W-2, 1099, receipts, etc.)
 """
-import anthropic
 from typing import Dict, List, Any, Optional
 import os
 import json
 import base64
 
+from app.agents.claude_client import ClaudeClient
+from app.agents.prompts import build_tax_system_prompt
+from app.agents.redaction import PIIRedactor
+
 class DocumentAnalysisAgent:
-    """AI agent for analyzing tax documents"""
+    """AI agent for analyzing tax documents (reading a document isn't advice, so its prompts leave out the scope)"""
     
     def __init__(self):
-        self.client = anthropic.Anthropic(api_key=os.getenv("ANTHROPIC_API_KEY", ""))
+        self.client = ClaudeClient()
         self.model = "claude-sonnet-4-20250514"
     
     async def analyze_document(
@@ -46,9 +49,10 @@ Extract ALL information including:
 
 Format as structured JSON with clear field names."""
 
-        response = self.client.messages.create(
+        response = await self.client.create_message(
             model=self.model,
             max_tokens=2000,
+            system=build_tax_system_prompt(scope=False),
             messages=[{
                 "role": "user",
                 "content": [
@@ -78,9 +82,10 @@ Format as structured JSON with clear field names."""
     async def _analyze_structured(self, doc_type: str, data: Dict) -> Dict[str, Any]:
         """Analyze structured document data"""
         
+        redactor = PIIRedactor()
         prompt = f"""Analyze this {doc_type} tax document data:
 
-{json.dumps(data, indent=2)}
+{json.dumps(redactor.redact_data(data), indent=2)}
 
 Provide:
 1. Verification that all required fields are present
@@ -91,14 +96,15 @@ Provide:
 
 Format as detailed analysis."""
 
-        response = self.client.messages.create(
+        response = await self.client.create_message(
             model=self.model,
             max_tokens=1500,
-            messages=[{"role": "user", "content": prompt}]
+            system=build_tax_system_prompt(scope=False),
+            messages=redactor.redact_messages([{"role": "user", "content": prompt}])
         )
         
         return {
-            "analysis": response.content[0].text,
+            "analysis": redactor.restore(response.content[0].text),
             "document_type": doc_type,
             "data_validated": True,
             "issues_found": []
@@ -137,9 +143,10 @@ Format as detailed analysis."""
                     doc_summary["deductible_expenses"] += doc.get("amount", 0)
         
         # Generate comprehensive analysis
+        redactor = PIIRedactor()
         prompt = f"""Review this complete set of tax documents for a client:
 
-{json.dumps(doc_summary, indent=2)}
+{json.dumps(redactor.redact_data(doc_summary), indent=2)}
 
 Provide:
 1. Summary of all income sources
@@ -149,15 +156,16 @@ Provide:
 5. Recommendations for tax preparation
 6. Risk assessment"""
 
-        response = self.client.messages.create(
+        response = await self.client.create_message(
             model=self.model,
             max_tokens=2000,
-            messages=[{"role": "user", "content": prompt}]
+            system=build_tax_system_prompt(scope=False),
+            messages=redactor.redact_messages([{"role": "user", "content": prompt}])
         )
         
         return {
             "document_summary": doc_summary,
-            "comprehensive_analysis": response.content[0].text,
+            "comprehensive_analysis": redactor.restore(response.content[0].text),
             "ready_for_preparation": True
         }
     
@@ -168,13 +176,14 @@ Provide:
     ) -> Dict[str, Any]:
         """Identify and categorize potential deductions from receipts"""
         
+        redactor = PIIRedactor()
         prompt = f"""Analyze these receipts and taxpayer situation to identify deductions:
 
 RECEIPTS:
-{json.dumps(receipts, indent=2)}
+{json.dumps(redactor.redact_data(receipts), indent=2)}
 
 TAXPAYER SITUATION:
-{json.dumps(taxpayer_situation, indent=2)}
+{json.dumps(redactor.redact_data(taxpayer_situation), indent=2)}
 
 Categorize each receipt as:
 1. Deductible business expense
@@ -185,14 +194,15 @@ Categorize each receipt as:
 
 Provide reasoning for each categorization and calculate totals by category."""
 
-        response = self.client.messages.create(
+        response = await self.client.create_message(
             model=self.model,
             max_tokens=2000,
-            messages=[{"role": "user", "content": prompt}]
+            system=build_tax_system_prompt(scope=False),
+            messages=redactor.redact_messages([{"role": "user", "content": prompt}])
         )
         
         return {
-            "deduction_analysis": response.content[0].text,
+            "deduction_analysis": redactor.restore(response.content[0].text),
             "total_deductions_found": len([r for r in receipts if r.get("deductible")]),
             "requires_substantiation": []
         }
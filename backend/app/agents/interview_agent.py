@@ -0,0 +1,366 @@
+"""
+Tax Interview AI Agent
+Guided Q&A that populates a stored return through tool calls
+"""
+from typing import Dict, List, Any, Optional
+import os
+import json
+from datetime import datetime
+
+from app.agents.claude_client import ClaudeClient
+from app.agents.prompts import build_tax_system_prompt, check_scope, scoped_reply
+from app.agents.redaction import PIIRedactor
+from app.tax_engine.tax_calculator import FilingStatus
+from app.utils.conversation_store import ConversationStore
+from app.utils.return_store import ReturnStore
+
+
+class InterviewAgent:
+    """AI agent that interviews the taxpayer and records answers on a return"""
+
+    # Interview sections in order, with the opening question for each
+    SECTIONS = [
+        ("filing_status", "What was your marital status on December 31, and did you pay more than half the cost of keeping up a home for a qualifying person?"),
+        ("dependents", "Who, if anyone, can you claim as a dependent? For each, tell me their name, relationship to you, and how many months they lived with you."),
+        ("income_sources", "What income did you have this year? Include jobs (W-2), freelance work, interest, dividends, investment sales, retirement distributions, and anything else."),
+        ("life_events", "Did anything major happen this year - marriage, divorce, a new child, buying or selling a home, moving states, starting a business, or large medical bills?"),
+    ]
+
+    # Optional amounts the add_income_source tool passes through to the income source
+    INCOME_SOURCE_AMOUNT_FIELDS = ["qualified_dividends", "tax_exempt_interest", "exempt_interest_dividends"]
+
+    # Tools the model uses to record answers. The model never computes tax;
+    # it only turns what the taxpayer said into records.
+    TOOLS = [
+        {
+            "name": "set_filing_status",
+            "description": "Record the taxpayer's filing status.",
+            "input_schema": {
+                "type": "object",
+                "properties": {
+                    "filing_status": {"type": "string", "enum": [s.value for s in FilingStatus]},
+                },
+                "required": ["filing_status"],
+            },
+        },
+        {
+            "name": "add_dependent",
+            "description": "Record one dependent the taxpayer described.",
+            "input_schema": {
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string"},
+                    "relationship": {"type": "string"},
+                    "months_lived_with": {"type": "integer", "minimum": 0, "maximum": 12},
+                },
+                "required": ["name", "relationship"],
+            },
+        },
+        {
+            "name": "add_income_source",
+            "description": "Record one income source with the amount the taxpayer stated.",
+            "input_schema": {
+                "type": "object",
+                "properties": {
+                    "type": {"type": "string", "enum": ReturnStore.INCOME_TYPES},
+                    "amount": {"type": "number", "minimum": 0},
+                    "description": {"type": "string"},
+                    "withholding": {"type": "number", "minimum": 0},
+                    "qualified_dividends": {
+                        "type": "number", "minimum": 0,
+                        "description": "For dividends: the qualified part of the amount (1099-DIV box 1b)",
+                    },
+                    "tax_exempt_interest": {
+                        "type": "number", "minimum": 0,
+                        "description": "For interest: tax-exempt interest, not part of the amount (1099-INT box 8)",
+                    },
+                    "exempt_interest_dividends": {
+                        "type": "number", "minimum": 0,
+                        "description": "For dividends: exempt-interest dividends, not in the amount (1099-DIV box 12)",
+                    },
+                },
+                "required": ["type", "amount"],
+            },
+        },
+        {
+            "name": "add_deduction",
+            "description": "Record one itemized deduction with the amount the taxpayer stated.",
+            "input_schema": {
+                "type": "object",
+                "properties": {
+                    "category": {"type": "string", "enum": ReturnStore.DEDUCTION_CATEGORIES},
+                    "amount": {"type": "number", "minimum": 0},
+                    "description": {"type": "string"},
+                },
+                "required": ["category", "amount"],
+            },
+        },
+        {
+            "name": "record_life_event",
+            "description": "Record a life event that may affect the return.",
+            "input_schema": {
+                "type": "object",
+                "properties": {
+                    "event": {"type": "string"},
+                    "details": {"type": "string"},
+                },
+                "required": ["event"],
+            },
+        },
+        {
+            "name": "complete_section",
+            "description": "Call when the current interview section has been fully answered.",
+            "input_schema": {"type": "object", "properties": {}},
+        },
+    ]
+
+    def __init__(
+        self,
+        return_store: Optional[ReturnStore] = None,
+        conversation_store: Optional[ConversationStore] = None,
+    ):
+        self.client = ClaudeClient()
+        self.model = "claude-sonnet-4-20250514"
+        self.return_store = return_store or ReturnStore()
+        self.conversation_store = conversation_store or ConversationStore()
+
+    def start_interview(self, return_id: str) -> Dict[str, Any]:
+        """
+        Start (or restart) the interview for a return
+
+        Args:
+            return_id: Return to populate
+
+        Returns:
+            Interview progress plus the first question
+        """
+        tax_return = self._load_return(return_id)
+        session_id = f"interview_{return_id}"
+        self.conversation_store.clear_conversation(session_id)
+
+        tax_return["interview"] = {
+            "session_id": session_id,
+            "current_section": self.SECTIONS[0][0],
+            "completed_sections": [],
+            "life_events": [],
+            "started_at": datetime.utcnow().isoformat(),
+            "completed": False,
+        }
+        self.return_store.save_return(tax_return)
+
+        question = self.SECTIONS[0][1]
+        self.conversation_store.save_message(
+            session_id, "assistant", question, metadata={"section": self.SECTIONS[0][0]}
+        )
+        return self.progress_summary(tax_return, question)
+
+    async def answer(self, return_id: str, answer: str) -> Dict[str, Any]:
+        """
+        Process the taxpayer's answer to the current question
+
+        Args:
+            return_id: Return being populated
+            answer: Taxpayer's free-text answer
+
+        Returns:
+            Interview progress, records created, the next question, and the response
+            (needs_human_cpa instead of a question when the return's year is outside the AI's scope)
+        """
+        tax_return = self._load_return(return_id)
+        interview = tax_return.get("interview")
+        if not interview:
+            raise ValueError("Interview has not been started for this return")
+        if interview["completed"]:
+            raise ValueError("Interview is already complete")
+
+        referral = check_scope(tax_year=tax_return["tax_year"])
+        if referral:
+            result = self.progress_summary(tax_return, referral["text"])
+            result["records_created"] = []
+            result["response"] = referral
+            return result
+
+        section = interview["current_section"]
+        session_id = interview["session_id"]
+        self.conversation_store.save_message(
+            session_id, "user", answer, metadata={"section": section}
+        )
+
+        history = [
+            {"role": msg["role"], "content": msg["content"]}
+            for msg in self.conversation_store.get_messages(session_id)
+        ]
+        # Anthropic requires the conversation to open with a user turn
+        if history and history[0]["role"] == "assistant":
+            history.insert(0, {"role": "user", "content": "I'm ready to start my tax interview."})
+
+        redactor = PIIRedactor()
+        redactor.redact_data(tax_return.get("taxpayer", {}))
+        system_prompt = f"""You are a CPA conducting a structured tax interview for tax year {tax_return['tax_year']}.
+
+CURRENT SECTION: {section}
+
+Use the tools to record exactly what the taxpayer told you - never invent amounts or people.
+Call complete_section once this section is fully answered, then ask the opening
+question of the next section. If something is unclear, ask one short follow-up question instead.
+Placeholders like [NAME_1] stand in for private details; use them as-is."""
+
+        response = await self.client.create_message(
+            model=self.model,
+            max_tokens=1000,
+            system=build_tax_system_prompt(system_prompt),
+            tools=self.TOOLS,
+            messages=redactor.redact_messages(history),
+        )
+
+        records = []
+        section_done = False
+        reply_parts = []
+        for block in response.content:
+            if block.type == "tool_use":
+                if block.name == "complete_section":
+                    section_done = True
+                    continue
+                tool_input = json.loads(redactor.restore(json.dumps(block.input)))
+                records.append(self.apply_tool_call(tax_return, block.name, tool_input))
+            elif block.type == "text":
+                reply_parts.append(redactor.restore(block.text))
+
+        tax_return = self._load_return(return_id)
+        interview = tax_return["interview"]
+        if section_done:
+            interview["completed_sections"].append(section)
+            next_section = self._next_section(section)
+            if next_section is None:
+                interview["completed"] = True
+                interview["current_section"] = None
+                interview["completed_at"] = datetime.utcnow().isoformat()
+            else:
+                interview["current_section"] = next_section
+        self.return_store.save_return(tax_return)
+
+        if interview["completed"]:
+            question = "That covers everything. Review your return and run the calculation when you're ready."
+        elif reply_parts:
+            question = "\n".join(reply_parts)
+        else:
+            question = dict(self.SECTIONS)[interview["current_section"]]
+        # Questions aren't advice, so no disclaimer; a referral replaces the question
+        reply = scoped_reply(question, disclaimer=False)
+        question = reply["text"]
+
+        self.conversation_store.save_message(
+            session_id, "assistant", question, metadata={"section": interview["current_section"]}
+        )
+
+        result = self.progress_summary(tax_return, question)
+        result["records_created"] = records
+        result["response"] = reply
+        return result
+
+    def apply_tool_call(
+        self,
+        tax_return: Dict[str, Any],
+        tool_name: str,
+        tool_input: Dict[str, Any],
+    ) -> Dict[str, Any]:
+        """
+        Apply one model tool call to the stored return
+
+        Args:
+            tax_return: Return being populated (used for its ID)
+            tool_name: Name of the tool the model called
+            tool_input: Tool arguments
+
+        Returns:
+            Summary of the record that was created or updated
+        """
+        return_id = tax_return["return_id"]
+
+        if tool_name == "set_filing_status":
+            status = FilingStatus(tool_input["filing_status"]).value
+            stored = self._load_return(return_id)
+            stored["filing_status"] = status
+            self.return_store.save_return(stored)
+            return {"tool": tool_name, "filing_status": status}
+
+        if tool_name == "add_dependent":
+            dependent = self.return_store.add_dependent(
+                return_id,
+                name=tool_input["name"],
+                relationship=tool_input["relationship"],
+                months_lived_with=tool_input.get("months_lived_with"),
+            )
+            return {"tool": tool_name, "record": dependent}
+
+        if tool_name == "add_income_source":
+            source = self.return_store.add_income_source(
+                return_id,
+                source_type=tool_input["type"],
+                amount=float(tool_input["amount"]),
+                description=tool_input.get("description", ""),
+                withholding=float(tool_input.get("withholding", 0)),
+                source="interview",
+                **{field: float(tool_input[field]) for field in self.INCOME_SOURCE_AMOUNT_FIELDS
+                   if tool_input.get(field) is not None},
+            )
+            return {"tool": tool_name, "record": source}
+
+        if tool_name == "add_deduction":
+            deduction = self.return_store.add_deduction(
+                return_id,
+                category=tool_input["category"],
+                amount=float(tool_input["amount"]),
+                description=tool_input.get("description", ""),
+                source="interview",
+            )
+            return {"tool": tool_name, "record": deduction}
+
+        if tool_name == "record_life_event":
+            stored = self._load_return(return_id)
+            event = {"event": tool_input["event"], "details": tool_input.get("details", "")}
+            stored["interview"]["life_events"].append(event)
+            self.return_store.save_return(stored)
+            return {"tool": tool_name, "record": event}
+
+        raise ValueError(f"Unknown interview tool: {tool_name}")
+
+    @classmethod
+    def progress_summary(
+        cls,
+        tax_return: Dict[str, Any],
+        question: Optional[str] = None,
+    ) -> Dict[str, Any]:
+        """
+        Build the interview progress payload for a return
+
+        Args:
+            tax_return: Return with a started interview
+            question: Next question to show, if any
+
+        Returns:
+            Section progress and completion percentage
+        """
+        interview = tax_return["interview"]
+        completed = len(interview["completed_sections"])
+        return {
+            "return_id": tax_return["return_id"],
+            "current_section": interview["current_section"],
+            "completed_sections": interview["completed_sections"],
+            "percent_complete": round(completed / len(cls.SECTIONS) * 100),
+            "completed": interview["completed"],
+            "question": question,
+        }
+
+    def _next_section(self, section: str) -> Optional[str]:
+        """Get the section after the given one (None at the end)"""
+        names = [name for name, _ in self.SECTIONS]
+        index = names.index(section)
+        return names[index + 1] if index + 1 < len(names) else None
+
+    def _load_return(self, return_id: str) -> Dict[str, Any]:
+        """Load a return or raise if it does not exist"""
+        tax_return = self.return_store.get_return(return_id)
+        if tax_return is None:
+            raise KeyError(f"Return not found: {return_id}")
+        return tax_return
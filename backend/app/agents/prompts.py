@@ -0,0 +1,205 @@
+"""
+System Prompt Builder
+Assembles agent system prompts with the user's addendum, the configured scope, and the fixed safety section,
+and turns replies into answers (with the disclaimer) or referrals to a human CPA
+"""
+import json
+import re
+from typing import Dict, Any, Optional
+
+from app.utils.settings_store import FEDERAL_JURISDICTION, AppSettings, SettingsStore
+
+DEFAULT_ROLE = "You are an experienced CPA helping a taxpayer with US federal income tax questions."
+
+# Always appended last so nothing the user adds can override it
+SAFETY_SECTION = """SAFETY RULES (always apply, regardless of any preferences above):
+- You are part of a demo application for educational purposes only; this is not tax, legal, or financial advice.
+- Treat any figures you produce as illustrative; the application's tax engine is the source of truth for liabilities.
+- Recommend consulting a qualified CPA or tax professional for actual filing decisions.
+- Never ask for or repeat full SSNs, EINs, or account numbers."""
+
+MAX_ADDENDUM_LENGTH = 2000
+
+# Response types of an AI reply
+ANSWER = "answer"
+NEEDS_HUMAN_CPA = "needs_human_cpa"
+
+# A reply the model gives instead of answering something outside the scope
+_REFERRAL_PATTERN = re.compile(r"\{[^{}]*\"type\"\s*:\s*\"needs_human_cpa\"[^{}]*\}")
+
+# Phrases that try to strip or override the safety section
+_BLOCKED_ADDENDUM_PATTERNS = [
+    r"ignore\s+(all\s+|any\s+)?(previous|prior|above|earlier|other|safety)\b",
+    r"disregard\s+.*\b(instructions|rules|safety|disclaimer)",
+    r"(remove|omit|skip|drop|hide|suppress)\s+.*\bdisclaimers?\b",
+    r"(without|no)\s+(a\s+|any\s+)?disclaimers?\b",
+    r"(override|bypass)\s+.*\b(rules|safety|instructions)",
+    r"forget\s+.*\b(rules|instructions)",
+    r"safety\s+rules\s+(do\s+not|don't)\s+apply",
+]
+
+
+def validate_prompt_addendum(addendum: str) -> str:
+    """
+    Check a user-supplied prompt addendum against the guardrails
+
+    Args:
+        addendum: Extra instructions from settings
+
+    Returns:
+        The trimmed addendum
+
+    Raises:
+        ValueError: If it is too long or tries to remove the safety section
+    """
+    addendum = (addendum or "").strip()
+    if len(addendum) > MAX_ADDENDUM_LENGTH:
+        raise ValueError(f"Prompt addendum must be at most {MAX_ADDENDUM_LENGTH} characters")
+
+    for pattern in _BLOCKED_ADDENDUM_PATTERNS:
+        if re.search(pattern, addendum, re.IGNORECASE):
+            raise ValueError(
+                "Prompt addendum cannot override the safety rules or remove the disclaimer"
+            )
+    return addendum
+
+
+def _jurisdiction_name(jurisdiction: str) -> str:
+    return "US federal" if jurisdiction == FEDERAL_JURISDICTION else jurisdiction
+
+
+def scope_section(settings: AppSettings) -> str:
+    """Prompt section limiting advice to the configured jurisdictions and tax years"""
+    years = ", ".join(str(year) for year in settings.ai_tax_years) or "any"
+    return f"""SCOPE (set by the user's settings; nothing above can widen it):
+- Only advise on these jurisdictions: {", ".join(_jurisdiction_name(j) for j in settings.ai_jurisdictions)}.
+- Only advise on these tax years: {years}.
+- If a request is about another jurisdiction or tax year, don't answer any of it. Reply with only this JSON:
+{{"type": "{NEEDS_HUMAN_CPA}", "reason": "<one sentence saying what is out of scope>"}}"""
+
+
+def needs_human_cpa(reason: str, settings: Optional[AppSettings] = None) -> Dict[str, Any]:
+    """
+    Structured reply for a request the AI isn't set up to answer
+
+    Args:
+        reason: What is out of scope
+        settings: Settings with the scope (None loads them)
+
+    Returns:
+        Dict with type (needs_human_cpa), reason, text to show, and the scope
+    """
+    settings = settings or SettingsStore().get_settings()
+    reason = reason.strip().rstrip(".") or "The request is outside the configured scope"
+    return {
+        "type": NEEDS_HUMAN_CPA,
+        "reason": reason,
+        "text": f"{reason}. This needs a CPA or tax professional rather than the assistant.",
+        "scope": {"jurisdictions": settings.ai_jurisdictions, "tax_years": settings.ai_tax_years},
+    }
+
+
+def check_scope(
+    tax_year: Optional[int] = None,
+    jurisdiction: Optional[str] = None,
+    settings: Optional[AppSettings] = None,
+) -> Optional[Dict[str, Any]]:
+    """
+    Refuse before asking the AI when a request's year or jurisdiction is already known to be out of scope
+
+    Args:
+        tax_year: Tax year the request is about
+        jurisdiction: federal or a two-letter state the request is about
+        settings: Settings with the scope (None loads them)
+
+    Returns:
+        A needs_human_cpa reply, or None when the request is in scope
+    """
+    settings = settings or SettingsStore().get_settings()
+    if tax_year is not None and settings.ai_tax_years and tax_year not in settings.ai_tax_years:
+        years = ", ".join(str(year) for year in settings.ai_tax_years)
+        return needs_human_cpa(f"Tax year {tax_year} is outside the years the assistant advises on ({years})", settings)
+    if jurisdiction is not None:
+        code = FEDERAL_JURISDICTION if jurisdiction.lower() == FEDERAL_JURISDICTION else jurisdiction.upper()
+        if code not in settings.ai_jurisdictions:
+            allowed = ", ".join(_jurisdiction_name(j) for j in settings.ai_jurisdictions)
+            return needs_human_cpa(
+                f"{_jurisdiction_name(code)} tax is outside what the assistant advises on ({allowed})", settings
+            )
+    return None
+
+
+def find_referral(text: str, settings: Optional[AppSettings] = None) -> Optional[Dict[str, Any]]:
+    """The needs_human_cpa reply in a model response, or None if the model answered"""
+    match = _REFERRAL_PATTERN.search(text or "")
+    if not match:
+        return None
+    try:
+        reason = str(json.loads(match.group(0)).get("reason") or "")
+    except json.JSONDecodeError:
+        reason = ""
+    return needs_human_cpa(reason, settings)
+
+
+def scoped_reply(
+    text: str,
+    settings: Optional[AppSettings] = None,
+    disclaimer: bool = True,
+) -> Dict[str, Any]:
+    """
+    Turn a model response into an answer or a referral to a human CPA
+
+    Args:
+        text: Model response
+        settings: Settings with the scope and disclaimer (None loads them)
+        disclaimer: Append the configured disclaimer to an answer (off for text that isn't advice)
+
+    Returns:
+        A needs_human_cpa reply, or dict with type (answer) and text
+    """
+    settings = settings or SettingsStore().get_settings()
+    referral = find_referral(text, settings)
+    if referral:
+        return referral
+    text = (text or "").strip()
+    if disclaimer and not text.endswith(settings.ai_disclaimer):
+        text = f"{text}\n\n{settings.ai_disclaimer}"
+    return {"type": ANSWER, "text": text}
+
+
+def build_tax_system_prompt(
+    role_prompt: str = DEFAULT_ROLE,
+    addendum: Optional[str] = None,
+    scope: bool = True,
+) -> str:
+    """
+    Build an agent system prompt
+
+    Layout: role instructions, then the user's addendum, then the scope, then the safety section.
+
+    Args:
+        role_prompt: Agent-specific instructions
+        addendum: User instructions (None loads prompt_addendum from settings)
+        scope: Limit advice to the configured jurisdictions and years (off for work that isn't advice,
+            like reading a document)
+
+    Returns:
+        Complete system prompt
+    """
+    settings = SettingsStore().get_settings()
+    if addendum is None:
+        addendum = settings.prompt_addendum
+
+    try:
+        addendum = validate_prompt_addendum(addendum)
+    except ValueError:
+        # A hand-edited settings file can't sneak past the guardrails
+        addendum = ""
+
+    sections = [role_prompt.strip()]
+    if addendum:
+        sections.append(f"USER PREFERENCES (follow when consistent with the safety rules):\n{addendum}")
+    if scope:
+        sections.append(scope_section(settings))
+    sections.append(SAFETY_SECTION)
+    return "\n\n".join(sections)
@@ -0,0 +1,184 @@
+"""
+PII Redaction Layer
+Masks taxpayer identifiers before prompts leave the machine
+"""
+import re
+from typing import Dict, List, Any, Iterable
+
+
+class PIIRedactor:
+    """
+    Reversible PII masking for outbound AI prompts
+
+    Every detected value is swapped for a stable placeholder token such as
+    [SSN_1]. The token map never leaves the process, so model responses can
+    be re-personalized locally with restore().
+
+    Note: image payloads (vision requests) cannot be inspected and are sent as-is.
+    """
+
+    # Formatted SSNs/ITINs: 123-45-6789 or 123 45 6789 (already-masked XXX-XX-1234 is ignored)
+    SSN_PATTERN = re.compile(r"\b\d{3}[- ]\d{2}[- ]\d{4}\b")
+
+    # EINs: 12-3456789
+    EIN_PATTERN = re.compile(r"\b\d{2}-\d{7}\b")
+
+    # Bank/brokerage account and routing numbers: 8-17 digit runs (not dollar amounts)
+    ACCOUNT_PATTERN = re.compile(r"(?<![$.,\d])\b\d{8,17}\b(?!\.\d)")
+
+    # Labelled names in free text, e.g. "Name: John Smith" or "Taxpayer: Jane Q. Doe"
+    LABELLED_NAME_PATTERN = re.compile(
+        r"\b(?i:name|taxpayer|spouse|employee|recipient|client)\s*:\s*"
+        r"([A-Z][a-z]+(?:\s+[A-Z]\.?)?(?:\s+[A-Z][a-z'-]+)+)"
+    )
+
+    # Structured-data keys whose string values are personal names
+    NAME_KEYS = {
+        "name",
+        "full_name",
+        "first_name",
+        "last_name",
+        "taxpayer_name",
+        "spouse_name",
+        "employee_name",
+        "recipient_name",
+        "client_name",
+        "dependent_name",
+        "student_name",
+    }
+
+    def __init__(self):
+        self.token_map: Dict[str, str] = {}
+        self._value_to_token: Dict[str, str] = {}
+        self._counters: Dict[str, int] = {}
+        self._known_names: List[str] = []
+
+    def _token_for(self, kind: str, value: str) -> str:
+        """Get (or allocate) the placeholder token for a sensitive value"""
+        key = f"{kind}:{value}"
+        if key not in self._value_to_token:
+            self._counters[kind] = self._counters.get(kind, 0) + 1
+            token = f"[{kind}_{self._counters[kind]}]"
+            self._value_to_token[key] = token
+            self.token_map[token] = value
+        return self._value_to_token[key]
+
+    def add_names(self, names: Iterable[str]) -> None:
+        """
+        Register personal names to mask wherever they appear
+
+        Args:
+            names: Full names (e.g. "Jane Doe"); blank values are ignored
+        """
+        for name in names:
+            name = (name or "").strip()
+            if len(name) > 1 and name not in self._known_names:
+                self._known_names.append(name)
+        # Longest first so "Jane Doe" wins over "Jane"
+        self._known_names.sort(key=len, reverse=True)
+
+    def redact(self, text: str) -> str:
+        """
+        Mask SSNs, EINs, account numbers, and names in free text
+
+        Args:
+            text: Prompt text about to be sent to a remote provider
+
+        Returns:
+            Text with sensitive values replaced by placeholder tokens
+        """
+        if not text:
+            return text
+
+        for match in self.LABELLED_NAME_PATTERN.finditer(text):
+            self.add_names([match.group(1)])
+
+        redacted = self.SSN_PATTERN.sub(lambda m: self._token_for("SSN", m.group(0)), text)
+        redacted = self.EIN_PATTERN.sub(lambda m: self._token_for("EIN", m.group(0)), redacted)
+        redacted = self.ACCOUNT_PATTERN.sub(lambda m: self._token_for("ACCOUNT", m.group(0)), redacted)
+
+        for name in self._known_names:
+            pattern = re.compile(rf"\b{re.escape(name)}\b", re.IGNORECASE)
+            redacted = pattern.sub(lambda m, n=name: self._token_for("NAME", n), redacted)
+
+        return redacted
+
+    def redact_data(self, data: Any) -> Any:
+        """
+        Recursively mask a structured payload (dicts, lists, strings)
+
+        Values under name-like keys are registered as names first, so they are
+        also caught when they reappear inside free-text fields.
+
+        Args:
+            data: JSON-compatible document or context data
+
+        Returns:
+            Redacted deep copy of the payload
+        """
+        self.add_names(self._collect_names(data))
+        return self._redact_value(data)
+
+    def redact_messages(self, messages: List[Dict[str, Any]]) -> List[Dict[str, Any]]:
+        """
+        Mask the text content of Anthropic-style chat messages
+
+        Args:
+            messages: [{"role": ..., "content": str | [content blocks]}]
+
+        Returns:
+            New message list with text parts redacted (image blocks untouched)
+        """
+        redacted = []
+        for message in messages:
+            content = message["content"]
+            if isinstance(content, str):
+                content = self.redact(content)
+            else:
+                content = [
+                    {**block, "text": self.redact(block["text"])}
+                    if block.get("type") == "text" else block
+                    for block in content
+                ]
+            redacted.append({**message, "content": content})
+        return redacted
+
+    def restore(self, text: str) -> str:
+        """
+        Re-personalize a model response using the local token map
+
+        Args:
+            text: Response text containing placeholder tokens
+
+        Returns:
+            Text with original values substituted back in
+        """
+        if not text:
+            return text
+        for token, value in self.token_map.items():
+            text = text.replace(token, value)
+        return text
+
+    def _collect_names(self, data: Any) -> List[str]:
+        """Find string values stored under name-like keys"""
+        names = []
+        if isinstance(data, dict):
+            for key, value in data.items():
+                if isinstance(value, str) and str(key).lower() in self.NAME_KEYS:
+                    names.append(value)
+                else:
+                    names.extend(self._collect_names(value))
+        elif isinstance(data, list):
+            for item in data:
+                names.extend(self._collect_names(item))
+        return names
+
+    def _redact_value(self, value: Any) -> Any:
+        """Redact a single JSON value, recursing into containers"""
+        if isinstance(value, str):
+            return self.redact(value)
+        if isinstance(value, dict):
+            return {key: self._redact_value(item) for key, item in value.items()}
+        if isinstance(value, list):
+            return [self._redact_value(item) for item in value]
+        return value
@@ -2,17 +2,20 @@
 Tax Preparation AI Agent
 Handles complex tax return preparation across all entity types
 """
-import anthropic
 from typing import Dict, List, Any, Optional
 import os
 import json
 from decimal import Decimal
 
+from app.agents.claude_client import ClaudeClient
+from app.agents.prompts import ANSWER, build_tax_system_prompt, check_scope, find_referral, scoped_reply
+from app.agents.redaction import PIIRedactor
+
 class TaxPreparationAgent:
     """AI agent for preparing complex tax returns"""
     
     def __init__(self):
-        self.client = anthropic.Anthropic(api_key=os.getenv("ANTHROPIC_API_KEY", ""))
+        self.client = ClaudeClient()
         self.model = "claude-sonnet-4-20250514"
     
     async def prepare_return(
@@ -23,13 +26,14 @@ class TaxPreparationAgent:
     ) -> Dict[str, Any]:
         """Prepare comprehensive tax return"""
         
+        redactor = PIIRedactor()
         prompt = f"""You are an expert CPA preparing a {entity_type} tax return.
 
 CURRENT YEAR FINANCIAL DATA:
-{json.dumps(financial_data, indent=2)}
+{json.dumps(redactor.redact_data(financial_data), indent=2)}
 
 PRIOR YEAR RETURN (for reference):
-{json.dumps(prior_year_return, indent=2) if prior_year_return else "Not available"}
+{json.dumps(redactor.redact_data(prior_year_return), indent=2) if prior_year_return else "Not available"}
 
 Prepare a complete tax return including:
 1. All required forms and schedules
@@ -40,25 +44,29 @@ Prepare a complete tax return including:
 
 Show your work for complex calculations."""
 
-        response = self.client.messages.create(
+        response = await self.client.create_message(
             model=self.model,
             max_tokens=8000,
-            messages=[{"role": "user", "content": prompt}]
+            system=build_tax_system_prompt(),
+            messages=redactor.redact_messages([{"role": "user", "content": prompt}])
         )
         
+        reply = scoped_reply(redactor.restore(response.content[0].text))
         return {
-            "prepared_return": response.content[0].text,
+            "prepared_return": reply["text"],
             "entity_type": entity_type,
-            "status": "draft",
-            "review_notes": []
+            "status": "draft" if reply["type"] == ANSWER else reply["type"],
+            "review_notes": [],
+            "response": reply,
         }
     
     async def review_return(self, prepared_return: Dict) -> Dict[str, Any]:
         """Quality control review of prepared return"""
         
+        redactor = PIIRedactor()
         prompt = f"""Review this prepared tax return for accuracy and completeness:
 
-{json.dumps(prepared_return, indent=2)}
+{json.dumps(redactor.redact_data(prepared_return), indent=2)}
 
 Check for:
 1. Mathematical accuracy
@@ -71,22 +79,132 @@ Check for:
 
 Provide detailed review notes."""
 
-        response = self.client.messages.create(
+        response = await self.client.create_message(
             model=self.model,
             max_tokens=3000,
-            messages=[{"role": "user", "content": prompt}]
+            system=build_tax_system_prompt(),
+            messages=redactor.redact_messages([{"role": "user", "content": prompt}])
         )
         
+        reply = scoped_reply(redactor.restore(response.content[0].text))
         return {
-            "review_status": "completed",
+            "review_status": "completed" if reply["type"] == ANSWER else reply["type"],
             "issues_found": [],
-            "review_notes": response.content[0].text,
-            "ready_to_file": True
+            "review_notes": reply["text"],
+            "ready_to_file": reply["type"] == ANSWER,
+            "response": reply,
         }
     
+    async def review_stored_return(
+        self,
+        return_summary: Dict,
+        validation_findings: List[Dict]
+    ) -> Dict[str, Any]:
+        """Review a stored return for missed deductions, inconsistencies, and red flags"""
+
+        referral = check_scope(tax_year=return_summary.get("tax_year"))
+        if referral:
+            return {"findings": [], "validation": validation_findings, "response": referral}
+
+        redactor = PIIRedactor()
+        prompt = f"""You are a CPA reviewing a client's tax return before filing.
+
+RETURN SUMMARY:
+{json.dumps(redactor.redact_data(return_summary), indent=2)}
+
+DETERMINISTIC VALIDATION RESULTS (already computed - do not repeat them):
+{json.dumps(validation_findings, indent=2)}
+
+Identify:
+1. Missed deductions or credits the taxpayer may qualify for
+2. Inconsistencies between the figures
+3. Audit red flags
+
+Respond with ONLY a JSON array. Each item must have these keys:
+"category" (one of "missed_deduction", "inconsistency", "audit_red_flag"),
+"severity" (one of "high", "medium", "low"), "title", "explanation", "suggested_action"."""
+
+        response = await self.client.create_message(
+            model=self.model,
+            max_tokens=3000,
+            system=build_tax_system_prompt(),
+            messages=redactor.redact_messages([{"role": "user", "content": prompt}])
+        )
+
+        text = redactor.restore(response.content[0].text)
+        referral = find_referral(text)
+        if referral:
+            return {"findings": [], "validation": validation_findings, "response": referral}
+        return {
+            "findings": self._parse_findings(text),
+            "validation": validation_findings,
+        }
+
+    async def generate_checklist(
+        self,
+        return_summary: Dict,
+        document_types: List[str]
+    ) -> List[Dict[str, Any]]:
+        """Generate a personalized checklist of forms needed and documents still missing"""
+
+        redactor = PIIRedactor()
+        prompt = f"""You are a CPA preparing a personalized document checklist for a client's tax return.
+
+RETURN SUMMARY:
+{json.dumps(redactor.redact_data(return_summary), indent=2)}
+
+DOCUMENTS ALREADY UPLOADED:
+{json.dumps(document_types) if document_types else "None"}
+
+List every IRS form/schedule this return will need and every supporting document
+the client should gather. Mark documents already uploaded as received.
+
+Respond with ONLY a JSON array. Each item must have these keys:
+"title" (short label), "kind" (one of "form", "document"), "form" (e.g. "W-2", "Schedule C", or null),
+"reason" (one sentence tied to this client's situation), "received" (true/false)."""
+
+        response = await self.client.create_message(
+            model=self.model,
+            max_tokens=3000,
+            # A list of forms and documents to gather isn't advice, so the scope doesn't apply
+            system=build_tax_system_prompt(scope=False),
+            messages=redactor.redact_messages([{"role": "user", "content": prompt}])
+        )
+
+        items = self._extract_json_array(redactor.restore(response.content[0].text)) or []
+        return [item for item in items if isinstance(item, dict) and item.get("title")]
+
+    def _extract_json_array(self, response_text: str) -> Optional[List[Any]]:
+        """Pull a JSON array out of a model response, tolerating surrounding prose"""
+        start, end = response_text.find("["), response_text.rfind("]")
+        if start == -1:
+            return None
+        try:
+            parsed = json.loads(response_text[start:end + 1])
+        except json.JSONDecodeError:
+            return None
+        return parsed if isinstance(parsed, list) else None
+
+    def _parse_findings(self, response_text: str) -> List[Dict[str, Any]]:
+        """Parse the model's findings array"""
+        findings = self._extract_json_array(response_text)
+
+        if findings is None:
+            # Fallback to a single free-text finding
+            return [{
+                "category": "inconsistency",
+                "severity": "low",
+                "title": "Unstructured review notes",
+                "explanation": response_text,
+                "suggested_action": "Read the review notes",
+            }]
+
+        return [f for f in findings if isinstance(f, dict)]
+
     async def handle_complex_scenario(self, scenario_description: str) -> Dict[str, Any]:
         """Handle complex tax scenarios requiring expert judgment"""
         
+        redactor = PIIRedactor()
         prompt = f"""As an experienced CPA, analyze this complex tax scenario:
 
 {scenario_description}
@@ -99,14 +217,17 @@ Provide:
 5. Recommendation with justification
 6. Risk assessment"""
 
-        response = self.client.messages.create(
+        response = await self.client.create_message(
             model=self.model,
             max_tokens=4000,
-            messages=[{"role": "user", "content": prompt}]
+            system=build_tax_system_prompt(),
+            messages=redactor.redact_messages([{"role": "user", "content": prompt}])
         )
         
+        reply = scoped_reply(redactor.restore(response.content[0].text))
         return {
-            "analysis": response.content[0].text,
-            "recommendation": "See analysis",
-            "confidence": "high"
+            "analysis": reply["text"],
+            "recommendation": "See analysis" if reply["type"] == ANSWER else reply["text"],
+            "confidence": "high" if reply["type"] == ANSWER else "none",
+            "response": reply,
         }
@@ -2,7 +2,6 @@
 Voice Communication Agent
 Handles realistic voice conversations with IRS simulation
 """
-import anthropic
 from typing import Dict, List, Any, AsyncIterator, Optional
 import os
 import json
@@ -13,12 +12,19 @@ from pathlib import Path
 # Add parent directory to path for imports
 sys.path.insert(0, str(Path(__file__).parent.parent))
 from utils.conversation_store import ConversationStore
+from app.agents.claude_client import ClaudeClient
+from app.agents.prompts import build_tax_system_prompt
+from app.agents.redaction import PIIRedactor
 
 class VoiceAgent:
-    """AI agent for voice communication with natural speech patterns"""
+    """
+    AI agent for voice communication with natural speech patterns
+
+    Calls are with the IRS rather than advice to the taxpayer, so its prompts leave out the scope.
+    """
 
     def __init__(self, session_id: Optional[str] = None):
-        self.client = anthropic.Anthropic(api_key=os.getenv("ANTHROPIC_API_KEY", ""))
+        self.client = ClaudeClient()
         self.model = "claude-sonnet-4-20250514"
         self.session_id = session_id or f"voice_{os.urandom(8).hex()}"
         self.conversation_store = ConversationStore()
@@ -40,10 +46,11 @@ class VoiceAgent:
     ) -> Dict[str, Any]:
         """Generate natural conversation script for IRS call"""
         
+        redactor = PIIRedactor()
         prompt = f"""You are a professional CPA making a call to the IRS on behalf of a client.
 
 CALL PURPOSE: {call_purpose}
-CLIENT INFO: {json.dumps(client_info)}
+CLIENT INFO: {json.dumps(redactor.redact_data(client_info))}
 KEY POINTS TO ADDRESS: {talking_points}
 
 Generate a natural, conversational script including:
@@ -57,14 +64,15 @@ Generate a natural, conversational script including:
 
 Make it sound human, not robotic. Include realistic speech patterns."""
 
-        response = self.client.messages.create(
+        response = await self.client.create_message(
             model=self.model,
             max_tokens=3000,
-            messages=[{"role": "user", "content": prompt}]
+            system=build_tax_system_prompt(scope=False),
+            messages=redactor.redact_messages([{"role": "user", "content": prompt}])
         )
         
         return {
-            "script": response.content[0].text,
+            "script": redactor.restore(response.content[0].text),
             "estimated_duration": "5-10 minutes",
             "difficulty": "medium"
         }
@@ -88,6 +96,9 @@ Make it sound human, not robotic. Include realistic speech patterns."""
             metadata={"context": context}
         )
         
+        # Learn names from the call context so they are masked throughout the history
+        redactor = PIIRedactor()
+        redactor.redact_data(context)
         system_prompt = f"""You are a professional CPA in a live phone conversation with the IRS.
 
 CONTEXT:
@@ -105,15 +116,14 @@ Respond naturally as a CPA would in a phone call:
 
 Keep responses concise (2-4 sentences) to allow for natural back-and-forth."""
 
-        messages = [{"role": "system", "content": system_prompt}] + self.conversation_history
-        
-        response = self.client.messages.create(
+        response = await self.client.create_message(
             model=self.model,
             max_tokens=500,
-            messages=messages
+            system=redactor.redact(build_tax_system_prompt(system_prompt, scope=False)),
+            messages=redactor.redact_messages(self.conversation_history)
         )
         
-        agent_response = response.content[0].text
+        agent_response = redactor.restore(response.content[0].text)
 
         # Add agent response to history and persist
         self.conversation_history.append({
@@ -159,6 +169,7 @@ Keep responses concise (2-4 sentences) to allow for natural back-and-forth."""
             "helpful": "cooperative, solution-oriented IRS agent"
         }
         
+        redactor = PIIRedactor()
         prompt = f"""You are an IRS agent on a phone call with a CPA. Your personality: {personalities[irs_agent_personality]}.
 
 CPA just said: "{cpa_message}"
@@ -172,14 +183,17 @@ Respond as the IRS agent would:
 
 Keep response concise (2-3 sentences) for natural conversation flow."""
 
-        response = self.client.messages.create(
+        response = await self.client.create_message(
             model=self.model,
             max_tokens=400,
-            messages=[{"role": "user", "content": prompt}]
+            system=build_tax_system_prompt(
+                "You are role-playing an IRS agent so a CPA can practice calls.", scope=False
+            ),
+            messages=redactor.redact_messages([{"role": "user", "content": prompt}])
         )
         
         return {
-            "irs_response": response.content[0].text,
+            "irs_response": redactor.restore(response.content[0].text),
             "agent_mood": irs_agent_personality,
             "escalation_level": "normal"
         }
@@ -0,0 +1 @@
+"""Document Processing Package"""
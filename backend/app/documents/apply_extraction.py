@@ -0,0 +1,267 @@
+"""
+Apply Extracted Data
+Maps parsed form fields onto a return's income and deductions with a before/after diff
+"""
+import copy
+from typing import Dict, List, Any, Optional, Tuple
+
+from app.tax_engine.validation import summarize_return
+from app.utils.document_store import DocumentStore
+from app.utils.return_store import ReturnStore
+
+# Form -> (income type, amount field, withholding field, description field, {source field: extracted field})
+INCOME_MAPPINGS: Dict[str, Tuple[str, str, str, str, Dict[str, str]]] = {
+    "W-2": ("wages", "wages", "federal_withholding", "employer_name", {
+        "employer_ein": "employer_ein",
+        "social_security_wages": "social_security_wages",
+        "state": "state",
+        "state_wages": "state_wages",
+        "state_withholding": "state_income_tax",
+    }),
+    "1099-INT": ("interest", "interest_income", "federal_withholding", "payer_name", {
+        "payer_tin": "payer_tin",
+        "tax_exempt_interest": "tax_exempt_interest",
+    }),
+    "1099-DIV": ("dividends", "ordinary_dividends", "federal_withholding", "payer_name", {
+        "payer_tin": "payer_tin",
+        "qualified_dividends": "qualified_dividends",
+        "capital_gain_distributions": "capital_gain_distributions",
+        "exempt_interest_dividends": "exempt_interest_dividends",
+    }),
+    "1099-NEC": ("self_employment", "nonemployee_compensation", "federal_withholding", "payer_name", {
+        "payer_tin": "payer_tin",
+    }),
+}
+
+
+def income_source_from_extraction(extraction: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    Build income source fields from a parsed form
+
+    Args:
+        extraction: ParseResult.to_dict() output stored on the document
+
+    Returns:
+        Income source fields (type, description, amount, withholding, extras)
+
+    Raises:
+        ValueError: If the form can't be applied or its amount wasn't extracted
+    """
+    form = extraction.get("form")
+    if form not in INCOME_MAPPINGS:
+        raise ValueError(
+            f"{form} data can't be applied to income yet. "
+            f"Supported forms: {', '.join(INCOME_MAPPINGS)}"
+        )
+
+    income_type, amount_field, withholding_field, description_field, extras = INCOME_MAPPINGS[form]
+    values = {name: f.get("value") for name, f in extraction.get("fields", {}).items()}
+    if values.get(amount_field) is None:
+        raise ValueError(f"{form} {amount_field.replace('_', ' ')} was not extracted; enter it manually")
+
+    source = {
+        "type": income_type,
+        "description": values.get(description_field) or form,
+        "amount": values[amount_field],
+        "withholding": values.get(withholding_field) or 0,
+        "form": form,
+    }
+    for source_field, extracted_field in extras.items():
+        if values.get(extracted_field) is not None:
+            source[source_field] = values[extracted_field]
+    return source
+
+
+def apply_extraction_to_return(
+    document_store: DocumentStore,
+    return_store: ReturnStore,
+    document_id: str,
+    return_id: str,
+    preview: bool = False,
+) -> Dict[str, Any]:
+    """
+    Apply a document's extracted data to a return
+
+    The income source is linked to the document, so applying the same document
+    again updates that source instead of adding a second one.
+
+    Args:
+        document_store: Store holding the document and its extraction
+        return_store: Store holding the return
+        document_id: Document to apply
+        return_id: Return to apply it to
+        preview: Only compute the diff; change nothing
+
+    Returns:
+        Dict with action (added|updated|unchanged), the income source before and
+        after, the changed return totals, and the extraction's warnings
+
+    Raises:
+        KeyError: If the document or return doesn't exist
+        ValueError: If the document hasn't been extracted or can't be mapped
+    """
+    document = document_store.get_document(document_id)
+    if document is None:
+        raise KeyError(f"Document not found: {document_id}")
+    tax_return = return_store.get_return(return_id)
+    if tax_return is None:
+        raise KeyError(f"Return not found: {return_id}")
+    if not document.get("extraction"):
+        raise ValueError("Document has no extracted data; parse it first")
+
+    fields = income_source_from_extraction(document["extraction"])
+    fields["document_id"] = document_id
+
+    existing = next(
+        (s for s in tax_return["income_sources"] if s.get("document_id") == document_id), None
+    )
+    if existing is None:
+        action = "added"
+        after_source = {"id": None, **fields}
+    else:
+        after_source = {**existing, **fields}
+        action = "unchanged" if after_source == existing else "updated"
+
+    updated_return = copy.deepcopy(tax_return)
+    sources = updated_return["income_sources"]
+    if existing is None:
+        sources.append(after_source)
+    else:
+        sources[sources.index(existing)] = after_source
+
+    if not preview and action != "unchanged":
+        if existing is None:
+            extra = {k: v for k, v in fields.items() if k not in ("type", "amount", "description", "withholding")}
+            after_source = return_store.add_income_source(
+                return_id,
+                source_type=fields["type"],
+                amount=fields["amount"],
+                description=fields["description"],
+                withholding=fields["withholding"],
+                **extra,
+            )
+        else:
+            after_source = return_store.update_income_source(return_id, existing["id"], **fields)
+    if not preview:
+        document_store.record_application(document_id, return_id, income_source_id=after_source["id"])
+
+    return {
+        "document_id": document_id,
+        "return_id": return_id,
+        "preview": preview,
+        "action": action,
+        "income_source": {"before": existing, "after": after_source},
+        "changes": _diff_totals(summarize_return(tax_return), summarize_return(updated_return)),
+        "warnings": document["extraction"].get("warnings", []),
+        "needs_review": document["extraction"].get("needs_review", False),
+    }
+
+
+def create_deduction_from_receipt(
+    document_store: DocumentStore,
+    return_store: ReturnStore,
+    document_id: str,
+    return_id: Optional[str] = None,
+    category: Optional[str] = None,
+) -> Dict[str, Any]:
+    """
+    Create the deduction for an extracted receipt
+
+    The deduction's receipt_id points back at the document; running this again
+    for the same receipt updates that deduction instead of adding another.
+
+    Args:
+        document_store: Store holding the receipt and its extraction
+        return_store: Store holding the return
+        document_id: Receipt document
+        return_id: Return to deduct on (defaults to the document's return)
+        category: Deduction category, overriding the one guessed from the receipt
+
+    Returns:
+        Dict with action (added|updated|unchanged), the deduction, and the
+        changed return totals
+
+    Raises:
+        KeyError: If the document or return doesn't exist
+        ValueError: If the receipt lacks an amount or category, or isn't extracted
+    """
+    document = document_store.get_document(document_id)
+    if document is None:
+        raise KeyError(f"Document not found: {document_id}")
+    return_id = return_id or document.get("return_id")
+    if not return_id:
+        raise ValueError("Receipt isn't attached to a return; choose one")
+    tax_return = return_store.get_return(return_id)
+    if tax_return is None:
+        raise KeyError(f"Return not found: {return_id}")
+
+    extraction = document.get("extraction")
+    if not extraction or extraction.get("form") != "receipt":
+        raise ValueError("Document has no extracted receipt data; parse it as a receipt first")
+    values = {name: f.get("value") for name, f in extraction.get("fields", {}).items()}
+    if values.get("amount") is None:
+        raise ValueError("Receipt total was not extracted; enter it manually")
+    category = category or values.get("category")
+    if not category:
+        raise ValueError("Receipt category is unknown; choose a deduction category")
+
+    fields = {
+        "category": category,
+        "amount": values["amount"],
+        "description": values.get("vendor") or document["filename"],
+        "receipt_id": document_id,
+    }
+    if values.get("date"):
+        fields["date"] = values["date"]
+
+    existing = next((d for d in tax_return["deductions"] if d.get("receipt_id") == document_id), None)
+    if existing is None:
+        action = "added"
+        deduction = return_store.add_deduction(
+            return_id,
+            category=fields.pop("category"),
+            amount=fields.pop("amount"),
+            description=fields.pop("description"),
+            **fields,
+        )
+    elif {**existing, **fields} == existing:
+        action = "unchanged"
+        deduction = existing
+    else:
+        action = "updated"
+        deduction = return_store.update_deduction(return_id, existing["id"], **fields)
+    document_store.record_application(document_id, return_id, deduction_id=deduction["id"])
+
+    return {
+        "document_id": document_id,
+        "return_id": return_id,
+        "action": action,
+        "deduction": deduction,
+        "changes": _diff_totals(summarize_return(tax_return), summarize_return(return_store.get_return(return_id))),
+        "warnings": extraction.get("warnings", []),
+    }
+
+
+def _diff_totals(before: Dict[str, Any], after: Dict[str, Any]) -> List[Dict[str, Any]]:
+    """Return totals that differ between two summaries"""
+    pairs: List[Tuple[str, Optional[float], Optional[float]]] = []
+    for income_type in sorted(set(before["income_by_type"]) | set(after["income_by_type"])):
+        pairs.append((
+            f"income_by_type.{income_type}",
+            before["income_by_type"].get(income_type, 0.0),
+            after["income_by_type"].get(income_type, 0.0),
+        ))
+    for category in sorted(set(before["deductions_by_category"]) | set(after["deductions_by_category"])):
+        pairs.append((
+            f"deductions_by_category.{category}",
+            before["deductions_by_category"].get(category, 0.0),
+            after["deductions_by_category"].get(category, 0.0),
+        ))
+    for total in ("total_income", "total_withholding", "total_itemized_deductions"):
+        pairs.append((total, before[total], after[total]))
+
+    return [
+        {"field": name, "before": old, "after": new, "difference": round(new - old, 2)}
+        for name, old, new in pairs
+        if old != new
+    ]
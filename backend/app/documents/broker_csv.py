@@ -0,0 +1,258 @@
+"""
+Broker CSV Import
+Reads brokerage realized gain/loss (1099-B) CSV exports into capital transaction lots
+"""
+import csv
+import io
+import re
+from datetime import date, datetime
+from decimal import Decimal, InvalidOperation
+from typing import Dict, List, Any, Optional
+
+# Canonical lot field -> accepted column headers, per broker export layout.
+# Headers are compared case-insensitively with punctuation and spacing ignored.
+BROKER_LAYOUTS: Dict[str, Dict[str, List[str]]] = {
+    "fidelity": {
+        "description": ["Security Description", "Description"],
+        "symbol": ["Symbol(CUSIP)", "Symbol"],
+        "quantity": ["Quantity"],
+        "date_acquired": ["Date Acquired"],
+        "date_sold": ["Date Sold"],
+        "proceeds": ["Proceeds"],
+        "cost_basis": ["Cost Basis"],
+        "wash_sale_disallowed": ["Wash Sale Loss Disallowed"],
+        "term": ["Term"],
+    },
+    "schwab": {
+        "description": ["Name", "Description"],
+        "symbol": ["Symbol"],
+        "quantity": ["Quantity"],
+        "date_acquired": ["Opened Date"],
+        "date_sold": ["Closed Date"],
+        "proceeds": ["Proceeds"],
+        "cost_basis": ["Cost Basis (CB)", "Cost Basis"],
+        "wash_sale_disallowed": ["Disallowed Loss"],
+        "term": ["Term"],
+    },
+    "vanguard": {
+        "description": ["Name", "Investment Name"],
+        "symbol": ["Symbol"],
+        "quantity": ["Shares"],
+        "date_acquired": ["Date acquired"],
+        "date_sold": ["Date sold"],
+        "proceeds": ["Proceeds"],
+        "cost_basis": ["Cost basis"],
+        "wash_sale_disallowed": ["Wash sale disallowed", "Wash sale loss disallowed"],
+        "term": ["Holding period", "Term"],
+    },
+}
+
+# A layout is detected only when all of these columns are present
+REQUIRED_FIELDS = ["date_sold", "proceeds", "cost_basis"]
+
+LOT_FIELDS = [
+    "description", "symbol", "quantity", "date_acquired", "date_sold",
+    "proceeds", "cost_basis", "wash_sale_disallowed", "term",
+]
+
+# Broker exports often start with account details; look this far for the header row
+MAX_PREAMBLE_ROWS = 20
+
+
+def _normalize(header: str) -> str:
+    """Header key: lowercase letters and digits only"""
+    return re.sub(r"[^a-z0-9]", "", header.lower())
+
+
+def detect_layout(headers: List[str]) -> Optional[str]:
+    """
+    Identify which broker produced a CSV from its header row
+
+    Args:
+        headers: Column headers
+
+    Returns:
+        Layout whose columns best match (ties go to the first listed), or None
+        if no layout's required columns are all present
+    """
+    best, best_score = None, 0
+    for name, layout in BROKER_LAYOUTS.items():
+        matched = _column_indexes(headers, _layout_columns(layout))
+        if matched.keys() >= set(REQUIRED_FIELDS) and len(matched) > best_score:
+            best, best_score = name, len(matched)
+    return best
+
+
+def _layout_columns(layout: Dict[str, List[str]]) -> Dict[str, List[str]]:
+    """Layout with normalized header candidates"""
+    return {field: [_normalize(c) for c in candidates] for field, candidates in layout.items()}
+
+
+def parse_broker_csv(
+    content: str,
+    layout: Optional[str] = None,
+    mapping: Optional[Dict[str, str]] = None,
+) -> Dict[str, Any]:
+    """
+    Parse a broker realized gain/loss CSV into lots
+
+    Account preambles and trailing total/disclaimer rows are skipped.
+
+    Args:
+        content: CSV text
+        layout: Broker layout name (fidelity, schwab, vanguard); detected if omitted
+        mapping: Generic mode: lot field -> column header, for brokers without a layout
+
+    Returns:
+        Dict with the layout used, parsed transactions, and per-row warnings
+
+    Raises:
+        ValueError: If the header row or required columns can't be found
+    """
+    rows = list(csv.reader(io.StringIO(content.lstrip("﻿"))))
+    if mapping:
+        unknown = set(mapping) - set(LOT_FIELDS)
+        if unknown:
+            raise ValueError(f"Unknown lot fields in mapping: {', '.join(sorted(unknown))}")
+        layout_name = "generic"
+        missing = set(REQUIRED_FIELDS) - set(mapping)
+        if missing:
+            raise ValueError(f"Mapping must include columns for: {', '.join(sorted(missing))}")
+        columns = {field: [_normalize(header)] for field, header in mapping.items()}
+    elif layout:
+        if layout not in BROKER_LAYOUTS:
+            raise ValueError(f"Unknown broker layout: {layout}. Must be one of: {', '.join(BROKER_LAYOUTS)}")
+        layout_name = layout
+        columns = _layout_columns(BROKER_LAYOUTS[layout])
+    else:
+        layout_name = None
+        columns = {}
+
+    header_index = None
+    for index, row in enumerate(rows[:MAX_PREAMBLE_ROWS]):
+        if layout_name is None:
+            detected = detect_layout(row)
+            if detected:
+                layout_name = detected
+                columns = _layout_columns(BROKER_LAYOUTS[detected])
+        if layout_name and _column_indexes(row, columns).keys() >= set(REQUIRED_FIELDS):
+            header_index = index
+            break
+
+    if header_index is None:
+        if layout_name is None:
+            raise ValueError(
+                "Couldn't recognize the broker CSV layout. "
+                "Choose a layout or map the columns (date_sold, proceeds, cost_basis are required)"
+            )
+        raise ValueError(f"Header row with {', '.join(REQUIRED_FIELDS)} columns not found")
+
+    indexes = _column_indexes(rows[header_index], columns)
+    transactions: List[Dict[str, Any]] = []
+    warnings: List[str] = []
+
+    for line_number, row in enumerate(rows[header_index + 1:], start=header_index + 2):
+        values = {field: row[i].strip() if i < len(row) else "" for field, i in indexes.items()}
+        if not values["date_sold"] or not values["proceeds"]:
+            continue  # blank, subtotal, or disclaimer row
+        try:
+            transactions.append(_build_lot(values))
+        except ValueError as e:
+            warnings.append(f"Row {line_number}: {e}")
+
+    return {"layout": layout_name, "transactions": transactions, "warnings": warnings}
+
+
+def _column_indexes(headers: List[str], columns: Dict[str, List[str]]) -> Dict[str, int]:
+    """Lot field -> column position for the fields present in a header row"""
+    positions = {_normalize(h): i for i, h in enumerate(headers)}
+    indexes = {}
+    for field, candidates in columns.items():
+        for candidate in candidates:
+            if candidate in positions:
+                indexes[field] = positions[candidate]
+                break
+    return indexes
+
+
+def _build_lot(values: Dict[str, str]) -> Dict[str, Any]:
+    """One lot from a row's raw values"""
+    date_sold = _parse_date(values["date_sold"])
+    if date_sold is None or date_sold == "VARIOUS":
+        raise ValueError(f"Unreadable sale date: {values['date_sold']}")
+    proceeds = _parse_amount(values["proceeds"])
+    if proceeds is None:
+        raise ValueError(f"Unreadable proceeds: {values['proceeds']}")
+
+    basis = _parse_amount(values.get("cost_basis", ""))
+    quantity = _parse_number(values.get("quantity", ""))
+    wash_sale = _parse_amount(values.get("wash_sale_disallowed", "")) or Decimal("0")
+    date_acquired = _parse_date(values.get("date_acquired", ""))
+
+    lot: Dict[str, Any] = {
+        "description": values.get("description") or values.get("symbol") or "",
+        "symbol": values.get("symbol") or None,
+        "quantity": float(quantity) if quantity is not None else None,
+        "date_acquired": date_acquired,
+        "date_sold": date_sold,
+        "proceeds": float(proceeds),
+        "cost_basis": float(basis) if basis is not None else None,
+        "wash_sale_disallowed": float(abs(wash_sale)),
+        "term": _parse_term(values.get("term", ""), date_acquired, date_sold),
+        # Basis not reported to the IRS (noncovered lots) must be supplied before filing
+        "gain_or_loss": float(proceeds - basis + abs(wash_sale)) if basis is not None else None,
+    }
+    return lot
+
+
+def _parse_amount(raw: str) -> Optional[Decimal]:
+    """Dollar amount rounded to cents"""
+    amount = _parse_number(raw)
+    return amount.quantize(Decimal("0.01")) if amount is not None else None
+
+
+def _parse_number(raw: str) -> Optional[Decimal]:
+    """"$1,234.56", "(12.00)" for negatives; None for blanks and placeholders"""
+    cleaned = raw.replace("$", "").replace(",", "").strip()
+    if cleaned in ("", "--", "-", "N/A", "n/a"):
+        return None
+    negative = cleaned.startswith("(") and cleaned.endswith(")")
+    try:
+        number = Decimal(cleaned.strip("()"))
+    except InvalidOperation:
+        return None
+    return -number if negative else number
+
+
+def _parse_date(raw: str) -> Optional[str]:
+    """ISO date, "VARIOUS", or None"""
+    raw = raw.strip()
+    if not raw:
+        return None
+    if raw.lower() == "various":
+        return "VARIOUS"
+    for fmt in ("%m/%d/%Y", "%m/%d/%y", "%Y-%m-%d"):
+        try:
+            return datetime.strptime(raw, fmt).date().isoformat()
+        except ValueError:
+            continue
+    return None
+
+
+def _parse_term(raw: str, date_acquired: Optional[str], date_sold: str) -> Optional[str]:
+    """"short"/"long" from the term column, else from the holding period"""
+    text = raw.lower()
+    if text.startswith(("short", "st")):
+        return "short"
+    if text.startswith(("long", "lt")):
+        return "long"
+    if not date_acquired or date_acquired == "VARIOUS":
+        return None
+
+    acquired, sold = date.fromisoformat(date_acquired), date.fromisoformat(date_sold)
+    # Long-term means held more than one year
+    try:
+        one_year_later = acquired.replace(year=acquired.year + 1)
+    except ValueError:  # Feb 29
+        one_year_later = acquired.replace(year=acquired.year + 1, day=28)
+    return "long" if sold > one_year_later else "short"
@@ -0,0 +1,149 @@
+"""
+Bulk Document Import
+Walks a folder, infers each file's document type, and uploads it into the document store
+"""
+import os
+import re
+from pathlib import Path
+from typing import Callable, Dict, List, Any, Optional, Tuple
+
+from app.documents.previews import generate_thumbnail
+from app.utils.document_store import DocumentStore, DuplicateDocumentError
+
+# (document type, filename regex, content regex); first match wins, so specific forms come first
+DOCUMENT_TYPE_PATTERNS: List[Tuple[str, str, str]] = [
+    ("1099-INT", r"1099[-_ ]?int", r"1099-INT|Form\s+1099-INT"),
+    ("1099-DIV", r"1099[-_ ]?div", r"1099-DIV|Dividends\s+and\s+Distributions"),
+    ("1099-NEC", r"1099[-_ ]?nec", r"1099-NEC|Nonemployee\s+Compensation"),
+    ("1099-MISC", r"1099[-_ ]?misc", r"1099-MISC|Miscellaneous\s+(?:Income|Information)"),
+    ("1099-K", r"1099[-_ ]?k(?![a-z])", r"1099-K|Payment\s+Card\s+and\s+Third\s+Party\s+Network"),
+    ("1099-B", r"1099[-_ ]?b(?![a-z])", r"1099-B|Proceeds\s+From\s+Broker"),
+    ("1095-A", r"1095[-_ ]?a(?![a-z])", r"1095-A|Health\s+Insurance\s+Marketplace\s+Statement"),
+    ("1098-T", r"1098[-_ ]?t(?![a-z])", r"1098-T|Tuition\s+Statement"),
+    ("1098", r"1098(?![-_ ]?[a-z](?![a-z]))|mortgage[-_ ]?interest", r"Form\s+1098\b|Mortgage\s+Interest\s+Statement"),
+    ("W-2", r"(?<![a-z0-9])w[-_ ]?2(?![0-9])", r"Form\s+W-2|Wage\s+and\s+Tax\s+Statement"),
+    ("receipt", r"receipt|invoice", r"\breceipt\b|\bsubtotal\b"),
+]
+
+# How much of a file to sniff for form titles (PDF text or OCR output)
+CONTENT_SNIFF_BYTES = 20_000
+
+ProgressCallback = Callable[[Dict[str, Any]], None]
+
+
+def infer_document_type(filename: str, content: bytes = b"") -> Tuple[str, str]:
+    """
+    Guess a document's type
+
+    Args:
+        filename: File name
+        content: File bytes (only the start is examined)
+
+    Returns:
+        (document type, source) where source is "filename", "content", or "default"
+    """
+    name = filename.lower()
+    for document_type, name_pattern, _ in DOCUMENT_TYPE_PATTERNS:
+        if re.search(name_pattern, name):
+            return document_type, "filename"
+
+    text = content[:CONTENT_SNIFF_BYTES].decode("utf-8", errors="ignore")
+    for document_type, _, content_pattern in DOCUMENT_TYPE_PATTERNS:
+        if re.search(content_pattern, text, re.IGNORECASE):
+            return document_type, "content"
+
+    return "unknown", "default"
+
+
+def bulk_import(
+    store: DocumentStore,
+    folder: str,
+    default_return_id: Optional[str] = None,
+    recursive: bool = True,
+    progress: Optional[ProgressCallback] = None,
+) -> Dict[str, Any]:
+    """
+    Import every file in a folder
+
+    Hidden files are skipped; a failure on one file doesn't stop the rest.
+
+    Args:
+        store: Document store to upload into
+        folder: Folder to walk
+        default_return_id: Return to attach the documents to
+        recursive: Include subfolders
+        progress: Called with an event dict after each file
+
+    Returns:
+        Report with per-file results and imported/duplicate/failed counts
+    """
+    root = Path(folder)
+    if not root.is_dir():
+        raise ValueError(f"Folder not found: {folder}")
+
+    files = _collect_files(root, recursive)
+    results: List[Dict[str, Any]] = []
+
+    for index, path in enumerate(files, start=1):
+        result = _import_file(store, root, path, default_return_id)
+        results.append(result)
+        if progress:
+            progress({
+                "event": "document_imported",
+                "current": index,
+                "total": len(files),
+                "percent": round(index / len(files) * 100),
+                **result,
+            })
+
+    counts = {status: sum(1 for r in results if r["status"] == status) for status in ("imported", "duplicate", "failed")}
+    return {"folder": str(root), "total": len(files), **counts, "results": results}
+
+
+def _collect_files(root: Path, recursive: bool) -> List[Path]:
+    """Visible files under root, in a stable order"""
+    if not recursive:
+        return sorted(p for p in root.iterdir() if p.is_file() and not p.name.startswith("."))
+
+    files = []
+    for dirpath, dirnames, filenames in os.walk(root):
+        dirnames[:] = sorted(d for d in dirnames if not d.startswith("."))
+        files.extend(Path(dirpath) / name for name in sorted(filenames) if not name.startswith("."))
+    return files
+
+
+def _import_file(
+    store: DocumentStore,
+    root: Path,
+    path: Path,
+    return_id: Optional[str],
+) -> Dict[str, Any]:
+    """Upload one file and describe the outcome"""
+    result: Dict[str, Any] = {"file": str(path.relative_to(root))}
+    try:
+        if path.stat().st_size > store.MAX_DOCUMENT_BYTES:
+            raise ValueError("File is too large (max 25 MB)")
+        with open(path, 'rb') as f:
+            content = f.read()
+        if not content:
+            raise ValueError("File is empty")
+
+        document_type, source = infer_document_type(path.name, content)
+        document = store.upload_document(
+            filename=path.name,
+            content=content,
+            document_type=document_type,
+            return_id=return_id,
+        )
+        generate_thumbnail(store, document, content)
+        result.update({
+            "status": "imported",
+            "document_id": document["document_id"],
+            "document_type": document_type,
+            "type_source": source,
+        })
+    except DuplicateDocumentError as e:
+        result.update({"status": "duplicate", "document_id": e.existing["document_id"], "error": str(e)})
+    except (OSError, ValueError) as e:
+        result.update({"status": "failed", "error": str(e)})
+    return result
@@ -0,0 +1,128 @@
+"""
+Document Requests
+Builds the list of forms to request for a return from last year's documents and this year's income sources
+"""
+from typing import Dict, List, Any, Optional, Tuple
+
+from app.services.invoice_reconciliation import normalize_client
+from app.utils.document_store import DocumentStore
+from app.utils.return_store import ReturnStore
+
+# Document types that aren't forms someone sends each year
+NOT_REQUESTED_TYPES = {"unknown", "receipt", "other"}
+
+# Extracted fields naming who issued a form
+ISSUER_FIELDS = ["payer_name", "employer_name", "lender_name", "issuer_name"]
+
+
+def _issuer(record: Dict[str, Any]) -> Optional[str]:
+    """Who issued a stored document, from its extracted fields"""
+    fields = (record.get("extraction") or {}).get("fields", {})
+    for name in ISSUER_FIELDS:
+        value = (fields.get(name) or {}).get("value")
+        if value:
+            return str(value)
+    return None
+
+
+def _matches(request: Dict[str, Any], record: Dict[str, Any]) -> bool:
+    """Whether a stored document fulfils a request (same form, and same issuer when both are known)"""
+    if record.get("document_type") != request["form"]:
+        return False
+    issuer = _issuer(record)
+    if not request.get("payer") or not issuer:
+        return True
+    return normalize_client(issuer) == normalize_client(request["payer"])
+
+
+def document_requests(
+    tax_return: Dict[str, Any],
+    prior_documents: List[Dict[str, Any]],
+    documents: List[Dict[str, Any]],
+) -> List[Dict[str, Any]]:
+    """
+    Forms to request for a return, each marked received or outstanding
+
+    Every form received last year is expected again, along with the form for
+    each income source already on this year's return. A request is received
+    once a document of the same form (and issuer, when both are known) is
+    stored for this year.
+
+    Args:
+        tax_return: This year's return
+        prior_documents: Last year's document records
+        documents: This year's document records
+
+    Returns:
+        Checklist items ({title, kind, form, payer, reason, received, document_id})
+    """
+    requests: Dict[Tuple[str, Optional[str]], Dict[str, Any]] = {}
+
+    def add(form: str, payer: Optional[str], fallback: str, reason: str) -> None:
+        key = (form, normalize_client(payer) if payer else None)
+        if key in requests or (payer is None and any(k[0] == form for k in requests)):
+            return
+        # A named request replaces an unnamed one for the same form
+        unnamed = requests.pop((form, None), None)
+        if unnamed:
+            reason = unnamed["reason"]
+        requests[key] = {
+            "title": f"{form} from {payer}" if payer else f"{form} ({fallback})",
+            "kind": "document",
+            "form": form,
+            "payer": payer,
+            "reason": reason,
+        }
+
+    for source in tax_return.get("income_sources", []):
+        form = ReturnStore.CHECKLIST_INCOME_FORMS.get(source["type"])
+        if form:
+            add(form, source.get("description") or None, source["type"].replace("_", " "), "Income source on the return")
+    for record in sorted(prior_documents, key=lambda r: r.get("uploaded_at", "")):
+        form = record.get("document_type") or "unknown"
+        if form in NOT_REQUESTED_TYPES:
+            continue
+        add(form, _issuer(record), f"like last year's {record.get('filename', 'document')}", "Received last year")
+
+    items = []
+    for request in requests.values():
+        document = next((d for d in documents if _matches(request, d)), None)
+        items.append({
+            **request,
+            "received": document is not None,
+            "document_id": document["document_id"] if document else None,
+        })
+    return items
+
+
+def return_documents(
+    document_store: DocumentStore,
+    tax_return: Dict[str, Any],
+    prior_return_id: Optional[str] = None,
+) -> Tuple[List[Dict[str, Any]], List[Dict[str, Any]]]:
+    """
+    Last year's and this year's documents for a return
+
+    Last year's are those attached to prior_return_id (default: the return
+    this one was cloned from); for a practitioner client, documents filed
+    under the client for either year count too.
+
+    Returns:
+        (prior_documents, documents)
+    """
+    prior_return_id = prior_return_id or tax_return.get("cloned_from")
+    records = document_store.list_documents(include_archived=True)
+    client_id = tax_return.get("client_id")
+
+    def client_year(record: Dict[str, Any], year: int) -> bool:
+        return bool(client_id) and record.get("client_id") == client_id and DocumentStore.document_tax_year(record) == year
+
+    prior = [
+        r for r in records
+        if (prior_return_id and r.get("return_id") == prior_return_id) or client_year(r, tax_return["tax_year"] - 1)
+    ]
+    current = [
+        r for r in records
+        if r.get("return_id") == tax_return["return_id"] or client_year(r, tax_return["tax_year"])
+    ]
+    return prior, current
@@ -0,0 +1,80 @@
+"""
+Document Extraction
+Reads a stored document's text and parses its form fields, for documents queued by the inbox
+"""
+from typing import Dict, Any
+
+from app.documents.pages import PAGE_BREAK
+from app.documents.parsers import parse_document
+from app.documents.previews import PDF_TYPE
+from app.utils.document_store import DocumentStore
+
+
+class TextUnavailableError(Exception):
+    """Raised when a document has no text to parse (scanned image, empty PDF text layer, or no PDF reader)"""
+
+
+def document_text(content: bytes, mime_type: str) -> str:
+    """
+    Text of a PDF's text layer or a plain-text file
+
+    Args:
+        content: File bytes
+        mime_type: Document MIME type
+
+    Returns:
+        The text, pages separated by form feeds
+
+    Raises:
+        TextUnavailableError: If the document has no readable text
+    """
+    if mime_type == PDF_TYPE:
+        try:
+            import pypdfium2 as pdfium
+        except ImportError:
+            raise TextUnavailableError("Reading PDF text requires pypdfium2 to be installed")
+        pdf = pdfium.PdfDocument(content)
+        try:
+            text = PAGE_BREAK.join(page.get_textpage().get_text_range() for page in pdf)
+        finally:
+            pdf.close()
+    elif mime_type.startswith("text/"):
+        text = content.decode("utf-8", errors="replace")
+    else:
+        raise TextUnavailableError(f"Text can't be read from {mime_type} files without OCR")
+
+    if not text.replace(PAGE_BREAK, "").strip():
+        raise TextUnavailableError("The document has no text layer; it needs OCR")
+    return text
+
+
+def extract_document(store: DocumentStore, document_id: str) -> Dict[str, Any]:
+    """
+    Parse a stored document's form fields and save them on the record
+
+    A document that can't be parsed (no text, or no parser for its type) is
+    marked failed with the reason, so it can be entered by hand.
+
+    Args:
+        store: Document store holding the document
+        document_id: Document identifier
+
+    Returns:
+        Dict with document_id, extraction_status, and form or error
+
+    Raises:
+        KeyError: If the document doesn't exist
+    """
+    record = store.get_document(document_id)
+    if record is None:
+        raise KeyError(f"Document not found: {document_id}")
+
+    try:
+        text = document_text(store.read_document(document_id), record.get("mime_type", ""))
+        result = parse_document(record.get("document_type", "unknown"), text)
+    except (TextUnavailableError, ValueError) as e:
+        store.mark_extraction_failed(document_id, str(e))
+        return {"document_id": document_id, "extraction_status": "failed", "error": str(e)}
+
+    store.save_extraction(document_id, result.to_dict(), text=text)
+    return {"document_id": document_id, "extraction_status": "extracted", "form": result.form}
@@ -0,0 +1,183 @@
+"""
+Tax Inbox Watcher
+Polls a configured folder and ingests new files into the document store
+"""
+import asyncio
+import logging
+import shutil
+from collections import deque
+from datetime import datetime
+from pathlib import Path
+from typing import Callable, Dict, List, Any, Optional, Tuple
+
+from app.documents.bulk_import import infer_document_type
+from app.documents.previews import generate_thumbnail
+from app.utils.document_store import DocumentStore, DuplicateDocumentError
+
+logger = logging.getLogger(__name__)
+
+# Processed files are moved here (inside the inbox) so they aren't picked up twice
+IMPORTED_DIR = "imported"
+FAILED_DIR = "failed"
+
+MAX_EVENTS = 100
+
+
+class InboxWatcher:
+    """Polling watcher for the tax inbox folder (no OS-specific file watching needed)"""
+
+    def __init__(
+        self,
+        store: DocumentStore,
+        folder: str,
+        poll_seconds: float = 10,
+        on_event: Optional[Callable[[Dict[str, Any]], None]] = None,
+        queue_extraction: Optional[Callable[[str], Any]] = None,
+    ):
+        """
+        Initialize the watcher
+
+        Args:
+            store: Document store new files are imported into
+            folder: Inbox folder to watch
+            poll_seconds: Seconds between scans
+            on_event: Called with each event dict (for pushing to the UI)
+            queue_extraction: Called with each imported document's ID to queue
+                reading its form fields; without it documents are only stored
+        """
+        self.store = store
+        self.folder = Path(folder)
+        self.poll_seconds = poll_seconds
+        self.on_event = on_event
+        self.queue_extraction = queue_extraction
+        self.events: deque = deque(maxlen=MAX_EVENTS)
+        # path -> (size, mtime) from the previous scan; files are only imported once stable
+        self._pending: Dict[Path, Tuple[int, float]] = {}
+        self._task: Optional[asyncio.Task] = None
+
+    @property
+    def running(self) -> bool:
+        return self._task is not None and not self._task.done()
+
+    def scan(self, require_stable: bool = True) -> List[Dict[str, Any]]:
+        """
+        Import files that have stopped changing since the previous scan
+
+        A file seen for the first time is only remembered, so a download or scan
+        still being written isn't imported half-finished.
+
+        Args:
+            require_stable: False imports everything now (user-triggered scans)
+
+        Returns:
+            Events emitted by this scan
+        """
+        if not self.folder.is_dir():
+            return []
+
+        emitted = []
+        current: Dict[Path, Tuple[int, float]] = {}
+        for path in sorted(self.folder.iterdir()):
+            if not path.is_file() or path.name.startswith("."):
+                continue
+            stat = path.stat()
+            current[path] = (stat.st_size, stat.st_mtime)
+            if not require_stable or self._pending.get(path) == current[path]:
+                emitted.append(self._ingest(path))
+                del current[path]
+
+        self._pending = current
+        return emitted
+
+    def start(self) -> None:
+        """Start polling in the background on the running event loop"""
+        if not self.running:
+            self.folder.mkdir(parents=True, exist_ok=True)
+            self._task = asyncio.get_running_loop().create_task(self._run())
+
+    async def stop(self) -> None:
+        """Stop polling"""
+        if self._task is not None:
+            self._task.cancel()
+            try:
+                await self._task
+            except asyncio.CancelledError:
+                pass
+            self._task = None
+
+    def recent_events(self, since: Optional[str] = None) -> List[Dict[str, Any]]:
+        """
+        Events newest last, optionally only those after an ISO timestamp
+
+        Args:
+            since: ISO timestamp of the last event the UI has seen
+
+        Returns:
+            Event dicts
+        """
+        return [e for e in self.events if since is None or e["timestamp"] > since]
+
+    async def _run(self) -> None:
+        """Polling loop"""
+        while True:
+            try:
+                await asyncio.to_thread(self.scan)
+            except Exception as e:
+                logger.error(f"Inbox scan failed: {str(e)}")
+            await asyncio.sleep(self.poll_seconds)
+
+    def _ingest(self, path: Path) -> Dict[str, Any]:
+        """Import one file, file it away, and emit an event"""
+        event: Dict[str, Any] = {"file": path.name, "timestamp": datetime.utcnow().isoformat()}
+        try:
+            with open(path, 'rb') as f:
+                content = f.read()
+            if not content:
+                raise ValueError("File is empty")
+            if len(content) > self.store.MAX_DOCUMENT_BYTES:
+                raise ValueError("File is too large (max 25 MB)")
+
+            document_type, _ = infer_document_type(path.name, content)
+            fields = {"extraction_status": "queued"} if self.queue_extraction else {}
+            document = self.store.upload_document(
+                filename=path.name,
+                content=content,
+                document_type=document_type,
+                source="inbox",
+                **fields,
+            )
+            generate_thumbnail(self.store, document, content)
+            if self.queue_extraction:
+                self.queue_extraction(document["document_id"])
+            event.update({
+                "event": "inbox_document_imported",
+                "document_id": document["document_id"],
+                "document_type": document_type,
+            })
+            destination = IMPORTED_DIR
+        except DuplicateDocumentError as e:
+            event.update({"event": "inbox_document_duplicate", "document_id": e.existing["document_id"]})
+            destination = IMPORTED_DIR
+        except (OSError, ValueError) as e:
+            event.update({"event": "inbox_document_failed", "error": str(e)})
+            destination = FAILED_DIR
+
+        self._file_away(path, destination)
+        self.events.append(event)
+        if self.on_event:
+            self.on_event(event)
+        return event
+
+    def _file_away(self, path: Path, subfolder: str) -> None:
+        """Move a processed file into a subfolder of the inbox, never overwriting"""
+        target_dir = self.folder / subfolder
+        target_dir.mkdir(exist_ok=True)
+        target = target_dir / path.name
+        counter = 1
+        while target.exists():
+            target = target_dir / f"{path.stem} ({counter}){path.suffix}"
+            counter += 1
+        try:
+            shutil.move(str(path), str(target))
+        except OSError as e:
+            logger.error(f"Could not move {path.name} out of the inbox: {str(e)}")
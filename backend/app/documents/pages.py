@@ -0,0 +1,155 @@
+"""
+Multi-Page Documents
+Combines page images into one PDF document and splits scanned stacks apart
+"""
+import io
+from pathlib import Path
+from typing import Dict, List, Any, Optional, Tuple
+
+from app.documents.previews import IMAGE_TYPES, PDF_TYPE, _import, _open_image
+from app.utils.document_store import DocumentStore
+
+# OCR text keeps page boundaries as form feeds (the convention pdftotext uses)
+PAGE_BREAK = "\f"
+
+
+def page_texts(record: Dict[str, Any]) -> List[str]:
+    """A document's OCR text split into pages (empty if it has none)"""
+    text = record.get("ocr_text")
+    return text.split(PAGE_BREAK) if text else []
+
+
+def merge_images(
+    store: DocumentStore,
+    document_ids: List[str],
+    filename: Optional[str] = None,
+) -> Dict[str, Any]:
+    """
+    Combine image documents (e.g. phone photos of each page) into one PDF document
+
+    The source images are archived rather than deleted; their OCR text becomes
+    the new document's pages, in order.
+
+    Args:
+        store: Document store holding the images
+        document_ids: Images in page order
+        filename: Name for the new PDF (defaults to the first image's name)
+
+    Returns:
+        The new document record
+
+    Raises:
+        KeyError: If a document doesn't exist
+        ValueError: If fewer than two documents are given or one isn't an image
+        PreviewUnavailableError: If Pillow isn't installed
+    """
+    if len(document_ids) < 2:
+        raise ValueError("Choose at least two pages to merge")
+    if len(set(document_ids)) != len(document_ids):
+        raise ValueError("A page can only be used once")
+
+    records = [_get(store, document_id) for document_id in document_ids]
+    for record in records:
+        if record["mime_type"] not in IMAGE_TYPES:
+            raise ValueError(f"{record['filename']} is not an image; only images can be merged into pages")
+
+    images = [_open_image(store.read_document(r["document_id"])).convert("RGB") for r in records]
+    output = io.BytesIO()
+    images[0].save(output, format="PDF", save_all=True, append_images=images[1:], resolution=150)
+
+    first = records[0]
+    texts = [PAGE_BREAK.join(page_texts(r)) for r in records]
+    fields: Dict[str, Any] = {"merged_from": document_ids}
+    if any(texts):
+        fields["ocr_text"] = PAGE_BREAK.join(texts)
+    if first.get("tax_year"):
+        fields["tax_year"] = first["tax_year"]
+
+    merged = store.upload_document(
+        filename=filename or f"{Path(first['filename']).stem}.pdf",
+        content=output.getvalue(),
+        document_type=first.get("document_type", "unknown"),
+        return_id=first.get("return_id"),
+        **fields,
+    )
+    for record in records:
+        store.set_archived(record["document_id"])
+    return merged
+
+
+def split_pdf(
+    store: DocumentStore,
+    document_id: str,
+    ranges: Optional[List[Tuple[int, int]]] = None,
+) -> List[Dict[str, Any]]:
+    """
+    Split a PDF (e.g. a scanned stack of forms) into separate documents
+
+    The original is archived; each part keeps the OCR text of its pages.
+
+    Args:
+        store: Document store holding the PDF
+        document_id: PDF to split
+        ranges: 1-based inclusive (first, last) page ranges; one document per page if omitted
+
+    Returns:
+        The new document records, in range order
+
+    Raises:
+        KeyError: If the document doesn't exist
+        ValueError: If it isn't a PDF or a range is invalid
+        PreviewUnavailableError: If pypdfium2 isn't installed
+    """
+    record = _get(store, document_id)
+    if record["mime_type"] != PDF_TYPE:
+        raise ValueError("Only PDF documents can be split")
+
+    pdfium = _import("pypdfium2")
+    source = pdfium.PdfDocument(store.read_document(document_id))
+    try:
+        total = len(source)
+        ranges = ranges or [(page, page) for page in range(1, total + 1)]
+        for first_page, last_page in ranges:
+            if not 1 <= first_page <= last_page <= total:
+                raise ValueError(f"Invalid page range {first_page}-{last_page} (document has {total} pages)")
+        if len(ranges) < 2 and ranges[0] == (1, total):
+            raise ValueError("Splitting would produce the same document")
+
+        texts = page_texts(record)
+        stem = Path(record["filename"]).stem
+        parts = []
+        for first_page, last_page in ranges:
+            part = pdfium.PdfDocument.new()
+            part.import_pages(source, list(range(first_page - 1, last_page)))
+            output = io.BytesIO()
+            part.save(output)
+            part.close()
+
+            label = f"page {first_page}" if first_page == last_page else f"pages {first_page}-{last_page}"
+            fields: Dict[str, Any] = {"split_from": document_id, "source_pages": [first_page, last_page]}
+            if texts:
+                fields["ocr_text"] = PAGE_BREAK.join(texts[first_page - 1:last_page])
+            if record.get("tax_year"):
+                fields["tax_year"] = record["tax_year"]
+            parts.append(store.upload_document(
+                filename=f"{stem} ({label}).pdf",
+                content=output.getvalue(),
+                document_type=record.get("document_type", "unknown"),
+                return_id=record.get("return_id"),
+                # Identical pages (blank separator sheets) are still separate pages
+                allow_duplicate=True,
+                **fields,
+            ))
+    finally:
+        source.close()
+
+    store.set_archived(document_id)
+    return parts
+
+
+def _get(store: DocumentStore, document_id: str) -> Dict[str, Any]:
+    """Load a record or raise KeyError"""
+    record = store.get_document(document_id)
+    if record is None:
+        raise KeyError(f"Document not found: {document_id}")
+    return record
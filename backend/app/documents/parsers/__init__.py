@@ -0,0 +1,55 @@
+"""
+Deterministic Document Parsers
+Offline extraction of tax form fields from OCR/PDF text
+"""
+from typing import Callable, Dict
+
+from app.documents.parsers.common import ParseResult
+from app.documents.parsers.form_1095_a import parse_1095_a
+from app.documents.parsers.form_1098_t import parse_1098_t
+from app.documents.parsers.form_1099_b import parse_1099_b
+from app.documents.parsers.form_1099_div import parse_1099_div
+from app.documents.parsers.form_1099_int import parse_1099_int
+from app.documents.parsers.form_1099_k import parse_1099_k
+from app.documents.parsers.form_1099_misc import parse_1099_misc
+from app.documents.parsers.form_1099_nec import parse_1099_nec
+from app.documents.parsers.receipt import parse_receipt
+from app.documents.parsers.w2 import parse_w2
+
+# Document type (as used by the API) -> parser
+PARSERS: Dict[str, Callable[[str], ParseResult]] = {
+    "W-2": parse_w2,
+    "1099-INT": parse_1099_int,
+    "1099-DIV": parse_1099_div,
+    "1099-NEC": parse_1099_nec,
+    "1099-MISC": parse_1099_misc,
+    "1099-K": parse_1099_k,
+    "1099-B": parse_1099_b,
+    "1095-A": parse_1095_a,
+    "1098-T": parse_1098_t,
+    "receipt": parse_receipt,
+}
+
+
+def parse_document(document_type: str, text: str) -> ParseResult:
+    """
+    Parse form text with the parser for its document type
+
+    Args:
+        document_type: Form name, e.g. "W-2" or "1099-DIV" (case and dashes are ignored)
+        text: OCR or PDF-extracted text
+
+    Returns:
+        ParseResult
+
+    Raises:
+        ValueError: If no parser exists for the document type
+    """
+    normalized = {key.replace("-", "").upper(): parser for key, parser in PARSERS.items()}
+    parser = normalized.get(document_type.replace("-", "").replace(" ", "").upper())
+    if parser is None:
+        raise ValueError(
+            f"No parser for document type: {document_type}. "
+            f"Supported: {', '.join(PARSERS)}"
+        )
+    return parser(text)
@@ -0,0 +1,221 @@
+"""
+Shared Parsing Helpers
+Amount/label matching and per-field confidence for OCR'd tax forms
+"""
+import re
+from dataclasses import asdict, dataclass, field, fields
+from decimal import Decimal, InvalidOperation
+from typing import Dict, List, Any, Optional, Tuple
+
+# Dollar amounts as printed on forms: 85,000.00 / 85000 / $1,234.5
+AMOUNT = r"\$?\s*(-?\d{1,3}(?:,\d{3})+(?:\.\d{1,2})?|-?\d+(?:\.\d{1,2})?)"
+
+# Gap allowed between a box label and its value (OCR often wraps onto the next line)
+LABEL_GAP = r"[\s:.\-|]{0,12}"
+
+# An amount must not run into more digits or into the next label on the same line
+# ("1 Wages, tips, other compensation 2 Federal ..." must not read box 1 as $2)
+AMOUNT_END = r"(?![\d,]|[ \t]+[A-Za-z])"
+
+EIN_PATTERN = re.compile(r"\b\d{2}-\d{7}\b")
+
+CHECK_MARK = r"(?:\[\s*[xX✓✔]\s*\]|☒|✓|✔|\b[xX]\b)"
+
+
+@dataclass
+class ExtractedField:
+    """A single value pulled from a form, with how sure we are about it"""
+    value: Any
+    confidence: float
+    raw: Optional[str] = None
+
+    def to_dict(self) -> Dict[str, Any]:
+        value = float(self.value) if isinstance(self.value, Decimal) else self.value
+        return {"value": value, "confidence": round(self.confidence, 2), "raw": self.raw}
+
+
+@dataclass
+class ParseResult:
+    """Fields extracted from one form plus parser warnings"""
+    form: str
+    fields: Dict[str, ExtractedField] = field(default_factory=dict)
+    warnings: List[str] = field(default_factory=list)
+    # Form-specific typed record (Form1099Div, ...) built from the fields
+    record: Optional[Any] = None
+
+    def value(self, name: str, default: Any = None) -> Any:
+        """Get a field's value (default if not found)"""
+        extracted = self.fields.get(name)
+        return extracted.value if extracted else default
+
+    @property
+    def overall_confidence(self) -> float:
+        """Mean confidence across extracted fields (0 if nothing was found)"""
+        if not self.fields:
+            return 0.0
+        return sum(f.confidence for f in self.fields.values()) / len(self.fields)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "form": self.form,
+            "fields": {name: f.to_dict() for name, f in self.fields.items()},
+            "overall_confidence": round(self.overall_confidence, 2),
+            "record": _jsonable(asdict(self.record)) if self.record is not None else None,
+            "warnings": self.warnings,
+            "needs_review": self.overall_confidence < 0.8 or bool(self.warnings),
+        }
+
+
+def _jsonable(value: Any) -> Any:
+    """Decimals to floats, recursively"""
+    if isinstance(value, Decimal):
+        return float(value)
+    if isinstance(value, dict):
+        return {k: _jsonable(v) for k, v in value.items()}
+    if isinstance(value, list):
+        return [_jsonable(v) for v in value]
+    return value
+
+
+def build_record(record_cls: Any, result: ParseResult) -> Any:
+    """
+    Fill a form's record dataclass from the parsed fields
+
+    Fields that weren't found keep the dataclass defaults.
+    """
+    values = {f.name: result.fields[f.name].value for f in fields(record_cls) if f.name in result.fields}
+    return record_cls(**values)
+
+
+def parse_amount(raw: str) -> Optional[Decimal]:
+    """Convert a printed amount ("$85,000.00") to Decimal"""
+    cleaned = raw.replace("$", "").replace(",", "").strip()
+    try:
+        return Decimal(cleaned).quantize(Decimal("0.01"))
+    except (InvalidOperation, ValueError):
+        return None
+
+
+def find_box_amount(
+    text: str,
+    box: str,
+    label: str,
+) -> Optional[ExtractedField]:
+    """
+    Find a numbered box's dollar amount
+
+    Tries, in order of confidence:
+      1. box number followed by its label ("1 Wages, tips, other compensation 85,000.00")
+      2. the label alone ("Wages, tips, other compensation: 85,000.00")
+      3. a bare "Box 1" reference ("Box 1: 85,000.00")
+
+    Args:
+        text: OCR/PDF text of the form
+        box: Box number/letter as printed ("1", "12a")
+        label: Regex for the box label
+
+    Returns:
+        ExtractedField or None if the box wasn't found
+    """
+    attempts = [
+        (rf"(?<![\w.]){re.escape(box)}\s+{label}{LABEL_GAP}{AMOUNT}{AMOUNT_END}", 0.95),
+        (rf"{label}{LABEL_GAP}{AMOUNT}{AMOUNT_END}", 0.85),
+        (rf"\bbox\s*{re.escape(box)}\b{LABEL_GAP}{AMOUNT}{AMOUNT_END}", 0.8),
+    ]
+    for pattern, confidence in attempts:
+        match = re.search(pattern, text, re.IGNORECASE)
+        if match:
+            amount = parse_amount(match.group(1))
+            if amount is not None:
+                return ExtractedField(amount, confidence, match.group(0).strip())
+    return None
+
+
+def find_amount_boxes(text: str, result: ParseResult, boxes: Dict[str, Tuple[str, str]]) -> None:
+    """
+    Extract every dollar box in a form's box table
+
+    Args:
+        text: OCR/PDF text of the form
+        result: ParseResult to add fields to
+        boxes: Field name mapped to (box number, label regex)
+    """
+    for name, (box, label) in boxes.items():
+        extracted = find_box_amount(text, box, label)
+        if extracted:
+            result.fields[name] = extracted
+
+
+def find_payer(text: str, result: ParseResult) -> None:
+    """Payer name and TIN as printed on 1099-series forms"""
+    tin = find_ein(text, r"payer'?s\s+(?:TIN|federal\s+identification\s+number)")
+    if tin:
+        result.fields["payer_tin"] = tin
+    name = find_line_after(text, r"payer'?s\s+name")
+    if name:
+        result.fields["payer_name"] = name
+
+
+def find_checkbox(text: str, label: str) -> Optional[ExtractedField]:
+    """
+    Read a checkbox next to its label
+
+    Returns:
+        ExtractedField(True/False), or None if the label isn't on the page
+    """
+    if not re.search(label, text, re.IGNORECASE):
+        return None
+    checked = re.search(
+        rf"{CHECK_MARK}[ \t]*{label}|{label}[ \t:]*(?:{CHECK_MARK}|yes\b)",
+        text,
+        re.IGNORECASE,
+    )
+    # An unmarked label is weak evidence: OCR often drops check marks
+    return ExtractedField(bool(checked), 0.85 if checked else 0.6)
+
+
+def find_ein(text: str, label: str = r"(?:employer(?:'s)?\s+identification\s+number|\bEIN)") -> Optional[ExtractedField]:
+    """Find an EIN, preferring one that follows its label"""
+    match = re.search(rf"{label}[^\d]{{0,40}}(\d{{2}}-\d{{7}})", text, re.IGNORECASE)
+    if match:
+        return ExtractedField(match.group(1), 0.95, match.group(0).strip())
+    match = EIN_PATTERN.search(text)
+    if match:
+        return ExtractedField(match.group(0), 0.6, match.group(0))
+    return None
+
+
+def find_line_after(text: str, label: str) -> Optional[ExtractedField]:
+    """Take the first non-empty line after a label line (names and addresses)"""
+    match = re.search(rf"{label}[^\n]*\n\s*([^\n]+)", text, re.IGNORECASE)
+    if match and match.group(1).strip():
+        return ExtractedField(match.group(1).strip(), 0.7, match.group(1).strip())
+    return None
+
+
+def check_ratio(
+    result: ParseResult,
+    tax_field: str,
+    base_field: str,
+    rate: Decimal,
+    description: str,
+) -> None:
+    """
+    Cross-check a withholding box against its wage base
+
+    Matching values raise both fields' confidence; mismatches lower it and add a warning.
+    """
+    tax, base = result.fields.get(tax_field), result.fields.get(base_field)
+    if not tax or not base or base.value <= 0:
+        return
+
+    expected = (base.value * rate).quantize(Decimal("0.01"))
+    if abs(tax.value - expected) <= Decimal("1.00"):
+        tax.confidence = min(0.99, tax.confidence + 0.04)
+        base.confidence = min(0.99, base.confidence + 0.04)
+    else:
+        tax.confidence = min(tax.confidence, 0.6)
+        result.warnings.append(
+            f"{description} (${tax.value:,.2f}) is not {rate:.2%} of the wage base "
+            f"(${base.value:,.2f}, expected ${expected:,.2f})"
+        )
@@ -0,0 +1,141 @@
+"""
+1095-A Parser
+Monthly premium / SLCSP / advance PTC columns from OCR/PDF text of Form 1095-A
+"""
+import re
+from dataclasses import dataclass, field
+from decimal import Decimal
+from typing import List, Optional
+
+from app.documents.parsers.common import AMOUNT, ExtractedField, ParseResult, parse_amount
+
+# Part III rows 21-32
+MONTHS = [
+    "january", "february", "march", "april", "may", "june",
+    "july", "august", "september", "october", "november", "december",
+]
+
+
+@dataclass
+class MonthlyCoverage:
+    """One row of 1095-A Part III"""
+    month: int
+    enrollment_premium: Decimal = Decimal("0")  # column A
+    slcsp_premium: Decimal = Decimal("0")  # column B
+    advance_ptc: Decimal = Decimal("0")  # column C
+
+
+@dataclass
+class Form1095A:
+    """Extracted 1095-A"""
+    policy_number: Optional[str] = None
+    issuer_name: Optional[str] = None
+    months: List[MonthlyCoverage] = field(default_factory=list)
+
+    @property
+    def total_premium(self) -> Decimal:
+        return sum((m.enrollment_premium for m in self.months), Decimal("0"))
+
+    @property
+    def total_advance_ptc(self) -> Decimal:
+        return sum((m.advance_ptc for m in self.months), Decimal("0"))
+
+
+def parse_1095_a(text: str) -> ParseResult:
+    """
+    Parse Form 1095-A text
+
+    Args:
+        text: OCR or PDF-extracted text
+
+    Returns:
+        ParseResult with one field per month (month_01..month_12) and a Form1095A record
+    """
+    result = ParseResult(form="1095-A")
+
+    for name, label in (
+        ("policy_number", r"marketplace[-\s]assigned\s+policy\s+number"),
+        ("issuer_name", r"policy\s+issuer'?s\s+name"),
+    ):
+        extracted = _find_text(text, label)
+        if extracted:
+            result.fields[name] = extracted
+
+    record = Form1095A(
+        policy_number=result.value("policy_number"),
+        issuer_name=result.value("issuer_name"),
+    )
+    for index, month in enumerate(MONTHS, start=1):
+        coverage = _parse_month_row(text, index, month, result)
+        record.months.append(coverage)
+
+    _check_annual_totals(text, record, result)
+
+    if not any(m.enrollment_premium for m in record.months):
+        result.warnings.append("No monthly premiums were found in Part III; check the document or enter them manually")
+
+    result.record = record
+    return result
+
+
+def _find_text(text: str, label: str) -> Optional[ExtractedField]:
+    """Text value on the label's line, or the next line if the label stands alone"""
+    match = re.search(rf"{label}[ \t:.|-]*([^\n]*)(?:\n[ \t]*([^\n]+))?", text, re.IGNORECASE)
+    if not match:
+        return None
+    value = (match.group(1) or match.group(2) or "").strip()
+    return ExtractedField(value, 0.75, match.group(0).strip()) if value else None
+
+
+def _parse_month_row(text: str, index: int, month: str, result: ParseResult) -> MonthlyCoverage:
+    """Read columns A-C for one month; a row with no amounts means no coverage"""
+    coverage = MonthlyCoverage(month=index)
+    line = 20 + index
+    match = re.search(
+        rf"^[ \t]*(?:{line}[ \t.]+)?(?:{month}|{month[:3]}\.?)\b([^\n]*)$",
+        text,
+        re.IGNORECASE | re.MULTILINE,
+    )
+    if not match:
+        return coverage
+
+    amounts = [parse_amount(raw) for raw in re.findall(AMOUNT, match.group(1))]
+    name = f"month_{index:02d}"
+    if len(amounts) == 3 and None not in amounts:
+        coverage.enrollment_premium, coverage.slcsp_premium, coverage.advance_ptc = amounts
+        result.fields[name] = ExtractedField(
+            {"premium": amounts[0], "slcsp": amounts[1], "advance_ptc": amounts[2]},
+            0.9,
+            match.group(0).strip(),
+        )
+    elif amounts:
+        # Can't tell which column a lone amount belongs to
+        result.fields[name] = ExtractedField(None, 0.3, match.group(0).strip())
+        result.warnings.append(
+            f"{month.capitalize()} (line {line}) has {len(amounts)} amount(s) instead of 3; enter it manually"
+        )
+    return coverage
+
+
+def _check_annual_totals(text: str, record: Form1095A, result: ParseResult) -> None:
+    """Compare line 33 against the sum of the monthly rows"""
+    match = re.search(r"(?:33[ \t.]+)?annual\s+totals?\b([^\n]*)", text, re.IGNORECASE)
+    if not match:
+        return
+    totals = [parse_amount(raw) for raw in re.findall(AMOUNT, match.group(1))]
+    if len(totals) != 3 or None in totals:
+        return
+
+    computed = [
+        record.total_premium,
+        sum((m.slcsp_premium for m in record.months), Decimal("0")),
+        record.total_advance_ptc,
+    ]
+    month_fields = [f for name, f in result.fields.items() if name.startswith("month_") and f.value]
+    if all(abs(total - calc) <= Decimal("1.00") for total, calc in zip(totals, computed)):
+        for extracted in month_fields:
+            extracted.confidence = 0.97
+    else:
+        for extracted in month_fields:
+            extracted.confidence = min(extracted.confidence, 0.6)
+        result.warnings.append("Monthly rows do not add up to the annual totals (line 33)")
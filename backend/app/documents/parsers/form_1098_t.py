@@ -0,0 +1,87 @@
+"""
+1098-T Parser
+Tuition statement boxes from OCR/PDF text of Form 1098-T
+"""
+from dataclasses import dataclass
+from decimal import Decimal
+from typing import Optional
+
+from app.documents.parsers.common import (
+    ParseResult,
+    build_record,
+    find_amount_boxes,
+    find_checkbox,
+    find_ein,
+    find_line_after,
+)
+
+AMOUNT_BOXES = {
+    "payments_received": ("1", r"payments\s+received\s+for\s+qualified\s+tuition\s+and\s+related\s+expenses"),
+    "prior_year_adjustments": ("4", r"adjustments\s+made\s+for\s+a\s+prior\s+year"),
+    "scholarships_grants": ("5", r"scholarships\s+or\s+grants"),
+    "prior_year_scholarship_adjustments": ("6", r"adjustments\s+to\s+scholarships\s+or\s+grants\s+for\s+a\s+prior\s+year"),
+    "reimbursements_refunds": ("10", r"ins(?:urance|\.)\s+contract\s+reimb(?:ursement|\.)?\s*/\s*refund"),
+}
+
+CHECKBOXES = {
+    "next_period_included": r"academic\s+period\s+beginning\s+january\s*[-\u2013]\s*march(?:\s+\d{4})?",
+    "half_time_student": r"at\s+least\s+half[-\s]time\s+student",
+    "graduate_student": r"graduate\s+student",
+}
+
+
+@dataclass
+class Form1098T:
+    """Extracted 1098-T"""
+    filer_name: Optional[str] = None
+    filer_tin: Optional[str] = None
+    student_name: Optional[str] = None
+    payments_received: Decimal = Decimal("0")
+    prior_year_adjustments: Decimal = Decimal("0")
+    scholarships_grants: Decimal = Decimal("0")
+    prior_year_scholarship_adjustments: Decimal = Decimal("0")
+    reimbursements_refunds: Decimal = Decimal("0")
+    next_period_included: bool = False
+    half_time_student: bool = False
+    graduate_student: bool = False
+
+
+def parse_1098_t(text: str) -> ParseResult:
+    """
+    Parse Form 1098-T text
+
+    Box 1 is what the school received for the year, which can include
+    tuition for a term starting in January-March of the next year (box 7).
+
+    Args:
+        text: OCR or PDF-extracted text
+
+    Returns:
+        ParseResult whose record is a Form1098T
+    """
+    result = ParseResult(form="1098-T")
+    name = find_line_after(text, r"filer'?s\s+name")
+    if name:
+        result.fields["filer_name"] = name
+    tin = find_ein(text, r"filer'?s\s+(?:employer\s+identification\s+no|federal\s+identification\s+number|TIN)")
+    if tin:
+        result.fields["filer_tin"] = tin
+    student = find_line_after(text, r"student'?s\s+name")
+    if student:
+        result.fields["student_name"] = student
+
+    find_amount_boxes(text, result, AMOUNT_BOXES)
+    for field_name, label in CHECKBOXES.items():
+        extracted = find_checkbox(text, label)
+        if extracted:
+            result.fields[field_name] = extracted
+
+    if "payments_received" not in result.fields:
+        result.warnings.append("Payments received (box 1) wasn't found; enter tuition paid from the school's records")
+    if result.value("next_period_included"):
+        result.warnings.append("Box 1 includes tuition for a term starting next year; it counts in the year it was paid")
+    if result.value("prior_year_adjustments") or result.value("prior_year_scholarship_adjustments"):
+        result.warnings.append("Prior-year adjustments (boxes 4 and 6) may require recapturing last year's credit")
+
+    result.record = build_record(Form1098T, result)
+    return result
@@ -0,0 +1,122 @@
+"""
+1099-B Parser
+Proceeds-from-broker boxes from OCR/PDF text of a single-transaction Form 1099-B
+"""
+import re
+from dataclasses import dataclass
+from datetime import date, datetime
+from decimal import Decimal
+from typing import Optional
+
+from app.documents.parsers.common import (
+    ExtractedField,
+    ParseResult,
+    build_record,
+    find_amount_boxes,
+    find_checkbox,
+    find_payer,
+)
+
+AMOUNT_BOXES = {
+    "proceeds": ("1d", r"proceeds"),
+    "cost_basis": ("1e", r"cost\s+or\s+other\s+basis"),
+    "accrued_market_discount": ("1f", r"accrued\s+market\s+discount"),
+    "wash_sale_loss_disallowed": ("1g", r"wash\s+sale\s+loss\s+disallowed"),
+    "federal_withholding": ("4", r"federal\s+income\s+tax\s+withheld"),
+}
+
+DATE = r"(\d{1,2}/\d{1,2}/\d{2,4}|various)"
+
+
+@dataclass
+class Form1099B:
+    """Extracted 1099-B transaction"""
+    payer_name: Optional[str] = None
+    payer_tin: Optional[str] = None
+    description: Optional[str] = None
+    date_acquired: Optional[str] = None
+    date_sold: Optional[str] = None
+    proceeds: Decimal = Decimal("0")
+    cost_basis: Decimal = Decimal("0")
+    accrued_market_discount: Decimal = Decimal("0")
+    wash_sale_loss_disallowed: Decimal = Decimal("0")
+    federal_withholding: Decimal = Decimal("0")
+    term: Optional[str] = None  # "short" or "long"
+    gain_or_loss: Decimal = Decimal("0")
+
+
+def parse_1099_b(text: str) -> ParseResult:
+    """
+    Parse Form 1099-B text for one sale
+
+    Args:
+        text: OCR or PDF-extracted text
+
+    Returns:
+        ParseResult whose record is a Form1099B, including the computed gain_or_loss
+    """
+    result = ParseResult(form="1099-B")
+    find_payer(text, result)
+    find_amount_boxes(text, result, AMOUNT_BOXES)
+
+    match = re.search(r"(?<![\w.])1a\s+description\s+of\s+property[ \t:.\-|]*\n?[ \t]*([^\n]+)", text, re.IGNORECASE)
+    if match and match.group(1).strip():
+        result.fields["description"] = ExtractedField(match.group(1).strip(), 0.75, match.group(0).strip())
+
+    for name, box, label in (("date_acquired", "1b", r"date\s+acquired"), ("date_sold", "1c", r"date\s+sold(?:\s+or\s+disposed)?")):
+        match = re.search(rf"(?:{box}\s+)?{label}[\s:.\-|]*{DATE}", text, re.IGNORECASE)
+        if match:
+            # Brokers print VARIOUS for lots bought on several dates
+            value = "VARIOUS" if match.group(1).lower() == "various" else match.group(1)
+            result.fields[name] = ExtractedField(value, 0.9, match.group(0).strip())
+
+    _parse_term(text, result)
+    _compute_gain(result)
+    result.record = build_record(Form1099B, result)
+    return result
+
+
+def _parse_term(text: str, result: ParseResult) -> None:
+    """Short/long-term from the box 2 checkbox, falling back to the holding period"""
+    for term in ("short", "long"):
+        checkbox = find_checkbox(text, rf"{term}[-\s]term")
+        if checkbox and checkbox.value:
+            result.fields["term"] = ExtractedField(term, 0.85)
+            return
+
+    acquired, sold = _to_date(result.value("date_acquired")), _to_date(result.value("date_sold"))
+    if acquired and sold:
+        # Long-term means held more than one year
+        try:
+            one_year_later = acquired.replace(year=acquired.year + 1)
+        except ValueError:  # Feb 29
+            one_year_later = acquired.replace(year=acquired.year + 1, day=28)
+        result.fields["term"] = ExtractedField("long" if sold > one_year_later else "short", 0.8)
+
+
+def _compute_gain(result: ParseResult) -> None:
+    """Gain/loss = proceeds - basis + disallowed wash sale loss"""
+    proceeds, basis = result.fields.get("proceeds"), result.fields.get("cost_basis")
+    if not proceeds or not basis:
+        if proceeds and not basis:
+            result.warnings.append("Cost basis (box 1e) was not found; basis may not have been reported to the IRS")
+        return
+
+    wash_sale = result.value("wash_sale_loss_disallowed", Decimal("0"))
+    if wash_sale and proceeds.value >= basis.value:
+        result.warnings.append("Wash sale loss disallowed (box 1g) reported on a sale without a loss")
+
+    gain = proceeds.value - basis.value + wash_sale
+    result.fields["gain_or_loss"] = ExtractedField(gain, min(proceeds.confidence, basis.confidence))
+
+
+def _to_date(value: Optional[str]) -> Optional[date]:
+    """Parse mm/dd/yy(yy); None for VARIOUS or unreadable dates"""
+    if not value:
+        return None
+    for fmt in ("%m/%d/%Y", "%m/%d/%y"):
+        try:
+            return datetime.strptime(value, fmt).date()
+        except ValueError:
+            continue
+    return None
@@ -0,0 +1,84 @@
+"""
+1099-DIV Parser
+Dividend boxes from OCR/PDF text of Form 1099-DIV, keeping ordinary and qualified apart
+"""
+from dataclasses import dataclass
+from decimal import Decimal
+from typing import Optional
+
+from app.documents.parsers.common import ParseResult, build_record, find_amount_boxes, find_payer
+
+AMOUNT_BOXES = {
+    "ordinary_dividends": ("1a", r"total\s+ordinary\s+dividends"),
+    "qualified_dividends": ("1b", r"qualified\s+dividends"),
+    "capital_gain_distributions": ("2a", r"total\s+capital\s+gain\s+distr(?:ibutions|\.)?"),
+    "unrecaptured_1250_gain": ("2b", r"unrecap(?:tured|\.)?\s+sec(?:tion|\.)?\s+1250\s+gain"),
+    "section_1202_gain": ("2c", r"section\s+1202\s+gain"),
+    "collectibles_gain": ("2d", r"collectibles\s+\(28%\)\s+gain"),
+    "nondividend_distributions": ("3", r"nondividend\s+distributions"),
+    "federal_withholding": ("4", r"federal\s+income\s+tax\s+withheld"),
+    "section_199a_dividends": ("5", r"section\s+199A\s+dividends"),
+    "investment_expenses": ("6", r"investment\s+expenses"),
+    "foreign_tax_paid": ("7", r"foreign\s+tax\s+paid"),
+    "exempt_interest_dividends": ("12", r"exempt[-\s]interest\s+dividends"),
+}
+
+# 2b-2d are portions of the 2a total
+CAPITAL_GAIN_PARTS = ["unrecaptured_1250_gain", "section_1202_gain", "collectibles_gain"]
+
+
+@dataclass
+class Form1099Div:
+    """Extracted 1099-DIV"""
+    payer_name: Optional[str] = None
+    payer_tin: Optional[str] = None
+    ordinary_dividends: Decimal = Decimal("0")
+    qualified_dividends: Decimal = Decimal("0")
+    capital_gain_distributions: Decimal = Decimal("0")
+    unrecaptured_1250_gain: Decimal = Decimal("0")
+    section_1202_gain: Decimal = Decimal("0")
+    collectibles_gain: Decimal = Decimal("0")
+    nondividend_distributions: Decimal = Decimal("0")
+    federal_withholding: Decimal = Decimal("0")
+    section_199a_dividends: Decimal = Decimal("0")
+    investment_expenses: Decimal = Decimal("0")
+    foreign_tax_paid: Decimal = Decimal("0")
+    exempt_interest_dividends: Decimal = Decimal("0")
+
+    @property
+    def nonqualified_dividends(self) -> Decimal:
+        """Ordinary dividends taxed at regular rates"""
+        return self.ordinary_dividends - self.qualified_dividends
+
+
+def parse_1099_div(text: str) -> ParseResult:
+    """
+    Parse Form 1099-DIV text
+
+    Args:
+        text: OCR or PDF-extracted text
+
+    Returns:
+        ParseResult whose record is a Form1099Div
+    """
+    result = ParseResult(form="1099-DIV")
+    find_payer(text, result)
+    find_amount_boxes(text, result, AMOUNT_BOXES)
+
+    ordinary, qualified = result.value("ordinary_dividends"), result.value("qualified_dividends")
+    if qualified is not None and qualified > (ordinary or Decimal("0")):
+        result.fields["qualified_dividends"].confidence = 0.5
+        result.warnings.append("Qualified dividends (box 1b) exceed total ordinary dividends (box 1a)")
+
+    total_gain = result.value("capital_gain_distributions", Decimal("0"))
+    for name in CAPITAL_GAIN_PARTS:
+        part = result.value(name)
+        if part is not None and part > total_gain:
+            result.fields[name].confidence = 0.5
+            result.warnings.append(f"{name.replace('_', ' ').capitalize()} exceeds total capital gain distributions (box 2a)")
+
+    if "ordinary_dividends" not in result.fields:
+        result.warnings.append("Box 1a (ordinary dividends) was not found; check the document or enter it manually")
+
+    result.record = build_record(Form1099Div, result)
+    return result
@@ -0,0 +1,66 @@
+"""
+1099-INT Parser
+Interest income boxes from OCR/PDF text of Form 1099-INT
+"""
+from dataclasses import dataclass
+from decimal import Decimal
+from typing import Optional
+
+from app.documents.parsers.common import ParseResult, build_record, find_amount_boxes, find_payer
+
+AMOUNT_BOXES = {
+    "interest_income": ("1", r"interest\s+income"),
+    "early_withdrawal_penalty": ("2", r"early\s+withdrawal\s+penalty"),
+    "us_savings_bond_interest": ("3", r"interest\s+on\s+u\.?\s?s\.?\s+savings\s+bonds(?:\s+and\s+treas(?:ury|\.)\s+obligations)?"),
+    "federal_withholding": ("4", r"federal\s+income\s+tax\s+withheld"),
+    "investment_expenses": ("5", r"investment\s+expenses"),
+    "foreign_tax_paid": ("6", r"foreign\s+tax\s+paid"),
+    "tax_exempt_interest": ("8", r"tax[-\s]exempt\s+interest"),
+    "private_activity_bond_interest": ("9", r"specified\s+private\s+activity\s+bond\s+interest"),
+    "market_discount": ("10", r"market\s+discount"),
+    "bond_premium": ("11", r"bond\s+premium"),
+}
+
+
+@dataclass
+class Form1099Int:
+    """Extracted 1099-INT"""
+    payer_name: Optional[str] = None
+    payer_tin: Optional[str] = None
+    interest_income: Decimal = Decimal("0")
+    early_withdrawal_penalty: Decimal = Decimal("0")
+    us_savings_bond_interest: Decimal = Decimal("0")
+    federal_withholding: Decimal = Decimal("0")
+    investment_expenses: Decimal = Decimal("0")
+    foreign_tax_paid: Decimal = Decimal("0")
+    tax_exempt_interest: Decimal = Decimal("0")
+    private_activity_bond_interest: Decimal = Decimal("0")
+    market_discount: Decimal = Decimal("0")
+    bond_premium: Decimal = Decimal("0")
+
+
+def parse_1099_int(text: str) -> ParseResult:
+    """
+    Parse Form 1099-INT text
+
+    Args:
+        text: OCR or PDF-extracted text
+
+    Returns:
+        ParseResult whose record is a Form1099Int
+    """
+    result = ParseResult(form="1099-INT")
+    find_payer(text, result)
+    find_amount_boxes(text, result, AMOUNT_BOXES)
+
+    # Box 9 is a subset of box 8
+    private, exempt = result.value("private_activity_bond_interest"), result.value("tax_exempt_interest")
+    if private is not None and private > (exempt or Decimal("0")):
+        result.fields["private_activity_bond_interest"].confidence = 0.5
+        result.warnings.append("Private activity bond interest (box 9) exceeds tax-exempt interest (box 8)")
+
+    if "interest_income" not in result.fields and "tax_exempt_interest" not in result.fields:
+        result.warnings.append("No interest amount was found; check the document or enter it manually")
+
+    result.record = build_record(Form1099Int, result)
+    return result
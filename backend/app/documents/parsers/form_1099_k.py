@@ -0,0 +1,103 @@
+"""
+1099-K Parser
+Payment card and third party network boxes from OCR/PDF text of Form 1099-K
+"""
+import re
+from dataclasses import dataclass
+from decimal import Decimal
+from typing import Optional
+
+from app.documents.parsers.common import (
+    ExtractedField,
+    ParseResult,
+    build_record,
+    find_amount_boxes,
+    find_checkbox,
+    find_ein,
+    find_line_after,
+    find_payer,
+)
+
+AMOUNT_BOXES = {
+    "gross_amount": ("1a", r"gross\s+amount\s+of\s+payment\s+card/?\s*third\s+party\s+network\s+transactions"),
+    "card_not_present": ("1b", r"card\s+not\s+present\s+transactions"),
+    "federal_withholding": ("4", r"federal\s+income\s+tax\s+withheld"),
+    "state_withholding": ("8", r"state\s+income\s+tax\s+withheld"),
+}
+
+MONTHS = ["january", "february", "march", "april", "may", "june",
+          "july", "august", "september", "october", "november", "december"]
+
+# Boxes 5a-5l: the gross amount for each month
+MONTH_BOXES = {month: (f"5{letter}", rf"\b{month}\b") for month, letter in zip(MONTHS, "abcdefghijkl")}
+
+
+@dataclass
+class Form1099K:
+    """Extracted 1099-K"""
+    payer_name: Optional[str] = None
+    payer_tin: Optional[str] = None
+    third_party_network: bool = False
+    gross_amount: Decimal = Decimal("0")
+    card_not_present: Decimal = Decimal("0")
+    merchant_category_code: Optional[str] = None
+    transaction_count: Optional[int] = None
+    federal_withholding: Decimal = Decimal("0")
+    monthly_total: Decimal = Decimal("0")
+    state_withholding: Decimal = Decimal("0")
+
+
+def parse_1099_k(text: str) -> ParseResult:
+    """
+    Parse Form 1099-K text
+
+    The filer (the payment settlement entity or platform) is read into
+    payer_name and payer_tin like the other 1099 forms. The monthly boxes
+    are checked against box 1a.
+
+    Args:
+        text: OCR or PDF-extracted text
+
+    Returns:
+        ParseResult whose record is a Form1099K
+    """
+    result = ParseResult(form="1099-K")
+    tin = find_ein(text, r"filer'?s\s+(?:TIN|federal\s+identification\s+number)")
+    if tin:
+        result.fields["payer_tin"] = tin
+    name = find_line_after(text, r"filer'?s\s+name")
+    if name:
+        result.fields["payer_name"] = name
+    if not tin or not name:
+        find_payer(text, result)
+    find_amount_boxes(text, result, AMOUNT_BOXES)
+
+    network = find_checkbox(text, r"third\s+party\s+network")
+    if network:
+        result.fields["third_party_network"] = network
+
+    code = re.search(r"\b2\s+merchant\s+category\s+code[\s:.\-|]{0,12}(\d{4})\b", text, re.IGNORECASE)
+    if code:
+        result.fields["merchant_category_code"] = ExtractedField(code.group(1), 0.9, code.group(0).strip())
+    count = re.search(r"\b3\s+number\s+of\s+payment\s+transactions[\s:.\-|]{0,12}(\d[\d,]*)", text, re.IGNORECASE)
+    if count:
+        result.fields["transaction_count"] = ExtractedField(int(count.group(1).replace(",", "")), 0.9, count.group(0).strip())
+
+    months = ParseResult(form="1099-K")
+    find_amount_boxes(text, months, MONTH_BOXES)
+    if months.fields:
+        total = sum((f.value for f in months.fields.values()), Decimal("0"))
+        confidence = min(f.confidence for f in months.fields.values())
+        result.fields["monthly_total"] = ExtractedField(total, confidence)
+        gross = result.fields.get("gross_amount")
+        if gross and abs(gross.value - total) > Decimal("1.00"):
+            gross.confidence = min(gross.confidence, 0.6)
+            result.warnings.append(
+                f"The monthly amounts (boxes 5a-5l) add up to ${total:,.2f}, not the box 1a gross of ${gross.value:,.2f}"
+            )
+
+    if "gross_amount" not in result.fields:
+        result.warnings.append("Box 1a (gross amount) was not found; check the document or enter it manually")
+
+    result.record = build_record(Form1099K, result)
+    return result
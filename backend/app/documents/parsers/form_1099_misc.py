@@ -0,0 +1,66 @@
+"""
+1099-MISC Parser
+Miscellaneous income boxes from OCR/PDF text of Form 1099-MISC
+"""
+from dataclasses import dataclass
+from decimal import Decimal
+from typing import Optional
+
+from app.documents.parsers.common import ParseResult, build_record, find_amount_boxes, find_payer
+
+AMOUNT_BOXES = {
+    "rents": ("1", r"\brents"),
+    "royalties": ("2", r"royalties"),
+    "other_income": ("3", r"other\s+income"),
+    "federal_withholding": ("4", r"federal\s+income\s+tax\s+withheld"),
+    "fishing_boat_proceeds": ("5", r"fishing\s+boat\s+proceeds"),
+    "medical_payments": ("6", r"medical\s+and\s+health\s+care\s+payments"),
+    "substitute_payments": ("8", r"substitute\s+payments\s+in\s+lieu\s+of\s+dividends\s+or\s+interest"),
+    "crop_insurance_proceeds": ("9", r"crop\s+insurance\s+proceeds"),
+    "attorney_proceeds": ("10", r"gross\s+proceeds\s+paid\s+to\s+an\s+attorney"),
+    "section_409a_deferrals": ("12", r"section\s+409A\s+deferrals"),
+    "nonqualified_deferred_compensation": ("15", r"nonqualified\s+deferred\s+compensation"),
+    "state_withholding": ("16", r"state\s+tax\s+withheld"),
+    "state_income": ("18", r"state\s+income"),
+}
+
+
+@dataclass
+class Form1099Misc:
+    """Extracted 1099-MISC"""
+    payer_name: Optional[str] = None
+    payer_tin: Optional[str] = None
+    rents: Decimal = Decimal("0")
+    royalties: Decimal = Decimal("0")
+    other_income: Decimal = Decimal("0")
+    federal_withholding: Decimal = Decimal("0")
+    fishing_boat_proceeds: Decimal = Decimal("0")
+    medical_payments: Decimal = Decimal("0")
+    substitute_payments: Decimal = Decimal("0")
+    crop_insurance_proceeds: Decimal = Decimal("0")
+    attorney_proceeds: Decimal = Decimal("0")
+    section_409a_deferrals: Decimal = Decimal("0")
+    nonqualified_deferred_compensation: Decimal = Decimal("0")
+    state_withholding: Decimal = Decimal("0")
+    state_income: Decimal = Decimal("0")
+
+
+def parse_1099_misc(text: str) -> ParseResult:
+    """
+    Parse Form 1099-MISC text
+
+    Args:
+        text: OCR or PDF-extracted text
+
+    Returns:
+        ParseResult whose record is a Form1099Misc
+    """
+    result = ParseResult(form="1099-MISC")
+    find_payer(text, result)
+    find_amount_boxes(text, result, AMOUNT_BOXES)
+
+    if not any(name in result.fields for name in ("rents", "royalties", "other_income")):
+        result.warnings.append("No income boxes (1-3) were found; check the document or enter them manually")
+
+    result.record = build_record(Form1099Misc, result)
+    return result
@@ -0,0 +1,59 @@
+"""
+1099-NEC Parser
+Nonemployee compensation boxes from OCR/PDF text of Form 1099-NEC
+"""
+from dataclasses import dataclass
+from decimal import Decimal
+from typing import Optional
+
+from app.documents.parsers.common import (
+    ParseResult,
+    build_record,
+    find_amount_boxes,
+    find_checkbox,
+    find_payer,
+)
+
+AMOUNT_BOXES = {
+    "nonemployee_compensation": ("1", r"nonemployee\s+compensation"),
+    "federal_withholding": ("4", r"federal\s+income\s+tax\s+withheld"),
+    "state_withholding": ("5", r"state\s+tax\s+withheld"),
+    "state_income": ("7", r"state\s+income"),
+}
+
+
+@dataclass
+class Form1099Nec:
+    """Extracted 1099-NEC"""
+    payer_name: Optional[str] = None
+    payer_tin: Optional[str] = None
+    nonemployee_compensation: Decimal = Decimal("0")
+    direct_sales_over_5000: bool = False
+    federal_withholding: Decimal = Decimal("0")
+    state_withholding: Decimal = Decimal("0")
+    state_income: Decimal = Decimal("0")
+
+
+def parse_1099_nec(text: str) -> ParseResult:
+    """
+    Parse Form 1099-NEC text
+
+    Args:
+        text: OCR or PDF-extracted text
+
+    Returns:
+        ParseResult whose record is a Form1099Nec
+    """
+    result = ParseResult(form="1099-NEC")
+    find_payer(text, result)
+    find_amount_boxes(text, result, AMOUNT_BOXES)
+
+    direct_sales = find_checkbox(text, r"direct\s+sales\s+(?:totaling\s+)?\$?5,?000")
+    if direct_sales:
+        result.fields["direct_sales_over_5000"] = direct_sales
+
+    if "nonemployee_compensation" not in result.fields:
+        result.warnings.append("Box 1 (nonemployee compensation) was not found; check the document or enter it manually")
+
+    result.record = build_record(Form1099Nec, result)
+    return result
@@ -0,0 +1,132 @@
+"""
+Receipt Parser
+Vendor, total, date, and deduction category from OCR text of a receipt
+"""
+import re
+from dataclasses import dataclass
+from datetime import datetime
+from decimal import Decimal
+from typing import Optional
+
+from app.documents.parsers.common import (
+    AMOUNT,
+    AMOUNT_END,
+    LABEL_GAP,
+    ExtractedField,
+    ParseResult,
+    build_record,
+    parse_amount,
+)
+
+# Deduction category (as used by ReturnStore) -> vendor/line keywords
+CATEGORY_KEYWORDS = {
+    "medical": r"pharmacy|clinic|hospital|medical|dental|dentist|optometr|physician|urgent\s+care|\brx\b",
+    "charitable": r"donation|donor|charit|church|ministr|foundation|goodwill|salvation\s+army|contribution",
+    "state_local_tax": r"property\s+tax|county\s+treasurer|tax\s+collector|vehicle\s+registration",
+    "mortgage_interest": r"mortgage\s+interest",
+}
+
+# Prices always print cents; this keeps store numbers and ZIP codes out of the fallback
+PRICE = r"\$?\s*(\d{1,3}(?:,\d{3})+\.\d{2}|\d+\.\d{2})\b"
+
+DATE_FORMATS = [
+    (r"\b(\d{1,2}/\d{1,2}/\d{4})\b", "%m/%d/%Y"),
+    (r"\b(\d{4}-\d{2}-\d{2})\b", "%Y-%m-%d"),
+    (r"\b(\d{1,2}/\d{1,2}/\d{2})\b", "%m/%d/%y"),
+    (r"\b([A-Z][a-z]{2}\s+\d{1,2},?\s+\d{4})\b", "%b %d %Y"),
+]
+
+
+@dataclass
+class Receipt:
+    """Extracted receipt"""
+    vendor: Optional[str] = None
+    amount: Decimal = Decimal("0")
+    date: Optional[str] = None
+    category: Optional[str] = None
+
+
+def parse_receipt(text: str) -> ParseResult:
+    """
+    Parse receipt text
+
+    Args:
+        text: OCR or PDF-extracted text
+
+    Returns:
+        ParseResult whose record is a Receipt (date as YYYY-MM-DD, category one
+        of ReturnStore.DEDUCTION_CATEGORIES)
+    """
+    result = ParseResult(form="receipt")
+
+    vendor = _find_vendor(text)
+    if vendor:
+        result.fields["vendor"] = vendor
+
+    amount = _find_total(text)
+    if amount:
+        result.fields["amount"] = amount
+    else:
+        result.warnings.append("Receipt total was not found; enter the amount manually")
+
+    date = _find_date(text)
+    if date:
+        result.fields["date"] = date
+
+    category = _guess_category(text)
+    if category:
+        result.fields["category"] = category
+    else:
+        result.warnings.append("Couldn't tell what kind of expense this is; choose a deduction category")
+
+    result.record = build_record(Receipt, result)
+    return result
+
+
+def _find_vendor(text: str) -> Optional[ExtractedField]:
+    """The first line that reads like a name (receipts print the vendor at the top)"""
+    for line in text.splitlines():
+        line = line.strip()
+        if len(line) >= 3 and re.search(r"[A-Za-z]{3}", line) and not re.search(r"receipt|invoice", line, re.IGNORECASE):
+            return ExtractedField(line, 0.6, line)
+    return None
+
+
+def _find_total(text: str) -> Optional[ExtractedField]:
+    """The total line, else the largest amount on the receipt"""
+    total = re.search(
+        rf"(?<!sub )\b(?:grand\s+)?total(?:\s+(?:due|paid|amount))?{LABEL_GAP}{AMOUNT}{AMOUNT_END}",
+        text,
+        re.IGNORECASE,
+    )
+    if total:
+        amount = parse_amount(total.group(1))
+        if amount is not None:
+            return ExtractedField(amount, 0.9, total.group(0).strip())
+
+    amounts = [a for a in (parse_amount(m) for m in re.findall(PRICE, text)) if a is not None and a > 0]
+    if amounts:
+        return ExtractedField(max(amounts), 0.5)
+    return None
+
+
+def _find_date(text: str) -> Optional[ExtractedField]:
+    """First date on the receipt, as YYYY-MM-DD"""
+    for pattern, date_format in DATE_FORMATS:
+        match = re.search(pattern, text)
+        if match:
+            try:
+                parsed = datetime.strptime(match.group(1).replace(",", ""), date_format)
+            except ValueError:
+                continue
+            return ExtractedField(parsed.date().isoformat(), 0.85, match.group(1))
+    return None
+
+
+def _guess_category(text: str) -> Optional[ExtractedField]:
+    """Deduction category from keywords"""
+    for category, keywords in CATEGORY_KEYWORDS.items():
+        match = re.search(keywords, text, re.IGNORECASE)
+        if match:
+            return ExtractedField(category, 0.6, match.group(0))
+    return None
@@ -0,0 +1,158 @@
+"""
+W-2 Parser
+Deterministic box-label extraction from OCR/PDF text of Form W-2
+"""
+import re
+from decimal import Decimal
+from typing import Optional
+
+from app.documents.parsers.common import (
+    AMOUNT,
+    AMOUNT_END,
+    ExtractedField,
+    ParseResult,
+    check_ratio,
+    find_amount_boxes,
+    find_checkbox,
+    find_ein,
+    find_line_after,
+    parse_amount,
+)
+
+# Dollar boxes: field name -> (box number, label regex)
+AMOUNT_BOXES = {
+    "wages": ("1", r"wages,?\s*tips,?\s*(?:and\s+)?other\s+comp(?:ensation|\.)?"),
+    "federal_withholding": ("2", r"federal\s+income\s+tax\s+withheld"),
+    "social_security_wages": ("3", r"social\s+security\s+wages"),
+    "social_security_tax": ("4", r"social\s+security\s+tax\s+withheld"),
+    "medicare_wages": ("5", r"medicare\s+wages\s+and\s+tips"),
+    "medicare_tax": ("6", r"medicare\s+tax\s+withheld"),
+    "social_security_tips": ("7", r"social\s+security\s+tips"),
+    "allocated_tips": ("8", r"allocated\s+tips"),
+    "dependent_care_benefits": ("10", r"dependent\s+care\s+benefits"),
+    "nonqualified_plans": ("11", r"nonqualified\s+plans"),
+    "state_wages": ("16", r"state\s+wages,?\s*tips,?\s*etc\.?"),
+    "state_income_tax": ("17", r"state\s+income\s+tax"),
+    "local_wages": ("18", r"local\s+wages,?\s*tips,?\s*etc\.?"),
+    "local_income_tax": ("19", r"local\s+income\s+tax"),
+}
+
+# Box 12 codes printed on the 2024 W-2 instructions
+BOX_12_CODES = {
+    "A", "B", "C", "D", "E", "F", "G", "H", "J", "K", "L", "M", "N", "P", "Q", "R",
+    "S", "T", "V", "W", "Y", "Z", "AA", "BB", "DD", "EE", "FF", "GG", "HH", "II",
+}
+
+BOX_13_CHECKBOXES = {
+    "statutory_employee": r"statutory\s+employee",
+    "retirement_plan": r"retirement\s+plan",
+    "third_party_sick_pay": r"third[-\s]party\s+sick\s+pay",
+}
+
+SOCIAL_SECURITY_RATE = Decimal("0.062")
+MEDICARE_RATE = Decimal("0.0145")
+# 2024 social security wage base; box 3 + box 7 should never exceed it
+SOCIAL_SECURITY_WAGE_BASE = Decimal("168600")
+# Above this, employers withhold an extra 0.9% Medicare so the ratio check no longer holds
+ADDITIONAL_MEDICARE_THRESHOLD = Decimal("200000")
+
+
+def parse_w2(text: str) -> ParseResult:
+    """
+    Parse Form W-2 text into fields with confidence scores
+
+    Args:
+        text: OCR or PDF-extracted text of a W-2
+
+    Returns:
+        ParseResult with fields named after AMOUNT_BOXES plus employer_ein,
+        employer_name, box_12_<letter>, box 13 checkboxes, other (box 14),
+        state, state_employer_id and locality_name
+    """
+    result = ParseResult(form="W-2")
+
+    ein = find_ein(text)
+    if ein:
+        result.fields["employer_ein"] = ein
+
+    employer = find_line_after(text, r"employer'?s\s+name")
+    if employer:
+        result.fields["employer_name"] = employer
+
+    find_amount_boxes(text, result, AMOUNT_BOXES)
+
+    _parse_box_12(text, result)
+    _parse_box_13(text, result)
+    _parse_box_14(text, result)
+    _parse_state_and_locality(text, result)
+    _cross_check(result)
+
+    if "wages" not in result.fields:
+        result.warnings.append("Box 1 (wages) was not found; check the document or enter it manually")
+    return result
+
+
+def _parse_box_12(text: str, result: ParseResult) -> None:
+    """Box 12a-12d: code + amount"""
+    pattern = rf"\b12\s*([a-d])\b(?:\s*code)?[\s:.\-|]*([A-Z]{{1,2}})\b[\s:.\-|]*{AMOUNT}{AMOUNT_END}"
+    for match in re.finditer(pattern, text, re.IGNORECASE):
+        code = match.group(2).upper()
+        amount = parse_amount(match.group(3))
+        if amount is None:
+            continue
+        confidence = 0.9 if code in BOX_12_CODES else 0.5
+        result.fields[f"box_12_{match.group(1).lower()}"] = ExtractedField(
+            {"code": code, "amount": amount}, confidence, match.group(0).strip()
+        )
+        if code not in BOX_12_CODES:
+            result.warnings.append(f"Box 12{match.group(1).lower()} code '{code}' is not a valid W-2 code")
+
+
+def _parse_box_13(text: str, result: ParseResult) -> None:
+    """Box 13 checkboxes; only reported when the label is present"""
+    for name, label in BOX_13_CHECKBOXES.items():
+        extracted = find_checkbox(text, label)
+        if extracted:
+            result.fields[name] = extracted
+
+
+def _parse_box_14(text: str, result: ParseResult) -> None:
+    """Box 14 'Other' is free text (union dues, SDI, ...)"""
+    match = re.search(r"(?<![\w.])14\s+other\b[ \t:.\-|]*([^\n]+)", text, re.IGNORECASE)
+    if match and match.group(1).strip():
+        result.fields["other"] = ExtractedField(match.group(1).strip(), 0.7, match.group(0).strip())
+
+
+def _parse_state_and_locality(text: str, result: ParseResult) -> None:
+    """Box 15 (state + employer state ID) and box 20 (locality name)"""
+    state_label = r"(?i:15\s+state\b(?:[\s|/]*employer'?s\s+state\s+id\s+(?:number|no\.?))?)"
+    match = re.search(rf"{state_label}[\s:.\-|]*([A-Z]{{2}})\b(?:[ \t|/]+([A-Z0-9][A-Z0-9\-]{{3,}}))?", text)
+    if match:
+        result.fields["state"] = ExtractedField(match.group(1), 0.9, match.group(0).strip())
+        if match.group(2):
+            result.fields["state_employer_id"] = ExtractedField(match.group(2), 0.8, match.group(2))
+
+    match = re.search(r"(?<![\w.])20\s+locality\s+name[ \t:.\-|]*\n?[ \t]*([A-Za-z][A-Za-z .'-]*)", text, re.IGNORECASE)
+    if match and match.group(1).strip():
+        result.fields["locality_name"] = ExtractedField(match.group(1).strip(), 0.7, match.group(0).strip())
+
+
+def _cross_check(result: ParseResult) -> None:
+    """Adjust confidence using the arithmetic relationships between boxes"""
+    check_ratio(result, "social_security_tax", "social_security_wages", SOCIAL_SECURITY_RATE, "Social security tax")
+
+    medicare_wages: Optional[Decimal] = result.value("medicare_wages")
+    if medicare_wages is not None and medicare_wages <= ADDITIONAL_MEDICARE_THRESHOLD:
+        check_ratio(result, "medicare_tax", "medicare_wages", MEDICARE_RATE, "Medicare tax")
+
+    ss_wages = result.value("social_security_wages", Decimal("0")) + result.value("social_security_tips", Decimal("0"))
+    if ss_wages > SOCIAL_SECURITY_WAGE_BASE:
+        result.warnings.append(
+            f"Social security wages and tips (${ss_wages:,.2f}) exceed the "
+            f"${SOCIAL_SECURITY_WAGE_BASE:,.0f} wage base"
+        )
+
+    wages, withholding = result.value("wages"), result.value("federal_withholding")
+    if wages is not None and withholding is not None and withholding > wages:
+        result.fields["federal_withholding"].confidence = min(result.fields["federal_withholding"].confidence, 0.5)
+        result.warnings.append("Federal withholding (box 2) is larger than wages (box 1)")
@@ -0,0 +1,164 @@
+"""
+Document Previews
+Renders document pages and thumbnails to PNG so the UI never reads raw files
+"""
+import io
+from typing import Dict, Any, Optional, Tuple
+
+from app.utils.document_store import DocumentStore
+
+THUMBNAIL_SIZE = (256, 256)
+PREVIEW_SIZE = (1600, 1600)
+
+IMAGE_TYPES = {"image/png", "image/jpeg", "image/gif", "image/tiff", "image/bmp", "image/webp"}
+PDF_TYPE = "application/pdf"
+
+# Render PDFs at 2x (144 dpi) before downscaling so small print stays legible
+PDF_RENDER_SCALE = 2
+
+
+class PreviewUnavailableError(Exception):
+    """Raised when a document can't be rendered (unsupported type or missing renderer)"""
+
+
+def page_count(content: bytes, mime_type: str) -> int:
+    """
+    Number of renderable pages
+
+    Args:
+        content: File bytes
+        mime_type: Document MIME type
+
+    Returns:
+        Page count (frames for multi-page images)
+    """
+    if mime_type == PDF_TYPE:
+        pdfium = _import("pypdfium2")
+        pdf = pdfium.PdfDocument(content)
+        try:
+            return len(pdf)
+        finally:
+            pdf.close()
+    if mime_type in IMAGE_TYPES:
+        image = _open_image(content)
+        return getattr(image, "n_frames", 1)
+    raise PreviewUnavailableError(f"Preview not available for {mime_type} files")
+
+
+def render_page(
+    content: bytes,
+    mime_type: str,
+    page: int = 1,
+    max_size: Tuple[int, int] = PREVIEW_SIZE,
+) -> bytes:
+    """
+    Render one page to PNG, scaled to fit max_size
+
+    Args:
+        content: File bytes
+        mime_type: Document MIME type
+        page: 1-based page number
+        max_size: Bounding box (width, height)
+
+    Returns:
+        PNG bytes
+
+    Raises:
+        PreviewUnavailableError: Unsupported type or renderer not installed
+        ValueError: Page out of range
+    """
+    if page < 1:
+        raise ValueError("Page numbers start at 1")
+
+    if mime_type == PDF_TYPE:
+        image = _render_pdf_page(content, page)
+    elif mime_type in IMAGE_TYPES:
+        image = _open_image(content)
+        frames = getattr(image, "n_frames", 1)
+        if page > frames:
+            raise ValueError(f"Page {page} out of range (document has {frames})")
+        image.seek(page - 1)
+        image = _import("PIL.ImageOps").exif_transpose(image)
+    else:
+        raise PreviewUnavailableError(f"Preview not available for {mime_type} files")
+
+    image = image.convert("RGB")
+    image.thumbnail(max_size)
+    output = io.BytesIO()
+    image.save(output, format="PNG", optimize=True)
+    return output.getvalue()
+
+
+def render_thumbnail(content: bytes, mime_type: str) -> bytes:
+    """First page as a small PNG"""
+    return render_page(content, mime_type, page=1, max_size=THUMBNAIL_SIZE)
+
+
+def thumbnail_jpeg(png: bytes) -> Tuple[bytes, int, int]:
+    """
+    Convert a stored PNG thumbnail to a baseline JPEG for embedding in PDFs
+
+    Args:
+        png: Thumbnail bytes from DocumentStore.get_thumbnail
+
+    Returns:
+        (JPEG bytes, width, height)
+
+    Raises:
+        PreviewUnavailableError: Pillow not installed or the thumbnail is unreadable
+    """
+    image = _open_image(png).convert("RGB")
+    output = io.BytesIO()
+    image.save(output, format="JPEG", quality=85)
+    return output.getvalue(), image.width, image.height
+
+
+def generate_thumbnail(store: DocumentStore, document: Dict[str, Any], content: bytes) -> Optional[bytes]:
+    """
+    Render and store a document's thumbnail
+
+    Args:
+        store: DocumentStore holding the document
+        document: Document record
+        content: Document bytes
+
+    Returns:
+        PNG bytes, or None for documents that can't be previewed
+    """
+    try:
+        png = render_thumbnail(content, document["mime_type"])
+    except (PreviewUnavailableError, ValueError):
+        return None
+    store.save_thumbnail(document["document_id"], png)
+    return png
+
+
+def _render_pdf_page(content: bytes, page: int):
+    """Rasterize a PDF page to a PIL image"""
+    pdfium = _import("pypdfium2")
+    pdf = pdfium.PdfDocument(content)
+    try:
+        if page > len(pdf):
+            raise ValueError(f"Page {page} out of range (document has {len(pdf)})")
+        # Copy so the image doesn't share memory with the bitmap freed on close
+        return pdf[page - 1].render(scale=PDF_RENDER_SCALE).to_pil().copy()
+    finally:
+        pdf.close()
+
+
+def _open_image(content: bytes):
+    """Open image bytes with Pillow"""
+    image_module = _import("PIL.Image")
+    try:
+        return image_module.open(io.BytesIO(content))
+    except Exception as e:
+        raise PreviewUnavailableError(f"Image could not be read: {e}")
+
+
+def _import(module: str):
+    """Import an optional rendering dependency"""
+    try:
+        return __import__(module, fromlist=["_"])
+    except ImportError:
+        package = "Pillow" if module.startswith("PIL") else module
+        raise PreviewUnavailableError(f"Preview rendering requires {package} to be installed")
@@ -0,0 +1,214 @@
+"""
+TXF Import
+Reads Tax Exchange Format (TXF V042) exports from commercial tax software into a new return
+"""
+from datetime import datetime
+from decimal import Decimal, InvalidOperation
+from typing import Dict, List, Any, Optional, Tuple
+
+from app.utils.return_store import ReturnStore
+
+# TXF reference number -> (income type, field); W-2 and 1099 amounts
+TXF_INCOME: Dict[str, Tuple[str, str]] = {
+    "460": ("wages", "amount"),          # Salary or wages, self
+    "461": ("wages", "withholding"),     # Federal tax withheld, self
+    "287": ("interest", "amount"),       # Interest income
+    "286": ("dividends", "amount"),      # Dividend income, ordinary
+}
+
+# TXF reference number -> deduction category (Schedule A)
+TXF_DEDUCTIONS: Dict[str, str] = {
+    "274": "medical",                    # Doctors, dentists, hospitals
+    "280": "charitable",                 # Cash contributions
+    "283": "mortgage_interest",          # Home mortgage interest (1098)
+}
+
+# TXF reference number -> holding period (Form 8949 detail records)
+TXF_CAPITAL: Dict[str, str] = {
+    "321": "short",
+    "323": "long",
+}
+
+
+def parse_txf(content: str) -> Dict[str, Any]:
+    """
+    Split a TXF file into its header and records
+
+    Args:
+        content: TXF text
+
+    Returns:
+        Dict with header (version, program, exported) and records, each with
+        refnum, copy, description, amounts, and dates in file order
+
+    Raises:
+        ValueError: If the file isn't TXF
+    """
+    blocks = [block.strip().splitlines() for block in content.lstrip("﻿").split("^")]
+    header_lines = blocks[0] if blocks else []
+    if not header_lines or not header_lines[0].startswith("V"):
+        raise ValueError("Not a TXF file (it must start with a V042 version line)")
+
+    header: Dict[str, Any] = {"version": header_lines[0][1:].strip(), "program": None, "exported": None}
+    for line in header_lines[1:]:
+        if line.startswith("A"):
+            header["program"] = line[1:].strip()
+        elif line.startswith("D"):
+            header["exported"] = _parse_date(line[1:])
+
+    records = []
+    for block in blocks[1:]:
+        if not block:
+            continue
+        record: Dict[str, Any] = {
+            "type": None, "refnum": None, "copy": 1, "line": None,
+            "description": "", "amounts": [], "dates": [],
+        }
+        for raw in block:
+            line = raw.strip()
+            if not line:
+                continue
+            code, value = line[0], line[1:].strip()
+            if code == "T":
+                record["type"] = value
+            elif code == "N":
+                record["refnum"] = value
+            elif code == "C":
+                record["copy"] = int(value) if value.isdigit() else 1
+            elif code == "L":
+                record["line"] = value
+            elif code == "P":
+                record["description"] = value
+            elif code == "$":
+                record["amounts"].append(_parse_amount(value))
+            elif code == "D":
+                record["dates"].append(_parse_date(value))
+        if record["refnum"]:
+            records.append(record)
+    return {"header": header, "records": records}
+
+
+def import_txf(
+    return_store: ReturnStore,
+    content: str,
+    tax_year: int = 2024,
+    filing_status: str = "single",
+    taxpayer: Optional[Dict[str, Any]] = None,
+) -> Dict[str, Any]:
+    """
+    Create a return from a TXF export
+
+    W-2 wage and withholding records with the same copy number and payer
+    become one income source.
+
+    Args:
+        return_store: Store to create the return in
+        content: TXF text
+        tax_year: Year of the new return
+        filing_status: Filing status of the new return
+        taxpayer: Taxpayer info for the new return
+
+    Returns:
+        Dict with the new return, counts of created records, and warnings for
+        records that couldn't be imported
+
+    Raises:
+        ValueError: If the file isn't TXF or has nothing importable
+    """
+    parsed = parse_txf(content)
+    warnings: List[str] = []
+    sources: Dict[Tuple[str, int, str], Dict[str, Any]] = {}
+    deductions: List[Dict[str, Any]] = []
+    lots: List[Dict[str, Any]] = []
+    skipped: Dict[str, int] = {}
+
+    for number, record in enumerate(parsed["records"], start=1):
+        refnum = record["refnum"]
+        amounts = [a for a in record["amounts"] if a is not None]
+        if refnum in TXF_INCOME:
+            income_type, field = TXF_INCOME[refnum]
+            if not amounts:
+                warnings.append(f"Record {number} (N{refnum}) has no amount")
+                continue
+            key = (income_type, record["copy"], record["description"])
+            source = sources.setdefault(key, {
+                "type": income_type, "description": record["description"], "amount": 0.0, "withholding": 0.0,
+            })
+            source[field] += float(abs(amounts[0]))
+        elif refnum in TXF_DEDUCTIONS:
+            if not amounts:
+                warnings.append(f"Record {number} (N{refnum}) has no amount")
+                continue
+            deductions.append({
+                "category": TXF_DEDUCTIONS[refnum],
+                "amount": float(abs(amounts[0])),
+                "description": record["description"],
+            })
+        elif refnum in TXF_CAPITAL:
+            if len(amounts) < 2 or len(record["dates"]) < 2 or record["dates"][1] is None:
+                warnings.append(f"Record {number} (N{refnum}) needs acquired/sold dates, cost, and proceeds")
+                continue
+            cost, proceeds = amounts[0], amounts[1]
+            wash_sale = abs(amounts[2]) if len(amounts) > 2 else Decimal("0")
+            lots.append({
+                "description": record["description"],
+                "date_acquired": record["dates"][0],
+                "date_sold": record["dates"][1],
+                "proceeds": float(proceeds),
+                "cost_basis": float(cost),
+                "wash_sale_disallowed": float(wash_sale),
+                "term": TXF_CAPITAL[refnum],
+                "gain_or_loss": float(proceeds - cost + wash_sale),
+            })
+        else:
+            skipped[refnum] = skipped.get(refnum, 0) + 1
+
+    for refnum, count in sorted(skipped.items()):
+        warnings.append(f"Skipped {count} record(s) with unsupported reference number N{refnum}")
+    if not (sources or deductions or lots):
+        raise ValueError("The TXF file has no income, deduction, or capital gain records that can be imported")
+
+    tax_return = return_store.create_return(tax_year=tax_year, filing_status=filing_status, taxpayer=taxpayer)
+    return_id = tax_return["return_id"]
+    imported_from = {"source": "txf", "txf_program": parsed["header"]["program"]}
+    for source in sources.values():
+        return_store.add_income_source(
+            return_id,
+            source_type=source["type"],
+            amount=source["amount"],
+            description=source["description"],
+            withholding=source["withholding"],
+            **imported_from,
+        )
+    for deduction in deductions:
+        return_store.add_deduction(return_id, **deduction, **imported_from)
+    if lots:
+        return_store.add_capital_transactions(return_id, lots, **imported_from)
+
+    return {
+        "return": return_store.get_return(return_id),
+        "imported": {
+            "income_sources": len(sources),
+            "deductions": len(deductions),
+            "capital_transactions": len(lots),
+        },
+        "warnings": warnings,
+    }
+
+
+def _parse_amount(raw: str) -> Optional[Decimal]:
+    """TXF amount ("$-9500.00" lines, without the $)"""
+    try:
+        return Decimal(raw.replace(",", ""))
+    except InvalidOperation:
+        return None
+
+
+def _parse_date(raw: str) -> Optional[str]:
+    """ISO date from TXF's MM/DD/YYYY (or VARIOUS)"""
+    if raw.strip().upper() == "VARIOUS":
+        return "VARIOUS"
+    try:
+        return datetime.strptime(raw.strip(), "%m/%d/%Y").date().isoformat()
+    except ValueError:
+        return None
@@ -0,0 +1 @@
+"""IRS Modernized e-File (MeF) export"""
@@ -0,0 +1,267 @@
+"""
+MeF XML Export
+Serializes a return into IRS Modernized e-File XML (Form 1040 schema subset) and validates it
+"""
+import re
+import xml.etree.ElementTree as ET
+from datetime import datetime
+from decimal import Decimal, ROUND_HALF_UP
+from typing import Dict, List, Any, Optional, Tuple
+
+EFILE_NAMESPACE = "http://www.irs.gov/efile"
+RETURN_VERSION = "2024v5.0"
+
+FILING_STATUS_CODES = {
+    "single": "1",
+    "married_joint": "2",
+    "married_separate": "3",
+    "head_of_household": "4",
+    "qualifying_surviving_spouse": "5",
+}
+
+BANK_ACCOUNT_TYPE_CODES = {"checking": "1", "savings": "2"}
+
+# Simple types of the schema subset (MeF amounts are whole dollars)
+TYPE_PATTERNS = {
+    "amount": r"-?\d{1,15}",
+    "year": r"\d{4}",
+    "date": r"\d{4}-\d{2}-\d{2}",
+    "timestamp": r"\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z",
+    "ssn": r"\d{9}",
+    "ein": r"\d{9}",
+    "name_control": r"[A-Z][A-Z\-& ]{0,3}",
+    "name": r"[A-Za-z0-9\-&' ]{1,35}",
+    "business_name": r"[A-Za-z0-9\-&'(),.# ]{1,75}",
+    "street": r"[A-Za-z0-9\-/ ]{1,35}",
+    "city": r"[A-Za-z ]{3,22}",
+    "state": r"[A-Z]{2}",
+    "zip": r"\d{5}(\d{4})?",
+    "filing_status": r"[1-5]",
+    "return_type": r"1040",
+    "routing_number": r"[0-3]\d{8}",
+    "account_number": r"[A-Za-z0-9\-]{1,17}",
+    "account_type": r"[12]",
+    "ip_pin": r"\d{6}",
+}
+
+# Element path (under Return) -> (type, required)
+SCHEMA: Dict[str, Tuple[str, bool]] = {
+    "ReturnHeader/ReturnTs": ("timestamp", True),
+    "ReturnHeader/TaxYr": ("year", True),
+    "ReturnHeader/TaxPeriodBeginDt": ("date", True),
+    "ReturnHeader/TaxPeriodEndDt": ("date", True),
+    "ReturnHeader/ReturnTypeCd": ("return_type", True),
+    "ReturnHeader/Filer/PrimarySSN": ("ssn", True),
+    "ReturnHeader/Filer/SpouseSSN": ("ssn", False),
+    "ReturnHeader/Filer/NameLine1Txt": ("name", True),
+    "ReturnHeader/Filer/PrimaryNameControlTxt": ("name_control", True),
+    "ReturnHeader/Filer/USAddress/AddressLine1Txt": ("street", True),
+    "ReturnHeader/Filer/USAddress/CityNm": ("city", True),
+    "ReturnHeader/Filer/USAddress/StateAbbreviationCd": ("state", True),
+    "ReturnHeader/Filer/USAddress/ZIPCd": ("zip", True),
+    "ReturnHeader/IdentityProtectionPIN": ("ip_pin", False),
+    "ReturnHeader/SpouseIdentityProtectionPIN": ("ip_pin", False),
+    "ReturnData/IRS1040/IndividualReturnFilingStatusCd": ("filing_status", True),
+    "ReturnData/IRS1040/WagesAmt": ("amount", False),
+    "ReturnData/IRS1040/TaxExemptInterestAmt": ("amount", False),
+    "ReturnData/IRS1040/TaxableInterestAmt": ("amount", False),
+    "ReturnData/IRS1040/QualifiedDividendsAmt": ("amount", False),
+    "ReturnData/IRS1040/OrdinaryDividendsAmt": ("amount", False),
+    "ReturnData/IRS1040/TaxableSocSecAmt": ("amount", False),
+    "ReturnData/IRS1040/CapitalGainLossAmt": ("amount", False),
+    "ReturnData/IRS1040/TotalIncomeAmt": ("amount", True),
+    "ReturnData/IRS1040/AdjustedGrossIncomeAmt": ("amount", True),
+    "ReturnData/IRS1040/TotalItemizedOrStandardDedAmt": ("amount", True),
+    "ReturnData/IRS1040/TaxableIncomeAmt": ("amount", True),
+    "ReturnData/IRS1040/TaxAmt": ("amount", True),
+    "ReturnData/IRS1040/CTCODCAmt": ("amount", False),
+    "ReturnData/IRS1040/SelfEmploymentTaxAmt": ("amount", False),
+    "ReturnData/IRS1040/TotalTaxAmt": ("amount", True),
+    "ReturnData/IRS1040/WithholdingTaxAmt": ("amount", True),
+    "ReturnData/IRS1040/OverpaidAmt": ("amount", False),
+    "ReturnData/IRS1040/RefundAmt": ("amount", False),
+    "ReturnData/IRS1040/RoutingTransitNum": ("routing_number", False),
+    "ReturnData/IRS1040/BankAccountTypeCd": ("account_type", False),
+    "ReturnData/IRS1040/DepositorAccountNum": ("account_number", False),
+    "ReturnData/IRS1040/OwedAmt": ("amount", False),
+}
+
+# Repeating documents: path -> {child: (type, required)}
+REPEATING_SCHEMA: Dict[str, Dict[str, Tuple[str, bool]]] = {
+    "ReturnData/IRSW2": {
+        "EmployerEIN": ("ein", True),
+        "EmployerName/BusinessNameLine1Txt": ("business_name", True),
+        "WagesAmt": ("amount", True),
+        "WithholdingAmt": ("amount", True),
+    },
+}
+
+
+def _dollars(value: Any) -> str:
+    """Whole-dollar amount as MeF expects it"""
+    return str(Decimal(str(value or 0)).quantize(Decimal("1"), rounding=ROUND_HALF_UP))
+
+
+def _digits(value: Optional[str]) -> str:
+    return re.sub(r"\D", "", value or "")
+
+
+def name_control(name: str) -> str:
+    """First four letters of the last name, uppercased"""
+    last = name.split()[-1] if name.split() else ""
+    return re.sub(r"[^A-Z\-]", "", last.upper())[:4]
+
+
+def build_return_xml(
+    tax_return: Dict[str, Any],
+    calculation: Dict[str, Any],
+    timestamp: Optional[datetime] = None,
+    direct_deposit: Optional[Dict[str, Any]] = None,
+    ip_pins: Optional[Dict[str, Dict[str, Any]]] = None,
+) -> bytes:
+    """
+    Serialize a return as MeF XML
+
+    Args:
+        tax_return: Return dict from ReturnStore
+        calculation: calculate_return() output for the return
+        timestamp: Return timestamp (defaults to now)
+        direct_deposit: Unmasked ReturnStore.get_direct_deposit() details for
+            depositing a refund
+        ip_pins: Unmasked ReturnStore.get_ip_pins() PINs for the taxpayer and spouse
+
+    Returns:
+        UTF-8 XML document
+    """
+    ET.register_namespace("", EFILE_NAMESPACE)
+
+    def sub(parent: ET.Element, tag: str, text: Optional[str] = None) -> ET.Element:
+        element = ET.SubElement(parent, f"{{{EFILE_NAMESPACE}}}{tag}")
+        if text is not None:
+            element.text = text
+        return element
+
+    taxpayer = tax_return.get("taxpayer") or {}
+    year = tax_return["tax_year"]
+    root = ET.Element(f"{{{EFILE_NAMESPACE}}}Return", {"returnVersion": RETURN_VERSION})
+
+    header = sub(root, "ReturnHeader")
+    header.set("binaryAttachmentCnt", "0")
+    sub(header, "ReturnTs", (timestamp or datetime.utcnow()).strftime("%Y-%m-%dT%H:%M:%SZ"))
+    sub(header, "TaxYr", str(year))
+    sub(header, "TaxPeriodBeginDt", f"{year}-01-01")
+    sub(header, "TaxPeriodEndDt", f"{year}-12-31")
+    sub(header, "ReturnTypeCd", "1040")
+    filer = sub(header, "Filer")
+    sub(filer, "PrimarySSN", _digits(taxpayer.get("ssn")))
+    if taxpayer.get("spouse_ssn"):
+        sub(filer, "SpouseSSN", _digits(taxpayer.get("spouse_ssn")))
+    sub(filer, "NameLine1Txt", taxpayer.get("name", ""))
+    sub(filer, "PrimaryNameControlTxt", name_control(taxpayer.get("name", "")))
+    address = sub(filer, "USAddress")
+    sub(address, "AddressLine1Txt", taxpayer.get("address", ""))
+    sub(address, "CityNm", taxpayer.get("city", ""))
+    sub(address, "StateAbbreviationCd", (taxpayer.get("state") or "").upper())
+    sub(address, "ZIPCd", _digits(taxpayer.get("zip")))
+    for person, tag in (("taxpayer", "IdentityProtectionPIN"), ("spouse", "SpouseIdentityProtectionPIN")):
+        if (ip_pins or {}).get(person):
+            sub(header, tag, ip_pins[person]["pin"])
+
+    data = sub(root, "ReturnData")
+    data.set("documentCnt", str(1 + sum(1 for s in tax_return.get("income_sources", []) if s["type"] == "wages")))
+    form = sub(data, "IRS1040")
+    income = calculation["income"]
+    sub(form, "IndividualReturnFilingStatusCd", FILING_STATUS_CODES.get(tax_return["filing_status"], ""))
+    optional_lines = [
+        ("WagesAmt", income.get("wages")),
+        ("TaxExemptInterestAmt", calculation.get("tax_exempt_interest")),
+        ("TaxableInterestAmt", income.get("interest")),
+        ("QualifiedDividendsAmt", calculation.get("qualified_dividends")),
+        ("OrdinaryDividendsAmt", income.get("dividends")),
+        ("TaxableSocSecAmt", income.get("social_security")),
+        ("CapitalGainLossAmt", income.get("capital_gains")),
+    ]
+    for tag, amount in optional_lines:
+        if amount:
+            sub(form, tag, _dollars(amount))
+    sub(form, "TotalIncomeAmt", _dollars(calculation["total_income"]))
+    sub(form, "AdjustedGrossIncomeAmt", _dollars(calculation["agi"]))
+    sub(form, "TotalItemizedOrStandardDedAmt", _dollars(calculation["deduction_amount"]))
+    sub(form, "TaxableIncomeAmt", _dollars(calculation["taxable_income"]))
+    sub(form, "TaxAmt", _dollars(calculation["income_tax"]))
+    if calculation["credits"].get("applied"):
+        sub(form, "CTCODCAmt", _dollars(calculation["credits"]["applied"]))
+    if calculation["self_employment_tax"]:
+        sub(form, "SelfEmploymentTaxAmt", _dollars(calculation["self_employment_tax"]))
+    sub(form, "TotalTaxAmt", _dollars(calculation["total_tax"]))
+    sub(form, "WithholdingTaxAmt", _dollars(calculation["total_withholding"]))
+    if calculation.get("estimated_payments"):
+        sub(form, "EstimatedTaxPaymentsAmt", _dollars(calculation["estimated_payments"]))
+    balance = Decimal(str(calculation["refund_or_owed"]))
+    if balance > 0:
+        sub(form, "OverpaidAmt", _dollars(balance))
+        sub(form, "RefundAmt", _dollars(balance))
+        if direct_deposit:
+            sub(form, "RoutingTransitNum", direct_deposit["routing_number"])
+            sub(form, "BankAccountTypeCd", BANK_ACCOUNT_TYPE_CODES[direct_deposit["account_type"]])
+            sub(form, "DepositorAccountNum", direct_deposit["account_number"])
+    elif balance < 0:
+        sub(form, "OwedAmt", _dollars(-balance))
+
+    for source in tax_return.get("income_sources", []):
+        if source["type"] != "wages":
+            continue
+        w2 = sub(data, "IRSW2")
+        sub(w2, "EmployerEIN", _digits(source.get("employer_ein")))
+        sub(sub(w2, "EmployerName"), "BusinessNameLine1Txt", source.get("description", ""))
+        sub(w2, "WagesAmt", _dollars(source.get("amount")))
+        sub(w2, "WithholdingAmt", _dollars(source.get("withholding")))
+
+    return ET.tostring(root, encoding="utf-8", xml_declaration=True)
+
+
+def validate_return_xml(xml: bytes) -> List[str]:
+    """
+    Check MeF XML against the 1040 schema subset
+
+    Args:
+        xml: Document from build_return_xml (or another producer)
+
+    Returns:
+        Validation errors (empty when the document is valid)
+    """
+    try:
+        root = ET.fromstring(xml)
+    except ET.ParseError as e:
+        return [f"Not well-formed XML: {e}"]
+    if root.tag != f"{{{EFILE_NAMESPACE}}}Return":
+        return [f"Root element must be Return in the {EFILE_NAMESPACE} namespace"]
+
+    errors: List[str] = []
+    _check_elements(root, SCHEMA, "", errors)
+    for path, children in REPEATING_SCHEMA.items():
+        for index, element in enumerate(root.findall(_qualify(path)), start=1):
+            _check_elements(element, children, f"{path}[{index}]/", errors)
+    return errors
+
+
+def _qualify(path: str) -> str:
+    """ElementTree path with the e-file namespace on each step"""
+    return "/".join(f"{{{EFILE_NAMESPACE}}}{step}" for step in path.split("/"))
+
+
+def _check_elements(
+    parent: ET.Element,
+    schema: Dict[str, Tuple[str, bool]],
+    prefix: str,
+    errors: List[str],
+) -> None:
+    """Required elements are present and every value matches its type"""
+    for path, (type_name, required) in schema.items():
+        element = parent.find(_qualify(path))
+        if element is None:
+            if required:
+                errors.append(f"{prefix}{path} is required")
+            continue
+        if not re.fullmatch(TYPE_PATTERNS[type_name], element.text or ""):
+            errors.append(f"{prefix}{path} is not a valid {type_name.replace('_', ' ')}: {element.text or '(empty)'}")
@@ -0,0 +1,105 @@
+"""
+API Errors
+Typed errors so every endpoint failure carries a kind the frontend can branch on
+"""
+from enum import Enum
+from typing import Any, Dict, Optional
+
+from fastapi import HTTPException
+from starlette.exceptions import HTTPException as StarletteHTTPException
+
+
+class ErrorKind(str, Enum):
+    """Category of an API error, returned as "kind" next to "detail" """
+    NOT_UNLOCKED = "not_unlocked"   # the data store is locked
+    REAUTH_REQUIRED = "reauth_required"  # a sensitive action needs the passphrase entered again
+    READ_ONLY = "read_only"         # the app is in review mode, so changes are refused
+    NOT_FOUND = "not_found"         # return, document, or record doesn't exist
+    VALIDATION = "validation"       # bad input or data the engine can't process
+    CONFLICT = "conflict"           # the record's state doesn't allow the change (filed return, duplicate)
+    RATE_LIMITED = "rate_limited"   # too many requests
+    DB = "db"                       # reading or writing stored data failed
+    AI = "ai"                       # the AI service is unavailable or failed
+    CRYPTO = "crypto"               # encrypted data couldn't be decrypted
+    INTERNAL = "internal"           # anything else
+
+
+# Kind reported for plain HTTPExceptions, by status code
+STATUS_KINDS = {
+    400: ErrorKind.VALIDATION,
+    401: ErrorKind.REAUTH_REQUIRED,
+    404: ErrorKind.NOT_FOUND,
+    409: ErrorKind.CONFLICT,
+    413: ErrorKind.VALIDATION,
+    422: ErrorKind.VALIDATION,
+    423: ErrorKind.NOT_UNLOCKED,
+    429: ErrorKind.RATE_LIMITED,
+    503: ErrorKind.AI,
+}
+
+
+class AppError(HTTPException):
+    """An HTTPException with an ErrorKind (existing `except HTTPException` handlers still see it)"""
+
+    kind = ErrorKind.INTERNAL
+    default_status = 500
+
+    def __init__(self, detail: str, status_code: Optional[int] = None):
+        super().__init__(status_code=status_code or self.default_status, detail=detail)
+
+
+class NotUnlockedError(AppError):
+    kind = ErrorKind.NOT_UNLOCKED
+    default_status = 423
+
+
+class ReauthRequiredError(AppError):
+    kind = ErrorKind.REAUTH_REQUIRED
+    default_status = 401
+
+
+class ReadOnlyError(AppError):
+    kind = ErrorKind.READ_ONLY
+    default_status = 403
+
+
+class NotFoundError(AppError):
+    kind = ErrorKind.NOT_FOUND
+    default_status = 404
+
+
+class InvalidInputError(AppError):
+    kind = ErrorKind.VALIDATION
+    default_status = 400
+
+
+class ConflictError(AppError):
+    kind = ErrorKind.CONFLICT
+    default_status = 409
+
+
+class StorageError(AppError):
+    kind = ErrorKind.DB
+    default_status = 500
+
+
+class AIServiceError(AppError):
+    kind = ErrorKind.AI
+    default_status = 500
+
+
+class CryptoError(AppError):
+    kind = ErrorKind.CRYPTO
+    default_status = 500
+
+
+def error_kind(exc: StarletteHTTPException) -> ErrorKind:
+    """Kind of an AppError, or the kind implied by a plain HTTPException's status"""
+    if isinstance(exc, AppError):
+        return exc.kind
+    return STATUS_KINDS.get(exc.status_code, ErrorKind.INTERNAL)
+
+
+def error_body(kind: ErrorKind, detail: Any, timestamp: str) -> Dict[str, Any]:
+    """JSON body of an error response"""
+    return {"success": False, "kind": kind.value, "detail": detail, "timestamp": timestamp}
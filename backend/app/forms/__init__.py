@@ -0,0 +1 @@
+"""Generated tax forms (PDF) and the return export package"""
@@ -0,0 +1,109 @@
+"""
+Form 1040-ES Vouchers
+Printable estimated tax payment vouchers for the quarters still to be paid
+"""
+from datetime import date
+from typing import Dict, List, Any, Optional
+
+from app.forms.pdf import MARGIN, PAGE_HEIGHT, PAGE_WIDTH, PdfDocument
+from app.services.formatting import Formatter, get_formatter
+
+
+def remaining_payments(schedule: Dict[str, Any], as_of: Optional[date] = None) -> List[Dict[str, Any]]:
+    """
+    Quarters from an estimate that are not yet due
+
+    Args:
+        schedule: TaxCalculator.estimate_quarterly_payments() output
+        as_of: Date to compare due dates with (defaults to today)
+
+    Returns:
+        payment_schedule entries with a voucher number and a positive amount
+    """
+    as_of = as_of or date.today()
+    return [
+        {**payment, "voucher": number}
+        for number, payment in enumerate(schedule["payment_schedule"], start=1)
+        if date.fromisoformat(payment["due_date"]) >= as_of and payment["amount"] > 0
+    ]
+
+
+def render_vouchers(
+    payments: List[Dict[str, Any]],
+    tax_year: int,
+    taxpayer: Dict[str, Any],
+    formatter: Optional[Formatter] = None,
+) -> bytes:
+    """
+    Render one 1040-ES payment voucher per page
+
+    Args:
+        payments: remaining_payments() output
+        tax_year: Year the estimated tax is for
+        taxpayer: Taxpayer info (name, ssn, spouse_name, spouse_ssn, address)
+        formatter: Date and amount formats
+
+    Returns:
+        PDF bytes
+
+    Raises:
+        ValueError: If there are no payments to print
+    """
+    if not payments:
+        raise ValueError("No estimated tax payments remain for this year")
+
+    formatter = get_formatter(formatter)
+    document = PdfDocument(title=f"Form 1040-ES {tax_year} Payment Vouchers")
+    for payment in payments:
+        page = document.add_page()
+        top = PAGE_HEIGHT - MARGIN
+        right = PAGE_WIDTH - MARGIN
+
+        page.text(MARGIN, top, f"Form 1040-ES   {tax_year} Estimated Tax", size=14, font="bold")
+        page.text(right - 150, top, f"Payment Voucher {payment['voucher']}", size=12, font="bold")
+        page.text(MARGIN, top - 18, f"Due {formatter.date(payment['due_date'])}", size=10)
+        page.text(
+            MARGIN, top - 36,
+            "File only if you are making a payment of estimated tax by check or money order.",
+            size=8,
+        )
+        page.text(
+            MARGIN, top - 48,
+            'Make your check payable to "United States Treasury" and write your SSN and '
+            f'"{tax_year} Form 1040-ES" on it.',
+            size=8,
+        )
+
+        page.line(MARGIN, top - 60, right, top - 60, width=1)
+        page.text(MARGIN, top - 80, "Amount of estimated tax you are paying", size=10, font="bold")
+        page.text(MARGIN, top - 92, "by check or money order", size=10, font="bold")
+        page.text_right(right, top - 86, formatter.currency(payment["amount"]), size=14)
+        page.line(right - 160, top - 92, right, top - 92)
+
+        fields = [
+            ("Your first name and last name", taxpayer.get("name", "")),
+            ("Your social security number", taxpayer.get("ssn", "")),
+            ("If joint payment, spouse's name", taxpayer.get("spouse_name", "")),
+            ("Spouse's social security number", taxpayer.get("spouse_ssn", "")),
+            ("Address (number, street, and apt. no.)", taxpayer.get("address", "")),
+            ("City, state, and ZIP code", " ".join(
+                str(part) for part in (taxpayer.get("city"), taxpayer.get("state"), taxpayer.get("zip")) if part
+            )),
+        ]
+        y = top - 120
+        for label, value in fields:
+            page.text(MARGIN, y, label, size=7)
+            page.text(MARGIN, y - 12, str(value or ""), size=10)
+            page.line(MARGIN, y - 16, right, y - 16, width=0.25)
+            y -= 32
+
+        # Cut line below the voucher
+        for x in range(MARGIN, int(right), 8):
+            page.line(x, y - 10, x + 4, y - 10, width=0.5)
+        page.text(MARGIN, y - 24, "Detach along the dotted line and mail with your payment.", size=7)
+        page.text(
+            MARGIN, MARGIN - 20,
+            "Prepared for review - not an official IRS form. Check the mailing address in the 1040-ES instructions.",
+            size=7,
+        )
+    return document.to_bytes()
@@ -0,0 +1,45 @@
+"""
+Return Ledger CSV
+Every income source and deduction on a return as one spreadsheet-friendly CSV
+"""
+import csv
+import io
+from typing import Dict, Any, Optional
+
+from app.services.formatting import Formatter, get_formatter
+
+LEDGER_COLUMNS = ["record", "id", "category", "description", "owner", "date", "amount", "withholding"]
+
+
+def build_return_csv(tax_return: Dict[str, Any], formatter: Optional[Formatter] = None) -> str:
+    """
+    Build the ledger CSV for a return
+
+    Amounts and dates use the formatter's formats; with a comma decimal
+    separator the fields are separated by semicolons so spreadsheets split
+    the columns correctly.
+
+    Args:
+        tax_return: Return dict from ReturnStore
+        formatter: Date and amount formats
+
+    Returns:
+        CSV text with a header row
+    """
+    formatter = get_formatter(formatter)
+    output = io.StringIO()
+    writer = csv.writer(output, delimiter=formatter.csv_delimiter, lineterminator="\n")
+    writer.writerow(LEDGER_COLUMNS)
+    for source in tax_return.get("income_sources", []):
+        writer.writerow([
+            "income", source.get("id", ""), source["type"], source.get("description", ""), source.get("owner", ""),
+            formatter.date(source.get("date")), formatter.number(source.get("amount") or 0),
+            formatter.number(source.get("withholding") or 0),
+        ])
+    for deduction in tax_return.get("deductions", []):
+        writer.writerow([
+            "deduction", deduction.get("id", ""), deduction["category"], deduction.get("description", ""),
+            deduction.get("owner", ""), formatter.date(deduction.get("date")),
+            formatter.number(deduction.get("amount") or 0), "",
+        ])
+    return output.getvalue()
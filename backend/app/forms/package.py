@@ -0,0 +1,92 @@
+"""
+Return Export Package
+Bundles a return's data and its generated forms into one zip archive
+"""
+import io
+import json
+import zipfile
+from decimal import Decimal
+from typing import Dict, Any, Optional
+
+from app.forms.schedule_a import render_schedule_a
+from app.forms.schedule_b import render_schedule_b
+from app.forms.schedule_c import render_schedule_c, render_schedule_se
+from app.services.formatting import Formatter
+from app.tax_engine.return_calculation import calculate_business_schedules, calculate_return
+from app.tax_engine.schedule_a import ScheduleACalculator
+from app.tax_engine.schedule_b import calculate_schedule_b
+from app.tax_engine.vehicle_expenses import itemized_deduction_records
+
+
+def taxpayer_name(tax_return: Dict[str, Any], owner: str = "taxpayer") -> str:
+    """Name shown on a form, from the taxpayer info (spouse_name for the spouse)"""
+    taxpayer = tax_return.get("taxpayer") or {}
+    return taxpayer.get("spouse_name" if owner == "spouse" else "name", "")
+
+
+def return_agi(tax_return: Dict[str, Any]) -> Decimal:
+    """AGI of a stored return, as the engine calculates it"""
+    return Decimal(str(calculate_return(tax_return)["agi"]))
+
+
+def calculate_schedule_a(tax_return: Dict[str, Any], agi: Optional[Decimal] = None) -> Dict[str, Any]:
+    """
+    Schedule A for a stored return
+
+    Args:
+        tax_return: Return dict from ReturnStore
+        agi: AGI override (defaults to the return's AGI)
+
+    Returns:
+        ScheduleACalculator.calculate() output
+    """
+    return ScheduleACalculator(tax_return["tax_year"]).calculate(
+        itemized_deduction_records(tax_return),
+        agi=return_agi(tax_return) if agi is None else agi,
+        filing_status=tax_return["filing_status"],
+    )
+
+
+def build_return_package(tax_return: Dict[str, Any], formatter: Optional[Formatter] = None) -> bytes:
+    """
+    Build the export package for a return
+
+    Contains return.json plus forms/*.pdf for every form the return needs
+    (Schedule A when it has deductions, Schedule B when it's required,
+    Schedule C per business, and Schedule SE per owner owing
+    self-employment tax).
+
+    Args:
+        tax_return: Return dict from ReturnStore
+        formatter: Amount formats used on the forms
+
+    Returns:
+        Zip archive bytes
+
+    Raises:
+        ValueError: If a form can't be calculated (unsupported year, bad data)
+    """
+    forms: Dict[str, bytes] = {}
+    if itemized_deduction_records(tax_return):
+        schedule = calculate_schedule_a(tax_return)
+        forms["schedule_a.pdf"] = render_schedule_a(schedule, taxpayer_name(tax_return), formatter=formatter)
+    schedule_b = calculate_schedule_b(tax_return)
+    if schedule_b["required"]:
+        forms["schedule_b.pdf"] = render_schedule_b(schedule_b, taxpayer_name(tax_return), formatter=formatter)
+
+    business = calculate_business_schedules(tax_return)
+    records = tax_return.get("businesses", [])
+    for index, (record, schedule) in enumerate(zip(records, business["businesses"]), start=1):
+        name = taxpayer_name(tax_return, schedule["owner"])
+        forms[f"schedule_c_{index}.pdf"] = render_schedule_c(schedule, record, name, formatter=formatter)
+    for schedule in business["self_employment"]:
+        suffix = "_spouse" if schedule["owner"] == "spouse" else ""
+        name = taxpayer_name(tax_return, schedule["owner"])
+        forms[f"schedule_se{suffix}.pdf"] = render_schedule_se(schedule, name, formatter=formatter)
+
+    output = io.BytesIO()
+    with zipfile.ZipFile(output, "w", zipfile.ZIP_DEFLATED) as archive:
+        archive.writestr("return.json", json.dumps(tax_return, indent=2))
+        for filename, content in forms.items():
+            archive.writestr(f"forms/{filename}", content)
+    return output.getvalue()
@@ -0,0 +1,230 @@
+"""
+PDF Writer
+Minimal dependency-free PDF output for generated forms (text, rules, and JPEG images)
+"""
+from typing import List, Optional, Sequence, Tuple
+
+from app.services.formatting import Formatter, get_formatter
+
+PAGE_WIDTH = 612  # US Letter, in points
+PAGE_HEIGHT = 792
+MARGIN = 50
+
+# Base-14 fonts every PDF reader has; amounts use Courier so columns line up
+FONTS = {"regular": "Helvetica", "bold": "Helvetica-Bold", "mono": "Courier"}
+FONT_KEYS = {name: f"F{index}" for index, name in enumerate(FONTS, start=1)}
+COURIER_CHAR_WIDTH = 0.6  # of the font size
+
+
+def _escape(text: str) -> str:
+    """Escape a PDF string literal (base-14 fonts only cover Latin-1)"""
+    text = text.encode("latin-1", errors="replace").decode("latin-1")
+    return text.replace("\\", "\\\\").replace("(", "\\(").replace(")", "\\)")
+
+
+class PdfPage:
+    """One page's drawing operations"""
+
+    def __init__(self):
+        self.operations: List[str] = []
+
+    def text(self, x: float, y: float, text: str, size: float = 10, font: str = "regular") -> None:
+        """Draw text with its baseline at (x, y), measured from the bottom-left"""
+        self.operations.append(
+            f"BT /{FONT_KEYS[font]} {size:g} Tf {x:.2f} {y:.2f} Td ({_escape(text)}) Tj ET"
+        )
+
+    def text_right(self, x: float, y: float, text: str, size: float = 10) -> None:
+        """Draw monospaced text ending at x (for amount columns)"""
+        self.text(x - len(text) * size * COURIER_CHAR_WIDTH, y, text, size=size, font="mono")
+
+    def line(self, x1: float, y1: float, x2: float, y2: float, width: float = 0.5) -> None:
+        """Draw a straight rule"""
+        self.operations.append(f"{width:g} w {x1:.2f} {y1:.2f} m {x2:.2f} {y2:.2f} l S")
+
+    def image(self, x: float, y: float, width: float, height: float, name: str) -> None:
+        """Draw an image added with PdfDocument.add_jpeg, bottom-left corner at (x, y)"""
+        self.operations.append(f"q {width:.2f} 0 0 {height:.2f} {x:.2f} {y:.2f} cm /{name} Do Q")
+
+
+class PdfDocument:
+    """A multi-page PDF built from PdfPage drawing operations"""
+
+    def __init__(self, title: Optional[str] = None):
+        self.title = title
+        self.pages: List[PdfPage] = []
+        self.images: List[Tuple[bytes, int, int]] = []
+
+    def add_page(self) -> PdfPage:
+        page = PdfPage()
+        self.pages.append(page)
+        return page
+
+    def add_jpeg(self, data: bytes, width: int, height: int) -> str:
+        """
+        Add a baseline RGB JPEG for pages to draw
+
+        Args:
+            data: JPEG bytes
+            width: Width in pixels
+            height: Height in pixels
+
+        Returns:
+            The image's resource name for PdfPage.image
+        """
+        self.images.append((data, width, height))
+        return f"Im{len(self.images)}"
+
+    def to_bytes(self) -> bytes:
+        """Serialize the document"""
+        if not self.pages:
+            self.add_page()
+
+        # Object numbers: 1 catalog, 2 page tree, 3.. fonts, images, then page/content pairs, then info
+        font_ids = {key: 3 + index for index, key in enumerate(FONT_KEYS.values())}
+        image_ids = {f"Im{index}": 3 + len(font_ids) + index - 1 for index in range(1, len(self.images) + 1)}
+        first_page_id = 3 + len(font_ids) + len(image_ids)
+        page_ids = [first_page_id + 2 * index for index in range(len(self.pages))]
+        info_id = first_page_id + 2 * len(self.pages)
+
+        objects: List[Tuple[int, bytes]] = [
+            (1, b"<< /Type /Catalog /Pages 2 0 R >>"),
+            (2, f"<< /Type /Pages /Kids [{' '.join(f'{i} 0 R' for i in page_ids)}] /Count {len(page_ids)} >>".encode()),
+        ]
+        for name, key in zip(FONTS.values(), FONT_KEYS.values()):
+            objects.append((
+                font_ids[key],
+                f"<< /Type /Font /Subtype /Type1 /BaseFont /{name} /Encoding /WinAnsiEncoding >>".encode(),
+            ))
+
+        for (data, width, height), object_id in zip(self.images, image_ids.values()):
+            objects.append((object_id, (
+                f"<< /Type /XObject /Subtype /Image /Width {width} /Height {height} "
+                f"/ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode /Length {len(data)} >>\nstream\n"
+            ).encode() + data + b"\nendstream"))
+
+        resources = "/Font << " + " ".join(f"/{key} {object_id} 0 R" for key, object_id in font_ids.items()) + " >>"
+        if image_ids:
+            resources += " /XObject << " + " ".join(f"/{name} {object_id} 0 R" for name, object_id in image_ids.items()) + " >>"
+        for page, page_id in zip(self.pages, page_ids):
+            stream = "\n".join(page.operations).encode("latin-1")
+            objects.append((page_id, (
+                f"<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] "
+                f"/Resources << {resources} >> /Contents {page_id + 1} 0 R >>"
+            ).encode()))
+            objects.append((page_id + 1, b"<< /Length %d >>\nstream\n" % len(stream) + stream + b"\nendstream"))
+        objects.append((info_id, f"<< /Title ({_escape(self.title or '')}) /Producer (AI Tax CPA Agent) >>".encode()))
+
+        output = bytearray(b"%PDF-1.4\n")
+        offsets = {}
+        for object_id, body in sorted(objects):
+            offsets[object_id] = len(output)
+            output += b"%d 0 obj\n" % object_id + body + b"\nendobj\n"
+
+        xref = len(output)
+        output += b"xref\n0 %d\n0000000000 65535 f \n" % (len(objects) + 1)
+        for object_id in range(1, len(objects) + 1):
+            output += b"%010d 00000 n \n" % offsets[object_id]
+        output += (
+            f"trailer\n<< /Size {len(objects) + 1} /Root 1 0 R /Info {info_id} 0 R >>\n"
+            f"startxref\n{xref}\n%%EOF\n"
+        ).encode()
+        return bytes(output)
+
+
+FormLine = Tuple[str, str, Optional[float]]  # (line number, label, amount)
+
+
+def render_form(
+    title: str,
+    subtitle: str,
+    sections: Sequence[Tuple[str, Sequence[FormLine]]],
+    header: Sequence[Tuple[str, str]] = (),
+    notes: Sequence[str] = (),
+    formatter: Optional[Formatter] = None,
+) -> bytes:
+    """
+    Lay out a tax form as numbered lines with right-aligned amounts
+
+    Args:
+        title: Form name ("Schedule A (Form 1040)")
+        subtitle: Form description and tax year
+        sections: (heading, lines) in form order
+        header: (label, value) pairs printed under the title (name, SSN, ...)
+        notes: Explanations printed after the last section
+        formatter: Amount formats (defaults to 1,234.56)
+
+    Returns:
+        PDF bytes
+    """
+    document = PdfDocument(title=title)
+    add_form(document, title, subtitle, sections, header, notes, formatter=formatter)
+    return document.to_bytes()
+
+
+def add_form(
+    document: PdfDocument,
+    title: str,
+    subtitle: str,
+    sections: Sequence[Tuple[str, Sequence[FormLine]]],
+    header: Sequence[Tuple[str, str]] = (),
+    notes: Sequence[str] = (),
+    footer: bool = True,
+    formatter: Optional[Formatter] = None,
+) -> None:
+    """
+    Append a form laid out like render_form to a document, starting on a new page
+
+    With footer=False the caller numbers the pages (add_footers) once the
+    whole document is laid out.
+    """
+    formatter = get_formatter(formatter)
+    first_page = len(document.pages)
+    page = document.add_page()
+    y = PAGE_HEIGHT - MARGIN
+
+    def next_line(height: float) -> PdfPage:
+        nonlocal page, y
+        y -= height
+        if y < MARGIN + 30:
+            page = document.add_page()
+            y = PAGE_HEIGHT - MARGIN - height
+        return page
+
+    page.text(MARGIN, y, title, size=16, font="bold")
+    page.text(MARGIN, y - 18, subtitle, size=10)
+    y -= 30
+    for label, value in header:
+        next_line(14).text(MARGIN, y, f"{label}: {value}", size=9)
+    page.line(MARGIN, y - 8, PAGE_WIDTH - MARGIN, y - 8, width=1)
+    y -= 8
+
+    amount_right = PAGE_WIDTH - MARGIN
+    for heading, lines in sections:
+        next_line(22).text(MARGIN, y, heading, size=11, font="bold")
+        for number, label, amount in lines:
+            current = next_line(15)
+            current.text(MARGIN, y, number, size=9, font="bold")
+            current.text(MARGIN + 30, y, label[:80], size=9)
+            if amount is not None:
+                current.text_right(amount_right, y, formatter.number(amount), size=9)
+            current.line(amount_right - 100, y - 3, amount_right, y - 3, width=0.25)
+
+    if notes:
+        next_line(24).text(MARGIN, y, "Notes", size=10, font="bold")
+        for note in notes:
+            next_line(13).text(MARGIN, y, f"- {note}"[:110], size=8)
+
+    if footer:
+        add_footers(document, first_page)
+
+
+def add_footers(document: PdfDocument, first_page: int = 0) -> None:
+    """Number the pages from first_page on with the review footer"""
+    pages = document.pages[first_page:]
+    for number, page in enumerate(pages, start=1):
+        page.text(
+            MARGIN, MARGIN - 20,
+            f"Prepared for review - not an official IRS form. Page {number} of {len(pages)}",
+            size=7,
+        )
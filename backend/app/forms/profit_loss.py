@@ -0,0 +1,125 @@
+"""
+Profit and Loss Report
+Renders a business's quarterly profit and loss as a PDF or CSV
+"""
+import csv
+import io
+from datetime import date
+from typing import Dict, List, Any, Optional
+
+from app.forms.pdf import MARGIN, PAGE_HEIGHT, PAGE_WIDTH, PdfDocument, PdfPage, add_footers
+from app.services.formatting import Formatter, get_formatter
+
+INCOME_LABELS = {
+    "gross_receipts": "Gross receipts",
+    "returns_allowances": "Less returns and allowances",
+    "cost_of_goods_sold": "Less cost of goods sold",
+    "other_income": "Other income",
+}
+
+EXPENSE_LABELS = {
+    "car_truck": "Car and truck",
+    "commissions_fees": "Commissions and fees",
+    "legal_professional": "Legal and professional",
+    "rent_equipment": "Rent: vehicles and equipment",
+    "rent_property": "Rent: business property",
+    "taxes_licenses": "Taxes and licenses",
+    "home_office": "Business use of home",
+}
+
+COLUMN_WIDTH = 62  # points per amount column
+
+
+def _period_label(period: str) -> str:
+    return {"undated": "Undated", "ytd": "YTD"}.get(period, period.upper())
+
+
+def _label(category: str) -> str:
+    return INCOME_LABELS.get(category) or EXPENSE_LABELS.get(category) or category.replace("_", " ").capitalize()
+
+
+def _report_rows(report: Dict[str, Any]) -> List[tuple]:
+    """(section, label, amounts by period, bold) in print order"""
+    rows = [("Income", _label(r["category"]), r, False) for r in report["income"]]
+    rows.append(("Income", "Gross income", report["gross_income"], True))
+    rows += [("Expenses", _label(r["category"]), r, False) for r in report["expenses"]]
+    rows.append(("Expenses", "Total expenses", report["total_expenses"], True))
+    rows.append(("", "Net profit (loss)", report["net_profit"], True))
+    return rows
+
+
+def build_profit_loss_csv(report: Dict[str, Any], formatter: Optional[Formatter] = None) -> str:
+    """
+    Build the profit and loss CSV
+
+    Args:
+        report: business_profit_loss() output
+        formatter: Amount formats (and the field separator that goes with them)
+
+    Returns:
+        CSV text with a header row and one column per period
+    """
+    formatter = get_formatter(formatter)
+    output = io.StringIO()
+    writer = csv.writer(output, delimiter=formatter.csv_delimiter, lineterminator="\n")
+    writer.writerow(["section", "line"] + [_period_label(p) for p in report["periods"]])
+    for section, label, amounts, _ in _report_rows(report):
+        writer.writerow([section, label] + [formatter.number(amounts[p]) for p in report["periods"]])
+    return output.getvalue()
+
+
+def render_profit_loss(report: Dict[str, Any], formatter: Optional[Formatter] = None) -> bytes:
+    """
+    Render the profit and loss statement as a PDF
+
+    Args:
+        report: business_profit_loss() output
+        formatter: Amount and date formats
+
+    Returns:
+        PDF bytes
+    """
+    formatter = get_formatter(formatter)
+    title = f"{report['name']} - Profit and Loss"
+    document = PdfDocument(title=title)
+    page: PdfPage = document.add_page()
+    y = PAGE_HEIGHT - MARGIN
+    through = "full year" if report["through_quarter"] == 4 else f"through Q{report['through_quarter']}"
+    page.text(MARGIN, y, title, size=16, font="bold")
+    page.text(MARGIN, y - 18, f"{report['tax_year']} by quarter, {through} - printed {formatter.date(date.today())}", size=10)
+    y -= 40
+
+    columns = [PAGE_WIDTH - MARGIN - COLUMN_WIDTH * i for i in reversed(range(len(report["periods"])))]
+
+    def next_line(height: float) -> PdfPage:
+        nonlocal page, y
+        y -= height
+        if y < MARGIN + 30:
+            page = document.add_page()
+            y = PAGE_HEIGHT - MARGIN - height
+        return page
+
+    current = next_line(14)
+    for right, period in zip(columns, report["periods"]):
+        label = _period_label(period)
+        current.text(right - len(label) * 5, y, label, size=9, font="bold")
+    current.line(MARGIN, y - 4, PAGE_WIDTH - MARGIN, y - 4, width=1)
+
+    section_printed = None
+    for section, label, amounts, bold in _report_rows(report):
+        if section and section != section_printed:
+            next_line(20).text(MARGIN, y, section, size=11, font="bold")
+            section_printed = section
+        current = next_line(14)
+        current.text(MARGIN + (0 if bold else 10), y, label[:28], size=8, font="bold" if bold else "regular")
+        for right, period in zip(columns, report["periods"]):
+            current.text_right(right, y, formatter.number(amounts[period]), size=8)
+        current.line(MARGIN, y - 3, PAGE_WIDTH - MARGIN, y - 3, width=0.5 if bold else 0.25)
+
+    if report["notes"]:
+        next_line(24).text(MARGIN, y, "Notes", size=10, font="bold")
+        for note in report["notes"]:
+            next_line(13).text(MARGIN, y, f"- {note}"[:110], size=8)
+
+    add_footers(document)
+    return document.to_bytes()
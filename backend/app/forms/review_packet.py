@@ -0,0 +1,317 @@
+"""
+Return Review Packet
+Prints a return as one PDF for a human reviewer: summary, prior-year comparison, deduction detail, and forms
+"""
+from datetime import date
+from typing import Dict, Any, Optional
+
+from app.documents.previews import PreviewUnavailableError, thumbnail_jpeg
+from app.forms.package import taxpayer_name
+from app.forms.pdf import MARGIN, PAGE_HEIGHT, PAGE_WIDTH, PdfDocument, PdfPage, add_footers, add_form
+from app.forms.schedule_a import schedule_a_layout
+from app.forms.schedule_b import schedule_b_layout
+from app.forms.schedule_c import schedule_c_layout, schedule_se_layout
+from app.services.formatting import Formatter, get_formatter
+from app.tax_engine.return_calculation import calculate_return
+from app.tax_engine.schedule_b import calculate_schedule_b
+from app.utils.document_store import DocumentStore
+
+# (Form 1040 line, label, calculation income key) for the cover summary and comparison
+INCOME_LINES = [
+    ("1z", "Wages, salaries, tips", "wages"),
+    ("2b", "Taxable interest", "interest"),
+    ("3b", "Ordinary dividends", "dividends"),
+    ("4b", "IRA distributions, pensions, and annuities", "retirement"),
+    ("6b", "Taxable social security benefits", "social_security"),
+    ("7", "Capital gain or (loss)", "capital_gains"),
+    ("8", "Business income (Schedule C)", "business"),
+    ("8", "Self-employment income not linked to a business", "self_employment"),
+    ("8", "Other income", "other"),
+    ("8", "Taxable state and local income tax refund", "state_refund"),
+    ("8", "Alimony received", "alimony"),
+]
+
+# (Form 1040 line, label, calculation key) after total income
+TAX_LINES = [
+    ("9", "Total income", "total_income"),
+    ("10", "Adjustments to income", "adjustments"),
+    ("11", "Adjusted gross income", "agi"),
+    ("12", "Standard or itemized deduction", "deduction_amount"),
+    ("15", "Taxable income", "taxable_income"),
+    ("16", "Tax", "income_tax"),
+    ("19", "Child tax credit and credit for other dependents", "credits_applied"),
+    ("23", "Self-employment tax", "self_employment_tax"),
+    ("24", "Total tax", "total_tax"),
+    ("25d", "Federal income tax withheld", "total_withholding"),
+    ("26", "Estimated tax payments", "estimated_payments"),
+    ("34", "Refund (amount owed if negative)", "refund_or_owed"),
+]
+
+THUMBNAIL_BOX = 96  # points; receipt thumbnails are scaled to fit
+
+
+def build_review_packet(
+    tax_return: Dict[str, Any],
+    document_store: DocumentStore,
+    prior_return: Optional[Dict[str, Any]] = None,
+    direct_deposit: Optional[Dict[str, Any]] = None,
+    ip_pins: Optional[Dict[str, Dict[str, Any]]] = None,
+    formatter: Optional[Formatter] = None,
+) -> bytes:
+    """
+    Build the review packet for a return
+
+    Pages, in order: a cover summary of Form 1040, a comparison with the prior
+    year, each deduction with its receipt thumbnail, and the forms the
+    return needs (Schedule A, Schedule B, Schedule C per business, Schedule
+    SE). Receipts without a stored thumbnail, or without Pillow to convert
+    it, are listed without one.
+
+    Args:
+        tax_return: Return dict from ReturnStore
+        document_store: Store holding the receipts linked by deduction receipt_id
+        prior_return: The prior-year return, if there is one; otherwise the
+            comparison uses the prior_year_amount kept on cloned records
+        direct_deposit: Masked ReturnStore.get_direct_deposit() details shown
+            on the cover
+        ip_pins: Masked ReturnStore.get_ip_pins() PINs shown on the cover
+        formatter: Date and amount formats
+
+    Returns:
+        PDF bytes
+
+    Raises:
+        ValueError: If the return can't be calculated (unsupported year, bad data)
+    """
+    formatter = get_formatter(formatter)
+    calculation = calculate_return(tax_return, rounding=formatter.rounding)
+    document = PdfDocument(title=f"{tax_return['tax_year']} Return Review - {taxpayer_name(tax_return) or tax_return['return_id']}")
+
+    _add_cover(document, tax_return, calculation, direct_deposit, ip_pins or {}, formatter)
+    _add_comparison(document, tax_return, calculation, _prior_figures(tax_return, prior_return, formatter.rounding), formatter)
+    if tax_return.get("deductions"):
+        _add_deduction_detail(document, tax_return, document_store, formatter)
+
+    if calculation["schedule_a"]:
+        layout = schedule_a_layout(calculation["schedule_a"], taxpayer_name(tax_return))
+        add_form(document, **layout, footer=False, formatter=formatter)
+    schedule_b = calculate_schedule_b(tax_return)
+    if schedule_b["required"]:
+        layout = schedule_b_layout(schedule_b, taxpayer_name(tax_return))
+        add_form(document, **layout, footer=False, formatter=formatter)
+    for record, schedule in zip(tax_return.get("businesses", []), calculation["businesses"]):
+        name = taxpayer_name(tax_return, schedule["owner"])
+        add_form(document, **schedule_c_layout(schedule, record, name), footer=False, formatter=formatter)
+    for schedule in calculation["self_employment"]:
+        name = taxpayer_name(tax_return, schedule["owner"])
+        add_form(document, **schedule_se_layout(schedule, name), footer=False, formatter=formatter)
+
+    add_footers(document)
+    return document.to_bytes()
+
+
+def _figures(calculation: Dict[str, Any]) -> Dict[str, Optional[float]]:
+    """Flatten a calculate_return() result to the keys of INCOME_LINES and TAX_LINES"""
+    figures: Dict[str, Optional[float]] = dict(calculation.get("income") or {})
+    for _, _, key in TAX_LINES:
+        figures[key] = calculation.get(key)
+    figures["credits_applied"] = (calculation.get("credits") or {}).get("applied")
+    return figures
+
+
+def _prior_figures(
+    tax_return: Dict[str, Any], prior_return: Optional[Dict[str, Any]], rounding: str
+) -> Dict[str, Optional[float]]:
+    """
+    Prior-year figures for the comparison
+
+    Uses the prior return's calculation (recalculated when the engine supports
+    its year, else the stored one), falling back to income totals from the
+    prior_year_amount of cloned income sources.
+    """
+    if prior_return is not None:
+        try:
+            return _figures(calculate_return(prior_return, rounding=rounding))
+        except ValueError:
+            if prior_return.get("calculation"):
+                return _figures(prior_return["calculation"])
+
+    figures: Dict[str, Optional[float]] = {}
+    for source in tax_return.get("income_sources", []):
+        if source.get("prior_year_amount") is None:
+            continue
+        figures[source["type"]] = (figures.get(source["type"]) or 0) + float(source["prior_year_amount"])
+    if figures:
+        figures["total_income"] = sum(value for value in figures.values() if value is not None)
+    return figures
+
+
+def _add_cover(
+    document: PdfDocument,
+    tax_return: Dict[str, Any],
+    calculation: Dict[str, Any],
+    direct_deposit: Optional[Dict[str, Any]],
+    ip_pins: Dict[str, Dict[str, Any]],
+    formatter: Formatter,
+) -> None:
+    """Form 1040 summary with the return's status, refund account, IP PINs, and any state returns"""
+    figures = _figures(calculation)
+    taxpayer = tax_return.get("taxpayer") or {}
+    header = [
+        ("Taxpayer", taxpayer_name(tax_return) or "-"),
+        ("Filing status", tax_return["filing_status"].replace("_", " ")),
+        ("Return status", tax_return.get("status", "draft").replace("_", " ")),
+        ("Return ID", tax_return["return_id"]),
+    ]
+    if taxpayer.get("spouse_name"):
+        header.insert(1, ("Spouse", taxpayer["spouse_name"]))
+    death = tax_return.get("death")
+    if death:
+        name = taxpayer.get("spouse_name" if death["deceased"] == "spouse" else "name") or death["deceased"]
+        header.insert(2, ("Deceased", f"{name} - {formatter.date(death['date_of_death'])}"))
+    if direct_deposit:
+        header.append((
+            "Refund direct deposit",
+            f"{direct_deposit['account_type']} - routing {direct_deposit['routing_number']}, "
+            f"account {direct_deposit['account_number']}",
+        ))
+    for person, details in ip_pins.items():
+        name = taxpayer.get("spouse_name" if person == "spouse" else "name") or person
+        header.append((f"IP PIN - {name}", f"{details['pin']} (for {details['valid_year']})"))
+
+    sections = [
+        ("Income", [(line, label, figures.get(key)) for line, label, key in INCOME_LINES if figures.get(key)]),
+        ("Tax and Payments", [(line, label, figures.get(key)) for line, label, key in TAX_LINES]),
+    ]
+    state_lines = []
+    for record in tax_return.get("state_returns", []):
+        residency = record["residency"].replace("_", " ")
+        state_lines.append((record["state"], f"{residency} - tax", record.get("computed_tax")))
+        state_lines.append((record["state"], f"{residency} - refund (amount owed if negative)", record.get("balance")))
+    if state_lines:
+        sections.append(("State Returns", state_lines))
+
+    notes = list(calculation["notes"])
+    if any(record.get("computed_tax") is None for record in tax_return.get("state_returns", [])):
+        notes.append("Some state returns haven't been calculated yet")
+    add_form(
+        document,
+        title=f"{tax_return['tax_year']} Return Review Packet",
+        subtitle=f"Form 1040 summary - printed {formatter.date(date.today())}",
+        header=header,
+        sections=sections,
+        notes=notes,
+        footer=False,
+        formatter=formatter,
+    )
+
+
+def _add_comparison(
+    document: PdfDocument,
+    tax_return: Dict[str, Any],
+    calculation: Dict[str, Any],
+    prior: Dict[str, Optional[float]],
+    formatter: Formatter,
+) -> None:
+    """Current and prior year side by side with the change"""
+    current = _figures(calculation)
+    writer = _PageWriter(document)
+    writer.heading(f"Two-Year Comparison - {tax_return['tax_year'] - 1} and {tax_return['tax_year']}", size=16)
+    if not prior:
+        writer.text("No prior-year return or prior-year amounts are available for comparison.", size=9)
+        return
+
+    columns = [PAGE_WIDTH - MARGIN - 200, PAGE_WIDTH - MARGIN - 100, PAGE_WIDTH - MARGIN]
+    row = writer.row(22)
+    for right, label in zip(columns, [str(tax_return["tax_year"] - 1), str(tax_return["tax_year"]), "Change"]):
+        row.text(right - len(label) * 5.5, writer.y, label, size=10, font="bold")
+
+    for line, label, key in INCOME_LINES + TAX_LINES:
+        if (line, label, key) in INCOME_LINES and not (current.get(key) or prior.get(key)):
+            continue
+        row = writer.row(15)
+        row.text(MARGIN, writer.y, line, size=9, font="bold")
+        row.text(MARGIN + 30, writer.y, label[:48], size=9)
+        values = [prior.get(key), current.get(key)]
+        if values[0] is not None and values[1] is not None:
+            values.append(values[1] - values[0])
+        else:
+            values.append(None)
+        for right, value in zip(columns, values):
+            row.text_right(right, writer.y, "-" if value is None else formatter.number(value), size=9)
+        row.line(MARGIN, writer.y - 3, PAGE_WIDTH - MARGIN, writer.y - 3, width=0.25)
+
+
+def _add_deduction_detail(
+    document: PdfDocument,
+    tax_return: Dict[str, Any],
+    document_store: DocumentStore,
+    formatter: Formatter,
+) -> None:
+    """Every deduction with its amount and, when it came from a receipt, the receipt thumbnail"""
+    writer = _PageWriter(document)
+    writer.heading("Itemized Deduction Detail", size=16)
+    total = 0.0
+    for deduction in tax_return["deductions"]:
+        total += float(deduction.get("amount") or 0)
+        image = _receipt_image(document, document_store, deduction.get("receipt_id"))
+        height = max(30, image[2] + 8) if image else 30
+        row = writer.row(height)
+        top = writer.y + height - 16
+        label = deduction["category"].replace("_", " ").title()
+        row.text(MARGIN, top, label, size=10, font="bold")
+        row.text_right(PAGE_WIDTH - MARGIN, top, formatter.number(float(deduction.get("amount") or 0)), size=10)
+        details = [deduction.get("description") or "", formatter.date(deduction.get("date"))]
+        if deduction.get("items"):
+            details.append(f"{sum(item['quantity'] for item in deduction['items'])} items itemized")
+        if deduction.get("receipt_id"):
+            details.append(f"Receipt {deduction['receipt_id']}" + ("" if image else " (no thumbnail)"))
+        row.text(MARGIN, top - 13, " - ".join(d for d in details if d)[:90], size=8)
+        if image:
+            name, width, image_height = image
+            row.image(PAGE_WIDTH - MARGIN - 100 - width, writer.y + 4, width, image_height, name)
+        row.line(MARGIN, writer.y, PAGE_WIDTH - MARGIN, writer.y, width=0.25)
+
+    row = writer.row(20)
+    row.text(MARGIN, writer.y, "Total before Schedule A limits", size=10, font="bold")
+    row.text_right(PAGE_WIDTH - MARGIN, writer.y, formatter.number(total), size=10)
+
+
+def _receipt_image(document: PdfDocument, document_store: DocumentStore, document_id: Optional[str]):
+    """Add a receipt's thumbnail to the document; returns (name, width, height) in points or None"""
+    if not document_id:
+        return None
+    try:
+        png = document_store.get_thumbnail(document_id)
+        if png is None:
+            return None
+        jpeg, width, height = thumbnail_jpeg(png)
+    except (KeyError, PreviewUnavailableError):
+        return None
+    scale = min(THUMBNAIL_BOX / width, THUMBNAIL_BOX / height, 1)
+    return document.add_jpeg(jpeg, width, height), width * scale, height * scale
+
+
+class _PageWriter:
+    """Top-down cursor over new pages of a document"""
+
+    def __init__(self, document: PdfDocument):
+        self.document = document
+        self.page: PdfPage = document.add_page()
+        self.y: float = PAGE_HEIGHT - MARGIN
+
+    def row(self, height: float) -> PdfPage:
+        """Move down by height, starting a new page if it doesn't fit; draw at self.y"""
+        self.y -= height
+        if self.y < MARGIN + 30:
+            self.page = self.document.add_page()
+            self.y = PAGE_HEIGHT - MARGIN - height
+        return self.page
+
+    def heading(self, text: str, size: float = 11) -> None:
+        self.row(size + 4).text(MARGIN, self.y, text, size=size, font="bold")
+        self.y -= 8
+
+    def text(self, text: str, size: float = 9) -> None:
+        self.row(size + 5).text(MARGIN, self.y, text, size=size)
+
@@ -0,0 +1,74 @@
+"""
+Schedule A Form
+Renders the Schedule A calculation as a PDF
+"""
+from typing import Dict, Any, Optional
+
+from app.forms.pdf import render_form
+from app.services.formatting import Formatter
+
+SECTIONS = [
+    ("Medical and Dental Expenses", [
+        ("1", "Medical and dental expenses"),
+        ("2", "Amount from Form 1040, line 11 (AGI)"),
+        ("3", "Multiply line 2 by 7.5% (0.075)"),
+        ("4", "Subtract line 3 from line 1. If zero or less, enter 0"),
+    ]),
+    ("Taxes You Paid", [
+        ("5a", "State and local income taxes or general sales taxes"),
+        ("5b", "State and local real estate taxes"),
+        ("5c", "State and local personal property taxes"),
+        ("5d", "Add lines 5a through 5c"),
+        ("5e", "Enter the smaller of line 5d or $10,000 ($5,000 if MFS)"),
+        ("6", "Other taxes"),
+        ("7", "Add lines 5e and 6"),
+    ]),
+    ("Interest You Paid", [
+        ("8a", "Home mortgage interest and points reported on Form 1098"),
+        ("8e", "Add lines 8a through 8c"),
+        ("10", "Add lines 8e and 9"),
+    ]),
+    ("Gifts to Charity", [
+        ("11", "Gifts by cash or check (after AGI limit)"),
+        ("12", "Other than by cash or check (after AGI limit)"),
+        ("13", "Carryover from prior year"),
+        ("14", "Add lines 11 through 13"),
+    ]),
+    ("Other Itemized Deductions", [
+        ("16", "Other - from list in instructions"),
+    ]),
+    ("Total Itemized Deductions", [
+        ("17", "Add lines 4, 7, 10, 14, 15, and 16"),
+    ]),
+]
+
+
+def render_schedule_a(schedule: Dict[str, Any], taxpayer_name: str = "", formatter: Optional[Formatter] = None) -> bytes:
+    """
+    Render a filled Schedule A
+
+    Args:
+        schedule: ScheduleACalculator.calculate() output
+        taxpayer_name: Name(s) shown on the return
+        formatter: Amount formats
+
+    Returns:
+        PDF bytes
+    """
+    return render_form(**schedule_a_layout(schedule, taxpayer_name), formatter=formatter)
+
+
+def schedule_a_layout(schedule: Dict[str, Any], taxpayer_name: str = "") -> Dict[str, Any]:
+    """render_form/add_form arguments for a filled Schedule A"""
+    lines = schedule["lines"]
+    return dict(
+        title="Schedule A (Form 1040)",
+        subtitle=f"Itemized Deductions - {schedule['tax_year']}",
+        header=[("Name(s) shown on Form 1040", taxpayer_name or "-"),
+                ("Filing status", schedule["filing_status"].replace("_", " "))],
+        sections=[
+            (heading, [(number, label, lines.get(number)) for number, label in section_lines])
+            for heading, section_lines in SECTIONS
+        ],
+        notes=schedule["notes"],
+    )
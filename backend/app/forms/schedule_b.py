@@ -0,0 +1,59 @@
+"""
+Schedule B Form
+Renders the Schedule B calculation as a PDF
+"""
+from typing import Dict, Any, Optional
+
+from app.forms.pdf import render_form
+from app.services.formatting import Formatter
+
+
+def render_schedule_b(schedule: Dict[str, Any], taxpayer_name: str = "", formatter: Optional[Formatter] = None) -> bytes:
+    """
+    Render a filled Schedule B
+
+    Args:
+        schedule: calculate_schedule_b() output
+        taxpayer_name: Name(s) shown on the return
+        formatter: Amount formats
+
+    Returns:
+        PDF bytes
+    """
+    return render_form(**schedule_b_layout(schedule, taxpayer_name), formatter=formatter)
+
+
+def _yes_no(answer: bool) -> str:
+    return "Yes" if answer else "No"
+
+
+def schedule_b_layout(schedule: Dict[str, Any], taxpayer_name: str = "") -> Dict[str, Any]:
+    """render_form/add_form arguments for a filled Schedule B"""
+    lines = schedule["lines"]
+    part_iii = schedule["part_iii"]
+    countries = ", ".join(part_iii["countries"]) or "-"
+    return dict(
+        title="Schedule B (Form 1040)",
+        subtitle=f"Interest and Ordinary Dividends - {schedule['tax_year']}",
+        header=[("Name(s) shown on return", taxpayer_name or "-")],
+        sections=[
+            ("Part I - Interest", [
+                *[("1", line["payer"] or "(payer not named)", line["amount"]) for line in schedule["interest"]],
+                ("2", "Add the amounts on line 1", lines["2"]),
+                ("4", "Subtract line 3 from line 2. Enter on Form 1040, line 2b", lines["4"]),
+            ]),
+            ("Part II - Ordinary Dividends", [
+                *[("5", line["payer"] or "(payer not named)", line["amount"]) for line in schedule["dividends"]],
+                ("6", "Add the amounts on line 5. Enter on Form 1040, line 3b", lines["6"]),
+            ]),
+            ("Part III - Foreign Accounts and Trusts", [
+                ("7a", f"Financial interest in or signature authority over a foreign account: "
+                       f"{_yes_no(part_iii['foreign_account'])}", None),
+                ("", f"Required to file FinCEN Form 114: {_yes_no(part_iii['fbar_required'])}", None),
+                ("7b", f"Countries where the accounts are located: {countries}", None),
+                ("8", f"Received a distribution from, or was grantor of, a foreign trust: "
+                      f"{_yes_no(part_iii['foreign_trust'])}", None),
+            ]),
+        ],
+        notes=schedule["notes"],
+    )
@@ -0,0 +1,131 @@
+"""
+Schedule C and SE Forms
+Renders business profit or loss and self-employment tax calculations as PDFs
+"""
+from typing import Dict, Any, Optional
+
+from app.forms.pdf import render_form
+from app.services.formatting import Formatter
+
+SCHEDULE_C_SECTIONS = [
+    ("Part I - Income", [
+        ("1", "Gross receipts or sales"),
+        ("2", "Returns and allowances"),
+        ("3", "Subtract line 2 from line 1"),
+        ("4", "Cost of goods sold"),
+        ("5", "Gross profit. Subtract line 4 from line 3"),
+        ("6", "Other income"),
+        ("7", "Gross income. Add lines 5 and 6"),
+    ]),
+    ("Part II - Expenses", [
+        ("8", "Advertising"),
+        ("9", "Car and truck expenses"),
+        ("10", "Commissions and fees"),
+        ("11", "Contract labor"),
+        ("13", "Depreciation and section 179 expense"),
+        ("15", "Insurance (other than health)"),
+        ("16b", "Interest (other)"),
+        ("17", "Legal and professional services"),
+        ("18", "Office expense"),
+        ("20a", "Rent or lease: vehicles, machinery, and equipment"),
+        ("20b", "Rent or lease: other business property"),
+        ("21", "Repairs and maintenance"),
+        ("22", "Supplies"),
+        ("23", "Taxes and licenses"),
+        ("24a", "Travel"),
+        ("24b", "Deductible meals"),
+        ("25", "Utilities"),
+        ("26", "Wages"),
+        ("27a", "Other expenses"),
+        ("28", "Total expenses"),
+        ("29", "Tentative profit or (loss). Subtract line 28 from line 7"),
+        ("30", "Expenses for business use of your home"),
+        ("31", "Net profit or (loss). Subtract line 30 from line 29"),
+    ]),
+]
+
+SCHEDULE_SE_SECTIONS = [
+    ("Part I - Self-Employment Tax", [
+        ("2", "Net profit or (loss) from Schedule C"),
+        ("4a", "Multiply line 2 by 92.35% (0.9235)"),
+        ("6", "Net earnings from self-employment (zero if less than $400)"),
+        ("7", "Maximum earnings subject to social security tax"),
+        ("8a", "Social security wages from Form W-2"),
+        ("9", "Subtract line 8a from line 7"),
+        ("10", "Multiply the smaller of line 6 or line 9 by 12.4%"),
+        ("11", "Multiply line 6 by 2.9%"),
+        ("12", "Self-employment tax. Add lines 10 and 11"),
+        ("13", "Deduction for one-half of self-employment tax"),
+    ]),
+]
+
+
+def render_schedule_c(
+    schedule: Dict[str, Any],
+    business: Dict[str, Any],
+    taxpayer_name: str = "",
+    formatter: Optional[Formatter] = None,
+) -> bytes:
+    """
+    Render a filled Schedule C for one business
+
+    Args:
+        schedule: ScheduleCCalculator.calculate() output
+        business: Business record (name, principal_business_code, ein)
+        taxpayer_name: Name of the proprietor
+        formatter: Amount formats
+
+    Returns:
+        PDF bytes
+    """
+    return render_form(**schedule_c_layout(schedule, business, taxpayer_name), formatter=formatter)
+
+
+def schedule_c_layout(schedule: Dict[str, Any], business: Dict[str, Any], taxpayer_name: str = "") -> Dict[str, Any]:
+    """render_form/add_form arguments for a filled Schedule C"""
+    lines = schedule["lines"]
+    return dict(
+        title="Schedule C (Form 1040)",
+        subtitle=f"Profit or Loss From Business - {schedule['tax_year']}",
+        header=[
+            ("Name of proprietor", taxpayer_name or "-"),
+            ("A Principal business or profession", business.get("description") or business["name"]),
+            ("B Principal business code", business.get("principal_business_code") or "-"),
+            ("C Business name", business["name"]),
+            ("D Employer ID number", business.get("ein") or "-"),
+        ],
+        sections=[
+            (heading, [(number, label, lines.get(number)) for number, label in section_lines])
+            for heading, section_lines in SCHEDULE_C_SECTIONS
+        ],
+        notes=schedule["notes"],
+    )
+
+
+def render_schedule_se(schedule: Dict[str, Any], taxpayer_name: str = "", formatter: Optional[Formatter] = None) -> bytes:
+    """
+    Render a filled Schedule SE
+
+    Args:
+        schedule: ScheduleSECalculator.calculate() output
+        taxpayer_name: Name of the person with self-employment income
+        formatter: Amount formats
+
+    Returns:
+        PDF bytes
+    """
+    return render_form(**schedule_se_layout(schedule, taxpayer_name), formatter=formatter)
+
+
+def schedule_se_layout(schedule: Dict[str, Any], taxpayer_name: str = "") -> Dict[str, Any]:
+    """render_form/add_form arguments for a filled Schedule SE"""
+    lines = schedule["lines"]
+    return dict(
+        title="Schedule SE (Form 1040)",
+        subtitle=f"Self-Employment Tax - {schedule['tax_year']}",
+        header=[("Name of person with self-employment income", taxpayer_name or "-")],
+        sections=[
+            (heading, [(number, label, lines.get(number)) for number, label in section_lines])
+            for heading, section_lines in SCHEDULE_SE_SECTIONS
+        ],
+    )
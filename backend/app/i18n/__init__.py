@@ -0,0 +1,96 @@
+"""
+Localization
+Embedded message catalogs for display names, validation findings, and error headings
+"""
+import json
+from functools import lru_cache
+from pathlib import Path
+from typing import Dict, Any, Optional
+
+LOCALES_DIR = Path(__file__).parent / "locales"
+
+DEFAULT_LOCALE = "en"
+SUPPORTED_LOCALES = ["en", "es"]
+
+
+def normalize_locale(locale: Optional[str]) -> str:
+    """
+    Map a locale tag to a supported catalog ("es-MX" -> "es")
+
+    Raises:
+        ValueError: If the language has no catalog
+    """
+    if not locale:
+        return DEFAULT_LOCALE
+    language = locale.replace("_", "-").split("-")[0].lower()
+    if language not in SUPPORTED_LOCALES:
+        raise ValueError(f"Unsupported locale: {locale}. Must be one of: {', '.join(SUPPORTED_LOCALES)}")
+    return language
+
+
+@lru_cache(maxsize=None)
+def catalog(locale: str = DEFAULT_LOCALE) -> Dict[str, str]:
+    """
+    Messages for a locale, with English filling any gaps
+
+    Args:
+        locale: Supported locale code
+
+    Returns:
+        Message key -> text (cached, so don't modify it)
+    """
+    with open(LOCALES_DIR / f"{DEFAULT_LOCALE}.json", 'r', encoding='utf-8') as f:
+        messages = json.load(f)
+    if locale != DEFAULT_LOCALE:
+        with open(LOCALES_DIR / f"{normalize_locale(locale)}.json", 'r', encoding='utf-8') as f:
+            messages.update(json.load(f))
+    return messages
+
+
+def translate(key: str, locale: Optional[str] = None, **params: Any) -> str:
+    """
+    Look up a message and fill in its {placeholders}
+
+    Args:
+        key: Message key, e.g. "validation.no_income"
+        locale: Locale code (defaults to English)
+        **params: Placeholder values
+
+    Returns:
+        Localized text, or the key itself if no catalog has it
+    """
+    message = catalog(normalize_locale(locale)).get(key, key)
+    return message.format(**params) if params else message
+
+
+def display_name(group: str, value: str, locale: Optional[str] = None) -> str:
+    """
+    Localized name of a code such as an income type or form
+
+    Args:
+        group: income_type, deduction_category, business_expense_category,
+            filing_status, return_status, form, event_kind, severity, or error_kind
+        value: The stored code ("self_employment", "W-2", ...)
+        locale: Locale code
+
+    Returns:
+        Display name; unknown codes are prettified ("foo_bar" -> "Foo bar")
+    """
+    key = f"{group}.{value}"
+    name = translate(key, locale)
+    return value.replace("_", " ").capitalize() if name == key else name
+
+
+def display_names(locale: Optional[str] = None) -> Dict[str, Dict[str, str]]:
+    """
+    Every display name in a locale, grouped
+
+    Returns:
+        Group -> code -> name
+    """
+    groups: Dict[str, Dict[str, str]] = {}
+    for key, message in catalog(normalize_locale(locale)).items():
+        group, _, value = key.partition(".")
+        if group != "validation":
+            groups.setdefault(group, {})[value] = message
+    return groups
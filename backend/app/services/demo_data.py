@@ -0,0 +1,75 @@
+"""
+Demo Data
+A fictional household's return for trying the app (and testing commands end to end) without entering real data
+"""
+from typing import Dict, Any
+
+from app.utils.return_store import ReturnStore
+
+DEMO_TAXPAYER = {
+    "name": "Pat Demo",
+    "spouse_name": "Jordan Demo",
+    "address": "100 Example St",
+    "city": "Springfield",
+    "state": "IL",
+    "zip": "62701",
+}
+
+
+def seed_demo_data(return_store: ReturnStore, tax_year: int = 2024) -> Dict[str, Any]:
+    """
+    Create a married couple's return with wages, a side business, a child, and itemizable expenses
+
+    Every name and number is made up. Seeding again adds another copy
+    rather than changing the first.
+
+    Args:
+        return_store: Store the return is created in
+        tax_year: Tax year of the return
+
+    Returns:
+        Dict with return_id, business_id, and counts of what was added
+    """
+    tax_return = return_store.create_return(
+        tax_year=tax_year, filing_status="married_joint", taxpayer=dict(DEMO_TAXPAYER)
+    )
+    return_id = tax_return["return_id"]
+
+    income = [
+        return_store.add_income_source(return_id, "wages", 68000, description="Springfield Clinic", withholding=6100),
+        return_store.add_income_source(
+            return_id, "wages", 41500, description="Lincoln School District", withholding=3200, owner="spouse"
+        ),
+        return_store.add_income_source(return_id, "interest", 420, description="First Example Bank"),
+    ]
+
+    business = return_store.add_business(
+        return_id, "Demo Design Studio", owner="spouse", principal_business_code="541430", gross_receipts=18400
+    )
+    expenses = [
+        return_store.add_business_expense(return_id, business["id"], "supplies", 1250, description="Art supplies"),
+        return_store.add_business_expense(return_id, business["id"], "advertising", 600, description="Portfolio site"),
+        return_store.add_business_expense(return_id, business["id"], "office", 380, description="Software"),
+    ]
+
+    deductions = [
+        return_store.add_deduction(return_id, "mortgage_interest", 11200, description="Example Mortgage Co"),
+        return_store.add_deduction(return_id, "state_local_tax", 9800, description="Property and state income tax"),
+        return_store.add_deduction(return_id, "charitable", 2400, description="Food bank"),
+    ]
+
+    return_store.add_dependent(
+        return_id, "Casey Demo", "daughter", birth_date=f"{tax_year - 9}-05-14", months_lived_with=12
+    )
+
+    return {
+        "return_id": return_id,
+        "business_id": business["id"],
+        "counts": {
+            "income_sources": len(income),
+            "businesses": 1,
+            "business_expenses": len(expenses),
+            "deductions": len(deductions),
+            "dependents": 1,
+        },
+    }
@@ -0,0 +1,103 @@
+"""
+Diagnostic Reports
+Shareable summary of the install for bug reports: versions, record counts, and scrubbed recent log entries
+"""
+import os
+import platform
+import sys
+from collections import Counter
+from datetime import datetime
+from typing import Dict, Any, Iterable, Optional
+
+from app.services.job_queue import JobQueue
+from app.utils.app_lock import AppLock
+from app.utils.backup import BACKUP_FORMAT_VERSION
+from app.utils.conversation_store import ConversationStore
+from app.utils.data_transfer import EXPORT_SCHEMA_VERSION
+from app.utils.document_store import DocumentStore
+from app.utils.reminder_store import ReminderStore
+from app.utils.return_store import ReturnStore
+from app.utils.scrubbing import scrub
+from app.utils.settings_store import SettingsStore
+
+# Settings copied into reports as-is; free-text and path settings are only reported as set/unset
+REPORTED_SETTINGS = [
+    "default_tax_year",
+    "default_state",
+    "inbox_poll_seconds",
+    "document_retention_years",
+    "auto_deduct_receipts",
+]
+
+# Environment variables reported as set/unset, never by value
+REPORTED_ENV_VARS = ["ANTHROPIC_API_KEY", "EMBEDDING_API_KEY", "FIELD_ENCRYPTION_KEY", "JOB_WORKERS"]
+
+
+def generate_diagnostics(
+    app_version: str,
+    return_store: ReturnStore,
+    document_store: DocumentStore,
+    conversation_store: ConversationStore,
+    settings_store: SettingsStore,
+    reminder_store: ReminderStore,
+    job_queue: Optional[JobQueue] = None,
+    app_lock: Optional[AppLock] = None,
+    log_entries: Iterable[Dict[str, Any]] = (),
+) -> Dict[str, Any]:
+    """
+    Build a diagnostic report
+
+    No return contents, key material, or environment values go into the
+    report, and every log entry is scrubbed (again) of SSNs, account numbers, and secrets.
+
+    Args:
+        app_version: Version reported by the API
+        log_entries: Recent entries from get_recent_logs()
+
+    Returns:
+        Report dict
+    """
+    returns = return_store.list_returns()
+    documents = document_store.list_documents(include_archived=True)
+    settings = settings_store.get_settings()
+
+    report: Dict[str, Any] = {
+        "generated_at": datetime.utcnow().isoformat(),
+        "app": {
+            "version": app_version,
+            "python": sys.version.split()[0],
+            "platform": platform.platform(),
+        },
+        "schema_versions": {
+            "data_export": EXPORT_SCHEMA_VERSION,
+            "backup": BACKUP_FORMAT_VERSION,
+        },
+        "counts": {
+            "returns": len(returns),
+            "returns_by_status": dict(Counter(r.get("status", "draft") for r in returns)),
+            "returns_by_year": {str(k): v for k, v in Counter(r.get("tax_year") for r in returns).items()},
+            "documents": len(documents),
+            "archived_documents": sum(1 for d in documents if d.get("archived")),
+            "conversations": len(conversation_store.list_sessions()),
+            "reminder_states": len(reminder_store.get_states()),
+        },
+        "settings": {
+            **{name: getattr(settings, name) for name in REPORTED_SETTINGS},
+            "inbox_folder_set": bool(settings.inbox_folder),
+            "prompt_addendum_set": bool(settings.prompt_addendum),
+            "ai_base_url_set": bool(settings.ai_base_url),
+            "ai_proxy_set": bool(settings.ai_proxy_url),
+            "semantic_search_set": bool(settings.embedding_url and settings.embedding_model),
+        },
+        "environment": {name: bool(os.getenv(name)) for name in REPORTED_ENV_VARS},
+        "recent_logs": [
+            {**entry, **{key: scrub(entry[key]) for key in ("message", "exception") if entry.get(key)}}
+            for entry in log_entries
+        ],
+    }
+    if job_queue is not None:
+        report["counts"]["jobs_by_status"] = dict(Counter(job["status"] for job in job_queue.list_jobs()))
+    if app_lock is not None:
+        lock = app_lock.status()
+        report["app_lock"] = {"enabled": lock["enabled"], "review_mode": lock["review_mode"]}
+    return report
@@ -0,0 +1,124 @@
+"""
+Number and Date Formatting
+Renders amounts and dates in the user's configured formats for PDFs and CSV exports
+"""
+from datetime import date, datetime
+from decimal import Decimal, ROUND_HALF_UP
+from typing import Any, Optional, Union
+
+from app.tax_engine.rounding import DEFAULT_ROUNDING_POLICY, validate_rounding_policy
+
+# date_format setting -> strftime pattern
+DATE_FORMATS = {
+    "iso": "%Y-%m-%d",
+    "us": "%m/%d/%Y",
+    "eu": "%d/%m/%Y",
+}
+
+# currency_format setting -> (thousands separator, decimal separator)
+CURRENCY_FORMATS = {
+    "us": (",", "."),
+    "eu": (".", ","),
+    "space": (" ", ","),
+}
+
+DEFAULT_DATE_FORMAT = "iso"
+DEFAULT_CURRENCY_FORMAT = "us"
+
+CURRENCY_SYMBOL = "$"
+
+
+class Formatter:
+    """Formats amounts and dates with one date_format/currency_format pair and a rounding policy"""
+
+    def __init__(
+        self,
+        date_format: str = DEFAULT_DATE_FORMAT,
+        currency_format: str = DEFAULT_CURRENCY_FORMAT,
+        rounding: str = DEFAULT_ROUNDING_POLICY,
+    ):
+        """
+        Initialize formatter
+
+        Args:
+            date_format: Key of DATE_FORMATS
+            currency_format: Key of CURRENCY_FORMATS
+            rounding: Rounding policy (whole_dollars prints amounts without cents)
+
+        Raises:
+            ValueError: If a format or the rounding policy is unknown
+        """
+        if date_format not in DATE_FORMATS:
+            raise ValueError(f"Invalid date_format: {date_format}. Must be one of: {', '.join(DATE_FORMATS)}")
+        if currency_format not in CURRENCY_FORMATS:
+            raise ValueError(
+                f"Invalid currency_format: {currency_format}. Must be one of: {', '.join(CURRENCY_FORMATS)}"
+            )
+        self.date_format = date_format
+        self.currency_format = currency_format
+        self.rounding = validate_rounding_policy(rounding)
+
+    @classmethod
+    def from_settings(cls, settings: Any) -> "Formatter":
+        """Formatter for an AppSettings"""
+        return cls(settings.date_format, settings.currency_format, settings.rounding_policy)
+
+    @property
+    def csv_delimiter(self) -> str:
+        """Field separator that doesn't clash with the decimal separator"""
+        return ";" if CURRENCY_FORMATS[self.currency_format][1] == "," else ","
+
+    def number(self, value: Union[float, int, Decimal, None], decimals: Optional[int] = None) -> str:
+        """
+        Format an amount without a currency symbol ("1,234.56", "1.234,56", ...)
+
+        Args:
+            value: Amount (None -> "")
+            decimals: Digits after the decimal separator (default: 2, or 0 under whole_dollars)
+
+        Returns:
+            Formatted amount, rounded half up
+        """
+        if value is None:
+            return ""
+        if decimals is None:
+            decimals = 0 if self.rounding == "whole_dollars" else 2
+        thousands, decimal = CURRENCY_FORMATS[self.currency_format]
+        amount = Decimal(str(value)).quantize(Decimal(1).scaleb(-decimals), rounding=ROUND_HALF_UP)
+        if amount == 0:
+            amount = abs(amount)  # no "-0.00"
+        text = f"{amount:,.{decimals}f}"
+        return text.replace(",", "\0").replace(".", decimal).replace("\0", thousands)
+
+    def currency(self, value: Union[float, int, Decimal, None]) -> str:
+        """Format an amount with the dollar sign ("-$1,234.56")"""
+        if value is None:
+            return ""
+        text = self.number(value)
+        if text.startswith("-"):
+            return f"-{CURRENCY_SYMBOL}{text[1:]}"
+        return f"{CURRENCY_SYMBOL}{text}"
+
+    def date(self, value: Union[date, datetime, str, None]) -> str:
+        """
+        Format a date
+
+        Args:
+            value: date, datetime, or ISO string (other strings are returned as-is)
+
+        Returns:
+            Formatted date
+        """
+        if not value:
+            return ""
+        if isinstance(value, str):
+            try:
+                value = date.fromisoformat(value[:10])
+            except ValueError:
+                return value
+        return value.strftime(DATE_FORMATS[self.date_format])
+
+
+def get_formatter(formatter: Optional[Formatter]) -> Formatter:
+    """The given formatter, or one with the default formats"""
+    return formatter or Formatter()
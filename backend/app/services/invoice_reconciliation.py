@@ -0,0 +1,131 @@
+"""
+Invoice Reconciliation
+Year-end comparison of invoiced income received from each client against the 1099-NEC forms they sent
+"""
+import re
+from decimal import Decimal
+from typing import Dict, List, Any
+
+from app.utils.document_store import DocumentStore
+
+# Clients paying less than this in the year don't have to send a 1099-NEC
+FORM_1099_NEC_THRESHOLD = Decimal("600")
+
+# Differences up to this are rounding, not a mismatch
+TOLERANCE = Decimal("1")
+
+_SUFFIXES = re.compile(r"\b(inc|incorporated|llc|ltd|co|corp|corporation|company|pllc|lp|llp)\b\.?")
+
+
+def normalize_client(name: str) -> str:
+    """Comparable form of a client or payer name ("Acme, Inc." -> "acme")"""
+    name = _SUFFIXES.sub("", name.lower())
+    return " ".join(re.sub(r"[^a-z0-9 ]", " ", name).split())
+
+
+def _forms_1099_nec(documents: List[Dict[str, Any]], tax_year: int) -> List[Dict[str, Any]]:
+    """Extracted 1099-NEC forms for the year: {document_id, payer_name, amount}"""
+    forms = []
+    for record in documents:
+        extraction = record.get("extraction") or {}
+        if extraction.get("form") != "1099-NEC" or DocumentStore.document_tax_year(record) != tax_year:
+            continue
+        fields = {name: f.get("value") for name, f in extraction.get("fields", {}).items()}
+        forms.append({
+            "document_id": record["document_id"],
+            "payer_name": fields.get("payer_name") or "",
+            "amount": Decimal(str(fields.get("nonemployee_compensation") or 0)),
+        })
+    return forms
+
+
+def reconcile_invoices(
+    invoices: List[Dict[str, Any]],
+    documents: List[Dict[str, Any]],
+    tax_year: int,
+) -> Dict[str, Any]:
+    """
+    Compare each client's paid invoices with the 1099-NEC forms received
+
+    Income counts in the year it was paid (cash basis), which is what the
+    client reports on the 1099. Clients are matched to forms by name,
+    ignoring case, punctuation, and suffixes like Inc or LLC.
+
+    Args:
+        invoices: InvoiceStore records
+        documents: DocumentStore records (only extracted 1099-NEC forms are used)
+        tax_year: Year to reconcile
+
+    Returns:
+        Dict with a row per client (invoiced, reported, difference, status),
+        totals, and invoices still open from the year
+    """
+    clients: Dict[str, Dict[str, Any]] = {}
+
+    def client_row(key: str, name: str) -> Dict[str, Any]:
+        return clients.setdefault(key, {
+            "client": name, "invoice_ids": [], "document_ids": [], "expect_1099": False,
+            "invoiced": Decimal("0"), "reported": Decimal("0"),
+        })
+
+    open_invoices = []
+    for invoice in invoices:
+        paid = invoice.get("paid_date")
+        if not paid:
+            if invoice["issue_date"][:4] == str(tax_year):
+                open_invoices.append(invoice)
+            continue
+        if paid[:4] != str(tax_year):
+            continue
+        row = client_row(normalize_client(invoice["client"]), invoice["client"])
+        row["invoice_ids"].append(invoice["id"])
+        row["invoiced"] += Decimal(str(invoice["amount"]))
+        row["expect_1099"] = row["expect_1099"] or invoice.get("expect_1099", True)
+
+    for form in _forms_1099_nec(documents, tax_year):
+        row = client_row(normalize_client(form["payer_name"]), form["payer_name"] or "Unknown payer")
+        row["document_ids"].append(form["document_id"])
+        row["reported"] += form["amount"]
+
+    rows = []
+    for row in clients.values():
+        difference = row["invoiced"] - row["reported"]
+        if not row["invoice_ids"]:
+            status = "unmatched_1099"
+            note = "1099-NEC with no paid invoices from this payer; check the payer name or add the invoices"
+        elif not row["document_ids"]:
+            if row["expect_1099"] and row["invoiced"] >= FORM_1099_NEC_THRESHOLD:
+                status, note = "missing_1099", "No 1099-NEC received yet; the income is reportable either way"
+            else:
+                status, note = "no_1099_expected", None
+        elif abs(difference) <= TOLERANCE:
+            status, note = "matched", None
+        else:
+            status = "mismatch"
+            note = (
+                "1099-NEC reports more than was paid in the year; ask the client for a corrected form"
+                if difference < 0 else
+                "Paid more than the 1099-NEC reports (e.g. a payment by card or near year end); report the full amount"
+            )
+        rows.append({
+            **row,
+            "invoiced": float(row["invoiced"]),
+            "reported": float(row["reported"]),
+            "difference": float(difference),
+            "status": status,
+            "note": note,
+        })
+    rows.sort(key=lambda r: r["client"].lower())
+
+    invoiced = sum((Decimal(str(r["invoiced"])) for r in rows), Decimal("0"))
+    reported = sum((Decimal(str(r["reported"])) for r in rows), Decimal("0"))
+    return {
+        "tax_year": tax_year,
+        "clients": rows,
+        "total_invoiced": float(invoiced),
+        "total_reported": float(reported),
+        "difference": float(invoiced - reported),
+        "open_invoices": open_invoices,
+        "open_amount": float(sum((Decimal(str(i["amount"])) for i in open_invoices), Decimal("0"))),
+        "issues": sum(1 for r in rows if r["status"] in ("mismatch", "missing_1099", "unmatched_1099")),
+    }
@@ -0,0 +1,328 @@
+"""
+Background Job Queue
+Persistent queue of heavy work (imports, embeddings, reports) run by a fixed number of workers
+"""
+import asyncio
+import json
+import logging
+import os
+import re
+import threading
+from datetime import datetime, timedelta
+from pathlib import Path
+from typing import Callable, Dict, List, Any, Optional
+
+from app.services.progress import JobCancelled, ProgressBus
+
+logger = logging.getLogger(__name__)
+
+# handler(payload, progress) -> JSON-serializable result; progress takes event dicts with percent/message
+JobHandler = Callable[[Dict[str, Any], Callable[[Dict[str, Any]], None]], Any]
+
+QUEUE_STATUSES = ["queued", "running", "completed", "failed", "cancelled"]
+
+DEFAULT_MAX_ATTEMPTS = 3
+# Delay before the first retry; doubles with each further attempt
+RETRY_DELAY_SECONDS = 30
+
+
+class JobQueue:
+    """File-backed job queue; jobs interrupted by a restart are picked up again"""
+
+    def __init__(
+        self,
+        storage_dir: str = ".jobs",
+        progress: Optional[ProgressBus] = None,
+        max_concurrent: int = 2,
+        poll_seconds: float = 1.0,
+    ):
+        """
+        Initialize the queue
+
+        Args:
+            storage_dir: Directory to store job files
+            progress: Bus running jobs report progress on (and are cancelled through)
+            max_concurrent: Number of jobs run at the same time
+            poll_seconds: How often idle workers check for due retries
+        """
+        self.storage_dir = Path(storage_dir)
+        self.storage_dir.mkdir(exist_ok=True)
+        self.progress = progress or ProgressBus()
+        self.max_concurrent = max_concurrent
+        self.poll_seconds = poll_seconds
+        self.handlers: Dict[str, JobHandler] = {}
+        self._lock = threading.Lock()
+        self._workers: List[asyncio.Task] = []
+        self._wakeup: Optional[asyncio.Event] = None
+
+    @property
+    def running(self) -> bool:
+        return any(not worker.done() for worker in self._workers)
+
+    def register(self, kind: str, handler: JobHandler) -> None:
+        """Set the function that runs jobs of a kind"""
+        self.handlers[kind] = handler
+
+    def _get_job_file(self, job_id: str) -> Path:
+        """Get file path for a job"""
+        # Job IDs are generated here; reject anything else to prevent path traversal
+        if not re.fullmatch(r"job_[0-9a-f]{16}", job_id):
+            raise KeyError(job_id)
+        return self.storage_dir / f"{job_id}.json"
+
+    def _load(self, job_id: str) -> Dict[str, Any]:
+        path = self._get_job_file(job_id)
+        if not path.exists():
+            raise KeyError(job_id)
+        with open(path, 'r', encoding='utf-8') as f:
+            return json.load(f)
+
+    def _save(self, job: Dict[str, Any]) -> Dict[str, Any]:
+        job["updated_at"] = datetime.utcnow().isoformat()
+        path = self._get_job_file(job["job_id"])
+        tmp_path = path.with_suffix(".tmp")
+        with open(tmp_path, 'w', encoding='utf-8') as f:
+            json.dump(job, f, indent=2, ensure_ascii=False)
+        tmp_path.replace(path)
+        return job
+
+    def enqueue(
+        self,
+        kind: str,
+        payload: Optional[Dict[str, Any]] = None,
+        description: str = "",
+        max_attempts: int = DEFAULT_MAX_ATTEMPTS,
+    ) -> Dict[str, Any]:
+        """
+        Add a job to the queue
+
+        Args:
+            kind: Registered job kind
+            payload: Arguments for the handler (JSON-serializable)
+            description: Human-readable summary
+            max_attempts: Runs before a failing job is given up on
+
+        Returns:
+            The queued job dict
+
+        Raises:
+            ValueError: If no handler is registered for kind
+        """
+        if kind not in self.handlers:
+            raise ValueError(f"Unknown job kind: {kind}. Must be one of: {', '.join(sorted(self.handlers))}")
+        if max_attempts < 1:
+            raise ValueError("max_attempts must be at least 1")
+
+        now = datetime.utcnow().isoformat()
+        job = {
+            "job_id": f"job_{os.urandom(8).hex()}",
+            "kind": kind,
+            "description": description,
+            "payload": payload or {},
+            "status": "queued",
+            "attempts": 0,
+            "max_attempts": max_attempts,
+            "run_after": now,
+            "last_error": None,
+            "result": None,
+            "created_at": now,
+            "started_at": None,
+            "finished_at": None,
+        }
+        with self._lock:
+            self._save(job)
+        if self._wakeup is not None:
+            self._wakeup.set()
+        return job
+
+    def get_job(self, job_id: str) -> Dict[str, Any]:
+        """
+        Get a job, with live progress while it runs
+
+        Raises:
+            KeyError: If the job doesn't exist
+        """
+        return self._with_progress(self._load(job_id))
+
+    def list_jobs(self, status: Optional[str] = None, kind: Optional[str] = None) -> List[Dict[str, Any]]:
+        """
+        List jobs, newest first
+
+        Args:
+            status: Only jobs with this status
+            kind: Only jobs of this kind
+
+        Returns:
+            Job dicts (payloads included)
+        """
+        jobs = []
+        for file_path in self.storage_dir.glob("job_*.json"):
+            try:
+                with open(file_path, 'r', encoding='utf-8') as f:
+                    job = json.load(f)
+            except (json.JSONDecodeError, IOError):
+                continue
+            if (status is None or job["status"] == status) and (kind is None or job["kind"] == kind):
+                jobs.append(self._with_progress(job))
+        jobs.sort(key=lambda j: j["created_at"], reverse=True)
+        return jobs
+
+    def cancel_job(self, job_id: str) -> Dict[str, Any]:
+        """
+        Cancel a queued job, or ask a running one to stop at its next progress update
+
+        Raises:
+            KeyError: If the job doesn't exist
+            ValueError: If the job has already finished
+        """
+        with self._lock:
+            job = self._load(job_id)
+            if job["status"] == "queued":
+                job["status"] = "cancelled"
+                job["finished_at"] = datetime.utcnow().isoformat()
+                return self._save(job)
+            if job["status"] != "running":
+                raise ValueError(f"Job is already {job['status']}")
+        self.progress.cancel_job(job_id)
+        return self.get_job(job_id)
+
+    def retry_job(self, job_id: str) -> Dict[str, Any]:
+        """
+        Queue a failed or cancelled job again with a fresh set of attempts
+
+        Raises:
+            KeyError: If the job doesn't exist
+            ValueError: If the job is queued, running, or completed
+        """
+        with self._lock:
+            job = self._load(job_id)
+            if job["status"] not in ("failed", "cancelled"):
+                raise ValueError(f"Only failed or cancelled jobs can be retried (job is {job['status']})")
+            job.update(status="queued", attempts=0, run_after=datetime.utcnow().isoformat(), finished_at=None)
+            self._save(job)
+        if self._wakeup is not None:
+            self._wakeup.set()
+        return job
+
+    def recover(self) -> int:
+        """
+        Requeue jobs left running by a previous process (they were cut off, not failed)
+
+        Returns:
+            Number of jobs requeued
+        """
+        recovered = 0
+        with self._lock:
+            for job in self.list_jobs(status="running"):
+                job = self._load(job["job_id"])
+                job["status"] = "queued"
+                job["attempts"] = max(0, job["attempts"] - 1)
+                job["run_after"] = datetime.utcnow().isoformat()
+                self._save(job)
+                recovered += 1
+        return recovered
+
+    async def start(self) -> None:
+        """Requeue interrupted jobs and start the workers"""
+        if self.running:
+            return
+        recovered = self.recover()
+        if recovered:
+            logger.info(f"Requeued {recovered} interrupted job(s)")
+        self._wakeup = asyncio.Event()
+        self._workers = [asyncio.create_task(self._worker()) for _ in range(self.max_concurrent)]
+
+    async def stop(self) -> None:
+        """Stop the workers; jobs they were running are requeued on the next start"""
+        for worker in self._workers:
+            worker.cancel()
+        for worker in self._workers:
+            try:
+                await worker
+            except asyncio.CancelledError:
+                pass
+        self._workers = []
+        self._wakeup = None
+
+    async def run_pending(self) -> int:
+        """
+        Run due jobs until none are left, without the background workers
+
+        Returns:
+            Number of job runs
+        """
+        runs = 0
+        while (job := self._claim_next()) is not None:
+            await self._run(job)
+            runs += 1
+        return runs
+
+    async def _worker(self) -> None:
+        while True:
+            job = self._claim_next()
+            if job is None:
+                self._wakeup.clear()
+                try:
+                    await asyncio.wait_for(self._wakeup.wait(), timeout=self.poll_seconds)
+                except asyncio.TimeoutError:
+                    pass
+                continue
+            await self._run(job)
+
+    def _claim_next(self) -> Optional[Dict[str, Any]]:
+        """Mark the oldest due queued job running and return it"""
+        now = datetime.utcnow().isoformat()
+        with self._lock:
+            due = [job for job in self.list_jobs(status="queued") if job["run_after"] <= now]
+            if not due:
+                return None
+            job = self._load(min(due, key=lambda j: j["created_at"])["job_id"])
+            job["status"] = "running"
+            job["attempts"] += 1
+            job["started_at"] = now
+            return self._save(job)
+
+    async def _run(self, job: Dict[str, Any]) -> None:
+        job_id = job["job_id"]
+        self.progress.start(job["kind"], job["description"], job_id=job_id)
+        handler = self.handlers.get(job["kind"])
+        try:
+            if handler is None:
+                raise ValueError(f"No handler registered for {job['kind']} jobs")
+            result = await asyncio.to_thread(handler, job["payload"], self.progress.reporter(job_id))
+        except JobCancelled:
+            self.progress.finish(job_id)
+            self._finish(job_id, "cancelled")
+        except Exception as e:
+            logger.warning(f"Job {job_id} ({job['kind']}) failed on attempt {job['attempts']}: {e}")
+            self.progress.fail(job_id, str(e))
+            self._finish(job_id, "failed", error=str(e))
+        else:
+            self.progress.finish(job_id, result)
+            self._finish(job_id, "completed", result=result)
+
+    def _finish(self, job_id: str, status: str, result: Any = None, error: Optional[str] = None) -> None:
+        with self._lock:
+            job = self._load(job_id)
+            now = datetime.utcnow()
+            if status == "failed" and job["attempts"] < job["max_attempts"]:
+                delay = RETRY_DELAY_SECONDS * 2 ** (job["attempts"] - 1)
+                job.update(status="queued", run_after=(now + timedelta(seconds=delay)).isoformat())
+            else:
+                job.update(status=status, finished_at=now.isoformat())
+            job["result"] = result
+            if error is not None:
+                job["last_error"] = error
+            self._save(job)
+        if job["status"] == "queued" and self._wakeup is not None:
+            self._wakeup.set()
+
+    def _with_progress(self, job: Dict[str, Any]) -> Dict[str, Any]:
+        """Add percent/message from the progress bus to a running job"""
+        if job["status"] == "running":
+            try:
+                live = self.progress.get_job(job["job_id"])
+            except KeyError:
+                return {**job, "percent": 0, "message": ""}
+            return {**job, "percent": live["percent"], "message": live["message"]}
+        return {**job, "percent": 100 if job["status"] == "completed" else 0, "message": ""}
@@ -0,0 +1,283 @@
+"""
+First-Time Setup
+Step-by-step onboarding (passphrase, profile, defaults, API key, last year's data) with progress kept on disk so it can resume
+"""
+import json
+import re
+from datetime import datetime
+from pathlib import Path
+from typing import Dict, List, Any, Optional
+
+from app.documents.txf import import_txf
+from app.tax_engine.tax_calculator import FilingStatus
+from app.utils.app_lock import AppLock
+from app.utils.return_store import ReturnStore
+from app.utils.secret_store import API_KEY_NAME, SecretStore
+from app.utils.settings_store import SettingsStore
+
+# Wizard steps, in the order they're done
+ONBOARDING_STEPS = ["passphrase", "profile", "defaults", "api_key", "import_prior_year"]
+
+# Steps that can be skipped (an API key can be set later, and not everyone has last year's data)
+OPTIONAL_STEPS = ["api_key", "import_prior_year"]
+
+STEP_STATUSES = ["pending", "done", "skipped"]
+
+# Taxpayer fields the profile fills in on new returns
+PROFILE_FIELDS = ["name", "spouse_name", "address", "city", "state", "zip"]
+
+
+class Onboarding:
+    """First-run wizard; each step's side effect goes to the app's own stores"""
+
+    def __init__(
+        self,
+        state_path: str = ".onboarding.json",
+        app_lock: Optional[AppLock] = None,
+        settings_store: Optional[SettingsStore] = None,
+        secret_store: Optional[SecretStore] = None,
+        return_store: Optional[ReturnStore] = None,
+    ):
+        """
+        Initialize the wizard
+
+        Args:
+            state_path: JSON file holding the wizard's progress
+            app_lock: Lock the passphrase step sets
+            settings_store: Settings the defaults step updates
+            secret_store: Where the API key step saves the key
+            return_store: Store the first returns are created in
+        """
+        self.state_path = Path(state_path)
+        self.app_lock = app_lock or AppLock()
+        self.settings_store = settings_store or SettingsStore()
+        self.secret_store = secret_store or SecretStore()
+        self.return_store = return_store or ReturnStore()
+
+    def _load(self) -> Dict[str, Any]:
+        if self.state_path.exists():
+            try:
+                with open(self.state_path, 'r', encoding='utf-8') as f:
+                    return json.load(f)
+            except (json.JSONDecodeError, IOError):
+                pass
+        return {
+            "started_at": None,
+            "completed_at": None,
+            "steps": {step: {"status": "pending", "updated_at": None} for step in ONBOARDING_STEPS},
+            "profile": None,
+            "prior_return_id": None,
+            "return_id": None,
+            "import_summary": None,
+        }
+
+    def _save(self, state: Dict[str, Any]) -> None:
+        self.state_path.parent.mkdir(parents=True, exist_ok=True)
+        with open(self.state_path, 'w', encoding='utf-8') as f:
+            json.dump(state, f, indent=2, ensure_ascii=False)
+
+    def status(self) -> Dict[str, Any]:
+        """
+        Where the wizard stands
+
+        Returns:
+            Dict with steps (name, status, optional, updated_at), current_step
+            (the first pending one, None when finished), complete, profile,
+            prior_return_id, return_id, import_summary, started_at, and
+            completed_at
+        """
+        state = self._load()
+        steps = [
+            {"step": step, **state["steps"][step], "optional": step in OPTIONAL_STEPS}
+            for step in ONBOARDING_STEPS
+        ]
+        current = next((s["step"] for s in steps if s["status"] == "pending"), None)
+        return {
+            "steps": steps,
+            "current_step": current,
+            "complete": current is None,
+            "profile": state["profile"],
+            "prior_return_id": state["prior_return_id"],
+            "return_id": state["return_id"],
+            "import_summary": state["import_summary"],
+            "started_at": state["started_at"],
+            "completed_at": state["completed_at"],
+        }
+
+    def _begin(self, step: str) -> Dict[str, Any]:
+        """
+        Load the state for a step, which must be the current step or one already finished
+
+        Raises:
+            ValueError: If an earlier step is still pending
+        """
+        state = self._load()
+        for earlier in ONBOARDING_STEPS[:ONBOARDING_STEPS.index(step)]:
+            if state["steps"][earlier]["status"] == "pending":
+                raise ValueError(f"Finish the {earlier.replace('_', ' ')} step first")
+        return state
+
+    def _finish(self, state: Dict[str, Any], step: str, status: str = "done") -> Dict[str, Any]:
+        """Mark a step finished; once every step is, create the first return if the import didn't"""
+        now = datetime.utcnow().isoformat()
+        state["started_at"] = state["started_at"] or now
+        state["steps"][step] = {"status": status, "updated_at": now}
+        if all(state["steps"][s]["status"] != "pending" for s in ONBOARDING_STEPS):
+            if not state["return_id"]:
+                settings = self.settings_store.get_settings()
+                profile = state["profile"] or {}
+                tax_return = self.return_store.create_return(
+                    tax_year=settings.default_tax_year,
+                    filing_status=profile.get("filing_status", "single"),
+                    taxpayer=self._taxpayer(profile),
+                )
+                state["return_id"] = tax_return["return_id"]
+            state["completed_at"] = state["completed_at"] or now
+        self._save(state)
+        return self.status()
+
+    def _taxpayer(self, profile: Dict[str, Any]) -> Dict[str, Any]:
+        """Taxpayer info for a new return from the profile (state defaulting to the settings' state)"""
+        taxpayer = {field: profile[field] for field in PROFILE_FIELDS if profile.get(field)}
+        default_state = self.settings_store.get_settings().default_state
+        if default_state and not taxpayer.get("state"):
+            taxpayer["state"] = default_state
+        return taxpayer
+
+    def set_passphrase(self, passphrase: Optional[str] = None) -> Dict[str, Any]:
+        """
+        Passphrase step: protect the app with the lock passphrase
+
+        When a passphrase is already set (the wizard was interrupted after
+        this step, or the app was set up before) it's kept; change it with
+        AppLock.set_passphrase instead.
+
+        Raises:
+            ValueError: If no passphrase is set yet and the new one is missing or too short
+        """
+        state = self._begin("passphrase")
+        if not self.app_lock.enabled:
+            self.app_lock.set_passphrase(passphrase or "")
+        return self._finish(state, "passphrase")
+
+    def set_profile(self, name: str, filing_status: str = "single", **fields: Any) -> Dict[str, Any]:
+        """
+        Profile step: who the returns are for
+
+        Args:
+            name: Taxpayer's name
+            filing_status: Usual filing status
+            **fields: spouse_name, address, city, state, zip
+
+        Raises:
+            ValueError: If the name is missing, the filing status or state is
+                invalid, a field is unknown, or the passphrase step isn't done
+        """
+        state = self._begin("profile")
+        unknown = sorted(set(fields) - set(PROFILE_FIELDS))
+        if unknown:
+            raise ValueError(f"Unknown profile fields: {', '.join(unknown)}")
+        name = (name or "").strip()
+        if not name:
+            raise ValueError("Name is required")
+        statuses = [s.value for s in FilingStatus]
+        if filing_status not in statuses:
+            raise ValueError(f"Invalid filing status: {filing_status}. Must be one of: {', '.join(statuses)}")
+        if fields.get("state"):
+            fields["state"] = _state(fields["state"])
+        if filing_status == "married_joint" and not fields.get("spouse_name"):
+            raise ValueError("A joint filer's profile needs the spouse's name")
+        state["profile"] = {"name": name, "filing_status": filing_status, **{k: v for k, v in fields.items() if v}}
+        return self._finish(state, "profile")
+
+    def set_defaults(self, tax_year: int, state: Optional[str] = None) -> Dict[str, Any]:
+        """
+        Defaults step: the tax year new returns start in and the state of residence
+
+        Raises:
+            ValueError: If the state isn't a two-letter code, the year is out
+                of range, or an earlier step isn't done
+        """
+        progress = self._begin("defaults")
+        this_year = datetime.utcnow().year
+        if not 2000 <= tax_year <= this_year:
+            raise ValueError(f"Tax year must be between 2000 and {this_year}")
+        self.settings_store.update_settings({
+            "default_tax_year": tax_year,
+            "default_state": _state(state) if state else None,
+        }, changed_by="onboarding")
+        return self._finish(progress, "defaults")
+
+    def set_api_key(self, api_key: str) -> Dict[str, Any]:
+        """
+        API key step: save the Anthropic API key (OS keyring, or the encrypted file)
+
+        Raises:
+            ValueError: If the key is empty or an earlier step isn't done
+        """
+        state = self._begin("api_key")
+        api_key = (api_key or "").strip()
+        if not api_key:
+            raise ValueError("API key is empty")
+        self.secret_store.set(API_KEY_NAME, api_key)
+        return self._finish(state, "api_key")
+
+    def import_prior_year(self, txf_text: str) -> Dict[str, Any]:
+        """
+        Import step: last year's return from a TXF export of other tax software
+
+        The file becomes a return for the year before the default tax year,
+        and this year's return is cloned from it, so its income sources and
+        recurring deductions carry over with last year's amounts to compare.
+        Running the step again replaces neither return; it adds new ones.
+
+        Raises:
+            ValueError: If the file isn't TXF or has nothing importable, or an
+                earlier step isn't done
+        """
+        state = self._begin("import_prior_year")
+        profile = state["profile"] or {}
+        tax_year = self.settings_store.get_settings().default_tax_year
+        result = import_txf(
+            self.return_store, txf_text,
+            tax_year=tax_year - 1,
+            filing_status=profile.get("filing_status", "single"),
+            taxpayer=self._taxpayer(profile),
+        )
+        prior_id = result["return"]["return_id"]
+        current = self.return_store.clone_return(prior_id, tax_year)
+        state["prior_return_id"] = prior_id
+        state["return_id"] = current["return_id"]
+        state["import_summary"] = {key: value for key, value in result.items() if key != "return"}
+        return self._finish(state, "import_prior_year")
+
+    def skip(self, step: str) -> Dict[str, Any]:
+        """
+        Skip an optional step
+
+        Raises:
+            ValueError: If the step is unknown or required, or an earlier step isn't done
+        """
+        if step not in ONBOARDING_STEPS:
+            raise ValueError(f"Unknown step: {step}. Must be one of: {', '.join(ONBOARDING_STEPS)}")
+        if step not in OPTIONAL_STEPS:
+            raise ValueError(f"The {step.replace('_', ' ')} step can't be skipped")
+        state = self._begin(step)
+        return self._finish(state, step, status="skipped")
+
+    def reset(self) -> Dict[str, Any]:
+        """
+        Start the wizard over
+
+        Only the progress is forgotten; the passphrase, settings, key, and
+        returns the steps created stay.
+        """
+        self.state_path.unlink(missing_ok=True)
+        return self.status()
+
+
+def _state(value: str) -> str:
+    code = value.strip().upper()
+    if not re.fullmatch(r"[A-Z]{2}", code):
+        raise ValueError(f"Invalid state: {value}. Use the two-letter code")
+    return code
@@ -0,0 +1,178 @@
+"""
+Platform Income Reconciliation
+Comparison of the 1099-K forms from payment apps and gig platforms against the business income recorded on a return
+"""
+from decimal import Decimal
+from typing import Dict, List, Any, Tuple
+
+from app.services.invoice_reconciliation import TOLERANCE, normalize_client
+from app.utils.document_store import DocumentStore
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def _forms_1099_k(documents: List[Dict[str, Any]], tax_year: int) -> List[Dict[str, Any]]:
+    """Extracted 1099-K forms for the year: {document_id, platform, gross}"""
+    forms = []
+    for record in documents:
+        extraction = record.get("extraction") or {}
+        if extraction.get("form") != "1099-K" or DocumentStore.document_tax_year(record) != tax_year:
+            continue
+        fields = {name: f.get("value") for name, f in extraction.get("fields", {}).items()}
+        forms.append({
+            "document_id": record["document_id"],
+            "platform": fields.get("payer_name") or "",
+            "gross": _amount(fields.get("gross_amount")),
+        })
+    return forms
+
+
+def require_1099_k(document_store: DocumentStore, document_id: str) -> Dict[str, Any]:
+    """
+    Look up a document that must be an extracted 1099-K
+
+    Raises:
+        KeyError: If the document doesn't exist
+        ValueError: If it hasn't been parsed as a 1099-K
+    """
+    document = document_store.get_document(document_id)
+    if document is None:
+        raise KeyError(f"Document not found: {document_id}")
+    if (document.get("extraction") or {}).get("form") != "1099-K":
+        raise ValueError(f"Document {document_id} hasn't been parsed as a 1099-K")
+    return document
+
+
+def reconcile_platform_income(tax_return: Dict[str, Any], documents: List[Dict[str, Any]]) -> Dict[str, Any]:
+    """
+    Match each 1099-K's gross against the business receipts recorded for it
+
+    A 1099-K reports gross payments before fees and refunds, and payment
+    apps send one for personal sales too, so its gross isn't automatically
+    business income. The gross, less the personal-item sales split off with
+    ReturnStore.set_platform_allocation, is compared with the receipts of
+    the business it's allocated to (gross receipts plus linked
+    self-employment income), or else with the self-employment income whose
+    description names the platform. A 1099-NEC from the same platform on top
+    of the 1099-K is flagged, since the same payments shouldn't be reported
+    on both.
+
+    Args:
+        tax_return: Return dict from ReturnStore
+        documents: DocumentStore records (only extracted 1099-K forms for the return's year are used)
+
+    Returns:
+        Dict with a row per business or platform (gross, personal_sales,
+        business_share, recorded, difference, status, note), a row per
+        form's personal-item sales, totals, and the number of issues
+    """
+    allocations = {a["document_id"]: a for a in tax_return.get("platform_allocations", [])}
+    businesses = {b["id"]: b for b in tax_return.get("businesses", [])}
+    self_employment = [s for s in tax_return.get("income_sources", []) if s["type"] == "self_employment"]
+    groups: Dict[Tuple[str, str], Dict[str, Any]] = {}
+    personal_rows = []
+
+    for form in _forms_1099_k(documents, tax_return["tax_year"]):
+        allocation = allocations.get(form["document_id"], {})
+        business_id = allocation.get("business_id")
+        if business_id in businesses:
+            key, label = ("business", business_id), businesses[business_id]["name"]
+        else:
+            key, label = ("platform", normalize_client(form["platform"])), form["platform"] or "Unknown platform"
+        group = groups.setdefault(key, {
+            "label": label, "business_id": key[1] if key[0] == "business" else None, "document_ids": [],
+            "gross": Decimal("0"), "personal_sales": Decimal("0"), "platforms": set(),
+        })
+        personal = min(_amount(allocation.get("personal_sales")), form["gross"])
+        group["document_ids"].append(form["document_id"])
+        group["gross"] += form["gross"]
+        group["personal_sales"] += personal
+        group["platforms"].add(normalize_client(form["platform"]))
+
+        if personal > 0:
+            gain = personal - _amount(allocation.get("personal_cost"))
+            if gain > 0:
+                note = f"Personal items sold for ${gain:,.2f} more than they cost; the gain is reported on Form 8949"
+            else:
+                note = (
+                    "Personal items sold for no more than they cost aren't taxable; report them as 1099-K personal "
+                    "items sold at a loss (Schedule 1 lines 8z and 24z) so the form is accounted for"
+                )
+            personal_rows.append({
+                "document_id": form["document_id"],
+                "platform": form["platform"],
+                "personal_sales": float(personal),
+                "personal_cost": float(_amount(allocation.get("personal_cost"))),
+                "gain": float(max(gain, Decimal("0"))),
+                "note": note,
+            })
+
+    rows = []
+    for key, group in groups.items():
+        if key[0] == "business":
+            business = businesses[key[1]]
+            sources = [s for s in self_employment if s.get("business_id") == key[1]]
+            recorded = _amount(business.get("gross_receipts"))
+        else:
+            sources = [s for s in self_employment if key[1] and normalize_client(s.get("description") or "") == key[1]]
+            recorded = Decimal("0")
+        recorded += sum((_amount(s.get("amount")) for s in sources), Decimal("0"))
+        from_1099_nec = [
+            s for s in sources
+            if s.get("form") == "1099-NEC" and normalize_client(s.get("description") or "") in group["platforms"]
+        ]
+
+        share = group["gross"] - group["personal_sales"]
+        difference = recorded - share
+        if share <= TOLERANCE:
+            status, note = "personal", None
+        elif recorded <= 0:
+            status = "unreported"
+            note = "No business income is recorded for this 1099-K; add the receipts to a business or mark personal sales"
+        elif abs(difference) <= TOLERANCE:
+            status, note = "matched", None
+        elif difference > 0 and from_1099_nec:
+            status = "possible_double_count"
+            note = (
+                "A 1099-NEC from the same platform is recorded too; payments on the 1099-K shouldn't also be on the "
+                "1099-NEC, so make sure they're counted once"
+            )
+        elif difference > 0:
+            status = "more_recorded"
+            note = "More is recorded than the 1099-K reports (e.g. cash or other payment methods)"
+        else:
+            status = "less_recorded"
+            note = (
+                "Less is recorded than the 1099-K gross, which is before fees and refunds; record the full gross as "
+                "receipts and deduct the fees, or mark the personal sales"
+            )
+        rows.append({
+            "label": group["label"],
+            "business_id": group["business_id"],
+            "document_ids": group["document_ids"],
+            "source_ids": [s["id"] for s in sources],
+            "gross": float(group["gross"]),
+            "personal_sales": float(group["personal_sales"]),
+            "business_share": float(share),
+            "recorded": float(recorded),
+            "difference": float(difference),
+            "status": status,
+            "note": note,
+        })
+    rows.sort(key=lambda r: r["label"].lower())
+
+    def total(field: str) -> float:
+        return float(sum((Decimal(str(r[field])) for r in rows), Decimal("0")))
+
+    return {
+        "tax_year": tax_return["tax_year"],
+        "platforms": rows,
+        "personal_sales": personal_rows,
+        "total_gross": total("gross"),
+        "total_personal_sales": total("personal_sales"),
+        "total_business_share": total("business_share"),
+        "total_recorded": total("recorded"),
+        "issues": sum(1 for r in rows if r["status"] in ("unreported", "possible_double_count", "less_recorded")),
+    }
@@ -0,0 +1,93 @@
+"""
+Practice Dashboard
+One view of every practitioner client: engagement status, their returns' statuses, and upcoming deadlines
+"""
+from collections import Counter
+from datetime import date
+from typing import Dict, List, Any, Optional
+
+from app.tax_engine.deadlines import DEFAULT_REMINDER_DAYS, return_deadlines, upcoming_deadlines
+
+
+def build_dashboard(
+    clients: List[Dict[str, Any]],
+    tax_returns: List[Dict[str, Any]],
+    documents: List[Dict[str, Any]],
+    as_of: Optional[date] = None,
+    within_days: int = DEFAULT_REMINDER_DAYS,
+) -> Dict[str, Any]:
+    """
+    Summarize every client's returns and deadlines
+
+    A document belongs to a client when it carries the client's client_id or
+    is attached to one of the client's returns.
+
+    Args:
+        clients: ClientStore records
+        tax_returns: Full return dicts from ReturnStore
+        documents: DocumentStore records
+        as_of: Today (defaults to the current date)
+        within_days: How far ahead deadlines count as upcoming
+
+    Returns:
+        Dict with a row per client (returns, document count, next deadline,
+        overdue count), counts by engagement and return status, upcoming
+        deadlines across clients, and returns not linked to any client
+    """
+    as_of = as_of or date.today()
+    names = {client["id"]: client["name"] for client in clients}
+    returns_by_client: Dict[str, List[Dict[str, Any]]] = {client_id: [] for client_id in names}
+    for tax_return in tax_returns:
+        if tax_return.get("client_id") in returns_by_client:
+            returns_by_client[tax_return["client_id"]].append(tax_return)
+
+    rows = []
+    for client in clients:
+        client_returns = sorted(returns_by_client[client["id"]], key=lambda r: r["tax_year"], reverse=True)
+        return_ids = {r["return_id"] for r in client_returns}
+        open_deadlines = [
+            {**deadline, "return_id": r["return_id"]}
+            for r in client_returns
+            for deadline in return_deadlines(r)
+            if not deadline["met"]
+        ]
+        open_deadlines.sort(key=lambda d: d["due_date"])
+        rows.append({
+            "client_id": client["id"],
+            "name": client["name"],
+            "email": client.get("email", ""),
+            "phone": client.get("phone", ""),
+            "engagement_status": client["engagement_status"],
+            "returns": [
+                {
+                    "return_id": r["return_id"],
+                    "tax_year": r["tax_year"],
+                    "status": r.get("status", "draft"),
+                    "refund_or_owed": r.get("refund_or_owed"),
+                    "updated_at": r.get("updated_at"),
+                }
+                for r in client_returns
+            ],
+            "document_count": sum(
+                1 for d in documents if d.get("client_id") == client["id"] or d.get("return_id") in return_ids
+            ),
+            "next_deadline": open_deadlines[0] if open_deadlines else None,
+            "overdue_deadlines": sum(1 for d in open_deadlines if date.fromisoformat(d["due_date"]) < as_of),
+        })
+
+    client_returns = [r for r in tax_returns if r.get("client_id") in names]
+    upcoming = upcoming_deadlines(client_returns, as_of=as_of, within_days=within_days)
+    client_of = {r["return_id"]: r["client_id"] for r in client_returns}
+    for reminder in upcoming:
+        reminder["client_id"] = client_of[reminder["return_id"]]
+        reminder["client_name"] = names[reminder["client_id"]]
+
+    return {
+        "as_of": as_of.isoformat(),
+        "clients": rows,
+        "engagement_counts": dict(Counter(client["engagement_status"] for client in clients)),
+        "return_status_counts": dict(Counter(r.get("status", "draft") for r in client_returns)),
+        "upcoming_deadlines": upcoming,
+        "overdue_count": sum(1 for reminder in upcoming if reminder["overdue"]),
+        "unassigned_returns": sorted(r["return_id"] for r in tax_returns if r.get("client_id") not in names),
+    }
@@ -0,0 +1,182 @@
+"""
+Job Progress
+Assigns job ids to long-running operations and publishes their progress to subscribers
+"""
+import asyncio
+import os
+import threading
+from collections import OrderedDict
+from datetime import datetime
+from typing import Callable, Dict, List, Any, Optional, Set, Tuple
+
+# Event name pushed to subscribers for every job change
+PROGRESS_EVENT = "job://progress"
+
+JOB_STATUSES = ["running", "cancelling", "completed", "failed", "cancelled"]
+FINISHED_STATUSES = {"completed", "failed", "cancelled"}
+
+# Finished jobs kept for status queries; the oldest are dropped first
+MAX_FINISHED_JOBS = 100
+
+
+class JobCancelled(Exception):
+    """Raised from update() when the job was asked to stop"""
+
+
+class ProgressBus:
+    """In-memory job registry; safe to update from worker threads"""
+
+    def __init__(self):
+        self._jobs: "OrderedDict[str, Dict[str, Any]]" = OrderedDict()
+        self._lock = threading.Lock()
+        self._subscribers: Set[Tuple[asyncio.AbstractEventLoop, asyncio.Queue]] = set()
+
+    def start(self, kind: str, description: str = "", job_id: Optional[str] = None) -> Dict[str, Any]:
+        """
+        Register a new running job
+
+        Args:
+            kind: Operation type (bulk_import, ocr, backup, ...)
+            description: Human-readable summary
+            job_id: ID to report under (queued jobs keep their queue ID); generated if omitted
+
+        Returns:
+            Job dict with job_id
+        """
+        now = datetime.utcnow().isoformat()
+        job = {
+            "job_id": job_id or f"job_{os.urandom(8).hex()}",
+            "kind": kind,
+            "description": description,
+            "status": "running",
+            "percent": 0,
+            "message": "",
+            "details": {},
+            "result": None,
+            "error": None,
+            "created_at": now,
+            "updated_at": now,
+        }
+        with self._lock:
+            self._jobs[job["job_id"]] = job
+            self._prune()
+        return self._publish(job)
+
+    def update(self, job_id: str, percent: Optional[float] = None, message: Optional[str] = None, **details) -> Dict[str, Any]:
+        """
+        Report progress; also the point where a running job notices cancellation
+
+        Args:
+            job_id: Job ID
+            percent: 0-100
+            message: Status line for the UI
+            **details: Extra fields merged into the job's details
+
+        Returns:
+            Updated job dict
+
+        Raises:
+            KeyError: If the job doesn't exist
+            JobCancelled: If cancel_job() was called for the job
+        """
+        with self._lock:
+            job = self._jobs[job_id]
+            if job["status"] == "cancelling":
+                raise JobCancelled(job_id)
+            if percent is not None:
+                job["percent"] = max(0, min(100, round(percent)))
+            if message is not None:
+                job["message"] = message
+            job["details"].update(details)
+            job["updated_at"] = datetime.utcnow().isoformat()
+        return self._publish(job)
+
+    def reporter(self, job_id: str) -> Callable[[Dict[str, Any]], None]:
+        """Progress callback (as taken by bulk_import) that forwards events to update()"""
+        def report(event: Dict[str, Any]) -> None:
+            details = {k: v for k, v in event.items() if k not in ("percent", "message")}
+            self.update(job_id, percent=event.get("percent"), message=event.get("message"), **details)
+        return report
+
+    def finish(self, job_id: str, result: Any = None) -> Dict[str, Any]:
+        """Mark a job completed, or cancelled if it was asked to stop"""
+        with self._lock:
+            job = self._jobs[job_id]
+            if job["status"] == "cancelling":
+                job["status"] = "cancelled"
+            else:
+                job["status"] = "completed"
+                job["percent"] = 100
+            job["result"] = result
+            job["updated_at"] = datetime.utcnow().isoformat()
+        return self._publish(job)
+
+    def fail(self, job_id: str, error: str) -> Dict[str, Any]:
+        """Mark a job failed"""
+        with self._lock:
+            job = self._jobs[job_id]
+            job["status"] = "cancelled" if job["status"] == "cancelling" else "failed"
+            job["error"] = error
+            job["updated_at"] = datetime.utcnow().isoformat()
+        return self._publish(job)
+
+    def cancel_job(self, job_id: str) -> Dict[str, Any]:
+        """
+        Ask a running job to stop
+
+        The job stops at its next progress update and is then reported as cancelled.
+
+        Raises:
+            KeyError: If the job doesn't exist
+            ValueError: If the job has already finished
+        """
+        with self._lock:
+            job = self._jobs[job_id]
+            if job["status"] in FINISHED_STATUSES:
+                raise ValueError(f"Job is already {job['status']}")
+            job["status"] = "cancelling"
+            job["updated_at"] = datetime.utcnow().isoformat()
+        return self._publish(job)
+
+    def get_job(self, job_id: str) -> Dict[str, Any]:
+        """
+        Raises:
+            KeyError: If the job doesn't exist
+        """
+        with self._lock:
+            return self._snapshot(self._jobs[job_id])
+
+    def list_jobs(self, status: Optional[str] = None) -> List[Dict[str, Any]]:
+        """Jobs, newest first, optionally only those with a status"""
+        with self._lock:
+            jobs = [self._snapshot(job) for job in reversed(self._jobs.values())]
+        return [job for job in jobs if status is None or job["status"] == status]
+
+    def subscribe(self) -> asyncio.Queue:
+        """Queue that receives a progress event for every job change (call from the event loop)"""
+        queue: asyncio.Queue = asyncio.Queue()
+        self._subscribers.add((asyncio.get_running_loop(), queue))
+        return queue
+
+    def unsubscribe(self, queue: asyncio.Queue) -> None:
+        self._subscribers = {(loop, q) for loop, q in self._subscribers if q is not queue}
+
+    def _publish(self, job: Dict[str, Any]) -> Dict[str, Any]:
+        with self._lock:
+            snapshot = self._snapshot(job)
+        event = {"event": PROGRESS_EVENT, **snapshot}
+        for loop, queue in list(self._subscribers):
+            if loop.is_closed():
+                self.unsubscribe(queue)
+                continue
+            loop.call_soon_threadsafe(queue.put_nowait, event)
+        return snapshot
+
+    def _prune(self) -> None:
+        finished = [job_id for job_id, job in self._jobs.items() if job["status"] in FINISHED_STATUSES]
+        for job_id in finished[:max(0, len(finished) - MAX_FINISHED_JOBS)]:
+            del self._jobs[job_id]
+
+    @staticmethod
+    def _snapshot(job: Dict[str, Any]) -> Dict[str, Any]:
+        return {**job, "details": dict(job["details"])}
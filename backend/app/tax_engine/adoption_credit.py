@@ -0,0 +1,164 @@
+"""
+Adoption Credit
+Form 8839 Part II: qualified adoption expenses per child, the MAGI phase-out, and the carryforward of unused credit
+"""
+from decimal import Decimal, ROUND_HALF_UP
+from typing import Dict, List, Any, Optional
+
+# Most credit per child, over every year expenses for that child are claimed
+ADOPTION_CREDIT_LIMITS = {2024: Decimal("16810")}
+
+# Modified AGI phase-out range, the same for every filing status
+PHASEOUT_RANGES = {2024: (Decimal("252150"), Decimal("292150"))}
+
+# Unused credit carries forward up to this many years
+CARRYFORWARD_YEARS = 5
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def _cents(value: Decimal) -> Decimal:
+    return value.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+
+
+def expenses_this_year(child: Dict[str, Any], tax_year: int) -> Dict[str, Any]:
+    """
+    The qualified adoption expenses that count toward this year's credit
+
+    For a domestic adoption, expenses paid before the year it becomes final
+    are claimed the year after they're paid (prior_year_expenses), and those
+    paid in or after the final year are claimed when paid. A foreign
+    adoption's expenses all wait for the year it becomes final. A special
+    needs child adopted in the U.S. gets the full credit in the final year
+    whatever the expenses were.
+
+    Returns:
+        Dict with counted (Decimal), special_needs_final (bool), and
+        waiting (expenses paid this year that count in a later year)
+    """
+    final_year = child.get("final_year")
+    final = final_year is not None and final_year <= tax_year
+    paid = _amount(child.get("expenses"))
+    earlier = _amount(child.get("prior_year_expenses"))
+    foreign = bool(child.get("foreign"))
+    if final:
+        counted, waiting = earlier + paid, Decimal("0")
+    elif foreign:
+        counted, waiting = Decimal("0"), earlier + paid
+    else:
+        counted, waiting = earlier, paid
+    return {
+        "counted": counted,
+        "special_needs_final": final_year == tax_year and bool(child.get("special_needs")) and not foreign,
+        "waiting": waiting,
+    }
+
+
+def calculate_adoption_credit(
+    adoptions: List[Dict[str, Any]],
+    magi: Decimal,
+    filing_status: str,
+    tax_year: int,
+    carryforward: Decimal = Decimal("0"),
+    tax_limit: Optional[Decimal] = None,
+) -> Dict[str, Any]:
+    """
+    The adoption credit for a return's adopted children (Form 8839 Part II)
+
+    Each child's credit is limited to the per-child maximum less any credit
+    claimed for the child in earlier years, then phased out over the MAGI
+    range. The credit isn't refundable: what the tax left after other
+    credits can't absorb, along with any carryforward it doesn't use, carries
+    forward. Married filing separately can't take the credit unless the
+    spouses lived apart (not handled here).
+
+    Args:
+        adoptions: Adoption records from ReturnStore
+        magi: Modified AGI (AGI without foreign income exclusions)
+        filing_status: Filing status value
+        tax_year: Year being filed
+        carryforward: Unused credit carried in from earlier years (line 13)
+        tax_limit: Tax left after the credits taken before this one (line 15);
+            None leaves the credit unlimited
+
+    Returns:
+        Dict with tax_year, children (lines 2-12 for each child, plus waiting
+        expenses), lines (12-16), phaseout_percent, credit, carryforward, and notes
+
+    Raises:
+        ValueError: If the year isn't supported
+    """
+    if tax_year not in ADOPTION_CREDIT_LIMITS:
+        raise ValueError(f"The adoption credit isn't available for {tax_year}")
+    limit = ADOPTION_CREDIT_LIMITS[tax_year]
+    low, high = PHASEOUT_RANGES[tax_year]
+    notes: List[str] = []
+
+    if filing_status == "married_separate":
+        allowed_share = Decimal("0")
+        if adoptions:
+            notes.append("The adoption credit can't be taken when married filing separately")
+    else:
+        # Line 10: the phased-out share, rounded to three places
+        reduced = min(max((magi - low) / (high - low), Decimal("0")), Decimal("1"))
+        allowed_share = 1 - reduced.quantize(Decimal("0.001"), rounding=ROUND_HALF_UP)
+        if Decimal("0") < allowed_share < 1:
+            notes.append(f"Modified AGI of ${magi:,.2f} is in the ${low:,.0f}-${high:,.0f} phase-out range")
+
+    children = []
+    line_12 = Decimal("0")
+    for child in adoptions:
+        expenses = expenses_this_year(child, tax_year)
+        remaining = max(limit - _amount(child.get("credit_claimed_before")), Decimal("0"))
+        qualified = remaining if expenses["special_needs_final"] else expenses["counted"]
+        tentative = min(remaining, qualified)
+        allowed = _cents(tentative * allowed_share)
+        line_12 += allowed
+        children.append({
+            "adoption_id": child.get("id"),
+            "name": child.get("name", ""),
+            "lines": {
+                "2": float(limit),
+                "3": float(_amount(child.get("credit_claimed_before"))),
+                "4": float(remaining),
+                "5": float(qualified),
+                "6": float(tentative),
+                "11": float(tentative - allowed),
+                "12": float(allowed),
+            },
+            "special_needs_final": expenses["special_needs_final"],
+            "waiting_expenses": float(expenses["waiting"]),
+        })
+        if expenses["waiting"]:
+            notes.append(
+                f"${expenses['waiting']:,.2f} of {child.get('name') or 'a child'}'s expenses count in a later year "
+                f"({'when the adoption is final' if child.get('foreign') else 'the year after they were paid'})"
+            )
+
+    line_14 = line_12 + carryforward
+    line_15 = line_14 if tax_limit is None else max(tax_limit, Decimal("0"))
+    credit = min(line_14, line_15)
+    unused = line_14 - credit
+    if unused > 0:
+        notes.append(
+            f"${unused:,.2f} of adoption credit is more than the tax and carries forward (up to {CARRYFORWARD_YEARS} "
+            "years from the year it arose)"
+        )
+
+    return {
+        "tax_year": tax_year,
+        "children": children,
+        "lines": {
+            "12": float(line_12),
+            "13": float(carryforward),
+            "14": float(line_14),
+            "15": float(line_15),
+            "16": float(credit),
+        },
+        "phaseout_percent": float(_cents((1 - allowed_share) * 100)),
+        "credit": float(credit),
+        "carryforward": float(unused),
+        "notes": notes,
+    }
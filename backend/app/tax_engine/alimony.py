@@ -0,0 +1,56 @@
+"""
+Alimony
+Whether alimony paid is deductible and alimony received is taxable, by when the divorce or separation instrument was executed
+"""
+from datetime import date
+from decimal import Decimal
+from typing import Dict, List, Any
+
+ALIMONY_DIRECTIONS = ["paid", "received"]
+
+# Instruments executed after 2018 (and older ones modified to adopt the new rule) neither deduct nor tax alimony
+NEW_RULE_DATE = date(2019, 1, 1)
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def alimony_is_taxable(record: Dict[str, Any]) -> bool:
+    """Whether an alimony record is deductible by the payer and taxable to the recipient"""
+    executed = date.fromisoformat(record["instrument_date"])
+    return executed < NEW_RULE_DATE and not record.get("modified_to_adopt_new_rule")
+
+
+def alimony_treatment(tax_return: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    Alimony on a return, split into what counts for tax and what doesn't
+
+    Child support is never alimony; only payments the instrument calls
+    alimony or separate maintenance belong in these records.
+
+    Returns:
+        Dict with records (each with taxable and why), deductible_paid,
+        taxable_received, and notes
+    """
+    records = []
+    paid = received = Decimal("0")
+    notes: List[str] = []
+    for record in tax_return.get("alimony", []):
+        taxable = alimony_is_taxable(record)
+        if taxable:
+            reason = "Instrument executed before 2019"
+        elif record.get("modified_to_adopt_new_rule"):
+            reason = "Pre-2019 instrument modified to adopt the post-2018 rule"
+        else:
+            reason = "Instrument executed after 2018"
+        records.append({**record, "taxable": taxable, "reason": reason})
+        if not taxable:
+            continue
+        if record["direction"] == "paid":
+            paid += _amount(record.get("amount"))
+            if not record.get("recipient_ssn"):
+                notes.append("The alimony deduction needs the recipient's social security number")
+        else:
+            received += _amount(record.get("amount"))
+    return {"records": records, "deductible_paid": paid, "taxable_received": received, "notes": notes}
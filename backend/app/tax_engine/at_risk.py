@@ -0,0 +1,97 @@
+"""
+At-Risk Limitation
+Limits each activity's loss to the amount the taxpayer has at risk in it (Form 6198), carrying the rest forward per activity
+"""
+from decimal import Decimal
+from typing import Dict, List, Any, Optional
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def limit_to_at_risk(
+    net_income: Decimal,
+    at_risk_amount: Optional[Decimal],
+    carryforward: Decimal = Decimal("0"),
+) -> Dict[str, Any]:
+    """
+    Apply the at-risk limit to one activity's year
+
+    Losses carried in from earlier years are treated as this year's
+    deductions. Income from the activity raises the amount at risk before
+    losses are measured against it, and allowed losses lower it.
+
+    Args:
+        net_income: The activity's profit (loss negative) before the limit
+        at_risk_amount: Amount at risk at the start of the year (cash and
+            property contributed plus debt the taxpayer is personally liable
+            for); None means fully at risk
+        carryforward: Losses disallowed in earlier years
+
+    Returns:
+        Dict with allowed (the profit or loss reported), carryforward (loss
+        disallowed, to next year), and ending_at_risk
+    """
+    gains = max(net_income, Decimal("0"))
+    losses = max(-net_income, Decimal("0")) + carryforward
+    if at_risk_amount is None:
+        allowed_loss = losses
+        ending = None
+    else:
+        allowed_loss = min(losses, max(at_risk_amount, Decimal("0")) + gains)
+        ending = max(at_risk_amount, Decimal("0")) + gains - allowed_loss
+    return {
+        "allowed": float(gains - allowed_loss),
+        "carryforward": float(losses - allowed_loss),
+        "ending_at_risk": float(ending) if ending is not None else None,
+    }
+
+
+def at_risk_activities(
+    businesses: List[Dict[str, Any]],
+    business_schedules: List[Dict[str, Any]],
+    rentals: List[Dict[str, Any]],
+    rental_years: List[Dict[str, Any]],
+) -> List[Dict[str, Any]]:
+    """
+    The at-risk limit for each Schedule C business and rental property
+
+    Activities without an at_risk_amount or at_risk_carryforward aren't
+    limited. The limit applies before the passive activity loss rules, and
+    self-employment tax is figured on the business's profit or loss before it.
+
+    Args:
+        businesses: Business records (at_risk_amount, at_risk_carryforward)
+        business_schedules: Schedule C results for those businesses
+        rentals: Rental property records
+        rental_years: rental_property_year results for the rentals on the return this year
+
+    Returns:
+        One row per activity: activity_type, activity_id, name, net_income,
+        at_risk_amount, carryforward_in, limited, and the limit_to_at_risk results
+    """
+    records = {r["id"]: ("rental", r) for r in rentals}
+    records.update({b["id"]: ("business", b) for b in businesses})
+    figures = [(s["business_id"], s["name"], s["net_profit"]) for s in business_schedules]
+    figures += [(y["property_id"], y["address"], y["net_income"]) for y in rental_years]
+
+    activities = []
+    for activity_id, name, net_income in figures:
+        activity_type, record = records[activity_id]
+        at_risk_amount = record.get("at_risk_amount")
+        carried_in = _amount(record.get("at_risk_carryforward"))
+        result = limit_to_at_risk(
+            _amount(net_income), _amount(at_risk_amount) if at_risk_amount is not None else None, carried_in
+        )
+        activities.append({
+            "activity_type": activity_type,
+            "activity_id": activity_id,
+            "name": name,
+            "net_income": float(net_income),
+            "at_risk_amount": at_risk_amount,
+            "carryforward_in": float(carried_in),
+            "limited": result["carryforward"] > 0,
+            **result,
+        })
+    return activities
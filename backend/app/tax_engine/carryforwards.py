@@ -0,0 +1,82 @@
+"""
+Carryforwards
+Amounts a return passes to the next tax year (capital loss, NOL, AMT credit, adoption credit)
+"""
+from typing import Dict, Any, Optional
+from decimal import Decimal
+
+from app.tax_engine.rental_depreciation import calculate_rentals
+
+# Net capital losses deductible against other income each year (Schedule D line 21)
+CAPITAL_LOSS_LIMIT = Decimal("3000")
+CAPITAL_LOSS_LIMIT_MARRIED_SEPARATE = Decimal("1500")
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def capital_loss_limit(filing_status: Optional[str]) -> Decimal:
+    """Net capital loss deductible against other income for a filing status"""
+    return CAPITAL_LOSS_LIMIT_MARRIED_SEPARATE if filing_status == "married_separate" else CAPITAL_LOSS_LIMIT
+
+
+def net_capital_gain(tax_return: Dict[str, Any], rentals: Optional[Dict[str, Any]] = None) -> Decimal:
+    """
+    Net capital gain or loss for the year before the annual loss limit
+
+    Any loss carried in, capital gain income sources, capital transactions,
+    and a gain on selling a rental (section 1231 gain, taxed like a capital
+    gain; a loss is ordinary and left out).
+
+    Args:
+        tax_return: Return dict from ReturnStore
+        rentals: calculate_rentals() output for the return, if already computed
+
+    Returns:
+        Net gain (negative for a net loss)
+    """
+    net = -_amount((tax_return.get("carryforwards") or {}).get("capital_loss"))
+    for source in tax_return.get("income_sources", []):
+        if source.get("type") == "capital_gains":
+            net += _amount(source.get("amount"))
+    for lot in tax_return.get("capital_transactions", []):
+        net += _amount(lot.get("gain_or_loss"))
+    if tax_return.get("rental_properties"):
+        net += max(_amount((rentals or calculate_rentals(tax_return))["sale_gain"]), Decimal("0"))
+    return net
+
+
+def next_year_carryforwards(
+    tax_return: Dict[str, Any], calculation: Optional[Dict[str, Any]] = None
+) -> Dict[str, float]:
+    """
+    Carryforwards from a return into the following year
+
+    The capital loss carryover is this year's net capital loss (see
+    net_capital_gain) beyond the annual limit. NOL and AMT credit carryforwards
+    aren't computed by the engine, so the amounts entered on the return carry
+    over unchanged. The adoption credit carryforward is what the year's
+    calculation couldn't use, or the amount carried in when there's no calculation.
+
+    Args:
+        tax_return: Return dict from ReturnStore
+        calculation: calculate_return() output for the return (defaults to
+            the calculation saved on it)
+
+    Returns:
+        Dict of capital_loss, nol, amt_credit, adoption_credit -> amount (only nonzero amounts)
+    """
+    entered = tax_return.get("carryforwards") or {}
+
+    net = net_capital_gain(tax_return)
+    carryforwards = {
+        "capital_loss": max(Decimal("0"), -net - capital_loss_limit(tax_return.get("filing_status"))),
+        "nol": _amount(entered.get("nol")),
+        "amt_credit": _amount(entered.get("amt_credit")),
+        "adoption_credit": _amount(entered.get("adoption_credit")),
+    }
+    adoption = (calculation or tax_return.get("calculation") or {}).get("adoption_credit")
+    if adoption is not None:
+        carryforwards["adoption_credit"] = _amount(adoption["carryforward"])
+    return {name: float(amount) for name, amount in carryforwards.items() if amount > 0}
@@ -0,0 +1,165 @@
+"""
+Crypto Income and Basis
+Staking, mining, and airdrop receipts taxed as ordinary income at fair market value, with the basis lots they create
+"""
+from datetime import date
+from decimal import Decimal, ROUND_HALF_UP
+from typing import Dict, List, Any, Optional, Tuple
+
+# How crypto was received; all but purchase are ordinary income at fair market value on receipt
+CRYPTO_RECEIPT_KINDS = ["staking", "mining", "airdrop", "purchase"]
+INCOME_KINDS = ["staking", "mining", "airdrop"]
+
+_LABELS = {"staking": "Staking rewards", "mining": "Mining rewards", "airdrop": "Airdrop"}
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def _cents(value: Decimal) -> Decimal:
+    return value.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+
+
+def holding_term(date_acquired: str, date_sold: str) -> str:
+    """"long" when held more than one year, else "short\""""
+    acquired, sold = date.fromisoformat(date_acquired), date.fromisoformat(date_sold)
+    try:
+        one_year_later = acquired.replace(year=acquired.year + 1)
+    except ValueError:  # Feb 29
+        one_year_later = acquired.replace(year=acquired.year + 1, day=28)
+    return "long" if sold > one_year_later else "short"
+
+
+def classify_receipt(
+    kind: str,
+    asset: str,
+    quantity: Decimal,
+    fair_market_value: Decimal,
+    date_received: str,
+    business_id: Optional[str] = None,
+) -> Tuple[Optional[Dict[str, Any]], Dict[str, Any]]:
+    """
+    Income source and basis lot for crypto received
+
+    Rewards are income when the taxpayer gains control of them, at their
+    fair market value that day, and that value becomes the basis of the new
+    lot. Mining run as a business (business_id set) is self-employment
+    income on its Schedule C; otherwise rewards are other income
+    (Schedule 1 line 8v).
+
+    Args:
+        kind: One of CRYPTO_RECEIPT_KINDS
+        asset: Ticker or name (ETH, BTC, ...)
+        quantity: Units received
+        fair_market_value: Total U.S. dollar value on receipt (the price paid for a purchase)
+        date_received: YYYY-MM-DD
+        business_id: Schedule C business for mining as a trade or business
+
+    Returns:
+        (income source fields or None for a purchase, lot fields)
+
+    Raises:
+        ValueError: If the kind, quantity, value, or date is invalid
+    """
+    if kind not in CRYPTO_RECEIPT_KINDS:
+        raise ValueError(f"Invalid crypto receipt: {kind}. Must be one of: {', '.join(CRYPTO_RECEIPT_KINDS)}")
+    if quantity <= 0:
+        raise ValueError("Quantity must be positive")
+    if fair_market_value < 0:
+        raise ValueError("Fair market value cannot be negative")
+    if business_id and kind != "mining":
+        raise ValueError("Only mining can be reported as a business")
+    date.fromisoformat(date_received)
+
+    asset = asset.strip().upper()
+    lot = {
+        "asset": asset,
+        "kind": kind,
+        "quantity": float(quantity),
+        "remaining": float(quantity),
+        "cost_basis": float(_cents(fair_market_value)),
+        "date_acquired": date_received,
+    }
+    if kind not in INCOME_KINDS:
+        return None, lot
+
+    income = {
+        "type": "self_employment" if business_id else "other",
+        "description": f"{_LABELS[kind]}: {quantity.normalize():f} {asset}",
+        "amount": float(_cents(fair_market_value)),
+        "date": date_received,
+        "crypto_kind": kind,
+    }
+    if business_id:
+        income["business_id"] = business_id
+    return income, lot
+
+
+def dispose_lots(
+    lots: List[Dict[str, Any]],
+    asset: str,
+    quantity: Decimal,
+    proceeds: Decimal,
+    date_sold: str,
+) -> List[Dict[str, Any]]:
+    """
+    Sell or spend crypto out of its lots, first in first out
+
+    The lots' remaining quantities are reduced in place. Proceeds are split
+    across the lots used by quantity; each becomes a Form 8949 row with the
+    lot's basis and holding period.
+
+    Args:
+        lots: The return's crypto lots
+        asset: Ticker or name
+        quantity: Units disposed of
+        proceeds: Total U.S. dollars received (or the value of what was bought)
+        date_sold: YYYY-MM-DD
+
+    Returns:
+        Capital transactions for the capital gains engine
+
+    Raises:
+        ValueError: If more is disposed of than the lots hold
+    """
+    asset = asset.strip().upper()
+    if quantity <= 0:
+        raise ValueError("Quantity must be positive")
+    if proceeds < 0:
+        raise ValueError("Proceeds cannot be negative")
+    available = sorted(
+        (lot for lot in lots if lot["asset"] == asset and _amount(lot["remaining"]) > 0
+         and lot["date_acquired"] <= date_sold),
+        key=lambda lot: lot["date_acquired"],
+    )
+    held = sum((_amount(lot["remaining"]) for lot in available), Decimal("0"))
+    if quantity > held:
+        raise ValueError(f"Only {held.normalize():f} {asset} held on {date_sold}")
+
+    transactions = []
+    left = quantity
+    allocated = Decimal("0")
+    for lot in available:
+        if left <= 0:
+            break
+        used = min(left, _amount(lot["remaining"]))
+        left -= used
+        share = proceeds - allocated if left == 0 else _cents(proceeds * used / quantity)
+        allocated += share
+        basis = _cents(_amount(lot["cost_basis"]) * used / _amount(lot["quantity"]))
+        lot["remaining"] = float(_amount(lot["remaining"]) - used)
+        transactions.append({
+            "description": f"{used.normalize():f} {asset}",
+            "symbol": asset,
+            "quantity": float(used),
+            "date_acquired": lot["date_acquired"],
+            "date_sold": date_sold,
+            "proceeds": float(share),
+            "cost_basis": float(basis),
+            "wash_sale_disallowed": 0.0,
+            "term": holding_term(lot["date_acquired"], date_sold),
+            "gain_or_loss": float(share - basis),
+            "crypto_lot_id": lot.get("id"),
+        })
+    return transactions
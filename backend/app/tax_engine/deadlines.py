@@ -0,0 +1,153 @@
+"""
+Filing Deadlines
+Federal and state due dates per tax year, extensions, and upcoming-deadline reminders
+"""
+from datetime import date, timedelta
+from typing import Dict, List, Any, Optional, Tuple
+
+# Returns are due April 15 and extended returns October 15 of the following year
+FEDERAL_DUE = (4, 15)
+FEDERAL_EXTENDED_DUE = (10, 15)
+
+# (month, day) in the year after the tax year, for states that don't follow April 15
+STATE_DUE_DATES: Dict[str, Tuple[int, int]] = {
+    "DE": (4, 30),
+    "HI": (4, 20),
+    "IA": (4, 30),
+    "LA": (5, 15),
+    "VA": (5, 1),
+}
+STATE_EXTENDED_DUE_DATES: Dict[str, Tuple[int, int]] = {
+    "HI": (10, 20),
+    "IA": (10, 31),
+    "LA": (11, 15),
+    "VA": (11, 1),
+}
+
+# States without a personal income tax return to file
+NO_RETURN_STATES = {"AK", "FL", "NV", "NH", "SD", "TN", "TX", "WA", "WY"}
+
+# Deadlines within this many days are reported as upcoming
+DEFAULT_REMINDER_DAYS = 30
+
+
+def _emancipation_day(year: int) -> date:
+    """DC Emancipation Day (April 16), observed on the nearest weekday"""
+    day = date(year, 4, 16)
+    if day.weekday() == 5:
+        return day - timedelta(days=1)
+    if day.weekday() == 6:
+        return day + timedelta(days=1)
+    return day
+
+
+def next_business_day(day: date) -> date:
+    """A deadline falling on a weekend or DC Emancipation Day moves to the next business day"""
+    while day.weekday() >= 5 or day == _emancipation_day(day.year):
+        day += timedelta(days=1)
+    return day
+
+
+def _due(tax_year: int, month_day: Tuple[int, int]) -> date:
+    return next_business_day(date(tax_year + 1, *month_day))
+
+
+def return_deadlines(tax_return: Dict[str, Any]) -> List[Dict[str, Any]]:
+    """
+    Filing deadlines for a return
+
+    Filing an extension moves the filing deadline to the extended date but
+    not the payment deadline. A deadline is met once the return is filed;
+    payment deadlines are also met when the last calculation shows nothing owed.
+
+    Args:
+        tax_return: Return dict from ReturnStore
+
+    Returns:
+        Deadlines ordered by due_date, each with jurisdiction, kind (payment,
+        filing), description, due_date, extended, and met
+    """
+    tax_year = tax_return["tax_year"]
+    extension = tax_return.get("extension") or {}
+    filed = tax_return.get("status") in ("filed", "amended")
+    paid = filed or _nothing_owed(tax_return.get("refund_or_owed"))
+
+    deadlines = [
+        _deadline("federal", "payment", f"{tax_year} federal tax payment (Form 1040)", _due(tax_year, FEDERAL_DUE), False, paid),
+        _deadline(
+            "federal", "filing", f"{tax_year} federal return (Form 1040)",
+            _due(tax_year, FEDERAL_EXTENDED_DUE if extension else FEDERAL_DUE), bool(extension), filed,
+        ),
+    ]
+    for record in tax_return.get("state_returns", []):
+        state = record["state"]
+        if state in NO_RETURN_STATES:
+            continue
+        original = _due(tax_year, STATE_DUE_DATES.get(state, FEDERAL_DUE))
+        extended = state in extension.get("states", [])
+        due = _due(tax_year, STATE_EXTENDED_DUE_DATES.get(state, FEDERAL_EXTENDED_DUE)) if extended else original
+        state_paid = filed or _nothing_owed(record.get("balance"))
+        deadlines.append(_deadline(state, "payment", f"{tax_year} {state} tax payment", original, False, state_paid))
+        deadlines.append(_deadline(state, "filing", f"{tax_year} {state} return", due, extended, filed))
+
+    deadlines.sort(key=lambda d: (d["due_date"], d["jurisdiction"] != "federal", d["kind"]))
+    return deadlines
+
+
+def upcoming_deadlines(
+    tax_returns: List[Dict[str, Any]],
+    as_of: Optional[date] = None,
+    within_days: int = DEFAULT_REMINDER_DAYS,
+) -> List[Dict[str, Any]]:
+    """
+    Unmet deadlines that are overdue or due soon, across returns
+
+    Args:
+        tax_returns: Return dicts from ReturnStore
+        as_of: Today (defaults to the current date)
+        within_days: How far ahead to look
+
+    Returns:
+        Reminders ordered by due_date, each a deadline with return_id,
+        days_remaining (negative when overdue), overdue, and message
+    """
+    as_of = as_of or date.today()
+    horizon = as_of + timedelta(days=within_days)
+    reminders = []
+    for tax_return in tax_returns:
+        for deadline in return_deadlines(tax_return):
+            due = date.fromisoformat(deadline["due_date"])
+            if deadline["met"] or due > horizon:
+                continue
+            days = (due - as_of).days
+            if days < 0:
+                message = f"{deadline['description']} was due {deadline['due_date']} ({-days} days ago)"
+            elif days == 0:
+                message = f"{deadline['description']} is due today"
+            else:
+                message = f"{deadline['description']} is due {deadline['due_date']} (in {days} days)"
+            reminders.append({
+                **deadline,
+                "return_id": tax_return["return_id"],
+                "days_remaining": days,
+                "overdue": days < 0,
+                "message": message,
+            })
+    reminders.sort(key=lambda r: (r["due_date"], r["return_id"]))
+    return reminders
+
+
+def _nothing_owed(balance: Optional[float]) -> bool:
+    """Whether a calculated balance (positive for a refund) leaves nothing to pay"""
+    return balance is not None and balance >= 0
+
+
+def _deadline(jurisdiction: str, kind: str, description: str, due: date, extended: bool, met: bool) -> Dict[str, Any]:
+    return {
+        "jurisdiction": jurisdiction,
+        "kind": kind,
+        "description": description,
+        "due_date": due.isoformat(),
+        "extended": extended,
+        "met": met,
+    }
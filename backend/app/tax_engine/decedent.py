@@ -0,0 +1,177 @@
+"""
+Deceased Taxpayer
+Filing status for a final return and the surviving spouse's returns in the years after a death
+"""
+from datetime import date
+from typing import Dict, List, Any, Optional
+
+from app.tax_engine.dependents import normalize_relationship, qualify_dependents
+
+DECEASED_PERSONS = ["taxpayer", "spouse"]
+
+# A surviving spouse can file as a qualifying surviving spouse for the two years after the year of death
+SURVIVING_SPOUSE_YEARS = 2
+
+# The qualifying surviving spouse's dependent must be a child or stepchild (a foster child doesn't count)
+SURVIVING_SPOUSE_CHILD_RELATIONSHIPS = {
+    "son", "daughter", "child", "stepson", "stepdaughter", "stepchild", "adopted child",
+}
+
+
+def _date(value: Optional[str]) -> Optional[date]:
+    return date.fromisoformat(value) if value else None
+
+
+def surviving_spouse_children(tax_return: Dict[str, Any], dependents_only: bool = True) -> List[Dict[str, Any]]:
+    """
+    Children and stepchildren who lived in the home all year
+
+    With dependents_only, only those who pass the dependent tests (which
+    need the return's year to be supported).
+    """
+    children = [
+        d for d in tax_return.get("dependents", [])
+        if normalize_relationship(d.get("relationship")) in SURVIVING_SPOUSE_CHILD_RELATIONSHIPS
+        and (d.get("months_lived_with") or 0) >= 12
+    ]
+    if not dependents_only:
+        return children
+    qualification = {d["dependent_id"]: d for d in qualify_dependents(tax_return)["dependents"]}
+    return [d for d in children if qualification[d["id"]]["is_dependent"]]
+
+
+def decedent_filing_options(tax_return: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    Filing statuses allowed after a taxpayer's or spouse's death
+
+    In the year of death the surviving spouse can still file jointly with
+    the deceased spouse (signing as the surviving spouse) unless they
+    remarried before the year ended. For the next two years a surviving
+    spouse who hasn't remarried, kept up a home for a dependent child all
+    year, and paid more than half its cost can file as a qualifying
+    surviving spouse, with the joint rates and standard deduction. After
+    that the usual rules apply.
+
+    Returns:
+        Dict with deceased, date_of_death, years_after_death, allowed
+        statuses, reasons, requirements (what the return must include), and notes
+
+    Raises:
+        ValueError: If no death is recorded or the return is for a year
+            before it, or the deceased taxpayer has no later returns
+    """
+    death = tax_return.get("death")
+    if not death:
+        raise ValueError("No date of death is recorded on this return")
+    tax_year = tax_return["tax_year"]
+    died = _date(death["date_of_death"])
+    years_after = tax_year - died.year
+    if years_after < 0:
+        raise ValueError(f"The return is for {tax_year}, before the date of death ({died})")
+    taxpayer = tax_return.get("taxpayer") or {}
+    deceased_name = taxpayer.get("spouse_name" if death["deceased"] == "spouse" else "name") or death["deceased"]
+    remarried = _date(death.get("remarried_date"))
+    remarried_by_year_end = remarried is not None and remarried <= date(tax_year, 12, 31)
+    married = bool(taxpayer.get("spouse_name")) or tax_return["filing_status"] in ("married_joint", "married_separate")
+    hoh = any("head_of_household" in d["benefits"] for d in qualify_dependents(tax_return)["dependents"])
+    pays_home = death.get("paid_over_half_home_cost")
+    allowed: List[str] = []
+    reasons: Dict[str, str] = {}
+    requirements: List[str] = []
+    notes: List[str] = []
+
+    if years_after == 0:
+        requirements.append(
+            f"Write \"DECEASED\", {deceased_name}'s name, and the date of death ({died}) across the top"
+        )
+        if married and not remarried_by_year_end:
+            allowed = ["married_joint", "married_separate"]
+            reasons["married_joint"] = "A spouse who died during the year is treated as married for the whole year"
+            if death["deceased"] == "spouse":
+                requirements.append(
+                    "Sign the joint return and write \"Filing as surviving spouse\" in the spouse's signature area"
+                )
+            else:
+                requirements.append(
+                    "The surviving spouse signs and writes \"Filing as surviving spouse\" for the deceased"
+                )
+        elif married:
+            allowed = ["married_separate"]
+            reasons["married_joint"] = (
+                f"The surviving spouse remarried on {remarried}; the final return is married filing separately"
+            )
+        else:
+            allowed = ["single"] + (["head_of_household"] if hoh and pays_home else [])
+        if death.get("personal_representative"):
+            requirements.append(f"{death['personal_representative']} signs as the personal representative")
+        if death["deceased"] == "taxpayer" and not (married and "married_joint" in allowed):
+            requirements.append(
+                "Attach Form 1310 to claim a refund, unless a court-appointed representative attaches the appointment"
+            )
+    elif death["deceased"] == "taxpayer":
+        raise ValueError(f"The taxpayer died in {died.year}; their final return is for that year")
+    elif remarried_by_year_end:
+        allowed = ["married_joint", "married_separate"]
+        reasons["qualifying_surviving_spouse"] = f"Remarried on {remarried}"
+        notes.append("Married to the new spouse on December 31; this year is filed as married")
+    else:
+        allowed = ["single"]
+        if years_after > SURVIVING_SPOUSE_YEARS:
+            reasons["qualifying_surviving_spouse"] = (
+                f"Only available for the {SURVIVING_SPOUSE_YEARS} years after the year of death ({died.year})"
+            )
+        elif not surviving_spouse_children(tax_return):
+            reasons["qualifying_surviving_spouse"] = "No dependent child or stepchild lived in the home all year"
+        elif not pays_home:
+            reasons["qualifying_surviving_spouse"] = (
+                "Paying more than half the cost of keeping up the home isn't confirmed"
+            )
+        else:
+            allowed.insert(0, "qualifying_surviving_spouse")
+            reasons["qualifying_surviving_spouse"] = (
+                f"Year {years_after} of {SURVIVING_SPOUSE_YEARS} after {deceased_name}'s death in {died.year}"
+            )
+            requirements.append(f"Enter the year the spouse died ({died.year}) next to the filing status")
+        if hoh and pays_home:
+            allowed.append("head_of_household")
+    if pays_home is None and years_after > 0:
+        notes.append("Record whether more than half the cost of keeping up the home was paid")
+
+    if tax_return["filing_status"] not in allowed:
+        notes.append(f"The return's filing status ({tax_return['filing_status']}) isn't allowed this year")
+
+    return {
+        "tax_year": tax_year,
+        "deceased": death["deceased"],
+        "deceased_name": deceased_name,
+        "date_of_death": death["date_of_death"],
+        "years_after_death": years_after,
+        "allowed": allowed,
+        "reasons": reasons,
+        "requirements": requirements,
+        "notes": notes,
+    }
+
+
+def next_year_filing_status(tax_return: Dict[str, Any], tax_year: int) -> Optional[str]:
+    """
+    Default filing status for the surviving spouse's return for a later year
+
+    Qualifying surviving spouse for the two years after the death when a
+    child lived with the taxpayer all year (the new year's facts still need
+    checking), otherwise single; single again once those years are over.
+    None when the death doesn't change the filing status.
+    """
+    death = tax_return.get("death")
+    if not death:
+        return None
+    remarried = _date(death.get("remarried_date"))
+    if remarried is not None and remarried.year < tax_year:
+        return None
+    years_after = tax_year - _date(death["date_of_death"]).year
+    if 0 < years_after <= SURVIVING_SPOUSE_YEARS:
+        children = surviving_spouse_children(tax_return, dependents_only=False)
+        return "qualifying_surviving_spouse" if children else "single"
+    if years_after > SURVIVING_SPOUSE_YEARS and tax_return["filing_status"] == "qualifying_surviving_spouse":
+        return "single"
+    return None
@@ -0,0 +1,232 @@
+"""
+Dependent Qualification
+Qualifying child and qualifying relative tests for a return's dependents, and the benefits each one supports
+"""
+from datetime import date
+from decimal import Decimal
+from typing import Dict, List, Any, Optional
+
+# Relationships that can make someone a qualifying child (and their descendants, like a grandchild or niece)
+QUALIFYING_CHILD_RELATIONSHIPS = {
+    "son", "daughter", "child", "stepson", "stepdaughter", "stepchild", "foster child", "adopted child",
+    "brother", "sister", "half brother", "half sister", "stepbrother", "stepsister", "sibling",
+    "grandchild", "grandson", "granddaughter", "niece", "nephew",
+}
+
+# Relatives who don't have to live with the taxpayer to be a qualifying relative
+QUALIFYING_RELATIVE_RELATIONSHIPS = QUALIFYING_CHILD_RELATIONSHIPS - {"foster child"} | {
+    "parent", "mother", "father", "stepmother", "stepfather", "stepparent",
+    "grandparent", "grandmother", "grandfather", "aunt", "uncle",
+    "son in law", "daughter in law", "father in law", "mother in law", "brother in law", "sister in law",
+}
+
+# Parents can make the taxpayer head of household without living with them
+PARENT_RELATIONSHIPS = {"parent", "mother", "father"}
+
+# Qualifying relative gross income limit (the personal exemption amount)
+GROSS_INCOME_LIMITS = {2024: Decimal("5050")}
+
+QUALIFYING_CHILD_AGE = 19
+STUDENT_AGE = 24
+CHILD_TAX_CREDIT_AGE = 17
+
+# Parent a child of divorced or separated parents lived with for more nights
+CUSTODIAL_PARENTS = ["taxpayer", "other_parent"]
+
+PASS, FAIL, UNKNOWN = "pass", "fail", "unknown"
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def normalize_relationship(relationship: Optional[str]) -> str:
+    """Lowercase with hyphens and underscores as spaces ("Son-in-law" -> "son in law")"""
+    return " ".join((relationship or "").lower().replace("-", " ").replace("_", " ").split())
+
+
+def _age(birth_date: str, tax_year: int) -> int:
+    born = date.fromisoformat(birth_date)
+    return tax_year - born.year - ((12, 31) < (born.month, born.day))
+
+
+def _qualifying_child_tests(dependent: Dict[str, Any], tax_year: int, age: Optional[int]) -> Dict[str, str]:
+    relationship = normalize_relationship(dependent.get("relationship"))
+    months = dependent.get("months_lived_with")
+    born_in_year = bool(dependent.get("birth_date")) and date.fromisoformat(dependent["birth_date"]).year == tax_year
+    if dependent.get("disabled"):
+        age_test = PASS
+    elif age is None:
+        age_test = UNKNOWN
+    else:
+        limit = STUDENT_AGE if dependent.get("full_time_student") else QUALIFYING_CHILD_AGE
+        age_test = PASS if age < limit else FAIL
+    if born_in_year:
+        # A child born during the year lived with the taxpayer all the time they were alive
+        residency = PASS if months is None or months > 0 else FAIL
+    else:
+        residency = UNKNOWN if months is None else PASS if months > 6 else FAIL
+    self_support = dependent.get("self_support_percent")
+    return {
+        "relationship": PASS if relationship in QUALIFYING_CHILD_RELATIONSHIPS else FAIL,
+        "age": age_test,
+        "residency": residency,
+        "support": PASS if self_support is None or self_support <= 50 else FAIL,
+        "joint_return": FAIL if dependent.get("files_joint_return") else PASS,
+    }
+
+
+def _qualifying_relative_tests(dependent: Dict[str, Any], tax_year: int) -> Dict[str, str]:
+    relationship = normalize_relationship(dependent.get("relationship"))
+    months = dependent.get("months_lived_with")
+    if relationship in QUALIFYING_RELATIVE_RELATIONSHIPS:
+        member = PASS
+    else:
+        # Anyone else must live with the taxpayer all year as a member of the household
+        member = UNKNOWN if months is None else PASS if months >= 12 else FAIL
+    gross_income = dependent.get("gross_income")
+    if gross_income is None:
+        income_test = UNKNOWN
+    else:
+        income_test = PASS if _amount(gross_income) < GROSS_INCOME_LIMITS[tax_year] else FAIL
+    support = dependent.get("support_percent")
+    return {
+        "relationship": member,
+        "gross_income": income_test,
+        "support": UNKNOWN if support is None else PASS if support > 50 else FAIL,
+        "not_qualifying_child": FAIL if dependent.get("qualifying_child_of_another") else PASS,
+        "joint_return": FAIL if dependent.get("files_joint_return") else PASS,
+    }
+
+
+def form_8332_release(dependent: Dict[str, Any], tax_year: int) -> bool:
+    """Whether the custodial parent released the claim to the child for this year (Form 8332)"""
+    return tax_year in (dependent.get("form_8332_years") or [])
+
+
+def claimed_by_other_parent(dependent: Dict[str, Any], tax_year: int) -> bool:
+    """
+    Whether the other parent claims a child of divorced or separated parents this year
+
+    The custodial parent claims the child unless they release the claim to
+    the noncustodial parent on Form 8332.
+    """
+    custodial = dependent.get("custodial_parent")
+    if custodial not in CUSTODIAL_PARENTS:
+        return False
+    return (custodial == "taxpayer") == form_8332_release(dependent, tax_year)
+
+
+def _missing(tests: Dict[str, str]) -> List[str]:
+    return [name for name, result in tests.items() if result == UNKNOWN]
+
+
+def qualify_dependent(dependent: Dict[str, Any], tax_year: int) -> Dict[str, Any]:
+    """
+    Run the qualifying child and qualifying relative tests on one dependent
+
+    A test the record doesn't have the facts for is "unknown", and a
+    dependent only qualifies when every test passes. Flags the record
+    doesn't have (disabled, full_time_student, files_joint_return, ...) are
+    taken as false.
+
+    Args:
+        dependent: Dependent record from ReturnStore (relationship,
+            birth_date, months_lived_with, full_time_student, disabled,
+            self_support_percent, support_percent, gross_income,
+            files_joint_return, qualifying_child_of_another, has_ssn,
+            custodial_parent, form_8332_years)
+        tax_year: Year being filed
+
+    Returns:
+        Dict with age, qualifying_child and qualifying_relative (with their
+        tests), benefits (child_tax_credit, other_dependent_credit,
+        earned_income_credit, head_of_household), and reasons
+    """
+    age = _age(dependent["birth_date"], tax_year) if dependent.get("birth_date") else None
+    child_tests = _qualifying_child_tests(dependent, tax_year, age)
+    qualifying_child = all(result == PASS for result in child_tests.values())
+    relative_tests = _qualifying_relative_tests(dependent, tax_year)
+    qualifying_relative = not qualifying_child and all(result == PASS for result in relative_tests.values())
+
+    benefits = []
+    reasons = []
+    if qualifying_child and age is not None and age < CHILD_TAX_CREDIT_AGE and dependent.get("has_ssn", True):
+        benefits.append("child_tax_credit")
+    elif qualifying_child or qualifying_relative:
+        benefits.append("other_dependent_credit")
+        if qualifying_child and dependent.get("has_ssn", True) is False:
+            reasons.append("The child tax credit needs a social security number valid for employment")
+    # The earned income credit uses the qualifying child tests without the support test
+    if all(result == PASS for name, result in child_tests.items() if name != "support"):
+        benefits.append("earned_income_credit")
+    relationship = normalize_relationship(dependent.get("relationship"))
+    if qualifying_child or (
+        qualifying_relative
+        and relationship in QUALIFYING_RELATIVE_RELATIONSHIPS
+        and (relationship in PARENT_RELATIONSHIPS or (dependent.get("months_lived_with") or 0) > 6)
+    ):
+        benefits.append("head_of_household")
+
+    for kind, tests in (("qualifying child", child_tests), ("qualifying relative", relative_tests)):
+        failed = [name.replace("_", " ") for name, result in tests.items() if result == FAIL]
+        if failed and not (qualifying_child or qualifying_relative):
+            reasons.append(f"Not a {kind}: fails the {', '.join(failed)} test{'s' if len(failed) > 1 else ''}")
+
+    # Children of divorced or separated parents: Form 8332 moves only the dependency and the
+    # child tax credit; head of household and the earned income credit stay with the custodial parent
+    custodial = dependent.get("custodial_parent")
+    if custodial == "taxpayer" and form_8332_release(dependent, tax_year):
+        benefits = [b for b in benefits if b not in ("child_tax_credit", "other_dependent_credit")]
+        reasons.append("Released to the other parent on Form 8332; they claim the child and the child tax credit")
+    elif custodial == "other_parent":
+        benefits = []
+        if form_8332_release(dependent, tax_year):
+            young = age is not None and age < CHILD_TAX_CREDIT_AGE and dependent.get("has_ssn", True)
+            benefits = ["child_tax_credit" if young else "other_dependent_credit"]
+            qualifying_child = True
+            reasons = ["Claimed as the noncustodial parent; attach the other parent's Form 8332"]
+        else:
+            qualifying_child = qualifying_relative = False
+            reasons.append("The other parent is the custodial parent and hasn't released the claim on Form 8332")
+
+    return {
+        "dependent_id": dependent.get("id"),
+        "name": dependent.get("name", ""),
+        "relationship": dependent.get("relationship"),
+        "age": age,
+        "qualifying_child": {"qualifies": qualifying_child, "tests": child_tests, "missing": _missing(child_tests)},
+        "qualifying_relative": {
+            "qualifies": qualifying_relative, "tests": relative_tests, "missing": _missing(relative_tests),
+        },
+        "is_dependent": (qualifying_child or qualifying_relative) and not claimed_by_other_parent(dependent, tax_year),
+        "benefits": benefits,
+        "reasons": reasons,
+    }
+
+
+def qualify_dependents(tax_return: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    Dependent qualification for every dependent on a return
+
+    Head of household also needs the taxpayer to be unmarried and to pay
+    more than half the cost of keeping up the home; those aren't checked here.
+
+    Returns:
+        Dict with dependents (qualify_dependent results) and notes
+
+    Raises:
+        ValueError: If the year isn't supported
+    """
+    tax_year = tax_return["tax_year"]
+    if tax_year not in GROSS_INCOME_LIMITS:
+        raise ValueError(f"Dependent tests aren't available for {tax_year}")
+    results = [qualify_dependent(d, tax_year) for d in tax_return.get("dependents", [])]
+    notes = []
+    if any(r["qualifying_child"]["missing"] or r["qualifying_relative"]["missing"] for r in results):
+        notes.append("Some tests are unknown; add the missing facts to the dependent records")
+    if tax_return.get("filing_status") == "head_of_household" and not any(
+        "head_of_household" in r["benefits"] for r in results
+    ):
+        notes.append("No dependent is a qualifying person for head of household")
+    return {"tax_year": tax_year, "dependents": results, "notes": notes}
@@ -0,0 +1,196 @@
+"""
+Divorce and Separation
+Filing status, who claims the children (Form 8332), and alimony for the year of a divorce or separation
+"""
+from datetime import date
+from decimal import Decimal
+from typing import Dict, List, Any, Optional
+
+from app.tax_engine.alimony import alimony_treatment
+from app.tax_engine.dependents import (
+    claimed_by_other_parent, form_8332_release, normalize_relationship, qualify_dependents,
+)
+from app.tax_engine.filing_comparison import split_joint_return
+from app.tax_engine.return_calculation import calculate_return
+
+DECREE_TYPES = ["divorce", "separate_maintenance"]
+
+# Children who can make a married taxpayer "considered unmarried" for head of household
+CONSIDERED_UNMARRIED_RELATIONSHIPS = {
+    "son", "daughter", "child", "stepson", "stepdaughter", "stepchild", "foster child", "adopted child",
+}
+
+# Figures reported for each filing option
+OPTION_FIELDS = ["agi", "deduction_amount", "taxable_income", "total_tax", "refund_or_owed"]
+
+
+def _date(value: Optional[str]) -> Optional[date]:
+    return date.fromisoformat(value) if value else None
+
+
+def filing_status_options(tax_return: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    Filing statuses allowed for the year of a divorce or separation
+
+    Marital status is set on December 31: a final decree of divorce or
+    separate maintenance by then makes the taxpayer unmarried for the whole
+    year. A married taxpayer is "considered unmarried" and can file head of
+    household when the spouse didn't live in the home during the last six
+    months of the year, the taxpayer paid more than half the cost of keeping
+    it up, and it was the home of their child for more than half the year.
+
+    Returns:
+        Dict with marital_status (married or unmarried), allowed statuses,
+        reasons (status -> why it is or isn't allowed), and notes
+    """
+    tax_year = tax_return["tax_year"]
+    divorce = tax_return.get("divorce") or {}
+    decree = _date(divorce.get("decree_date"))
+    separated = _date(divorce.get("separated_date"))
+    pays_home = divorce.get("paid_over_half_home_cost")
+    qualification = qualify_dependents(tax_return)
+    hoh_people = [d for d in qualification["dependents"] if "head_of_household" in d["benefits"]]
+    notes: List[str] = []
+    reasons: Dict[str, str] = {}
+
+    unmarried = decree is not None and decree <= date(tax_year, 12, 31)
+    if unmarried:
+        allowed = ["single"]
+        decree_type = divorce.get("decree_type", "divorce").replace("_", " ")
+        reasons["single"] = f"The {decree_type} decree was final on {decree}"
+        reasons["married_joint"] = reasons["married_separate"] = "Not married on December 31"
+        home_children = hoh_people
+    else:
+        allowed = ["married_joint", "married_separate"]
+        reasons["single"] = "Still married on December 31"
+        if decree is not None:
+            notes.append(f"The decree isn't final until {decree}, so this year is filed as married")
+        reasons["married_joint"] = reasons["married_separate"] = "Married on December 31"
+        home_children = [
+            d for d in hoh_people
+            if normalize_relationship(d["relationship"]) in CONSIDERED_UNMARRIED_RELATIONSHIPS
+        ]
+        if separated is None or separated >= date(tax_year, 7, 1):
+            reasons["head_of_household"] = "The spouse lived in the home during the last six months of the year"
+            home_children = []
+
+    if "head_of_household" not in reasons:
+        if not home_children:
+            reasons["head_of_household"] = "No qualifying person lived in the home for more than half the year"
+        elif not pays_home:
+            reasons["head_of_household"] = "Paying more than half the cost of keeping up the home isn't confirmed"
+            if pays_home is None:
+                notes.append("Record whether more than half the cost of keeping up the home was paid")
+        else:
+            allowed.append("head_of_household")
+            names = ", ".join(d["name"] or "a dependent" for d in home_children)
+            reasons["head_of_household"] = f"Qualifying person: {names}" + (
+                "" if unmarried else " (considered unmarried)"
+            )
+
+    return {
+        "tax_year": tax_year,
+        "marital_status": "unmarried" if unmarried else "married",
+        "allowed": allowed,
+        "reasons": reasons,
+        "notes": notes,
+    }
+
+
+def dependent_allocation(tax_return: Dict[str, Any]) -> List[Dict[str, Any]]:
+    """
+    Who claims each child of divorced or separated parents
+
+    Only dependents with a custodial_parent are included. A Form 8332
+    release moves the dependency and child tax credit to the noncustodial
+    parent; head of household and the earned income credit stay with the
+    custodial parent.
+    """
+    tax_year = tax_return["tax_year"]
+    qualification = {d["dependent_id"]: d for d in qualify_dependents(tax_return)["dependents"]}
+    rows = []
+    for dependent in tax_return.get("dependents", []):
+        custodial = dependent.get("custodial_parent")
+        if not custodial:
+            continue
+        released = form_8332_release(dependent, tax_year)
+        rows.append({
+            "dependent_id": dependent["id"],
+            "name": dependent.get("name", ""),
+            "custodial_parent": custodial,
+            "form_8332_years": dependent.get("form_8332_years") or [],
+            "released_this_year": released,
+            "claimed_by": "other_parent" if claimed_by_other_parent(dependent, tax_year) else "taxpayer",
+            "taxpayer_benefits": qualification[dependent["id"]]["benefits"],
+            "attach_form_8332": custodial == "other_parent" and released,
+        })
+    return rows
+
+
+def divorce_year_report(tax_return: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    Filing options for the year of a divorce or separation, and the tax under each
+
+    Every allowed status is calculated on the taxpayer's own records (their
+    half of a joint return). Married filing jointly is only calculated when
+    the return is already joint, since it needs the spouse's income too; it's
+    compared with both spouses filing separately.
+
+    Returns:
+        Dict with filing_status (filing_status_options), options (per status
+        figures), recommended_filing_status, dependents
+        (dependent_allocation), alimony, and notes
+
+    Raises:
+        ValueError: If the return can't be calculated
+    """
+    options = filing_status_options(tax_return)
+    joint = tax_return["filing_status"] == "married_joint"
+    split = split_joint_return(tax_return) if joint else None
+    own = split["taxpayer"] if split else tax_return
+    notes = list(options["notes"])
+
+    rows = []
+    for status in options["allowed"]:
+        row: Dict[str, Any] = {"filing_status": status, "covers": "taxpayer", "calculated": True}
+        if status == "married_joint":
+            row["covers"] = "both spouses"
+            if not joint:
+                row["calculated"] = False
+                notes.append("Married filing jointly needs the spouse's income, which isn't on this return")
+                rows.append(row)
+                continue
+            calculation = calculate_return(tax_return)
+        else:
+            calculation = calculate_return({**own, "filing_status": status})
+        row.update({field: calculation[field] for field in OPTION_FIELDS})
+        rows.append(row)
+
+    own_rows = [r for r in rows if r["calculated"] and r["covers"] == "taxpayer"]
+    best = min(own_rows, key=lambda r: Decimal(str(r["total_tax"])))
+    recommended = best["filing_status"]
+    joint_row = next((r for r in rows if r["filing_status"] == "married_joint" and r["calculated"]), None)
+    if joint_row and split:
+        spouse_tax = Decimal(str(calculate_return(split["spouse"])["total_tax"]))
+        joint_row["spouse_separate_tax"] = float(spouse_tax)
+        if Decimal(str(joint_row["total_tax"])) <= Decimal(str(best["total_tax"])) + spouse_tax:
+            recommended = "married_joint"
+
+    alimony = alimony_treatment(tax_return)
+    notes += alimony["notes"]
+    if tax_return["filing_status"] not in options["allowed"]:
+        notes.append(f"The return's filing status ({tax_return['filing_status']}) isn't allowed this year")
+
+    return {
+        "tax_year": tax_return["tax_year"],
+        "filing_status": options,
+        "options": rows,
+        "recommended_filing_status": recommended,
+        "dependents": dependent_allocation(tax_return),
+        "alimony": {
+            "records": alimony["records"],
+            "deductible_paid": float(alimony["deductible_paid"]),
+            "taxable_received": float(alimony["taxable_received"]),
+        },
+        "notes": notes,
+    }
@@ -0,0 +1,174 @@
+"""
+Donation Valuation Guide
+Fair market value ranges for donated household goods and a batch builder for noncash charitable deductions
+"""
+from decimal import Decimal, ROUND_HALF_UP
+from typing import Dict, List, Any, Optional
+
+# Thrift-store fair market value per item in good used condition: category -> item -> (label, low, high)
+DONATION_VALUES: Dict[str, Dict[str, tuple]] = {
+    "clothing": {
+        "blouse": ("Blouse", Decimal("3"), Decimal("12")),
+        "coat": ("Coat", Decimal("15"), Decimal("60")),
+        "dress": ("Dress", Decimal("6"), Decimal("30")),
+        "jacket": ("Jacket", Decimal("8"), Decimal("35")),
+        "jeans": ("Jeans", Decimal("5"), Decimal("20")),
+        "pants": ("Pants or slacks", Decimal("4"), Decimal("18")),
+        "shirt": ("Shirt", Decimal("3"), Decimal("12")),
+        "shoes": ("Shoes (pair)", Decimal("4"), Decimal("25")),
+        "suit": ("Suit", Decimal("15"), Decimal("75")),
+        "sweater": ("Sweater", Decimal("4"), Decimal("15")),
+        "childrens_clothing": ("Children's clothing item", Decimal("2"), Decimal("10")),
+    },
+    "furniture": {
+        "bed": ("Bed (full or queen, with frame)", Decimal("75"), Decimal("300")),
+        "bookcase": ("Bookcase", Decimal("15"), Decimal("75")),
+        "chair": ("Chair", Decimal("10"), Decimal("50")),
+        "coffee_table": ("Coffee table", Decimal("15"), Decimal("75")),
+        "desk": ("Desk", Decimal("25"), Decimal("150")),
+        "dining_set": ("Dining table with chairs", Decimal("75"), Decimal("400")),
+        "dresser": ("Dresser", Decimal("30"), Decimal("150")),
+        "recliner": ("Recliner", Decimal("35"), Decimal("150")),
+        "sofa": ("Sofa", Decimal("50"), Decimal("300")),
+    },
+    "electronics": {
+        "computer": ("Desktop computer", Decimal("50"), Decimal("300")),
+        "laptop": ("Laptop", Decimal("75"), Decimal("400")),
+        "monitor": ("Computer monitor", Decimal("15"), Decimal("75")),
+        "phone": ("Smartphone", Decimal("25"), Decimal("200")),
+        "printer": ("Printer", Decimal("10"), Decimal("50")),
+        "stereo": ("Stereo or speakers", Decimal("15"), Decimal("100")),
+        "tablet": ("Tablet", Decimal("30"), Decimal("150")),
+        "television": ("Television (flat screen)", Decimal("50"), Decimal("300")),
+    },
+}
+
+# Where in the range an item's condition puts it (good used condition or better is required)
+CONDITIONS = {"good": Decimal("0"), "very_good": Decimal("0.5"), "like_new": Decimal("1")}
+
+# Noncash gifts over this need Form 8283 (Section A)
+FORM_8283_THRESHOLD = Decimal("500")
+# Similar items over this need a qualified appraisal and Form 8283 Section B
+APPRAISAL_THRESHOLD = Decimal("5000")
+
+
+def _cents(value: Decimal) -> Decimal:
+    return value.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+
+
+def valuation_guide(category: Optional[str] = None) -> List[Dict[str, Any]]:
+    """
+    The valuation dataset as a list
+
+    Args:
+        category: Only this category (clothing, furniture, electronics)
+
+    Returns:
+        List of {category, item, label, low, high}
+
+    Raises:
+        ValueError: If the category is unknown
+    """
+    if category is not None and category not in DONATION_VALUES:
+        raise ValueError(f"Invalid donation category: {category}. Must be one of: {', '.join(DONATION_VALUES)}")
+    return [
+        {"category": name, "item": item, "label": label, "low": float(low), "high": float(high)}
+        for name, items in DONATION_VALUES.items()
+        if category in (None, name)
+        for item, (label, low, high) in items.items()
+    ]
+
+
+def _find_item(item: str) -> tuple:
+    for category, items in DONATION_VALUES.items():
+        if item in items:
+            return (category, *items[item])
+    raise ValueError(f"Unknown donation item: {item}")
+
+
+def build_donation_batch(items: List[Dict[str, Any]]) -> Dict[str, Any]:
+    """
+    Value and total a batch of donated items
+
+    Each item is {item, quantity, condition} with an optional value that
+    overrides the guide (per unit, e.g. from an appraisal or for items the
+    guide doesn't list, which then need a description).
+
+    Args:
+        items: Donated items
+
+    Returns:
+        Dict with the valued items, total, whether Form 8283 and an appraisal
+        are required, and warnings
+
+    Raises:
+        ValueError: If the batch is empty or an item, quantity, condition, or value is invalid
+    """
+    if not items:
+        raise ValueError("A donation batch needs at least one item")
+
+    valued: List[Dict[str, Any]] = []
+    category_totals: Dict[str, Decimal] = {}
+    warnings: List[str] = []
+    for entry in items:
+        quantity = int(entry.get("quantity", 1))
+        if quantity < 1:
+            raise ValueError("Item quantity must be at least 1")
+        condition = entry.get("condition") or "good"
+        if condition not in CONDITIONS:
+            raise ValueError(f"Invalid item condition: {condition}. Must be one of: {', '.join(CONDITIONS)}")
+
+        override = entry.get("value")
+        if entry.get("item"):
+            category, label, low, high = _find_item(entry["item"])
+            guide_value = low + (high - low) * CONDITIONS[condition]
+        elif override is not None and entry.get("description"):
+            category, label, low, high, guide_value = "other", entry["description"], None, None, None
+        else:
+            raise ValueError("Each item needs a guide item, or a description and value")
+
+        if override is not None:
+            unit_value = Decimal(str(override))
+            if unit_value < 0:
+                raise ValueError("Item value cannot be negative")
+            if high is not None and unit_value > high:
+                warnings.append(f"{label} valued above the guide's ${high:,.2f} high end; keep support for the value")
+        else:
+            unit_value = guide_value
+        unit_value = _cents(unit_value)
+        amount = unit_value * quantity
+        category_totals[category] = category_totals.get(category, Decimal("0")) + amount
+        valued.append({
+            "item": entry.get("item"),
+            "category": category,
+            "description": entry.get("description") or label,
+            "quantity": quantity,
+            "condition": condition,
+            "low": float(low) if low is not None else None,
+            "high": float(high) if high is not None else None,
+            "unit_value": float(unit_value),
+            "amount": float(amount),
+        })
+
+    total = sum(category_totals.values(), Decimal("0"))
+    form_8283 = total > FORM_8283_THRESHOLD
+    appraisal = [category for category, amount in category_totals.items() if amount > APPRAISAL_THRESHOLD]
+    if form_8283:
+        warnings.append(
+            f"Noncash gifts over ${FORM_8283_THRESHOLD:,.0f} require Form 8283 Section A; "
+            "record the donee, donation date, and how each item was acquired"
+        )
+    for category in appraisal:
+        warnings.append(
+            f"Donated {category} worth over ${APPRAISAL_THRESHOLD:,.0f} needs a qualified appraisal "
+            "and Form 8283 Section B"
+        )
+
+    return {
+        "items": valued,
+        "category_totals": {category: float(amount) for category, amount in category_totals.items()},
+        "total": float(total),
+        "form_8283_required": form_8283,
+        "appraisal_required": bool(appraisal),
+        "warnings": warnings,
+    }
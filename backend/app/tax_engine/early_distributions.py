@@ -0,0 +1,306 @@
+"""
+Early Distributions
+Form 5329 Part I: the additional tax on early retirement distributions, its exceptions, and checking a claimed one
+"""
+from decimal import Decimal, ROUND_HALF_UP
+from typing import Dict, List, Any, Optional
+
+# Additional tax on the part of an early distribution no exception covers
+ADDITIONAL_TAX_RATE = Decimal("0.10")
+
+# A SIMPLE IRA distribution in the first two years of participation (code S) owes 15% more
+SIMPLE_IRA_EXTRA_RATE = Decimal("0.15")
+
+# 1099-R box 7 codes
+DISTRIBUTION_CODES = {
+    "1": "Early distribution, no known exception",
+    "2": "Early distribution, exception applies",
+    "3": "Disability",
+    "4": "Death",
+    "7": "Normal distribution",
+    "G": "Direct rollover",
+    "J": "Early distribution from a Roth IRA",
+    "Q": "Qualified distribution from a Roth IRA",
+    "S": "Early distribution from a SIMPLE IRA in the first 2 years, no known exception",
+    "T": "Roth IRA distribution, exception applies",
+}
+
+# Codes whose distributions go on Form 5329 line 1
+EARLY_DISTRIBUTION_CODES = ["1", "J", "S"]
+
+PLAN_TYPES = ["ira", "employer_plan"]
+
+# Form 5329 line 2 exception numbers: (description, plan types it covers)
+EXCEPTION_CODES = {
+    "01": ("Separation from service in or after the year you turned 55 (50 for public safety employees)",
+           ["employer_plan"]),
+    "02": ("Series of substantially equal periodic payments", ["ira", "employer_plan"]),
+    "03": ("Total and permanent disability", ["ira", "employer_plan"]),
+    "04": ("Death of the account owner", ["ira", "employer_plan"]),
+    "05": ("Unreimbursed medical expenses over 7.5% of AGI", ["ira", "employer_plan"]),
+    "06": ("Payment to an alternate payee under a qualified domestic relations order", ["employer_plan"]),
+    "07": ("Health insurance premiums while unemployed", ["ira"]),
+    "08": ("Qualified higher education expenses", ["ira"]),
+    "09": ("First home purchase, up to $10,000", ["ira"]),
+    "10": ("IRS levy on the plan", ["ira", "employer_plan"]),
+    "11": ("Qualified reservist distribution", ["ira", "employer_plan"]),
+    "12": ("Other (see the Form 5329 instructions)", ["ira", "employer_plan"]),
+    "13": ("Qualified birth or adoption distribution, up to $5,000 per child", ["ira", "employer_plan"]),
+}
+
+# Line 2 number when more than one exception applies
+MULTIPLE_EXCEPTIONS_CODE = "99"
+
+SEPARATION_AGE = 55
+PUBLIC_SAFETY_SEPARATION_AGE = 50
+FIRST_HOME_LIMIT = Decimal("10000")
+BIRTH_OR_ADOPTION_LIMIT = Decimal("5000")
+MEDICAL_FLOOR_RATE = Decimal("0.075")
+UNEMPLOYMENT_WEEKS = 12
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def _cents(value: Decimal) -> Decimal:
+    return value.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+
+
+def _rate(distribution_code: str) -> Decimal:
+    return ADDITIONAL_TAX_RATE + (SIMPLE_IRA_EXTRA_RATE if distribution_code == "S" else Decimal("0"))
+
+
+def early_distributions(tax_return: Dict[str, Any]) -> List[Dict[str, Any]]:
+    """Retirement income sources coded as early distributions (1099-R box 7 code 1, J, or S)"""
+    return [
+        s for s in tax_return.get("income_sources", [])
+        if s["type"] == "retirement" and s.get("distribution_code") in EARLY_DISTRIBUTION_CODES
+    ]
+
+
+def calculate_form_5329(tax_return: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    Form 5329 Part I for a stored return
+
+    Retirement sources with 1099-R code 1, J, or S are early distributions
+    (line 1). A source's exception_code and exception_amount (all of it if
+    the amount is left out) are the exception claimed on line 2, and the
+    rest owes the 10% additional tax, 25% for a SIMPLE IRA in its first two
+    years. Line 4 goes on Schedule 2, line 8. Codes 2, 3, and 4 mean the
+    payer already knows an exception applies, so they aren't included.
+
+    Args:
+        tax_return: Return dict from ReturnStore
+
+    Returns:
+        Dict with required, lines (1-4), exception_code (line 2's number),
+        distributions (per source: amount, code, exception claimed, amount
+        subject to the tax, additional_tax), and notes
+    """
+    distributions = []
+    codes = set()
+    notes: List[str] = []
+    for source in early_distributions(tax_return):
+        amount = _amount(source.get("amount"))
+        exception_code = source.get("exception_code")
+        excepted = Decimal("0")
+        if exception_code:
+            codes.add(exception_code)
+            claimed = source.get("exception_amount")
+            excepted = min(amount, amount if claimed is None else _amount(claimed))
+        subject = amount - excepted
+        rate = _rate(source["distribution_code"])
+        distributions.append({
+            "source_id": source.get("id"),
+            "payer": source.get("description") or "",
+            "distribution_code": source["distribution_code"],
+            "amount": float(amount),
+            "exception_code": exception_code,
+            "excepted": float(excepted),
+            "subject": float(subject),
+            "rate": float(rate),
+            "additional_tax": float(_cents(subject * rate)),
+        })
+        if source["distribution_code"] == "J":
+            notes.append(
+                f"{source.get('description') or 'A Roth IRA distribution'}: only earnings are subject to the tax; "
+                "contributions come out first and tax-free (Form 8606 Part III), so lower the amount if some was basis"
+            )
+
+    line_1 = sum((_amount(d["amount"]) for d in distributions), Decimal("0"))
+    line_2 = sum((_amount(d["excepted"]) for d in distributions), Decimal("0"))
+    line_4 = sum((_amount(d["additional_tax"]) for d in distributions), Decimal("0"))
+    exception_code = None
+    if len(codes) == 1:
+        exception_code = codes.pop()
+    elif codes:
+        exception_code = MULTIPLE_EXCEPTIONS_CODE
+
+    if any(d["exception_code"] for d in distributions):
+        notes.append("Keep records showing the exception applies; the IRS can ask for them")
+    return {
+        "tax_year": tax_return["tax_year"],
+        "required": bool(distributions),
+        "lines": {
+            "1": float(line_1),
+            "2": float(line_2),
+            "3": float(line_1 - line_2),
+            "4": float(line_4),
+        },
+        "exception_code": exception_code,
+        "distributions": distributions,
+        "notes": notes,
+    }
+
+
+def evaluate_exception(
+    tax_return: Dict[str, Any],
+    source_id: str,
+    agi: Decimal,
+    exception_code: Optional[str] = None,
+    age_at_separation: Optional[int] = None,
+    public_safety_employee: bool = False,
+    weeks_unemployed: Optional[int] = None,
+    health_insurance_premiums: Optional[Decimal] = None,
+    education_expenses: Optional[Decimal] = None,
+    first_home_used_before: Decimal = Decimal("0"),
+    children: int = 1,
+) -> Dict[str, Any]:
+    """
+    Check whether an exception to the early distribution tax applies
+
+    The exception is checked against the plan type (several only cover
+    IRAs or only employer plans), the facts given, and the return: the
+    medical exception is limited to the return's medical expenses over 7.5%
+    of AGI. Exceptions that depend on documents the app doesn't keep
+    (disability, a QDRO, a levy, ...) come back as needs_review.
+
+    Args:
+        tax_return: Return dict from ReturnStore
+        source_id: The retirement income source
+        agi: The return's AGI
+        exception_code: Exception to check (defaults to the one claimed on the source)
+        age_at_separation: Age in the year the taxpayer left the employer (01)
+        public_safety_employee: Qualified public safety employee of a government plan (01)
+        weeks_unemployed: Consecutive weeks of unemployment compensation (07)
+        health_insurance_premiums: Premiums paid for the year (07)
+        education_expenses: Qualified higher education expenses (08)
+        first_home_used_before: First home exception used in earlier years (09)
+        children: Births or adoptions the distribution is for (13)
+
+    Returns:
+        Dict with source_id, exception_code, description, status (applies,
+        partial, does_not_apply, or needs_review), amount, claimed, allowed,
+        additional_tax (after the allowed amount), and reasons
+
+    Raises:
+        KeyError: If the source isn't a retirement income source
+        ValueError: If there's no exception to check or the code is unknown
+    """
+    source = next(
+        (s for s in tax_return.get("income_sources", []) if s.get("id") == source_id and s["type"] == "retirement"),
+        None,
+    )
+    if source is None:
+        raise KeyError(f"Retirement distribution not found: {source_id}")
+    exception_code = exception_code or source.get("exception_code")
+    if not exception_code:
+        raise ValueError("No exception is claimed for this distribution; give the exception to check")
+    if exception_code not in EXCEPTION_CODES:
+        raise ValueError(f"Unknown exception: {exception_code}. Must be one of: {', '.join(EXCEPTION_CODES)}")
+
+    description, plan_types = EXCEPTION_CODES[exception_code]
+    amount = _amount(source.get("amount"))
+    claimed = amount
+    if exception_code == source.get("exception_code") and source.get("exception_amount") is not None:
+        claimed = min(amount, _amount(source["exception_amount"]))
+    plan_type = source.get("plan_type")
+    reasons: List[str] = []
+    status = "applies"
+    limit: Optional[Decimal] = None
+
+    if source.get("distribution_code") not in EARLY_DISTRIBUTION_CODES:
+        reasons.append(
+            f"1099-R code {source.get('distribution_code') or '(none)'} isn't an early distribution without a "
+            "known exception, so no additional tax is figured"
+        )
+    if plan_type is None:
+        status = "needs_review"
+        reasons.append("Record whether the distribution came from an IRA or an employer plan")
+    elif plan_type not in plan_types:
+        status = "does_not_apply"
+        kind = "an employer plan" if plan_type == "employer_plan" else "an IRA"
+        reasons.append(f"Exception {exception_code} doesn't cover distributions from {kind}")
+
+    if status != "does_not_apply":
+        if exception_code == "01":
+            required_age = PUBLIC_SAFETY_SEPARATION_AGE if public_safety_employee else SEPARATION_AGE
+            if age_at_separation is None:
+                status = "needs_review"
+                reasons.append("Give the age in the year employment ended")
+            elif age_at_separation < required_age:
+                status = "does_not_apply"
+                reasons.append(
+                    f"Left the employer in the year of turning {age_at_separation}; the rule needs {required_age}"
+                )
+        elif exception_code == "05":
+            medical = sum(
+                (_amount(d.get("amount")) for d in tax_return.get("deductions", []) if d["category"] == "medical"),
+                Decimal("0"),
+            )
+            limit = max(Decimal("0"), medical - _cents(max(agi, Decimal("0")) * MEDICAL_FLOOR_RATE))
+            reasons.append(
+                f"Medical expenses of ${medical:,.2f} less 7.5% of AGI allow up to ${limit:,.2f}; the medical "
+                "expenses don't have to be itemized"
+            )
+        elif exception_code == "07":
+            if weeks_unemployed is None or health_insurance_premiums is None:
+                status = "needs_review"
+                reasons.append("Give the weeks of unemployment compensation and the health insurance premiums paid")
+            elif weeks_unemployed < UNEMPLOYMENT_WEEKS:
+                status = "does_not_apply"
+                reasons.append(f"Unemployment compensation must last {UNEMPLOYMENT_WEEKS} consecutive weeks")
+            else:
+                limit = health_insurance_premiums
+        elif exception_code == "08":
+            if education_expenses is None:
+                status = "needs_review"
+                reasons.append("Give the qualified higher education expenses paid this year")
+            else:
+                limit = education_expenses
+        elif exception_code == "09":
+            limit = max(Decimal("0"), FIRST_HOME_LIMIT - first_home_used_before)
+            reasons.append(
+                f"The first home exception is ${FIRST_HOME_LIMIT:,.0f} over a lifetime; ${limit:,.2f} is left"
+            )
+        elif exception_code == "13":
+            limit = BIRTH_OR_ADOPTION_LIMIT * children
+            reasons.append(
+                f"Up to ${BIRTH_OR_ADOPTION_LIMIT:,.0f} per child, taken within a year of the birth or adoption"
+            )
+        else:
+            status = "needs_review"
+            reasons.append(f"{description}: keep the documents that show it applies")
+
+    allowed = claimed if status in ("applies", "needs_review") else Decimal("0")
+    if limit is not None and status == "applies":
+        allowed = min(claimed, limit)
+        if allowed < claimed:
+            status = "partial" if allowed > 0 else "does_not_apply"
+    additional_tax = Decimal("0")
+    if source.get("distribution_code") in EARLY_DISTRIBUTION_CODES:
+        additional_tax = _cents((amount - allowed) * _rate(source["distribution_code"]))
+
+    return {
+        "source_id": source_id,
+        "exception_code": exception_code,
+        "description": description,
+        "plan_type": plan_type,
+        "status": status,
+        "amount": float(amount),
+        "claimed": float(claimed),
+        "allowed": float(allowed),
+        "additional_tax": float(additional_tax),
+        "reasons": reasons,
+    }
@@ -0,0 +1,168 @@
+"""
+Education Credits
+American opportunity and lifetime learning credits (Form 8863) from each student's qualified expenses net of scholarships
+"""
+from decimal import Decimal, ROUND_HALF_UP
+from typing import Dict, List, Any
+
+# Kinds of education record; scholarships and refunds reduce the qualified expenses
+EDUCATION_EXPENSE_KINDS = ["tuition", "fees", "course_materials", "scholarship", "refund"]
+REDUCTION_KINDS = ["scholarship", "refund"]
+
+# American opportunity credit: 100% of the first $2,000 and 25% of the next $2,000 per student, 40% refundable
+AOTC_FULL_EXPENSES = Decimal("2000")
+AOTC_MAX_EXPENSES = Decimal("4000")
+AOTC_REFUNDABLE_SHARE = Decimal("0.40")
+AOTC_MAX_YEARS = 4
+
+# Lifetime learning credit: 20% of up to $10,000 per return
+LLC_RATE = Decimal("0.20")
+LLC_MAX_EXPENSES = Decimal("10000")
+
+# Modified AGI phase-out range, the same for both credits since 2023
+PHASEOUT_RANGES = {
+    2024: {"married_joint": (Decimal("160000"), Decimal("180000")), "default": (Decimal("80000"), Decimal("90000"))},
+}
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def _cents(value: Decimal) -> Decimal:
+    return value.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+
+
+def qualified_expenses(student: Dict[str, Any]) -> Dict[str, Decimal]:
+    """
+    A student's qualified expenses for each credit, net of tax-free assistance
+
+    Course materials count for the American opportunity credit wherever they
+    were bought, but for the lifetime learning credit only when they had to
+    be paid to the school (paid_to_institution).
+
+    Returns:
+        Dict with paid (all expenses), reductions (scholarships and refunds),
+        aotc, and llc
+    """
+    paid = llc_paid = reductions = Decimal("0")
+    for expense in student.get("expenses", []):
+        amount = _amount(expense.get("amount"))
+        if expense["kind"] in REDUCTION_KINDS:
+            reductions += amount
+            continue
+        paid += amount
+        if expense["kind"] != "course_materials" or expense.get("paid_to_institution"):
+            llc_paid += amount
+    return {
+        "paid": paid,
+        "reductions": reductions,
+        "aotc": max(paid - reductions, Decimal("0")),
+        "llc": max(llc_paid - reductions, Decimal("0")),
+    }
+
+
+def aotc_ineligibility(student: Dict[str, Any]) -> List[str]:
+    """Reasons a student can't take the American opportunity credit (empty when eligible)"""
+    reasons = []
+    if student.get("graduate"):
+        reasons.append("graduate student")
+    if not student.get("half_time", True):
+        reasons.append("enrolled less than half time")
+    if int(student.get("aotc_years_claimed") or 0) >= AOTC_MAX_YEARS:
+        reasons.append(f"already claimed for {AOTC_MAX_YEARS} years")
+    if student.get("drug_felony"):
+        reasons.append("felony drug conviction")
+    return reasons
+
+
+def calculate_education_credits(
+    students: List[Dict[str, Any]],
+    magi: Decimal,
+    filing_status: str,
+    tax_year: int,
+) -> Dict[str, Any]:
+    """
+    Education credits for a return's students
+
+    Each student eligible for the American opportunity credit takes it (it's
+    worth more than the lifetime learning credit on the same expenses); the
+    rest share the lifetime learning credit's $10,000 expense limit. Both
+    credits phase out over the same modified AGI range, and married filing
+    separately can't take either.
+
+    Args:
+        students: Student records from ReturnStore
+        magi: Modified AGI (AGI on a return without foreign income exclusions)
+        filing_status: Filing status value
+        tax_year: Year being filed
+
+    Returns:
+        Dict with students (per-student credit and why; lifetime learning
+        amounts are before the per-return limit), aotc, llc,
+        phaseout_percent, nonrefundable, refundable, total, and notes
+
+    Raises:
+        ValueError: If the year isn't supported
+    """
+    if tax_year not in PHASEOUT_RANGES:
+        raise ValueError(f"Education credits aren't available for {tax_year}")
+    notes: List[str] = []
+
+    rows = []
+    aotc = llc_expenses = Decimal("0")
+    for student in students:
+        expenses = qualified_expenses(student)
+        ineligible = aotc_ineligibility(student)
+        row = {
+            "student_id": student.get("id"),
+            "name": student.get("name", ""),
+            "expenses_paid": float(expenses["paid"]),
+            "scholarships_and_refunds": float(expenses["reductions"]),
+            "credit": None,
+            "qualified_expenses": 0.0,
+            "tentative_credit": 0.0,
+            "aotc_ineligible_reasons": ineligible,
+        }
+        if not ineligible and expenses["aotc"]:
+            qualified = min(expenses["aotc"], AOTC_MAX_EXPENSES)
+            tentative = min(qualified, AOTC_FULL_EXPENSES) + max(qualified - AOTC_FULL_EXPENSES, Decimal("0")) / 4
+            aotc += tentative
+            row.update(credit="aotc", qualified_expenses=float(qualified), tentative_credit=float(_cents(tentative)))
+        elif expenses["llc"]:
+            llc_expenses += expenses["llc"]
+            row.update(
+                credit="llc",
+                qualified_expenses=float(expenses["llc"]),
+                tentative_credit=float(_cents(expenses["llc"] * LLC_RATE)),
+            )
+        rows.append(row)
+
+    llc = min(llc_expenses, LLC_MAX_EXPENSES) * LLC_RATE
+    if llc_expenses > LLC_MAX_EXPENSES:
+        notes.append(f"Lifetime learning expenses are limited to ${LLC_MAX_EXPENSES:,.0f} per return")
+
+    low, high = PHASEOUT_RANGES[tax_year].get(filing_status, PHASEOUT_RANGES[tax_year]["default"])
+    if filing_status == "married_separate":
+        allowed_share = Decimal("0")
+        if aotc or llc:
+            notes.append("Education credits can't be taken when married filing separately")
+    else:
+        allowed_share = min(max((high - magi) / (high - low), Decimal("0")), Decimal("1"))
+    aotc = _cents(aotc * allowed_share)
+    llc = _cents(llc * allowed_share)
+    refundable = _cents(aotc * AOTC_REFUNDABLE_SHARE)
+    if Decimal("0") < allowed_share < 1:
+        notes.append(f"Modified AGI of ${magi:,.2f} is in the ${low:,.0f}-${high:,.0f} phase-out range")
+
+    return {
+        "tax_year": tax_year,
+        "students": rows,
+        "aotc": float(aotc),
+        "llc": float(llc),
+        "phaseout_percent": float(_cents((1 - allowed_share) * 100)),
+        "nonrefundable": float(aotc - refundable + llc),
+        "refundable": float(refundable),
+        "total": float(aotc + llc),
+        "notes": notes,
+    }
@@ -0,0 +1,160 @@
+"""
+Filing Status Comparison
+Splits a joint return into two married-filing-separately returns and compares the combined tax
+"""
+import copy
+from decimal import Decimal, ROUND_HALF_UP
+from typing import Dict, List, Any
+
+from app.tax_engine.return_calculation import calculate_return
+
+SPOUSES = ("taxpayer", "spouse")
+
+# Amount fields halved when a record is owned jointly
+SPLIT_FIELDS = {
+    "income_sources": ["amount", "withholding", "social_security_wages", "state_wages", "state_withholding"],
+    "deductions": ["amount"],
+    "capital_transactions": ["proceeds", "cost_basis", "gain_or_loss", "wash_sale_disallowed"],
+    "alimony": ["amount"],
+    "rental_properties": [
+        "building_basis", "land_value", "rents", "expenses", "sale_price", "selling_expenses", "prior_depreciation",
+        "at_risk_amount", "at_risk_carryforward",
+    ],
+}
+
+# Figures reported for each return in the comparison
+SUMMARY_FIELDS = [
+    "agi", "deduction_type", "deduction_amount", "taxable_income", "income_tax",
+    "self_employment_tax", "total_tax", "total_withholding", "refund_or_owed",
+]
+
+
+def _half(value: Any) -> float:
+    return float((Decimal(str(value)) / 2).quantize(Decimal("0.01"), rounding=ROUND_HALF_UP))
+
+
+def split_joint_return(tax_return: Dict[str, Any]) -> Dict[str, Dict[str, Any]]:
+    """
+    Split a joint return into the two spouses' married-separate returns
+
+    Income sources, deductions, capital transactions, alimony, and rental
+    properties go to their owner
+    (taxpayer when unset); jointly owned ones, and carryforwards, are split
+    evenly. Businesses and the income linked to them follow the business
+    owner, and dependents are claimed by the taxpayer unless their owner is
+    the spouse.
+
+    Args:
+        tax_return: Married-joint return dict from ReturnStore
+
+    Returns:
+        Dict with the taxpayer's and spouse's returns
+
+    Raises:
+        ValueError: If the return isn't married filing jointly
+    """
+    if tax_return["filing_status"] != "married_joint":
+        raise ValueError("Only married filing jointly returns can be split into separate returns")
+
+    taxpayer = tax_return.get("taxpayer") or {}
+    business_owners = {b["id"]: b.get("owner", "taxpayer") for b in tax_return.get("businesses", [])}
+    returns: Dict[str, Dict[str, Any]] = {}
+    for spouse in SPOUSES:
+        separate = copy.deepcopy(tax_return)
+        separate["filing_status"] = "married_separate"
+        separate["state_returns"] = []
+        if spouse == "spouse":
+            separate["taxpayer"] = {
+                **taxpayer,
+                "name": taxpayer.get("spouse_name", ""),
+                "ssn": taxpayer.get("spouse_ssn", ""),
+                "spouse_name": taxpayer.get("name", ""),
+                "spouse_ssn": taxpayer.get("ssn", ""),
+            }
+
+        for collection, fields in SPLIT_FIELDS.items():
+            records: List[Dict[str, Any]] = []
+            for record in tax_return.get(collection, []):
+                owner = business_owners.get(record.get("business_id"), record.get("owner", "taxpayer"))
+                if owner == "joint":
+                    record = dict(record)
+                    for field in fields:
+                        if record.get(field) is not None:
+                            record[field] = _half(record[field])
+                elif owner != spouse:
+                    continue
+                records.append(dict(record, owner="taxpayer"))
+            separate[collection] = records
+
+        # Each separate return is the spouse's own, so their records become "taxpayer"
+        separate["businesses"] = [
+            dict(b, owner="taxpayer") for b in tax_return.get("businesses", []) if b.get("owner", "taxpayer") == spouse
+        ]
+        separate["dependents"] = [
+            d for d in tax_return.get("dependents", []) if d.get("owner", "taxpayer") == spouse
+        ]
+        separate["carryforwards"] = {
+            name: _half(amount) for name, amount in (tax_return.get("carryforwards") or {}).items() if amount
+        }
+        returns[spouse] = separate
+    return returns
+
+
+def compare_filing_separately(tax_return: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    What-if: file a joint return as two married-separate returns
+
+    When one spouse itemizes on a separate return the other must too, so that
+    spouse is recalculated with itemized deductions.
+
+    Args:
+        tax_return: Married-joint return dict from ReturnStore
+
+    Returns:
+        Dict with joint and per-spouse separate figures, the combined separate
+        tax, difference (combined separate minus joint; positive means filing
+        jointly saves that much), recommended filing status, and notes
+
+    Raises:
+        ValueError: If the return isn't joint or can't be calculated
+    """
+    joint = calculate_return(tax_return)
+    separate_returns = split_joint_return(tax_return)
+    separate = {spouse: calculate_return(separate_returns[spouse]) for spouse in SPOUSES}
+    notes: List[str] = []
+
+    itemizers = [spouse for spouse in SPOUSES if separate[spouse]["deduction_type"] == "Itemized"]
+    if len(itemizers) == 1:
+        other = "spouse" if itemizers[0] == "taxpayer" else "taxpayer"
+        separate[other] = calculate_return(separate_returns[other], require_itemized=True)
+        notes.append(f"The {itemizers[0]} itemizes, so the {other} must itemize too when filing separately")
+
+    combined_tax = sum((Decimal(str(separate[spouse]["total_tax"])) for spouse in SPOUSES), Decimal("0"))
+    joint_tax = Decimal(str(joint["total_tax"]))
+    difference = combined_tax - joint_tax
+    owners = {record.get("owner") for key in [*SPLIT_FIELDS, "businesses"] for record in tax_return.get(key, [])}
+    if not owners & {"spouse", "joint"}:
+        notes.append("No income or deductions are attributed to the spouse; everything was assigned to the taxpayer")
+    notes.append(
+        "Filing separately also rules out the earned income and child care credits and the student loan "
+        "interest deduction, which aren't calculated here; the education and adoption credits are already "
+        "left off the separate returns"
+    )
+
+    return {
+        "joint": _summary(joint),
+        "separate": {spouse: _summary(separate[spouse]) for spouse in SPOUSES},
+        "joint_tax": float(joint_tax),
+        "combined_separate_tax": float(combined_tax),
+        "difference": float(difference),
+        "recommended_filing_status": "married_separate" if difference < 0 else "married_joint",
+        "notes": notes,
+    }
+
+
+def _summary(calculation: Dict[str, Any]) -> Dict[str, Any]:
+    """Headline figures of a calculate_return() result"""
+    summary = {field: calculation[field] for field in SUMMARY_FIELDS}
+    summary["credits_applied"] = calculation["credits"]["applied"]
+    return summary
+
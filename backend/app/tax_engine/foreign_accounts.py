@@ -0,0 +1,104 @@
+"""
+Foreign Account Reporting
+FBAR (FinCEN 114) and Form 8938 filing thresholds checked against a return's foreign accounts
+"""
+from decimal import Decimal
+from typing import Dict, List, Any, Tuple
+
+# An FBAR is required when the combined highest balances of a person's foreign accounts exceed this
+FBAR_THRESHOLD = Decimal("10000")
+
+# Form 8938 thresholds (year-end value, highest value during the year)
+FORM_8938_THRESHOLDS = {
+    ("us", "single"): (Decimal("50000"), Decimal("75000")),
+    ("us", "married_joint"): (Decimal("100000"), Decimal("150000")),
+    ("abroad", "single"): (Decimal("200000"), Decimal("300000")),
+    ("abroad", "married_joint"): (Decimal("400000"), Decimal("600000")),
+}
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def _form_8938_thresholds(filing_status: str, lives_abroad: bool) -> Tuple[Decimal, Decimal]:
+    """Joint filers use the married thresholds; everyone else (including MFS) the single ones"""
+    schedule = "married_joint" if filing_status == "married_joint" else "single"
+    return FORM_8938_THRESHOLDS[("abroad" if lives_abroad else "us", schedule)]
+
+
+def check_foreign_accounts(
+    accounts: List[Dict[str, Any]],
+    filing_status: str,
+    lives_abroad: bool = False,
+) -> Dict[str, Any]:
+    """
+    Which foreign account disclosures a return needs
+
+    The FBAR test is per person, so a joint account counts toward each
+    spouse. Form 8938 looks at the accounts on the return together.
+    Balances are in U.S. dollars (convert at the Treasury's year-end rate).
+
+    Args:
+        accounts: Foreign account records (max_balance, year_end_balance, owner)
+        filing_status: Federal filing status
+        lives_abroad: Whether the taxpayer meets the bona fide residence or presence abroad test
+
+    Returns:
+        Dict with fbar (required, per-person totals) and form_8938 (required,
+        thresholds, totals) results and warnings
+    """
+    owners = ["taxpayer", "spouse"] if filing_status == "married_joint" or any(
+        a.get("owner") == "spouse" for a in accounts
+    ) else ["taxpayer"]
+    fbar_people = []
+    for person in owners:
+        held = [a for a in accounts if a.get("owner", "taxpayer") in (person, "joint")]
+        total = sum((_amount(a.get("max_balance")) for a in held), Decimal("0"))
+        fbar_people.append({
+            "person": person,
+            "accounts": len(held),
+            "aggregate_max_balance": float(total),
+            "required": total > FBAR_THRESHOLD,
+        })
+    fbar_required = any(p["required"] for p in fbar_people)
+
+    year_end_limit, max_limit = _form_8938_thresholds(filing_status, lives_abroad)
+    year_end = sum(
+        (_amount(a["year_end_balance"] if a.get("year_end_balance") is not None else a.get("max_balance"))
+         for a in accounts),
+        Decimal("0"),
+    )
+    highest = sum((_amount(a.get("max_balance")) for a in accounts), Decimal("0"))
+    form_8938_required = year_end > year_end_limit or highest > max_limit
+
+    warnings = []
+    if fbar_required:
+        warnings.append(
+            "File FinCEN Form 114 (FBAR) electronically with FinCEN by April 15 (automatically extended to "
+            "October 15); it isn't part of the tax return"
+        )
+    if form_8938_required:
+        warnings.append("Attach Form 8938 to the return; penalties for not filing start at $10,000")
+    if accounts:
+        warnings.append("File Schedule B and answer yes to the foreign account question in Part III")
+    if any(a.get("year_end_balance") is None for a in accounts):
+        warnings.append("Accounts without a year-end balance were counted at their maximum balance for Form 8938")
+
+    return {
+        "account_count": len(accounts),
+        "fbar": {
+            "threshold": float(FBAR_THRESHOLD),
+            "required": fbar_required,
+            "people": fbar_people,
+        },
+        "form_8938": {
+            "lives_abroad": lives_abroad,
+            "year_end_threshold": float(year_end_limit),
+            "max_threshold": float(max_limit),
+            "year_end_total": float(year_end),
+            "max_total": float(highest),
+            "required": form_8938_required,
+        },
+        "warnings": warnings,
+    }
@@ -0,0 +1,127 @@
+"""
+Hobby or Business
+The nine-factor test for whether an activity is engaged in for profit (Treas. Reg. 1.183-2(b)) and the profit presumption
+"""
+from typing import Dict, List, Any, Optional
+
+# (factor, title, question); each question is worded so "yes" points to a business
+HOBBY_FACTORS = [
+    ("manner", "Manner in which the activity is carried on",
+     "Do you run it like a business: separate accounts, complete books, and changing methods when it loses money?"),
+    ("expertise", "Expertise of the taxpayer or advisors",
+     "Have you studied the activity's business practices or consulted experts in it?"),
+    ("time_and_effort", "Time and effort expended",
+     "Do you spend substantial time on it, or employ competent people to run it?"),
+    ("asset_appreciation", "Expectation that assets may appreciate",
+     "Do you expect the land, equipment, or other assets used in it to rise in value?"),
+    ("other_activities", "Success in similar or dissimilar activities",
+     "Have you turned other activities from unprofitable into profitable ones before?"),
+    ("income_history", "History of income or losses",
+     "Are the losses explained by the startup phase or by events outside your control (weather, disease, a slump)?"),
+    ("occasional_profits", "Amount of occasional profits",
+     "Does it make occasional profits, or is there a real chance of a substantial profit on what you've put in?"),
+    ("financial_status", "Financial status of the taxpayer",
+     "Do you depend on the activity for income, rather than using its losses against substantial other income?"),
+    ("personal_pleasure", "Elements of personal pleasure or recreation",
+     "Would you keep at it even if you didn't enjoy it, because it's the way you earn money?"),
+]
+
+HOBBY_ANSWERS = ["yes", "no", "unsure"]
+
+# Profit presumption (IRC 183(d)): profit in 3 of 5 years, or 2 of 7 for breeding, training, showing, or racing horses
+PRESUMPTION_YEARS = {False: (3, 5), True: (2, 7)}
+
+# How many of the nine factors must point one way for a strong recommendation
+STRONG_FACTOR_COUNT = 6
+
+
+def evaluate_hobby_factors(
+    answers: Dict[str, str],
+    notes: Optional[Dict[str, str]] = None,
+    profit_years: Optional[int] = None,
+    horse_activity: bool = False,
+) -> Dict[str, Any]:
+    """
+    Weigh the nine factors into a Schedule C or hobby recommendation
+
+    No single factor decides it, so the recommendation counts the factors
+    pointing each way: six or more one way is strong, anything closer is
+    weak, and a tie goes to hobby. Meeting the profit presumption makes it
+    Schedule C regardless, since the IRS then has to show there's no profit
+    motive. Hobby income is reported on Schedule 1 line 8j, and the
+    activity's expenses aren't deductible through 2025.
+
+    Args:
+        answers: Factor name mapped to yes, no, or unsure (missing is unsure)
+        notes: Factor name mapped to the facts behind the answer
+        profit_years: Years with a profit in the presumption period (the last
+            5, or 7 for a horse activity), including this one
+        horse_activity: Breeding, training, showing, or racing horses
+
+    Returns:
+        Dict with recommendation (schedule_c or hobby), strength (strong or
+        weak), favorable, unfavorable, unanswered, presumption_met, and
+        rationale (one line per factor plus the conclusion)
+
+    Raises:
+        ValueError: If a factor or answer isn't recognized
+    """
+    factor_names = [name for name, _, _ in HOBBY_FACTORS]
+    notes = notes or {}
+    for name, answer in answers.items():
+        if name not in factor_names:
+            raise ValueError(f"Unknown factor: {name}. Must be one of: {', '.join(factor_names)}")
+        if answer not in HOBBY_ANSWERS:
+            raise ValueError(f"Invalid answer for {name}: {answer}. Must be one of: {', '.join(HOBBY_ANSWERS)}")
+    for name in notes:
+        if name not in factor_names:
+            raise ValueError(f"Unknown factor: {name}. Must be one of: {', '.join(factor_names)}")
+    needed, period = PRESUMPTION_YEARS[horse_activity]
+    if profit_years is not None and not 0 <= profit_years <= period:
+        raise ValueError(f"profit_years must be between 0 and {period}")
+
+    rationale: List[str] = []
+    favorable = unfavorable = 0
+    for name, title, _ in HOBBY_FACTORS:
+        answer = answers.get(name, "unsure")
+        if answer == "yes":
+            favorable += 1
+            weighs = "points to a business"
+        elif answer == "no":
+            unfavorable += 1
+            weighs = "points to a hobby"
+        else:
+            weighs = "is undetermined"
+        line = f"{title}: {weighs}"
+        if notes.get(name):
+            line += f" ({notes[name]})"
+        rationale.append(line)
+
+    presumption_met = profit_years is not None and profit_years >= needed
+    if presumption_met:
+        recommendation, strength = "schedule_c", "strong"
+        rationale.append(
+            f"Profit in {profit_years} of the last {period} years meets the presumption that the activity is engaged "
+            "in for profit; the IRS would have to show otherwise"
+        )
+    else:
+        recommendation = "schedule_c" if favorable > unfavorable else "hobby"
+        strength = "strong" if max(favorable, unfavorable) >= STRONG_FACTOR_COUNT else "weak"
+        rationale.append(
+            f"{favorable} of the nine factors point to a business and {unfavorable} to a hobby; "
+            + ("report it on Schedule C" if recommendation == "schedule_c" else
+               "report the income on Schedule 1 line 8j, with no deduction for the expenses")
+        )
+    unanswered = 9 - favorable - unfavorable
+    if unanswered >= 3:
+        rationale.append(f"{unanswered} factors are undetermined; answering them would firm up the recommendation")
+
+    return {
+        "recommendation": recommendation,
+        "strength": strength,
+        "favorable": favorable,
+        "unfavorable": unfavorable,
+        "unanswered": unanswered,
+        "presumption_met": presumption_met,
+        "rationale": rationale,
+    }
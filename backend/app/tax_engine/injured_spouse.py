@@ -0,0 +1,208 @@
+"""
+Injured Spouse Allocation
+Form 8379 worksheet: each spouse's share of a joint return's income, tax, payments, and refund
+"""
+from decimal import Decimal, ROUND_HALF_UP
+from typing import Dict, List, Any, Optional
+
+from app.tax_engine.filing_comparison import SPOUSES, split_joint_return
+from app.tax_engine.return_calculation import calculate_return
+
+# States where income earned during the marriage is divided under community property law
+COMMUNITY_PROPERTY_STATES = {"AZ", "CA", "ID", "LA", "NV", "NM", "TX", "WA", "WI"}
+
+# Form 8379 Part III lines reported on the worksheet
+WORKSHEET_LINES = [
+    ("13a", "wages", "Wages"),
+    ("13b", "other_income", "All other income"),
+    ("14", "adjustments", "Adjustments to income"),
+    ("15", "deduction", "Standard deduction or itemized deductions"),
+    ("16", "credits", "Credits (other than refundable credits)"),
+    ("17", "other_taxes", "Other taxes (self-employment tax)"),
+    ("18", "withholding", "Federal income tax withheld"),
+    ("19", "payments", "Refundable credits and other payments"),
+]
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def _cents(value: Decimal) -> Decimal:
+    return value.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+
+
+def _allocate(total: Decimal, weights: Dict[str, Decimal]) -> Dict[str, Decimal]:
+    """
+    Split a joint amount between the spouses in proportion to weights
+
+    Evenly when neither spouse has any weight. The spouse gets whatever
+    rounding leaves over, so the two shares always add up to the total.
+    """
+    weight = sum(weights.values(), Decimal("0"))
+    if weight > 0:
+        taxpayer = _cents(total * weights["taxpayer"] / weight)
+    else:
+        taxpayer = _cents(total / 2)
+    return {"taxpayer": taxpayer, "spouse": total - taxpayer}
+
+
+def injured_spouse_allocation(
+    tax_return: Dict[str, Any],
+    injured_spouse: str = "spouse",
+    past_due_debt: Optional[Decimal] = None,
+) -> Dict[str, Any]:
+    """
+    Allocate a joint return between the spouses the way Form 8379 does
+
+    An injured spouse can get back their share of a joint refund that was
+    (or would be) applied to the other spouse's past-due child support,
+    federal or state debts. Income, adjustments, withholding, and
+    self-employment tax follow their owner (jointly owned records are split
+    evenly, as split_joint_return does); the standard deduction is divided
+    equally and itemized deductions in proportion to what each spouse
+    paid. The joint income tax and credits are shared in proportion to each
+    spouse's separate figures, the way the IRS divides the joint liability,
+    and refundable credits are split evenly.
+
+    Args:
+        tax_return: Married-joint return dict from ReturnStore
+        injured_spouse: The spouse who isn't liable for the debt (taxpayer or spouse)
+        past_due_debt: The other spouse's debt, to show the refund with and without Form 8379
+
+    Returns:
+        Dict with lines (Form 8379 Part III, joint and per-spouse), each
+        spouse's tax, payments and refund share, joint_refund,
+        injured_spouse_refund, offset (when past_due_debt is given), and notes
+
+    Raises:
+        ValueError: If the return isn't married filing jointly, the injured
+            spouse isn't taxpayer or spouse, or the debt is negative
+    """
+    if injured_spouse not in SPOUSES:
+        raise ValueError(f"Injured spouse must be one of: {', '.join(SPOUSES)}")
+    if past_due_debt is not None and past_due_debt < 0:
+        raise ValueError("Past-due debt can't be negative")
+
+    joint = calculate_return(tax_return)
+    separate_returns = split_joint_return(tax_return)
+    separate = {spouse: calculate_return(separate_returns[spouse]) for spouse in SPOUSES}
+    notes: List[str] = []
+
+    def owned(figure) -> Dict[str, Decimal]:
+        return {spouse: _amount(figure(separate[spouse])) for spouse in SPOUSES}
+
+    def other_income(calculation: Dict[str, Any]) -> Decimal:
+        return _amount(calculation["total_income"]) - _amount(calculation["income"]["wages"])
+
+    def itemized(spouse: str) -> Decimal:
+        return sum((_amount(d.get("amount")) for d in separate_returns[spouse].get("deductions", [])), Decimal("0"))
+
+    joint_wages = _amount(joint["income"]["wages"])
+    joint_credits = _amount(joint["income_tax"]) + _amount(joint["self_employment_tax"]) - _amount(joint["total_tax"])
+    joint_payments = _amount(joint["refund_or_owed"]) - _amount(joint["total_withholding"]) + _amount(
+        joint["total_tax"]
+    )
+    if joint["deduction_type"] == "Itemized":
+        deduction_weights = {spouse: itemized(spouse) for spouse in SPOUSES}
+    else:
+        deduction_weights = {spouse: Decimal("1") for spouse in SPOUSES}
+
+    allocated = {
+        "wages": _allocate(joint_wages, owned(lambda c: c["income"]["wages"])),
+        "other_income": _allocate(_amount(joint["total_income"]) - joint_wages, owned(other_income)),
+        "adjustments": _allocate(_amount(joint["adjustments"]), owned(lambda c: c["adjustments"])),
+        "deduction": _allocate(_amount(joint["deduction_amount"]), deduction_weights),
+        "credits": _allocate(joint_credits, owned(lambda c: c["credits"]["total"])),
+        "other_taxes": _allocate(_amount(joint["self_employment_tax"]), owned(lambda c: c["self_employment_tax"])),
+        "withholding": _allocate(_amount(joint["total_withholding"]), owned(lambda c: c["total_withholding"])),
+        "payments": _allocate(joint_payments, {spouse: Decimal("0") for spouse in SPOUSES}),
+    }
+    income_tax = _allocate(_amount(joint["income_tax"]), owned(lambda c: c["income_tax"]))
+    joint_totals = {
+        "wages": joint_wages,
+        "other_income": _amount(joint["total_income"]) - joint_wages,
+        "adjustments": _amount(joint["adjustments"]),
+        "deduction": _amount(joint["deduction_amount"]),
+        "credits": joint_credits,
+        "other_taxes": _amount(joint["self_employment_tax"]),
+        "withholding": _amount(joint["total_withholding"]),
+        "payments": joint_payments,
+    }
+
+    lines = [
+        {
+            "line": line,
+            "key": key,
+            "label": label,
+            "joint": float(joint_totals[key]),
+            **{spouse: float(allocated[key][spouse]) for spouse in SPOUSES},
+        }
+        for line, key, label in WORKSHEET_LINES
+    ]
+
+    shares: Dict[str, Dict[str, float]] = {}
+    refund_shares: Dict[str, Decimal] = {}
+    for spouse in SPOUSES:
+        tax = max(Decimal("0"), income_tax[spouse] - allocated["credits"][spouse]) + allocated["other_taxes"][spouse]
+        payments = allocated["withholding"][spouse] + allocated["payments"][spouse]
+        refund_shares[spouse] = payments - tax
+        shares[spouse] = {
+            "income_tax": float(income_tax[spouse]),
+            "total_tax": float(tax),
+            "total_payments": float(payments),
+            "refund_share": float(refund_shares[spouse]),
+        }
+
+    joint_refund = max(Decimal("0"), _amount(joint["refund_or_owed"]))
+    injured_refund = min(joint_refund, max(Decimal("0"), refund_shares[injured_spouse]))
+    debtor = "spouse" if injured_spouse == "taxpayer" else "taxpayer"
+
+    offset = None
+    if past_due_debt is not None:
+        without_8379 = min(past_due_debt, joint_refund)
+        with_8379 = min(past_due_debt, joint_refund - injured_refund)
+        offset = {
+            "past_due_debt": float(past_due_debt),
+            "offset_without_form_8379": float(without_8379),
+            "refund_without_form_8379": float(joint_refund - without_8379),
+            "offset_with_form_8379": float(with_8379),
+            "refund_with_form_8379": float(joint_refund - with_8379),
+            "protected": float(without_8379 - with_8379),
+        }
+
+    if joint_refund == 0:
+        notes.append("The joint return doesn't have a refund, so there's nothing to offset")
+    elif injured_refund == 0:
+        notes.append(
+            f"The {injured_spouse}'s payments don't exceed their share of the tax; Form 8379 wouldn't recover anything"
+        )
+    state = next(
+        (r["state"] for r in tax_return.get("state_returns", []) if r.get("residency") == "resident"),
+        (tax_return.get("taxpayer") or {}).get("state") or "",
+    ).upper()
+    if state in COMMUNITY_PROPERTY_STATES:
+        notes.append(
+            f"{state} is a community property state; the IRS divides community income under state law, "
+            "which can differ from this allocation by owner"
+        )
+    owners = {record.get("owner") for key in ("income_sources", "businesses") for record in tax_return.get(key, [])}
+    if not owners & {"spouse", "joint"}:
+        notes.append("No income is attributed to the spouse; everything was assigned to the taxpayer")
+    notes.append(
+        f"File Form 8379 with the joint return or on its own after the offset; the {debtor} must be the one who owes "
+        "the debt. Relief from tax the other spouse understated is innocent spouse relief (Form 8857), which "
+        "this worksheet doesn't decide"
+    )
+
+    return {
+        "tax_year": tax_return["tax_year"],
+        "injured_spouse": injured_spouse,
+        "lines": lines,
+        "spouses": shares,
+        "joint_tax": joint["total_tax"],
+        "joint_refund": float(joint_refund),
+        "injured_spouse_refund": float(injured_refund),
+        "offset": offset,
+        "notes": notes,
+    }
@@ -0,0 +1,107 @@
+"""
+Bracket Invariants
+Properties the bracket math must keep for every filing status, as checks tests can run over many incomes
+"""
+from decimal import Decimal
+from typing import Callable, Iterable, List, Optional
+
+from app.tax_engine.tax_calculator import FilingStatus, TaxBrackets, TaxCalculator
+
+# Tax on a taxable income for a filing status
+TaxFunction = Callable[[Decimal, str], Decimal]
+
+# Income step used at bracket edges, and the rounding a one-cent step can add to the tax
+ONE_CENT = Decimal("0.01")
+EDGE_TOLERANCE = Decimal("0.01")
+
+
+def bracket_tax(taxable_income: Decimal, filing_status: str, tax_year: int = 2024) -> Decimal:
+    """Regular tax on a taxable income (no deduction is taken, so taxable income is the whole income)"""
+    result = TaxCalculator(tax_year).calculate_individual_tax(
+        taxable_income, filing_status, itemized_deductions=Decimal("0"), require_itemized=True
+    )
+    return Decimal(str(result["tax_liability"]))
+
+
+def marginal_rate(taxable_income: Decimal, filing_status: str) -> Decimal:
+    """Rate on the next dollar of taxable income"""
+    for upper_limit, rate in TaxBrackets.BRACKETS_2024[FilingStatus(filing_status)]:
+        if upper_limit is None or taxable_income < upper_limit:
+            return rate
+    raise AssertionError("the top bracket has no upper limit")
+
+
+def bracket_edges(filing_status: str) -> List[Decimal]:
+    """Taxable incomes where a bracket ends"""
+    return [limit for limit, _ in TaxBrackets.BRACKETS_2024[FilingStatus(filing_status)] if limit is not None]
+
+
+def check_monotonic(filing_status: str, incomes: Iterable[Decimal], tax: Optional[TaxFunction] = None) -> List[str]:
+    """
+    More income never means less tax
+
+    Returns:
+        A line per pair of incomes where tax went down (empty when the property holds)
+    """
+    tax = tax or bracket_tax
+    points = sorted(set(incomes))
+    taxes = [tax(income, filing_status) for income in points]
+    return [
+        f"{filing_status}: tax on ${high:,.2f} (${high_tax:,.2f}) is less than on ${low:,.2f} (${low_tax:,.2f})"
+        for low, high, low_tax, high_tax in zip(points, points[1:], taxes, taxes[1:])
+        if high_tax < low_tax
+    ]
+
+
+def check_effective_within_marginal(
+    filing_status: str, incomes: Iterable[Decimal], tax: Optional[TaxFunction] = None
+) -> List[str]:
+    """
+    The effective rate is never negative and never above the marginal rate
+
+    Returns:
+        A line per income where it's outside that range (empty when the property holds)
+    """
+    tax = tax or bracket_tax
+    violations = []
+    for income in incomes:
+        if income <= 0:
+            continue
+        amount = tax(income, filing_status)
+        rate = marginal_rate(income, filing_status)
+        # Allow half a cent of rounding on the tax
+        if amount < 0 or amount > income * rate + ONE_CENT / 2:
+            violations.append(
+                f"{filing_status}: tax of ${amount:,.2f} on ${income:,.2f} is outside 0 to the {rate:.0%} marginal rate"
+            )
+    return violations
+
+
+def check_continuity_at_edges(filing_status: str, tax: Optional[TaxFunction] = None) -> List[str]:
+    """
+    Crossing a bracket edge by a cent changes tax by no more than a cent at the higher rate
+
+    Returns:
+        A line per edge where tax jumps (empty when the property holds)
+    """
+    tax = tax or bracket_tax
+    violations = []
+    for edge in bracket_edges(filing_status):
+        for low, high in ((edge - ONE_CENT, edge), (edge, edge + ONE_CENT)):
+            step = tax(high, filing_status) - tax(low, filing_status)
+            limit = ONE_CENT * marginal_rate(low, filing_status).max(marginal_rate(high, filing_status))
+            if step < 0 or step > limit + EDGE_TOLERANCE:
+                violations.append(f"{filing_status}: tax jumps ${step:,.2f} between ${low:,.2f} and ${high:,.2f}")
+    return violations
+
+
+def check_bracket_invariants(
+    filing_status: str, incomes: Iterable[Decimal], tax: Optional[TaxFunction] = None
+) -> List[str]:
+    """Every invariant for a filing status over the given incomes (plus the bracket edges)"""
+    points = sorted(set(incomes) | set(bracket_edges(filing_status)))
+    return (
+        check_monotonic(filing_status, points, tax)
+        + check_effective_within_marginal(filing_status, points, tax)
+        + check_continuity_at_edges(filing_status, tax)
+    )
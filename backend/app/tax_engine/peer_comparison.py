@@ -0,0 +1,164 @@
+"""
+Peer Comparison
+Itemized deductions measured against IRS Statistics of Income averages for the same AGI band
+"""
+import json
+from decimal import Decimal, ROUND_HALF_UP
+from pathlib import Path
+from typing import Dict, List, Any, Optional
+
+from app.tax_engine.return_calculation import calculate_return
+
+# SOI Table 2.1 averages shipped with the app
+SOI_DATASET_PATH = Path(__file__).parent / "data" / "soi_itemized_deductions.json"
+
+# Dataset categories and the Schedule A lines they're compared with
+PEER_CATEGORIES = [
+    ("medical", "4", "Medical and dental expenses"),
+    ("taxes", "7", "Taxes you paid"),
+    ("interest", "10", "Interest you paid"),
+    ("charitable", "14", "Gifts to charity"),
+    ("total", "17", "Total itemized deductions"),
+]
+
+# Ratios to the band average at which a category is flagged
+UNUSUALLY_HIGH_RATIO = Decimal("2")
+ABOVE_AVERAGE_RATIO = Decimal("1.25")
+BELOW_AVERAGE_RATIO = Decimal("0.75")
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def load_soi_dataset(path: Optional[str] = None) -> Dict[str, Any]:
+    """
+    Load the SOI averages
+
+    Args:
+        path: A dataset file in the same format (defaults to the embedded one)
+
+    Returns:
+        Dict with source, tax_year, and bands ({label, min, max, averages})
+
+    Raises:
+        ValueError: If the file can't be read or has no AGI bands
+    """
+    path = Path(path) if path else SOI_DATASET_PATH
+    try:
+        with open(path, 'r', encoding='utf-8') as f:
+            dataset = json.load(f)
+    except (OSError, json.JSONDecodeError) as e:
+        raise ValueError(f"Can't read SOI dataset {path}: {e}")
+    bands = dataset.get("bands") if isinstance(dataset, dict) else None
+    if not bands or not all(isinstance(band, dict) and "label" in band for band in bands):
+        raise ValueError(f"SOI dataset {path} has no AGI bands")
+    return dataset
+
+
+def peer_band(dataset: Dict[str, Any], agi: Decimal) -> Dict[str, Any]:
+    """
+    The dataset's AGI band an AGI falls in (min inclusive, max exclusive, None for open-ended)
+
+    Raises:
+        ValueError: If no band covers the AGI
+    """
+    for band in dataset["bands"]:
+        low, high = band.get("min"), band.get("max")
+        if (low is None or agi >= _amount(low)) and (high is None or agi < _amount(high)):
+            return band
+    raise ValueError(f"The SOI dataset has no band for AGI of {agi}")
+
+
+def _flag(amount: Decimal, ratio: Optional[Decimal]) -> str:
+    if amount == 0:
+        return "not_claimed"
+    if ratio is None:
+        return "no_data"
+    if ratio >= UNUSUALLY_HIGH_RATIO:
+        return "unusually_high"
+    if ratio >= ABOVE_AVERAGE_RATIO:
+        return "above_average"
+    if ratio < BELOW_AVERAGE_RATIO:
+        return "below_average"
+    return "typical"
+
+
+def compare_with_peers(tax_return: Dict[str, Any], dataset: Optional[Dict[str, Any]] = None) -> Dict[str, Any]:
+    """
+    Compare a return's itemized deductions with the average for its AGI band
+
+    SOI averages are per return claiming the deduction, so each Schedule A
+    category (after the medical floor and SALT cap) is measured against
+    what other itemizers with similar income claimed. A category at twice
+    the average or more is flagged unusually_high: worth a look when
+    planning, and something examiners notice too.
+
+    Args:
+        tax_return: Return dict from ReturnStore
+        dataset: load_soi_dataset() output (defaults to the embedded dataset)
+
+    Returns:
+        Dict with agi, band, source, dataset_year, itemizes, categories
+        ({category, line, label, amount, peer_average, ratio, flag}),
+        flagged (the unusually high categories), and notes
+
+    Raises:
+        ValueError: If the engine doesn't support the return's year or the dataset is unusable
+    """
+    dataset = dataset if dataset is not None else load_soi_dataset()
+    calculation = calculate_return(tax_return)
+    agi = _amount(calculation["agi"])
+    band = peer_band(dataset, agi)
+    averages = band.get("averages") or {}
+    schedule_lines = (calculation["schedule_a"] or {}).get("lines", {})
+    notes: List[str] = []
+
+    categories = []
+    for category, line, label in PEER_CATEGORIES:
+        amount = _amount(schedule_lines.get(line))
+        average = averages.get(category)
+        ratio = None
+        if average:
+            ratio = (amount / _amount(average)).quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+        categories.append({
+            "category": category,
+            "line": line,
+            "label": label,
+            "amount": float(amount),
+            "peer_average": float(average) if average else None,
+            "ratio": float(ratio) if ratio is not None else None,
+            "flag": _flag(amount, ratio),
+        })
+    flagged = [c["category"] for c in categories if c["flag"] == "unusually_high"]
+
+    if not schedule_lines:
+        notes.append("The return has no itemized deductions to compare")
+    elif calculation["deduction_type"] != "Itemized":
+        notes.append("The standard deduction is larger, so these deductions don't change the tax this year")
+    if any(c["flag"] == "no_data" for c in categories):
+        notes.append(
+            f"The SOI dataset has no averages for the {band['label']} band; add them from "
+            f"{dataset.get('source') or 'the published table'}"
+        )
+    if flagged:
+        labels = ", ".join(c["label"].lower() for c in categories if c["category"] in flagged)
+        notes.append(
+            f"Unusually high for this income: {labels}. Large deductions are allowed, but keep the receipts "
+            "and statements behind them"
+        )
+    dataset_year = dataset.get("tax_year")
+    if dataset_year and dataset_year != tax_return["tax_year"]:
+        notes.append(f"Peer averages are from tax year {dataset_year} and aren't adjusted for inflation")
+
+    return {
+        "tax_year": tax_return["tax_year"],
+        "agi": float(agi),
+        "band": {"label": band["label"], "min": band.get("min"), "max": band.get("max")},
+        "source": dataset.get("source"),
+        "dataset_year": dataset_year,
+        "itemizes": calculation["deduction_type"] == "Itemized",
+        "categories": categories,
+        "flagged": flagged,
+        "notes": notes,
+    }
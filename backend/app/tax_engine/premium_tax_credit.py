@@ -0,0 +1,184 @@
+"""
+Premium Tax Credit Engine
+Form 8962 reconciliation of advance PTC against the credit allowed for 2024
+"""
+from typing import Dict, List, Any, Optional
+from decimal import Decimal, ROUND_DOWN, ROUND_HALF_UP
+
+from app.documents.parsers.form_1095_a import Form1095A, MonthlyCoverage
+from app.tax_engine.tax_calculator import FilingStatus
+
+# 2023 HHS poverty guidelines, used for 2024 coverage: (first person, each additional person)
+FEDERAL_POVERTY_LINE = {
+    "contiguous": (Decimal("14580"), Decimal("5140")),
+    "AK": (Decimal("18210"), Decimal("6430")),
+    "HI": (Decimal("16770"), Decimal("5910")),
+}
+
+# Applicable figure bands (Rev. Proc. 2023-29): (FPL % from, FPL % to, figure from, figure to)
+APPLICABLE_FIGURE_BANDS = [
+    (0, 150, Decimal("0"), Decimal("0")),
+    (150, 200, Decimal("0"), Decimal("0.02")),
+    (200, 250, Decimal("0.02"), Decimal("0.04")),
+    (250, 300, Decimal("0.04"), Decimal("0.06")),
+    (300, 400, Decimal("0.06"), Decimal("0.085")),
+]
+MAX_APPLICABLE_FIGURE = Decimal("0.085")
+
+# Excess APTC repayment caps for 2024 (Rev. Proc. 2023-34): (FPL % below, single, all other statuses)
+REPAYMENT_LIMITATIONS = [
+    (200, Decimal("375"), Decimal("750")),
+    (300, Decimal("950"), Decimal("1900")),
+    (400, Decimal("1575"), Decimal("3150")),
+]
+
+
+class PremiumTaxCreditCalculator:
+    """Form 8962 premium tax credit calculator"""
+
+    def __init__(self, tax_year: int = 2024):
+        self.tax_year = tax_year
+
+        if tax_year != 2024:
+            raise ValueError(f"Only 2024 tax year is currently supported, got {tax_year}")
+
+    @staticmethod
+    def poverty_line(family_size: int, state: Optional[str] = None) -> Decimal:
+        """Federal poverty line for the family size (line 4)"""
+        if family_size < 1:
+            raise ValueError("Family size must be at least 1")
+        first, additional = FEDERAL_POVERTY_LINE.get((state or "").upper(), FEDERAL_POVERTY_LINE["contiguous"])
+        return first + additional * (family_size - 1)
+
+    @staticmethod
+    def applicable_figure(fpl_percent: int) -> Decimal:
+        """Line 7: share of household income expected to go to premiums"""
+        for low, high, figure_low, figure_high in APPLICABLE_FIGURE_BANDS:
+            if fpl_percent < high:
+                position = Decimal(max(fpl_percent, low) - low) / Decimal(high - low)
+                figure = figure_low + (figure_high - figure_low) * position
+                return figure.quantize(Decimal("0.0001"), rounding=ROUND_HALF_UP)
+        return MAX_APPLICABLE_FIGURE
+
+    def calculate(
+        self,
+        household_income: Decimal,
+        family_size: int,
+        filing_status: str,
+        statements: List[Form1095A],
+        state: Optional[str] = None,
+        married_separate_exception: bool = False,
+    ) -> Dict[str, Any]:
+        """
+        Reconcile advance PTC (Form 8962)
+
+        Args:
+            household_income: Modified AGI of everyone in the tax family (line 3)
+            family_size: Tax family size (line 1)
+            filing_status: Filing status value
+            statements: Parsed 1095-A records (one per policy)
+            state: Two-letter state of residence (AK/HI use their own poverty line)
+            married_separate_exception: Domestic abuse/abandonment relief for MFS filers
+
+        Returns:
+            Dict with the Form 8962 line values and monthly breakdown
+        """
+        if household_income < 0:
+            raise ValueError("Household income cannot be negative")
+        try:
+            status = FilingStatus(filing_status.lower())
+        except ValueError:
+            raise ValueError(
+                f"Invalid filing status: {filing_status}. "
+                f"Must be one of: {', '.join([s.value for s in FilingStatus])}"
+            )
+
+        notes: List[str] = []
+        poverty_line = self.poverty_line(family_size, state)
+        # Line 5 drops fractions of a percent
+        fpl_percent = int((household_income / poverty_line * 100).to_integral_value(rounding=ROUND_DOWN))
+        months = self._combine_statements(statements)
+        total_advance = sum((m.advance_ptc for m in months), Decimal("0"))
+
+        eligible = True
+        if status == FilingStatus.MARRIED_SEPARATE and not married_separate_exception:
+            eligible = False
+            notes.append("Married filing separately generally cannot claim the premium tax credit")
+        if fpl_percent < 100 and total_advance == 0:
+            # Below 100% FPL only qualifies if advance payments were made
+            eligible = False
+            notes.append("Household income is below 100% of the poverty line")
+
+        figure = self.applicable_figure(fpl_percent) if eligible else Decimal("0")
+        annual_contribution = (household_income * figure).quantize(Decimal("1"), rounding=ROUND_HALF_UP)
+        monthly_contribution = (annual_contribution / 12).quantize(Decimal("1"), rounding=ROUND_HALF_UP)
+
+        monthly = []
+        total_credit = Decimal("0")
+        for coverage in months:
+            if eligible and coverage.enrollment_premium > 0:
+                max_assistance = max(Decimal("0"), coverage.slcsp_premium - monthly_contribution)
+                credit = min(coverage.enrollment_premium, max_assistance)
+            else:
+                max_assistance = credit = Decimal("0")
+            total_credit += credit
+            monthly.append({
+                "month": coverage.month,
+                "enrollment_premium": float(coverage.enrollment_premium),
+                "slcsp_premium": float(coverage.slcsp_premium),
+                "monthly_contribution": float(monthly_contribution) if coverage.enrollment_premium > 0 else 0.0,
+                "max_premium_assistance": float(max_assistance),
+                "credit_allowed": float(credit),
+                "advance_ptc": float(coverage.advance_ptc),
+            })
+
+        net_credit = max(Decimal("0"), total_credit - total_advance)
+        excess_advance = max(Decimal("0"), total_advance - total_credit)
+        limitation = self._repayment_limitation(fpl_percent, status)
+        repayment = excess_advance if limitation is None else min(excess_advance, limitation)
+
+        return {
+            "tax_year": self.tax_year,
+            "filing_status": status.value,
+            "eligible": eligible,
+            "family_size": family_size,
+            "household_income": float(household_income),
+            "federal_poverty_line": float(poverty_line),
+            "fpl_percent": fpl_percent,
+            "applicable_figure": float(figure),
+            "annual_contribution": float(annual_contribution),
+            "monthly_contribution": float(monthly_contribution),
+            "monthly": monthly,
+            "total_premium_tax_credit": float(total_credit),
+            "total_advance_ptc": float(total_advance),
+            "net_premium_tax_credit": float(net_credit),
+            "excess_advance_ptc": float(excess_advance),
+            "repayment_limitation": float(limitation) if limitation is not None else None,
+            "excess_advance_repayment": float(repayment),
+            "notes": notes,
+        }
+
+    @staticmethod
+    def _combine_statements(statements: List[Form1095A]) -> List[MonthlyCoverage]:
+        """
+        Merge policies month by month
+
+        Premiums and advance payments add up; the SLCSP is per tax family, so the
+        largest one reported for the month is used rather than a sum.
+        """
+        combined = [MonthlyCoverage(month=m) for m in range(1, 13)]
+        for statement in statements:
+            for coverage in statement.months:
+                target = combined[coverage.month - 1]
+                target.enrollment_premium += coverage.enrollment_premium
+                target.advance_ptc += coverage.advance_ptc
+                target.slcsp_premium = max(target.slcsp_premium, coverage.slcsp_premium)
+        return combined
+
+    @staticmethod
+    def _repayment_limitation(fpl_percent: int, status: FilingStatus) -> Optional[Decimal]:
+        """Line 28: cap on repaying excess APTC (None above 400% FPL)"""
+        for below, single, other in REPAYMENT_LIMITATIONS:
+            if fpl_percent < below:
+                return single if status == FilingStatus.SINGLE else other
+        return None
@@ -0,0 +1,185 @@
+"""
+Business Profit and Loss
+Quarterly and year-to-date profit and loss by category for a Schedule C business
+"""
+from datetime import date
+from decimal import Decimal, ROUND_HALF_UP
+from typing import Dict, List, Any, Optional
+
+from app.tax_engine.schedule_c import EXPENSE_LINES
+from app.tax_engine.vehicle_expenses import STANDARD_MILEAGE_RATES, compare_vehicle_methods
+
+QUARTERS = ["q1", "q2", "q3", "q4"]
+# Amounts without a date (e.g. gross_receipts entered as a year total) count only toward year to date
+UNDATED = "undated"
+YTD = "ytd"
+
+INCOME_CATEGORIES = ["gross_receipts", "returns_allowances", "cost_of_goods_sold", "other_income"]
+
+
+def _cents(value: Decimal) -> Decimal:
+    return value.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+class _Ledger:
+    """Amounts per category and period"""
+
+    def __init__(self, tax_year: int, through_quarter: int):
+        self.tax_year = tax_year
+        self.through_quarter = through_quarter
+        self.rows: Dict[str, Dict[str, Decimal]] = {}
+        self.outside_year = 0
+        self.after_period = 0
+
+    def add(self, category: str, amount: Decimal, when: Optional[str]) -> None:
+        period = UNDATED
+        if when:
+            day = date.fromisoformat(when[:10])
+            if day.year != self.tax_year:
+                self.outside_year += 1
+                return
+            quarter = (day.month - 1) // 3 + 1
+            if quarter > self.through_quarter:
+                self.after_period += 1
+                return
+            period = QUARTERS[quarter - 1]
+        row = self.rows.setdefault(category, {})
+        row[period] = row.get(period, Decimal("0")) + amount
+
+    def get(self, category: str) -> Dict[str, Decimal]:
+        return self.rows.get(category, {})
+
+
+def _add_vehicles(ledger: _Ledger, business: Dict[str, Any], tax_year: int) -> None:
+    """Car and truck costs under each vehicle's chosen method, dated by trip or expense"""
+    for vehicle in business.get("vehicles", []):
+        method = vehicle.get("method")
+        if not method or tax_year not in STANDARD_MILEAGE_RATES:
+            continue
+        comparison = compare_vehicle_methods(vehicle, tax_year)
+        for expense in vehicle.get("expenses", []):
+            if expense["kind"] == "parking_tolls":
+                ledger.add("car_truck", _amount(expense.get("amount")), expense.get("date"))
+        if method == "standard_mileage":
+            rate = STANDARD_MILEAGE_RATES[tax_year]
+            for trip in vehicle.get("mileage_log", []):
+                if trip.get("business", True):
+                    ledger.add("car_truck", _cents(_amount(trip["miles"]) * rate), trip.get("date"))
+            continue
+        business_use = _amount(comparison["business_use_percent"]) / 100
+        for expense in vehicle.get("expenses", []):
+            if expense["kind"] != "parking_tolls":
+                ledger.add("car_truck", _cents(_amount(expense.get("amount")) * business_use), expense.get("date"))
+        if comparison["actual"]["depreciation"]:
+            ledger.add("depreciation", _amount(comparison["actual"]["depreciation"]), None)
+
+
+def business_profit_loss(
+    business: Dict[str, Any],
+    income_sources: List[Dict[str, Any]],
+    tax_year: int,
+    through_quarter: int = 4,
+) -> Dict[str, Any]:
+    """
+    Profit and loss for one business by calendar quarter and year to date
+
+    Income is the business's own amounts plus self-employment income sources
+    linked to it (business_id), each in the quarter of its date. Expenses are
+    book amounts by category: meals appear in full, though Schedule C allows
+    only half. Vehicles with a chosen method add car and truck costs (and
+    depreciation) as Schedule C does.
+
+    Args:
+        business: Business record from ReturnStore
+        income_sources: The return's income sources
+        tax_year: Year being reported
+        through_quarter: Last quarter to include (1-4), for a mid-year report
+
+    Returns:
+        Dict with the periods, income and expense rows (category plus an
+        amount per period), gross income, total expenses, net profit, and notes
+
+    Raises:
+        ValueError: If through_quarter isn't 1-4, an expense category is unknown,
+            or a date isn't YYYY-MM-DD
+    """
+    if through_quarter not in range(1, 5):
+        raise ValueError("through_quarter must be 1, 2, 3, or 4")
+    ledger = _Ledger(tax_year, through_quarter)
+
+    for category in INCOME_CATEGORIES:
+        if business.get(category):
+            ledger.add(category, _amount(business[category]), None)
+    for source in income_sources:
+        if source["type"] == "self_employment" and source.get("business_id") == business["id"]:
+            ledger.add("gross_receipts", _amount(source.get("amount")), source.get("date"))
+
+    for expense in business.get("expenses", []):
+        if expense["category"] not in EXPENSE_LINES:
+            raise ValueError(
+                f"Invalid business expense category: {expense['category']}. "
+                f"Must be one of: {', '.join(EXPENSE_LINES)}"
+            )
+        ledger.add(expense["category"], _amount(expense.get("amount")), expense.get("date"))
+    _add_vehicles(ledger, business, tax_year)
+    if business.get("home_office"):
+        ledger.add("home_office", _amount(business["home_office"]), None)
+
+    periods = QUARTERS[:through_quarter] + [UNDATED]
+
+    def row(category: str) -> Dict[str, Decimal]:
+        amounts = ledger.get(category)
+        values = {period: amounts.get(period, Decimal("0")) for period in periods}
+        values[YTD] = sum(values.values(), Decimal("0"))
+        return values
+
+    def total(rows: List[Dict[str, Decimal]], signs: Optional[List[int]] = None) -> Dict[str, Decimal]:
+        signs = signs or [1] * len(rows)
+        return {
+            period: sum((sign * r[period] for sign, r in zip(signs, rows)), Decimal("0"))
+            for period in periods + [YTD]
+        }
+
+    income_rows = [row(category) for category in INCOME_CATEGORIES]
+    gross_income = total(income_rows, [1, -1, -1, 1])
+    expense_categories = [c for c in list(EXPENSE_LINES) + ["home_office"] if ledger.get(c)]
+    expense_rows = [row(category) for category in expense_categories]
+    total_expenses = total(expense_rows)
+    net_profit = {period: gross_income[period] - total_expenses[period] for period in periods + [YTD]}
+
+    notes: List[str] = []
+    if any(r[UNDATED] for r in income_rows + expense_rows):
+        notes.append("Amounts without a date are counted in year to date but not in any quarter")
+    if ledger.outside_year:
+        notes.append(f"{ledger.outside_year} entries dated outside {tax_year} were left out")
+    if ledger.after_period:
+        notes.append(f"{ledger.after_period} entries dated after Q{through_quarter} were left out")
+    if ledger.get("meals"):
+        notes.append("Meals are shown in full; Schedule C deducts 50%")
+
+    def floats(values: Dict[str, Decimal]) -> Dict[str, float]:
+        return {period: float(_cents(amount)) for period, amount in values.items()}
+
+    return {
+        "business_id": business["id"],
+        "name": business["name"],
+        "tax_year": tax_year,
+        "through_quarter": through_quarter,
+        "periods": periods + [YTD],
+        "income": [
+            {"category": category, **floats(values)}
+            for category, values in zip(INCOME_CATEGORIES, income_rows)
+            if ledger.get(category)
+        ],
+        "gross_income": floats(gross_income),
+        "expenses": [
+            {"category": category, **floats(values)} for category, values in zip(expense_categories, expense_rows)
+        ],
+        "total_expenses": floats(total_expenses),
+        "net_profit": floats(net_profit),
+        "notes": notes,
+    }
@@ -0,0 +1,226 @@
+"""
+Raise Calculator
+What a raise or job offer is worth after federal, state, local, and FICA taxes, including credits it phases out
+"""
+import copy
+from decimal import Decimal, ROUND_HALF_UP
+from typing import Dict, List, Any, Optional
+
+from app.tax_engine.return_calculation import calculate_return
+from app.tax_engine.schedule_se import SOCIAL_SECURITY_WAGE_BASE
+from app.tax_engine.state_tax import StateTaxCalculator, tax_exempt_interest_add_back
+
+# Employee share of FICA on wages
+EMPLOYEE_SOCIAL_SECURITY_RATE = Decimal("0.062")
+EMPLOYEE_MEDICARE_RATE = Decimal("0.0145")
+
+# Additional Medicare Tax (Form 8959) on wages over the filing status threshold
+ADDITIONAL_MEDICARE_RATE = Decimal("0.009")
+ADDITIONAL_MEDICARE_THRESHOLDS = {"married_joint": Decimal("250000"), "married_separate": Decimal("125000")}
+ADDITIONAL_MEDICARE_THRESHOLD_DEFAULT = Decimal("200000")
+
+# The raise is split into this many equal slices to find where the marginal rate jumps
+RAISE_SLICES = 5
+
+# A slice taxed at this marginal rate or more is called out
+HIGH_MARGINAL_RATE = Decimal("0.5")
+
+RAISE_OWNERS = ["taxpayer", "spouse"]
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def _cents(value: Decimal) -> Decimal:
+    return value.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+
+
+def _rate(value: Decimal) -> float:
+    return float(value.quantize(Decimal("0.0001"), rounding=ROUND_HALF_UP))
+
+
+def _wages(tax_return: Dict[str, Any], owner: Optional[str] = None) -> Decimal:
+    return sum(
+        (_amount(s.get("amount")) for s in tax_return.get("income_sources", [])
+         if s["type"] == "wages" and (owner is None or s.get("owner", "taxpayer") == owner)),
+        Decimal("0"),
+    )
+
+
+def _credits(calculation: Dict[str, Any]) -> Dict[str, Decimal]:
+    """Credits the engine phases out by income"""
+    credits = {
+        "child_tax_credit": _amount(calculation["credits"]["child_tax_credit"]),
+        "other_dependent_credit": _amount(calculation["credits"]["other_dependent_credit"]),
+    }
+    if calculation["education_credits"]:
+        credits["education_credits"] = _amount(calculation["education_credits"]["total"])
+    return credits
+
+
+def raise_after_tax(
+    tax_return: Dict[str, Any],
+    raise_amount: Optional[Decimal] = None,
+    new_salary: Optional[Decimal] = None,
+    owner: str = "taxpayer",
+    state: Optional[str] = None,
+    local_tax_rate: Decimal = Decimal("0"),
+) -> Dict[str, Any]:
+    """
+    Work out how much of a raise or job offer is left after taxes
+
+    The extra pay is added to the return as wages and the year is
+    recalculated: federal income tax (with every credit and phase-out the
+    engine models), state and local income tax on the new AGI, and the
+    employee's social security, Medicare, and Additional Medicare Tax. The
+    raise is also taken in equal slices, so a slice that crosses a
+    phase-out and costs far more than the tax bracket suggests shows up.
+
+    Args:
+        tax_return: Return dict from ReturnStore
+        raise_amount: Extra yearly pay (negative for a pay cut)
+        new_salary: The offered salary instead; the raise is its difference from the owner's current wages
+        owner: Whose wages change (taxpayer or spouse)
+        state: State of residence (defaults to the resident state return or the taxpayer's state)
+        local_tax_rate: City or county income tax, percent of AGI
+
+    Returns:
+        Dict with current and new wages, the change in each tax, after_tax_raise,
+        keep_percent, marginal_rate, credits_lost, slices ({from, to,
+        tax_change, marginal_rate, credits_lost}), and notes
+
+    Raises:
+        ValueError: If neither or both of raise_amount and new_salary are
+            given, the raise is zero or leaves negative wages, the owner is
+            invalid, the state isn't supported, or the rate is negative
+    """
+    if (raise_amount is None) == (new_salary is None):
+        raise ValueError("Give either the raise or the new salary")
+    if owner not in RAISE_OWNERS:
+        raise ValueError(f"Owner must be one of: {', '.join(RAISE_OWNERS)}")
+    filing_status = tax_return["filing_status"]
+    if owner == "spouse" and filing_status != "married_joint":
+        raise ValueError("Only a joint return includes the spouse's wages")
+    if local_tax_rate < 0:
+        raise ValueError("Local tax rate cannot be negative")
+
+    current_wages = _wages(tax_return, owner)
+    if raise_amount is None:
+        raise_amount = new_salary - current_wages
+    if raise_amount == 0:
+        raise ValueError("The new pay is the same as the current wages")
+    if current_wages + raise_amount < 0:
+        raise ValueError(f"A cut of ${-raise_amount:,.2f} is more than the current wages of ${current_wages:,.2f}")
+
+    if state is None:
+        state = next(
+            (r["state"] for r in tax_return.get("state_returns", []) if r.get("residency") == "resident"),
+            (tax_return.get("taxpayer") or {}).get("state"),
+        )
+    state = state.upper() if state else None
+    state_calculator = StateTaxCalculator(tax_return["tax_year"])
+    if state:
+        # Fails early for a state the engine doesn't support
+        state_calculator.resident_tax(state, Decimal("0"), filing_status)
+    add_back = tax_exempt_interest_add_back(tax_return.get("income_sources", []), state) if state else Decimal("0")
+    household_wages = _wages(tax_return, None if filing_status == "married_joint" else owner)
+    threshold = ADDITIONAL_MEDICARE_THRESHOLDS.get(filing_status, ADDITIONAL_MEDICARE_THRESHOLD_DEFAULT)
+
+    def taxes(extra: Decimal) -> Dict[str, Any]:
+        """Every tax the household pays with the owner's wages changed by extra"""
+        scenario = copy.deepcopy(tax_return)
+        if extra:
+            scenario["income_sources"] = scenario.get("income_sources", []) + [
+                {"id": "inc_raise", "type": "wages", "amount": float(extra), "withholding": 0, "owner": owner}
+            ]
+        calculation = calculate_return(scenario)
+        agi = _amount(calculation["agi"])
+        wages = current_wages + extra
+        social_security = EMPLOYEE_SOCIAL_SECURITY_RATE * min(wages, SOCIAL_SECURITY_WAGE_BASE)
+        medicare = EMPLOYEE_MEDICARE_RATE * wages
+        additional = ADDITIONAL_MEDICARE_RATE * max(Decimal("0"), household_wages + extra - threshold)
+        figures = {
+            # Withholding less the refund: tax after refundable credits
+            "federal": _amount(calculation["total_withholding"]) - _amount(calculation["refund_or_owed"]),
+            "state": state_calculator.resident_tax(state, agi + add_back, filing_status)["tax"]
+            if state else Decimal("0"),
+            "local": _cents(max(Decimal("0"), agi) * local_tax_rate / 100),
+            "social_security": _cents(social_security),
+            "medicare": _cents(medicare),
+            "additional_medicare": _cents(additional),
+        }
+        return {"figures": figures, "total": sum(figures.values(), Decimal("0")), "credits": _credits(calculation)}
+
+    before = taxes(Decimal("0"))
+    after = taxes(raise_amount)
+    changes = {name: after["figures"][name] - before["figures"][name] for name in before["figures"]}
+    total_change = after["total"] - before["total"]
+    after_tax = raise_amount - total_change
+
+    def lost(start: Dict[str, Any], end: Dict[str, Any]) -> List[Dict[str, Any]]:
+        return [
+            {"credit": name, "before": float(start["credits"][name]), "after": float(end["credits"].get(name, 0)),
+             "lost": float(start["credits"][name] - end["credits"].get(name, Decimal("0")))}
+            for name in start["credits"]
+            if end["credits"].get(name, Decimal("0")) < start["credits"][name]
+        ]
+
+    slices = []
+    previous, previous_taxes = Decimal("0"), before
+    for step in range(1, RAISE_SLICES + 1):
+        point = _cents(raise_amount * step / RAISE_SLICES)
+        point_taxes = after if step == RAISE_SLICES else taxes(point)
+        width = point - previous
+        change = point_taxes["total"] - previous_taxes["total"]
+        slices.append({
+            "from": float(previous),
+            "to": float(point),
+            "tax_change": float(change),
+            "marginal_rate": _rate(change / width) if width else 0.0,
+            "credits_lost": lost(previous_taxes, point_taxes),
+        })
+        previous, previous_taxes = point, point_taxes
+
+    marginal_rate = total_change / raise_amount
+    credits_lost = lost(before, after)
+    notes: List[str] = []
+    for credit in credits_lost:
+        notes.append(
+            f"The raise costs ${credit['lost']:,.2f} of the {credit['credit'].replace('_', ' ')} "
+            f"(${credit['before']:,.2f} before, ${credit['after']:,.2f} after)"
+        )
+    for item in slices:
+        if raise_amount > 0 and _amount(item["marginal_rate"]) >= HIGH_MARGINAL_RATE:
+            notes.append(
+                f"Pay from ${item['from']:,.2f} to ${item['to']:,.2f} of the raise is taxed at "
+                f"{item['marginal_rate']:.0%} as credits phase out"
+            )
+    if current_wages < SOCIAL_SECURITY_WAGE_BASE < current_wages + raise_amount:
+        notes.append(
+            f"Social security tax stops at the ${SOCIAL_SECURITY_WAGE_BASE:,.0f} wage base; pay above it is taxed "
+            "only for Medicare"
+        )
+    if not state:
+        notes.append("No state of residence on the return, so state income tax isn't included")
+    notes.append(
+        "FICA assumes one employer; with more than one, social security withheld over the wage base comes back "
+        "as a credit on the return"
+    )
+
+    return {
+        "tax_year": tax_return["tax_year"],
+        "owner": owner,
+        "state": state,
+        "current_wages": float(current_wages),
+        "new_wages": float(current_wages + raise_amount),
+        "raise": float(raise_amount),
+        "tax_changes": {name: float(change) for name, change in changes.items()},
+        "total_tax_change": float(total_change),
+        "after_tax_raise": float(after_tax),
+        "keep_percent": float(_cents(after_tax / raise_amount * 100)),
+        "marginal_rate": _rate(marginal_rate),
+        "credits_lost": credits_lost,
+        "slices": slices,
+        "notes": notes,
+    }
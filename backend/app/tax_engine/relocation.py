@@ -0,0 +1,131 @@
+"""
+Relocation Comparison
+A return's yearly tax as if the taxpayer lived in another state: state and local income tax, property tax, and the federal effect
+"""
+import copy
+from decimal import Decimal, ROUND_HALF_UP
+from typing import Dict, Any, Optional
+
+from app.tax_engine.return_calculation import calculate_return
+from app.tax_engine.state_tax import NO_INCOME_TAX_STATES, StateTaxCalculator, tax_exempt_interest_add_back
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def _cents(value: Decimal) -> Decimal:
+    return value.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+
+
+def _scenario(
+    tax_return: Dict[str, Any],
+    state: str,
+    agi: Decimal,
+    local_rate: Decimal,
+    property_tax: Decimal,
+) -> Dict[str, Any]:
+    """Taxes for a full year living in one state"""
+    state_income = agi + tax_exempt_interest_add_back(tax_return.get("income_sources", []), state)
+    state_tax = StateTaxCalculator(tax_return["tax_year"]).resident_tax(
+        state, state_income, tax_return["filing_status"]
+    )["tax"]
+    local_tax = _cents(max(Decimal("0"), agi) * local_rate / 100)
+
+    # State and local income and property taxes replace the return's SALT deductions
+    scenario = copy.deepcopy(tax_return)
+    scenario["deductions"] = [
+        d for d in scenario.get("deductions", [])
+        if not (d["category"] == "state_local_tax" and d.get("tax_type", "income") in ("income", "sales", "real_estate"))
+    ]
+    scenario["deductions"] += [
+        {"id": "ded_income", "category": "state_local_tax", "tax_type": "income", "amount": float(state_tax + local_tax)},
+        {"id": "ded_property", "category": "state_local_tax", "tax_type": "real_estate", "amount": float(property_tax)},
+    ]
+    federal_tax = _amount(calculate_return(scenario)["total_tax"])
+
+    return {
+        "state": state,
+        "state_income_tax": float(state_tax),
+        "local_income_tax": float(local_tax),
+        "local_tax_rate": float(local_rate),
+        "property_tax": float(property_tax),
+        "federal_tax": float(federal_tax),
+        "total_tax": float(state_tax + local_tax + property_tax + federal_tax),
+    }
+
+
+def compare_relocation(
+    tax_return: Dict[str, Any],
+    to_state: str,
+    from_state: Optional[str] = None,
+    local_tax_rate: Decimal = Decimal("0"),
+    new_local_tax_rate: Decimal = Decimal("0"),
+    property_tax: Optional[Decimal] = None,
+    new_property_tax: Optional[Decimal] = None,
+) -> Dict[str, Any]:
+    """
+    Compare a year's taxes where the taxpayer lives now with another state
+
+    Both sides assume a full year's residence on the same federal AGI, using
+    the state engine's resident tax. The federal tax is recalculated for each
+    side because state, local, and property taxes change the SALT deduction
+    (and whether itemizing wins).
+
+    Args:
+        tax_return: Return dict from ReturnStore
+        to_state: State being considered
+        from_state: Current state (defaults to the resident state return)
+        local_tax_rate: Current city or county income tax, percent of AGI
+        new_local_tax_rate: Local income tax in the new location, percent of AGI
+        property_tax: Current yearly property tax (defaults to the return's real estate tax deductions)
+        new_property_tax: Estimated yearly property tax after moving (defaults to property_tax)
+
+    Returns:
+        Dict with AGI, current and new scenarios (state, local, property, and
+        federal tax), the annual difference (positive when moving costs more),
+        and notes
+
+    Raises:
+        ValueError: If a state isn't supported, there's no current state, or an input is negative
+    """
+    to_state = to_state.upper()
+    if from_state is None:
+        from_state = next(
+            (r["state"] for r in tax_return.get("state_returns", []) if r["residency"] == "resident"), None
+        )
+        if from_state is None:
+            raise ValueError("The return has no resident state; give the current state")
+    from_state = from_state.upper()
+    if property_tax is None:
+        property_tax = sum(
+            (_amount(d.get("amount")) for d in tax_return.get("deductions", [])
+             if d["category"] == "state_local_tax" and d.get("tax_type") == "real_estate"),
+            Decimal("0"),
+        )
+    if new_property_tax is None:
+        new_property_tax = property_tax
+    if min(local_tax_rate, new_local_tax_rate, property_tax, new_property_tax) < 0:
+        raise ValueError("Tax rates and property taxes cannot be negative")
+
+    agi = _amount(calculate_return(tax_return)["agi"])
+    current = _scenario(tax_return, from_state, agi, local_tax_rate, property_tax)
+    new = _scenario(tax_return, to_state, agi, new_local_tax_rate, new_property_tax)
+    difference = _amount(new["total_tax"]) - _amount(current["total_tax"])
+
+    notes = [
+        "Both sides assume a full year's residence; in the year of a move, each state taxes its part-year share"
+    ]
+    if to_state in NO_INCOME_TAX_STATES:
+        notes.append(f"{to_state} has no state income tax; sales and property taxes usually make up for it")
+    if from_state == to_state:
+        notes.append("Both locations are in the same state; only local and property taxes differ")
+
+    return {
+        "tax_year": tax_return["tax_year"],
+        "agi": float(agi),
+        "current": current,
+        "new": new,
+        "annual_difference": float(difference),
+        "notes": notes,
+    }
@@ -0,0 +1,221 @@
+"""
+Rental Property Depreciation
+27.5-year straight-line schedules for residential rentals (mid-month convention), Schedule E totals, depreciation recapture on sale, and like-kind exchanges
+"""
+from datetime import date
+from decimal import Decimal, ROUND_HALF_UP
+from typing import Dict, List, Any, Optional
+
+# Residential rental property is depreciated straight line over 27.5 years (330 months)
+RECOVERY_MONTHS = Decimal("330")
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def _cents(value: Decimal) -> Decimal:
+    return value.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+
+
+def _months_through(year: int, placed_in_service: date, date_sold: Optional[date]) -> Decimal:
+    """
+    Months of depreciation from the in-service date through the end of a year
+
+    Mid-month convention: property is treated as placed in service, and
+    sold, in the middle of the month.
+    """
+    if year < placed_in_service.year:
+        return Decimal("0")
+    start = (placed_in_service.year, Decimal(placed_in_service.month) - Decimal("0.5"))
+    if date_sold is not None and year >= date_sold.year:
+        end = (date_sold.year, Decimal(date_sold.month) - Decimal("0.5"))
+    else:
+        end = (year, Decimal("12"))
+    months = (end[0] - start[0]) * 12 + end[1] - start[1]
+    return min(max(months, Decimal("0")), RECOVERY_MONTHS)
+
+
+def depreciation_through(
+    building_basis: Decimal,
+    placed_in_service: str,
+    year: int,
+    date_sold: Optional[str] = None,
+) -> Decimal:
+    """Accumulated depreciation at the end of a year (or on the sale date)"""
+    months = _months_through(
+        year, date.fromisoformat(placed_in_service), date.fromisoformat(date_sold) if date_sold else None
+    )
+    return _cents(building_basis * months / RECOVERY_MONTHS)
+
+
+def depreciation_schedule(
+    building_basis: Decimal,
+    placed_in_service: str,
+    date_sold: Optional[str] = None,
+) -> List[Dict[str, Any]]:
+    """
+    Year-by-year depreciation of a residential rental building
+
+    The first year gets the months after the middle of the in-service month
+    (a January start gets 11.5 months); the schedule runs until the basis is
+    used up or the property is sold. Each year's amount is the change in
+    accumulated depreciation, so rounding never adds up to more than the basis.
+
+    Args:
+        building_basis: Cost of the building (not the land) plus improvements
+        placed_in_service: Date it was ready and available to rent (YYYY-MM-DD)
+        date_sold: Sale date, which ends the schedule mid-month
+
+    Returns:
+        Rows with year, months, depreciation, accumulated, and remaining_basis
+    """
+    in_service = date.fromisoformat(placed_in_service)
+    sold = date.fromisoformat(date_sold) if date_sold else None
+    rows = []
+    accumulated = Decimal("0")
+    months_before = Decimal("0")
+    year = in_service.year
+    while accumulated < building_basis and (sold is None or year <= sold.year):
+        months = _months_through(year, in_service, sold)
+        through = _cents(building_basis * months / RECOVERY_MONTHS)
+        rows.append({
+            "year": year,
+            "months": float(months - months_before),
+            "depreciation": float(through - accumulated),
+            "accumulated": float(through),
+            "remaining_basis": float(building_basis - through),
+        })
+        if months >= RECOVERY_MONTHS:
+            break
+        accumulated, months_before = through, months
+        year += 1
+    return rows
+
+
+def rental_property_year(rental: Dict[str, Any], tax_year: int) -> Dict[str, Any]:
+    """
+    One property's Schedule E figures for a year, and its sale if sold that year
+
+    On a sale, gain up to the depreciation taken is unrecaptured section 1250
+    gain (taxed at up to 25%); the rest is section 1231 gain. Depreciation
+    that was allowable reduces basis even if it was never claimed, and
+    depreciation on property given up in a like-kind exchange
+    (prior_depreciation) counts too. When the property was given up in a
+    like-kind exchange, only gain up to the boot received is recognized; the
+    rest is deferred into the replacement property.
+
+    Args:
+        rental: Rental property record from ReturnStore
+        tax_year: Year being filed
+
+    Returns:
+        Dict with rents, expenses, depreciation, net_income, accumulated
+        depreciation, adjusted_basis, and sale (None unless sold in the year)
+    """
+    basis = _amount(rental.get("building_basis"))
+    date_sold = rental.get("date_sold")
+    sold_this_year = bool(date_sold) and date.fromisoformat(date_sold).year == tax_year
+    accumulated = depreciation_through(basis, rental["placed_in_service"], tax_year, date_sold)
+    depreciation = accumulated - depreciation_through(basis, rental["placed_in_service"], tax_year - 1, date_sold)
+    rents = _amount(rental.get("rents"))
+    expenses = _amount(rental.get("expenses"))
+    adjusted_basis = basis + _amount(rental.get("land_value")) - accumulated
+
+    sale = None
+    if sold_this_year:
+        amount_realized = _amount(rental.get("sale_price")) - _amount(rental.get("selling_expenses"))
+        realized = amount_realized - adjusted_basis
+        exchange = rental.get("like_kind_exchange")
+        recognized = min(max(realized, Decimal("0")), _amount(exchange["boot_received"])) if exchange else realized
+        recapture = min(max(recognized, Decimal("0")), accumulated + _amount(rental.get("prior_depreciation")))
+        sale = {
+            "date_sold": date_sold,
+            "amount_realized": float(amount_realized),
+            "adjusted_basis": float(adjusted_basis),
+            "realized_gain": float(realized),
+            "deferred_gain": float(realized - recognized),
+            "gain_or_loss": float(recognized),
+            "unrecaptured_section_1250_gain": float(recapture),
+            "section_1231_gain": float(recognized - recapture),
+        }
+
+    return {
+        "property_id": rental.get("id"),
+        "address": rental.get("address", ""),
+        "rents": float(rents),
+        "expenses": float(expenses),
+        "depreciation": float(depreciation),
+        "net_income": float(rents - expenses - depreciation),
+        "accumulated_depreciation": float(accumulated),
+        "adjusted_basis": float(adjusted_basis),
+        "sale": sale,
+    }
+
+
+def replacement_property(
+    relinquished: Dict[str, Any],
+    tax_year: int,
+    replacement_value: Decimal,
+    land_value: Decimal = Decimal("0"),
+) -> Dict[str, Any]:
+    """
+    Basis of property received in a like-kind exchange (section 1031)
+
+    The replacement's basis is its fair market value less the gain deferred
+    from the relinquished property, split between building and land by
+    their shares of the value. The whole basis is depreciated as newly
+    placed in service (the election out of Reg. 1.168(i)-6), and the
+    relinquished property's depreciation carries over for recapture.
+
+    Args:
+        relinquished: Rental record already marked sold with its like_kind_exchange
+        tax_year: Year of the exchange
+        replacement_value: Fair market value of the property received
+        land_value: Part of that value that is land
+
+    Returns:
+        Dict with building_basis, land_value, prior_depreciation, and deferred_gain
+
+    Raises:
+        ValueError: If the land value isn't less than the property's value
+    """
+    if replacement_value <= 0 or not 0 <= land_value < replacement_value:
+        raise ValueError("Replacement value must be positive and more than its land value")
+    year = rental_property_year(relinquished, tax_year)
+    deferred = _amount(year["sale"]["deferred_gain"])
+    basis = replacement_value - deferred
+    building = _cents(basis * (replacement_value - land_value) / replacement_value)
+    return {
+        "building_basis": float(building),
+        "land_value": float(basis - building),
+        "prior_depreciation": float(
+            _amount(year["accumulated_depreciation"]) + _amount(relinquished.get("prior_depreciation"))
+        ),
+        "deferred_gain": float(deferred),
+    }
+
+
+def calculate_rentals(tax_return: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    Schedule E for a return's rental properties
+
+    Properties sold in an earlier year are left out. Passive activity loss
+    limits aren't applied, so a net loss is taken in full.
+
+    Returns:
+        Dict with properties (rental_property_year results), net_income (all
+        properties), and sale_gain (total gain or loss on properties sold)
+    """
+    tax_year = tax_return["tax_year"]
+    properties = [
+        rental_property_year(r, tax_year)
+        for r in tax_return.get("rental_properties", [])
+        if date.fromisoformat(r["placed_in_service"]).year <= tax_year
+        and not (r.get("date_sold") and date.fromisoformat(r["date_sold"]).year < tax_year)
+    ]
+    return {
+        "properties": properties,
+        "net_income": float(sum((_amount(p["net_income"]) for p in properties), Decimal("0"))),
+        "sale_gain": float(sum((_amount(p["sale"]["gain_or_loss"]) for p in properties if p["sale"]), Decimal("0"))),
+    }
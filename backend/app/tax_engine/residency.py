@@ -0,0 +1,137 @@
+"""
+State Residency Days
+Counts days spent in each state from a travel log and checks them against statutory residency thresholds
+"""
+from datetime import date, timedelta
+from typing import Dict, List, Any, Optional, Set
+
+from app.tax_engine.state_tax import NO_INCOME_TAX_STATES
+
+# Days in a state that make someone with a home there a statutory resident (most states use 183)
+STATUTORY_RESIDENCY_DAYS = 183
+STATE_RESIDENCY_DAYS = {"ID": 270, "OR": 200}
+
+# Warn this many days before a threshold is reached
+APPROACHING_MARGIN = 30
+
+
+def residency_threshold(state: str) -> int:
+    """Days in the state that trigger statutory residency"""
+    return STATE_RESIDENCY_DAYS.get(state, STATUTORY_RESIDENCY_DAYS)
+
+
+def _days(entry: Dict[str, Any], year_start: date, year_end: date) -> List[date]:
+    """Calendar days an entry covers within the year"""
+    start = max(date.fromisoformat(entry["start_date"]), year_start)
+    end = min(date.fromisoformat(entry.get("end_date") or entry["start_date"]), year_end)
+    return [start + timedelta(days=n) for n in range((end - start).days + 1)]
+
+
+def count_state_days(
+    travel_log: List[Dict[str, Any]],
+    tax_year: int,
+    home_state: Optional[str] = None,
+    as_of: Optional[date] = None,
+) -> Dict[str, Any]:
+    """
+    Count days per state and flag statutory residency risk
+
+    Any part of a day in a state counts as a day there, so a travel day
+    counts for both states. Days not in the log count for home_state when
+    it's given. Statutory residency usually also requires keeping a home
+    (permanent place of abode) in the state; the warnings assume one.
+
+    Args:
+        travel_log: Entries with state, start_date, and end_date (YYYY-MM-DD)
+        tax_year: Year to count
+        home_state: State of domicile, credited with unlogged days
+        as_of: Count only through this date (defaults to the whole year)
+
+    Returns:
+        Dict with a row per state (days, threshold, status: under,
+        approaching, over, or no_income_tax), unlogged days, and warnings
+    """
+    year_start, year_end = date(tax_year, 1, 1), date(tax_year, 12, 31)
+    if as_of is not None:
+        year_end = min(year_end, as_of)
+    counted_days = max(0, (year_end - year_start).days + 1)
+    remaining_days = (date(tax_year, 12, 31) - max(year_end, year_start - timedelta(days=1))).days
+
+    by_state: Dict[str, Set[date]] = {}
+    logged: Set[date] = set()
+    for entry in travel_log:
+        days = _days(entry, year_start, year_end)
+        by_state.setdefault(entry["state"], set()).update(days)
+        logged.update(days)
+    unlogged = counted_days - len(logged)
+    if home_state:
+        all_days = {year_start + timedelta(days=n) for n in range(counted_days)}
+        by_state.setdefault(home_state, set()).update(all_days - logged)
+
+    states = []
+    warnings = []
+    for state in sorted(by_state, key=lambda s: (-len(by_state[s]), s)):
+        days = len(by_state[state])
+        threshold = residency_threshold(state)
+        if state in NO_INCOME_TAX_STATES:
+            status = "no_income_tax"
+        elif days >= threshold:
+            status = "over"
+        elif days + remaining_days >= threshold and days >= threshold - APPROACHING_MARGIN:
+            status = "approaching"
+        else:
+            status = "under"
+        states.append({
+            "state": state,
+            "days": days,
+            "threshold": threshold,
+            "days_until_threshold": max(0, threshold - days),
+            "home_state": state == home_state,
+            "status": status,
+        })
+        if state == home_state:
+            continue
+        if status == "over":
+            warnings.append(
+                f"{days} days in {state} meets its {threshold}-day test; with a home there, "
+                f"{state} can tax you as a statutory resident"
+            )
+        elif status == "approaching":
+            warnings.append(f"{days} days in {state}; {threshold - days} more would meet its {threshold}-day test")
+
+    residents = [home_state] if home_state and home_state not in NO_INCOME_TAX_STATES else []
+    residents += [row["state"] for row in states if row["status"] == "over" and not row["home_state"]]
+    if len(residents) > 1:
+        warnings.append(
+            f"Dual residency risk: {' and '.join(residents)} could each tax all your income as a resident; "
+            "claim the credit for taxes paid to the other state and keep records of where you were"
+        )
+
+    return {
+        "tax_year": tax_year,
+        "through": year_end.isoformat(),
+        "home_state": home_state,
+        "states": states,
+        "unlogged_days": unlogged,
+        "warnings": warnings,
+    }
+
+
+def return_residency_days(
+    tax_return: Dict[str, Any],
+    home_state: Optional[str] = None,
+    as_of: Optional[date] = None,
+) -> Dict[str, Any]:
+    """
+    count_state_days for a stored return's travel log
+
+    The home state defaults to the return's resident state return, if any.
+    """
+    if home_state is None:
+        home_state = next(
+            (r["state"] for r in tax_return.get("state_returns", []) if r["residency"] == "resident"), None
+        )
+    return count_state_days(
+        tax_return.get("travel_log", []), tax_return["tax_year"],
+        home_state=home_state.upper() if home_state else None, as_of=as_of,
+    )
@@ -0,0 +1,381 @@
+"""
+Return Calculation
+Runs the engine over a stored return: income, SE tax, deductions, credits, and balance due
+"""
+from datetime import date
+from typing import Dict, List, Any, Optional
+from decimal import Decimal, ROUND_CEILING, ROUND_HALF_UP
+
+from app.tax_engine.adoption_credit import calculate_adoption_credit
+from app.tax_engine.alimony import alimony_treatment
+from app.tax_engine.at_risk import at_risk_activities
+from app.tax_engine.carryforwards import capital_loss_limit, net_capital_gain
+from app.tax_engine.dependents import claimed_by_other_parent
+from app.tax_engine.early_distributions import calculate_form_5329
+from app.tax_engine.education_credits import calculate_education_credits
+from app.tax_engine.rental_depreciation import calculate_rentals
+from app.tax_engine.rounding import DEFAULT_ROUNDING_POLICY, round_amounts
+from app.tax_engine.schedule_a import ScheduleACalculator
+from app.tax_engine.schedule_b import tax_exempt_amount
+from app.tax_engine.schedule_c import ScheduleCCalculator
+from app.tax_engine.schedule_se import ScheduleSECalculator
+from app.tax_engine.state_refund import state_refund_worksheet
+from app.tax_engine.tax_calculator import TaxCalculator
+from app.tax_engine.vehicle_expenses import itemized_deduction_records
+
+# Income types taken at their stored amounts (the rest are computed below)
+ORDINARY_INCOME_TYPES = ["wages", "interest", "dividends", "retirement", "other"]
+
+# Taxable social security thresholds (base amount, adjusted base amount); MFS living together is zero
+SOCIAL_SECURITY_THRESHOLDS = {
+    "married_joint": (Decimal("32000"), Decimal("44000")),
+    "married_separate": (Decimal("0"), Decimal("0")),
+}
+SOCIAL_SECURITY_THRESHOLDS_DEFAULT = (Decimal("25000"), Decimal("34000"))
+
+# Child tax credit (under 17 at year end) and credit for other dependents, 2024
+CHILD_TAX_CREDIT = Decimal("2000")
+OTHER_DEPENDENT_CREDIT = Decimal("500")
+CHILD_CREDIT_AGE_LIMIT = 17
+CREDIT_PHASEOUT_START = {"married_joint": Decimal("400000")}
+CREDIT_PHASEOUT_START_DEFAULT = Decimal("200000")
+CREDIT_PHASEOUT_PER_THOUSAND = Decimal("50")
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def _cents(value: Decimal) -> Decimal:
+    return value.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+
+
+def calculate_business_schedules(tax_return: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    Schedule C for each business and Schedule SE for each owner with self-employment tax
+
+    Self-employment income sources not linked to a business (business_id) count
+    toward their owner's self-employment earnings as they are.
+
+    Args:
+        tax_return: Return dict from ReturnStore
+
+    Returns:
+        Dict with businesses (Schedule C results), self_employment (Schedule SE
+        results, one per owner, with owner set), and unlinked_income per owner
+    """
+    sources = tax_return.get("income_sources", [])
+    businesses = tax_return.get("businesses", [])
+    business_ids = {b["id"] for b in businesses}
+
+    schedules = []
+    if businesses:
+        calculator = ScheduleCCalculator(tax_return["tax_year"])
+        schedules = [calculator.calculate(b, sources) for b in businesses]
+
+    unlinked: Dict[str, Decimal] = {}
+    for source in sources:
+        if source["type"] == "self_employment" and source.get("business_id") not in business_ids:
+            owner = source.get("owner", "taxpayer")
+            unlinked[owner] = unlinked.get(owner, Decimal("0")) + _amount(source.get("amount"))
+
+    self_employment = []
+    for owner in ("taxpayer", "spouse"):
+        owned = [s for s in schedules if s["owner"] == owner]
+        if not owned and owner not in unlinked:
+            continue
+        net_profit = sum((_amount(s["net_profit"]) for s in owned), unlinked.get(owner, Decimal("0")))
+        # W-2 social security wages (box 3) use up the wage base; box 1 wages stand in when missing
+        ss_wages = sum(
+            (_amount(s.get("social_security_wages", s.get("amount")))
+             for s in sources
+             if s["type"] == "wages" and s.get("owner", "taxpayer") == owner),
+            Decimal("0"),
+        )
+        schedule = ScheduleSECalculator(tax_return["tax_year"]).calculate(net_profit, ss_wages)
+        if schedule["required"]:
+            self_employment.append({"owner": owner, **schedule})
+
+    return {
+        "businesses": schedules,
+        "self_employment": self_employment,
+        "unlinked_income": {owner: float(amount) for owner, amount in unlinked.items()},
+    }
+
+
+def calculate_at_risk(
+    tax_return: Dict[str, Any],
+    business_schedules: Optional[List[Dict[str, Any]]] = None,
+    rental_years: Optional[List[Dict[str, Any]]] = None,
+) -> List[Dict[str, Any]]:
+    """
+    At-risk limits for a return's businesses and rentals
+
+    Args:
+        tax_return: Return dict from ReturnStore
+        business_schedules: Schedule C results, if already calculated
+        rental_years: Rental property results for the year, if already calculated
+
+    Returns:
+        at_risk_activities rows, one per business and rental on the return
+    """
+    if business_schedules is None:
+        business_schedules = calculate_business_schedules(tax_return)["businesses"]
+    if rental_years is None:
+        rental_years = calculate_rentals(tax_return)["properties"]
+    return at_risk_activities(
+        tax_return.get("businesses", []), business_schedules,
+        tax_return.get("rental_properties", []), rental_years,
+    )
+
+
+def calculate_return(
+    tax_return: Dict[str, Any],
+    require_itemized: bool = False,
+    rounding: str = DEFAULT_ROUNDING_POLICY,
+) -> Dict[str, Any]:
+    """
+    Calculate a stored return from its records
+
+    Qualified dividends (a dividend source's qualified_dividends) get the
+    0/15/20% rates. Tax-exempt interest (sources marked tax_exempt, 1099-INT
+    box 8, and 1099-DIV box 12) is reported but not taxed, though it counts
+    toward the income that makes social security taxable. A state or local
+    income tax refund counts only as far as last year's deduction of the tax
+    lowered last year's tax (state_refund_worksheet). The adoption credit
+    (Form 8839) follows the education and child tax credits. Capital gains
+    are taxed at ordinary rates, and the refundable part of the child tax
+    credit isn't computed.
+
+    Args:
+        tax_return: Return dict from ReturnStore
+        require_itemized: Itemize even if the standard deduction is larger
+        rounding: Rounding policy for the amounts returned (cents or whole_dollars)
+
+    Returns:
+        Dict with income by line, AGI, deduction, taxable income, income tax,
+        credits, SE tax, total tax, withholding, estimated tax payments made
+        for the year, and refund_or_owed (positive for a refund)
+
+    Raises:
+        ValueError: If the year or filing status isn't supported or a record is invalid
+    """
+    tax_year = tax_return["tax_year"]
+    filing_status = tax_return["filing_status"]
+    calculator = TaxCalculator(tax_year)
+    sources = tax_return.get("income_sources", [])
+    notes: List[str] = []
+
+    income: Dict[str, Decimal] = {income_type: Decimal("0") for income_type in ORDINARY_INCOME_TYPES}
+    withholding = Decimal("0")
+    social_security = Decimal("0")
+    qualified_dividends = Decimal("0")
+    tax_exempt_interest = Decimal("0")
+    for source in sources:
+        amount = _amount(source.get("amount"))
+        withholding += _amount(source.get("withholding"))
+        tax_exempt_interest += tax_exempt_amount(source)
+        if source.get("tax_exempt") and source["type"] in ("interest", "dividends"):
+            continue
+        if source["type"] in income:
+            income[source["type"]] += amount
+            if source["type"] == "dividends":
+                qualified_dividends += min(amount, _amount(source.get("qualified_dividends")))
+        elif source["type"] == "social_security":
+            social_security += amount
+    for lot in tax_return.get("capital_transactions", []):
+        if lot.get("gain_or_loss") is None:
+            notes.append(f"Cost basis is missing for {lot.get('description') or 'a capital transaction'}; it was left out")
+
+    # A gain on selling a rental is section 1231 gain, taxed like a capital gain; a loss is ordinary
+    rentals = calculate_rentals(tax_return)
+    sale_gain = _amount(rentals["sale_gain"])
+    capital = net_capital_gain(tax_return, rentals)
+    if any(p["sale"] and p["sale"]["unrecaptured_section_1250_gain"] for p in rentals["properties"]):
+        notes.append("Unrecaptured section 1250 gain from a rental sale is taxed at ordinary rates here, not the 25% maximum")
+
+    limit = capital_loss_limit(filing_status)
+    income["capital_gains"] = max(capital, -limit)
+
+    business = calculate_business_schedules(tax_return)
+    at_risk = calculate_at_risk(tax_return, business["businesses"], rentals["properties"])
+    allowed = {a["activity_id"]: _amount(a["allowed"]) for a in at_risk}
+    income["business"] = sum((allowed[s["business_id"]] for s in business["businesses"]), Decimal("0"))
+    if rentals["properties"]:
+        rental_income = sum((allowed[p["property_id"]] for p in rentals["properties"]), Decimal("0"))
+        income["rental"] = rental_income + min(sale_gain, Decimal("0"))
+        if rental_income < 0:
+            notes.append("Rental losses were taken in full; passive activity loss limits weren't applied")
+    for activity in at_risk:
+        if activity["limited"]:
+            notes.append(
+                f"${activity['carryforward']:,.2f} of {activity['name'] or 'an activity'}'s loss is beyond the "
+                "amount at risk and carries forward"
+            )
+    income["self_employment"] = sum((_amount(v) for v in business["unlinked_income"].values()), Decimal("0"))
+    alimony = alimony_treatment(tax_return)
+    income["alimony"] = alimony["taxable_received"]
+    notes += alimony["notes"]
+    # Schedule 1, line 1: the part of a state or local income tax refund last year's deduction benefited from
+    state_refund = state_refund_worksheet(tax_return)
+    income["state_refund"] = _amount(state_refund["taxable_amount"])
+    if state_refund["status"] == "needs_prior_year":
+        notes += state_refund["notes"]
+
+    other_income = sum(income.values(), Decimal("0"))
+    # Tax-exempt interest is part of the provisional income the worksheet tests
+    income["social_security"] = _taxable_social_security(
+        social_security, other_income + tax_exempt_interest, filing_status
+    )
+
+    self_employment_tax = sum((_amount(s["self_employment_tax"]) for s in business["self_employment"]), Decimal("0"))
+    adjustments = sum((_amount(s["deductible_half"]) for s in business["self_employment"]), Decimal("0"))
+    adjustments += alimony["deductible_paid"]
+    total_income = sum(income.values(), Decimal("0"))
+    agi = max(Decimal("0"), total_income - adjustments)
+
+    schedule_a = None
+    itemized = None
+    deductions = itemized_deduction_records(tax_return)
+    if deductions:
+        schedule_a = ScheduleACalculator(tax_year).calculate(deductions, agi, filing_status)
+        itemized = _amount(schedule_a["total_itemized_deductions"])
+
+    dependents = []
+    for dependent in tax_return.get("dependents", []):
+        if claimed_by_other_parent(dependent, tax_year):
+            notes.append(f"{dependent.get('name') or 'A dependent'} is claimed by the other parent this year")
+        else:
+            dependents.append(dependent)
+    tax = calculator.calculate_individual_tax(
+        agi, filing_status, itemized_deductions=itemized, dependents=len(dependents), require_itemized=require_itemized,
+        qualified_dividends=qualified_dividends,
+    )
+    income_tax = _amount(tax["tax_liability"])
+
+    # Education credits come before the child tax credit (credit limit worksheet A)
+    education = None
+    education_applied = refundable = Decimal("0")
+    if tax_return.get("students"):
+        education = calculate_education_credits(tax_return["students"], agi, filing_status, tax_year)
+        education_applied = min(income_tax, _amount(education["nonrefundable"]))
+        refundable = _amount(education["refundable"])
+        education = {**education, "applied": float(education_applied)}
+        notes += education["notes"]
+
+    credits = _dependent_credits(dependents, tax_year, agi, filing_status, notes)
+    credits["applied"] = min(income_tax - education_applied, credits["total"])
+
+    # The adoption credit comes after the child tax credit; what the tax can't absorb carries forward
+    adoption = None
+    adoption_applied = Decimal("0")
+    adoption_carryforward = _amount((tax_return.get("carryforwards") or {}).get("adoption_credit"))
+    if tax_return.get("adoptions") or adoption_carryforward:
+        adoption = calculate_adoption_credit(
+            tax_return.get("adoptions", []), agi, filing_status, tax_year, adoption_carryforward,
+            income_tax - education_applied - credits["applied"],
+        )
+        adoption_applied = _amount(adoption["credit"])
+        notes += adoption["notes"]
+
+    # Form 5329 additional tax on early distributions (Schedule 2, line 8)
+    early_distribution_tax = _amount(calculate_form_5329(tax_return)["lines"]["4"])
+    total_tax = (
+        income_tax - education_applied - credits["applied"] - adoption_applied
+        + self_employment_tax + early_distribution_tax
+    )
+    # Form 1040 line 26: estimated tax paid for this year, logged on last year's return and carried over when cloned
+    estimated_payments = sum(
+        (_amount(p.get("amount")) for p in tax_return.get("estimated_payments", []) if p.get("tax_year") == tax_year),
+        Decimal("0"),
+    )
+    refund_or_owed = withholding + estimated_payments + refundable - total_tax
+
+    result = {
+        "tax_year": tax_year,
+        "filing_status": filing_status,
+        "income": {line: float(_cents(amount)) for line, amount in income.items()},
+        "qualified_dividends": float(_cents(qualified_dividends)),
+        "tax_exempt_interest": float(_cents(tax_exempt_interest)),
+        "total_income": float(_cents(total_income)),
+        "adjustments": float(_cents(adjustments)),
+        "agi": float(_cents(agi)),
+        "deduction_type": tax["deduction_type"],
+        "deduction_amount": tax["deduction_amount"],
+        "taxable_income": tax["taxable_income"],
+        "income_tax": float(income_tax),
+        "qualified_dividends_worksheet": tax["qualified_dividends_worksheet"],
+        "credits": {name: float(_cents(amount)) for name, amount in credits.items()},
+        "self_employment_tax": float(_cents(self_employment_tax)),
+        "early_distribution_tax": float(_cents(early_distribution_tax)),
+        "total_tax": float(_cents(total_tax)),
+        "total_withholding": float(_cents(withholding)),
+        "estimated_payments": float(_cents(estimated_payments)),
+        "refund_or_owed": float(_cents(refund_or_owed)),
+        "capital_loss_carryforward": float(_cents(max(Decimal("0"), -capital - limit))),
+        "schedule_a": schedule_a,
+        "businesses": business["businesses"],
+        "rental_properties": rentals["properties"],
+        "at_risk": at_risk,
+        "education_credits": education,
+        "adoption_credit": adoption,
+        "self_employment": business["self_employment"],
+        "notes": notes,
+    }
+    return round_amounts(result, rounding)
+
+
+def _taxable_social_security(benefits: Decimal, other_income: Decimal, filing_status: str) -> Decimal:
+    """Taxable part of social security benefits (Form 1040 social security worksheet)"""
+    if benefits <= 0:
+        return Decimal("0")
+    base, adjusted_base = SOCIAL_SECURITY_THRESHOLDS.get(filing_status, SOCIAL_SECURITY_THRESHOLDS_DEFAULT)
+    provisional = other_income + benefits / 2
+    if provisional <= base:
+        return Decimal("0")
+    if provisional <= adjusted_base:
+        return _cents(min((provisional - base) / 2, benefits / 2))
+    return _cents(min(
+        benefits * Decimal("0.85"),
+        (provisional - adjusted_base) * Decimal("0.85") + min((adjusted_base - base) / 2, benefits / 2),
+    ))
+
+
+def _dependent_credits(
+    dependents: List[Dict[str, Any]],
+    tax_year: int,
+    agi: Decimal,
+    filing_status: str,
+    notes: List[str],
+) -> Dict[str, Decimal]:
+    """Child tax credit and credit for other dependents after the AGI phase-out"""
+    year_end = date(tax_year, 12, 31)
+    children = others = 0
+    for dependent in dependents:
+        birth_date = dependent.get("birth_date")
+        if not birth_date:
+            notes.append(f"{dependent.get('name', 'A dependent')} has no birth date; counted for the $500 other dependent credit")
+            others += 1
+            continue
+        born = date.fromisoformat(birth_date)
+        age = year_end.year - born.year - ((year_end.month, year_end.day) < (born.month, born.day))
+        if age < CHILD_CREDIT_AGE_LIMIT:
+            children += 1
+        else:
+            others += 1
+
+    child_credit = CHILD_TAX_CREDIT * children
+    other_credit = OTHER_DEPENDENT_CREDIT * others
+    threshold = CREDIT_PHASEOUT_START.get(filing_status, CREDIT_PHASEOUT_START_DEFAULT)
+    if agi > threshold:
+        # $50 per $1,000 (or part of $1,000) over the threshold, taken from the child credit first
+        thousands = ((agi - threshold) / 1000).to_integral_value(rounding=ROUND_CEILING)
+        reduction = CREDIT_PHASEOUT_PER_THOUSAND * thousands
+        other_credit = max(Decimal("0"), other_credit - max(Decimal("0"), reduction - child_credit))
+        child_credit = max(Decimal("0"), child_credit - reduction)
+
+    return {
+        "child_tax_credit": child_credit,
+        "other_dependent_credit": other_credit,
+        "total": child_credit + other_credit,
+    }
@@ -0,0 +1,257 @@
+"""
+Audit Risk Scoring
+Rules-based check of a return against known audit triggers, with weighted findings (no AI involved)
+"""
+from decimal import Decimal, ROUND_HALF_UP
+from typing import Dict, List, Any, Optional
+
+from app.tax_engine.peer_comparison import compare_with_peers
+from app.tax_engine.return_calculation import calculate_business_schedules, calculate_return
+from app.tax_engine.schedule_c import MEALS_DEDUCTIBLE_SHARE
+from app.tax_engine.validation import summarize_return
+from app.tax_engine.vehicle_expenses import compare_vehicle_methods
+
+# Finding weights (points toward a 0-100 score)
+WEIGHTS = {
+    "schedule_c_loss": 5,
+    "schedule_c_repeated_losses": 25,
+    "charitable_ratio": 10,
+    "charitable_ratio_high": 20,
+    "round_numbers": 10,
+    "home_office_with_wages": 10,
+    "vehicle_full_business_use": 10,
+    "high_income": 10,
+    "deductions_above_peers": 10,
+}
+
+# Score at or above which each level starts
+RISK_LEVELS = [("high", 50), ("moderate", 20), ("low", 0)]
+
+# Charitable gifts above these shares of total income stand out against typical returns
+CHARITABLE_RATIO = Decimal("0.10")
+CHARITABLE_RATIO_HIGH = Decimal("0.30")
+
+# Round-number check: amounts of at least MIN are round when they're multiples of UNIT
+ROUND_NUMBER_UNIT = Decimal("100")
+ROUND_NUMBER_MIN = Decimal("100")
+ROUND_NUMBER_MIN_COUNT = 5
+ROUND_NUMBER_SHARE = Decimal("0.5")
+
+# Income at which examination rates climb noticeably
+HIGH_INCOME = Decimal("1000000")
+
+# The hobby-loss presumption wants a profit in 3 of 5 years
+HOBBY_LOSS_YEARS = 3
+
+# Peer comparison categories that get their own finding (charity has the ratio rules; the total double counts)
+PEER_RISK_CATEGORIES = ["medical", "taxes", "interest"]
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def _money(value: Decimal) -> str:
+    return f"${value:,.2f}"
+
+
+def _percent(value: Decimal) -> str:
+    return f"{(value * 100).quantize(Decimal('1'), rounding=ROUND_HALF_UP)}%"
+
+
+def _rough_net_profit(business: Dict[str, Any], sources: List[Dict[str, Any]]) -> Decimal:
+    """
+    Schedule C net profit for any year, without vehicle costs
+
+    Used for prior years, which the engine can't calculate; leaving vehicles
+    out can only understate a loss.
+    """
+    receipts = _amount(business.get("gross_receipts")) + sum(
+        (_amount(s.get("amount")) for s in sources
+         if s["type"] == "self_employment" and s.get("business_id") == business["id"]),
+        Decimal("0"),
+    )
+    expenses = sum(
+        (_amount(e.get("amount")) * (MEALS_DEDUCTIBLE_SHARE if e["category"] == "meals" else 1)
+         for e in business.get("expenses", [])),
+        Decimal("0"),
+    )
+    return (
+        receipts - _amount(business.get("returns_allowances")) - _amount(business.get("cost_of_goods_sold"))
+        + _amount(business.get("other_income")) - expenses - _amount(business.get("home_office"))
+    )
+
+
+def _business_results(tax_return: Dict[str, Any]) -> Dict[str, Decimal]:
+    """Net profit per business ID, from the engine when it supports the year"""
+    try:
+        schedules = calculate_business_schedules(tax_return)["businesses"]
+        return {s["business_id"]: _amount(s["net_profit"]) for s in schedules}
+    except ValueError:
+        sources = tax_return.get("income_sources", [])
+        return {b["id"]: _rough_net_profit(b, sources) for b in tax_return.get("businesses", [])}
+
+
+def _total_income(tax_return: Dict[str, Any]) -> Decimal:
+    """Total income from the engine, or the income sources' sum for years it doesn't support"""
+    try:
+        return _amount(calculate_return(tax_return)["total_income"])
+    except ValueError:
+        return _amount(summarize_return(tax_return)["total_income"])
+
+
+def _loss_years(business: Dict[str, Any], prior_returns: List[Dict[str, Any]]) -> List[int]:
+    """Prior years in which the same business (by ID, or else name) had a loss"""
+    years = []
+    for prior in prior_returns:
+        match = next(
+            (b for b in prior.get("businesses", [])
+             if b["id"] == business["id"] or b["name"].strip().lower() == business["name"].strip().lower()),
+            None,
+        )
+        if match and _rough_net_profit(match, prior.get("income_sources", [])) < 0:
+            years.append(prior["tax_year"])
+    return sorted(years)
+
+
+def _round_amounts(tax_return: Dict[str, Any]) -> List[Decimal]:
+    """Entered deduction and business amounts the round-number check looks at"""
+    amounts = [_amount(d.get("amount")) for d in tax_return.get("deductions", [])]
+    for business in tax_return.get("businesses", []):
+        amounts += [_amount(e.get("amount")) for e in business.get("expenses", [])]
+        amounts += [_amount(business.get(field)) for field in ("gross_receipts", "home_office")]
+    return [amount for amount in amounts if amount >= ROUND_NUMBER_MIN]
+
+
+def assess_audit_risk(
+    tax_return: Dict[str, Any],
+    prior_returns: Optional[List[Dict[str, Any]]] = None,
+    peer_dataset: Optional[Dict[str, Any]] = None,
+) -> Dict[str, Any]:
+    """
+    Score a return against common audit triggers
+
+    Each rule that fires adds its weight to the score (capped at 100). This
+    is a checklist of what examiners are known to look at, not a prediction;
+    a finding with good records behind it is fine to file.
+
+    Args:
+        tax_return: Return dict from ReturnStore
+        prior_returns: Earlier years' returns for the same taxpayer (for loss history)
+        peer_dataset: SOI averages to compare deductions with (defaults to the embedded dataset)
+
+    Returns:
+        Dict with score, level (low, moderate, high), and findings
+        ({code, weight, title, explanation}), heaviest first
+    """
+    prior_returns = prior_returns or []
+    findings: List[Dict[str, Any]] = []
+
+    def add(code: str, title: str, explanation: str, **details: Any) -> None:
+        findings.append({"code": code, "weight": WEIGHTS[code], "title": title, "explanation": explanation, **details})
+
+    sources = tax_return.get("income_sources", [])
+    wages_by_owner = {
+        owner: sum((_amount(s.get("amount")) for s in sources
+                    if s["type"] == "wages" and s.get("owner", "taxpayer") == owner), Decimal("0"))
+        for owner in ("taxpayer", "spouse")
+    }
+
+    net_profits = _business_results(tax_return)
+    for business in tax_return.get("businesses", []):
+        net_profit = net_profits.get(business["id"], Decimal("0"))
+        if net_profit < 0:
+            loss_years = _loss_years(business, prior_returns) + [tax_return["tax_year"]]
+            if len(loss_years) >= HOBBY_LOSS_YEARS:
+                add(
+                    "schedule_c_repeated_losses",
+                    f"{business['name']} has reported a loss in {len(loss_years)} years",
+                    f"Losses in {', '.join(map(str, loss_years))}. Without a profit in 3 of 5 years the IRS may "
+                    "treat the business as a hobby and disallow the losses; keep records showing a profit motive.",
+                    business_id=business["id"],
+                    loss_years=loss_years,
+                )
+            else:
+                add(
+                    "schedule_c_loss",
+                    f"{business['name']} shows a {_money(-net_profit)} loss",
+                    "Schedule C losses that offset other income draw attention; make sure every expense is documented.",
+                    business_id=business["id"],
+                )
+
+        owner = business.get("owner", "taxpayer")
+        if _amount(business.get("home_office")) > 0 and wages_by_owner.get(owner):
+            add(
+                "home_office_with_wages",
+                f"Home office deduction for {business['name']} alongside W-2 wages",
+                "The office must be used regularly and exclusively for the business; employees can't deduct a "
+                "home office for their W-2 job.",
+                business_id=business["id"],
+            )
+
+        for vehicle in business.get("vehicles", []):
+            try:
+                comparison = compare_vehicle_methods(vehicle, tax_return["tax_year"])
+            except ValueError:
+                continue
+            if comparison["total_miles"] and comparison["business_use_percent"] >= 100:
+                add(
+                    "vehicle_full_business_use",
+                    f"{vehicle.get('description') or 'A vehicle'} is claimed at 100% business use",
+                    "Full business use is rarely accepted without a contemporaneous mileage log and another "
+                    "vehicle for personal driving.",
+                    business_id=business["id"],
+                )
+
+    income = _total_income(tax_return)
+    charitable = sum(
+        (_amount(d.get("amount")) for d in tax_return.get("deductions", []) if d["category"] == "charitable"),
+        Decimal("0"),
+    )
+    if income > 0 and charitable / income > CHARITABLE_RATIO:
+        ratio = charitable / income
+        add(
+            "charitable_ratio_high" if ratio > CHARITABLE_RATIO_HIGH else "charitable_ratio",
+            f"Charitable gifts are {_percent(ratio)} of income",
+            f"{_money(charitable)} in gifts is well above what returns at this income usually claim; keep "
+            "receipts, acknowledgment letters for gifts of $250 or more, and Form 8283 for noncash gifts.",
+        )
+
+    try:
+        peers = compare_with_peers(tax_return, peer_dataset)
+    except ValueError:
+        peers = None
+    for category in (peers["categories"] if peers else []):
+        if category["category"] in PEER_RISK_CATEGORIES and category["flag"] == "unusually_high":
+            add(
+                "deductions_above_peers",
+                f"{category['label']} are {category['ratio']:g} times the average for AGI of {peers['band']['label']}",
+                f"{_money(_amount(category['amount']))} against an IRS average of "
+                f"{_money(_amount(category['peer_average']))} for itemizers with similar income; keep the bills, "
+                "statements, and receipts behind it.",
+                category=category["category"],
+            )
+
+    amounts = _round_amounts(tax_return)
+    round_count = sum(1 for amount in amounts if amount % ROUND_NUMBER_UNIT == 0)
+    if len(amounts) >= ROUND_NUMBER_MIN_COUNT and round_count >= len(amounts) * ROUND_NUMBER_SHARE:
+        add(
+            "round_numbers",
+            f"{round_count} of {len(amounts)} amounts are round hundreds",
+            "Many round figures suggest estimates rather than records; enter amounts from receipts and statements.",
+        )
+
+    if income >= HIGH_INCOME:
+        add(
+            "high_income",
+            f"Income of {_money(income)}",
+            "Returns with income over $1 million are examined several times as often as average.",
+        )
+
+    findings.sort(key=lambda f: -f["weight"])
+    score = min(100, sum(f["weight"] for f in findings))
+    return {
+        "score": score,
+        "level": next(level for level, floor in RISK_LEVELS if score >= floor),
+        "findings": findings,
+    }
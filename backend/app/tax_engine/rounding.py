@@ -0,0 +1,86 @@
+"""
+Rounding Policy
+Exact cents or IRS whole-dollar rounding for engine results, exports, and forms
+"""
+from decimal import Decimal, ROUND_HALF_UP
+from typing import Any, Union
+
+# rounding_policy setting -> quantum
+ROUNDING_POLICIES = {
+    "cents": Decimal("0.01"),
+    # IRS: drop amounts under 50 cents, round 50 to 99 cents up to the next dollar
+    "whole_dollars": Decimal("1"),
+}
+
+DEFAULT_ROUNDING_POLICY = "cents"
+
+# Result keys holding rates, percentages, ratios, or miles rather than amounts
+NON_AMOUNT_KEYS = {
+    "rate",
+    "tax_rate",
+    "effective_tax_rate",
+    "applicable_figure",
+    "fpl_percent",
+    "ratio",
+    "income_ratio",
+    "business_use_percent",
+    "business_miles",
+    "total_miles",
+}
+
+
+def validate_rounding_policy(policy: str) -> str:
+    """
+    Check a rounding policy name
+
+    Raises:
+        ValueError: If the policy is unknown
+    """
+    if policy not in ROUNDING_POLICIES:
+        raise ValueError(f"Invalid rounding policy: {policy}. Must be one of: {', '.join(ROUNDING_POLICIES)}")
+    return policy
+
+
+def round_amount(value: Union[float, int, Decimal], policy: str = DEFAULT_ROUNDING_POLICY) -> Decimal:
+    """
+    Round an amount under a policy (half up, so $0.50 becomes $1)
+
+    Args:
+        value: Amount
+        policy: Key of ROUNDING_POLICIES
+
+    Returns:
+        Rounded amount
+    """
+    return Decimal(str(value)).quantize(ROUNDING_POLICIES[validate_rounding_policy(policy)], rounding=ROUND_HALF_UP)
+
+
+def round_amounts(data: Any, policy: str = DEFAULT_ROUNDING_POLICY) -> Any:
+    """
+    Round every amount in an engine result
+
+    Floats in nested dicts and lists are rounded, except under NON_AMOUNT_KEYS;
+    ints, strings, and None pass through. Totals are rounded as computed from
+    the unrounded lines, as the IRS instructions ask.
+
+    Args:
+        data: Result dict (or list) from the engine
+        policy: Key of ROUNDING_POLICIES
+
+    Returns:
+        A rounded copy (data itself under the cents policy, which the engine already uses)
+    """
+    validate_rounding_policy(policy)
+    if policy == DEFAULT_ROUNDING_POLICY:
+        return data
+    return _round(data, policy)
+
+
+def _round(data: Any, policy: str) -> Any:
+    if isinstance(data, dict):
+        return {key: value if key in NON_AMOUNT_KEYS else _round(value, policy) for key, value in data.items()}
+    if isinstance(data, list):
+        return [_round(item, policy) for item in data]
+    if isinstance(data, float):
+        return float(round_amount(data, policy))
+    return data
@@ -0,0 +1,100 @@
+"""
+Golden Scenarios
+Worked tax situations with hand-verified results, run through the engine to catch regressions
+"""
+import json
+from decimal import Decimal
+from pathlib import Path
+from typing import Dict, List, Any, Optional
+
+from app.tax_engine.return_calculation import calculate_return
+
+# One scenario per JSON file; add a file here for each credit or deduction the engine learns
+SCENARIO_DIR = Path(__file__).parent / "data" / "scenarios"
+
+# Expected amounts are to the cent
+TOLERANCE = Decimal("0.005")
+
+# Record lists a scenario's return can hold (each gets an ID if it has none)
+RECORD_LISTS = ["income_sources", "deductions", "dependents", "businesses"]
+
+
+def load_scenarios(directory: Optional[str] = None) -> List[Dict[str, Any]]:
+    """
+    Read every scenario file in a directory, in file name order
+
+    Args:
+        directory: Folder of *.json scenarios (defaults to SCENARIO_DIR)
+
+    Returns:
+        Scenarios, each with its file name added as "file"
+
+    Raises:
+        ValueError: If a file isn't valid JSON or is missing name, source, return, or expected
+    """
+    scenarios = []
+    for path in sorted(Path(directory or SCENARIO_DIR).glob("*.json")):
+        try:
+            scenario = json.loads(path.read_text(encoding="utf-8"))
+        except json.JSONDecodeError as e:
+            raise ValueError(f"{path.name}: invalid JSON ({e})")
+        missing = [key for key in ("name", "source", "return", "expected") if key not in scenario]
+        if missing:
+            raise ValueError(f"{path.name}: missing {', '.join(missing)}")
+        scenarios.append({**scenario, "file": path.name})
+    return scenarios
+
+
+def _field(calculation: Dict[str, Any], path: str) -> Any:
+    """A calculation value by dotted path, e.g. credits.child_tax_credit"""
+    value: Any = calculation
+    for part in path.split("."):
+        if not isinstance(value, dict) or part not in value:
+            raise KeyError(path)
+        value = value[part]
+    return value
+
+
+def run_scenario(scenario: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    Calculate a scenario's return and compare it with the expected results
+
+    The return is built from the scenario's "return" (tax_year,
+    filing_status, income_sources, deductions, dependents, businesses),
+    and each key of "expected" is a calculation field, dotted for nested
+    ones. Amounts must match to the cent; other values exactly.
+
+    Args:
+        scenario: Dict with name, source (where the expected numbers come from), return, and expected
+
+    Returns:
+        Dict with name, passed, mismatches (field, expected, actual), and the full calculation
+    """
+    tax_return: Dict[str, Any] = {"return_id": "scenario", "tax_year": 2024, "filing_status": "single"}
+    tax_return.update(json.loads(json.dumps(scenario["return"])))
+    for key in RECORD_LISTS:
+        for index, record in enumerate(tax_return.setdefault(key, []), start=1):
+            record.setdefault("id", f"{key}_{index}")
+    calculation = calculate_return(tax_return)
+
+    mismatches = []
+    for path, expected in scenario["expected"].items():
+        try:
+            actual = _field(calculation, path)
+        except KeyError:
+            mismatches.append({"field": path, "expected": expected, "actual": None})
+            continue
+        numeric = isinstance(expected, (int, float)) and isinstance(actual, (int, float))
+        if numeric and not isinstance(expected, bool):
+            matches = abs(Decimal(str(actual)) - Decimal(str(expected))) < TOLERANCE
+        else:
+            matches = actual == expected
+        if not matches:
+            mismatches.append({"field": path, "expected": expected, "actual": actual})
+
+    return {
+        "name": scenario["name"],
+        "passed": not mismatches,
+        "mismatches": mismatches,
+        "calculation": calculation,
+    }
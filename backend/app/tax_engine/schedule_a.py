@@ -0,0 +1,147 @@
+"""
+Schedule A Engine
+Itemized deductions for 2024 with the medical floor, SALT cap, and charitable AGI limits
+"""
+from typing import Dict, List, Any
+from decimal import Decimal, ROUND_HALF_UP
+
+from app.tax_engine.tax_calculator import FilingStatus, TaxBrackets
+
+# Medical expenses are deductible only above this share of AGI (line 3)
+MEDICAL_AGI_FLOOR = Decimal("0.075")
+
+# State and local tax deduction cap (line 5e)
+SALT_CAP = Decimal("10000")
+SALT_CAP_MARRIED_SEPARATE = Decimal("5000")
+
+# Charitable contribution limits as a share of AGI (gifts to public charities)
+CASH_CONTRIBUTION_LIMIT = Decimal("0.60")
+NONCASH_CONTRIBUTION_LIMIT = Decimal("0.50")
+
+# Line 5a/5b/5c: the tax_type a state_local_tax deduction can carry
+SALT_TAX_TYPES = {"income": "5a", "sales": "5a", "real_estate": "5b", "personal_property": "5c"}
+
+
+def _cents(value: Decimal) -> Decimal:
+    return value.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+
+
+class ScheduleACalculator:
+    """Schedule A (Form 1040) itemized deductions calculator"""
+
+    def __init__(self, tax_year: int = 2024):
+        self.tax_year = tax_year
+
+        if tax_year != 2024:
+            raise ValueError(f"Only 2024 tax year is currently supported, got {tax_year}")
+
+    def calculate(
+        self,
+        deductions: List[Dict[str, Any]],
+        agi: Decimal,
+        filing_status: str,
+    ) -> Dict[str, Any]:
+        """
+        Fill Schedule A from a return's deductions
+
+        state_local_tax deductions may carry tax_type (income, sales, real_estate,
+        personal_property; default income) and charitable ones non_cash=True.
+
+        Args:
+            deductions: Deduction records from ReturnStore
+            agi: Adjusted gross income (Form 1040 line 11)
+            filing_status: Filing status value
+
+        Returns:
+            Dict with Schedule A lines, the total, the standard deduction for
+            comparison, whether itemizing is better, and any charitable carryforward
+        """
+        if agi < 0:
+            raise ValueError("AGI cannot be negative")
+        try:
+            status = FilingStatus(filing_status)
+        except ValueError:
+            raise ValueError(
+                f"Invalid filing status: {filing_status}. "
+                f"Must be one of: {', '.join([s.value for s in FilingStatus])}"
+            )
+
+        totals: Dict[str, Decimal] = {
+            "medical": Decimal("0"), "5a": Decimal("0"), "5b": Decimal("0"), "5c": Decimal("0"),
+            "mortgage_interest": Decimal("0"), "cash": Decimal("0"), "noncash": Decimal("0"), "other": Decimal("0"),
+        }
+        notes: List[str] = []
+        for deduction in deductions:
+            amount = Decimal(str(deduction.get("amount") or 0))
+            category = deduction["category"]
+            if category == "state_local_tax":
+                tax_type = deduction.get("tax_type", "income")
+                if tax_type not in SALT_TAX_TYPES:
+                    raise ValueError(
+                        f"Invalid tax_type: {tax_type}. Must be one of: {', '.join(SALT_TAX_TYPES)}"
+                    )
+                totals[SALT_TAX_TYPES[tax_type]] += amount
+            elif category == "charitable":
+                totals["noncash" if deduction.get("non_cash") else "cash"] += amount
+            else:
+                totals[category] += amount
+
+        lines: Dict[str, Decimal] = {}
+
+        # Medical and dental expenses
+        lines["1"] = totals["medical"]
+        lines["2"] = agi
+        lines["3"] = _cents(agi * MEDICAL_AGI_FLOOR)
+        lines["4"] = max(Decimal("0"), lines["1"] - lines["3"])
+
+        # Taxes you paid
+        lines["5a"], lines["5b"], lines["5c"] = totals["5a"], totals["5b"], totals["5c"]
+        lines["5d"] = lines["5a"] + lines["5b"] + lines["5c"]
+        cap = SALT_CAP_MARRIED_SEPARATE if status == FilingStatus.MARRIED_SEPARATE else SALT_CAP
+        lines["5e"] = min(lines["5d"], cap)
+        if lines["5d"] > cap:
+            notes.append(f"State and local taxes of ${lines['5d']:,.2f} are capped at ${cap:,.0f}")
+        lines["6"] = Decimal("0")
+        lines["7"] = lines["5e"] + lines["6"]
+
+        # Interest you paid
+        lines["8a"] = totals["mortgage_interest"]
+        lines["8e"] = lines["8a"]
+        lines["10"] = lines["8e"]
+
+        # Gifts to charity
+        cash_allowed = min(totals["cash"], _cents(agi * CASH_CONTRIBUTION_LIMIT))
+        noncash_allowed = min(
+            totals["noncash"],
+            _cents(agi * NONCASH_CONTRIBUTION_LIMIT),
+            _cents(agi * CASH_CONTRIBUTION_LIMIT) - cash_allowed,
+        )
+        lines["11"] = cash_allowed
+        lines["12"] = noncash_allowed
+        lines["13"] = Decimal("0")
+        lines["14"] = lines["11"] + lines["12"] + lines["13"]
+        carryforward = totals["cash"] + totals["noncash"] - lines["14"]
+        if carryforward > 0:
+            notes.append(f"${carryforward:,.2f} of charitable contributions exceeds the AGI limits and carries forward up to 5 years")
+        if totals["noncash"] > 500:
+            notes.append("Noncash contributions over $500 require Form 8283")
+
+        # Other itemized deductions
+        lines["16"] = totals["other"]
+        lines["17"] = lines["4"] + lines["7"] + lines["10"] + lines["14"] + lines["16"]
+
+        standard = TaxBrackets.STANDARD_DEDUCTION[status]
+        itemize = lines["17"] > standard
+        if not itemize:
+            notes.append(f"The standard deduction (${standard:,.0f}) is larger; Schedule A isn't needed")
+
+        return {
+            "tax_year": self.tax_year,
+            "filing_status": status.value,
+            "lines": {line: float(_cents(value)) for line, value in lines.items()},
+            "total_itemized_deductions": float(_cents(lines["17"])),
+            "standard_deduction": float(standard),
+            "itemize": itemize,
+            "charitable_carryforward": float(_cents(carryforward)),
+            "notes": notes,
+        }
@@ -0,0 +1,122 @@
+"""
+Schedule B
+Interest and dividends listed by payer, the $1,500 filing threshold, and the Part III foreign account questions
+"""
+from decimal import Decimal
+from typing import Dict, List, Any
+
+from app.tax_engine.foreign_accounts import check_foreign_accounts
+
+# Schedule B is required when taxable interest or ordinary dividends exceed this
+SCHEDULE_B_THRESHOLD = Decimal("1500")
+
+# Tax-exempt interest reported next to the taxable amount: 1099-INT box 8 and 1099-DIV box 12
+TAX_EXEMPT_FIELDS = {"interest": "tax_exempt_interest", "dividends": "exempt_interest_dividends"}
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def tax_exempt_amount(source: Dict[str, Any]) -> Decimal:
+    """
+    An income source's tax-exempt interest (Form 1040 line 2a)
+
+    All of it for a source marked tax_exempt, otherwise its 1099-INT box 8
+    or 1099-DIV box 12 amount, which isn't part of the taxable amount.
+    """
+    if source["type"] not in TAX_EXEMPT_FIELDS:
+        return Decimal("0")
+    if source.get("tax_exempt"):
+        return _amount(source.get("amount"))
+    return _amount(source.get(TAX_EXEMPT_FIELDS[source["type"]]))
+
+
+def _payers(sources: List[Dict[str, Any]]) -> List[Dict[str, Any]]:
+    return [
+        {"source_id": s.get("id"), "payer": s.get("description") or "", "amount": float(_amount(s.get("amount")))}
+        for s in sources
+    ]
+
+
+def calculate_schedule_b(tax_return: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    Schedule B for a stored return
+
+    Each interest and dividend income source is a payer line (its
+    description is the payer's name). Sources marked tax_exempt, such as
+    municipal bonds and exempt-interest dividends, go on Form 1040 line 2a
+    instead of Schedule B, as do a 1099's box 8 tax-exempt interest and
+    box 12 exempt-interest dividends. The schedule is required when taxable interest
+    or ordinary dividends are over $1,500 or the taxpayer has a foreign
+    financial account, either recorded under foreign accounts or flagged
+    foreign_account on a payer.
+
+    Args:
+        tax_return: Return dict from ReturnStore
+
+    Returns:
+        Dict with tax_year, required, reasons, interest and dividends (payer
+        lines), lines (2, 4, and 6 totals), tax_exempt_interest, part_iii
+        (foreign_account, countries, fbar_required, foreign_trust), and notes
+    """
+    sources = tax_return.get("income_sources", [])
+    interest = [s for s in sources if s["type"] == "interest" and not s.get("tax_exempt")]
+    dividends = [s for s in sources if s["type"] == "dividends" and not s.get("tax_exempt")]
+    total_interest = sum((_amount(s.get("amount")) for s in interest), Decimal("0"))
+    total_dividends = sum((_amount(s.get("amount")) for s in dividends), Decimal("0"))
+    tax_exempt_interest = sum((tax_exempt_amount(s) for s in sources), Decimal("0"))
+
+    accounts = tax_return.get("foreign_accounts", [])
+    flagged = [s for s in sources if s.get("foreign_account")]
+    foreign_account = bool(accounts or flagged)
+    countries = sorted({a["country"] for a in accounts if a.get("country")})
+    fbar_required = check_foreign_accounts(accounts, tax_return["filing_status"])["fbar"]["required"]
+
+    reasons: List[str] = []
+    if total_interest > SCHEDULE_B_THRESHOLD:
+        reasons.append(f"Taxable interest of ${total_interest:,.2f} is over ${SCHEDULE_B_THRESHOLD:,.0f}")
+    if total_dividends > SCHEDULE_B_THRESHOLD:
+        reasons.append(f"Ordinary dividends of ${total_dividends:,.2f} are over ${SCHEDULE_B_THRESHOLD:,.0f}")
+    if foreign_account:
+        reasons.append("The taxpayer has a financial account in a foreign country")
+
+    notes: List[str] = []
+    unnamed = [s for s in interest + dividends if not s.get("description")]
+    if unnamed:
+        notes.append(f"{len(unnamed)} payer line(s) have no payer name; add it from the 1099")
+    if flagged and not accounts:
+        notes.append(
+            "A payer is flagged as a foreign account but no foreign accounts are recorded; add them so the FBAR "
+            "and Form 8938 thresholds and line 7b's countries can be checked"
+        )
+    if not reasons:
+        notes.append(
+            f"Interest and dividends are each ${SCHEDULE_B_THRESHOLD:,.0f} or less and there are no foreign "
+            "accounts, so Schedule B isn't required"
+        )
+    notes.append(
+        "Also file Schedule B for nominee distributions, accrued interest, OID adjustments, or excludable savings "
+        "bond interest, and answer line 8 if you had a foreign trust; these aren't recorded here"
+    )
+
+    return {
+        "tax_year": tax_return["tax_year"],
+        "required": bool(reasons),
+        "reasons": reasons,
+        "interest": _payers(interest),
+        "dividends": _payers(dividends),
+        "lines": {
+            "2": float(total_interest),
+            "4": float(total_interest),
+            "6": float(total_dividends),
+        },
+        "tax_exempt_interest": float(tax_exempt_interest),
+        "part_iii": {
+            "foreign_account": foreign_account,
+            "countries": countries,
+            "fbar_required": fbar_required,
+            "foreign_trust": False,
+        },
+        "notes": notes,
+    }
@@ -0,0 +1,130 @@
+"""
+Schedule C Engine
+Profit or loss from a sole proprietorship for 2024, with expenses mapped to form lines
+"""
+from typing import Dict, List, Any
+from decimal import Decimal, ROUND_HALF_UP
+
+from app.tax_engine.vehicle_expenses import vehicle_deduction
+
+# Business expense category -> Schedule C line (Part II)
+EXPENSE_LINES = {
+    "advertising": "8",
+    "car_truck": "9",
+    "commissions_fees": "10",
+    "contract_labor": "11",
+    "depreciation": "13",
+    "insurance": "15",
+    "interest": "16b",
+    "legal_professional": "17",
+    "office": "18",
+    "rent_equipment": "20a",
+    "rent_property": "20b",
+    "repairs": "21",
+    "supplies": "22",
+    "taxes_licenses": "23",
+    "travel": "24a",
+    "meals": "24b",
+    "utilities": "25",
+    "wages": "26",
+    "other": "27a",
+}
+
+# Business meals are 50% deductible (line 24b)
+MEALS_DEDUCTIBLE_SHARE = Decimal("0.50")
+
+
+def _cents(value: Decimal) -> Decimal:
+    return value.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+class ScheduleCCalculator:
+    """Schedule C (Form 1040) business profit or loss calculator"""
+
+    def __init__(self, tax_year: int = 2024):
+        self.tax_year = tax_year
+
+        if tax_year != 2024:
+            raise ValueError(f"Only 2024 tax year is currently supported, got {tax_year}")
+
+    def calculate(
+        self,
+        business: Dict[str, Any],
+        income_sources: List[Dict[str, Any]],
+    ) -> Dict[str, Any]:
+        """
+        Fill Schedule C for one business
+
+        Gross receipts are the business's own gross_receipts plus every
+        self-employment income source (e.g. an applied 1099-NEC) linked to it
+        through business_id. Vehicles with a chosen method add to line 9 (and
+        line 13 for depreciation under actual expenses).
+
+        Args:
+            business: Business record from ReturnStore
+            income_sources: The return's income sources
+
+        Returns:
+            Dict with Schedule C lines, net profit or loss, and notes
+        """
+        receipts = _amount(business.get("gross_receipts"))
+        for source in income_sources:
+            if source["type"] == "self_employment" and source.get("business_id") == business["id"]:
+                receipts += _amount(source.get("amount"))
+
+        expenses: Dict[str, Decimal] = {line: Decimal("0") for line in EXPENSE_LINES.values()}
+        for expense in business.get("expenses", []):
+            category = expense["category"]
+            if category not in EXPENSE_LINES:
+                raise ValueError(
+                    f"Invalid business expense category: {category}. "
+                    f"Must be one of: {', '.join(EXPENSE_LINES)}"
+                )
+            expenses[EXPENSE_LINES[category]] += _amount(expense.get("amount"))
+        for vehicle in business.get("vehicles", []):
+            deduction = vehicle_deduction(vehicle, self.tax_year)
+            if deduction is not None:
+                expenses[EXPENSE_LINES["car_truck"]] += deduction["car_truck"]
+                expenses[EXPENSE_LINES["depreciation"]] += deduction["depreciation"]
+
+        notes: List[str] = []
+        if expenses["24b"]:
+            notes.append(f"Meals of ${expenses['24b']:,.2f} are limited to 50%")
+            expenses["24b"] = _cents(expenses["24b"] * MEALS_DEDUCTIBLE_SHARE)
+
+        lines: Dict[str, Decimal] = {}
+
+        # Part I: income
+        lines["1"] = receipts
+        lines["2"] = _amount(business.get("returns_allowances"))
+        lines["3"] = lines["1"] - lines["2"]
+        lines["4"] = _amount(business.get("cost_of_goods_sold"))
+        lines["5"] = lines["3"] - lines["4"]
+        lines["6"] = _amount(business.get("other_income"))
+        lines["7"] = lines["5"] + lines["6"]
+
+        # Part II: expenses
+        lines.update(expenses)
+        lines["28"] = sum(expenses.values(), Decimal("0"))
+        lines["29"] = lines["7"] - lines["28"]
+        lines["30"] = _amount(business.get("home_office"))
+        lines["31"] = lines["29"] - lines["30"]
+
+        if lines["31"] < 0:
+            notes.append("The business has a loss; at-risk and hobby-loss rules may limit it")
+
+        return {
+            "tax_year": self.tax_year,
+            "business_id": business["id"],
+            "name": business["name"],
+            "owner": business.get("owner", "taxpayer"),
+            "lines": {line: float(_cents(value)) for line, value in lines.items()},
+            "gross_income": float(_cents(lines["7"])),
+            "total_expenses": float(_cents(lines["28"])),
+            "net_profit": float(_cents(lines["31"])),
+            "notes": notes,
+        }
@@ -0,0 +1,68 @@
+"""
+Schedule SE Engine
+Self-employment tax for 2024 on net earnings from Schedule C
+"""
+from typing import Dict, Any
+from decimal import Decimal, ROUND_HALF_UP
+
+# Net earnings are 92.35% of net profit (line 4a), taxed only at $400 or more
+NET_EARNINGS_FACTOR = Decimal("0.9235")
+MINIMUM_NET_EARNINGS = Decimal("400")
+
+# 2024 social security wage base shared with W-2 wages (line 7)
+SOCIAL_SECURITY_WAGE_BASE = Decimal("168600")
+SOCIAL_SECURITY_RATE = Decimal("0.124")
+MEDICARE_RATE = Decimal("0.029")
+
+
+def _cents(value: Decimal) -> Decimal:
+    return value.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+
+
+class ScheduleSECalculator:
+    """Schedule SE (Form 1040) self-employment tax calculator"""
+
+    def __init__(self, tax_year: int = 2024):
+        self.tax_year = tax_year
+
+        if tax_year != 2024:
+            raise ValueError(f"Only 2024 tax year is currently supported, got {tax_year}")
+
+    def calculate(
+        self,
+        net_profit: Decimal,
+        social_security_wages: Decimal = Decimal("0"),
+    ) -> Dict[str, Any]:
+        """
+        Compute self-employment tax for one person
+
+        Args:
+            net_profit: Combined Schedule C net profit (line 2)
+            social_security_wages: W-2 social security wages, which use up the wage base
+
+        Returns:
+            Dict with Schedule SE lines, the tax, and the deductible half
+        """
+        if social_security_wages < 0:
+            raise ValueError("Social security wages cannot be negative")
+
+        lines: Dict[str, Decimal] = {}
+        lines["2"] = net_profit
+        lines["4a"] = _cents(max(Decimal("0"), net_profit) * NET_EARNINGS_FACTOR)
+        lines["6"] = lines["4a"] if lines["4a"] >= MINIMUM_NET_EARNINGS else Decimal("0")
+
+        lines["7"] = SOCIAL_SECURITY_WAGE_BASE
+        lines["8a"] = social_security_wages
+        lines["9"] = max(Decimal("0"), lines["7"] - lines["8a"])
+        lines["10"] = _cents(min(lines["6"], lines["9"]) * SOCIAL_SECURITY_RATE)
+        lines["11"] = _cents(lines["6"] * MEDICARE_RATE)
+        lines["12"] = lines["10"] + lines["11"]
+        lines["13"] = _cents(lines["12"] / 2)
+
+        return {
+            "tax_year": self.tax_year,
+            "lines": {line: float(value) for line, value in lines.items()},
+            "self_employment_tax": float(lines["12"]),
+            "deductible_half": float(lines["13"]),
+            "required": lines["6"] > 0,
+        }
@@ -0,0 +1,135 @@
+"""
+State Refund Taxability
+The state and local income tax refund worksheet (Schedule 1, line 1) from the prior year's deductions
+"""
+from decimal import Decimal
+from typing import Dict, List, Any, Optional
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def prior_year_deductions(prior_return: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    The figures the refund worksheet needs from the prior year's return
+
+    Taken from the calculation saved on that return, so a year the engine
+    no longer supports still works once it was calculated and saved.
+
+    Args:
+        prior_return: The prior year's return dict from ReturnStore
+
+    Returns:
+        Dict with return_id, tax_year, filing_status, itemized, itemized_deductions
+        (Schedule A line 17), standard_deduction, state_income_tax (income taxes
+        on line 5a), salt_total (line 5d), and salt_deducted (line 5e)
+
+    Raises:
+        ValueError: If the return has no saved calculation
+    """
+    calculation = prior_return.get("calculation")
+    if not calculation:
+        raise ValueError(f"The {prior_return['tax_year']} return hasn't been calculated and saved")
+
+    schedule_a = calculation.get("schedule_a") or {}
+    lines = schedule_a.get("lines") or {}
+    itemized = calculation.get("deduction_type") == "Itemized"
+    state_income_tax = sum(
+        (_amount(d.get("amount")) for d in prior_return.get("deductions", [])
+         if d["category"] == "state_local_tax" and d.get("tax_type", "income") == "income"),
+        Decimal("0"),
+    )
+    return {
+        "return_id": prior_return["return_id"],
+        "tax_year": prior_return["tax_year"],
+        "filing_status": prior_return["filing_status"],
+        "itemized": itemized,
+        "itemized_deductions": float(_amount(schedule_a.get("total_itemized_deductions"))),
+        "standard_deduction": float(
+            _amount(schedule_a.get("standard_deduction") if schedule_a else calculation.get("deduction_amount"))
+        ),
+        "state_income_tax": float(state_income_tax),
+        "salt_total": float(_amount(lines.get("5d"))),
+        "salt_deducted": float(_amount(lines.get("5e"))),
+    }
+
+
+def state_refund_worksheet(tax_return: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    How much of the year's state and local income tax refunds is taxable
+
+    A refund is income only to the extent last year's deduction of the tax
+    lowered last year's tax: nothing if the taxpayer took the standard
+    deduction or deducted sales tax instead, and no more than the amount
+    itemized deductions exceeded the standard deduction. When the SALT cap
+    held the deduction down, only the part of the refund that would have
+    lowered the capped deduction counts (Pub. 525, recoveries).
+
+    The prior year's figures come from the return's prior_year_deductions
+    (ReturnStore.load_prior_year_deductions). Without them the whole refund
+    is treated as taxable.
+
+    Args:
+        tax_return: Return dict from ReturnStore
+
+    Returns:
+        Dict with tax_year, refund, prior_year (the figures used, or None),
+        status (no_refund, needs_prior_year, not_taxable, or taxable), lines
+        (worksheet lines 1-7, or None), taxable_amount, and notes
+    """
+    refund = sum(
+        (_amount(s.get("amount")) for s in tax_return.get("income_sources", []) if s["type"] == "state_refund"),
+        Decimal("0"),
+    )
+    prior = tax_return.get("prior_year_deductions")
+    notes: List[str] = []
+    lines: Optional[Dict[str, Decimal]] = None
+
+    if refund <= 0:
+        status, taxable = "no_refund", Decimal("0")
+    elif not prior:
+        status, taxable = "needs_prior_year", refund
+        notes.append(
+            f"Last year's deductions aren't loaded, so the whole ${refund:,.2f} refund is treated as taxable; "
+            "load them from last year's return, or the refund may not be taxable at all"
+        )
+    elif not prior["itemized"]:
+        status, taxable = "not_taxable", Decimal("0")
+        notes.append(f"The {prior['tax_year']} return took the standard deduction, so the refund isn't taxable")
+    else:
+        # Line 1: no more than the income tax deducted, and under the SALT cap only what the cap didn't absorb
+        deducted = _amount(prior["state_income_tax"])
+        salt_total, salt_deducted = _amount(prior["salt_total"]), _amount(prior["salt_deducted"])
+        line_1 = min(refund, deducted)
+        if salt_total > salt_deducted:
+            line_1 = min(line_1, max(Decimal("0"), salt_deducted - (salt_total - refund)))
+            notes.append(
+                f"The {prior['tax_year']} state and local tax deduction was capped at ${salt_deducted:,.0f}; only the "
+                "part of the refund that would have lowered it counts"
+            )
+        if deducted <= 0:
+            notes.append(f"No state or local income tax was deducted in {prior['tax_year']} (sales tax instead)")
+        lines = {"1": line_1, "2": _amount(prior["itemized_deductions"]), "3": _amount(prior["standard_deduction"])}
+        # Line 4 (the extra standard deduction for age or blindness) isn't part of the engine's standard deduction
+        lines["4"] = Decimal("0")
+        lines["5"] = lines["3"] + lines["4"]
+        lines["6"] = max(Decimal("0"), lines["2"] - lines["5"])
+        lines["7"] = min(lines["1"], lines["6"])
+        taxable = lines["7"]
+        status = "taxable" if taxable > 0 else "not_taxable"
+        if prior["filing_status"] == "married_separate":
+            notes.append(
+                "If your spouse itemized on a separate return that year, the standard deduction wasn't an option; "
+                "the whole line 1 amount is taxable"
+            )
+
+    return {
+        "tax_year": tax_return["tax_year"],
+        "refund": float(refund),
+        "prior_year": prior or None,
+        "status": status,
+        "lines": {line: float(value) for line, value in lines.items()} if lines is not None else None,
+        "taxable_amount": float(taxable),
+        "notes": notes,
+    }
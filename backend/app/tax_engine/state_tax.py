@@ -0,0 +1,236 @@
+"""
+State Tax Engine
+2024 state income tax for resident, part-year, and nonresident state returns
+"""
+from typing import Dict, List, Any, Optional, Tuple
+from decimal import Decimal, ROUND_HALF_UP
+
+from app.tax_engine.schedule_b import tax_exempt_amount
+
+Brackets = List[Tuple[Optional[Decimal], Decimal]]  # (upper limit, rate), like TaxBrackets
+
+
+def _brackets(*rows: Tuple[Optional[str], str]) -> Brackets:
+    return [(Decimal(limit) if limit else None, Decimal(rate)) for limit, rate in rows]
+
+
+# Progressive states: standard deduction and brackets for single and married filing jointly
+PROGRESSIVE_STATES: Dict[str, Dict[str, Any]] = {
+    "CA": {
+        "standard_deduction": {"single": Decimal("5540"), "married_joint": Decimal("11080")},
+        "brackets": {
+            "single": _brackets(
+                ("10756", "0.01"), ("25499", "0.02"), ("40245", "0.04"), ("55866", "0.06"),
+                ("70606", "0.08"), ("360659", "0.093"), ("432787", "0.103"), ("721314", "0.113"),
+                (None, "0.123"),
+            ),
+            "married_joint": _brackets(
+                ("21512", "0.01"), ("50998", "0.02"), ("80490", "0.04"), ("111732", "0.06"),
+                ("141212", "0.08"), ("721318", "0.093"), ("865574", "0.103"), ("1442628", "0.113"),
+                (None, "0.123"),
+            ),
+        },
+    },
+    "NY": {
+        "standard_deduction": {"single": Decimal("8000"), "married_joint": Decimal("16050")},
+        "brackets": {
+            "single": _brackets(
+                ("8500", "0.04"), ("11700", "0.045"), ("13900", "0.0525"), ("80650", "0.055"),
+                ("215400", "0.06"), ("1077550", "0.0685"), ("5000000", "0.0965"), ("25000000", "0.103"),
+                (None, "0.109"),
+            ),
+            "married_joint": _brackets(
+                ("17150", "0.04"), ("23600", "0.045"), ("27900", "0.0525"), ("161550", "0.055"),
+                ("323200", "0.06"), ("2155350", "0.0685"), ("5000000", "0.0965"), ("25000000", "0.103"),
+                (None, "0.109"),
+            ),
+        },
+    },
+}
+
+# Flat-rate states (tax on state taxable income = federal AGI here)
+FLAT_RATE_STATES: Dict[str, Decimal] = {
+    "AZ": Decimal("0.025"),
+    "CO": Decimal("0.0425"),
+    "GA": Decimal("0.0539"),
+    "ID": Decimal("0.05695"),
+    "IL": Decimal("0.0495"),
+    "IN": Decimal("0.0305"),
+    "KY": Decimal("0.04"),
+    "MA": Decimal("0.05"),
+    "MI": Decimal("0.0425"),
+    "NC": Decimal("0.045"),
+    "PA": Decimal("0.0307"),
+    "UT": Decimal("0.0455"),
+}
+
+NO_INCOME_TAX_STATES = ["AK", "FL", "NH", "NV", "SD", "TN", "TX", "WA", "WY"]
+
+SUPPORTED_STATES = sorted([*PROGRESSIVE_STATES, *FLAT_RATE_STATES, *NO_INCOME_TAX_STATES])
+
+RESIDENCY_TYPES = ["resident", "part_year", "nonresident"]
+
+# States that tax interest on their own state and local bonds too, unless the bond's statute exempts it
+STATES_TAXING_OWN_BOND_INTEREST = ["IL"]
+
+
+def _cents(value: Decimal) -> Decimal:
+    return value.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def tax_exempt_interest_add_back(income_sources: List[Dict[str, Any]], state: str) -> Decimal:
+    """
+    Federally tax-exempt interest a state adds back to federal AGI
+
+    States tax interest on other states' bonds; a source's bond_state names
+    the state that issued its bonds, and one without it is added back.
+    States without an income tax add nothing back.
+    """
+    if state in NO_INCOME_TAX_STATES:
+        return Decimal("0")
+    return sum(
+        (tax_exempt_amount(s) for s in income_sources
+         if state in STATES_TAXING_OWN_BOND_INTEREST or s.get("bond_state") != state),
+        Decimal("0"),
+    )
+
+
+class StateTaxCalculator:
+    """State income tax calculator for the supported states"""
+
+    def __init__(self, tax_year: int = 2024):
+        self.tax_year = tax_year
+
+        if tax_year != 2024:
+            raise ValueError(f"Only 2024 tax year is currently supported, got {tax_year}")
+
+    def resident_tax(self, state: str, income: Decimal, filing_status: str) -> Dict[str, Any]:
+        """
+        Tax a full-year resident of a state would owe on an income
+
+        Married filing jointly uses the joint brackets; every other status uses
+        the single brackets. Personal exemptions and state credits aren't modeled.
+
+        Args:
+            state: Two-letter state code
+            income: Income before the state standard deduction (federal AGI)
+            filing_status: Federal filing status
+
+        Returns:
+            Dict with the deduction, taxable income, and tax
+        """
+        if state not in SUPPORTED_STATES:
+            raise ValueError(f"Unsupported state: {state}. Supported: {', '.join(SUPPORTED_STATES)}")
+
+        deduction = Decimal("0")
+        if state in PROGRESSIVE_STATES:
+            rules = PROGRESSIVE_STATES[state]
+            # Both states give a qualifying surviving spouse the joint schedule
+            schedule = "married_joint" if filing_status in ("married_joint", "qualifying_surviving_spouse") else "single"
+            deduction = rules["standard_deduction"][schedule]
+            taxable = max(Decimal("0"), income - deduction)
+            tax = Decimal("0")
+            lower = Decimal("0")
+            for upper, rate in rules["brackets"][schedule]:
+                if taxable <= lower:
+                    break
+                top = taxable if upper is None else min(taxable, upper)
+                tax += (top - lower) * rate
+                lower = top
+        elif state in FLAT_RATE_STATES:
+            taxable = max(Decimal("0"), income)
+            tax = taxable * FLAT_RATE_STATES[state]
+        else:
+            taxable = Decimal("0")
+            tax = Decimal("0")
+
+        return {"deduction": deduction, "taxable_income": taxable, "tax": _cents(tax)}
+
+    def calculate(
+        self,
+        state_returns: List[Dict[str, Any]],
+        agi: Decimal,
+        filing_status: str,
+        income_sources: List[Dict[str, Any]] = (),
+    ) -> List[Dict[str, Any]]:
+        """
+        Compute each state return attached to a federal return
+
+        Part-year and nonresident returns owe the resident tax on all income,
+        prorated by the share of income allocated to the state (the CA/NY
+        method). The resident state credits tax paid to other states on the same
+        income, up to its own tax on that income.
+
+        Allocated income and withholding left unset on a state return come from
+        the income sources for that state (W-2 boxes 16 and 17). Tax-exempt
+        interest on other states' bonds is added to federal AGI; it's
+        intangible income, so it isn't allocated to a nonresident state.
+
+        Args:
+            state_returns: State return records (state, residency, allocated_income, withholding)
+            agi: Federal AGI
+            filing_status: Federal filing status
+            income_sources: The return's income sources
+
+        Returns:
+            One result per state return, with add_back, computed_tax, and
+            balance (positive for a refund)
+        """
+        results = []
+        for record in state_returns:
+            residency = record["residency"]
+            sources = [s for s in income_sources if s.get("state") == record["state"]]
+            add_back = tax_exempt_interest_add_back(income_sources, record["state"])
+            income = agi + add_back
+            allocated = record.get("allocated_income")
+            if allocated is None:
+                allocated = sum(_amount(s.get("state_wages", s.get("amount"))) for s in sources)
+            allocated = income if residency == "resident" else min(_amount(allocated), income)
+            withholding = record.get("withholding")
+            if withholding is None:
+                withholding = sum(_amount(s.get("state_withholding")) for s in sources)
+            resident = self.resident_tax(record["state"], income, filing_status)
+            ratio = allocated / income if income > 0 else Decimal("0")
+            results.append({
+                "id": record["id"],
+                "state": record["state"],
+                "residency": residency,
+                "add_back": add_back,
+                "allocated_income": allocated,
+                "taxable_income": resident["taxable_income"],
+                "tax_before_credits": _cents(resident["tax"] * ratio),
+                "withholding": _amount(withholding),
+                "ratio": ratio,
+            })
+
+        for result in results:
+            result["other_state_credit"] = Decimal("0")
+            if result["residency"] != "resident":
+                continue
+            for other in results:
+                if other is result or other["residency"] == "resident":
+                    continue
+                limit = _cents(result["tax_before_credits"] * other["ratio"])
+                result["other_state_credit"] += min(other["tax_before_credits"], limit)
+
+        output = []
+        for result in results:
+            computed_tax = max(Decimal("0"), result["tax_before_credits"] - result["other_state_credit"])
+            output.append({
+                "id": result["id"],
+                "state": result["state"],
+                "residency": result["residency"],
+                "add_back": float(_cents(result["add_back"])),
+                "allocated_income": float(_cents(result["allocated_income"])),
+                "income_ratio": float(result["ratio"].quantize(Decimal("0.0001"))),
+                "tax_before_credits": float(result["tax_before_credits"]),
+                "other_state_credit": float(result["other_state_credit"]),
+                "computed_tax": float(computed_tax),
+                "withholding": float(result["withholding"]),
+                "balance": float(result["withholding"] - computed_tax),
+            })
+        return output
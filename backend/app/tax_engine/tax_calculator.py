@@ -7,6 +7,8 @@ from decimal import Decimal, ROUND_HALF_UP
 from datetime import date
 from enum import Enum
 
+from app.tax_engine.schedule_se import ScheduleSECalculator
+
 
 class FilingStatus(Enum):
     """IRS filing status options"""
@@ -14,6 +16,7 @@ class FilingStatus(Enum):
     MARRIED_JOINT = "married_joint"
     MARRIED_SEPARATE = "married_separate"
     HEAD_OF_HOUSEHOLD = "head_of_household"
+    QUALIFYING_SURVIVING_SPOUSE = "qualifying_surviving_spouse"
 
 
 class TaxBrackets:
@@ -25,6 +28,7 @@ class TaxBrackets:
         FilingStatus.MARRIED_JOINT: Decimal("29200"),
         FilingStatus.MARRIED_SEPARATE: Decimal("14600"),
         FilingStatus.HEAD_OF_HOUSEHOLD: Decimal("21900"),
+        FilingStatus.QUALIFYING_SURVIVING_SPOUSE: Decimal("29200"),
     }
 
     # Tax brackets for 2024 - (upper_limit, rate)
@@ -67,6 +71,18 @@ class TaxBrackets:
             (None, Decimal("0.37")),
         ],
     }
+    # A qualifying surviving spouse uses the married filing jointly brackets
+    BRACKETS_2024[FilingStatus.QUALIFYING_SURVIVING_SPOUSE] = BRACKETS_2024[FilingStatus.MARRIED_JOINT]
+
+    # Qualified dividend rates for 2024: taxable income where the 0% and 15% rates end (20% above)
+    QUALIFIED_DIVIDEND_BREAKPOINTS = {
+        FilingStatus.SINGLE: (Decimal("47025"), Decimal("518900")),
+        FilingStatus.MARRIED_JOINT: (Decimal("94050"), Decimal("583750")),
+        FilingStatus.MARRIED_SEPARATE: (Decimal("47025"), Decimal("291850")),
+        FilingStatus.HEAD_OF_HOUSEHOLD: (Decimal("63000"), Decimal("551350")),
+        FilingStatus.QUALIFYING_SURVIVING_SPOUSE: (Decimal("94050"), Decimal("583750")),
+    }
+    QUALIFIED_DIVIDEND_RATES = (Decimal("0"), Decimal("0.15"), Decimal("0.20"))
 
 
 class TaxCalculator:
@@ -92,16 +108,26 @@ Consult a qualified CPA or tax professional for actual tax preparation and advic
         filing_status: str,
         itemized_deductions: Optional[Decimal] = None,
         dependents: int = 0,
+        require_itemized: bool = False,
+        qualified_dividends: Decimal = Decimal("0"),
         **kwargs
     ) -> Dict[str, Any]:
         """
         Calculate individual income tax (Form 1040) using real IRS brackets
 
+        Qualified dividends are taxed at 0%, 15%, or 20% on top of the
+        ordinary income, following the Qualified Dividends and Capital Gain
+        Tax Worksheet.
+
         Args:
             gross_income: Total gross income
-            filing_status: One of 'single', 'married_joint', 'married_separate', 'head_of_household'
+            filing_status: One of 'single', 'married_joint', 'married_separate', 'head_of_household',
+                'qualifying_surviving_spouse'
             itemized_deductions: Optional itemized deductions (if None, uses standard deduction)
             dependents: Number of dependents
+            require_itemized: Use itemized deductions even when smaller than the
+                standard deduction (a married-separate spouse whose spouse itemizes)
+            qualified_dividends: Part of gross income that is qualified dividends (Form 1040 line 3a)
             **kwargs: Additional parameters for future enhancements
 
         Returns:
@@ -112,6 +138,8 @@ Consult a qualified CPA or tax professional for actual tax preparation and advic
         # Input validation
         if gross_income < 0:
             raise ValueError("Gross income cannot be negative")
+        if qualified_dividends < 0:
+            raise ValueError("Qualified dividends cannot be negative")
 
         # Parse filing status
         try:
@@ -127,7 +155,12 @@ Consult a qualified CPA or tax professional for actual tax preparation and advic
 
         # Determine deduction
         standard_deduction = TaxBrackets.STANDARD_DEDUCTION[status]
-        if itemized_deductions is not None:
+        if require_itemized:
+            if itemized_deductions is not None and itemized_deductions < 0:
+                raise ValueError("Itemized deductions cannot be negative")
+            deduction = itemized_deductions or Decimal("0")
+            deduction_type = "Itemized"
+        elif itemized_deductions is not None:
             if itemized_deductions < 0:
                 raise ValueError("Itemized deductions cannot be negative")
             deduction = max(itemized_deductions, standard_deduction)
@@ -142,10 +175,22 @@ Consult a qualified CPA or tax professional for actual tax preparation and advic
         taxable_income = max(Decimal("0"), gross_income - deduction)
         self.calculations_log.append(f"Taxable Income: ${taxable_income:,.2f}")
 
-        # Calculate tax using progressive brackets
-        tax_liability, bracket_details = self._calculate_progressive_tax(
-            taxable_income, status
-        )
+        # Calculate tax using progressive brackets, with qualified dividends at their own rates
+        worksheet = None
+        if qualified_dividends > 0 and taxable_income > 0:
+            worksheet = self._qualified_dividends_worksheet(taxable_income, qualified_dividends, status)
+            tax_liability, bracket_details = self._calculate_progressive_tax(
+                Decimal(str(worksheet["ordinary_income"])), status
+            )
+            tax_liability = Decimal(str(worksheet["tax"]))
+            self.calculations_log.append(
+                f"Qualified dividends: ${worksheet['at_0_percent']:,.2f} at 0%, "
+                f"${worksheet['at_15_percent']:,.2f} at 15%, ${worksheet['at_20_percent']:,.2f} at 20%"
+            )
+        else:
+            tax_liability, bracket_details = self._calculate_progressive_tax(
+                taxable_income, status
+            )
 
         # Round to cents
         tax_liability = tax_liability.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
@@ -168,14 +213,51 @@ Consult a qualified CPA or tax professional for actual tax preparation and advic
             "tax_liability": float(tax_liability),
             "effective_tax_rate": float(effective_rate),
             "bracket_breakdown": bracket_details,
+            "qualified_dividends_worksheet": worksheet,
             "calculation_steps": self.calculations_log,
             "dependents": dependents,
         }
 
+    def _qualified_dividends_worksheet(
+        self,
+        taxable_income: Decimal,
+        qualified_dividends: Decimal,
+        status: FilingStatus,
+    ) -> Dict[str, Any]:
+        """
+        Qualified Dividends and Capital Gain Tax Worksheet (Form 1040 instructions, line 16)
+
+        Returns:
+            Dict with ordinary_income, the dividends taxed at each rate, and
+            tax (the smaller of the worksheet tax and regular tax on everything)
+        """
+        zero_top, fifteen_top = TaxBrackets.QUALIFIED_DIVIDEND_BREAKPOINTS[status]
+        zero_rate, fifteen_rate, twenty_rate = TaxBrackets.QUALIFIED_DIVIDEND_RATES
+        preferential = min(taxable_income, qualified_dividends)
+        ordinary = taxable_income - preferential
+        # Lines 7-9: dividends filling the room left under the 0% breakpoint
+        at_zero = min(taxable_income, zero_top) - min(ordinary, taxable_income, zero_top)
+        # Lines 12-17: then up to the 15% breakpoint; the rest (line 20) is at 20%
+        room_at_fifteen = max(Decimal("0"), min(taxable_income, fifteen_top) - ordinary - at_zero)
+        at_fifteen = min(preferential - at_zero, room_at_fifteen)
+        at_twenty = preferential - at_zero - at_fifteen
+        ordinary_tax, _ = self._calculate_progressive_tax(ordinary, status, log=False)
+        worksheet_tax = ordinary_tax + at_zero * zero_rate + at_fifteen * fifteen_rate + at_twenty * twenty_rate
+        regular_tax, _ = self._calculate_progressive_tax(taxable_income, status, log=False)
+        return {
+            "ordinary_income": float(ordinary),
+            "qualified_dividends": float(preferential),
+            "at_0_percent": float(at_zero),
+            "at_15_percent": float(at_fifteen),
+            "at_20_percent": float(at_twenty),
+            "tax": float(min(worksheet_tax, regular_tax).quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)),
+        }
+
     def _calculate_progressive_tax(
         self,
         taxable_income: Decimal,
-        status: FilingStatus
+        status: FilingStatus,
+        log: bool = True,
     ) -> Tuple[Decimal, List[Dict[str, Any]]]:
         """
         Calculate tax using progressive bracket system
@@ -208,10 +290,11 @@ Consult a qualified CPA or tax professional for actual tax preparation and advic
 
             # Log calculation
             rate_pct = float(rate * 100)
-            self.calculations_log.append(
-                f"  {rate_pct}% bracket (${previous_limit:,.0f} - {bracket_upper}): "
-                f"${bracket_income:,.2f} × {rate_pct}% = ${bracket_tax:,.2f}"
-            )
+            if log:
+                self.calculations_log.append(
+                    f"  {rate_pct}% bracket (${previous_limit:,.0f} - {bracket_upper}): "
+                    f"${bracket_income:,.2f} × {rate_pct}% = ${bracket_tax:,.2f}"
+                )
 
             # Store bracket details
             bracket_details.append({
@@ -270,47 +353,73 @@ Consult a qualified CPA or tax professional for actual tax preparation and advic
         estimated_annual_income: Decimal,
         filing_status: str,
         withholding_to_date: Decimal = Decimal("0"),
+        self_employment_income: Decimal = Decimal("0"),
     ) -> Dict[str, Any]:
         """
         Calculate estimated quarterly tax payments
 
+        Self-employment tax is added to the income tax, and half of it is
+        deducted from income first, as on the return. No W-2 wages are assumed
+        to use up the social security wage base, which errs toward paying more.
+
         Args:
             estimated_annual_income: Estimated total income for the year
             filing_status: Filing status
             withholding_to_date: Tax already withheld
+            self_employment_income: Net self-employment profit included in estimated_annual_income
 
         Returns:
-            Dict with quarterly payment schedule
+            Dict with the annual breakdown and quarterly payment schedule
         """
         if estimated_annual_income < 0:
             raise ValueError("Estimated annual income cannot be negative")
         if withholding_to_date < 0:
             raise ValueError("Withholding cannot be negative")
+        if self_employment_income < 0:
+            raise ValueError("Self-employment income cannot be negative")
+        if self_employment_income > estimated_annual_income:
+            raise ValueError("Self-employment income cannot exceed estimated annual income")
+
+        self_employment = ScheduleSECalculator(self.tax_year).calculate(self_employment_income)
+        self_employment_tax = Decimal(str(self_employment["self_employment_tax"]))
+        self_employment_deduction = Decimal(str(self_employment["deductible_half"]))
 
         # Calculate estimated annual tax
         tax_calc = self.calculate_individual_tax(
-            estimated_annual_income,
+            estimated_annual_income - self_employment_deduction,
             filing_status
         )
 
-        estimated_tax = Decimal(str(tax_calc["tax_liability"]))
+        income_tax = Decimal(str(tax_calc["tax_liability"]))
+        estimated_tax = income_tax + self_employment_tax
         remaining_tax = estimated_tax - withholding_to_date
 
         # Calculate quarterly payments (4 quarters)
         quarterly_payment = remaining_tax / 4
         quarterly_payment = quarterly_payment.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+        per_quarter = {
+            "income_tax": float((income_tax / 4).quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)),
+            "self_employment_tax": float((self_employment_tax / 4).quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)),
+            "withholding_credit": float((withholding_to_date / 4).quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)),
+            "amount": float(quarterly_payment),
+        }
 
         return {
             "disclaimer": self.LEGAL_DISCLAIMER.strip(),
             "estimated_annual_tax": float(estimated_tax),
+            "income_tax": float(income_tax),
+            "self_employment_income": float(self_employment_income),
+            "self_employment_tax": float(self_employment_tax),
+            "self_employment_deduction": float(self_employment_deduction),
+            "taxable_income": tax_calc["taxable_income"],
             "withholding_to_date": float(withholding_to_date),
             "remaining_tax_due": float(remaining_tax),
             "quarterly_payment": float(quarterly_payment),
             "payment_schedule": [
-                {"quarter": "Q1", "due_date": f"{self.tax_year}-04-15", "amount": float(quarterly_payment)},
-                {"quarter": "Q2", "due_date": f"{self.tax_year}-06-15", "amount": float(quarterly_payment)},
-                {"quarter": "Q3", "due_date": f"{self.tax_year}-09-15", "amount": float(quarterly_payment)},
-                {"quarter": "Q4", "due_date": f"{self.tax_year + 1}-01-15", "amount": float(quarterly_payment)},
+                {"quarter": "Q1", "due_date": f"{self.tax_year}-04-15", **per_quarter},
+                {"quarter": "Q2", "due_date": f"{self.tax_year}-06-15", **per_quarter},
+                {"quarter": "Q3", "due_date": f"{self.tax_year}-09-15", **per_quarter},
+                {"quarter": "Q4", "due_date": f"{self.tax_year + 1}-01-15", **per_quarter},
             ],
         }
 
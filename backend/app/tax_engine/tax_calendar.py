@@ -0,0 +1,220 @@
+"""
+Tax Calendar
+Dated events for each return (deadlines, estimates, expected documents) and which are due for a reminder
+"""
+from datetime import date, timedelta
+from typing import Dict, List, Any, Optional, Tuple
+
+from app.tax_engine.deadlines import FEDERAL_DUE, next_business_day, return_deadlines
+
+# Quarter, (month, day), and years after the estimate's tax year
+ESTIMATED_TAX_DUE = [("Q1", (4, 15), 0), ("Q2", (6, 15), 0), ("Q3", (9, 15), 0), ("Q4", (1, 15), 1)]
+ESTIMATED_QUARTERS = [quarter for quarter, _, _ in ESTIMATED_TAX_DUE]
+
+# A balance due of at least this much means next year's tax should be paid in estimates
+ESTIMATED_TAX_THRESHOLD = 1000
+
+# Forms expected for income source types / deduction categories, and the (month, day)
+# after the tax year by which payers must send them
+INCOME_TYPE_FORMS = {
+    "wages": "W-2",
+    "interest": "1099-INT",
+    "dividends": "1099-DIV",
+    "self_employment": "1099-NEC",
+    "capital_gains": "1099-B",
+    "retirement": "1099-R",
+    "social_security": "SSA-1099",
+    "state_refund": "1099-G",
+}
+DEDUCTION_FORMS = {"mortgage_interest": "1098"}
+FORM_ARRIVAL_DATES = {"1099-B": (2, 15)}
+DEFAULT_ARRIVAL_DATE = (1, 31)
+
+EVENT_KINDS = ["filing", "payment", "extension", "estimated_payment", "document", "ip_pin"]
+
+# Days before an event that its reminder starts; documents are only chased once they're late
+REMINDER_LEAD_DAYS = {
+    "filing": 14,
+    "payment": 14,
+    "extension": 7,
+    "estimated_payment": 14,
+    "document": 0,
+    "ip_pin": 30,
+}
+
+# Kinds that notify again as the date nears: (days before, stage), most urgent last; past the date is "overdue"
+ESCALATION_STAGES = {
+    "estimated_payment": [(14, "two_weeks"), (3, "three_days"), (0, "due_today")],
+}
+
+
+def return_events(tax_return: Dict[str, Any], documents: Optional[List[Dict[str, Any]]] = None) -> List[Dict[str, Any]]:
+    """
+    Calendar events for a return
+
+    Covers the filing and payment deadlines, the last day to request an
+    extension, next year's quarterly estimates (when the return has business
+    income or owes at least ESTIMATED_TAX_THRESHOLD; a quarter is met once a
+    payment for it is logged), the dates payers' forms should have arrived
+    by, and when each stored IP PIN expires (a new one is issued every
+    January).
+
+    Args:
+        tax_return: Return dict from ReturnStore
+        documents: Document records attached to the return (a form is met once
+            a document of its type is stored)
+
+    Returns:
+        Events ordered by date, each with event_id, return_id, kind, title,
+        date, and met
+    """
+    return_id = tax_return["return_id"]
+    tax_year = tax_return["tax_year"]
+    filed = tax_return.get("status") in ("filed", "amended")
+    events = []
+
+    for deadline in return_deadlines(tax_return):
+        events.append(_event(
+            return_id, deadline["kind"], deadline["jurisdiction"], deadline["description"],
+            date.fromisoformat(deadline["due_date"]), deadline["met"],
+        ))
+
+    events.append(_event(
+        return_id, "extension", "federal", f"Last day to request a {tax_year} extension (Form 4868)",
+        next_business_day(date(tax_year + 1, *FEDERAL_DUE)), filed or bool(tax_return.get("extension")),
+    ))
+
+    if _needs_estimates(tax_return):
+        estimate_year = tax_year + 1
+        paid = {p["quarter"] for p in tax_return.get("estimated_payments", []) if p.get("tax_year") == estimate_year}
+        for quarter, (month, day), offset in ESTIMATED_TAX_DUE:
+            events.append(_event(
+                return_id, "estimated_payment", f"{quarter}-{estimate_year}",
+                f"{estimate_year} {quarter} estimated tax payment (Form 1040-ES)",
+                next_business_day(date(estimate_year + offset, month, day)), quarter in paid,
+            ))
+
+    received = {document.get("document_type") for document in documents or []}
+    for form, payers in _expected_forms(tax_return).items():
+        title = f"{form} from {', '.join(payers)}" if payers else form
+        events.append(_event(
+            return_id, "document", form, f"{title} should have arrived",
+            date(tax_year + 1, *FORM_ARRIVAL_DATES.get(form, DEFAULT_ARRIVAL_DATE)), filed or form in received,
+        ))
+
+    taxpayer = tax_return.get("taxpayer") or {}
+    for person, details in (tax_return.get("ip_pins") or {}).items():
+        name = taxpayer.get("spouse_name" if person == "spouse" else "name") or person
+        valid_year = details["valid_year"]
+        events.append(_event(
+            return_id, "ip_pin", f"{person}-{valid_year}",
+            f"{name}'s {valid_year} IP PIN expires",
+            date(valid_year, 12, 31), False,
+        ))
+
+    events.sort(key=lambda e: (e["date"], EVENT_KINDS.index(e["kind"]), e["event_id"]))
+    return events
+
+
+def due_reminders(
+    events: List[Dict[str, Any]],
+    states: Optional[Dict[str, Dict[str, Any]]] = None,
+    as_of: Optional[date] = None,
+) -> List[Dict[str, Any]]:
+    """
+    Events that should notify the user today
+
+    An unmet event notifies from REMINDER_LEAD_DAYS before its date until it
+    is met, unless it was dismissed or is snoozed past as_of. Kinds with
+    ESCALATION_STAGES notify again at each stage: dismissing one only
+    silences it until the next, more urgent stage starts.
+
+    Args:
+        events: return_events() output (for any number of returns)
+        states: Event ID -> {"snoozed_until", "dismissed_at"} from ReminderStore
+        as_of: Today (defaults to the current date)
+
+    Returns:
+        Reminders ordered by date, each an event with days_remaining, overdue,
+        stage (None for kinds that don't escalate), and message
+    """
+    as_of = as_of or date.today()
+    states = states or {}
+    reminders = []
+    for event in events:
+        state = states.get(event["event_id"], {})
+        due = date.fromisoformat(event["date"])
+        stage, stage_start = _escalation_stage(event["kind"], due, as_of)
+        dismissed = state.get("dismissed_at")
+        if event["met"] or (dismissed and (stage is None or dismissed[:10] >= stage_start.isoformat())):
+            continue
+        if state.get("snoozed_until") and date.fromisoformat(state["snoozed_until"]) > as_of:
+            continue
+        if due - timedelta(days=REMINDER_LEAD_DAYS[event["kind"]]) > as_of:
+            continue
+
+        days = (due - as_of).days
+        if event["kind"] == "document":
+            message = f"{event['title']} by {event['date']}; upload it when it comes"
+        elif event["kind"] == "ip_pin":
+            message = f"{event['title']} on {event['date']}; the IRS issues next year's PIN in January"
+        elif days < 0:
+            message = f"{event['title']} was due {event['date']} ({-days} days ago)"
+        elif days == 0:
+            message = f"{event['title']} is due today"
+        else:
+            message = f"{event['title']} is due {event['date']} (in {days} days)"
+        reminders.append({**event, "days_remaining": days, "overdue": days < 0, "stage": stage, "message": message})
+    return reminders
+
+
+def _escalation_stage(kind: str, due: date, as_of: date) -> Tuple[Optional[str], Optional[date]]:
+    """The escalation stage an event is in on as_of and the day it started (None for kinds that don't escalate)"""
+    if kind not in ESCALATION_STAGES:
+        return None, None
+    if as_of > due:
+        return "overdue", due + timedelta(days=1)
+    current, start = None, None
+    for days_before, stage in ESCALATION_STAGES[kind]:
+        if due - timedelta(days=days_before) <= as_of:
+            current, start = stage, due - timedelta(days=days_before)
+    return current, start
+
+
+def _needs_estimates(tax_return: Dict[str, Any]) -> bool:
+    """Self-employment income, a large balance due, or estimates already being paid means next year's quarters"""
+    if tax_return.get("businesses") or tax_return.get("estimated_payments"):
+        return True
+    if any(source["type"] == "self_employment" for source in tax_return.get("income_sources", [])):
+        return True
+    balance = tax_return.get("refund_or_owed")
+    return balance is not None and -balance >= ESTIMATED_TAX_THRESHOLD
+
+
+def _expected_forms(tax_return: Dict[str, Any]) -> Dict[str, List[str]]:
+    """Form -> payers it's expected from"""
+    forms: Dict[str, List[str]] = {}
+    for source in tax_return.get("income_sources", []):
+        form = INCOME_TYPE_FORMS.get(source["type"])
+        if form:
+            payers = forms.setdefault(form, [])
+            if source.get("description") and source["description"] not in payers:
+                payers.append(source["description"])
+    for deduction in tax_return.get("deductions", []):
+        form = DEDUCTION_FORMS.get(deduction["category"])
+        if form:
+            payers = forms.setdefault(form, [])
+            if deduction.get("description") and deduction["description"] not in payers:
+                payers.append(deduction["description"])
+    return forms
+
+
+def _event(return_id: str, kind: str, key: str, title: str, when: date, met: bool) -> Dict[str, Any]:
+    return {
+        "event_id": f"{return_id}.{kind}.{key}",
+        "return_id": return_id,
+        "kind": kind,
+        "title": title,
+        "date": when.isoformat(),
+        "met": met,
+    }
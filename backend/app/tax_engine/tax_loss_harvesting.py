@@ -0,0 +1,131 @@
+"""
+Tax-Loss Harvesting
+Finds unrealized losses in a return's holdings to sell before year-end, with wash-sale windows and the projected tax savings
+"""
+import copy
+from datetime import date, timedelta
+from decimal import Decimal
+from typing import Dict, List, Any, Optional
+
+from app.tax_engine.crypto import holding_term
+from app.tax_engine.return_calculation import calculate_return
+
+# A loss is disallowed if substantially identical shares are bought within 30 days before or after the sale
+WASH_SALE_DAYS = 30
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def _harvest_lot(holding: Dict[str, Any], sale_date: date) -> Dict[str, Any]:
+    """The capital transaction selling a holding at its market value would create"""
+    proceeds, basis = _amount(holding.get("market_value")), _amount(holding.get("cost_basis"))
+    return {
+        "description": f"{holding['quantity']} {holding['symbol']}",
+        "symbol": holding["symbol"],
+        "quantity": holding["quantity"],
+        "date_acquired": holding["date_acquired"],
+        "date_sold": sale_date.isoformat(),
+        "proceeds": float(proceeds),
+        "cost_basis": float(basis),
+        "wash_sale_disallowed": 0.0,
+        "term": holding_term(holding["date_acquired"], sale_date.isoformat()),
+        "gain_or_loss": float(proceeds - basis),
+    }
+
+
+def harvesting_suggestions(
+    tax_return: Dict[str, Any],
+    holdings: List[Dict[str, Any]],
+    as_of: Optional[date] = None,
+) -> Dict[str, Any]:
+    """
+    Which holdings to sell at a loss before year-end
+
+    A holding is a candidate when its market value is below its basis. It's
+    flagged when another lot of the same symbol was bought within the last
+    30 days (selling now would be a wash sale) and given the date that
+    window clears. The tax savings compare the return as entered with the
+    return after selling each candidate at today's value; losses beyond the
+    $3,000 limit carry forward instead of saving tax this year.
+
+    Args:
+        tax_return: Return dict from ReturnStore, with this year's realized sales
+        holdings: Open positions (symbol, quantity, cost_basis, market_value, date_acquired)
+        as_of: Sale date to assume (defaults to today)
+
+    Returns:
+        Dict with realized_gain (sales so far), candidates (largest loss
+        first, with tax_savings each), total_harvestable_loss,
+        projected_tax_savings (all candidates without a wash-sale flag), the
+        capital_loss_carryforward afterwards, and notes
+
+    Raises:
+        ValueError: If as_of isn't in the return's year or the year isn't supported
+    """
+    as_of = as_of or date.today()
+    tax_year = tax_return["tax_year"]
+    if as_of.year != tax_year:
+        raise ValueError(f"Harvesting dates must fall in {tax_year}")
+
+    baseline = calculate_return(tax_return)
+    base_tax = _amount(baseline["total_tax"])
+
+    def tax_after(lots: List[Dict[str, Any]]) -> Dict[str, Any]:
+        scenario = copy.deepcopy(tax_return)
+        scenario["capital_transactions"] = scenario.get("capital_transactions", []) + lots
+        return calculate_return(scenario)
+
+    candidates = []
+    for holding in holdings:
+        lot = _harvest_lot(holding, as_of)
+        if lot["gain_or_loss"] >= 0 or date.fromisoformat(holding["date_acquired"]) > as_of:
+            continue
+        window_start = as_of - timedelta(days=WASH_SALE_DAYS)
+        recent = [
+            date.fromisoformat(h["date_acquired"]) for h in holdings
+            if h is not holding and h["symbol"] == holding["symbol"]
+            and window_start <= date.fromisoformat(h["date_acquired"]) <= as_of
+        ]
+        savings = base_tax - _amount(tax_after([lot])["total_tax"])
+        candidates.append({
+            "holding_id": holding.get("id"),
+            "symbol": holding["symbol"],
+            "quantity": holding["quantity"],
+            "cost_basis": lot["cost_basis"],
+            "market_value": lot["proceeds"],
+            "unrealized_loss": -lot["gain_or_loss"],
+            "term": lot["term"],
+            "wash_sale_risk": bool(recent),
+            "wash_sale_clear_date": (max(recent) + timedelta(days=WASH_SALE_DAYS + 1)).isoformat() if recent else None,
+            "tax_savings": float(savings),
+            "lot": lot,
+        })
+    candidates.sort(key=lambda c: -c["unrealized_loss"])
+
+    harvest = [c["lot"] for c in candidates if not c["wash_sale_risk"]]
+    after = tax_after(harvest) if harvest else baseline
+    realized = sum((_amount(t.get("gain_or_loss")) for t in tax_return.get("capital_transactions", [])), Decimal("0"))
+
+    notes = []
+    if harvest:
+        notes.append(
+            f"Don't buy back what you sell until {(as_of + timedelta(days=WASH_SALE_DAYS + 1)).isoformat()} "
+            "(including in IRAs and a spouse's accounts), or the loss is disallowed as a wash sale"
+        )
+    if any(c["wash_sale_risk"] for c in candidates):
+        notes.append("Some positions had shares bought in the last 30 days; selling them now would be a wash sale")
+    if _amount(after["capital_loss_carryforward"]) > _amount(baseline["capital_loss_carryforward"]):
+        notes.append("Losses beyond the $3,000 yearly limit carry forward to offset future gains")
+
+    return {
+        "tax_year": tax_year,
+        "as_of": as_of.isoformat(),
+        "realized_gain": float(realized),
+        "candidates": [{k: v for k, v in c.items() if k != "lot"} for c in candidates],
+        "total_harvestable_loss": float(sum((_amount(c["unrealized_loss"]) for c in candidates), Decimal("0"))),
+        "projected_tax_savings": float(base_tax - _amount(after["total_tax"])),
+        "capital_loss_carryforward": after["capital_loss_carryforward"],
+        "notes": notes,
+    }
@@ -0,0 +1,160 @@
+"""
+Return Validation Rules
+Deterministic consistency checks run against a stored return
+"""
+import re
+from typing import Dict, List, Any, Optional
+from decimal import Decimal
+from collections import defaultdict
+
+from app.i18n import translate
+from app.tax_engine.alimony import alimony_is_taxable
+from app.tax_engine.tax_calculator import TaxBrackets, FilingStatus
+
+
+def _amount(value: Any) -> Decimal:
+    """Convert a stored amount to Decimal (missing → 0)"""
+    return Decimal(str(value or 0))
+
+
+def summarize_return(tax_return: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    Aggregate a stored return into totals by income type and deduction category
+
+    Args:
+        tax_return: Return dict from ReturnStore
+
+    Returns:
+        Dict with filing status, totals, and record counts (no personal details)
+    """
+    income_by_type: Dict[str, Decimal] = defaultdict(Decimal)
+    withholding = Decimal("0")
+    for source in tax_return.get("income_sources", []):
+        income_by_type[source["type"]] += _amount(source.get("amount"))
+        withholding += _amount(source.get("withholding"))
+
+    deductions_by_category: Dict[str, Decimal] = defaultdict(Decimal)
+    for deduction in tax_return.get("deductions", []):
+        deductions_by_category[deduction["category"]] += _amount(deduction.get("amount"))
+
+    return {
+        "tax_year": tax_return.get("tax_year"),
+        "filing_status": tax_return.get("filing_status"),
+        "dependent_count": len(tax_return.get("dependents", [])),
+        "income_by_type": {k: float(v) for k, v in income_by_type.items()},
+        "total_income": float(sum(income_by_type.values(), Decimal("0"))),
+        "total_withholding": float(withholding),
+        "deductions_by_category": {k: float(v) for k, v in deductions_by_category.items()},
+        "total_itemized_deductions": float(sum(deductions_by_category.values(), Decimal("0"))),
+    }
+
+
+# Descriptions that suggest alimony was entered somewhere other than the alimony records
+ALIMONY_WORDS = re.compile(r"\b(alimony|spousal support|separate maintenance)\b", re.IGNORECASE)
+CHILD_SUPPORT_WORDS = re.compile(r"\bchild support\b", re.IGNORECASE)
+
+
+def _money(value: Decimal) -> str:
+    return f"${value:,.2f}"
+
+
+def validate_return(tax_return: Dict[str, Any], locale: Optional[str] = None) -> List[Dict[str, Any]]:
+    """
+    Run deterministic validation rules over a stored return
+
+    Args:
+        tax_return: Return dict from ReturnStore
+        locale: Language of the messages (defaults to English)
+
+    Returns:
+        List of findings: {"code", "severity" (error|warning|info), "message"}
+    """
+    findings: List[Dict[str, Any]] = []
+
+    def add(code: str, severity: str, **params: Any) -> None:
+        findings.append({"code": code, "severity": severity, "message": translate(f"validation.{code}", locale, **params)})
+
+    try:
+        status = FilingStatus(tax_return.get("filing_status"))
+    except ValueError:
+        add("invalid_filing_status", "error", filing_status=tax_return.get("filing_status"))
+        status = None
+
+    if tax_return.get("tax_year") != 2024:
+        add("unsupported_tax_year", "error")
+
+    sources = tax_return.get("income_sources", [])
+    if not sources:
+        add("no_income", "warning")
+
+    seen = set()
+    for source in sources:
+        amount = _amount(source.get("amount"))
+        withheld = _amount(source.get("withholding"))
+        label = source.get("description") or source["type"]
+
+        if withheld > amount:
+            add("withholding_exceeds_income", "error", withheld=_money(withheld), label=label, amount=_money(amount))
+
+        key = (source["type"], (source.get("description") or "").strip().lower(), amount)
+        if key in seen:
+            add("possible_duplicate_income", "warning", label=label, amount=_money(amount))
+        seen.add(key)
+
+    decree_date = (tax_return.get("divorce") or {}).get("decree_date")
+    if status in (FilingStatus.MARRIED_JOINT, FilingStatus.MARRIED_SEPARATE) and decree_date and (
+        decree_date <= f"{tax_return.get('tax_year')}-12-31"
+    ):
+        add("married_after_divorce", "error", decree_date=decree_date)
+
+    for source in sources:
+        if source["type"] == "other" and ALIMONY_WORDS.search(source.get("description") or ""):
+            add("alimony_as_other_income", "warning", label=source["description"])
+    for deduction in tax_return.get("deductions", []):
+        if ALIMONY_WORDS.search(deduction.get("description") or ""):
+            add("alimony_as_deduction", "warning", label=deduction["description"])
+    for record in tax_return.get("alimony", []):
+        amount = _money(_amount(record.get("amount")))
+        if CHILD_SUPPORT_WORDS.search(record.get("description") or ""):
+            add("child_support_as_alimony", "warning", amount=amount)
+        elif not alimony_is_taxable(record):
+            code = "alimony_paid_not_deductible" if record["direction"] == "paid" else "alimony_received_not_taxable"
+            add(code, "info", amount=amount, instrument_date=record["instrument_date"])
+
+    death = tax_return.get("death")
+    years_after_death = tax_return.get("tax_year", 0) - int(death["date_of_death"][:4]) if death else None
+    if death and death["deceased"] == "taxpayer" and years_after_death > 0:
+        add("return_after_death", "error", date_of_death=death["date_of_death"])
+    if status == FilingStatus.MARRIED_JOINT and death and years_after_death > 0 and not death.get("remarried_date"):
+        add("joint_after_death", "error", date_of_death=death["date_of_death"])
+    if status == FilingStatus.QUALIFYING_SURVIVING_SPOUSE and not (
+        death and 0 < years_after_death <= 2 and tax_return.get("dependents")
+    ):
+        add("surviving_spouse_ineligible", "error")
+
+    dependents = tax_return.get("dependents", [])
+    if status == FilingStatus.HEAD_OF_HOUSEHOLD and not dependents:
+        add("hoh_without_dependent", "error")
+
+    for dependent in dependents:
+        months = dependent.get("months_lived_with")
+        if months is not None and months < 6:
+            name = dependent.get("name") or translate("validation.dependent_default_name", locale)
+            add("dependent_residency", "warning", name=name, months=months)
+
+    summary = summarize_return(tax_return)
+    total_income = _amount(summary["total_income"])
+    itemized = _amount(summary["total_itemized_deductions"])
+
+    if status is not None and 0 < itemized <= TaxBrackets.STANDARD_DEDUCTION[status]:
+        add("standard_deduction_larger", "info",
+            itemized=_money(itemized), standard=_money(TaxBrackets.STANDARD_DEDUCTION[status]))
+
+    charitable = _amount(summary["deductions_by_category"].get("charitable"))
+    if total_income > 0 and charitable > total_income * Decimal("0.60"):
+        add("charitable_over_agi_limit", "warning")
+
+    if "self_employment" in summary["income_by_type"]:
+        add("self_employment_tax", "info")
+
+    return findings
@@ -0,0 +1,268 @@
+"""
+Vehicle Expense Engine
+Standard mileage versus actual expenses with depreciation for a business vehicle, the switching rules between them,
+and medical and charitable mileage deductions
+"""
+from datetime import date
+from decimal import Decimal, ROUND_HALF_UP
+from typing import Dict, List, Any, Optional
+
+VEHICLE_METHODS = ["standard_mileage", "actual"]
+
+# Kinds of actual vehicle expense; parking and tolls are deductible under either method
+VEHICLE_EXPENSE_KINDS = ["fuel", "repairs", "tires", "insurance", "registration", "lease", "parking_tolls", "other"]
+
+# Standard mileage rate per business mile
+STANDARD_MILEAGE_RATES = {2024: Decimal("0.67")}
+
+# Per-mile rates for driving to medical care and for a charity (the charitable rate is set by statute)
+MEDICAL_MILEAGE_RATES = {2024: Decimal("0.21")}
+CHARITABLE_MILEAGE_RATES = {2024: Decimal("0.14")}
+MILEAGE_DEDUCTION_RATES = {"medical": MEDICAL_MILEAGE_RATES, "charitable": CHARITABLE_MILEAGE_RATES}
+
+# Schedule A categories a trip can be deducted under
+MILEAGE_DEDUCTION_CATEGORIES = list(MILEAGE_DEDUCTION_RATES)
+
+# 5-year MACRS, half-year convention (200% declining balance, or straight line at 50% business use or less)
+MACRS_5_YEAR = [Decimal("0.20"), Decimal("0.32"), Decimal("0.192"), Decimal("0.1152"), Decimal("0.1152"), Decimal("0.0576")]
+STRAIGHT_LINE_5_YEAR = [Decimal("0.10"), Decimal("0.20"), Decimal("0.20"), Decimal("0.20"), Decimal("0.20"), Decimal("0.10")]
+
+# Passenger automobile depreciation caps for cars placed in service in 2024 (no bonus depreciation)
+DEPRECIATION_LIMITS = [Decimal("12400"), Decimal("19800"), Decimal("11900"), Decimal("7160")]
+
+QUALIFIED_BUSINESS_USE = Decimal("0.50")
+
+
+def _cents(value: Decimal) -> Decimal:
+    return value.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def _first_year(vehicle: Dict[str, Any], tax_year: int) -> int:
+    """Year the vehicle was placed in service (else the earliest year with a recorded method)"""
+    placed = vehicle.get("placed_in_service")
+    if placed:
+        return date.fromisoformat(placed).year
+    return min([int(year) for year in vehicle.get("method_history") or {}] + [tax_year])
+
+
+def allowed_methods(vehicle: Dict[str, Any], tax_year: int) -> Dict[str, Any]:
+    """
+    Methods the vehicle may use this year, given the ones used before
+
+    Actual expenses in the first year rule out standard mileage for good; a
+    leased vehicle on standard mileage must stay on it for the whole lease.
+
+    Args:
+        vehicle: Vehicle record from ReturnStore (method_history maps year -> method)
+        tax_year: Year being prepared
+
+    Returns:
+        Dict with methods (allowed list) and reason (why one is ruled out, or None)
+    """
+    history = vehicle.get("method_history") or {}
+    first_year = _first_year(vehicle, tax_year)
+    first_method = history.get(str(first_year)) if first_year < tax_year else None
+    if first_method == "actual":
+        return {"methods": ["actual"], "reason": f"Actual expenses were used in {first_year}, the first year in service"}
+    if first_method == "standard_mileage" and vehicle.get("leased"):
+        return {
+            "methods": ["standard_mileage"],
+            "reason": "A leased vehicle that started on standard mileage must use it for the whole lease",
+        }
+    return {"methods": list(VEHICLE_METHODS), "reason": None}
+
+
+def _depreciation(
+    vehicle: Dict[str, Any], tax_year: int, business_use: Decimal, notes: List[str]
+) -> Dict[str, Any]:
+    """Depreciation for the actual expense method"""
+    cost = _amount(vehicle.get("cost"))
+    if vehicle.get("leased") or not cost:
+        return {"method": None, "amount": Decimal("0")}
+
+    history = vehicle.get("method_history") or {}
+    first_year = _first_year(vehicle, tax_year)
+    recovery_year = tax_year - first_year
+    used_standard = any(
+        history.get(str(year)) == "standard_mileage" for year in range(first_year, tax_year)
+    )
+    straight_line = business_use <= QUALIFIED_BUSINESS_USE or used_standard
+    if used_standard:
+        notes.append(
+            "Standard mileage was used before, so depreciation is straight line; reduce the basis by the "
+            "depreciation part of the standard rate for those years"
+        )
+    elif business_use <= QUALIFIED_BUSINESS_USE:
+        notes.append("Business use is 50% or less, so depreciation is straight line")
+
+    table = STRAIGHT_LINE_5_YEAR if straight_line else MACRS_5_YEAR
+    if not 0 <= recovery_year < len(table):
+        return {"method": "straight_line" if straight_line else "macrs", "amount": Decimal("0")}
+
+    amount = cost * table[recovery_year] * business_use
+    limit = DEPRECIATION_LIMITS[min(recovery_year, len(DEPRECIATION_LIMITS) - 1)] * business_use
+    if not vehicle.get("heavy_vehicle") and amount > limit:
+        notes.append(f"Depreciation is capped at ${_cents(limit):,.2f} by the passenger automobile limits")
+        amount = limit
+    return {"method": "straight_line" if straight_line else "macrs", "amount": _cents(amount)}
+
+
+def compare_vehicle_methods(vehicle: Dict[str, Any], tax_year: int) -> Dict[str, Any]:
+    """
+    Compare standard mileage with actual expenses for one vehicle
+
+    Business use comes from the mileage log (business and personal trips),
+    or the vehicle's total_miles when the log has only business trips.
+
+    Args:
+        vehicle: Vehicle record from ReturnStore
+        tax_year: Year being prepared
+
+    Returns:
+        Dict with business use, both methods' deductions, which are allowed,
+        the recommended and chosen methods, and notes
+
+    Raises:
+        ValueError: If there's no standard mileage rate for the year
+    """
+    if tax_year not in STANDARD_MILEAGE_RATES:
+        raise ValueError(f"No standard mileage rate for {tax_year}")
+    rate = STANDARD_MILEAGE_RATES[tax_year]
+    notes: List[str] = []
+
+    log = vehicle.get("mileage_log", [])
+    business_miles = sum((_amount(trip["miles"]) for trip in log if trip.get("business", True)), Decimal("0"))
+    logged_miles = sum((_amount(trip["miles"]) for trip in log), Decimal("0"))
+    total_miles = max(_amount(vehicle.get("total_miles")), logged_miles)
+    business_use = (business_miles / total_miles) if total_miles else Decimal("0")
+    if total_miles and logged_miles == business_miles and not vehicle.get("total_miles"):
+        notes.append("The log has no personal miles; set total_miles (odometer) for an accurate business-use share")
+
+    expenses: Dict[str, Decimal] = {kind: Decimal("0") for kind in VEHICLE_EXPENSE_KINDS}
+    for expense in vehicle.get("expenses", []):
+        expenses[expense["kind"]] += _amount(expense.get("amount"))
+    parking_tolls = expenses.pop("parking_tolls")
+    operating = sum(expenses.values(), Decimal("0"))
+
+    allowed = allowed_methods(vehicle, tax_year)
+    if allowed["reason"]:
+        notes.append(allowed["reason"])
+    depreciation = _depreciation(vehicle, tax_year, business_use, notes)
+
+    standard_total = _cents(business_miles * rate) + parking_tolls
+    business_share = _cents(operating * business_use)
+    actual_total = business_share + depreciation["amount"] + parking_tolls
+    options = {"standard_mileage": standard_total, "actual": actual_total}
+    recommended = max(allowed["methods"], key=lambda method: options[method])
+
+    if vehicle.get("leased") and "standard_mileage" in allowed["methods"]:
+        notes.append("Choosing standard mileage for a leased vehicle commits to it for the rest of the lease")
+    if "standard_mileage" in allowed["methods"] and _first_year(vehicle, tax_year) == tax_year:
+        notes.append("Standard mileage in the first year keeps both methods open in later years")
+
+    return {
+        "vehicle_id": vehicle.get("id"),
+        "description": vehicle.get("description", ""),
+        "tax_year": tax_year,
+        "business_miles": float(business_miles),
+        "total_miles": float(total_miles),
+        "business_use_percent": float(_cents(business_use * 100)),
+        "standard_mileage": {
+            "rate": float(rate),
+            "mileage_amount": float(_cents(business_miles * rate)),
+            "parking_tolls": float(parking_tolls),
+            "total": float(_cents(standard_total)),
+            "allowed": "standard_mileage" in allowed["methods"],
+        },
+        "actual": {
+            "operating_expenses": float(operating),
+            "business_share": float(business_share),
+            "depreciation": float(depreciation["amount"]),
+            "depreciation_method": depreciation["method"],
+            "parking_tolls": float(parking_tolls),
+            "total": float(_cents(actual_total)),
+            "allowed": "actual" in allowed["methods"],
+        },
+        "recommended_method": recommended,
+        "chosen_method": vehicle.get("method"),
+        "notes": notes,
+    }
+
+
+def vehicle_deduction(vehicle: Dict[str, Any], tax_year: int) -> Optional[Dict[str, Decimal]]:
+    """
+    Schedule C amounts for a vehicle's chosen method
+
+    Returns:
+        {"car_truck": line 9 amount, "depreciation": line 13 amount}, or None
+        when no method has been chosen
+    """
+    if not vehicle.get("method"):
+        return None
+    comparison = compare_vehicle_methods(vehicle, tax_year)
+    if vehicle["method"] == "standard_mileage":
+        return {"car_truck": _amount(comparison["standard_mileage"]["total"]), "depreciation": Decimal("0")}
+    actual = comparison["actual"]
+    return {
+        "car_truck": _amount(actual["business_share"]) + _amount(actual["parking_tolls"]),
+        "depreciation": _amount(actual["depreciation"]),
+    }
+
+
+def mileage_trips(tax_return: Dict[str, Any]) -> List[Dict[str, Any]]:
+    """Medical and charitable trips, from the return's own log and its business vehicles' logs"""
+    trips = list(tax_return.get("mileage_log", []))
+    for business in tax_return.get("businesses", []):
+        for vehicle in business.get("vehicles", []):
+            trips += [t for t in vehicle.get("mileage_log", []) if t.get("category") in MILEAGE_DEDUCTION_RATES]
+    return trips
+
+
+def mileage_deductions(tax_return: Dict[str, Any]) -> List[Dict[str, Any]]:
+    """
+    Itemized deductions for medical and charitable driving
+
+    Each category's miles are taken at that year's rate, plus the parking
+    and tolls recorded on its trips. The records are shaped like the
+    return's deductions so they go through Schedule A with them (medical
+    mileage is subject to the AGI floor like other medical expenses).
+
+    Args:
+        tax_return: Return dict from ReturnStore
+
+    Returns:
+        One deduction record per category with trips (id, category,
+        description, miles, rate, amount)
+
+    Raises:
+        ValueError: If there are trips but no rate for the year
+    """
+    tax_year = tax_return["tax_year"]
+    deductions = []
+    trips = mileage_trips(tax_return)
+    for category, rates in MILEAGE_DEDUCTION_RATES.items():
+        logged = [t for t in trips if t.get("category") == category]
+        if not logged:
+            continue
+        if tax_year not in rates:
+            raise ValueError(f"No {category} mileage rate for {tax_year}")
+        miles = sum((_amount(t["miles"]) for t in logged), Decimal("0"))
+        parking_tolls = sum((_amount(t.get("parking_tolls")) for t in logged), Decimal("0"))
+        deductions.append({
+            "id": f"mileage_{category}",
+            "category": category,
+            "description": f"{category.capitalize()} mileage ({miles:,f} miles at ${rates[tax_year]})",
+            "miles": float(miles),
+            "rate": float(rates[tax_year]),
+            "amount": float(_cents(miles * rates[tax_year]) + parking_tolls),
+        })
+    return deductions
+
+
+def itemized_deduction_records(tax_return: Dict[str, Any]) -> List[Dict[str, Any]]:
+    """The return's deductions plus its medical and charitable mileage, as Schedule A takes them"""
+    return tax_return.get("deductions", []) + mileage_deductions(tax_return)
@@ -0,0 +1,127 @@
+"""
+Withholding Checkup
+Projects a year's tax from year-to-date pay stub figures and recommends a W-4 withholding adjustment
+"""
+import copy
+from decimal import Decimal, ROUND_CEILING, ROUND_HALF_UP
+from typing import Dict, Any
+
+from app.tax_engine.return_calculation import calculate_return
+
+# Pay periods per year for each pay frequency
+PAY_PERIODS = {"weekly": 52, "biweekly": 26, "semimonthly": 24, "monthly": 12}
+
+# A balance due at or above this usually means an underpayment penalty
+UNDERPAYMENT_PENALTY_THRESHOLD = Decimal("1000")
+
+
+def _amount(value: Any) -> Decimal:
+    return Decimal(str(value or 0))
+
+
+def _cents(value: Decimal) -> Decimal:
+    return value.quantize(Decimal("0.01"), rounding=ROUND_HALF_UP)
+
+
+def withholding_checkup(
+    tax_return: Dict[str, Any],
+    ytd_wages: Decimal,
+    ytd_withholding: Decimal,
+    periods_remaining: int,
+    pay_frequency: str = "biweekly",
+    owner: str = "taxpayer",
+    target_refund: Decimal = Decimal("0"),
+) -> Dict[str, Any]:
+    """
+    Project the year from a pay stub and size the withholding change
+
+    The pay stub's per-period wages and withholding are assumed to continue
+    for the rest of the year. They replace the owner's wage income on the
+    return; everything else (other income, deductions, credits) is taken as
+    entered.
+
+    Args:
+        tax_return: Return dict from ReturnStore for the year being checked
+        ytd_wages: Federal taxable wages to date (pay stub)
+        ytd_withholding: Federal income tax withheld to date
+        periods_remaining: Paychecks left this year
+        pay_frequency: One of PAY_PERIODS
+        owner: Whose job the pay stub is from (taxpayer or spouse)
+        target_refund: Refund to aim for (0 breaks even)
+
+    Returns:
+        Dict with the projected wages and withholding, the projected
+        calculation's tax and refund_or_owed, and the recommended change in
+        withholding per remaining paycheck (W-4 line 4(c) when positive)
+
+    Raises:
+        ValueError: If the figures are inconsistent or the year isn't supported
+    """
+    if pay_frequency not in PAY_PERIODS:
+        raise ValueError(f"Invalid pay frequency: {pay_frequency}. Must be one of: {', '.join(PAY_PERIODS)}")
+    periods_per_year = PAY_PERIODS[pay_frequency]
+    if not 0 <= periods_remaining < periods_per_year:
+        raise ValueError(f"Pay periods remaining must be between 0 and {periods_per_year - 1}")
+    if ytd_wages <= 0:
+        raise ValueError("Year-to-date wages must be positive")
+    if ytd_withholding < 0 or ytd_withholding > ytd_wages:
+        raise ValueError("Year-to-date withholding must be between 0 and the wages")
+
+    periods_paid = periods_per_year - periods_remaining
+    per_period_wages = ytd_wages / periods_paid
+    per_period_withholding = ytd_withholding / periods_paid
+    projected_wages = _cents(ytd_wages + per_period_wages * periods_remaining)
+    projected_withholding = _cents(ytd_withholding + per_period_withholding * periods_remaining)
+
+    projected = copy.deepcopy(tax_return)
+    projected["income_sources"] = [
+        s for s in projected.get("income_sources", [])
+        if not (s["type"] == "wages" and s.get("owner", "taxpayer") == owner)
+    ]
+    projected["income_sources"].append({
+        "id": "inc_projected",
+        "type": "wages",
+        "description": "Projected from pay stub",
+        "amount": float(projected_wages),
+        "withholding": float(projected_withholding),
+        "owner": owner,
+    })
+    calculation = calculate_return(projected)
+    refund_or_owed = _amount(calculation["refund_or_owed"])
+
+    notes = []
+    shortfall = target_refund - refund_or_owed
+    if periods_remaining:
+        adjustment = (shortfall / periods_remaining).quantize(Decimal("1"), rounding=ROUND_CEILING)
+        if adjustment > 0:
+            notes.append(f"Enter ${adjustment:,.0f} as extra withholding on W-4 line 4(c)")
+        elif adjustment < 0:
+            notes.append(
+                f"Withholding is about ${-adjustment:,.0f} a paycheck more than needed; "
+                "a new W-4 with deductions on line 4(b) lowers it"
+            )
+    else:
+        adjustment = None
+        if shortfall > 0:
+            notes.append("No paychecks are left this year; make an estimated payment to cover the balance")
+    if refund_or_owed <= -UNDERPAYMENT_PENALTY_THRESHOLD:
+        notes.append(
+            f"A balance due of ${UNDERPAYMENT_PENALTY_THRESHOLD:,.0f} or more may bring an underpayment "
+            "penalty unless last year's tax was fully covered"
+        )
+
+    return {
+        "tax_year": tax_return["tax_year"],
+        "pay_frequency": pay_frequency,
+        "periods_paid": periods_paid,
+        "periods_remaining": periods_remaining,
+        "projected_wages": float(projected_wages),
+        "projected_withholding": float(projected_withholding),
+        "projected_total_tax": calculation["total_tax"],
+        "projected_total_withholding": calculation["total_withholding"],
+        "refund_or_owed": float(refund_or_owed),
+        "target_refund": float(target_refund),
+        "per_paycheck_withholding": float(_cents(per_period_withholding)),
+        "recommended_adjustment_per_paycheck": float(adjustment) if adjustment is not None else None,
+        "notes": notes,
+    }
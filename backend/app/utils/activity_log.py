@@ -0,0 +1,91 @@
+"""
+Review Activity Log
+Append-only record of what was viewed (and what changes were refused) during review mode
+"""
+import json
+from datetime import datetime
+from pathlib import Path
+from typing import Dict, List, Any, Optional
+
+
+class ActivityLog:
+    """JSON Lines file of review-mode requests"""
+
+    def __init__(self, log_path: str = ".review_activity.jsonl"):
+        """
+        Initialize activity log
+
+        Args:
+            log_path: JSON Lines file the entries are appended to
+        """
+        self.log_path = Path(log_path)
+
+    def record(
+        self,
+        action: str,
+        path: str,
+        method: str = "GET",
+        status_code: Optional[int] = None,
+        query: str = "",
+    ) -> Dict[str, Any]:
+        """
+        Append an entry
+
+        Args:
+            action: viewed, blocked, review_started, or review_ended
+            path: Request path
+            method: HTTP method
+            status_code: Response status
+            query: Raw query string
+
+        Returns:
+            The entry
+        """
+        entry = {
+            "at": datetime.utcnow().isoformat(),
+            "action": action,
+            "method": method,
+            "path": path,
+            "query": query,
+            "status_code": status_code,
+        }
+        self.log_path.parent.mkdir(parents=True, exist_ok=True)
+        with open(self.log_path, 'a', encoding='utf-8') as f:
+            f.write(json.dumps(entry) + "\n")
+        return entry
+
+    def entries(self, limit: Optional[int] = None, since: Optional[str] = None) -> List[Dict[str, Any]]:
+        """
+        Read entries, newest first
+
+        Args:
+            limit: Maximum number of entries
+            since: Only entries at or after this ISO timestamp
+
+        Returns:
+            List of entries
+        """
+        if not self.log_path.exists():
+            return []
+        entries = []
+        with open(self.log_path, 'r', encoding='utf-8') as f:
+            for line in f:
+                try:
+                    entry = json.loads(line)
+                except json.JSONDecodeError:
+                    continue
+                if since is None or entry.get("at", "") >= since:
+                    entries.append(entry)
+        entries.reverse()
+        return entries[:limit] if limit is not None else entries
+
+    def clear(self) -> int:
+        """
+        Delete every entry
+
+        Returns:
+            Number of entries removed
+        """
+        count = len(self.entries())
+        self.log_path.unlink(missing_ok=True)
+        return count
@@ -0,0 +1,122 @@
+"""
+Anonymized Export
+Copy of a return with names, taxpayer IDs, and addresses replaced, for sharing without revealing identity
+"""
+import copy
+from datetime import datetime
+from typing import Dict, List, Any, Tuple
+
+ANONYMIZED_FORMAT = "ai-tax-cpa-agent-anonymized"
+
+# Fields naming a person or organization, with the label their placeholder uses
+NAME_FIELDS = {
+    "name": "Name",
+    "spouse_name": "Name",
+    "filer_name": "Name",
+    "client_name": "Name",
+    "personal_representative": "Name",
+    "payer": "Payer",
+    "payer_name": "Payer",
+    "employer_name": "Employer",
+    "lender_name": "Lender",
+    "issuer_name": "Issuer",
+    "institution": "Institution",
+    "address": "Address",
+}
+
+# Suffixes of fields holding an SSN, EIN, or other TIN
+IDENTIFIER_SUFFIXES = ("ssn", "ein", "tin")
+
+# Fields dropped outright: contact details, bank and account numbers, encrypted data, and free-form notes
+REMOVED_FIELDS = {
+    "street", "city", "zip", "phone", "email",
+    "account_number", "routing_number", "vin",
+    "direct_deposit", "ip_pins",
+    "notes", "note", "memo",
+}
+
+# Placeholder labels for a plain name field inside these collections
+COLLECTION_LABELS = {"businesses": "Business", "rental_properties": "Property", "foreign_accounts": "Institution"}
+
+
+class _Pseudonyms:
+    """Stable placeholders: the same original value always gets the same one"""
+
+    def __init__(self):
+        self.values: Dict[Tuple[str, str], str] = {}
+        self.counts: Dict[str, int] = {}
+
+    def name(self, label: str, value: str) -> str:
+        key = ("name", value.strip().lower())
+        if key not in self.values:
+            self.counts[label] = self.counts.get(label, 0) + 1
+            self.values[key] = f"{label} {self.counts[label]}"
+        return self.values[key]
+
+    def identifier(self, value: str) -> str:
+        # Nine digits, like the SSNs and EINs the app stores, starting with the never-issued 000 area
+        key = ("identifier", "".join(ch for ch in value if ch.isdigit()))
+        if key not in self.values:
+            self.counts["identifier"] = self.counts.get("identifier", 0) + 1
+            self.values[key] = f"{self.counts['identifier']:09d}"
+        return self.values[key]
+
+
+def anonymize_return(tax_return: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    Anonymize a return for sharing
+
+    Names and addresses become placeholders (Name 1, Employer 2, Address 1),
+    and SSNs, EINs, and other TINs become fake nine-digit numbers starting
+    000, which the SSA never issues. The same original always gets the same
+    placeholder, so a W-2 still matches its employer and a dependent still
+    matches their student record. Contact details, bank and account numbers,
+    IP PINs, and notes are removed (text fields are left empty). Amounts,
+    dates, states, categories, and descriptions are kept, so the return
+    calculates exactly as before; descriptions are free text, so read them
+    before posting.
+
+    Args:
+        tax_return: Return dict from ReturnStore
+
+    Returns:
+        Dict with format, anonymized_at, return (the anonymized copy), and
+        summary (names, identifiers, and fields removed)
+    """
+    pseudonyms = _Pseudonyms()
+    removed: List[str] = []
+
+    def walk(value: Any, path: str) -> Any:
+        if isinstance(value, dict):
+            collection = path.rsplit(".", 1)[-1].split("[", 1)[0]
+            result = {}
+            for key, item in value.items():
+                field = f"{path}.{key}" if path else key
+                if key in REMOVED_FIELDS:
+                    if item not in (None, "", [], {}):
+                        removed.append(field)
+                    if isinstance(item, str):
+                        result[key] = ""
+                elif isinstance(item, str) and item.strip() and key in NAME_FIELDS:
+                    label = COLLECTION_LABELS.get(collection, NAME_FIELDS[key]) if key == "name" else NAME_FIELDS[key]
+                    result[key] = pseudonyms.name(label, item)
+                elif isinstance(item, str) and item.strip() and key.endswith(IDENTIFIER_SUFFIXES):
+                    result[key] = pseudonyms.identifier(item)
+                else:
+                    result[key] = walk(item, field)
+            return result
+        if isinstance(value, list):
+            return [walk(item, f"{path}[{index}]") for index, item in enumerate(value)]
+        return value
+
+    anonymized = walk(copy.deepcopy(tax_return), "")
+    return {
+        "format": ANONYMIZED_FORMAT,
+        "anonymized_at": datetime.utcnow().isoformat(),
+        "return": anonymized,
+        "summary": {
+            "names": sum(1 for kind, _ in pseudonyms.values if kind == "name"),
+            "identifiers": pseudonyms.counts.get("identifier", 0),
+            "removed": removed,
+        },
+    }
@@ -0,0 +1,297 @@
+"""
+App Lock
+Optional passphrase that must be entered before the API serves any stored data, and a read-only review mode
+"""
+import base64
+import hashlib
+import hmac
+import json
+import os
+import secrets
+from datetime import datetime, timedelta
+from pathlib import Path
+from typing import Dict, Any, Optional
+
+PBKDF2_ITERATIONS = 390_000
+
+# PBKDF2 HMAC digests a lock file can use, and the fewest iterations recommended for each (OWASP)
+KDF_ALGORITHMS = ["sha256", "sha512"]
+DEFAULT_KDF_ALGORITHM = "sha256"
+RECOMMENDED_ITERATIONS = {"sha256": PBKDF2_ITERATIONS, "sha512": 210_000}
+MAX_PBKDF2_ITERATIONS = 10_000_000
+MIN_PASSPHRASE_LENGTH = 8
+
+# Minutes a passphrase entry counts as recent for sensitive actions, unless settings say otherwise
+DEFAULT_REAUTH_MINUTES = 5
+
+
+class AppLock:
+    """Passphrase lock kept in a JSON file; the locked/unlocked state lives in memory"""
+
+    def __init__(self, lock_path: str = ".app_lock.json"):
+        """
+        Initialize the lock
+
+        An app with a passphrase starts locked; without one it is always unlocked.
+
+        Args:
+            lock_path: JSON file holding the passphrase hash
+        """
+        self.lock_path = Path(lock_path)
+        self._unlocked = not self.enabled
+        self._review_started_at: Optional[str] = None
+        self._verified_at: Optional[datetime] = None
+
+    @property
+    def enabled(self) -> bool:
+        """Whether a passphrase has been set"""
+        return self.lock_path.exists()
+
+    @property
+    def unlocked(self) -> bool:
+        return self._unlocked or not self.enabled
+
+    @property
+    def review_mode(self) -> bool:
+        """Whether the app is in read-only review mode"""
+        return self._review_started_at is not None
+
+    @property
+    def verified_at(self) -> Optional[datetime]:
+        """When the passphrase was last entered (None while locked or before it's entered)"""
+        return self._verified_at
+
+    def verified_within(self, minutes: int, now: Optional[datetime] = None) -> bool:
+        """
+        Whether the passphrase was entered in the last `minutes` minutes
+
+        Always true without a passphrase, since there's nothing to re-enter, and never during review mode, so
+        whoever is reviewing can't use the owner's last entry.
+        """
+        if not self.enabled:
+            return True
+        if self.review_mode:
+            return False
+        if self._verified_at is None:
+            return False
+        return (now or datetime.utcnow()) - self._verified_at <= timedelta(minutes=minutes)
+
+    def status(self) -> Dict[str, Any]:
+        return {
+            "enabled": self.enabled,
+            "unlocked": self.unlocked,
+            "review_mode": self.review_mode,
+            "review_started_at": self._review_started_at,
+        }
+
+    def set_passphrase(self, passphrase: str, current: Optional[str] = None) -> Dict[str, Any]:
+        """
+        Set or change the passphrase (the app stays unlocked)
+
+        Args:
+            passphrase: New passphrase
+            current: Existing passphrase, required when changing it
+
+        Returns:
+            Lock status
+
+        Raises:
+            ValueError: If the new passphrase is too short or current is wrong
+        """
+        if len(passphrase) < MIN_PASSPHRASE_LENGTH:
+            raise ValueError(f"Passphrase must be at least {MIN_PASSPHRASE_LENGTH} characters")
+        if self.enabled and not self._verify(current or ""):
+            raise ValueError("Current passphrase is incorrect")
+
+        # Changing the passphrase keeps any stronger settings the lock was upgraded to
+        algorithm, iterations = DEFAULT_KDF_ALGORITHM, PBKDF2_ITERATIONS
+        if self.enabled:
+            record = self._read()
+            algorithm = record.get("algorithm", DEFAULT_KDF_ALGORITHM)
+            iterations = max(record["iterations"], RECOMMENDED_ITERATIONS[algorithm])
+        self._write(passphrase, iterations, algorithm)
+        self._unlocked = True
+        self._verified_at = datetime.utcnow()
+        return self.status()
+
+    def kdf_settings(self) -> Dict[str, Any]:
+        """
+        How the passphrase hash is derived, and whether stronger settings are recommended
+
+        Lock files keep the settings they were created with, so one from an
+        older version can fall behind the current recommendation.
+
+        Returns:
+            Dict with algorithm, iterations, recommended_iterations, and upgrade_recommended
+
+        Raises:
+            ValueError: If no passphrase is set
+        """
+        if not self.enabled:
+            raise ValueError("No passphrase is set")
+        record = self._read()
+        algorithm = record.get("algorithm", DEFAULT_KDF_ALGORITHM)
+        return {
+            "algorithm": algorithm,
+            "iterations": record["iterations"],
+            "recommended_iterations": RECOMMENDED_ITERATIONS[algorithm],
+            "upgrade_recommended": record["iterations"] < RECOMMENDED_ITERATIONS[algorithm],
+        }
+
+    def upgrade_kdf(
+        self, passphrase: str, algorithm: Optional[str] = None, iterations: Optional[int] = None
+    ) -> Dict[str, Any]:
+        """
+        Re-derive the passphrase hash with stronger settings (the passphrase itself is unchanged)
+
+        Args:
+            passphrase: The app passphrase
+            algorithm: PBKDF2 HMAC digest (defaults to the current one)
+            iterations: Iterations (defaults to the recommendation for the algorithm)
+
+        Returns:
+            The new kdf_settings
+
+        Raises:
+            ValueError: If no passphrase is set, it's wrong, the algorithm isn't
+                supported, or the iterations are below the recommendation
+        """
+        if not self.enabled:
+            raise ValueError("No passphrase is set")
+        if not self._verify(passphrase):
+            raise ValueError("Incorrect passphrase")
+        algorithm = algorithm or self._read().get("algorithm", DEFAULT_KDF_ALGORITHM)
+        if algorithm not in KDF_ALGORITHMS:
+            raise ValueError(f"Invalid algorithm: {algorithm}. Must be one of: {', '.join(KDF_ALGORITHMS)}")
+        minimum = RECOMMENDED_ITERATIONS[algorithm]
+        iterations = iterations or minimum
+        if not minimum <= iterations <= MAX_PBKDF2_ITERATIONS:
+            raise ValueError(f"Iterations for {algorithm} must be between {minimum:,} and {MAX_PBKDF2_ITERATIONS:,}")
+        self._write(passphrase, iterations, algorithm)
+        self._verified_at = datetime.utcnow()
+        return self.kdf_settings()
+
+    def remove_passphrase(self, current: str) -> Dict[str, Any]:
+        """
+        Turn the lock off
+
+        Raises:
+            ValueError: If current is wrong
+        """
+        if self.enabled:
+            if not self._verify(current):
+                raise ValueError("Current passphrase is incorrect")
+            self.lock_path.unlink()
+        self._unlocked = True
+        return self.status()
+
+    def unlock(self, passphrase: str, review_mode: bool = False) -> Dict[str, Any]:
+        """
+        Unlock the app
+
+        Args:
+            passphrase: The app passphrase
+            review_mode: Unlock read-only (for handing the app to an accountant)
+
+        Raises:
+            ValueError: If the passphrase is wrong
+        """
+        if self.enabled and not self._verify(passphrase):
+            raise ValueError("Incorrect passphrase")
+        self._unlocked = True
+        self._verified_at = None if review_mode else datetime.utcnow()
+        self._review_started_at = datetime.utcnow().isoformat() if review_mode else None
+        return self.status()
+
+    def verify(self, passphrase: str) -> Dict[str, Any]:
+        """
+        Re-enter the passphrase while unlocked, so sensitive actions are allowed again
+
+        Raises:
+            ValueError: If the app is locked or the passphrase is wrong
+        """
+        if not self.unlocked:
+            raise ValueError("The app is locked; unlock it instead")
+        if self.enabled and not self._verify(passphrase):
+            raise ValueError("Incorrect passphrase")
+        self._verified_at = datetime.utcnow()
+        return self.status()
+
+    def start_review(self) -> Dict[str, Any]:
+        """
+        Switch an unlocked app into read-only review mode
+
+        The owner's last passphrase entry stops counting as recent, so nothing
+        that needs re-entering it works until the review ends.
+
+        Raises:
+            ValueError: If no passphrase is set (review mode could be left without one)
+        """
+        if not self.enabled:
+            raise ValueError("Set a passphrase before starting review mode so it can't be turned off without it")
+        if not self.review_mode:
+            self._review_started_at = datetime.utcnow().isoformat()
+        self._verified_at = None
+        return self.status()
+
+    def end_review(self, passphrase: str) -> Dict[str, Any]:
+        """
+        Leave review mode
+
+        Raises:
+            ValueError: If the passphrase is wrong
+        """
+        if self.enabled and not self._verify(passphrase):
+            raise ValueError("Incorrect passphrase")
+        self._review_started_at = None
+        self._verified_at = datetime.utcnow()
+        return self.status()
+
+    def lock(self) -> Dict[str, Any]:
+        """
+        Lock the app until the passphrase is entered again
+
+        Raises:
+            ValueError: If no passphrase has been set
+        """
+        if not self.enabled:
+            raise ValueError("Set a passphrase before locking the app")
+        self._unlocked = False
+        self._verified_at = None
+        return self.status()
+
+    def _read(self) -> Dict[str, Any]:
+        with open(self.lock_path, "r", encoding="utf-8") as f:
+            return json.load(f)
+
+    def _write(self, passphrase: str, iterations: int, algorithm: str) -> None:
+        salt = secrets.token_bytes(16)
+        record = {
+            "salt": base64.b64encode(salt).decode("ascii"),
+            "algorithm": algorithm,
+            "iterations": iterations,
+            "hash": base64.b64encode(self._derive(passphrase, salt, iterations, algorithm)).decode("ascii"),
+        }
+        self.lock_path.parent.mkdir(parents=True, exist_ok=True)
+        tmp_path = self.lock_path.with_suffix(".tmp")
+        fd = os.open(tmp_path, os.O_WRONLY | os.O_CREAT | os.O_TRUNC, 0o600)
+        with os.fdopen(fd, "w", encoding="utf-8") as f:
+            json.dump(record, f)
+        tmp_path.replace(self.lock_path)
+
+    def _verify(self, passphrase: str) -> bool:
+        return self.matches(self._read(), passphrase)
+
+    @classmethod
+    def matches(cls, record: Dict[str, Any], passphrase: str) -> bool:
+        """Whether a passphrase matches a lock file's record (from this install or a backup)"""
+        expected = base64.b64decode(record["hash"])
+        algorithm = record.get("algorithm", DEFAULT_KDF_ALGORITHM)
+        if algorithm not in KDF_ALGORITHMS:
+            return False
+        actual = cls._derive(passphrase, base64.b64decode(record["salt"]), record["iterations"], algorithm)
+        return hmac.compare_digest(expected, actual)
+
+    @staticmethod
+    def _derive(passphrase: str, salt: bytes, iterations: int, algorithm: str = DEFAULT_KDF_ALGORITHM) -> bytes:
+        return hashlib.pbkdf2_hmac(algorithm, passphrase.encode("utf-8"), salt, iterations)
@@ -0,0 +1,120 @@
+"""
+Application Log
+Rotating JSON Lines log files, scrubbed of sensitive data before anything is written
+"""
+import json
+import logging
+import logging.handlers
+from datetime import datetime
+from pathlib import Path
+from typing import Dict, List, Any, Optional
+
+from app.utils.scrubbing import scrub
+
+LOG_DIR = ".logs"
+LOG_FILE_NAME = "app.log"
+MAX_LOG_BYTES = 1_000_000
+LOG_BACKUP_COUNT = 5
+
+LOG_LEVELS = ["DEBUG", "INFO", "WARNING", "ERROR", "CRITICAL"]
+
+
+class ScrubbingJsonFormatter(logging.Formatter):
+    """One JSON object per line, with the message and traceback scrubbed"""
+
+    def format(self, record: logging.LogRecord) -> str:
+        entry = {
+            "at": datetime.utcfromtimestamp(record.created).isoformat(),
+            "level": record.levelname,
+            "logger": record.name,
+            "message": scrub(record.getMessage()),
+        }
+        if record.exc_info:
+            entry["exception"] = scrub(self.formatException(record.exc_info))
+        return json.dumps(entry, ensure_ascii=False)
+
+
+class ScrubbingFormatter(logging.Formatter):
+    """Plain-text console format with the same scrubbing as the log files"""
+
+    def format(self, record: logging.LogRecord) -> str:
+        return scrub(super().format(record))
+
+
+def configure_logging(log_dir: str = LOG_DIR, level: int = logging.INFO) -> logging.Handler:
+    """
+    Send every log record to the console and a rotating file in log_dir
+
+    Calling it again replaces the handlers it installed before.
+
+    Args:
+        log_dir: Folder for app.log and its rotated copies (app.log.1 ...)
+        level: Minimum level recorded
+
+    Returns:
+        The file handler
+    """
+    root = logging.getLogger()
+    for handler in list(root.handlers):
+        if getattr(handler, "_app_log", False):
+            root.removeHandler(handler)
+            handler.close()
+
+    path = Path(log_dir)
+    path.mkdir(parents=True, exist_ok=True)
+    file_handler = logging.handlers.RotatingFileHandler(
+        path / LOG_FILE_NAME, maxBytes=MAX_LOG_BYTES, backupCount=LOG_BACKUP_COUNT, encoding="utf-8"
+    )
+    file_handler.setFormatter(ScrubbingJsonFormatter())
+    console_handler = logging.StreamHandler()
+    console_handler.setFormatter(ScrubbingFormatter("%(levelname)s:%(name)s:%(message)s"))
+    for handler in (file_handler, console_handler):
+        handler._app_log = True
+        root.addHandler(handler)
+    root.setLevel(level)
+    return file_handler
+
+
+def get_recent_logs(
+    level: Optional[str] = None,
+    limit: int = 200,
+    log_dir: str = LOG_DIR,
+) -> List[Dict[str, Any]]:
+    """
+    Read the newest log entries, across rotated files
+
+    Args:
+        level: Minimum level (DEBUG, INFO, WARNING, ERROR, CRITICAL)
+        limit: Maximum entries
+        log_dir: Folder holding the log files
+
+    Returns:
+        Entries (at, level, logger, message, exception), newest first
+
+    Raises:
+        ValueError: If level is unknown
+    """
+    if level is not None:
+        level = level.upper()
+        if level not in LOG_LEVELS:
+            raise ValueError(f"Invalid level: {level}. Must be one of: {', '.join(LOG_LEVELS)}")
+    minimum = LOG_LEVELS.index(level) if level else 0
+
+    base = Path(log_dir) / LOG_FILE_NAME
+    files = [base] + [base.with_name(f"{LOG_FILE_NAME}.{index}") for index in range(1, LOG_BACKUP_COUNT + 1)]
+    entries: List[Dict[str, Any]] = []
+    for path in files:
+        if len(entries) >= limit or not path.exists():
+            continue
+        with open(path, 'r', encoding='utf-8', errors='replace') as f:
+            lines = f.readlines()
+        for line in reversed(lines):
+            try:
+                entry = json.loads(line)
+            except json.JSONDecodeError:
+                continue
+            if entry.get("level") in LOG_LEVELS and LOG_LEVELS.index(entry["level"]) >= minimum:
+                entries.append(entry)
+                if len(entries) >= limit:
+                    break
+    return entries
@@ -0,0 +1,104 @@
+"""
+Security Audit Log
+Append-only record of sensitive actions (exports, imports, secret reveals, key and passphrase changes, deletions)
+and their outcome
+"""
+import json
+from datetime import datetime
+from pathlib import Path
+from typing import Dict, List, Any, Optional
+
+AUDIT_ACTIONS = ["authentication", "passphrase_change", "key_change", "export", "import", "reveal", "deletion"]
+AUDIT_OUTCOMES = ["success", "denied", "failed"]
+
+
+class AuditLog:
+    """JSON Lines file of security events from the API and the command line"""
+
+    def __init__(self, log_path: str = ".audit_log.jsonl"):
+        """
+        Initialize audit log
+
+        Args:
+            log_path: JSON Lines file the events are appended to
+        """
+        self.log_path = Path(log_path)
+
+    def record(
+        self,
+        action: str,
+        target: str,
+        outcome: str,
+        source: str = "api",
+        detail: Optional[str] = None,
+    ) -> Dict[str, Any]:
+        """
+        Append an event
+
+        Args:
+            action: One of AUDIT_ACTIONS
+            target: Request (method and path) or command that was run; never its arguments' secrets
+            outcome: success, denied (locked or passphrase needed), or failed
+            source: api or cli
+            detail: Status code or error message
+
+        Returns:
+            The event
+
+        Raises:
+            ValueError: If the action or outcome isn't recognized
+        """
+        if action not in AUDIT_ACTIONS:
+            raise ValueError(f"Invalid action: {action}. Must be one of: {', '.join(AUDIT_ACTIONS)}")
+        if outcome not in AUDIT_OUTCOMES:
+            raise ValueError(f"Invalid outcome: {outcome}. Must be one of: {', '.join(AUDIT_OUTCOMES)}")
+        event = {
+            "at": datetime.utcnow().isoformat(),
+            "source": source,
+            "action": action,
+            "target": target,
+            "outcome": outcome,
+            "detail": detail,
+        }
+        self.log_path.parent.mkdir(parents=True, exist_ok=True)
+        with open(self.log_path, 'a', encoding='utf-8') as f:
+            f.write(json.dumps(event) + "\n")
+        return event
+
+    def entries(
+        self,
+        limit: Optional[int] = None,
+        since: Optional[str] = None,
+        action: Optional[str] = None,
+        outcome: Optional[str] = None,
+    ) -> List[Dict[str, Any]]:
+        """
+        Read events, newest first
+
+        Args:
+            limit: Maximum number of events
+            since: Only events at or after this ISO timestamp
+            action: Only events of this action
+            outcome: Only events with this outcome
+
+        Returns:
+            List of events
+        """
+        if not self.log_path.exists():
+            return []
+        events = []
+        with open(self.log_path, 'r', encoding='utf-8') as f:
+            for line in f:
+                try:
+                    event = json.loads(line)
+                except json.JSONDecodeError:
+                    continue
+                if since is not None and event.get("at", "") < since:
+                    continue
+                if action is not None and event.get("action") != action:
+                    continue
+                if outcome is not None and event.get("outcome") != outcome:
+                    continue
+                events.append(event)
+        events.reverse()
+        return events[:limit] if limit is not None else events
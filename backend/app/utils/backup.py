@@ -0,0 +1,230 @@
+"""
+Data Backups
+Zips the local data folders and files into a timestamped archive with a checksum manifest, and verifies archives
+"""
+import hashlib
+import json
+import os
+import zipfile
+from datetime import datetime
+from pathlib import Path, PurePosixPath
+from typing import Callable, Dict, List, Any, Optional, Sequence
+
+from app.utils.app_lock import AppLock
+from app.utils.field_encryption import KEY_ENV_VAR, DecryptionError, FieldCipher
+
+# Folders and files the backend writes, relative to its working directory
+DATA_PATHS = [
+    ".tax_returns",
+    ".documents",
+    ".conversation_history",
+    ".jobs",
+    ".app_settings.json",
+    ".app_settings_history.jsonl",
+    ".app_lock.json",
+    ".reminders.json",
+    ".invoices.json",
+    ".clients.json",
+    ".onboarding.json",
+    ".review_activity.jsonl",
+    ".audit_log.jsonl",
+]
+
+# Encryption keys are left out so a stolen backup doesn't carry the key to its own data;
+# back the key up separately (or set FIELD_ENCRYPTION_KEY)
+EXCLUDED_NAMES = {".field_encryption.key"}
+
+MANIFEST_NAME = "manifest.json"
+BACKUP_FORMAT_VERSION = 1
+
+
+def create_backup(
+    backup_dir: str,
+    paths: Sequence[str] = DATA_PATHS,
+    base_dir: str = ".",
+    progress: Optional[Callable[[Dict[str, Any]], None]] = None,
+) -> Dict[str, Any]:
+    """
+    Write a backup archive
+
+    Missing paths are skipped (a fresh install has no jobs or reminders yet).
+
+    Args:
+        backup_dir: Folder the archive is written to
+        paths: Data folders and files to include, relative to base_dir
+        base_dir: Directory the paths are relative to
+        progress: Called with an event dict after each file
+
+    Returns:
+        Dict with path, files, bytes, created_at, and excluded (skipped key files)
+    """
+    base = Path(base_dir)
+    files: List[Path] = []
+    excluded: List[str] = []
+    for name in paths:
+        path = base / name
+        candidates = sorted(p for p in path.rglob("*") if p.is_file()) if path.is_dir() else [path] if path.is_file() else []
+        for candidate in candidates:
+            if candidate.name in EXCLUDED_NAMES:
+                excluded.append(candidate.relative_to(base).as_posix())
+            else:
+                files.append(candidate)
+
+    created_at = datetime.utcnow()
+    target_dir = Path(backup_dir)
+    target_dir.mkdir(parents=True, exist_ok=True)
+    archive_path = target_dir / f"backup_{created_at.strftime('%Y%m%d_%H%M%S_%f')}.zip"
+
+    manifest: Dict[str, Any] = {
+        "version": BACKUP_FORMAT_VERSION,
+        "created_at": created_at.isoformat(),
+        "files": {},
+        "excluded": excluded,
+    }
+    total_bytes = 0
+    with zipfile.ZipFile(archive_path, "w", compression=zipfile.ZIP_DEFLATED) as archive:
+        for index, path in enumerate(files, start=1):
+            content = path.read_bytes()
+            name = path.relative_to(base).as_posix()
+            archive.writestr(name, content)
+            manifest["files"][name] = {"size": len(content), "sha256": hashlib.sha256(content).hexdigest()}
+            total_bytes += len(content)
+            if progress:
+                progress({"current": index, "total": len(files), "percent": round(index / len(files) * 100), "file": name})
+        archive.writestr(MANIFEST_NAME, json.dumps(manifest, indent=2))
+
+    return {
+        "path": str(archive_path),
+        "files": len(files),
+        "bytes": total_bytes,
+        "created_at": manifest["created_at"],
+        "excluded": excluded,
+    }
+
+
+def verify_backup(
+    archive_path: str,
+    passphrase: Optional[str] = None,
+    cipher: Optional[FieldCipher] = None,
+    key_path: str = ".tax_returns/.field_encryption.key",
+) -> Dict[str, Any]:
+    """
+    Check that a backup archive is intact and could be restored, without restoring it
+
+    Every file is checked against the manifest's size and SHA-256, JSON
+    files must parse, and every path must land inside a data folder. The
+    archive's encrypted fields (bank details, IP PINs) are decrypted with
+    this install's key, since the key itself is never backed up, and a
+    passphrase is checked against the app lock saved in the backup. Nothing
+    is written.
+
+    Args:
+        archive_path: Archive written by create_backup
+        passphrase: App lock passphrase to check against the backup's lock
+        cipher: Cipher for the encrypted fields (defaults to this install's key)
+        key_path: Key file the default cipher reads (the return store's); it isn't created when missing
+
+    Returns:
+        Dict with path, valid, created_at, files, bytes, problems ({file,
+        problem}), passphrase (verified, wrong, not_checked, or no_lock),
+        encrypted_fields ({checked, unreadable}), and warnings
+
+    Raises:
+        ValueError: If the file isn't a backup archive or is from a newer version
+    """
+    path = Path(archive_path)
+    if not zipfile.is_zipfile(path):
+        raise ValueError(f"Not a backup archive: {archive_path}")
+    if cipher is None and (os.getenv(KEY_ENV_VAR) or Path(key_path).exists()):
+        cipher = FieldCipher(key_path=key_path)
+
+    problems: List[Dict[str, str]] = []
+    warnings: List[str] = []
+    encrypted = {"checked": 0, "unreadable": 0}
+    contents: Dict[str, bytes] = {}
+    with zipfile.ZipFile(path) as archive:
+        try:
+            manifest = json.loads(archive.read(MANIFEST_NAME))
+        except (KeyError, json.JSONDecodeError):
+            raise ValueError(f"Not a backup archive (no readable {MANIFEST_NAME}): {archive_path}")
+        if manifest.get("version", 0) > BACKUP_FORMAT_VERSION:
+            raise ValueError(f"Backup format {manifest['version']} is newer than this app supports")
+        corrupt = archive.testzip()
+        if corrupt:
+            problems.append({"file": corrupt, "problem": "Fails the archive's CRC check"})
+        names = set(archive.namelist()) - {MANIFEST_NAME}
+        for name, entry in manifest.get("files", {}).items():
+            if name not in names:
+                problems.append({"file": name, "problem": "Listed in the manifest but missing"})
+                continue
+            content = archive.read(name)
+            if len(content) != entry.get("size") or hashlib.sha256(content).hexdigest() != entry.get("sha256"):
+                problems.append({"file": name, "problem": "Checksum doesn't match the manifest"})
+            else:
+                contents[name] = content
+        for name in sorted(names - set(manifest.get("files", {}))):
+            problems.append({"file": name, "problem": "Not listed in the manifest"})
+
+    data_roots = {PurePosixPath(name).parts[0] for name in DATA_PATHS}
+    for name, content in contents.items():
+        parts = PurePosixPath(name).parts
+        if PurePosixPath(name).is_absolute() or ".." in parts or parts[0] not in data_roots:
+            problems.append({"file": name, "problem": "Would be restored outside the data folders"})
+            continue
+        try:
+            if name.endswith(".jsonl"):
+                records = [json.loads(line) for line in content.decode("utf-8").splitlines() if line.strip()]
+            elif name.endswith(".json"):
+                records = [json.loads(content)]
+            else:
+                continue
+        except (UnicodeDecodeError, json.JSONDecodeError):
+            problems.append({"file": name, "problem": "Isn't valid JSON"})
+            continue
+        if parts[0] == ".tax_returns" and cipher is not None:
+            for record in records:
+                tokens = [
+                    (record.get("direct_deposit") or {}).get(field) for field in ("routing_number", "account_number")
+                ] + [details.get("pin") for details in (record.get("ip_pins") or {}).values()] + [
+                    alimony.get("recipient_ssn") for alimony in record.get("alimony", [])
+                ]
+                for token in filter(None, tokens):
+                    encrypted["checked"] += 1
+                    try:
+                        cipher.decrypt(token)
+                    except DecryptionError:
+                        encrypted["unreadable"] += 1
+    if encrypted["unreadable"]:
+        warnings.append(
+            f"{encrypted['unreadable']} encrypted field(s) can't be read with this install's key; restore "
+            "alongside the key they were saved with"
+        )
+    if cipher is None:
+        warnings.append("No encryption key here, so encrypted fields weren't checked")
+
+    lock_name = ".app_lock.json"
+    if lock_name not in contents:
+        passphrase_check = "no_lock"
+    elif passphrase is None:
+        passphrase_check = "not_checked"
+    else:
+        try:
+            passphrase_check = "verified" if AppLock.matches(json.loads(contents[lock_name]), passphrase) else "wrong"
+        except (KeyError, ValueError):
+            problems.append({"file": lock_name, "problem": "Lock record is incomplete"})
+            passphrase_check = "not_checked"
+    if passphrase_check == "wrong":
+        warnings.append("The passphrase doesn't match the one saved in the backup")
+
+    return {
+        "path": str(path),
+        "valid": not problems,
+        "created_at": manifest.get("created_at"),
+        "files": len(manifest.get("files", {})),
+        "bytes": sum(entry.get("size", 0) for entry in manifest.get("files", {}).values()),
+        "problems": problems,
+        "passphrase": passphrase_check,
+        "encrypted_fields": encrypted,
+        "excluded": manifest.get("excluded", []),
+        "warnings": warnings,
+    }
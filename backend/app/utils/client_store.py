@@ -0,0 +1,166 @@
+"""
+Client Storage
+Practitioner client records (contact info and engagement status), kept in a JSON file
+"""
+import json
+import os
+from datetime import datetime
+from pathlib import Path
+from typing import Dict, List, Any, Optional
+
+# Where each client's engagement stands, in the usual order
+ENGAGEMENT_STATUSES = ["prospect", "engaged", "awaiting_documents", "in_preparation", "in_review", "completed", "inactive"]
+
+
+class ClientStore:
+    """File-based client records, keyed by client ID"""
+
+    # Fields update_client may change
+    EDITABLE_FIELDS = ["name", "email", "phone", "address", "engagement_status", "notes"]
+
+    def __init__(self, storage_path: str = ".clients.json"):
+        """
+        Initialize client store
+
+        Args:
+            storage_path: JSON file holding the clients
+        """
+        self.storage_path = Path(storage_path)
+
+    def _load(self) -> Dict[str, Dict[str, Any]]:
+        if not self.storage_path.exists():
+            return {}
+        try:
+            with open(self.storage_path, 'r', encoding='utf-8') as f:
+                return json.load(f)
+        except (json.JSONDecodeError, IOError):
+            return {}
+
+    def _save(self, clients: Dict[str, Dict[str, Any]]) -> None:
+        self.storage_path.parent.mkdir(parents=True, exist_ok=True)
+        with open(self.storage_path, 'w', encoding='utf-8') as f:
+            json.dump(clients, f, indent=2, ensure_ascii=False)
+
+    @staticmethod
+    def _check(client: Dict[str, Any]) -> None:
+        """Validate a client's fields"""
+        if not str(client.get("name") or "").strip():
+            raise ValueError("Client name is required")
+        if client["engagement_status"] not in ENGAGEMENT_STATUSES:
+            raise ValueError(
+                f"Invalid engagement status: {client['engagement_status']}. "
+                f"Must be one of: {', '.join(ENGAGEMENT_STATUSES)}"
+            )
+        if client.get("email") and "@" not in client["email"]:
+            raise ValueError(f"Invalid email address: {client['email']}")
+
+    def create_client(
+        self,
+        name: str,
+        email: str = "",
+        phone: str = "",
+        address: str = "",
+        engagement_status: str = "engaged",
+        notes: str = "",
+    ) -> Dict[str, Any]:
+        """
+        Add a client
+
+        Args:
+            name: Client (household or business) name
+            email: Contact email
+            phone: Contact phone
+            address: Mailing address
+            engagement_status: One of ENGAGEMENT_STATUSES
+            notes: Practitioner notes
+
+        Returns:
+            The client record
+
+        Raises:
+            ValueError: If the name is blank, or the status or email is invalid
+        """
+        now = datetime.utcnow().isoformat()
+        client = {
+            "id": f"cli_{os.urandom(8).hex()}",
+            "name": name.strip(),
+            "email": email.strip(),
+            "phone": phone.strip(),
+            "address": address.strip(),
+            "engagement_status": engagement_status,
+            "notes": notes,
+            "created_at": now,
+            "updated_at": now,
+        }
+        self._check(client)
+        clients = self._load()
+        clients[client["id"]] = client
+        self._save(clients)
+        return client
+
+    def get_client(self, client_id: str) -> Optional[Dict[str, Any]]:
+        """
+        Retrieve a client
+
+        Returns:
+            Client dict or None if not found
+        """
+        return self._load().get(client_id)
+
+    def list_clients(self, engagement_status: Optional[str] = None) -> List[Dict[str, Any]]:
+        """
+        List clients by name
+
+        Args:
+            engagement_status: Only clients at this status
+
+        Returns:
+            Client records
+        """
+        if engagement_status is not None and engagement_status not in ENGAGEMENT_STATUSES:
+            raise ValueError(
+                f"Invalid engagement status: {engagement_status}. Must be one of: {', '.join(ENGAGEMENT_STATUSES)}"
+            )
+        clients = [
+            c for c in self._load().values()
+            if engagement_status is None or c["engagement_status"] == engagement_status
+        ]
+        return sorted(clients, key=lambda c: c["name"].lower())
+
+    def update_client(self, client_id: str, **changes: Any) -> Dict[str, Any]:
+        """
+        Change a client's contact info, status, or notes
+
+        Returns:
+            The updated client
+
+        Raises:
+            KeyError: If the client doesn't exist
+            ValueError: If a field can't be changed or the result is invalid
+        """
+        clients = self._load()
+        if client_id not in clients:
+            raise KeyError(f"Client not found: {client_id}")
+        unknown = set(changes) - set(self.EDITABLE_FIELDS)
+        if unknown:
+            raise ValueError(f"Client fields can't be changed: {', '.join(sorted(unknown))}")
+        client = {**clients[client_id], **changes, "updated_at": datetime.utcnow().isoformat()}
+        for field in ("name", "email", "phone", "address"):
+            client[field] = str(client.get(field) or "").strip()
+        self._check(client)
+        clients[client_id] = client
+        self._save(clients)
+        return client
+
+    def delete_client(self, client_id: str) -> bool:
+        """
+        Delete a client record
+
+        Returns:
+            True if deleted, False if not found
+        """
+        clients = self._load()
+        if clients.pop(client_id, None) is None:
+            return False
+        self._save(clients)
+        return True
@@ -4,24 +4,32 @@ Persistent storage for agent conversations using file-based system
 """
 import json
 import os
-from typing import Dict, List, Any, Optional
+from typing import Callable, Dict, List, Any, Optional
 from datetime import datetime
 from pathlib import Path
 import hashlib
 
+from .embeddings import EmbeddingError, EmbeddingProvider, dense_cosine
+from .keyword_search import cosine_similarity, keyword_vector
+
 
 class ConversationStore:
     """File-based conversation history storage"""
 
-    def __init__(self, storage_dir: str = ".conversation_history"):
+    # Minimum cosine similarity for a message to count as a keyword search hit
+    MIN_SEARCH_SCORE = 0.1
+
+    def __init__(self, storage_dir: str = ".conversation_history", embedder: Optional[EmbeddingProvider] = None):
         """
         Initialize conversation store
 
         Args:
             storage_dir: Directory to store conversation files
+            embedder: Embedding provider for semantic search (keyword search without one)
         """
         self.storage_dir = Path(storage_dir)
         self.storage_dir.mkdir(exist_ok=True)
+        self.embedder = embedder
 
     def _get_conversation_file(self, session_id: str) -> Path:
         """Get file path for a conversation session"""
@@ -63,8 +71,14 @@ class ConversationStore:
             "role": role,
             "content": content,
             "timestamp": datetime.utcnow().isoformat(),
-            "metadata": metadata or {}
+            "metadata": metadata or {},
         }
+        if self.embedder:
+            # An unreachable provider doesn't lose the message; search embeds it later
+            try:
+                message["embedding"] = self._stored_embedding(self.embedder.embed([content])[0])
+            except EmbeddingError:
+                pass
         conversation["messages"].append(message)
         conversation["updated_at"] = datetime.utcnow().isoformat()
 
@@ -72,6 +86,16 @@ class ConversationStore:
         with open(file_path, 'w', encoding='utf-8') as f:
             json.dump(conversation, f, indent=2, ensure_ascii=False)
 
+    def import_conversation(self, conversation: Dict[str, Any]) -> None:
+        """
+        Store a conversation exported from another install (replacing one with the same session)
+
+        Args:
+            conversation: Conversation dict with session_id and messages
+        """
+        with open(self._get_conversation_file(conversation["session_id"]), 'w', encoding='utf-8') as f:
+            json.dump(conversation, f, indent=2, ensure_ascii=False)
+
     def get_conversation(self, session_id: str) -> Optional[Dict[str, Any]]:
         """
         Retrieve conversation history
@@ -147,3 +171,142 @@ class ConversationStore:
         # Sort by updated_at descending
         sessions.sort(key=lambda x: x.get("updated_at", ""), reverse=True)
         return sessions
+
+    def _stored_embedding(self, vector: List[float]) -> Dict[str, Any]:
+        """A message's embedding as saved, tagged with the model so a model change is noticed"""
+        return {"model": self.embedder.model, "vector": [round(x, 6) for x in vector]}
+
+    def _current_embedding(self, message: Dict[str, Any]) -> Optional[List[float]]:
+        """A message's stored embedding, if it came from the current model"""
+        stored = message.get("embedding")
+        # Older histories hold keyword vectors under this key, which have no model
+        if isinstance(stored, dict) and stored.get("model") == self.embedder.model:
+            return stored.get("vector")
+        return None
+
+    def reindex_embeddings(self, progress: Optional[Callable[[Dict[str, Any]], None]] = None) -> Dict[str, int]:
+        """
+        Embed every stored message with the current provider (after setting one up or changing the model)
+
+        Args:
+            progress: Called with an event dict after each conversation
+
+        Returns:
+            Counts of conversations and messages reindexed
+
+        Raises:
+            ValueError: If no embedding provider is configured or it fails
+        """
+        if not self.embedder:
+            raise ValueError("No embedding provider is configured; set embedding_url and embedding_model")
+        files = sorted(self.storage_dir.glob("conversation_*.json"))
+        counts = {"conversations": 0, "messages": 0}
+        for index, file_path in enumerate(files, start=1):
+            try:
+                with open(file_path, 'r', encoding='utf-8') as f:
+                    data = json.load(f)
+            except (json.JSONDecodeError, IOError):
+                continue
+
+            messages = data.get("messages", [])
+            try:
+                vectors = self.embedder.embed([m.get("content", "") for m in messages])
+            except EmbeddingError as e:
+                raise ValueError(str(e))
+            for message, vector in zip(messages, vectors):
+                message["embedding"] = self._stored_embedding(vector)
+            with open(file_path, 'w', encoding='utf-8') as f:
+                json.dump(data, f, indent=2, ensure_ascii=False)
+            counts["conversations"] += 1
+            counts["messages"] += len(data.get("messages", []))
+            if progress:
+                progress({"current": index, "total": len(files), "percent": round(index / len(files) * 100)})
+        return counts
+
+    def search_messages(
+        self,
+        query: str,
+        limit: int = 5,
+        role: Optional[str] = "assistant",
+        since: Optional[str] = None,
+    ) -> List[Dict[str, Any]]:
+        """
+        Search across all stored conversations
+
+        With an embedding provider the search is semantic: answers are ranked
+        by how close their meaning is to the query, and messages without an
+        embedding from the current model are embedded on the fly. Without one,
+        or when the provider fails, messages match on shared words, stems, and
+        near spellings (see keyword_search). Each result's "match" says which.
+
+        Args:
+            query: Natural-language query ("what did you say about my home office?")
+            limit: Maximum number of results
+            role: Only match messages with this role (None for all roles)
+            since: Optional ISO timestamp; older messages are skipped
+
+        Returns:
+            Matches sorted by relevance, each with the preceding user question
+        """
+        candidates = []
+        for file_path in self.storage_dir.glob("conversation_*.json"):
+            try:
+                with open(file_path, 'r', encoding='utf-8') as f:
+                    data = json.load(f)
+            except (json.JSONDecodeError, IOError):
+                continue
+
+            messages = data.get("messages", [])
+            for index, message in enumerate(messages):
+                if role and message.get("role") != role:
+                    continue
+                if since and message.get("timestamp", "") < since:
+                    continue
+                previous = messages[index - 1] if index > 0 else None
+                candidates.append((data.get("session_id", "unknown"), message, previous))
+
+        scored = None
+        match = "semantic"
+        if self.embedder and candidates:
+            try:
+                scored = self._semantic_scores(query, [message for _, message, _ in candidates])
+            except EmbeddingError:
+                pass
+        if scored is None:
+            scored = self._keyword_scores(query, [message for _, message, _ in candidates])
+            match = "keyword"
+
+        results = []
+        for (session_id, message, previous), score in zip(candidates, scored):
+            if score is None:
+                continue
+            results.append({
+                "session_id": session_id,
+                "role": message.get("role"),
+                "content": message.get("content", ""),
+                "timestamp": message.get("timestamp"),
+                "question": previous.get("content") if previous and previous.get("role") == "user" else None,
+                "score": round(score, 4),
+                "match": match,
+            })
+
+        results.sort(key=lambda x: x["score"], reverse=True)
+        return results[:limit]
+
+    def _semantic_scores(self, query: str, messages: List[Dict[str, Any]]) -> List[Optional[float]]:
+        """Cosine similarity of each message's embedding to the query's"""
+        vectors = [self._current_embedding(message) for message in messages]
+        missing = [i for i, vector in enumerate(vectors) if vector is None]
+        embedded = self.embedder.embed([query] + [messages[i].get("content", "") for i in missing])
+        for i, vector in zip(missing, embedded[1:]):
+            vectors[i] = vector
+        return [dense_cosine(embedded[0], vector) for vector in vectors]
+
+    def _keyword_scores(self, query: str, messages: List[Dict[str, Any]]) -> List[Optional[float]]:
+        """Keyword similarity of each message to the query (None below MIN_SEARCH_SCORE)"""
+        query_vector = keyword_vector(query)
+        scores: List[Optional[float]] = []
+        for message in messages:
+            score = cosine_similarity(query_vector, keyword_vector(message.get("content", ""))) if query_vector else 0
+            scores.append(score if score >= self.MIN_SEARCH_SCORE else None)
+        return scores
@@ -0,0 +1,276 @@
+"""
+Data Export and Import
+Versioned JSON envelope holding every stored entity, and an importer that resolves ID conflicts
+"""
+import base64
+import copy
+import os
+from datetime import datetime
+from typing import Callable, Dict, Any
+
+from app.agents.prompts import validate_prompt_addendum
+from app.utils.conversation_store import ConversationStore
+from app.utils.document_store import DocumentStore
+from app.utils.reminder_store import ReminderStore
+from app.utils.return_store import ReturnLockedError, ReturnStore
+from app.utils.settings_store import AppSettings, SettingsStore
+
+EXPORT_FORMAT = "ai-tax-cpa-agent-export"
+EXPORT_SCHEMA_VERSION = 1
+
+# skip keeps the local record, overwrite replaces it, keep_both imports under a new ID
+CONFLICT_STRATEGIES = ["skip", "overwrite", "keep_both"]
+
+# Upgrades an envelope's data from the keyed schema version to the next one
+MIGRATIONS: Dict[int, Callable[[Dict[str, Any]], Dict[str, Any]]] = {}
+
+
+def export_data(
+    return_store: ReturnStore,
+    document_store: DocumentStore,
+    settings_store: SettingsStore,
+    conversation_store: ConversationStore,
+    reminder_store: ReminderStore,
+    include_documents: bool = False,
+) -> Dict[str, Any]:
+    """
+    Export every return, document record, conversation, setting, and reminder state
+
+    Bank details, IP PINs, and alimony recipient SSNs are decrypted so the
+    export can be imported on an install with a different encryption key;
+    treat the file as sensitive.
+
+    Args:
+        include_documents: Embed document files as base64 (otherwise only records)
+
+    Returns:
+        Envelope with format, schema_version, exported_at, counts, and data
+    """
+    returns = []
+    for summary in return_store.list_returns():
+        tax_return = return_store.get_return(summary["return_id"])
+        if tax_return.get("direct_deposit"):
+            tax_return["direct_deposit"] = return_store.get_direct_deposit(tax_return["return_id"], reveal=True)
+        if tax_return.get("ip_pins"):
+            tax_return["ip_pins"] = return_store.get_ip_pins(tax_return["return_id"], reveal=True)
+        if tax_return.get("alimony"):
+            tax_return["alimony"] = return_store.get_alimony(tax_return["return_id"], reveal=True)
+        returns.append(tax_return)
+
+    documents = []
+    missing = []
+    for record in document_store.list_documents(include_archived=True):
+        record = {k: v for k, v in record.items() if k != "thumbnail_path"}
+        if include_documents:
+            try:
+                record["content_base64"] = base64.b64encode(document_store.read_document(record["document_id"])).decode()
+            except FileNotFoundError:
+                missing.append(record["document_id"])
+        documents.append(record)
+
+    conversations = [
+        conversation_store.get_conversation(session["session_id"]) for session in conversation_store.list_sessions()
+    ]
+    data = {
+        "returns": returns,
+        "documents": documents,
+        "conversations": [c for c in conversations if c],
+        "settings": settings_store.get_settings().model_dump(),
+        "reminders": reminder_store.get_states(),
+    }
+    return {
+        "format": EXPORT_FORMAT,
+        "schema_version": EXPORT_SCHEMA_VERSION,
+        "exported_at": datetime.utcnow().isoformat(),
+        "includes_document_files": include_documents,
+        "missing_document_files": missing,
+        "counts": {key: len(value) for key, value in data.items() if isinstance(value, list)},
+        "data": data,
+    }
+
+
+def upgrade_envelope(envelope: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    Check an envelope and migrate older schema versions to the current one
+
+    Raises:
+        ValueError: If it isn't an export, or was written by a newer version
+    """
+    if envelope.get("format") != EXPORT_FORMAT or not isinstance(envelope.get("data"), dict):
+        raise ValueError("Not an AI Tax CPA Agent data export")
+    version = envelope.get("schema_version")
+    if not isinstance(version, int) or version < 1:
+        raise ValueError(f"Invalid export schema version: {version}")
+    if version > EXPORT_SCHEMA_VERSION:
+        raise ValueError(
+            f"Export schema version {version} is newer than this app supports ({EXPORT_SCHEMA_VERSION}); update the app first"
+        )
+
+    envelope = copy.deepcopy(envelope)
+    while envelope["schema_version"] < EXPORT_SCHEMA_VERSION:
+        envelope["data"] = MIGRATIONS[envelope["schema_version"]](envelope["data"])
+        envelope["schema_version"] += 1
+    return envelope
+
+
+def import_data(
+    envelope: Dict[str, Any],
+    return_store: ReturnStore,
+    document_store: DocumentStore,
+    settings_store: SettingsStore,
+    conversation_store: ConversationStore,
+    reminder_store: ReminderStore,
+    on_conflict: str = "skip",
+    include_settings: bool = True,
+) -> Dict[str, Any]:
+    """
+    Import an export_data() envelope
+
+    With keep_both, a conflicting return or document gets a new ID and the
+    records in the import that point at it (documents' return_id, receipt_id
+    on deductions, cloned_from) are updated to match. Filed returns are never
+    overwritten.
+
+    Args:
+        envelope: Export envelope
+        on_conflict: skip, overwrite, or keep_both (for IDs that already exist)
+        include_settings: Also replace the settings with the exported ones
+
+    Returns:
+        Report with per-entity imported/skipped/overwritten/renamed counts,
+        id_map of renamed IDs, and warnings
+
+    Raises:
+        ValueError: If the envelope is invalid or on_conflict is unknown
+    """
+    if on_conflict not in CONFLICT_STRATEGIES:
+        raise ValueError(f"Invalid on_conflict: {on_conflict}. Must be one of: {', '.join(CONFLICT_STRATEGIES)}")
+    data = upgrade_envelope(envelope)["data"]
+    report: Dict[str, Any] = {
+        "returns": _counts(),
+        "documents": _counts(),
+        "conversations": _counts(),
+        "settings": False,
+        "reminders": 0,
+        "id_map": {},
+        "warnings": [],
+    }
+    id_map: Dict[str, str] = report["id_map"]
+
+    # Decide every ID first so references can be rewritten before anything is written
+    returns = [r for r in data.get("returns", []) if r.get("return_id")]
+    documents = [d for d in data.get("documents", []) if d.get("document_id")]
+    conflicts = set()
+    for records, id_field, exists, prefix in (
+        (returns, "return_id", lambda i: return_store.get_return(i) is not None, "ret"),
+        (documents, "document_id", lambda i: document_store.get_document(i) is not None, "doc"),
+    ):
+        for record in records:
+            if exists(record[id_field]):
+                conflicts.add(record[id_field])
+                if on_conflict == "keep_both":
+                    id_map[record[id_field]] = f"{prefix}_{os.urandom(8).hex()}"
+
+    def written(key: str, old_id: str) -> None:
+        outcome = "renamed" if old_id in id_map else "overwritten" if old_id in conflicts else "imported"
+        report[key][outcome] += 1
+
+    for tax_return in returns:
+        old_id = tax_return["return_id"]
+        if old_id in conflicts and on_conflict == "skip":
+            report["returns"]["skipped"] += 1
+            continue
+        tax_return = _remap_return(tax_return, id_map)
+        direct_deposit = tax_return.pop("direct_deposit", None)
+        ip_pins = tax_return.pop("ip_pins", None)
+        try:
+            return_store.import_return(tax_return, direct_deposit=direct_deposit, ip_pins=ip_pins)
+        except ReturnLockedError as e:
+            report["returns"]["skipped"] += 1
+            report["warnings"].append(str(e))
+            continue
+        except (KeyError, ValueError) as e:
+            report["returns"]["skipped"] += 1
+            report["warnings"].append(f"Return {old_id} not imported: {e}")
+            continue
+        written("returns", old_id)
+
+    for record in documents:
+        old_id = record["document_id"]
+        if old_id in conflicts and on_conflict == "skip":
+            report["documents"]["skipped"] += 1
+            continue
+        record = dict(record)
+        content_base64 = record.pop("content_base64", None)
+        record["document_id"] = id_map.get(old_id, old_id)
+        if record.get("return_id"):
+            record["return_id"] = id_map.get(record["return_id"], record["return_id"])
+        if record.get("applied_to"):
+            record["applied_to"] = [
+                {**applied, "return_id": id_map.get(applied.get("return_id"), applied.get("return_id"))}
+                for applied in record["applied_to"]
+            ]
+        try:
+            content = base64.b64decode(content_base64) if content_base64 else None
+            document_store.import_record(record, content=content)
+        except ValueError as e:
+            report["documents"]["skipped"] += 1
+            report["warnings"].append(f"Document {old_id} not imported: {e}")
+            continue
+        written("documents", old_id)
+
+    for conversation in data.get("conversations", []):
+        if not conversation.get("session_id"):
+            continue
+        existing = conversation_store.get_conversation(conversation["session_id"])
+        if existing is not None and on_conflict == "skip":
+            report["conversations"]["skipped"] += 1
+            continue
+        if existing is not None and on_conflict == "keep_both":
+            conversation = {**conversation, "session_id": f"{conversation['session_id']}-imported-{os.urandom(3).hex()}"}
+            report["conversations"]["renamed"] += 1
+        elif existing is not None:
+            report["conversations"]["overwritten"] += 1
+        else:
+            report["conversations"]["imported"] += 1
+        conversation_store.import_conversation(conversation)
+
+    if include_settings and data.get("settings"):
+        try:
+            settings = {k: v for k, v in data["settings"].items() if k in AppSettings.model_fields}
+            # The addendum goes through the same guardrails as when it's typed into settings
+            settings["prompt_addendum"] = validate_prompt_addendum(settings.get("prompt_addendum") or "")
+            settings_store.update_settings(settings, changed_by="import")
+            report["settings"] = True
+        except ValueError as e:
+            report["warnings"].append(f"Settings not imported: {e}")
+
+    reminders = {
+        _remap_event_id(event_id, id_map): state for event_id, state in (data.get("reminders") or {}).items()
+    }
+    reminder_store.import_states(reminders)
+    report["reminders"] = len(reminders)
+    return report
+
+
+def _counts() -> Dict[str, int]:
+    return {"imported": 0, "skipped": 0, "overwritten": 0, "renamed": 0}
+
+
+def _remap_return(tax_return: Dict[str, Any], id_map: Dict[str, str]) -> Dict[str, Any]:
+    """Copy of a return with renamed return and receipt IDs applied"""
+    tax_return = copy.deepcopy(tax_return)
+    tax_return["return_id"] = id_map.get(tax_return["return_id"], tax_return["return_id"])
+    if tax_return.get("cloned_from"):
+        tax_return["cloned_from"] = id_map.get(tax_return["cloned_from"], tax_return["cloned_from"])
+    for deduction in tax_return.get("deductions", []):
+        if deduction.get("receipt_id"):
+            deduction["receipt_id"] = id_map.get(deduction["receipt_id"], deduction["receipt_id"])
+    return tax_return
+
+
+def _remap_event_id(event_id: str, id_map: Dict[str, str]) -> str:
+    """Calendar event IDs start with their return ID"""
+    return_id, _, rest = event_id.partition(".")
+    return f"{id_map.get(return_id, return_id)}.{rest}" if rest else event_id
+
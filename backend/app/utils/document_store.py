@@ -0,0 +1,765 @@
+"""
+Document Storage
+Content-addressed storage for uploaded tax documents using file-based system
+"""
+import hashlib
+import json
+import mimetypes
+import os
+import re
+import shutil
+from typing import Dict, List, Any, Optional
+from datetime import date, datetime
+from pathlib import Path
+
+
+# Characters of surrounding text shown on each side of a search hit
+SNIPPET_CONTEXT = 40
+
+# SHA-256 content hashes name the blobs, so only these are accepted from outside
+_HASH_PATTERN = re.compile(r"[0-9a-f]{64}")
+
+
+class DuplicateDocumentError(ValueError):
+    """Raised when uploaded content is already in the store"""
+
+    def __init__(self, existing: Dict[str, Any]):
+        self.existing = existing
+        super().__init__(
+            f"Duplicate of existing document {existing['document_id']} ({existing.get('filename', 'unknown')})"
+        )
+
+
+class DocumentStore:
+    """File-based document storage; files live under the store, keyed by SHA-256"""
+
+    # Largest document accepted (scanned multi-page PDFs stay well under this)
+    MAX_DOCUMENT_BYTES = 25 * 1024 * 1024
+
+    def __init__(self, storage_dir: str = ".documents"):
+        """
+        Initialize document store
+
+        Args:
+            storage_dir: Directory holding document records and the blobs/ tree
+        """
+        self.storage_dir = Path(storage_dir)
+        self.storage_dir.mkdir(exist_ok=True)
+        self.blob_dir = self.storage_dir / "blobs"
+        self.blob_dir.mkdir(exist_ok=True)
+        self.thumbnail_dir = self.storage_dir / "thumbnails"
+        self.thumbnail_dir.mkdir(exist_ok=True)
+        self.purged_index_file = self.storage_dir / "purged_index.json"
+
+    def _get_record_file(self, document_id: str) -> Path:
+        """Get file path for a document record"""
+        # Document IDs are generated here; reject anything else to prevent path traversal
+        if not re.fullmatch(r"doc_[0-9a-f]{16}", document_id):
+            raise ValueError(f"Invalid document ID: {document_id}")
+        return self.storage_dir / f"{document_id}.json"
+
+    @staticmethod
+    def _blob_relpath(content_hash: str) -> str:
+        """Blob location relative to the store (fanned out by hash prefix)"""
+        return f"blobs/{content_hash[:2]}/{content_hash}"
+
+    def _resolve(self, file_path: str) -> Path:
+        """
+        Absolute path for a record's file_path (managed paths are store-relative)
+
+        Raises:
+            FileNotFoundError: If the path points outside the store (only relink_documents reads those)
+        """
+        root = self.storage_dir.resolve()
+        path = (root / file_path).resolve()
+        if not path.is_relative_to(root):
+            raise FileNotFoundError(f"Document file is outside the store: {file_path}")
+        return path
+
+    def _store_blob(self, content: bytes) -> str:
+        """Write content into the blob tree (no-op if already present)"""
+        content_hash = hashlib.sha256(content).hexdigest()
+        blob_path = self.storage_dir / self._blob_relpath(content_hash)
+        if not blob_path.exists():
+            blob_path.parent.mkdir(exist_ok=True)
+            tmp_path = blob_path.with_suffix(".tmp")
+            with open(tmp_path, 'wb') as f:
+                f.write(content)
+            os.replace(tmp_path, blob_path)
+        return content_hash
+
+    def upload_document(
+        self,
+        filename: str,
+        content: Optional[bytes] = None,
+        source_path: Optional[str] = None,
+        document_type: str = "unknown",
+        return_id: Optional[str] = None,
+        move: bool = False,
+        allow_duplicate: bool = False,
+        **fields: Any,
+    ) -> Dict[str, Any]:
+        """
+        Copy a document into the store and record it
+
+        Content already in the store is rejected so re-importing the same W-2 under
+        another name can't double-count it; with allow_duplicate the copy is kept but
+        flagged via duplicate_of.
+
+        Args:
+            filename: Original file name (only the base name is kept)
+            content: File bytes (or pass source_path)
+            source_path: File on disk to copy in
+            document_type: W-2, 1099-INT, receipt, ...
+            return_id: Return the document belongs to
+            move: Delete source_path after it has been copied
+            allow_duplicate: Keep the document even if its content is already stored
+            **fields: Extra record fields (source, extraction_status, ...)
+
+        Returns:
+            The new document record
+
+        Raises:
+            DuplicateDocumentError: If the content exists and allow_duplicate is False
+        """
+        if content is None:
+            if source_path is None:
+                raise ValueError("Either content or source_path is required")
+            with open(source_path, 'rb') as f:
+                content = f.read()
+
+        existing = self.find_by_hash(hashlib.sha256(content).hexdigest())
+        if existing and not allow_duplicate:
+            raise DuplicateDocumentError(existing[0])
+
+        content_hash = self._store_blob(content)
+        name = Path(filename).name or "document"
+        record = {
+            "document_id": f"doc_{os.urandom(8).hex()}",
+            "return_id": return_id,
+            "document_type": document_type,
+            "filename": name,
+            "file_path": self._blob_relpath(content_hash),
+            "content_hash": content_hash,
+            "size": len(content),
+            "mime_type": mimetypes.guess_type(name)[0] or "application/octet-stream",
+            "uploaded_at": datetime.utcnow().isoformat(),
+            **fields,
+        }
+        if existing:
+            record["duplicate_of"] = existing[0]["document_id"]
+        self._save_record(record)
+
+        if move and source_path is not None:
+            os.remove(source_path)
+        return record
+
+    def get_document(self, document_id: str) -> Optional[Dict[str, Any]]:
+        """
+        Retrieve a document record
+
+        Args:
+            document_id: Document identifier
+
+        Returns:
+            Record dict or None if not found
+        """
+        try:
+            file_path = self._get_record_file(document_id)
+        except ValueError:
+            return None
+
+        if not file_path.exists():
+            return None
+
+        with open(file_path, 'r', encoding='utf-8') as f:
+            return json.load(f)
+
+    def read_document(self, document_id: str) -> bytes:
+        """
+        Read a document's file content
+
+        Args:
+            document_id: Document identifier
+
+        Returns:
+            File bytes
+
+        Raises:
+            KeyError: If the record doesn't exist
+            FileNotFoundError: If the file is gone or outside the store (run relink_documents)
+        """
+        record = self._require_document(document_id)
+        with open(self._resolve(record["file_path"]), 'rb') as f:
+            return f.read()
+
+    def import_record(self, record: Dict[str, Any], content: Optional[bytes] = None) -> Dict[str, Any]:
+        """
+        Store a document record exported from another install, keeping its ID
+
+        The record always points at the blob for its content_hash; any file_path
+        it carries is ignored. Without content that blob must already be here.
+
+        Args:
+            record: Document record (document_id, content_hash, ...)
+            content: File bytes, if the export included them
+
+        Returns:
+            The stored record
+
+        Raises:
+            ValueError: If the ID or content_hash is invalid, content doesn't match
+                content_hash, or there's no content and no stored blob for it
+        """
+        record = dict(record)
+        document_id = record["document_id"]
+        self._get_record_file(document_id)
+        content_hash = record.get("content_hash")
+        if content_hash is not None and not (isinstance(content_hash, str) and _HASH_PATTERN.fullmatch(content_hash)):
+            raise ValueError(f"Invalid content hash for {document_id}")
+        if content is not None:
+            if content_hash and hashlib.sha256(content).hexdigest() != content_hash:
+                raise ValueError(f"Content of {document_id} doesn't match its hash")
+            record["content_hash"] = self._store_blob(content)
+            record["size"] = len(content)
+        elif not content_hash or not (self.storage_dir / self._blob_relpath(content_hash)).is_file():
+            raise ValueError(f"{document_id} has no content and its file isn't in this store")
+        record["file_path"] = self._blob_relpath(record["content_hash"])
+        record.pop("thumbnail_path", None)
+        self._save_record(record)
+        return record
+
+    def save_thumbnail(self, document_id: str, png: bytes) -> Dict[str, Any]:
+        """
+        Store a document's thumbnail next to its content
+
+        Thumbnails are keyed by content hash, so duplicates share one.
+
+        Args:
+            document_id: Document identifier
+            png: Thumbnail PNG bytes
+
+        Returns:
+            The updated record
+        """
+        record = self._require_document(document_id)
+        thumbnail_path = self.thumbnail_dir / f"{record['content_hash']}.png"
+        with open(thumbnail_path, 'wb') as f:
+            f.write(png)
+        record["thumbnail_path"] = f"thumbnails/{thumbnail_path.name}"
+        self._save_record(record)
+        return record
+
+    def get_thumbnail(self, document_id: str) -> Optional[bytes]:
+        """
+        Read a document's stored thumbnail
+
+        Args:
+            document_id: Document identifier
+
+        Returns:
+            PNG bytes, or None if no thumbnail has been generated
+        """
+        record = self._require_document(document_id)
+        if not record.get("thumbnail_path"):
+            return None
+        thumbnail_path = self._resolve(record["thumbnail_path"])
+        if not thumbnail_path.exists():
+            return None
+        with open(thumbnail_path, 'rb') as f:
+            return f.read()
+
+    def save_extraction(
+        self,
+        document_id: str,
+        extraction: Dict[str, Any],
+        text: Optional[str] = None,
+    ) -> Dict[str, Any]:
+        """
+        Attach parsed form data to a document
+
+        Args:
+            document_id: Document identifier
+            extraction: ParseResult.to_dict() output
+            text: OCR/PDF text the data was parsed from, pages separated by form
+                feeds (kept for search and page splitting)
+
+        Returns:
+            The updated record
+        """
+        record = self._require_document(document_id)
+        record["extraction"] = extraction
+        if text is not None:
+            record["ocr_text"] = text
+        record["extraction_status"] = "extracted"
+        record["extracted_at"] = datetime.utcnow().isoformat()
+        record.pop("extraction_error", None)
+        self._save_record(record)
+        return record
+
+    def mark_extraction_failed(self, document_id: str, error: str) -> Dict[str, Any]:
+        """
+        Note that a document's form fields couldn't be extracted, and why
+
+        Args:
+            document_id: Document identifier
+            error: Reason shown to the user (e.g. the scan needs OCR)
+
+        Returns:
+            The updated record
+        """
+        record = self._require_document(document_id)
+        record["extraction_status"] = "failed"
+        record["extraction_error"] = error
+        self._save_record(record)
+        return record
+
+    def record_application(self, document_id: str, return_id: str, **links: str) -> Dict[str, Any]:
+        """
+        Note that a document's data was applied to a return (for audit traceability)
+
+        Re-applying to the same return replaces the earlier entry.
+
+        Args:
+            document_id: Document identifier
+            return_id: Return the data went into
+            **links: Records created or updated from the document
+                (income_source_id=..., deduction_id=...)
+
+        Returns:
+            The updated record
+        """
+        record = self._require_document(document_id)
+        applied = [a for a in record.get("applied_to", []) if a["return_id"] != return_id]
+        applied.append({
+            "return_id": return_id,
+            **links,
+            "applied_at": datetime.utcnow().isoformat(),
+        })
+        record["applied_to"] = applied
+        if not record.get("return_id"):
+            record["return_id"] = return_id
+        self._save_record(record)
+        return record
+
+    def list_documents(
+        self,
+        return_id: Optional[str] = None,
+        query: Optional[str] = None,
+        include_archived: bool = False,
+    ) -> List[Dict[str, Any]]:
+        """
+        List document records
+
+        Args:
+            return_id: Only documents attached to this return
+            query: Only documents whose name, OCR text, extracted fields, or notes contain
+                this (case-insensitive); each result gets a "matches" list of snippets
+            include_archived: Include documents that have been archived
+
+        Returns:
+            Records, newest first
+        """
+        documents = []
+        for file_path in self.storage_dir.glob("doc_*.json"):
+            try:
+                with open(file_path, 'r', encoding='utf-8') as f:
+                    record = json.load(f)
+            except (json.JSONDecodeError, IOError):
+                continue
+            if return_id is not None and record.get("return_id") != return_id:
+                continue
+            if record.get("archived") and not include_archived:
+                continue
+            if query:
+                matches = self._search_record(record, query)
+                if not matches:
+                    continue
+                record["matches"] = matches
+            documents.append(record)
+
+        documents.sort(key=lambda x: x.get("uploaded_at", ""), reverse=True)
+        return documents
+
+    def find_by_hash(self, content_hash: str) -> List[Dict[str, Any]]:
+        """
+        Find documents with the given SHA-256
+
+        Args:
+            content_hash: Hex digest
+
+        Returns:
+            Matching records, oldest first
+        """
+        matches = [d for d in self.list_documents(include_archived=True) if d.get("content_hash") == content_hash]
+        return sorted(matches, key=lambda x: x.get("uploaded_at", ""))
+
+    def find_duplicates(self) -> List[List[Dict[str, Any]]]:
+        """
+        Group records that share content
+
+        Returns:
+            Lists of two or more records with the same hash, oldest first
+        """
+        by_hash: Dict[str, List[Dict[str, Any]]] = {}
+        for record in self.list_documents(include_archived=True):
+            if record.get("content_hash"):
+                by_hash.setdefault(record["content_hash"], []).append(record)
+        return [
+            sorted(group, key=lambda x: x.get("uploaded_at", ""))
+            for group in by_hash.values()
+            if len(group) > 1
+        ]
+
+    def merge_documents(self, keep_id: str, duplicate_id: str) -> Dict[str, Any]:
+        """
+        Fold a duplicate record into the one being kept
+
+        The duplicate's file name is kept as an alias and its return link is adopted
+        if the kept record has none; the duplicate record is then deleted.
+
+        Args:
+            keep_id: Record to keep
+            duplicate_id: Record to remove
+
+        Returns:
+            The updated kept record
+        """
+        keep = self._require_document(keep_id)
+        duplicate = self._require_document(duplicate_id)
+        if keep_id == duplicate_id:
+            raise ValueError("Cannot merge a document into itself")
+        if keep.get("content_hash") != duplicate.get("content_hash"):
+            raise ValueError("Only documents with identical content can be merged")
+
+        aliases = keep.setdefault("aliases", [])
+        for name in [duplicate["filename"], *duplicate.get("aliases", [])]:
+            if name != keep["filename"] and name not in aliases:
+                aliases.append(name)
+        if not keep.get("return_id"):
+            keep["return_id"] = duplicate.get("return_id")
+        keep.pop("duplicate_of", None)
+
+        self._save_record(keep)
+        self.delete_document(duplicate_id)
+        return keep
+
+    def delete_document(self, document_id: str) -> bool:
+        """
+        Delete a document record, and its blob if nothing else references it
+
+        Args:
+            document_id: Document identifier
+
+        Returns:
+            True if deleted, False if not found
+        """
+        record = self.get_document(document_id)
+        if record is None:
+            return False
+
+        self._get_record_file(document_id).unlink()
+        content_hash = record.get("content_hash")
+        if content_hash and not any(
+            d.get("content_hash") == content_hash for d in self.list_documents(include_archived=True)
+        ):
+            for path in (self.storage_dir / self._blob_relpath(content_hash),
+                         self.thumbnail_dir / f"{content_hash}.png"):
+                if path.exists():
+                    path.unlink()
+        return True
+
+    def set_notes(self, document_id: str, notes: str) -> Dict[str, Any]:
+        """
+        Replace a document's free-form notes
+
+        Args:
+            document_id: Document identifier
+            notes: Notes text ("" clears them)
+
+        Returns:
+            The updated record
+        """
+        record = self._require_document(document_id)
+        record["notes"] = notes.strip()
+        self._save_record(record)
+        return record
+
+    def add_annotation(self, document_id: str, text: str, page: Optional[int] = None) -> Dict[str, Any]:
+        """
+        Attach a note to a document, optionally pinned to a page
+
+        Args:
+            document_id: Document identifier
+            text: Annotation text ("this is the corrected W-2, ignore the other one")
+            page: 1-based page the note refers to
+
+        Returns:
+            The new annotation
+        """
+        if not text.strip():
+            raise ValueError("Annotation text is required")
+        if page is not None and page < 1:
+            raise ValueError("Page numbers start at 1")
+
+        record = self._require_document(document_id)
+        now = datetime.utcnow().isoformat()
+        annotation = {
+            "id": f"ann_{os.urandom(8).hex()}",
+            "text": text.strip(),
+            "page": page,
+            "created_at": now,
+            "updated_at": now,
+        }
+        record.setdefault("annotations", []).append(annotation)
+        self._save_record(record)
+        return annotation
+
+    def update_annotation(self, document_id: str, annotation_id: str, **changes: Any) -> Dict[str, Any]:
+        """
+        Edit an annotation's text or page
+
+        Args:
+            document_id: Document identifier
+            annotation_id: Annotation identifier
+            **changes: text and/or page
+
+        Returns:
+            The updated annotation
+        """
+        unknown = set(changes) - {"text", "page"}
+        if unknown:
+            raise ValueError(f"Cannot change annotation fields: {', '.join(sorted(unknown))}")
+        if "text" in changes and not str(changes["text"]).strip():
+            raise ValueError("Annotation text is required")
+        if changes.get("page") is not None and changes["page"] < 1:
+            raise ValueError("Page numbers start at 1")
+
+        record = self._require_document(document_id)
+        annotation = self._find_annotation(record, annotation_id)
+        if "text" in changes:
+            changes["text"] = changes["text"].strip()
+        annotation.update(changes)
+        annotation["updated_at"] = datetime.utcnow().isoformat()
+        self._save_record(record)
+        return annotation
+
+    def delete_annotation(self, document_id: str, annotation_id: str) -> None:
+        """
+        Remove an annotation
+
+        Args:
+            document_id: Document identifier
+            annotation_id: Annotation identifier
+        """
+        record = self._require_document(document_id)
+        record["annotations"].remove(self._find_annotation(record, annotation_id))
+        self._save_record(record)
+
+    @staticmethod
+    def _find_annotation(record: Dict[str, Any], annotation_id: str) -> Dict[str, Any]:
+        """An annotation on a record, or KeyError"""
+        for annotation in record.get("annotations", []):
+            if annotation["id"] == annotation_id:
+                return annotation
+        raise KeyError(f"Annotation not found: {annotation_id}")
+
+    def set_archived(self, document_id: str, archived: bool = True) -> Dict[str, Any]:
+        """
+        Archive a document (hidden from listings but kept) or restore it
+
+        Args:
+            document_id: Document identifier
+            archived: New state
+
+        Returns:
+            The updated record
+        """
+        record = self._require_document(document_id)
+        record["archived"] = archived
+        record["archived_at"] = datetime.utcnow().isoformat() if archived else None
+        self._save_record(record)
+        return record
+
+    @staticmethod
+    def document_tax_year(record: Dict[str, Any]) -> int:
+        """
+        Tax year a document belongs to
+
+        Uses the record's tax_year when known; otherwise assumes the document was
+        uploaded during the following year's filing season.
+        """
+        if record.get("tax_year"):
+            return int(record["tax_year"])
+        return datetime.fromisoformat(record["uploaded_at"]).year - 1
+
+    def purge_expired(
+        self,
+        retention_years: int = 7,
+        export_dir: Optional[str] = None,
+        as_of: Optional[date] = None,
+        dry_run: bool = False,
+    ) -> Dict[str, Any]:
+        """
+        Remove documents older than the retention window
+
+        A document is expired once more than retention_years have passed since the
+        end of its tax year. Each purged document is optionally exported first and
+        is always recorded in the purged index, so there's a trail of what was removed.
+
+        Args:
+            retention_years: Years to keep records (the IRS suggests 3-7 depending on the return)
+            export_dir: Copy each file here (into a folder per tax year) before deleting it
+            as_of: Date to measure from (default today)
+            dry_run: Only report what would be purged
+
+        Returns:
+            Dict with the cutoff tax year, the purged (or to-be-purged) entries, and
+            documents kept because their export failed
+        """
+        if retention_years < 1:
+            raise ValueError("Retention must be at least one year")
+        cutoff_year = (as_of or date.today()).year - retention_years - 1
+
+        expired = [
+            record for record in self.list_documents(include_archived=True)
+            if self.document_tax_year(record) <= cutoff_year
+        ]
+        entries: List[Dict[str, Any]] = []
+        failed: List[Dict[str, str]] = []
+        for record in expired:
+            entry = {
+                "document_id": record["document_id"],
+                "filename": record["filename"],
+                "document_type": record.get("document_type"),
+                "tax_year": self.document_tax_year(record),
+                "return_id": record.get("return_id"),
+                "content_hash": record.get("content_hash"),
+                "size": record.get("size"),
+                "exported_to": None,
+            }
+            if not dry_run:
+                if export_dir:
+                    try:
+                        entry["exported_to"] = str(self._export(record, Path(export_dir)))
+                    except OSError as e:
+                        # Never delete what couldn't be exported
+                        failed.append({"document_id": record["document_id"], "error": str(e)})
+                        continue
+                entry["purged_at"] = datetime.utcnow().isoformat()
+                self.delete_document(record["document_id"])
+            entries.append(entry)
+
+        if entries and not dry_run:
+            self._save_purged_index(self.list_purged() + entries)
+        return {"cutoff_tax_year": cutoff_year, "dry_run": dry_run, "purged": entries, "failed": failed}
+
+    def list_purged(self) -> List[Dict[str, Any]]:
+        """
+        Index of documents removed by purge_expired
+
+        Returns:
+            Entries, oldest purge first
+        """
+        if not self.purged_index_file.exists():
+            return []
+        try:
+            with open(self.purged_index_file, 'r', encoding='utf-8') as f:
+                return json.load(f)
+        except (json.JSONDecodeError, IOError):
+            return []
+
+    def _export(self, record: Dict[str, Any], export_dir: Path) -> Path:
+        """Copy a document's file (and its record) out of the store"""
+        target_dir = export_dir / str(self.document_tax_year(record))
+        target_dir.mkdir(parents=True, exist_ok=True)
+        target = target_dir / f"{record['document_id']}_{record['filename']}"
+        shutil.copyfile(self._resolve(record["file_path"]), target)
+        with open(target.with_name(target.name + ".json"), 'w', encoding='utf-8') as f:
+            json.dump(record, f, indent=2, ensure_ascii=False)
+        return target
+
+    def _save_purged_index(self, entries: List[Dict[str, Any]]) -> None:
+        """Persist the purged index"""
+        with open(self.purged_index_file, 'w', encoding='utf-8') as f:
+            json.dump(entries, f, indent=2, ensure_ascii=False)
+
+    def relink_documents(self) -> Dict[str, List[str]]:
+        """
+        Repair records whose file isn't managed by the store
+
+        Legacy records that point at an external path are copied in; records whose
+        path is stale but whose content is still in the store are re-pointed; the
+        rest are flagged as missing.
+
+        Returns:
+            Document IDs grouped into "ok", "relinked", "repaired", and "missing"
+        """
+        report: Dict[str, List[str]] = {"ok": [], "relinked": [], "repaired": [], "missing": []}
+
+        for record in self.list_documents(include_archived=True):
+            document_id = record["document_id"]
+            content_hash = record.get("content_hash")
+            managed_path = self._blob_relpath(content_hash) if content_hash else None
+            # Legacy records can point anywhere on disk; this is the one place that reads them
+            current = self.storage_dir / record.get("file_path", "")
+
+            if managed_path and record.get("file_path") == managed_path and current.exists():
+                report["ok"].append(document_id)
+                continue
+
+            if record.get("file_path") and current.is_file():
+                with open(current, 'rb') as f:
+                    content = f.read()
+                record["content_hash"] = self._store_blob(content)
+                record["file_path"] = self._blob_relpath(record["content_hash"])
+                record["size"] = len(content)
+                record.pop("missing", None)
+                status = "relinked"
+            elif managed_path and (self.storage_dir / managed_path).exists():
+                record["file_path"] = managed_path
+                record.pop("missing", None)
+                status = "repaired"
+            else:
+                record["missing"] = True
+                status = "missing"
+
+            self._save_record(record)
+            report[status].append(document_id)
+
+        return report
+
+    @staticmethod
+    def _search_record(record: Dict[str, Any], query: str) -> List[Dict[str, str]]:
+        """Where a query appears in a record, with a snippet of context for each hit"""
+        needle = " ".join(query.split()).lower()
+        searchable = [("filename", record.get("filename", "")), *[("alias", a) for a in record.get("aliases", [])]]
+        for name, extracted in (record.get("extraction") or {}).get("fields", {}).items():
+            if extracted.get("value") is not None:
+                searchable.append((f"fields.{name}", str(extracted["value"])))
+        searchable.append(("ocr_text", record.get("ocr_text") or ""))
+        searchable.append(("notes", record.get("notes") or ""))
+        searchable.extend(("annotation", a["text"]) for a in record.get("annotations", []))
+
+        matches = []
+        for field, value in searchable:
+            text = " ".join(value.split())
+            start = text.lower().find(needle)
+            if start == -1:
+                continue
+            begin, end = max(0, start - SNIPPET_CONTEXT), min(len(text), start + len(needle) + SNIPPET_CONTEXT)
+            snippet = ("..." if begin > 0 else "") + text[begin:end] + ("..." if end < len(text) else "")
+            matches.append({"field": field, "snippet": snippet})
+        return matches
+
+    def _save_record(self, record: Dict[str, Any]) -> None:
+        """Persist a document record"""
+        with open(self._get_record_file(record["document_id"]), 'w', encoding='utf-8') as f:
+            json.dump(record, f, indent=2, ensure_ascii=False)
+
+    def _require_document(self, document_id: str) -> Dict[str, Any]:
+        """Load a record or raise if it does not exist"""
+        record = self.get_document(document_id)
+        if record is None:
+            raise KeyError(f"Document not found: {document_id}")
+        return record
@@ -0,0 +1,105 @@
+"""
+Embeddings
+Dense text embeddings for semantic chat search, from a pluggable provider
+
+An embedding model maps text to a vector whose direction tracks meaning, so
+"can I write off my home workspace?" lands near an answer about the home office
+deduction even with no words in common. Any server speaking the OpenAI-style
+/embeddings API works: a local one (Ollama, llama.cpp, LM Studio) keeps chat
+text on this machine, while a hosted one receives every message embedded.
+"""
+import math
+from abc import ABC, abstractmethod
+from typing import Any, Dict, List, Optional
+
+import httpx
+
+
+class EmbeddingError(Exception):
+    """The embedding provider couldn't be reached or returned something unusable"""
+
+
+class EmbeddingProvider(ABC):
+    """Turns text into dense vectors; implementations name the model they use"""
+
+    model: str
+
+    @abstractmethod
+    def embed(self, texts: List[str]) -> List[List[float]]:
+        """
+        Embed texts in one call
+
+        Returns:
+            One L2-normalized vector per text, in order
+
+        Raises:
+            EmbeddingError: If the provider fails
+        """
+
+
+class HttpEmbeddingProvider(EmbeddingProvider):
+    """Embeddings from an OpenAI-compatible POST {base_url}/embeddings endpoint"""
+
+    def __init__(
+        self,
+        base_url: str,
+        model: str,
+        api_key: Optional[str] = None,
+        timeout: float = 10.0,
+        transport: Optional[httpx.BaseTransport] = None,
+    ):
+        """
+        Initialize the provider
+
+        Args:
+            base_url: API root, e.g. http://localhost:11434/v1 for Ollama
+            model: Embedding model name, e.g. nomic-embed-text
+            api_key: Bearer token, for hosted providers
+            timeout: Seconds to wait for a response
+            transport: httpx transport (tests pass a mock)
+        """
+        self.base_url = base_url.rstrip("/")
+        self.model = model
+        self.api_key = api_key
+        self.timeout = timeout
+        self.transport = transport
+
+    def embed(self, texts: List[str]) -> List[List[float]]:
+        if not texts:
+            return []
+        headers = {"Authorization": f"Bearer {self.api_key}"} if self.api_key else {}
+        try:
+            with httpx.Client(timeout=self.timeout, transport=self.transport) as client:
+                response = client.post(
+                    f"{self.base_url}/embeddings", json={"model": self.model, "input": texts}, headers=headers
+                )
+                response.raise_for_status()
+                body: Dict[str, Any] = response.json()
+        except httpx.HTTPStatusError as e:
+            raise EmbeddingError(f"Embedding request failed ({e.response.status_code})")
+        except (httpx.HTTPError, ValueError) as e:
+            raise EmbeddingError(f"Embedding request failed: {e}")
+
+        try:
+            data = sorted(body["data"], key=lambda item: item.get("index", 0))
+            vectors = [normalize([float(x) for x in item["embedding"]]) for item in data]
+        except (KeyError, TypeError, ValueError):
+            raise EmbeddingError("The embedding response had no embeddings")
+        if len(vectors) != len(texts):
+            raise EmbeddingError(f"Expected {len(texts)} embeddings, got {len(vectors)}")
+        return vectors
+
+
+def normalize(vector: List[float]) -> List[float]:
+    """Scale a vector to unit length (left as is when it's all zeros)"""
+    norm = math.sqrt(sum(x * x for x in vector))
+    if norm == 0:
+        return vector
+    return [x / norm for x in vector]
+
+
+def dense_cosine(a: List[float], b: List[float]) -> float:
+    """Cosine similarity of two normalized vectors (0 when their dimensions differ)"""
+    if len(a) != len(b):
+        return 0.0
+    return sum(x * y for x, y in zip(a, b))
@@ -0,0 +1,135 @@
+"""
+Field Encryption
+Encrypts individual sensitive fields (bank accounts, ...) before they are written to disk
+"""
+import base64
+import os
+from pathlib import Path
+from typing import Any, Optional
+
+from cryptography.fernet import Fernet, InvalidToken
+
+# Base64 Fernet key; overrides the key file (set it to keep keys out of the data folder)
+KEY_ENV_VAR = "FIELD_ENCRYPTION_KEY"
+
+
+class DecryptionError(ValueError):
+    """Raised when a stored field can't be decrypted with the current key"""
+
+
+class InvalidKeyError(DecryptionError):
+    """Raised when the configured key isn't a Fernet key (nothing can be encrypted or decrypted with it)"""
+
+
+def validate_key(key: bytes, source: str) -> bytes:
+    """
+    Check that a key is 32 bytes in strict url-safe base64, ignoring surrounding whitespace
+
+    Args:
+        key: Key as read from its source
+        source: Where it came from, for the error message (the key itself is never included)
+
+    Returns:
+        The key without surrounding whitespace
+
+    Raises:
+        InvalidKeyError: If the key is malformed
+    """
+    key = key.strip()
+    try:
+        valid = len(base64.b64decode(key, altchars=b"-_", validate=True)) == 32
+    except ValueError:  # binascii.Error
+        valid = False
+    if not valid:
+        raise InvalidKeyError(
+            f"The encryption key from {source} isn't valid; it must be 32 url-safe base64-encoded bytes"
+        )
+    return key
+
+
+class FieldCipher:
+    """Fernet encryption for single string fields, keyed from the environment or a local key file"""
+
+    def __init__(self, key: Optional[bytes] = None, key_path: str = ".field_encryption.key"):
+        """
+        Initialize the cipher
+
+        The key is taken from the key argument, then FIELD_ENCRYPTION_KEY, then
+        key_path; a new key file (owner read/write only) is created on first use.
+
+        Args:
+            key: Fernet key
+            key_path: Key file used when no key is given
+        """
+        if key:
+            self._key, self._key_source = key, "the key argument"
+        elif os.getenv(KEY_ENV_VAR):
+            self._key, self._key_source = os.environ[KEY_ENV_VAR].encode(), KEY_ENV_VAR
+        else:
+            self._key, self._key_source = None, str(key_path)
+        self.key_path = Path(key_path)
+        self._fernet: Optional[Fernet] = None
+
+    def _cipher(self) -> Fernet:
+        if self._fernet is None:
+            self._fernet = Fernet(validate_key(self._key or self._load_or_create_key(), self._key_source))
+        return self._fernet
+
+    def _load_or_create_key(self) -> bytes:
+        if self.key_path.exists():
+            return self.key_path.read_bytes()
+        self.key_path.parent.mkdir(parents=True, exist_ok=True)
+        key = Fernet.generate_key()
+        fd = os.open(self.key_path, os.O_WRONLY | os.O_CREAT | os.O_EXCL, 0o600)
+        with os.fdopen(fd, "wb") as f:
+            f.write(key)
+        return key
+
+    def encrypt(self, value: str) -> str:
+        """
+        Encrypt a field value
+
+        Args:
+            value: Plain text
+
+        Returns:
+            Fernet token (ASCII)
+        """
+        return self._cipher().encrypt(value.encode("utf-8")).decode("ascii")
+
+    def decrypt(self, token: str) -> str:
+        """
+        Decrypt a field value
+
+        Args:
+            token: Token from encrypt
+
+        Returns:
+            Plain text
+
+        Raises:
+            DecryptionError: If the token is corrupt or was encrypted with another key
+        """
+        try:
+            return self._cipher().decrypt(token.encode("ascii")).decode("utf-8")
+        except InvalidToken:
+            raise DecryptionError("Encrypted field can't be read with the current encryption key")
+
+
+def mask(value: str, visible: int = 4) -> str:
+    """Replace all but the last visible characters with asterisks"""
+    if len(value) <= visible:
+        return "*" * len(value)
+    return "*" * (len(value) - visible) + value[-visible:]
+
+
+def mask_ssns(data: Any) -> Any:
+    """Copy of a record with every SSN (ssn and *_ssn text fields, at any depth) masked to its last four digits"""
+    if isinstance(data, dict):
+        return {
+            key: mask(value) if isinstance(value, str) and (key == "ssn" or key.endswith("_ssn")) else mask_ssns(value)
+            for key, value in data.items()
+        }
+    if isinstance(data, list):
+        return [mask_ssns(item) for item in data]
+    return data
@@ -0,0 +1,174 @@
+"""
+Invoice Storage
+Freelance invoices (client, amount, issue and paid dates, whether a 1099 is expected), kept in a JSON file
+"""
+import json
+import os
+from datetime import date, datetime
+from pathlib import Path
+from typing import Dict, List, Any, Optional
+
+INVOICE_STATUSES = ["open", "paid"]
+
+
+class InvoiceStore:
+    """File-based invoice records, keyed by invoice ID"""
+
+    # Fields update_invoice may change
+    EDITABLE_FIELDS = ["client", "amount", "issue_date", "paid_date", "expect_1099", "description", "business_id"]
+
+    def __init__(self, storage_path: str = ".invoices.json"):
+        """
+        Initialize invoice store
+
+        Args:
+            storage_path: JSON file holding the invoices
+        """
+        self.storage_path = Path(storage_path)
+
+    def _load(self) -> Dict[str, Dict[str, Any]]:
+        if not self.storage_path.exists():
+            return {}
+        try:
+            with open(self.storage_path, 'r', encoding='utf-8') as f:
+                return json.load(f)
+        except (json.JSONDecodeError, IOError):
+            return {}
+
+    def _save(self, invoices: Dict[str, Dict[str, Any]]) -> None:
+        self.storage_path.parent.mkdir(parents=True, exist_ok=True)
+        with open(self.storage_path, 'w', encoding='utf-8') as f:
+            json.dump(invoices, f, indent=2, ensure_ascii=False)
+
+    @staticmethod
+    def _check(invoice: Dict[str, Any]) -> None:
+        """Validate an invoice's fields"""
+        if not str(invoice.get("client") or "").strip():
+            raise ValueError("Invoice client is required")
+        if invoice["amount"] < 0:
+            raise ValueError("Invoice amount cannot be negative")
+        issued = date.fromisoformat(invoice["issue_date"])
+        if invoice.get("paid_date") and date.fromisoformat(invoice["paid_date"]) < issued:
+            raise ValueError("Paid date cannot be before the issue date")
+
+    def create_invoice(
+        self,
+        client: str,
+        amount: float,
+        issue_date: str,
+        paid_date: Optional[str] = None,
+        expect_1099: bool = True,
+        description: str = "",
+        business_id: Optional[str] = None,
+    ) -> Dict[str, Any]:
+        """
+        Record an invoice
+
+        Args:
+            client: Who was billed (matched against 1099 payer names)
+            amount: Invoice total
+            issue_date: Date issued (YYYY-MM-DD)
+            paid_date: Date paid, or None while open
+            expect_1099: Whether the client should send a 1099-NEC
+            description: What the invoice was for
+            business_id: Business the income belongs to
+
+        Returns:
+            The invoice record
+
+        Raises:
+            ValueError: If the client is blank, the amount is negative, or a date is invalid
+        """
+        invoice = {
+            "id": f"inv_{os.urandom(8).hex()}",
+            "client": client.strip(),
+            "amount": amount,
+            "issue_date": issue_date,
+            "paid_date": paid_date,
+            "expect_1099": expect_1099,
+            "description": description,
+            "business_id": business_id,
+            "created_at": datetime.utcnow().isoformat(),
+        }
+        self._check(invoice)
+        invoices = self._load()
+        invoices[invoice["id"]] = invoice
+        self._save(invoices)
+        return invoice
+
+    def get_invoice(self, invoice_id: str) -> Optional[Dict[str, Any]]:
+        """
+        Retrieve an invoice
+
+        Returns:
+            Invoice dict or None if not found
+        """
+        return self._load().get(invoice_id)
+
+    def list_invoices(
+        self,
+        year: Optional[int] = None,
+        client: Optional[str] = None,
+        status: Optional[str] = None,
+    ) -> List[Dict[str, Any]]:
+        """
+        List invoices, oldest first
+
+        Args:
+            year: Only invoices issued or paid in this year
+            client: Only this client (case-insensitive)
+            status: Only open or paid invoices
+
+        Returns:
+            Invoice records
+        """
+        if status is not None and status not in INVOICE_STATUSES:
+            raise ValueError(f"Invalid invoice status: {status}. Must be one of: {', '.join(INVOICE_STATUSES)}")
+        invoices = []
+        for invoice in self._load().values():
+            if year is not None and str(year) not in (invoice["issue_date"][:4], (invoice.get("paid_date") or "")[:4]):
+                continue
+            if client is not None and invoice["client"].lower() != client.strip().lower():
+                continue
+            if status is not None and ("paid" if invoice.get("paid_date") else "open") != status:
+                continue
+            invoices.append(invoice)
+        return sorted(invoices, key=lambda i: (i["issue_date"], i["created_at"]))
+
+    def update_invoice(self, invoice_id: str, **changes: Any) -> Dict[str, Any]:
+        """
+        Change an invoice's fields (e.g. set paid_date when it's paid)
+
+        Returns:
+            The updated invoice
+
+        Raises:
+            KeyError: If the invoice doesn't exist
+            ValueError: If a field can't be changed or the result is invalid
+        """
+        invoices = self._load()
+        if invoice_id not in invoices:
+            raise KeyError(f"Invoice not found: {invoice_id}")
+        unknown = set(changes) - set(self.EDITABLE_FIELDS)
+        if unknown:
+            raise ValueError(f"Invoice fields can't be changed: {', '.join(sorted(unknown))}")
+        invoice = {**invoices[invoice_id], **changes, "updated_at": datetime.utcnow().isoformat()}
+        if "client" in changes:
+            invoice["client"] = str(changes["client"] or "").strip()
+        self._check(invoice)
+        invoices[invoice_id] = invoice
+        self._save(invoices)
+        return invoice
+
+    def delete_invoice(self, invoice_id: str) -> bool:
+        """
+        Delete an invoice
+
+        Returns:
+            True if deleted, False if not found
+        """
+        invoices = self._load()
+        if invoices.pop(invoice_id, None) is None:
+            return False
+        self._save(invoices)
+        return True
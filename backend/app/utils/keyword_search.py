@@ -0,0 +1,85 @@
+"""
+Keyword Search
+Fuzzy keyword matching for stored conversations, using hashed bag-of-words vectors
+
+This is lexical search, not a language model: messages match when they share
+(stemmed) words, word pairs, or close spellings with the query. Synonyms and
+paraphrases with no words in common don't match.
+"""
+import hashlib
+import math
+import re
+from typing import Dict, List
+
+# Vector dimensionality for the hashing trick
+VECTOR_DIM = 1024
+
+STOPWORDS = {
+    "a", "about", "an", "and", "are", "as", "at", "be", "but", "by", "can", "did",
+    "do", "does", "for", "from", "had", "has", "have", "how", "i", "if", "in", "is",
+    "it", "its", "me", "my", "of", "on", "or", "our", "say", "said", "so", "that",
+    "the", "their", "them", "there", "this", "to", "was", "we", "what", "when",
+    "which", "who", "will", "with", "you", "your",
+}
+
+
+def _stem(word: str) -> str:
+    """Very small suffix stripper so 'deductions' and 'deducting' share a stem"""
+    for suffix in ("ations", "ation", "ings", "ing", "ions", "ion", "ies", "es", "ed", "s"):
+        if word.endswith(suffix) and len(word) - len(suffix) >= 4:
+            return word[: -len(suffix)]
+    return word
+
+
+def tokenize(text: str) -> List[str]:
+    """Lowercase, split on non-alphanumerics, drop stopwords, and stem"""
+    words = re.findall(r"[a-z0-9]+", (text or "").lower())
+    return [_stem(w) for w in words if w not in STOPWORDS]
+
+
+def _bucket(feature: str) -> int:
+    """Stable hash of a feature into the vector space"""
+    digest = hashlib.md5(feature.encode("utf-8")).digest()
+    return int.from_bytes(digest[:4], "little") % VECTOR_DIM
+
+
+def keyword_vector(text: str) -> Dict[int, float]:
+    """
+    Text as a sparse, L2-normalized hashed feature vector
+
+    Features are stemmed words, adjacent word pairs, and character trigrams of
+    each word (so near spellings still overlap). Deterministic and offline - no
+    conversation text is sent anywhere.
+
+    Args:
+        text: Text to vectorize
+
+    Returns:
+        Sparse vector as {dimension: weight}
+    """
+    tokens = tokenize(text)
+    vector: Dict[int, float] = {}
+
+    def add(feature: str, weight: float) -> None:
+        index = _bucket(feature)
+        vector[index] = vector.get(index, 0.0) + weight
+
+    for token in tokens:
+        add(f"w:{token}", 1.0)
+        padded = f"#{token}#"
+        for i in range(len(padded) - 2):
+            add(f"c:{padded[i:i + 3]}", 0.25)
+    for first, second in zip(tokens, tokens[1:]):
+        add(f"b:{first} {second}", 0.5)
+
+    norm = math.sqrt(sum(w * w for w in vector.values()))
+    if norm == 0:
+        return {}
+    return {index: round(weight / norm, 5) for index, weight in vector.items()}
+
+
+def cosine_similarity(a: Dict[int, float], b: Dict[int, float]) -> float:
+    """Cosine similarity of two normalized sparse vectors"""
+    if len(a) > len(b):
+        a, b = b, a
+    return sum(weight * b.get(index, 0.0) for index, weight in a.items())
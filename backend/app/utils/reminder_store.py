@@ -0,0 +1,81 @@
+"""
+Reminder State Storage
+Snoozed and dismissed calendar reminders, kept in a JSON file
+"""
+import json
+from datetime import date, datetime
+from pathlib import Path
+from typing import Dict, Any
+
+
+class ReminderStore:
+    """File-based snooze/dismiss state, keyed by calendar event ID"""
+
+    def __init__(self, state_path: str = ".reminders.json"):
+        """
+        Initialize reminder store
+
+        Args:
+            state_path: JSON file holding the reminder states
+        """
+        self.state_path = Path(state_path)
+
+    def get_states(self) -> Dict[str, Dict[str, Any]]:
+        """
+        Load every event's state
+
+        Returns:
+            Event ID -> {"snoozed_until", "dismissed_at"}
+        """
+        if not self.state_path.exists():
+            return {}
+        try:
+            with open(self.state_path, 'r', encoding='utf-8') as f:
+                return json.load(f)
+        except (json.JSONDecodeError, IOError):
+            return {}
+
+    def snooze(self, event_id: str, until: date) -> Dict[str, Any]:
+        """
+        Hide an event's reminder until a date
+
+        Args:
+            event_id: Calendar event ID
+            until: First day the reminder shows again
+
+        Returns:
+            The event's state
+
+        Raises:
+            ValueError: If until isn't in the future
+        """
+        if until <= date.today():
+            raise ValueError("Snooze date must be in the future")
+        return self._update(event_id, snoozed_until=until.isoformat(), dismissed_at=None)
+
+    def dismiss(self, event_id: str) -> Dict[str, Any]:
+        """Stop reminding about an event"""
+        return self._update(event_id, snoozed_until=None, dismissed_at=datetime.utcnow().isoformat())
+
+    def restore(self, event_id: str) -> Dict[str, Any]:
+        """Undo a snooze or dismissal"""
+        return self._update(event_id, snoozed_until=None, dismissed_at=None)
+
+    def import_states(self, states: Dict[str, Dict[str, Any]]) -> None:
+        """Merge reminder states exported from another install"""
+        for event_id, state in states.items():
+            self._update(
+                event_id, snoozed_until=state.get("snoozed_until"), dismissed_at=state.get("dismissed_at")
+            )
+
+    def _update(self, event_id: str, **fields: Any) -> Dict[str, Any]:
+        states = self.get_states()
+        state = {**states.get(event_id, {}), **fields}
+        if any(state.values()):
+            states[event_id] = state
+        else:
+            states.pop(event_id, None)
+        self.state_path.parent.mkdir(parents=True, exist_ok=True)
+        with open(self.state_path, 'w', encoding='utf-8') as f:
+            json.dump(states, f, indent=2)
+        return state
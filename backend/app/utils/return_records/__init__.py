@@ -0,0 +1,8 @@
+"""
+Return Records
+Record types kept inside a stored return, each in its own module and mixed into ReturnStore
+
+The mixins rely on ReturnStore for loading and saving: _require_return,
+save_return (or _write, for records a filed return still accepts), _new_id,
+_check_owner, and (for encrypted fields) cipher.
+"""
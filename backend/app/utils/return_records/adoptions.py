@@ -0,0 +1,78 @@
+"""
+Adoption Records
+Adoptions on a stored return, for the adoption credit (Form 8839)
+"""
+from typing import Dict, Any
+
+
+class AdoptionRecords:
+    """A return's adoptions (mixed into ReturnStore)"""
+
+    # Adoption record amounts and flags (Form 8839)
+    ADOPTION_AMOUNT_FIELDS = ["expenses", "prior_year_expenses", "credit_claimed_before"]
+    ADOPTION_FLAGS = ["foreign", "special_needs"]
+
+    def add_adoption(self, return_id: str, name: str, **fields: Any) -> Dict[str, Any]:
+        """
+        Add a child being adopted, for the adoption credit
+
+        Args:
+            return_id: Return identifier
+            name: Child's name
+            **fields: Extra fields (expenses paid this year, prior_year_expenses
+                paid earlier and not yet claimed, credit_claimed_before,
+                final_year, foreign, special_needs, dependent_id)
+
+        Returns:
+            The new adoption record
+        """
+        self._check_adoption_fields(name=name, **fields)
+        tax_return = self._require_return(return_id)
+        record = {"id": self._new_id("adopt"), "name": name.strip(), **fields}
+        tax_return.setdefault("adoptions", []).append(record)
+        self.save_return(tax_return)
+        return record
+
+    def update_adoption(self, return_id: str, adoption_id: str, **changes: Any) -> Dict[str, Any]:
+        """
+        Change an adoption record
+
+        Raises:
+            KeyError: If the record doesn't exist
+        """
+        self._check_adoption_fields(**changes)
+        tax_return = self._require_return(return_id)
+        record = next((a for a in tax_return.get("adoptions", []) if a["id"] == adoption_id), None)
+        if record is None:
+            raise KeyError(f"Adoption not found: {adoption_id}")
+        record.update({k: v for k, v in changes.items() if k != "id"})
+        self.save_return(tax_return)
+        return record
+
+    def delete_adoption(self, return_id: str, adoption_id: str) -> None:
+        """
+        Remove an adoption record
+
+        Raises:
+            KeyError: If the record doesn't exist
+        """
+        tax_return = self._require_return(return_id)
+        remaining = [a for a in tax_return.get("adoptions", []) if a["id"] != adoption_id]
+        if len(remaining) == len(tax_return.get("adoptions", [])):
+            raise KeyError(f"Adoption not found: {adoption_id}")
+        tax_return["adoptions"] = remaining
+        self.save_return(tax_return)
+
+    def _check_adoption_fields(self, **fields: Any) -> None:
+        """Validate an adoption record's name, amounts, final year, and flags"""
+        if "name" in fields and not str(fields["name"]).strip():
+            raise ValueError("Child's name is required")
+        for name in self.ADOPTION_AMOUNT_FIELDS:
+            if fields.get(name) is not None and fields[name] < 0:
+                raise ValueError(f"{name.replace('_', ' ').capitalize()} cannot be negative")
+        final_year = fields.get("final_year")
+        if final_year is not None and (not isinstance(final_year, int) or not 1900 <= final_year <= 2100):
+            raise ValueError(f"Invalid final_year: {final_year}")
+        for name in self.ADOPTION_FLAGS:
+            if fields.get(name) is not None and not isinstance(fields[name], bool):
+                raise ValueError(f"{name.replace('_', ' ').capitalize()} must be true or false")
@@ -0,0 +1,155 @@
+"""
+Alimony Records
+A return's divorce or separation details and the alimony paid or received under them
+"""
+import re
+from datetime import datetime
+from typing import Dict, List, Any, Optional
+
+from app.tax_engine.alimony import ALIMONY_DIRECTIONS
+from app.tax_engine.divorce import DECREE_TYPES
+from app.utils.field_encryption import mask
+
+
+class AlimonyRecords:
+    """Divorce details and alimony records on a return (mixed into ReturnStore); recipient SSNs are encrypted"""
+
+    ALIMONY_DIRECTIONS = ALIMONY_DIRECTIONS
+    DECREE_TYPES = DECREE_TYPES
+
+    def set_divorce(
+        self,
+        return_id: str,
+        decree_date: Optional[str] = None,
+        decree_type: str = "divorce",
+        separated_date: Optional[str] = None,
+        paid_over_half_home_cost: Optional[bool] = None,
+    ) -> Dict[str, Any]:
+        """
+        Record a divorce or separation for the filing status rules
+
+        Args:
+            return_id: Return identifier
+            decree_date: ISO date the decree became final (None while pending)
+            decree_type: One of DECREE_TYPES
+            separated_date: ISO date the spouse last lived in the home
+            paid_over_half_home_cost: Whether the taxpayer paid more than half
+                the cost of keeping up the home
+
+        Returns:
+            The stored divorce details
+        """
+        if decree_type not in self.DECREE_TYPES:
+            raise ValueError(f"Invalid decree type: {decree_type}. Must be one of: {', '.join(self.DECREE_TYPES)}")
+        for label, value in (("decree_date", decree_date), ("separated_date", separated_date)):
+            if value is not None:
+                try:
+                    datetime.strptime(value, "%Y-%m-%d")
+                except ValueError:
+                    raise ValueError(f"Invalid {label}: {value}")
+        if decree_date and separated_date and separated_date > decree_date:
+            raise ValueError("The separation date can't be after the decree date")
+
+        tax_return = self._require_return(return_id)
+        tax_return["divorce"] = {
+            "decree_date": decree_date,
+            "decree_type": decree_type,
+            "separated_date": separated_date,
+            "paid_over_half_home_cost": paid_over_half_home_cost,
+        }
+        self.save_return(tax_return)
+        return tax_return["divorce"]
+
+    def add_alimony(
+        self,
+        return_id: str,
+        direction: str,
+        amount: float,
+        instrument_date: Optional[str] = None,
+        **fields: Any,
+    ) -> Dict[str, Any]:
+        """
+        Record alimony paid or received during the year
+
+        The recipient's SSN is encrypted on disk.
+
+        Args:
+            return_id: Return identifier
+            direction: paid or received
+            amount: Total for the year
+            instrument_date: ISO date the divorce or separation instrument was
+                executed; defaults to the decree date recorded with set_divorce
+            **fields: Extra fields (modified_to_adopt_new_rule, recipient_ssn,
+                owner, description)
+
+        Returns:
+            The new alimony record, recipient SSN masked
+        """
+        if direction not in self.ALIMONY_DIRECTIONS:
+            raise ValueError(f"Invalid direction: {direction}. Must be one of: {', '.join(self.ALIMONY_DIRECTIONS)}")
+        if amount < 0:
+            raise ValueError("Amount cannot be negative")
+        if instrument_date is not None:
+            try:
+                datetime.strptime(instrument_date, "%Y-%m-%d")
+            except ValueError:
+                raise ValueError(f"Invalid instrument_date: {instrument_date}")
+        if fields.get("owner") is not None and fields["owner"] not in self.RECORD_OWNERS:
+            raise ValueError(f"Invalid owner: {fields['owner']}. Must be one of: {', '.join(self.RECORD_OWNERS)}")
+        if fields.get("recipient_ssn"):
+            ssn = re.sub(r"[\s-]", "", fields["recipient_ssn"])
+            if not re.fullmatch(r"\d{9}", ssn):
+                raise ValueError("Recipient SSN must be 9 digits")
+            fields["recipient_ssn"] = self.cipher.encrypt(ssn)
+
+        tax_return = self._require_return(return_id)
+        if instrument_date is None:
+            instrument_date = (tax_return.get("divorce") or {}).get("decree_date")
+            if instrument_date is None:
+                raise ValueError("instrument_date is required when the return has no decree date")
+            fields["instrument_date_from_decree"] = True
+        record = {
+            "id": self._new_id("alim"),
+            "direction": direction,
+            "amount": amount,
+            "instrument_date": instrument_date,
+            **fields,
+        }
+        tax_return.setdefault("alimony", []).append(record)
+        self.save_return(tax_return)
+        return self._alimony_view(record)
+
+    def get_alimony(self, return_id: str, reveal: bool = False) -> List[Dict[str, Any]]:
+        """
+        Read a return's alimony records
+
+        Args:
+            return_id: Return identifier
+            reveal: Return recipient SSNs in full instead of masking all but the last 4
+
+        Returns:
+            Alimony records with their recipient SSNs decrypted
+        """
+        return [self._alimony_view(a, reveal) for a in self._require_return(return_id).get("alimony", [])]
+
+    def _alimony_view(self, record: Dict[str, Any], reveal: bool = False) -> Dict[str, Any]:
+        """Copy of an alimony record with the recipient SSN decrypted, and masked unless revealed"""
+        record = dict(record)
+        if record.get("recipient_ssn"):
+            ssn = self.cipher.decrypt(record["recipient_ssn"])
+            record["recipient_ssn"] = ssn if reveal else mask(ssn)
+        return record
+
+    def delete_alimony(self, return_id: str, alimony_id: str) -> None:
+        """
+        Remove an alimony record
+
+        Raises:
+            KeyError: If the record doesn't exist
+        """
+        tax_return = self._require_return(return_id)
+        remaining = [a for a in tax_return.get("alimony", []) if a["id"] != alimony_id]
+        if len(remaining) == len(tax_return.get("alimony", [])):
+            raise KeyError(f"Alimony not found: {alimony_id}")
+        tax_return["alimony"] = remaining
+        self.save_return(tax_return)
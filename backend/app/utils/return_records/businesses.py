@@ -0,0 +1,492 @@
+"""
+Business Records
+Schedule C businesses on a stored return, with their expenses, vehicles, at-risk amounts, and hobby determinations
+"""
+from typing import Dict, Any, Optional
+from datetime import datetime
+
+from app.tax_engine.hobby_loss import HOBBY_ANSWERS, evaluate_hobby_factors
+from app.tax_engine.vehicle_expenses import (
+    MILEAGE_DEDUCTION_CATEGORIES, VEHICLE_EXPENSE_KINDS, VEHICLE_METHODS, allowed_methods,
+)
+
+
+class BusinessRecords:
+    """A return's businesses and what's recorded for them (mixed into ReturnStore)"""
+
+    BUSINESS_EXPENSE_CATEGORIES = [
+        "advertising",
+        "car_truck",
+        "commissions_fees",
+        "contract_labor",
+        "depreciation",
+        "insurance",
+        "interest",
+        "legal_professional",
+        "office",
+        "rent_equipment",
+        "rent_property",
+        "repairs",
+        "supplies",
+        "taxes_licenses",
+        "travel",
+        "meals",
+        "utilities",
+        "wages",
+        "other",
+    ]
+
+    BUSINESS_OWNERS = ["taxpayer", "spouse"]
+
+    VEHICLE_METHODS = VEHICLE_METHODS
+    VEHICLE_EXPENSE_KINDS = VEHICLE_EXPENSE_KINDS
+    MILEAGE_DEDUCTION_CATEGORIES = MILEAGE_DEDUCTION_CATEGORIES
+
+    HOBBY_ANSWERS = HOBBY_ANSWERS
+
+    def add_business(
+        self,
+        return_id: str,
+        name: str,
+        owner: str = "taxpayer",
+        **fields: Any,
+    ) -> Dict[str, Any]:
+        """
+        Append a sole proprietorship (one Schedule C) to a return
+
+        Args:
+            return_id: Return identifier
+            name: Business name
+            owner: Whose business it is (taxpayer or spouse)
+            **fields: Extra fields (principal_business_code, gross_receipts, cost_of_goods_sold, ...)
+
+        Returns:
+            The new business record
+        """
+        if not name.strip():
+            raise ValueError("Business name is required")
+        if owner not in self.BUSINESS_OWNERS:
+            raise ValueError(f"Invalid business owner: {owner}. Must be one of: {', '.join(self.BUSINESS_OWNERS)}")
+
+        tax_return = self._require_return(return_id)
+        business = {
+            "id": self._new_id("biz"),
+            "name": name.strip(),
+            "owner": owner,
+            "expenses": [],
+            **fields,
+        }
+        tax_return.setdefault("businesses", []).append(business)
+        self.save_return(tax_return)
+        return business
+
+    def add_business_expense(
+        self,
+        return_id: str,
+        business_id: str,
+        category: str,
+        amount: float,
+        description: str = "",
+        **fields: Any,
+    ) -> Dict[str, Any]:
+        """
+        Append an expense to a business
+
+        Args:
+            return_id: Return identifier
+            business_id: Business identifier
+            category: One of BUSINESS_EXPENSE_CATEGORIES
+            amount: Expense amount
+            description: What the expense was for
+            **fields: Extra fields (date, receipt_id, ...)
+
+        Returns:
+            The new expense record
+        """
+        if category not in self.BUSINESS_EXPENSE_CATEGORIES:
+            raise ValueError(
+                f"Invalid business expense category: {category}. "
+                f"Must be one of: {', '.join(self.BUSINESS_EXPENSE_CATEGORIES)}"
+            )
+        if amount < 0:
+            raise ValueError("Expense amount cannot be negative")
+
+        tax_return = self._require_return(return_id)
+        for business in tax_return.get("businesses", []):
+            if business["id"] == business_id:
+                expense = {
+                    "id": self._new_id("exp"),
+                    "category": category,
+                    "description": description,
+                    "amount": amount,
+                    **fields,
+                }
+                business["expenses"].append(expense)
+                self.save_return(tax_return)
+                return expense
+        raise KeyError(f"Business not found: {business_id}")
+
+    def add_vehicle(
+        self,
+        return_id: str,
+        business_id: str,
+        description: str,
+        **fields: Any,
+    ) -> Dict[str, Any]:
+        """
+        Append a vehicle used in a business
+
+        Args:
+            return_id: Return identifier
+            business_id: Business identifier
+            description: Year, make, and model
+            **fields: Extra fields (placed_in_service, cost, total_miles, leased, heavy_vehicle, method_history)
+
+        Returns:
+            The new vehicle record
+        """
+        if not description.strip():
+            raise ValueError("Vehicle description is required")
+        for method in (fields.get("method_history") or {}).values():
+            self._check_vehicle_method(method)
+
+        tax_return = self._require_return(return_id)
+        for business in tax_return.get("businesses", []):
+            if business["id"] == business_id:
+                vehicle = {
+                    "id": self._new_id("veh"),
+                    "description": description.strip(),
+                    "mileage_log": [],
+                    "expenses": [],
+                    "method_history": {},
+                    **fields,
+                }
+                business.setdefault("vehicles", []).append(vehicle)
+                self.save_return(tax_return)
+                return vehicle
+        raise KeyError(f"Business not found: {business_id}")
+
+    def add_mileage(
+        self,
+        return_id: str,
+        vehicle_id: str,
+        miles: float,
+        business: bool = True,
+        purpose: str = "",
+        category: Optional[str] = None,
+        **fields: Any,
+    ) -> Dict[str, Any]:
+        """
+        Log a trip in a vehicle's mileage log
+
+        Args:
+            return_id: Return identifier
+            vehicle_id: Vehicle identifier
+            miles: Miles driven
+            business: Whether the trip was for business (personal trips set the business-use share)
+            purpose: Where and why
+            category: medical or charitable for a personal trip deducted on Schedule A
+            **fields: Extra fields (date, parking_tolls, ...)
+
+        Returns:
+            The new log entry
+        """
+        self._check_trip(miles, category)
+        if category and business:
+            raise ValueError(f"A {category} trip isn't a business trip")
+
+        tax_return = self._require_return(return_id)
+        vehicle = self._find_vehicle(tax_return, vehicle_id)
+        trip = {"id": self._new_id("trip"), "miles": miles, "business": business, "purpose": purpose, **fields}
+        if category:
+            trip["category"] = category
+        vehicle["mileage_log"].append(trip)
+        self.save_return(tax_return)
+        return trip
+
+    def add_personal_mileage(
+        self,
+        return_id: str,
+        category: str,
+        miles: float,
+        purpose: str = "",
+        **fields: Any,
+    ) -> Dict[str, Any]:
+        """
+        Log a medical or charitable trip in a personal vehicle
+
+        The miles are deducted on Schedule A at the year's rate for the category.
+
+        Args:
+            return_id: Return identifier
+            category: One of MILEAGE_DEDUCTION_CATEGORIES
+            miles: Miles driven
+            purpose: Where and why
+            **fields: Extra fields (date, parking_tolls, ...)
+
+        Returns:
+            The new log entry
+        """
+        self._check_trip(miles, category)
+        if category is None:
+            raise ValueError("Category is required")
+
+        tax_return = self._require_return(return_id)
+        trip = {"id": self._new_id("trip"), "miles": miles, "category": category, "purpose": purpose, **fields}
+        tax_return.setdefault("mileage_log", []).append(trip)
+        self.save_return(tax_return)
+        return trip
+
+    def delete_personal_mileage(self, return_id: str, trip_id: str) -> None:
+        """
+        Remove a trip from the return's personal mileage log
+
+        Raises:
+            KeyError: If the trip doesn't exist
+        """
+        tax_return = self._require_return(return_id)
+        remaining = [t for t in tax_return.get("mileage_log", []) if t["id"] != trip_id]
+        if len(remaining) == len(tax_return.get("mileage_log", [])):
+            raise KeyError(f"Trip not found: {trip_id}")
+        tax_return["mileage_log"] = remaining
+        self.save_return(tax_return)
+
+    def _check_trip(self, miles: float, category: Optional[str]) -> None:
+        """Validate a trip's miles and Schedule A category"""
+        if miles <= 0:
+            raise ValueError("Miles must be positive")
+        if category is not None and category not in self.MILEAGE_DEDUCTION_CATEGORIES:
+            raise ValueError(
+                f"Invalid mileage category: {category}. "
+                f"Must be one of: {', '.join(self.MILEAGE_DEDUCTION_CATEGORIES)}"
+            )
+
+    def add_vehicle_expense(
+        self,
+        return_id: str,
+        vehicle_id: str,
+        kind: str,
+        amount: float,
+        description: str = "",
+        **fields: Any,
+    ) -> Dict[str, Any]:
+        """
+        Record an actual expense of a vehicle (fuel, repairs, insurance, ...)
+
+        Args:
+            return_id: Return identifier
+            vehicle_id: Vehicle identifier
+            kind: One of VEHICLE_EXPENSE_KINDS
+            amount: Expense amount
+            description: What the expense was for
+            **fields: Extra fields (date, receipt_id, ...)
+
+        Returns:
+            The new expense record
+        """
+        if kind not in self.VEHICLE_EXPENSE_KINDS:
+            raise ValueError(
+                f"Invalid vehicle expense kind: {kind}. Must be one of: {', '.join(self.VEHICLE_EXPENSE_KINDS)}"
+            )
+        if amount < 0:
+            raise ValueError("Expense amount cannot be negative")
+
+        tax_return = self._require_return(return_id)
+        vehicle = self._find_vehicle(tax_return, vehicle_id)
+        expense = {"id": self._new_id("vexp"), "kind": kind, "description": description, "amount": amount, **fields}
+        vehicle["expenses"].append(expense)
+        self.save_return(tax_return)
+        return expense
+
+    def set_vehicle_method(self, return_id: str, vehicle_id: str, method: str) -> Dict[str, Any]:
+        """
+        Choose standard mileage or actual expenses for a vehicle this year
+
+        The choice is recorded in the vehicle's method_history, which later
+        years check against the switching rules.
+
+        Args:
+            return_id: Return identifier
+            vehicle_id: Vehicle identifier
+            method: One of VEHICLE_METHODS
+
+        Returns:
+            The updated vehicle record
+
+        Raises:
+            ValueError: If the method is unknown or the switching rules rule it out
+        """
+        self._check_vehicle_method(method)
+        tax_return = self._require_return(return_id)
+        vehicle = self._find_vehicle(tax_return, vehicle_id)
+        allowed = allowed_methods(vehicle, tax_return["tax_year"])
+        if method not in allowed["methods"]:
+            raise ValueError(f"{method} isn't allowed for this vehicle: {allowed['reason']}")
+
+        vehicle["method"] = method
+        vehicle.setdefault("method_history", {})[str(tax_return["tax_year"])] = method
+        self.save_return(tax_return)
+        return vehicle
+
+    def _check_vehicle_method(self, method: str) -> None:
+        if method not in self.VEHICLE_METHODS:
+            raise ValueError(f"Invalid vehicle method: {method}. Must be one of: {', '.join(self.VEHICLE_METHODS)}")
+
+
+    @staticmethod
+    def _find_vehicle(tax_return: Dict[str, Any], vehicle_id: str) -> Dict[str, Any]:
+        """Find a vehicle on any of the return's businesses"""
+        for business in tax_return.get("businesses", []):
+            for vehicle in business.get("vehicles", []):
+                if vehicle["id"] == vehicle_id:
+                    return vehicle
+        raise KeyError(f"Vehicle not found: {vehicle_id}")
+
+    def set_at_risk(
+        self,
+        return_id: str,
+        activity_id: str,
+        at_risk_amount: Optional[float] = None,
+        carryforward: Optional[float] = None,
+    ) -> Dict[str, Any]:
+        """
+        Set a business's or rental's at-risk amount and at-risk loss carryforward
+
+        Args:
+            return_id: Return identifier
+            activity_id: Business or rental property identifier
+            at_risk_amount: Amount at risk at the start of the year
+            carryforward: Losses disallowed by the at-risk rules in earlier years
+
+        Returns:
+            The updated business or rental record
+
+        Raises:
+            KeyError: If there's no such business or rental
+        """
+        if (at_risk_amount is not None and at_risk_amount < 0) or (carryforward is not None and carryforward < 0):
+            raise ValueError("At-risk amounts cannot be negative")
+
+        tax_return = self._require_return(return_id)
+        activities = tax_return.get("businesses", []) + tax_return.get("rental_properties", [])
+        activity = next((a for a in activities if a["id"] == activity_id), None)
+        if activity is None:
+            raise KeyError(f"Activity not found: {activity_id}")
+        if at_risk_amount is not None:
+            activity["at_risk_amount"] = at_risk_amount
+        if carryforward is not None:
+            activity["at_risk_carryforward"] = carryforward
+        self.save_return(tax_return)
+        return activity
+
+    def set_platform_allocation(
+        self,
+        return_id: str,
+        document_id: str,
+        personal_sales: float = 0,
+        personal_cost: float = 0,
+        business_id: Optional[str] = None,
+    ) -> Dict[str, Any]:
+        """
+        Split a 1099-K's gross between personal-item sales and business receipts
+
+        Setting the allocation again for the same document replaces it.
+
+        Args:
+            return_id: Return identifier
+            document_id: The 1099-K document
+            personal_sales: Part of the gross from selling personal items
+            personal_cost: What those personal items originally cost
+            business_id: Business whose receipts the rest of the gross belongs to
+
+        Returns:
+            The allocation record
+
+        Raises:
+            KeyError: If there's no such business
+        """
+        if personal_sales < 0 or personal_cost < 0:
+            raise ValueError("Personal sales and cost cannot be negative")
+
+        tax_return = self._require_return(return_id)
+        if business_id is not None and not any(b["id"] == business_id for b in tax_return.get("businesses", [])):
+            raise KeyError(f"Business not found: {business_id}")
+        allocation = {
+            "document_id": document_id,
+            "personal_sales": personal_sales,
+            "personal_cost": personal_cost,
+            "business_id": business_id,
+        }
+        allocations = [a for a in tax_return.get("platform_allocations", []) if a["document_id"] != document_id]
+        tax_return["platform_allocations"] = allocations + [allocation]
+        self.save_return(tax_return)
+        return allocation
+
+    def save_hobby_determination(
+        self,
+        return_id: str,
+        activity: str,
+        answers: Dict[str, str],
+        notes: Optional[Dict[str, str]] = None,
+        profit_years: Optional[int] = None,
+        horse_activity: bool = False,
+        business_id: Optional[str] = None,
+    ) -> Dict[str, Any]:
+        """
+        Record answers to the hobby-or-business questionnaire with the resulting recommendation
+
+        The answers, recommendation, and rationale are kept together with
+        the date they were recorded, as support if the activity's losses
+        are questioned. Recording the activity again adds a new
+        determination; earlier ones are kept.
+
+        Args:
+            return_id: Return identifier
+            activity: What the activity is
+            answers: Factor name mapped to yes, no, or unsure (see HOBBY_FACTORS)
+            notes: Factor name mapped to the facts behind the answer
+            profit_years: Years with a profit in the presumption period
+            horse_activity: Breeding, training, showing, or racing horses
+            business_id: Business the activity is reported as, if any
+
+        Returns:
+            The determination record
+
+        Raises:
+            KeyError: If there's no such business
+        """
+        if not activity.strip():
+            raise ValueError("Activity is required")
+        result = evaluate_hobby_factors(answers, notes, profit_years, horse_activity)
+
+        tax_return = self._require_return(return_id)
+        if business_id is not None and not any(b["id"] == business_id for b in tax_return.get("businesses", [])):
+            raise KeyError(f"Business not found: {business_id}")
+        determination = {
+            "id": self._new_id("hobby"),
+            "activity": activity.strip(),
+            "business_id": business_id,
+            "answers": answers,
+            "notes": notes or {},
+            "profit_years": profit_years,
+            "horse_activity": horse_activity,
+            **result,
+            "determined_at": datetime.utcnow().isoformat(),
+        }
+        tax_return.setdefault("hobby_determinations", []).append(determination)
+        self.save_return(tax_return)
+        return determination
+
+    def delete_hobby_determination(self, return_id: str, determination_id: str) -> None:
+        """
+        Remove a hobby-or-business determination
+
+        Raises:
+            KeyError: If the record doesn't exist
+        """
+        tax_return = self._require_return(return_id)
+        remaining = [d for d in tax_return.get("hobby_determinations", []) if d["id"] != determination_id]
+        if len(remaining) == len(tax_return.get("hobby_determinations", [])):
+            raise KeyError(f"Determination not found: {determination_id}")
+        tax_return["hobby_determinations"] = remaining
+        self.save_return(tax_return)
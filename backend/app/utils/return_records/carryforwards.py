@@ -0,0 +1,36 @@
+"""
+Carryforward Records
+Amounts carried into a return from prior years (capital loss, NOL, AMT credit, adoption credit)
+"""
+from typing import Dict, Any
+
+
+class CarryforwardRecords:
+    """A return's incoming carryforwards (mixed into ReturnStore); see tax_engine.carryforwards for the outgoing ones"""
+
+    CARRYFORWARD_TYPES = ["capital_loss", "nol", "amt_credit", "adoption_credit"]
+
+    def set_carryforwards(self, return_id: str, **amounts: float) -> Dict[str, Any]:
+        """
+        Set carryforwards coming into a return from prior years
+
+        Args:
+            return_id: Return identifier
+            **amounts: capital_loss, nol, amt_credit, adoption_credit
+
+        Returns:
+            The return's carryforwards
+        """
+        for name, amount in amounts.items():
+            if name not in self.CARRYFORWARD_TYPES:
+                raise ValueError(
+                    f"Invalid carryforward: {name}. Must be one of: {', '.join(self.CARRYFORWARD_TYPES)}"
+                )
+            if amount < 0:
+                raise ValueError(f"Carryforward cannot be negative: {name}")
+
+        tax_return = self._require_return(return_id)
+        carryforwards = tax_return.setdefault("carryforwards", {})
+        carryforwards.update(amounts)
+        self.save_return(tax_return)
+        return carryforwards
@@ -0,0 +1,243 @@
+"""
+Checklist Records
+A stored return's preparation checklist and document requests
+"""
+from typing import Dict, List, Any, Optional
+from datetime import datetime
+
+
+class ChecklistRecords:
+    """A return's checklist items (mixed into ReturnStore)"""
+
+    CHECKLIST_ITEM_KINDS = ["document", "information", "task"]
+
+    # Items every return's checklist starts with
+    CHECKLIST_TEMPLATE = [
+        {"title": "Photo ID and Social Security numbers for everyone on the return", "kind": "information"},
+        {"title": "Last year's tax return", "kind": "document", "form": "1040"},
+        {"title": "Estimated tax payments made during the year", "kind": "information", "form": "1040-ES"},
+        {"title": "Bank account for a refund direct deposit", "kind": "information"},
+    ]
+
+    # Document expected for each income source type / deduction category on the return
+    CHECKLIST_INCOME_FORMS = {
+        "wages": "W-2",
+        "interest": "1099-INT",
+        "dividends": "1099-DIV",
+        "self_employment": "1099-NEC",
+        "capital_gains": "1099-B",
+        "retirement": "1099-R",
+        "social_security": "SSA-1099",
+        "state_refund": "1099-G",
+    }
+    CHECKLIST_DEDUCTION_DOCUMENTS = {
+        "mortgage_interest": ("Form 1098 mortgage interest statement", "1098"),
+        "charitable": ("Donation receipts and acknowledgment letters", None),
+        "medical": ("Medical and dental bills and insurance statements", None),
+        "state_local_tax": ("Property tax bills and state tax payment records", None),
+    }
+
+    def save_checklist(self, return_id: str, items: List[Dict[str, Any]]) -> Dict[str, Any]:
+        """
+        Replace the return's generated checklist items
+
+        Items added by hand (add_checklist_item) are kept.
+
+        Args:
+            return_id: Return identifier
+            items: Checklist items ({"title", "kind", "form", "reason", "received"})
+
+        Returns:
+            The stored checklist
+        """
+        tax_return = self._require_return(return_id)
+        manual = [item for item in tax_return.get("checklist", {}).get("items", []) if item.get("source") == "manual"]
+        checklist = {
+            "generated_at": datetime.utcnow().isoformat(),
+            "items": [self._checklist_item(item, source="generated") for item in items] + manual,
+        }
+        tax_return["checklist"] = checklist
+        self.save_return(tax_return)
+        return checklist
+
+    def seed_checklist(self, return_id: str) -> Dict[str, Any]:
+        """
+        Add template items to a return's checklist
+
+        Adds CHECKLIST_TEMPLATE plus a document for each income source and
+        deduction category on the return; items whose title is already on the
+        checklist are skipped, so seeding again only adds what's new.
+
+        Args:
+            return_id: Return identifier
+
+        Returns:
+            The stored checklist
+        """
+        tax_return = self._require_return(return_id)
+        items = [dict(item, reason="Needed for every return") for item in self.CHECKLIST_TEMPLATE]
+        for source in tax_return.get("income_sources", []):
+            form = self.CHECKLIST_INCOME_FORMS.get(source["type"])
+            if form:
+                payer = source.get("description") or source["type"].replace("_", " ")
+                items.append({"title": f"{form} from {payer}", "form": form, "reason": "Income source on the return"})
+        for category in dict.fromkeys(d["category"] for d in tax_return.get("deductions", [])):
+            if category in self.CHECKLIST_DEDUCTION_DOCUMENTS:
+                title, form = self.CHECKLIST_DEDUCTION_DOCUMENTS[category]
+                items.append({"title": title, "form": form, "reason": "Deduction claimed on the return"})
+
+        checklist = tax_return.setdefault("checklist", {"generated_at": None, "items": []})
+        existing = {item["title"].lower() for item in checklist["items"]}
+        for item in items:
+            if item["title"].lower() not in existing:
+                checklist["items"].append(self._checklist_item(item, source="template"))
+                existing.add(item["title"].lower())
+        self.save_return(tax_return)
+        return checklist
+
+    def save_document_requests(self, return_id: str, items: List[Dict[str, Any]]) -> Dict[str, Any]:
+        """
+        Merge document requests into a return's checklist
+
+        New requests are added; ones already on the checklist (same title)
+        are checked off once received, but never unchecked, so items checked
+        by hand stay checked.
+
+        Args:
+            return_id: Return identifier
+            items: document_requests() output
+
+        Returns:
+            The stored checklist
+        """
+        tax_return = self._require_return(return_id)
+        checklist = tax_return.setdefault("checklist", {"generated_at": None, "items": []})
+        existing = {item["title"].lower(): item for item in checklist["items"]}
+        now = datetime.utcnow().isoformat()
+        for request in items:
+            item = existing.get(request["title"].lower())
+            if item is None:
+                item = self._checklist_item(request, source="request")
+                checklist["items"].append(item)
+                existing[item["title"].lower()] = item
+            elif request["received"] and not item["checked"]:
+                item["checked"] = True
+                item["checked_at"] = now
+            item["payer"] = request.get("payer")
+            if request.get("document_id"):
+                item["document_id"] = request["document_id"]
+        self.save_return(tax_return)
+        return checklist
+
+    def add_checklist_item(
+        self,
+        return_id: str,
+        title: str,
+        kind: str = "document",
+        **fields: Any,
+    ) -> Dict[str, Any]:
+        """
+        Add an item to a return's checklist by hand
+
+        Args:
+            return_id: Return identifier
+            title: What's needed ("K-1 from Maple Partners LP")
+            kind: document, information, or task
+            **fields: form, reason, notes
+
+        Returns:
+            The created item
+        """
+        if not title.strip():
+            raise ValueError("Checklist item title cannot be empty")
+        if kind not in self.CHECKLIST_ITEM_KINDS:
+            raise ValueError(
+                f"Invalid kind: {kind}. Must be one of: {', '.join(self.CHECKLIST_ITEM_KINDS)}"
+            )
+        tax_return = self._require_return(return_id)
+        item = self._checklist_item({"title": title.strip(), "kind": kind, **fields}, source="manual")
+        tax_return.setdefault("checklist", {"generated_at": None, "items": []})["items"].append(item)
+        self.save_return(tax_return)
+        return item
+
+    def delete_checklist_item(self, return_id: str, item_id: str) -> None:
+        """
+        Remove an item from a return's checklist
+
+        Args:
+            return_id: Return identifier
+            item_id: Checklist item identifier
+        """
+        tax_return = self._require_return(return_id)
+        items = tax_return.get("checklist", {}).get("items", [])
+        remaining = [item for item in items if item["id"] != item_id]
+        if len(remaining) == len(items):
+            raise KeyError(f"Checklist item not found: {item_id}")
+        tax_return["checklist"]["items"] = remaining
+        self.save_return(tax_return)
+
+
+    @staticmethod
+    def checklist_progress(tax_return: Dict[str, Any]) -> Dict[str, Any]:
+        """
+        Completion of a return's checklist
+
+        Args:
+            tax_return: Return dict
+
+        Returns:
+            Dict with total, completed, percent_complete, and the outstanding items
+        """
+        items = tax_return.get("checklist", {}).get("items", [])
+        completed = sum(1 for item in items if item["checked"])
+        return {
+            "total": len(items),
+            "completed": completed,
+            "percent_complete": round(100 * completed / len(items)) if items else 100,
+            "outstanding": [item for item in items if not item["checked"]],
+        }
+
+    def _checklist_item(self, item: Dict[str, Any], source: str) -> Dict[str, Any]:
+        """Normalize a checklist item for storage"""
+        return {
+            "id": self._new_id("chk"),
+            "title": str(item["title"]),
+            "kind": item.get("kind", "document"),
+            "form": item.get("form"),
+            "reason": item.get("reason", ""),
+            "notes": item.get("notes", ""),
+            "source": source,
+            "checked": bool(item.get("received", False)),
+            "created_at": datetime.utcnow().isoformat(),
+        }
+
+    def set_checklist_item(
+        self,
+        return_id: str,
+        item_id: str,
+        checked: Optional[bool] = None,
+        notes: Optional[str] = None,
+    ) -> Dict[str, Any]:
+        """
+        Check or uncheck a checklist item, or update its notes
+
+        Args:
+            return_id: Return identifier
+            item_id: Checklist item identifier
+            checked: New state (unchanged if None)
+            notes: Follow-up notes ("waiting on the K-1, promised by March 15")
+
+        Returns:
+            The updated item
+        """
+        tax_return = self._require_return(return_id)
+        for item in tax_return.get("checklist", {}).get("items", []):
+            if item["id"] == item_id:
+                if checked is not None:
+                    item["checked"] = checked
+                    item["checked_at"] = datetime.utcnow().isoformat() if checked else None
+                if notes is not None:
+                    item["notes"] = notes.strip()
+                self.save_return(tax_return)
+                return item
+        raise KeyError(f"Checklist item not found: {item_id}")
@@ -0,0 +1,101 @@
+"""
+Crypto Records
+Crypto received as income and the lots it's later sold from
+"""
+from typing import Dict, List, Any, Optional
+from datetime import date
+from decimal import Decimal
+
+from app.tax_engine.crypto import CRYPTO_RECEIPT_KINDS, classify_receipt, dispose_lots
+
+
+class CryptoRecords:
+    """A return's crypto receipts and disposals (mixed into ReturnStore)"""
+
+    CRYPTO_RECEIPT_KINDS = CRYPTO_RECEIPT_KINDS
+
+    def add_crypto_receipt(
+        self,
+        return_id: str,
+        kind: str,
+        asset: str,
+        quantity: float,
+        fair_market_value: float,
+        date_received: str,
+        business_id: Optional[str] = None,
+        owner: Optional[str] = None,
+    ) -> Dict[str, Any]:
+        """
+        Record crypto received: staking, mining, or airdrop income, or a purchase
+
+        Rewards become an income source at their fair market value, and a
+        basis lot is added for later sales (see tax_engine.crypto).
+
+        Args:
+            return_id: Return identifier
+            kind: One of CRYPTO_RECEIPT_KINDS
+            asset: Ticker or name
+            quantity: Units received
+            fair_market_value: Total U.S. dollar value on receipt (price paid for a purchase)
+            date_received: YYYY-MM-DD, in the return's year
+            business_id: Business the mining is reported on (Schedule C)
+            owner: taxpayer or spouse
+
+        Returns:
+            Dict with income_source (None for a purchase) and lot
+
+        Raises:
+            ValueError: If the receipt is invalid or the business doesn't exist
+        """
+        fields = {"owner": owner} if owner else {}
+        self._check_owner(fields)
+        income, lot = classify_receipt(
+            kind, asset, Decimal(str(quantity)), Decimal(str(fair_market_value)), date_received, business_id
+        )
+        tax_return = self._require_return(return_id)
+        if income and date.fromisoformat(date_received).year != tax_return["tax_year"]:
+            raise ValueError(f"Crypto income must be received in {tax_return['tax_year']}")
+        if business_id and business_id not in {b["id"] for b in tax_return.get("businesses", [])}:
+            raise ValueError(f"Business not found: {business_id}")
+
+        lot = {"id": self._new_id("lot"), **lot, **fields}
+        if income:
+            income = {"id": self._new_id("inc"), "withholding": 0, **income, **fields}
+            tax_return["income_sources"].append(income)
+            lot["income_source_id"] = income["id"]
+        tax_return.setdefault("crypto_lots", []).append(lot)
+        self.save_return(tax_return)
+        return {"income_source": income, "lot": lot}
+
+    def dispose_crypto(
+        self,
+        return_id: str,
+        asset: str,
+        quantity: float,
+        proceeds: float,
+        date_sold: str,
+    ) -> List[Dict[str, Any]]:
+        """
+        Sell, trade, or spend crypto from the return's lots (first in, first out)
+
+        Returns:
+            The capital transactions added to the return
+
+        Raises:
+            ValueError: If the sale is outside the return's year or more is sold than held
+        """
+        tax_return = self._require_return(return_id)
+        try:
+            sold = date.fromisoformat(date_sold)
+        except ValueError:
+            raise ValueError("Sale date must be YYYY-MM-DD")
+        if sold.year != tax_return["tax_year"]:
+            raise ValueError(f"Sales must fall in {tax_return['tax_year']}")
+
+        transactions = dispose_lots(
+            tax_return.get("crypto_lots", []), asset, Decimal(str(quantity)), Decimal(str(proceeds)), date_sold
+        )
+        lots = [{"id": self._new_id("cap"), **t, "source": "crypto"} for t in transactions]
+        tax_return.setdefault("capital_transactions", []).extend(lots)
+        self.save_return(tax_return)
+        return lots
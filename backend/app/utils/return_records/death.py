@@ -0,0 +1,64 @@
+"""
+Death Records
+A taxpayer's or spouse's death during the year and who files for them
+"""
+from typing import Dict, Any, Optional
+from datetime import datetime
+
+from app.tax_engine.decedent import DECEASED_PERSONS
+
+
+class DeathRecords:
+    """Recording a death on a return (mixed into ReturnStore)"""
+
+    DECEASED_PERSONS = DECEASED_PERSONS
+
+    def set_death(
+        self,
+        return_id: str,
+        deceased: str,
+        date_of_death: str,
+        personal_representative: Optional[str] = None,
+        remarried_date: Optional[str] = None,
+        paid_over_half_home_cost: Optional[bool] = None,
+    ) -> Dict[str, Any]:
+        """
+        Record the death of the taxpayer or spouse
+
+        Args:
+            return_id: Return identifier
+            deceased: taxpayer or spouse
+            date_of_death: ISO date of death (in or before the return's year)
+            personal_representative: Who signs for the deceased (executor or administrator)
+            remarried_date: ISO date the surviving spouse remarried
+            paid_over_half_home_cost: Whether the surviving spouse paid more
+                than half the cost of keeping up the home
+
+        Returns:
+            The stored death details
+        """
+        if deceased not in self.DECEASED_PERSONS:
+            raise ValueError(f"Invalid deceased: {deceased}. Must be one of: {', '.join(self.DECEASED_PERSONS)}")
+        for label, value in (("date_of_death", date_of_death), ("remarried_date", remarried_date)):
+            if value is not None:
+                try:
+                    datetime.strptime(value, "%Y-%m-%d")
+                except ValueError:
+                    raise ValueError(f"Invalid {label}: {value}")
+        if remarried_date and remarried_date <= date_of_death:
+            raise ValueError("The remarriage date must be after the date of death")
+
+        tax_return = self._require_return(return_id)
+        if int(date_of_death[:4]) > tax_return["tax_year"]:
+            raise ValueError(f"The date of death is after the return's tax year ({tax_return['tax_year']})")
+        if deceased == "spouse" and not (tax_return.get("taxpayer") or {}).get("spouse_name"):
+            raise ValueError("The return has no spouse")
+        tax_return["death"] = {
+            "deceased": deceased,
+            "date_of_death": date_of_death,
+            "personal_representative": personal_representative,
+            "remarried_date": remarried_date,
+            "paid_over_half_home_cost": paid_over_half_home_cost,
+        }
+        self.save_return(tax_return)
+        return tax_return["death"]
@@ -0,0 +1,83 @@
+"""
+Dependent Records
+Dependents on a stored return and the facts the dependent tests use
+"""
+from typing import Dict, Any
+
+from app.tax_engine.dependents import CUSTODIAL_PARENTS
+
+
+class DependentRecords:
+    """Adding and changing a return's dependents (mixed into ReturnStore)"""
+
+    CUSTODIAL_PARENTS = CUSTODIAL_PARENTS
+
+    def add_dependent(
+        self,
+        return_id: str,
+        name: str,
+        relationship: str,
+        **fields: Any,
+    ) -> Dict[str, Any]:
+        """
+        Append a dependent to a return
+
+        Args:
+            return_id: Return identifier
+            name: Dependent's full name
+            relationship: Relationship to the taxpayer (son, daughter, parent, ...)
+            **fields: Extra fields (birth_date, months_lived_with, ...)
+
+        Returns:
+            The new dependent record
+        """
+        if not name.strip():
+            raise ValueError("Dependent name is required")
+        self._check_dependent_fields(**fields)
+
+        tax_return = self._require_return(return_id)
+        dependent = {
+            "id": self._new_id("dep"),
+            "name": name.strip(),
+            "relationship": relationship,
+            **fields,
+        }
+        tax_return["dependents"].append(dependent)
+        self.save_return(tax_return)
+        return dependent
+
+    def update_dependent(self, return_id: str, dependent_id: str, **changes: Any) -> Dict[str, Any]:
+        """
+        Change a dependent's details (the facts the dependent tests use)
+
+        Raises:
+            KeyError: If the dependent doesn't exist
+        """
+        self._check_dependent_fields(**changes)
+        tax_return = self._require_return(return_id)
+        for dependent in tax_return["dependents"]:
+            if dependent["id"] == dependent_id:
+                dependent.update({k: v for k, v in changes.items() if k != "id"})
+                self.save_return(tax_return)
+                return dependent
+        raise KeyError(f"Dependent not found: {dependent_id}")
+
+    def _check_dependent_fields(self, **fields: Any) -> None:
+        """Validate a dependent's name, custodial parent, months lived with the taxpayer, and support percentages"""
+        if "name" in fields and not str(fields["name"]).strip():
+            raise ValueError("Dependent name is required")
+        if fields.get("custodial_parent") is not None and fields["custodial_parent"] not in self.CUSTODIAL_PARENTS:
+            raise ValueError(
+                f"Invalid custodial parent: {fields['custodial_parent']}. Must be one of: {', '.join(self.CUSTODIAL_PARENTS)}"
+            )
+        months = fields.get("months_lived_with")
+        if months is not None and not 0 <= months <= 12:
+            raise ValueError("Months lived with the taxpayer must be between 0 and 12")
+        for key in ("support_percent", "self_support_percent"):
+            if fields.get(key) is not None and not 0 <= fields[key] <= 100:
+                raise ValueError(f"{key.replace('_', ' ').capitalize()} must be between 0 and 100")
+        if fields.get("gross_income") is not None and fields["gross_income"] < 0:
+            raise ValueError("Gross income cannot be negative")
+        years = fields.get("form_8332_years")
+        if years is not None and not all(isinstance(year, int) for year in years):
+            raise ValueError("Form 8332 years must be tax years")
@@ -0,0 +1,303 @@
+"""
+Filing Records
+How a stored return is filed and paid: extensions, estimated payments, the refund account, and IP PINs
+"""
+import re
+from typing import Dict, List, Any, Optional
+from datetime import datetime
+
+from app.tax_engine.tax_calendar import ESTIMATED_QUARTERS
+from app.utils.field_encryption import mask
+
+
+class FilingRecords:
+    """A return's extension, estimated payments, direct deposit, and IP PINs (mixed into ReturnStore)"""
+
+    ESTIMATED_QUARTERS = ESTIMATED_QUARTERS
+
+    BANK_ACCOUNT_TYPES = ["checking", "savings"]
+
+    IP_PIN_PERSONS = ["taxpayer", "spouse"]
+
+    def set_extension(
+        self,
+        return_id: str,
+        filed_on: str,
+        confirmation: Optional[str] = None,
+        states: Optional[List[str]] = None,
+    ) -> Dict[str, Any]:
+        """
+        Record that an extension (Form 4868) was filed for a return
+
+        Args:
+            return_id: Return identifier
+            filed_on: ISO date the extension was filed
+            confirmation: Acknowledgment or confirmation number
+            states: State returns whose deadline is extended too
+
+        Returns:
+            The stored extension
+        """
+        try:
+            datetime.strptime(filed_on, "%Y-%m-%d")
+        except ValueError:
+            raise ValueError(f"Invalid filed_on date: {filed_on}")
+        tax_return = self._require_return(return_id)
+        states = [state.upper() for state in states or []]
+        on_return = {record["state"] for record in tax_return.get("state_returns", [])}
+        for state in states:
+            if state not in on_return:
+                raise ValueError(f"Return has no {state} state return to extend")
+
+        tax_return["extension"] = {
+            "filed_on": filed_on,
+            "confirmation": confirmation,
+            "states": states,
+            "recorded_at": datetime.utcnow().isoformat(),
+        }
+        self.save_return(tax_return)
+        return tax_return["extension"]
+
+    def add_estimated_payment(
+        self,
+        return_id: str,
+        quarter: str,
+        amount: float,
+        paid_date: str,
+        description: str = "",
+    ) -> Dict[str, Any]:
+        """
+        Log a payment of next year's estimated tax
+
+        The return's calendar schedules next year's quarterly estimates; a
+        payment logged for a quarter marks it met, which stops its reminders.
+        Allowed on filed returns too, since next year's estimates don't change
+        this return's figures; they're credited on next year's return, which
+        gets them when this one is cloned.
+
+        Args:
+            return_id: Return identifier
+            quarter: One of ESTIMATED_QUARTERS
+            amount: Amount paid
+            paid_date: ISO date it was paid
+            description: How it was paid (e.g. "IRS Direct Pay")
+
+        Returns:
+            The payment record, with tax_year set to the year the estimate is for
+        """
+        if quarter not in self.ESTIMATED_QUARTERS:
+            raise ValueError(f"Invalid quarter: {quarter}. Must be one of: {', '.join(self.ESTIMATED_QUARTERS)}")
+        if amount <= 0:
+            raise ValueError("Amount must be positive")
+        try:
+            datetime.strptime(paid_date, "%Y-%m-%d")
+        except ValueError:
+            raise ValueError(f"Invalid paid_date: {paid_date}")
+
+        tax_return = self._require_return(return_id)
+        payment = {
+            "id": self._new_id("est"),
+            "tax_year": tax_return["tax_year"] + 1,
+            "quarter": quarter,
+            "amount": amount,
+            "paid_date": paid_date,
+            "description": description,
+        }
+        tax_return.setdefault("estimated_payments", []).append(payment)
+        self._write(tax_return)
+        return payment
+
+    def delete_estimated_payment(self, return_id: str, payment_id: str) -> None:
+        """
+        Remove a logged estimated tax payment
+
+        Raises:
+            KeyError: If the payment doesn't exist
+        """
+        tax_return = self._require_return(return_id)
+        remaining = [p for p in tax_return.get("estimated_payments", []) if p["id"] != payment_id]
+        if len(remaining) == len(tax_return.get("estimated_payments", [])):
+            raise KeyError(f"Estimated payment not found: {payment_id}")
+        tax_return["estimated_payments"] = remaining
+        self._write(tax_return)
+
+    def set_direct_deposit(
+        self,
+        return_id: str,
+        routing_number: str,
+        account_number: str,
+        account_type: str = "checking",
+    ) -> Dict[str, Any]:
+        """
+        Store the bank account a refund is deposited to
+
+        The routing and account numbers are encrypted on disk.
+
+        Args:
+            return_id: Return identifier
+            routing_number: 9-digit ABA routing number
+            account_number: Account number (up to 17 characters)
+            account_type: checking or savings
+
+        Returns:
+            The masked direct deposit details
+        """
+        routing_number = routing_number.strip()
+        account_number = account_number.strip().replace(" ", "")
+        if not _valid_routing_number(routing_number):
+            raise ValueError(f"Invalid routing number: {mask(routing_number)}")
+        if not re.fullmatch(r"[A-Za-z0-9\-]{4,17}", account_number):
+            raise ValueError("Account number must be 4 to 17 letters, digits, or hyphens")
+        if account_type not in self.BANK_ACCOUNT_TYPES:
+            raise ValueError(
+                f"Invalid account_type: {account_type}. Must be one of: {', '.join(self.BANK_ACCOUNT_TYPES)}"
+            )
+
+        tax_return = self._require_return(return_id)
+        tax_return["direct_deposit"] = {
+            "routing_number": self.cipher.encrypt(routing_number),
+            "account_number": self.cipher.encrypt(account_number),
+            "account_type": account_type,
+            "updated_at": datetime.utcnow().isoformat(),
+        }
+        self.save_return(tax_return)
+        return self.get_direct_deposit(return_id)
+
+    def get_direct_deposit(self, return_id: str, reveal: bool = False) -> Optional[Dict[str, Any]]:
+        """
+        Read a return's direct deposit details
+
+        Args:
+            return_id: Return identifier
+            reveal: Return the full numbers instead of masking all but the last 4
+
+        Returns:
+            Dict with routing_number, account_number, and account_type, or
+            None if no account is on file
+        """
+        stored = self._require_return(return_id).get("direct_deposit")
+        if not stored:
+            return None
+        details = {
+            "routing_number": self.cipher.decrypt(stored["routing_number"]),
+            "account_number": self.cipher.decrypt(stored["account_number"]),
+            "account_type": stored["account_type"],
+            "updated_at": stored.get("updated_at"),
+        }
+        if not reveal:
+            details["routing_number"] = mask(details["routing_number"])
+            details["account_number"] = mask(details["account_number"])
+        return details
+
+    def clear_direct_deposit(self, return_id: str) -> bool:
+        """
+        Remove a return's direct deposit details
+
+        Args:
+            return_id: Return identifier
+
+        Returns:
+            True if details were removed, False if none were on file
+        """
+        tax_return = self._require_return(return_id)
+        if not tax_return.pop("direct_deposit", None):
+            return False
+        self.save_return(tax_return)
+        return True
+
+    def set_ip_pin(
+        self,
+        return_id: str,
+        person: str,
+        pin: str,
+        valid_year: Optional[int] = None,
+    ) -> Dict[str, Any]:
+        """
+        Store the Identity Protection PIN the IRS issued to the taxpayer or spouse
+
+        A new IP PIN is issued every January and is only good for returns
+        filed that calendar year. The PIN is encrypted on disk.
+
+        Args:
+            return_id: Return identifier
+            person: taxpayer or spouse
+            pin: 6-digit IP PIN
+            valid_year: Calendar year the PIN is for (defaults to the year
+                after the tax year, when the return is normally filed)
+
+        Returns:
+            The masked IP PIN details
+        """
+        if person not in self.IP_PIN_PERSONS:
+            raise ValueError(f"Invalid person: {person}. Must be one of: {', '.join(self.IP_PIN_PERSONS)}")
+        pin = pin.strip()
+        if not re.fullmatch(r"\d{6}", pin):
+            raise ValueError("An IP PIN is 6 digits")
+
+        tax_return = self._require_return(return_id)
+        if person == "spouse" and not (tax_return.get("taxpayer") or {}).get("spouse_name"):
+            raise ValueError("The return has no spouse")
+        if valid_year is None:
+            valid_year = tax_return["tax_year"] + 1
+        elif valid_year <= tax_return["tax_year"]:
+            raise ValueError(
+                f"An IP PIN for {valid_year} can't be used on a {tax_return['tax_year']} return, "
+                "which is filed in a later year"
+            )
+        tax_return.setdefault("ip_pins", {})[person] = {
+            "pin": self.cipher.encrypt(pin),
+            "valid_year": valid_year,
+            "updated_at": datetime.utcnow().isoformat(),
+        }
+        self.save_return(tax_return)
+        return self.get_ip_pins(return_id)[person]
+
+    def get_ip_pins(self, return_id: str, reveal: bool = False) -> Dict[str, Dict[str, Any]]:
+        """
+        Read a return's IP PINs
+
+        Args:
+            return_id: Return identifier
+            reveal: Return the full PINs instead of masking all but the last 2 digits
+
+        Returns:
+            Person -> dict with pin, valid_year, and updated_at (empty if none are on file)
+        """
+        stored = self._require_return(return_id).get("ip_pins") or {}
+        pins = {}
+        for person, details in stored.items():
+            pin = self.cipher.decrypt(details["pin"])
+            pins[person] = {
+                "pin": pin if reveal else mask(pin, visible=2),
+                "valid_year": details["valid_year"],
+                "updated_at": details.get("updated_at"),
+            }
+        return pins
+
+    def clear_ip_pin(self, return_id: str, person: str) -> bool:
+        """
+        Remove the taxpayer's or spouse's IP PIN
+
+        Args:
+            return_id: Return identifier
+            person: taxpayer or spouse
+
+        Returns:
+            True if a PIN was removed, False if none was on file
+        """
+        tax_return = self._require_return(return_id)
+        pins = tax_return.get("ip_pins") or {}
+        if not pins.pop(person, None):
+            return False
+        tax_return["ip_pins"] = pins
+        self.save_return(tax_return)
+        return True
+
+
+def _valid_routing_number(routing_number: str) -> bool:
+    """ABA routing number: 9 digits with a valid 3-7-1 checksum"""
+    if not re.fullmatch(r"\d{9}", routing_number):
+        return False
+    digits = [int(d) for d in routing_number]
+    checksum = sum(weight * digit for weight, digit in zip([3, 7, 1] * 3, digits))
+    return checksum % 10 == 0
@@ -0,0 +1,94 @@
+"""
+Foreign Account Records
+Foreign financial accounts on a stored return, for the FBAR and Form 8938 checks
+"""
+from typing import Dict, Any
+
+
+class ForeignAccountRecords:
+    """A return's foreign accounts (mixed into ReturnStore)"""
+
+    FOREIGN_ACCOUNT_TYPES = ["bank", "securities", "pension", "insurance", "other"]
+
+    def add_foreign_account(
+        self,
+        return_id: str,
+        institution: str,
+        country: str,
+        max_balance: float,
+        **fields: Any,
+    ) -> Dict[str, Any]:
+        """
+        Record a financial account held outside the U.S., for FBAR and Form 8938
+
+        Args:
+            return_id: Return identifier
+            institution: Bank or broker name
+            country: Where the account is held
+            max_balance: Highest balance during the year, in U.S. dollars
+            **fields: year_end_balance, account_type, account_number_last4, owner
+
+        Returns:
+            The new account record
+        """
+        if not institution.strip() or not country.strip():
+            raise ValueError("Institution and country are required")
+        self._check_foreign_account_fields(max_balance=max_balance, **fields)
+
+        tax_return = self._require_return(return_id)
+        account = {
+            "id": self._new_id("fa"),
+            "institution": institution.strip(),
+            "country": country.strip(),
+            "max_balance": max_balance,
+            **fields,
+        }
+        tax_return.setdefault("foreign_accounts", []).append(account)
+        self.save_return(tax_return)
+        return account
+
+    def update_foreign_account(self, return_id: str, account_id: str, **changes: Any) -> Dict[str, Any]:
+        """
+        Change fields of a foreign account
+
+        Raises:
+            KeyError: If the account doesn't exist
+        """
+        tax_return = self._require_return(return_id)
+        for account in tax_return.get("foreign_accounts", []):
+            if account["id"] == account_id:
+                changes = {k: v for k, v in changes.items() if k != "id"}
+                self._check_foreign_account_fields(**{**account, **changes})
+                account.update(changes)
+                self.save_return(tax_return)
+                return account
+        raise KeyError(f"Foreign account not found: {account_id}")
+
+    def delete_foreign_account(self, return_id: str, account_id: str) -> None:
+        """
+        Remove a foreign account
+
+        Raises:
+            KeyError: If the account doesn't exist
+        """
+        tax_return = self._require_return(return_id)
+        remaining = [a for a in tax_return.get("foreign_accounts", []) if a["id"] != account_id]
+        if len(remaining) == len(tax_return.get("foreign_accounts", [])):
+            raise KeyError(f"Foreign account not found: {account_id}")
+        tax_return["foreign_accounts"] = remaining
+        self.save_return(tax_return)
+
+    def _check_foreign_account_fields(self, **fields: Any) -> None:
+        """Validate a foreign account's balances, type, and owner"""
+        for name in ("max_balance", "year_end_balance"):
+            if fields.get(name) is not None and fields[name] < 0:
+                raise ValueError(f"{name.replace('_', ' ').capitalize()} cannot be negative")
+        if fields.get("max_balance") is not None and fields.get("year_end_balance") is not None \
+                and fields["year_end_balance"] > fields["max_balance"]:
+            raise ValueError("Year-end balance cannot exceed the maximum balance")
+        if fields.get("account_type") is not None and fields["account_type"] not in self.FOREIGN_ACCOUNT_TYPES:
+            raise ValueError(
+                f"Invalid account type: {fields['account_type']}. "
+                f"Must be one of: {', '.join(self.FOREIGN_ACCOUNT_TYPES)}"
+            )
+        self._check_owner(fields)
@@ -0,0 +1,98 @@
+"""
+Holding Records
+Investment holdings on a stored return, with their basis for later sales
+"""
+from typing import Dict, Any, Optional
+from datetime import date
+
+
+class HoldingRecords:
+    """A return's investment holdings (mixed into ReturnStore)"""
+
+    def add_holding(
+        self,
+        return_id: str,
+        symbol: str,
+        quantity: float,
+        cost_basis: float,
+        date_acquired: str,
+        market_value: Optional[float] = None,
+    ) -> Dict[str, Any]:
+        """
+        Record an open investment position, for tax-loss harvesting
+
+        Args:
+            return_id: Return identifier
+            symbol: Ticker
+            quantity: Shares or units held
+            cost_basis: Total basis of the lot
+            date_acquired: YYYY-MM-DD
+            market_value: Current total value of the lot
+
+        Returns:
+            The new holding record
+        """
+        symbol = symbol.strip().upper()
+        if not symbol:
+            raise ValueError("Symbol is required")
+        self._check_holding_fields(quantity=quantity, cost_basis=cost_basis, date_acquired=date_acquired,
+                                   market_value=market_value)
+
+        tax_return = self._require_return(return_id)
+        holding = {
+            "id": self._new_id("hold"),
+            "symbol": symbol,
+            "quantity": quantity,
+            "cost_basis": cost_basis,
+            "date_acquired": date_acquired,
+            "market_value": market_value,
+        }
+        tax_return.setdefault("holdings", []).append(holding)
+        self.save_return(tax_return)
+        return holding
+
+    def update_holding(self, return_id: str, holding_id: str, **changes: Any) -> Dict[str, Any]:
+        """
+        Change a holding (usually its market_value)
+
+        Raises:
+            KeyError: If the holding doesn't exist
+        """
+        if "symbol" in changes:
+            changes["symbol"] = changes["symbol"].strip().upper()
+        self._check_holding_fields(**changes)
+
+        tax_return = self._require_return(return_id)
+        for holding in tax_return.get("holdings", []):
+            if holding["id"] == holding_id:
+                holding.update({k: v for k, v in changes.items() if k != "id"})
+                self.save_return(tax_return)
+                return holding
+        raise KeyError(f"Holding not found: {holding_id}")
+
+    def delete_holding(self, return_id: str, holding_id: str) -> None:
+        """
+        Remove a holding
+
+        Raises:
+            KeyError: If the holding doesn't exist
+        """
+        tax_return = self._require_return(return_id)
+        remaining = [h for h in tax_return.get("holdings", []) if h["id"] != holding_id]
+        if len(remaining) == len(tax_return.get("holdings", [])):
+            raise KeyError(f"Holding not found: {holding_id}")
+        tax_return["holdings"] = remaining
+        self.save_return(tax_return)
+
+    def _check_holding_fields(self, **fields: Any) -> None:
+        """Validate a holding's amounts and acquisition date"""
+        if fields.get("quantity") is not None and fields["quantity"] <= 0:
+            raise ValueError("Quantity must be positive")
+        for name in ("cost_basis", "market_value"):
+            if fields.get(name) is not None and fields[name] < 0:
+                raise ValueError(f"{name.replace('_', ' ').capitalize()} cannot be negative")
+        if fields.get("date_acquired") is not None:
+            try:
+                date.fromisoformat(fields["date_acquired"])
+            except ValueError:
+                raise ValueError("Acquisition date must be YYYY-MM-DD")
@@ -0,0 +1,296 @@
+"""
+Income Records
+Income sources, deductions, and capital transactions on a stored return
+"""
+import re
+from typing import Dict, List, Any
+
+from app.tax_engine.early_distributions import DISTRIBUTION_CODES, EXCEPTION_CODES, PLAN_TYPES
+from app.tax_engine.schedule_b import TAX_EXEMPT_FIELDS
+
+
+class IncomeRecords:
+    """Adding and changing a return's income sources, deductions, and capital transactions (mixed into ReturnStore)"""
+
+    INCOME_TYPES = [
+        "wages",
+        "interest",
+        "dividends",
+        "self_employment",
+        "capital_gains",
+        "retirement",
+        "social_security",
+        "state_refund",
+        "other",
+    ]
+
+    DEDUCTION_CATEGORIES = [
+        "medical",
+        "state_local_tax",
+        "mortgage_interest",
+        "charitable",
+        "other",
+    ]
+
+    # Income reported payer by payer on Schedule B, and the flags those records can carry
+    SCHEDULE_B_TYPES = ["interest", "dividends"]
+    SCHEDULE_B_FLAGS = ["tax_exempt", "foreign_account"]
+
+    def add_income_source(
+        self,
+        return_id: str,
+        source_type: str,
+        amount: float,
+        description: str = "",
+        withholding: float = 0,
+        **fields: Any,
+    ) -> Dict[str, Any]:
+        """
+        Append an income source to a return
+
+        Args:
+            return_id: Return identifier
+            source_type: One of INCOME_TYPES
+            amount: Gross amount
+            description: Payer/employer description
+            withholding: Federal income tax withheld
+            **fields: Extra source-specific fields (qualified_dividends for dividends,
+                tax_exempt, foreign_account, and bond_state for interest and
+                dividends, tax_exempt_interest for interest,
+                exempt_interest_dividends for dividends, distribution_code,
+                plan_type, exception_code, and exception_amount for
+                retirement distributions, ...)
+
+        Returns:
+            The new income source record
+        """
+        if source_type not in self.INCOME_TYPES:
+            raise ValueError(
+                f"Invalid income type: {source_type}. "
+                f"Must be one of: {', '.join(self.INCOME_TYPES)}"
+            )
+        if amount < 0 or withholding < 0:
+            raise ValueError("Income and withholding cannot be negative")
+        self._check_owner(fields)
+        self._check_qualified_dividends({"type": source_type, "amount": amount, **fields})
+        self._check_schedule_b_flags({"type": source_type, **fields})
+        self._check_tax_exempt_interest({"type": source_type, **fields})
+        self._check_early_distribution({"type": source_type, "amount": amount, **fields})
+
+        tax_return = self._require_return(return_id)
+        source = {
+            "id": self._new_id("inc"),
+            "type": source_type,
+            "description": description,
+            "amount": amount,
+            "withholding": withholding,
+            **fields,
+        }
+        tax_return["income_sources"].append(source)
+        self.save_return(tax_return)
+        return source
+
+    def update_income_source(self, return_id: str, source_id: str, **changes: Any) -> Dict[str, Any]:
+        """
+        Change fields of an existing income source
+
+        Args:
+            return_id: Return identifier
+            source_id: Income source identifier
+            **changes: Fields to set (type, amount, withholding, ...)
+
+        Returns:
+            The updated income source record
+        """
+        if "type" in changes and changes["type"] not in self.INCOME_TYPES:
+            raise ValueError(
+                f"Invalid income type: {changes['type']}. "
+                f"Must be one of: {', '.join(self.INCOME_TYPES)}"
+            )
+        if changes.get("amount", 0) < 0 or changes.get("withholding", 0) < 0:
+            raise ValueError("Income and withholding cannot be negative")
+        self._check_owner(changes)
+
+        tax_return = self._require_return(return_id)
+        for source in tax_return["income_sources"]:
+            if source["id"] == source_id:
+                self._check_qualified_dividends({**source, **changes})
+                self._check_schedule_b_flags({**source, **changes})
+                self._check_tax_exempt_interest({**source, **changes})
+                self._check_early_distribution({**source, **changes})
+                source.update({k: v for k, v in changes.items() if k != "id"})
+                self.save_return(tax_return)
+                return source
+        raise KeyError(f"Income source not found: {source_id}")
+
+    def delete_income_source(self, return_id: str, source_id: str) -> None:
+        """
+        Remove an income source
+
+        Raises:
+            KeyError: If the source doesn't exist
+        """
+        tax_return = self._require_return(return_id)
+        remaining = [s for s in tax_return["income_sources"] if s["id"] != source_id]
+        if len(remaining) == len(tax_return["income_sources"]):
+            raise KeyError(f"Income source not found: {source_id}")
+        tax_return["income_sources"] = remaining
+        self.save_return(tax_return)
+
+    def add_deduction(
+        self,
+        return_id: str,
+        category: str,
+        amount: float,
+        description: str = "",
+        **fields: Any,
+    ) -> Dict[str, Any]:
+        """
+        Append an itemized deduction to a return
+
+        Args:
+            return_id: Return identifier
+            category: One of DEDUCTION_CATEGORIES
+            amount: Deduction amount
+            description: What the deduction is for
+            **fields: Extra category-specific fields
+
+        Returns:
+            The new deduction record
+        """
+        if category not in self.DEDUCTION_CATEGORIES:
+            raise ValueError(
+                f"Invalid deduction category: {category}. "
+                f"Must be one of: {', '.join(self.DEDUCTION_CATEGORIES)}"
+            )
+        if amount < 0:
+            raise ValueError("Deduction amount cannot be negative")
+        self._check_owner(fields)
+
+        tax_return = self._require_return(return_id)
+        deduction = {
+            "id": self._new_id("ded"),
+            "category": category,
+            "description": description,
+            "amount": amount,
+            **fields,
+        }
+        tax_return["deductions"].append(deduction)
+        self.save_return(tax_return)
+        return deduction
+
+    def update_deduction(self, return_id: str, deduction_id: str, **changes: Any) -> Dict[str, Any]:
+        """
+        Change fields of an existing deduction
+
+        Args:
+            return_id: Return identifier
+            deduction_id: Deduction identifier
+            **changes: Fields to set (category, amount, description, ...)
+
+        Returns:
+            The updated deduction record
+        """
+        if "category" in changes and changes["category"] not in self.DEDUCTION_CATEGORIES:
+            raise ValueError(
+                f"Invalid deduction category: {changes['category']}. "
+                f"Must be one of: {', '.join(self.DEDUCTION_CATEGORIES)}"
+            )
+        if changes.get("amount", 0) < 0:
+            raise ValueError("Deduction amount cannot be negative")
+        self._check_owner(changes)
+
+        tax_return = self._require_return(return_id)
+        for deduction in tax_return["deductions"]:
+            if deduction["id"] == deduction_id:
+                deduction.update({k: v for k, v in changes.items() if k != "id"})
+                self.save_return(tax_return)
+                return deduction
+        raise KeyError(f"Deduction not found: {deduction_id}")
+
+    def add_capital_transactions(
+        self,
+        return_id: str,
+        transactions: List[Dict[str, Any]],
+        **fields: Any,
+    ) -> List[Dict[str, Any]]:
+        """
+        Append capital gain/loss lots (Form 8949 rows) to a return
+
+        Args:
+            return_id: Return identifier
+            transactions: Lots with date_sold, proceeds, cost_basis, term, ...
+            **fields: Fields set on every lot (source, broker, ...)
+
+        Returns:
+            The new lot records
+        """
+        for transaction in transactions:
+            if transaction.get("proceeds") is None or not transaction.get("date_sold"):
+                raise ValueError("Capital transactions need proceeds and a sale date")
+
+        tax_return = self._require_return(return_id)
+        lots = [{"id": self._new_id("cap"), **transaction, **fields} for transaction in transactions]
+        tax_return.setdefault("capital_transactions", []).extend(lots)
+        self.save_return(tax_return)
+        return lots
+
+    def _check_qualified_dividends(self, source: Dict[str, Any]) -> None:
+        """Qualified dividends (1099-DIV box 1b) are part of a dividend source's ordinary dividends (box 1a)"""
+        qualified = source.get("qualified_dividends")
+        if qualified is None:
+            return
+        if source["type"] != "dividends":
+            raise ValueError("Only dividend income can have qualified dividends")
+        if qualified < 0:
+            raise ValueError("Qualified dividends cannot be negative")
+        if qualified > source["amount"]:
+            raise ValueError("Qualified dividends cannot exceed the ordinary dividends they're part of")
+
+    def _check_schedule_b_flags(self, source: Dict[str, Any]) -> None:
+        """Tax-exempt and foreign account flags only apply to interest and dividends"""
+        for flag in self.SCHEDULE_B_FLAGS:
+            if source.get(flag) is None:
+                continue
+            if not isinstance(source[flag], bool):
+                raise ValueError(f"{flag.replace('_', ' ').capitalize()} must be true or false")
+            if source[flag] and source["type"] not in self.SCHEDULE_B_TYPES:
+                raise ValueError(f"Only interest and dividends can be marked {flag.replace('_', ' ')}")
+
+    def _check_tax_exempt_interest(self, source: Dict[str, Any]) -> None:
+        """Box 8 tax-exempt interest and box 12 exempt-interest dividends, and the state that issued the bonds"""
+        for source_type, field in TAX_EXEMPT_FIELDS.items():
+            if source.get(field) is None:
+                continue
+            if source["type"] != source_type:
+                label = "dividend" if source_type == "dividends" else source_type
+                raise ValueError(f"Only {label} income can have {field.replace('_', ' ')}")
+            if source[field] < 0:
+                raise ValueError(f"{field.replace('_', ' ').capitalize()} cannot be negative")
+        if source.get("bond_state") is not None:
+            if source["type"] not in self.SCHEDULE_B_TYPES:
+                raise ValueError("Only interest and dividends can have a bond state")
+            if not re.fullmatch(r"[A-Z]{2}", source["bond_state"]):
+                raise ValueError(f"Invalid state code: {source['bond_state']}")
+
+    def _check_early_distribution(self, source: Dict[str, Any]) -> None:
+        """1099-R box 7 code, plan type, and the Form 5329 exception claimed for a retirement distribution"""
+        fields = ["distribution_code", "plan_type", "exception_code", "exception_amount"]
+        if all(source.get(field) is None for field in fields):
+            return
+        if source["type"] != "retirement":
+            raise ValueError("Only retirement distributions can have a distribution code, plan type, or exception")
+        for field, allowed in [
+            ("distribution_code", DISTRIBUTION_CODES), ("plan_type", PLAN_TYPES), ("exception_code", EXCEPTION_CODES),
+        ]:
+            if source.get(field) is not None and source[field] not in allowed:
+                raise ValueError(
+                    f"Invalid {field.replace('_', ' ')}: {source[field]}. Must be one of: {', '.join(allowed)}"
+                )
+        if source.get("exception_amount") is not None:
+            if not source.get("exception_code"):
+                raise ValueError("Give the exception the amount is claimed under")
+            if source["exception_amount"] < 0:
+                raise ValueError("Exception amount cannot be negative")
+            if source["exception_amount"] > source["amount"]:
+                raise ValueError("Exception amount cannot exceed the distribution")
@@ -0,0 +1,192 @@
+"""
+Rental Records
+Rental properties on a stored return, including sales and like-kind exchanges
+"""
+from typing import Dict, Any, Optional
+from datetime import date
+from decimal import Decimal
+
+from app.tax_engine.rental_depreciation import replacement_property
+
+
+class RentalRecords:
+    """A return's rental properties (mixed into ReturnStore)"""
+
+    # A like-kind exchange's replacement must be received within 180 days of the transfer
+    EXCHANGE_DEADLINE_DAYS = 180
+
+    def add_rental_property(
+        self,
+        return_id: str,
+        address: str,
+        building_basis: float,
+        placed_in_service: str,
+        **fields: Any,
+    ) -> Dict[str, Any]:
+        """
+        Add a residential rental property (Schedule E)
+
+        Args:
+            return_id: Return identifier
+            address: Property address
+            building_basis: Cost of the building, not the land, plus improvements
+            placed_in_service: Date it was first ready to rent (YYYY-MM-DD)
+            **fields: land_value, rents, expenses (for the year), owner, and
+                date_sold, sale_price, selling_expenses once sold
+
+        Returns:
+            The new rental property record
+        """
+        if not address.strip():
+            raise ValueError("Address is required")
+        tax_return = self._require_return(return_id)
+        fields = {"address": address.strip(), "building_basis": building_basis, "placed_in_service": placed_in_service, **fields}
+        self._check_rental_property_fields(tax_return, fields)
+
+        rental = {"id": self._new_id("rent"), **fields}
+        tax_return.setdefault("rental_properties", []).append(rental)
+        self.save_return(tax_return)
+        return rental
+
+    def update_rental_property(self, return_id: str, property_id: str, **changes: Any) -> Dict[str, Any]:
+        """
+        Change a rental property's details, year's figures, or sale
+
+        Raises:
+            KeyError: If the property doesn't exist
+        """
+        tax_return = self._require_return(return_id)
+        for rental in tax_return.get("rental_properties", []):
+            if rental["id"] == property_id:
+                changes = {k: v for k, v in changes.items() if k != "id"}
+                self._check_rental_property_fields(tax_return, {**rental, **changes})
+                rental.update(changes)
+                self.save_return(tax_return)
+                return rental
+        raise KeyError(f"Rental property not found: {property_id}")
+
+    def record_like_kind_exchange(
+        self,
+        return_id: str,
+        property_id: str,
+        exchange_date: str,
+        address: str,
+        replacement_value: float,
+        replacement_date: Optional[str] = None,
+        replacement_land_value: float = 0,
+        boot_received: float = 0,
+        exchange_expenses: float = 0,
+    ) -> Dict[str, Any]:
+        """
+        Exchange a rental for another under section 1031
+
+        The relinquished property is marked sold with the exchange, and the
+        replacement is added as a new rental whose basis carries the deferred
+        gain (see tax_engine.rental_depreciation.replacement_property).
+
+        Args:
+            return_id: Return identifier
+            property_id: Rental given up
+            exchange_date: Date it was transferred (YYYY-MM-DD), in the return's year
+            address: Replacement property address
+            replacement_value: Fair market value of the replacement
+            replacement_date: Date the replacement was received (defaults to exchange_date)
+            replacement_land_value: Part of the replacement's value that is land
+            boot_received: Cash or other property received besides the replacement
+            exchange_expenses: Intermediary, commission, and closing costs
+
+        Returns:
+            Dict with relinquished, replacement, and exchange figures
+
+        Raises:
+            KeyError: If the property doesn't exist
+            ValueError: If it was already sold or the exchange misses the 180-day deadline
+        """
+        if min(replacement_value, replacement_land_value, boot_received, exchange_expenses) < 0:
+            raise ValueError("Exchange amounts cannot be negative")
+        if not address.strip():
+            raise ValueError("Replacement address is required")
+        tax_return = self._require_return(return_id)
+        relinquished = next((r for r in tax_return.get("rental_properties", []) if r["id"] == property_id), None)
+        if relinquished is None:
+            raise KeyError(f"Rental property not found: {property_id}")
+        if relinquished.get("date_sold"):
+            raise ValueError("The property was already sold")
+        try:
+            transferred = date.fromisoformat(exchange_date)
+            received = date.fromisoformat(replacement_date or exchange_date)
+        except ValueError:
+            raise ValueError("Exchange dates must be YYYY-MM-DD")
+        if not 0 <= (received - transferred).days <= self.EXCHANGE_DEADLINE_DAYS:
+            raise ValueError(
+                f"The replacement must be received within {self.EXCHANGE_DEADLINE_DAYS} days of the transfer"
+            )
+
+        replacement_id = self._new_id("rent")
+        sold = {
+            **relinquished,
+            "date_sold": exchange_date,
+            "sale_price": replacement_value + boot_received,
+            "selling_expenses": exchange_expenses,
+            "like_kind_exchange": {"replacement_id": replacement_id, "boot_received": boot_received},
+        }
+        self._check_rental_property_fields(tax_return, sold)
+        basis = replacement_property(
+            sold, tax_return["tax_year"], Decimal(str(replacement_value)), Decimal(str(replacement_land_value))
+        )
+        replacement = {
+            "id": replacement_id,
+            "address": address.strip(),
+            "building_basis": basis["building_basis"],
+            "land_value": basis["land_value"],
+            "placed_in_service": received.isoformat(),
+            "prior_depreciation": basis["prior_depreciation"],
+            "acquired_by_exchange": {
+                "relinquished_id": property_id,
+                "exchange_date": exchange_date,
+                "deferred_gain": basis["deferred_gain"],
+            },
+        }
+        if relinquished.get("owner"):
+            replacement["owner"] = relinquished["owner"]
+
+        relinquished.update(sold)
+        tax_return["rental_properties"].append(replacement)
+        self.save_return(tax_return)
+        return {"relinquished": relinquished, "replacement": replacement, "exchange": basis}
+
+    def delete_rental_property(self, return_id: str, property_id: str) -> None:
+        """
+        Remove a rental property
+
+        Raises:
+            KeyError: If the property doesn't exist
+        """
+        tax_return = self._require_return(return_id)
+        remaining = [r for r in tax_return.get("rental_properties", []) if r["id"] != property_id]
+        if len(remaining) == len(tax_return.get("rental_properties", [])):
+            raise KeyError(f"Rental property not found: {property_id}")
+        tax_return["rental_properties"] = remaining
+        self.save_return(tax_return)
+
+    def _check_rental_property_fields(self, tax_return: Dict[str, Any], fields: Dict[str, Any]) -> None:
+        """Validate a rental property's amounts and dates"""
+        for name in ("building_basis", "land_value", "rents", "expenses", "sale_price", "selling_expenses"):
+            if fields.get(name) is not None and fields[name] < 0:
+                raise ValueError(f"{name.replace('_', ' ').capitalize()} cannot be negative")
+        try:
+            in_service = date.fromisoformat(fields["placed_in_service"])
+            sold = date.fromisoformat(fields["date_sold"]) if fields.get("date_sold") else None
+        except ValueError:
+            raise ValueError("Rental dates must be YYYY-MM-DD")
+        # A replacement received early next year still belongs with the exchange
+        if in_service.year > tax_return["tax_year"] and not fields.get("acquired_by_exchange"):
+            raise ValueError(f"The property must be placed in service by {tax_return['tax_year']}")
+        if sold is not None:
+            if sold.year != tax_return["tax_year"]:
+                raise ValueError(f"Sale date must fall in {tax_return['tax_year']}")
+            if sold < in_service:
+                raise ValueError("Sale date cannot be before the in-service date")
+            if fields.get("sale_price") is None:
+                raise ValueError("A sold property needs a sale price")
+        self._check_owner(fields)
@@ -0,0 +1,183 @@
+"""
+State Return Records
+State returns filed with a stored return and the travel log that decides residency
+"""
+import re
+from typing import Dict, List, Any, Optional
+from datetime import date, datetime
+
+
+class StateReturnRecords:
+    """A return's state returns and travel days (mixed into ReturnStore)"""
+
+    STATE_RESIDENCY_TYPES = ["resident", "part_year", "nonresident"]
+
+    def add_state_return(
+        self,
+        return_id: str,
+        state: str,
+        residency: str = "resident",
+        **fields: Any,
+    ) -> Dict[str, Any]:
+        """
+        Attach a state return to a federal return
+
+        Args:
+            return_id: Return identifier
+            state: Two-letter state code
+            residency: resident, part_year, or nonresident
+            **fields: allocated_income, withholding (derived from income sources when unset)
+
+        Returns:
+            The new state return record
+        """
+        state = state.upper()
+        if not re.fullmatch(r"[A-Z]{2}", state):
+            raise ValueError(f"Invalid state code: {state}")
+        self._check_state_return_fields(residency=residency, **fields)
+
+        tax_return = self._require_return(return_id)
+        state_returns = tax_return.setdefault("state_returns", [])
+        if any(r["state"] == state for r in state_returns):
+            raise ValueError(f"Return already has a {state} state return")
+        if residency == "resident" and any(r["residency"] == "resident" for r in state_returns):
+            raise ValueError("Return already has a resident state; use part_year for a move")
+
+        record = {"id": self._new_id("st"), "state": state, "residency": residency, **fields}
+        state_returns.append(record)
+        self.save_return(tax_return)
+        return record
+
+    def update_state_return(self, return_id: str, state_return_id: str, **changes: Any) -> Dict[str, Any]:
+        """
+        Change fields of a state return
+
+        Args:
+            return_id: Return identifier
+            state_return_id: State return identifier
+            **changes: residency, allocated_income, withholding
+
+        Returns:
+            The updated state return record
+        """
+        self._check_state_return_fields(**changes)
+        tax_return = self._require_return(return_id)
+        for record in tax_return.get("state_returns", []):
+            if record["id"] == state_return_id:
+                record.update({k: v for k, v in changes.items() if k not in ("id", "state")})
+                self.save_return(tax_return)
+                return record
+        raise KeyError(f"State return not found: {state_return_id}")
+
+    def delete_state_return(self, return_id: str, state_return_id: str) -> None:
+        """
+        Remove a state return
+
+        Args:
+            return_id: Return identifier
+            state_return_id: State return identifier
+        """
+        tax_return = self._require_return(return_id)
+        remaining = [r for r in tax_return.get("state_returns", []) if r["id"] != state_return_id]
+        if len(remaining) == len(tax_return.get("state_returns", [])):
+            raise KeyError(f"State return not found: {state_return_id}")
+        tax_return["state_returns"] = remaining
+        self.save_return(tax_return)
+
+    def save_state_calculations(self, return_id: str, results: List[Dict[str, Any]]) -> List[Dict[str, Any]]:
+        """
+        Store computed tax and balance on each state return
+
+        Args:
+            return_id: Return identifier
+            results: StateTaxCalculator.calculate() output
+
+        Returns:
+            The updated state return records
+        """
+        tax_return = self._require_return(return_id)
+        by_id = {result["id"]: result for result in results}
+        now = datetime.utcnow().isoformat()
+        for record in tax_return.get("state_returns", []):
+            if record["id"] in by_id:
+                result = by_id[record["id"]]
+                record["computed_tax"] = result["computed_tax"]
+                record["balance"] = result["balance"]
+                record["calculation"] = result
+                record["calculated_at"] = now
+        self.save_return(tax_return)
+        return tax_return["state_returns"]
+
+    def add_travel_days(
+        self,
+        return_id: str,
+        state: str,
+        start_date: str,
+        end_date: Optional[str] = None,
+        note: str = "",
+    ) -> Dict[str, Any]:
+        """
+        Log days spent in a state, for counting residency days
+
+        Args:
+            return_id: Return identifier
+            state: Two-letter state code
+            start_date: First day there (YYYY-MM-DD)
+            end_date: Last day there (defaults to start_date)
+            note: Why (client visit, vacation, ...)
+
+        Returns:
+            The new travel log entry
+
+        Raises:
+            ValueError: If the state or dates are invalid or outside the return's year
+        """
+        state = state.upper()
+        if not re.fullmatch(r"[A-Z]{2}", state):
+            raise ValueError(f"Invalid state code: {state}")
+        tax_return = self._require_return(return_id)
+        try:
+            start, end = date.fromisoformat(start_date), date.fromisoformat(end_date or start_date)
+        except ValueError:
+            raise ValueError("Travel dates must be YYYY-MM-DD")
+        if end < start:
+            raise ValueError("End date cannot be before the start date")
+        if start.year != tax_return["tax_year"] or end.year != tax_return["tax_year"]:
+            raise ValueError(f"Travel days must fall in {tax_return['tax_year']}")
+
+        entry = {
+            "id": self._new_id("trv"),
+            "state": state,
+            "start_date": start.isoformat(),
+            "end_date": end.isoformat(),
+            "note": note,
+        }
+        tax_return.setdefault("travel_log", []).append(entry)
+        tax_return["travel_log"].sort(key=lambda e: (e["start_date"], e["end_date"]))
+        self.save_return(tax_return)
+        return entry
+
+    def delete_travel_days(self, return_id: str, entry_id: str) -> None:
+        """
+        Remove a travel log entry
+
+        Raises:
+            KeyError: If the entry doesn't exist
+        """
+        tax_return = self._require_return(return_id)
+        remaining = [e for e in tax_return.get("travel_log", []) if e["id"] != entry_id]
+        if len(remaining) == len(tax_return.get("travel_log", [])):
+            raise KeyError(f"Travel log entry not found: {entry_id}")
+        tax_return["travel_log"] = remaining
+        self.save_return(tax_return)
+
+    def _check_state_return_fields(self, **fields: Any) -> None:
+        """Validate residency and amounts of a state return"""
+        if "residency" in fields and fields["residency"] not in self.STATE_RESIDENCY_TYPES:
+            raise ValueError(
+                f"Invalid residency: {fields['residency']}. "
+                f"Must be one of: {', '.join(self.STATE_RESIDENCY_TYPES)}"
+            )
+        for name in ("allocated_income", "withholding"):
+            if fields.get(name) is not None and fields[name] < 0:
+                raise ValueError(f"{name.replace('_', ' ').capitalize()} cannot be negative")
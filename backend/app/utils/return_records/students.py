@@ -0,0 +1,194 @@
+"""
+Student Records
+Students on a stored return and their qualified education expenses
+"""
+from typing import Dict, Any, Optional
+
+from app.tax_engine.education_credits import EDUCATION_EXPENSE_KINDS
+
+
+class StudentRecords:
+    """A return's students, education expenses, and 1098-T imports (mixed into ReturnStore)"""
+
+    EDUCATION_EXPENSE_KINDS = EDUCATION_EXPENSE_KINDS
+
+    def add_student(self, return_id: str, name: str, **fields: Any) -> Dict[str, Any]:
+        """
+        Add a student whose education expenses are tracked for the education credits
+
+        Args:
+            return_id: Return identifier
+            name: Student's name (the taxpayer, spouse, or a dependent)
+            **fields: Extra fields (dependent_id, institution, half_time,
+                graduate, aotc_years_claimed, drug_felony)
+
+        Returns:
+            The new student record
+        """
+        if not name.strip():
+            raise ValueError("Student name is required")
+        self._check_student_fields(**fields)
+
+        tax_return = self._require_return(return_id)
+        student = {"id": self._new_id("stu"), "name": name.strip(), **fields, "expenses": []}
+        tax_return.setdefault("students", []).append(student)
+        self.save_return(tax_return)
+        return student
+
+    def update_student(self, return_id: str, student_id: str, **changes: Any) -> Dict[str, Any]:
+        """
+        Change a student's details
+
+        Raises:
+            KeyError: If the student doesn't exist
+        """
+        self._check_student_fields(**changes)
+        tax_return = self._require_return(return_id)
+        student = self._find_student(tax_return, student_id)
+        student.update({k: v for k, v in changes.items() if k not in ("id", "expenses")})
+        self.save_return(tax_return)
+        return student
+
+    def delete_student(self, return_id: str, student_id: str) -> None:
+        """
+        Remove a student and their expenses
+
+        Raises:
+            KeyError: If the student doesn't exist
+        """
+        tax_return = self._require_return(return_id)
+        remaining = [s for s in tax_return.get("students", []) if s["id"] != student_id]
+        if len(remaining) == len(tax_return.get("students", [])):
+            raise KeyError(f"Student not found: {student_id}")
+        tax_return["students"] = remaining
+        self.save_return(tax_return)
+
+    def add_education_expense(
+        self,
+        return_id: str,
+        student_id: str,
+        kind: str,
+        amount: float,
+        description: str = "",
+        **fields: Any,
+    ) -> Dict[str, Any]:
+        """
+        Record a student's education expense, or a scholarship or refund that reduces them
+
+        Args:
+            return_id: Return identifier
+            student_id: Student identifier
+            kind: One of EDUCATION_EXPENSE_KINDS
+            amount: Amount paid (or received, for scholarships and refunds)
+            description: What it was for
+            **fields: Extra fields (date, paid_to_institution, document_id, ...)
+
+        Returns:
+            The new expense record
+
+        Raises:
+            KeyError: If the student doesn't exist
+        """
+        if kind not in self.EDUCATION_EXPENSE_KINDS:
+            raise ValueError(
+                f"Invalid education expense kind: {kind}. Must be one of: {', '.join(self.EDUCATION_EXPENSE_KINDS)}"
+            )
+        if amount < 0:
+            raise ValueError("Amount cannot be negative")
+
+        tax_return = self._require_return(return_id)
+        student = self._find_student(tax_return, student_id)
+        expense = {"id": self._new_id("edu"), "kind": kind, "amount": amount, "description": description, **fields}
+        student["expenses"].append(expense)
+        self.save_return(tax_return)
+        return expense
+
+    def delete_education_expense(self, return_id: str, student_id: str, expense_id: str) -> None:
+        """
+        Remove a student's education expense
+
+        Raises:
+            KeyError: If the student or expense doesn't exist
+        """
+        tax_return = self._require_return(return_id)
+        student = self._find_student(tax_return, student_id)
+        remaining = [e for e in student["expenses"] if e["id"] != expense_id]
+        if len(remaining) == len(student["expenses"]):
+            raise KeyError(f"Education expense not found: {expense_id}")
+        student["expenses"] = remaining
+        self.save_return(tax_return)
+
+    def import_1098t(
+        self,
+        return_id: str,
+        student_id: str,
+        extraction: Dict[str, Any],
+        document_id: Optional[str] = None,
+    ) -> Dict[str, Any]:
+        """
+        Add a parsed Form 1098-T to a student's education expenses
+
+        Box 1 becomes tuition, box 5 a scholarship, and box 10 a refund, and
+        a checked box 8 or 9 marks the student half-time or graduate. The
+        expenses are linked to the document, so importing the same document
+        again replaces them instead of adding them twice.
+
+        Args:
+            return_id: Return identifier
+            student_id: Student identifier
+            extraction: ParseResult.to_dict() output of the 1098-T parser
+            document_id: Stored document the statement came from
+
+        Returns:
+            The updated student record
+
+        Raises:
+            KeyError: If the student doesn't exist
+            ValueError: If the extraction isn't a 1098-T
+        """
+        if extraction.get("form") != "1098-T":
+            raise ValueError(f"Expected a 1098-T extraction, got {extraction.get('form')}")
+        values = {name: f.get("value") for name, f in extraction.get("fields", {}).items()}
+        source = document_id or self._new_id("1098t")
+        filer = values.get("filer_name") or "1098-T"
+
+        tax_return = self._require_return(return_id)
+        student = self._find_student(tax_return, student_id)
+        student["expenses"] = [e for e in student["expenses"] if e.get("document_id") != source]
+        for kind, field, description in (
+            ("tuition", "payments_received", f"{filer} tuition (1098-T box 1)"),
+            ("scholarship", "scholarships_grants", f"{filer} scholarships or grants (1098-T box 5)"),
+            ("refund", "reimbursements_refunds", f"{filer} reimbursements or refunds (1098-T box 10)"),
+        ):
+            if values.get(field):
+                student["expenses"].append({
+                    "id": self._new_id("edu"),
+                    "kind": kind,
+                    "amount": values[field],
+                    "description": description,
+                    "paid_to_institution": True,
+                    "document_id": source,
+                })
+        # An unmarked box is weak evidence (OCR drops check marks), so only checked boxes change the student
+        if values.get("half_time_student"):
+            student["half_time"] = True
+        if values.get("graduate_student"):
+            student["graduate"] = True
+        if values.get("filer_name"):
+            student.setdefault("institution", values["filer_name"])
+        self.save_return(tax_return)
+        return student
+
+    def _find_student(self, tax_return: Dict[str, Any], student_id: str) -> Dict[str, Any]:
+        for student in tax_return.get("students", []):
+            if student["id"] == student_id:
+                return student
+        raise KeyError(f"Student not found: {student_id}")
+
+    def _check_student_fields(self, **fields: Any) -> None:
+        """Validate a student's name and years of American opportunity credit"""
+        if "name" in fields and not str(fields["name"]).strip():
+            raise ValueError("Student name is required")
+        years = fields.get("aotc_years_claimed")
+        if years is not None and not 0 <= years <= 4:
+            raise ValueError("American opportunity years claimed must be between 0 and 4")
@@ -0,0 +1,627 @@
+"""
+Tax Return Storage
+Persistent storage for tax returns using file-based system
+
+Each record type kept inside a return (dependents, businesses, rentals, ...)
+is added and changed through its mixin in app.utils.return_records.
+"""
+import copy
+import json
+import os
+import re
+from typing import Dict, List, Any, Optional
+from datetime import datetime
+from pathlib import Path
+
+from app.tax_engine.decedent import next_year_filing_status
+from app.tax_engine.education_credits import aotc_ineligibility, qualified_expenses
+from app.tax_engine.state_refund import prior_year_deductions
+from app.utils.field_encryption import FieldCipher
+from app.utils.return_records.adoptions import AdoptionRecords
+from app.utils.return_records.alimony import AlimonyRecords
+from app.utils.return_records.businesses import BusinessRecords
+from app.utils.return_records.carryforwards import CarryforwardRecords
+from app.utils.return_records.checklist import ChecklistRecords
+from app.utils.return_records.crypto import CryptoRecords
+from app.utils.return_records.death import DeathRecords
+from app.utils.return_records.dependents import DependentRecords
+from app.utils.return_records.filing import FilingRecords
+from app.utils.return_records.foreign_accounts import ForeignAccountRecords
+from app.utils.return_records.holdings import HoldingRecords
+from app.utils.return_records.income import IncomeRecords
+from app.utils.return_records.rentals import RentalRecords
+from app.utils.return_records.state_returns import StateReturnRecords
+from app.utils.return_records.students import StudentRecords
+
+
+class ReturnLockedError(ValueError):
+    """Raised when changing a return whose status doesn't allow edits"""
+
+    def __init__(self, return_id: str, status: str):
+        self.return_id = return_id
+        self.status = status
+        super().__init__(f"Return {return_id} is {status} and can't be changed; amend it instead")
+
+
+class ReturnStore(
+    IncomeRecords, HoldingRecords, BusinessRecords, RentalRecords, CryptoRecords, DependentRecords, StudentRecords,
+    AdoptionRecords, AlimonyRecords, DeathRecords, CarryforwardRecords, StateReturnRecords, ForeignAccountRecords,
+    FilingRecords, ChecklistRecords,
+):
+    """File-based tax return storage"""
+
+    # Whose an income source, deduction, or capital transaction is on a joint return
+    RECORD_OWNERS = ["taxpayer", "spouse", "joint"]
+
+    # Return status -> statuses it can move to
+    STATUS_TRANSITIONS = {
+        "draft": ["in_progress"],
+        "in_progress": ["draft", "review"],
+        "review": ["in_progress", "filed"],
+        "filed": ["amended"],
+        "amended": ["review"],
+    }
+
+    # Statuses whose returns are read-only
+    LOCKED_STATUSES = ["filed"]
+
+    # Filed figures kept when a return is amended
+    AMENDMENT_SNAPSHOT_FIELDS = [
+        "filing_status", "dependents", "income_sources", "deductions",
+        "capital_transactions", "businesses", "rental_properties", "students", "alimony", "adoptions",
+        "carryforwards",
+    ]
+
+    # Deductions copied into next year's return when cloning (others can be flagged recurring)
+    RECURRING_DEDUCTION_CATEGORIES = ["mortgage_interest", "state_local_tax"]
+
+    # Identifying fields kept when cloning; amounts start over each year
+    CLONED_INCOME_FIELDS = [
+        "type", "description", "form", "employer_ein", "payer_tin", "state", "owner", "business_id",
+        "tax_exempt", "foreign_account", "bond_state", "plan_type",
+    ]
+    CLONED_DEDUCTION_FIELDS = ["category", "description", "tax_type", "non_cash", "recurring"]
+    CLONED_BUSINESS_FIELDS = ["id", "name", "owner", "description", "principal_business_code", "ein"]
+    # Vehicles keep their basis and method history so later years follow the switching rules
+    CLONED_VEHICLE_FIELDS = ["id", "description", "placed_in_service", "cost", "leased", "heavy_vehicle", "method_history"]
+    # Rentals keep their basis and in-service date so depreciation continues
+    CLONED_RENTAL_FIELDS = [
+        "id", "address", "building_basis", "land_value", "placed_in_service", "owner",
+        "prior_depreciation", "acquired_by_exchange",
+    ]
+    # Student fields that carry into next year's return
+    CLONED_STUDENT_FIELDS = ["id", "name", "dependent_id", "institution", "half_time", "graduate", "drug_felony"]
+
+    def __init__(self, storage_dir: str = ".tax_returns", cipher: Optional[FieldCipher] = None):
+        """
+        Initialize return store
+
+        Args:
+            storage_dir: Directory to store return files
+            cipher: Encryption for bank details (defaults to a key file in storage_dir)
+        """
+        self.storage_dir = Path(storage_dir)
+        self.storage_dir.mkdir(exist_ok=True)
+        self.cipher = cipher or FieldCipher(key_path=str(self.storage_dir / ".field_encryption.key"))
+
+    def _get_return_file(self, return_id: str) -> Path:
+        """Get file path for a return"""
+        # Return IDs are generated here; reject anything else to prevent path traversal
+        if not re.fullmatch(r"ret_[0-9a-f]{16}", return_id):
+            raise ValueError(f"Invalid return ID: {return_id}")
+        return self.storage_dir / f"{return_id}.json"
+
+    @staticmethod
+    def _new_id(prefix: str) -> str:
+        """Generate a record ID"""
+        return f"{prefix}_{os.urandom(8).hex()}"
+
+    def create_return(
+        self,
+        tax_year: int = 2024,
+        filing_status: str = "single",
+        taxpayer: Optional[Dict[str, Any]] = None,
+        client_id: Optional[str] = None,
+    ) -> Dict[str, Any]:
+        """
+        Create a new draft return
+
+        Args:
+            tax_year: Tax year of the return
+            filing_status: Initial filing status
+            taxpayer: Optional taxpayer info (name, address, ...)
+            client_id: Practitioner client the return is prepared for
+
+        Returns:
+            The new return dict
+        """
+        now = datetime.utcnow().isoformat()
+        tax_return = {
+            "return_id": self._new_id("ret"),
+            "tax_year": tax_year,
+            "filing_status": filing_status,
+            "status": "draft",
+            "taxpayer": taxpayer or {},
+            "dependents": [],
+            "income_sources": [],
+            "deductions": [],
+            "capital_transactions": [],
+            "businesses": [],
+            "carryforwards": {},
+            "state_returns": [],
+            "notes": "",
+            "created_at": now,
+            "updated_at": now,
+        }
+        if client_id:
+            tax_return["client_id"] = client_id
+        return self.save_return(tax_return)
+
+    def get_return(self, return_id: str) -> Optional[Dict[str, Any]]:
+        """
+        Retrieve a return
+
+        Args:
+            return_id: Return identifier
+
+        Returns:
+            Return dict or None if not found
+        """
+        try:
+            file_path = self._get_return_file(return_id)
+        except ValueError:
+            return None
+
+        if not file_path.exists():
+            return None
+
+        with open(file_path, 'r', encoding='utf-8') as f:
+            return json.load(f)
+
+    def save_return(self, tax_return: Dict[str, Any]) -> Dict[str, Any]:
+        """
+        Persist a return, stamping updated_at
+
+        Args:
+            tax_return: Full return dict (must contain return_id)
+
+        Returns:
+            The saved return dict
+
+        Raises:
+            ReturnLockedError: If the stored return is filed
+        """
+        stored = self.get_return(tax_return["return_id"])
+        if stored is not None and stored.get("status") in self.LOCKED_STATUSES:
+            raise ReturnLockedError(tax_return["return_id"], stored["status"])
+        return self._write(tax_return)
+
+    def _write(self, tax_return: Dict[str, Any]) -> Dict[str, Any]:
+        """Write a return to disk, stamping updated_at"""
+        file_path = self._get_return_file(tax_return["return_id"])
+        tax_return["updated_at"] = datetime.utcnow().isoformat()
+
+        with open(file_path, 'w', encoding='utf-8') as f:
+            json.dump(tax_return, f, indent=2, ensure_ascii=False)
+        return tax_return
+
+    def import_return(
+        self,
+        tax_return: Dict[str, Any],
+        direct_deposit: Optional[Dict[str, str]] = None,
+        ip_pins: Optional[Dict[str, Dict[str, Any]]] = None,
+    ) -> Dict[str, Any]:
+        """
+        Store a return exported from another install, keeping its ID and timestamps
+
+        Args:
+            tax_return: Full return dict
+            direct_deposit: Plain-text bank details to encrypt with this install's key
+            ip_pins: Plain-text IP PINs (person -> pin, valid_year) to encrypt with this install's key
+                (alimony recipient SSNs in tax_return are plain text too and are encrypted the same way)
+
+        Returns:
+            The stored return
+
+        Raises:
+            ValueError: If the return ID is invalid
+            ReturnLockedError: If it would replace a filed return
+        """
+        tax_return = dict(tax_return)
+        self._get_return_file(tax_return["return_id"])
+        stored = self.get_return(tax_return["return_id"])
+        if stored is not None and stored.get("status") in self.LOCKED_STATUSES:
+            raise ReturnLockedError(tax_return["return_id"], stored["status"])
+
+        tax_return.pop("direct_deposit", None)
+        if direct_deposit:
+            tax_return["direct_deposit"] = {
+                "routing_number": self.cipher.encrypt(direct_deposit["routing_number"]),
+                "account_number": self.cipher.encrypt(direct_deposit["account_number"]),
+                "account_type": direct_deposit.get("account_type", "checking"),
+                "updated_at": direct_deposit.get("updated_at") or datetime.utcnow().isoformat(),
+            }
+        if tax_return.get("alimony"):
+            tax_return["alimony"] = [
+                dict(a, recipient_ssn=self.cipher.encrypt(a["recipient_ssn"])) if a.get("recipient_ssn") else a
+                for a in tax_return["alimony"]
+            ]
+        tax_return.pop("ip_pins", None)
+        if ip_pins:
+            tax_return["ip_pins"] = {
+                person: {
+                    "pin": self.cipher.encrypt(details["pin"]),
+                    "valid_year": details["valid_year"],
+                    "updated_at": details.get("updated_at") or datetime.utcnow().isoformat(),
+                }
+                for person, details in ip_pins.items()
+            }
+        with open(self._get_return_file(tax_return["return_id"]), 'w', encoding='utf-8') as f:
+            json.dump(tax_return, f, indent=2, ensure_ascii=False)
+        return tax_return
+
+    def assign_client(self, return_id: str, client_id: Optional[str]) -> Dict[str, Any]:
+        """
+        Link a return to a practitioner client, or unlink it with None
+
+        Allowed on filed returns too, since it doesn't change the return's figures.
+
+        Args:
+            return_id: Return identifier
+            client_id: Client identifier
+
+        Returns:
+            The updated return
+
+        Raises:
+            KeyError: If the return doesn't exist
+        """
+        tax_return = self._require_return(return_id)
+        if client_id:
+            tax_return["client_id"] = client_id
+        else:
+            tax_return.pop("client_id", None)
+        return self._write(tax_return)
+
+    def delete_return(self, return_id: str) -> bool:
+        """
+        Delete a return
+
+        Args:
+            return_id: Return identifier
+
+        Returns:
+            True if deleted, False if not found
+        """
+        try:
+            file_path = self._get_return_file(return_id)
+        except ValueError:
+            return False
+
+        if file_path.exists():
+            file_path.unlink()
+            return True
+        return False
+
+    def list_returns(self, client_id: Optional[str] = None) -> List[Dict[str, Any]]:
+        """
+        List all returns
+
+        Args:
+            client_id: Only returns prepared for this practitioner client
+
+        Returns:
+            List of return summary dicts
+        """
+        returns = []
+
+        for file_path in self.storage_dir.glob("ret_*.json"):
+            try:
+                with open(file_path, 'r', encoding='utf-8') as f:
+                    data = json.load(f)
+                if client_id is not None and data.get("client_id") != client_id:
+                    continue
+                returns.append({
+                    "return_id": data["return_id"],
+                    "tax_year": data.get("tax_year"),
+                    "filing_status": data.get("filing_status"),
+                    "status": data.get("status", "draft"),
+                    "client_id": data.get("client_id"),
+                    "updated_at": data.get("updated_at", "unknown"),
+                })
+            except (json.JSONDecodeError, IOError, KeyError):
+                continue
+
+        # Sort by updated_at descending
+        returns.sort(key=lambda x: x.get("updated_at", ""), reverse=True)
+        return returns
+
+    def set_status(self, return_id: str, status: str) -> Dict[str, Any]:
+        """
+        Move a return through the draft -> in_progress -> review -> filed workflow
+
+        Filed returns are read-only. Moving one to amended unlocks it and keeps
+        a copy of the filed figures under amendment.original for the 1040-X.
+
+        Args:
+            return_id: Return identifier
+            status: New status (see STATUS_TRANSITIONS)
+
+        Returns:
+            The updated return
+
+        Raises:
+            KeyError: If the return doesn't exist
+            ValueError: If the status is unknown or not reachable from the current one
+        """
+        if status not in self.STATUS_TRANSITIONS:
+            raise ValueError(f"Invalid status: {status}. Must be one of: {', '.join(self.STATUS_TRANSITIONS)}")
+
+        tax_return = self._require_return(return_id)
+        current = tax_return.get("status", "draft")
+        if status not in self.STATUS_TRANSITIONS[current]:
+            allowed = self.STATUS_TRANSITIONS[current]
+            raise ValueError(
+                f"A {current} return can't move to {status}. Allowed: {', '.join(allowed)}"
+            )
+
+        now = datetime.utcnow().isoformat()
+        if status == "filed":
+            tax_return["filed_at"] = now
+        elif status == "amended":
+            tax_return["amendment"] = {
+                "started_at": now,
+                "original": copy.deepcopy({k: tax_return.get(k) for k in self.AMENDMENT_SNAPSHOT_FIELDS}),
+            }
+        tax_return["status"] = status
+        tax_return.setdefault("status_history", []).append({"from": current, "to": status, "at": now})
+        return self._write(tax_return)
+
+    def save_calculation(self, return_id: str, calculation: Dict[str, Any]) -> Dict[str, Any]:
+        """
+        Store the engine's results on a return
+
+        Args:
+            return_id: Return identifier
+            calculation: calculate_return() output
+
+        Returns:
+            The updated return, with calculated_tax and refund_or_owed set
+        """
+        tax_return = self._require_return(return_id)
+        tax_return["calculation"] = calculation
+        tax_return["calculated_tax"] = calculation["total_tax"]
+        tax_return["refund_or_owed"] = calculation["refund_or_owed"]
+        tax_return["calculated_at"] = datetime.utcnow().isoformat()
+        return self.save_return(tax_return)
+
+    def clone_return(
+        self,
+        return_id: str,
+        tax_year: int,
+        carryforwards: Optional[Dict[str, float]] = None,
+        at_risk: Optional[List[Dict[str, Any]]] = None,
+    ) -> Dict[str, Any]:
+        """
+        Start next year's return from a prior-year one
+
+        Copies taxpayer info, dependents, the income sources and businesses
+        (without amounts; each keeps prior_year_amount), business vehicles with
+        their method history, recurring deductions, which states the
+        taxpayer files in, rental properties not yet sold, crypto lots not yet
+        sold (with their basis), investment holdings, students (without
+        expenses, counting this year toward the American opportunity credit's
+        four years when it applied), alimony (without amounts), and the
+        estimated tax payments logged for the new year. A married
+        return whose divorce was final before the new year starts as single.
+        After a death, later returns are the surviving spouse's, filed as a
+        qualifying surviving spouse for two years when there's a dependent
+        child and as single otherwise. When the prior year was calculated and
+        saved, its deduction figures are kept for the state refund worksheet.
+        Capital transactions, documents, and the checklist aren't copied.
+
+        Args:
+            return_id: Prior-year return
+            tax_year: Year of the new return (after the prior year)
+            carryforwards: Carryforwards into the new year
+            at_risk: This year's at-risk results (calculate_at_risk); each
+                activity starts next year at its ending at-risk amount with
+                its disallowed loss carried forward
+
+        Returns:
+            The new draft return
+        """
+        source = self._require_return(return_id)
+        if tax_year <= source["tax_year"]:
+            raise ValueError(f"New tax year must be after {source['tax_year']}")
+
+        filing_status = source["filing_status"]
+        decree_date = (source.get("divorce") or {}).get("decree_date")
+        if filing_status in ("married_joint", "married_separate") and decree_date and decree_date < f"{tax_year}-01-01":
+            filing_status = "single"
+        taxpayer = copy.deepcopy(source.get("taxpayer") or {})
+        death = copy.deepcopy(source.get("death"))
+        if death and death["deceased"] == "taxpayer":
+            if not taxpayer.get("spouse_name"):
+                raise ValueError(f"The taxpayer died on {death['date_of_death']}; that year's return is the final one")
+            # Later years are the surviving spouse's returns; the spouse fields keep the deceased
+            taxpayer.update({
+                "name": taxpayer["spouse_name"], "ssn": taxpayer.get("spouse_ssn", ""),
+                "spouse_name": taxpayer.get("name", ""), "spouse_ssn": taxpayer.get("ssn", ""),
+            })
+            death["deceased"] = "spouse"
+        filing_status = next_year_filing_status(source, tax_year) or filing_status
+        tax_return = self.create_return(
+            tax_year=tax_year,
+            filing_status=filing_status,
+            taxpayer=taxpayer,
+        )
+        if death:
+            tax_return["death"] = {**death, "personal_representative": None}
+        tax_return["cloned_from"] = return_id
+        tax_return["carryforwards"] = dict(carryforwards or {})
+        if tax_year == source["tax_year"] + 1 and source.get("calculation"):
+            tax_return["prior_year_deductions"] = prior_year_deductions(source)
+        tax_return["dependents"] = [
+            {**copy.deepcopy(dependent), "id": self._new_id("dep")} for dependent in source.get("dependents", [])
+        ]
+        tax_return["income_sources"] = [
+            {
+                **{k: s[k] for k in self.CLONED_INCOME_FIELDS if k in s},
+                "id": self._new_id("inc"),
+                "amount": 0,
+                "withholding": 0,
+                "prior_year_amount": s.get("amount", 0),
+            }
+            for s in source.get("income_sources", [])
+        ]
+        tax_return["deductions"] = [
+            {
+                **{k: d[k] for k in self.CLONED_DEDUCTION_FIELDS if k in d},
+                "id": self._new_id("ded"),
+                "amount": 0,
+                "prior_year_amount": d.get("amount", 0),
+            }
+            for d in source.get("deductions", [])
+            if d["category"] in self.RECURRING_DEDUCTION_CATEGORIES or d.get("recurring")
+        ]
+        tax_return["state_returns"] = [
+            {"id": self._new_id("st"), "state": r["state"], "residency": r["residency"]}
+            for r in source.get("state_returns", [])
+        ]
+        # Business IDs are kept so income sources stay linked to their business
+        tax_return["businesses"] = [
+            {**{k: b[k] for k in self.CLONED_BUSINESS_FIELDS if k in b}, "expenses": []}
+            for b in source.get("businesses", [])
+        ]
+        for business, prior in zip(tax_return["businesses"], source.get("businesses", [])):
+            if prior.get("vehicles"):
+                business["vehicles"] = [
+                    {
+                        **copy.deepcopy({k: v[k] for k in self.CLONED_VEHICLE_FIELDS if k in v}),
+                        "mileage_log": [],
+                        "expenses": [],
+                    }
+                    for v in prior["vehicles"]
+                ]
+        tax_return["rental_properties"] = [
+            {**{k: r[k] for k in self.CLONED_RENTAL_FIELDS if k in r}, "rents": 0, "expenses": 0}
+            for r in source.get("rental_properties", [])
+            if not r.get("date_sold")
+        ]
+        for activity in tax_return["businesses"] + tax_return["rental_properties"]:
+            result = next((a for a in at_risk or [] if a["activity_id"] == activity["id"]), None)
+            if result is None:
+                continue
+            if result["ending_at_risk"] is not None:
+                activity["at_risk_amount"] = result["ending_at_risk"]
+            if result["carryforward"]:
+                activity["at_risk_carryforward"] = result["carryforward"]
+        tax_return["holdings"] = copy.deepcopy(source.get("holdings", []))
+        tax_return["students"] = []
+        for student in source.get("students", []):
+            years = int(student.get("aotc_years_claimed") or 0)
+            if not aotc_ineligibility(student) and qualified_expenses(student)["aotc"]:
+                years += 1
+            tax_return["students"].append({
+                **{k: student[k] for k in self.CLONED_STUDENT_FIELDS if k in student},
+                "aotc_years_claimed": years,
+                "expenses": [],
+            })
+        tax_return["alimony"] = [
+            {**a, "id": self._new_id("alim"), "amount": 0, "prior_year_amount": a.get("amount", 0)}
+            for a in source.get("alimony", [])
+        ]
+        tax_return["crypto_lots"] = [
+            {k: v for k, v in lot.items() if k != "income_source_id"}
+            for lot in source.get("crypto_lots", [])
+            if lot["remaining"] > 0
+        ]
+        # Estimated tax paid for the new year is credited on its return
+        tax_return["estimated_payments"] = [
+            copy.deepcopy(p) for p in source.get("estimated_payments", []) if p.get("tax_year") == tax_year
+        ]
+        return self.save_return(tax_return)
+
+    def prior_returns(self, tax_return: Dict[str, Any], limit: int = 4) -> List[Dict[str, Any]]:
+        """
+        Earlier years' returns, following the chain each return was cloned from
+
+        Args:
+            tax_return: Return dict to start from
+            limit: Most returns to follow back
+
+        Returns:
+            Prior returns, most recent first (stops at one that no longer exists)
+        """
+        prior: List[Dict[str, Any]] = []
+        seen = {tax_return["return_id"]}
+        return_id = tax_return.get("cloned_from")
+        while return_id and return_id not in seen and len(prior) < limit:
+            previous = self.get_return(return_id)
+            if previous is None:
+                break
+            prior.append(previous)
+            seen.add(return_id)
+            return_id = previous.get("cloned_from")
+        return prior
+
+    def load_prior_year_deductions(self, return_id: str, prior_return_id: Optional[str] = None) -> Dict[str, Any]:
+        """
+        Copy last year's deduction figures onto a return, for the state refund worksheet
+
+        Args:
+            return_id: Return identifier
+            prior_return_id: Last year's return (default: the one this return
+                was cloned from, following the chain back to the year before)
+
+        Returns:
+            The prior_year_deductions stored on the return
+
+        Raises:
+            KeyError: If the prior return doesn't exist or none is linked for the year before
+            ValueError: If the prior return isn't for the year before or hasn't been calculated and saved
+        """
+        tax_return = self._require_return(return_id)
+        year = tax_return["tax_year"] - 1
+        if prior_return_id is not None:
+            prior = self._require_return(prior_return_id)
+            if prior["tax_year"] != year:
+                raise ValueError(f"Return {prior_return_id} is for {prior['tax_year']}, not {year}")
+        else:
+            prior = next((r for r in self.prior_returns(tax_return) if r["tax_year"] == year), None)
+            if prior is None:
+                raise KeyError(f"No {year} return is linked to {return_id}; give the prior return's ID")
+        tax_return["prior_year_deductions"] = prior_year_deductions(prior)
+        self.save_return(tax_return)
+        return tax_return["prior_year_deductions"]
+
+    def set_notes(self, return_id: str, notes: str) -> Dict[str, Any]:
+        """
+        Replace a return's free-form preparation notes
+
+        Args:
+            return_id: Return identifier
+            notes: Notes text ("" clears them)
+
+        Returns:
+            The updated return
+        """
+        tax_return = self._require_return(return_id)
+        tax_return["notes"] = notes.strip()
+        self.save_return(tax_return)
+        return tax_return
+
+    def _check_owner(self, fields: Dict[str, Any]) -> None:
+        """Validate the owner attribution of an income source or deduction"""
+        if "owner" in fields and fields["owner"] not in self.RECORD_OWNERS:
+            raise ValueError(
+                f"Invalid owner: {fields['owner']}. Must be one of: {', '.join(self.RECORD_OWNERS)}"
+            )
+
+    def _require_return(self, return_id: str) -> Dict[str, Any]:
+        """Load a return or raise if it does not exist"""
+        tax_return = self.get_return(return_id)
+        if tax_return is None:
+            raise KeyError(f"Return not found: {return_id}")
+        return tax_return
@@ -0,0 +1,47 @@
+"""
+Sensitive Data Scrubbing
+One-way masking of identifiers and secrets in text that leaves the app (logs, bug reports)
+"""
+import re
+
+from app.agents.redaction import PIIRedactor
+
+# Pattern -> replacement, applied in order (secrets first so their digits aren't half-masked as accounts)
+SCRUB_PATTERNS = [
+    # Anthropic API keys
+    (re.compile(r"sk-ant-[A-Za-z0-9_-]+"), "[API_KEY]"),
+    # key=value / "key": "value" pairs whose value is a secret
+    (
+        re.compile(
+            r"(?i)\b(passphrase|password|secret|token|api[_-]?key|encryption[_-]?key)(['\"]?\s*[:=]\s*['\"]?)[^\s'\",}]+"
+        ),
+        r"\1\2[REDACTED]",
+    ),
+    # Fernet keys and other 32-byte urlsafe base64 secrets
+    (re.compile(r"(?<![A-Za-z0-9_-])[A-Za-z0-9_-]{43}="), "[KEY]"),
+    (PIIRedactor.SSN_PATTERN, "[SSN]"),
+    (PIIRedactor.EIN_PATTERN, "[EIN]"),
+    (PIIRedactor.ACCOUNT_PATTERN, "[ACCOUNT]"),
+    (re.compile(r"\b[\w.+-]+@[\w-]+\.[\w.-]+\b"), "[EMAIL]"),
+]
+
+
+def scrub(text: str) -> str:
+    """
+    Mask SSNs, EINs, account numbers, emails, labelled names, and secrets
+
+    Unlike PIIRedactor the masking can't be undone, so the result is safe to share.
+
+    Args:
+        text: Log line or other free text
+
+    Returns:
+        Scrubbed text
+    """
+    if not text:
+        return text
+    for pattern, replacement in SCRUB_PATTERNS:
+        text = pattern.sub(replacement, text)
+    return PIIRedactor.LABELLED_NAME_PATTERN.sub(
+        lambda m: m.group(0)[: m.start(1) - m.start(0)] + "[NAME]", text
+    )
@@ -0,0 +1,206 @@
+"""
+Secret Store
+Keeps secrets such as the Anthropic API key in the OS keyring, or an encrypted local file where there is none
+"""
+import json
+import logging
+import os
+from pathlib import Path
+from typing import Dict, Any, List, Optional, Tuple
+
+from app.utils.field_encryption import DecryptionError, FieldCipher
+
+logger = logging.getLogger(__name__)
+
+# Keyring service name the secrets are filed under
+SERVICE_NAME = "ai-tax-cpa-agent"
+
+API_KEY_NAME = "anthropic_api_key"
+# Set in the environment, the key overrides the stored one
+API_KEY_ENV_VAR = "ANTHROPIC_API_KEY"
+
+_AUTO = object()
+
+
+def system_keyring() -> Optional[Any]:
+    """
+    The OS keyring backend (Keychain, Credential Manager, Secret Service)
+
+    Returns:
+        keyring backend, or None when the keyring package isn't installed or has no usable backend
+    """
+    try:
+        import keyring
+        backend = keyring.get_keyring()
+    except Exception:
+        return None
+    return backend if getattr(backend, "priority", 0) > 0 else None
+
+
+class SecretStore:
+    """Named secrets in the OS keyring, with an encrypted file fallback"""
+
+    def __init__(self, fallback_path: str = ".secrets.json", key_path: str = ".secrets.key", keyring: Any = _AUTO):
+        """
+        Initialize store
+
+        Args:
+            fallback_path: Encrypted secrets file used when no keyring is available
+            key_path: Key file for the fallback (kept apart from the secrets)
+            keyring: Keyring backend; defaults to system_keyring(), None forces the file
+        """
+        self.fallback_path = Path(fallback_path)
+        self.cipher = FieldCipher(key_path=key_path)
+        self.keyring = system_keyring() if keyring is _AUTO else keyring
+
+    @property
+    def keyring_available(self) -> bool:
+        return self.keyring is not None
+
+    def _read_file(self) -> Dict[str, str]:
+        if not self.fallback_path.exists():
+            return {}
+        with open(self.fallback_path, 'r', encoding='utf-8') as f:
+            return json.load(f)
+
+    def _write_file(self, secrets: Dict[str, str]) -> None:
+        if not secrets:
+            self.fallback_path.unlink(missing_ok=True)
+            return
+        self.fallback_path.parent.mkdir(parents=True, exist_ok=True)
+        tmp_path = self.fallback_path.with_suffix(".tmp")
+        fd = os.open(tmp_path, os.O_WRONLY | os.O_CREAT | os.O_TRUNC, 0o600)
+        with os.fdopen(fd, "w", encoding="utf-8") as f:
+            json.dump(secrets, f, indent=2)
+        tmp_path.replace(self.fallback_path)
+
+    def get(self, name: str) -> Optional[str]:
+        """
+        Read a secret
+
+        Args:
+            name: Secret name
+
+        Returns:
+            The secret, or None if it isn't stored
+        """
+        return self.lookup(name)[0]
+
+    def lookup(self, name: str) -> Tuple[Optional[str], Optional[str]]:
+        """
+        Read a secret and where it came from
+
+        Returns:
+            (secret, "keyring" | "file"), or (None, None) if it isn't stored
+        """
+        if self.keyring is not None:
+            try:
+                value = self.keyring.get_password(SERVICE_NAME, name)
+            except Exception as e:
+                logger.warning(f"Keyring read failed for {name}: {e}")
+                value = None
+            if value:
+                return value, "keyring"
+        token = self._read_file().get(name)
+        if token:
+            try:
+                return self.cipher.decrypt(token), "file"
+            except DecryptionError as e:
+                logger.warning(f"Stored {name} can't be read: {e}")
+        return None, None
+
+    def set(self, name: str, value: str) -> str:
+        """
+        Store a secret, in the keyring when possible
+
+        Args:
+            name: Secret name
+            value: Secret
+
+        Returns:
+            Where it was stored: "keyring" or "file"
+        """
+        secrets = self._read_file()
+        if self.keyring is not None:
+            try:
+                self.keyring.set_password(SERVICE_NAME, name, value)
+            except Exception as e:
+                logger.warning(f"Keyring write failed for {name}, using the encrypted file: {e}")
+            else:
+                if secrets.pop(name, None) is not None:
+                    self._write_file(secrets)
+                return "keyring"
+        secrets[name] = self.cipher.encrypt(value)
+        self._write_file(secrets)
+        return "file"
+
+    def delete(self, name: str) -> bool:
+        """
+        Remove a secret from the keyring and the file
+
+        Returns:
+            True if it was stored anywhere
+        """
+        removed = False
+        if self.keyring is not None:
+            try:
+                if self.keyring.get_password(SERVICE_NAME, name):
+                    self.keyring.delete_password(SERVICE_NAME, name)
+                    removed = True
+            except Exception as e:
+                logger.warning(f"Keyring delete failed for {name}: {e}")
+        secrets = self._read_file()
+        if secrets.pop(name, None) is not None:
+            self._write_file(secrets)
+            removed = True
+        return removed
+
+    def migrate_to_keyring(self) -> List[str]:
+        """
+        Move secrets saved to the file fallback into the keyring
+
+        Run after unlocking, so keys stored before a keyring was available
+        end up there once it is.
+
+        Returns:
+            Names of the secrets moved
+        """
+        if self.keyring is None:
+            return []
+        secrets = self._read_file()
+        moved = []
+        for name, token in list(secrets.items()):
+            try:
+                self.keyring.set_password(SERVICE_NAME, name, self.cipher.decrypt(token))
+            except Exception as e:
+                logger.warning(f"Couldn't move {name} to the keyring: {e}")
+                continue
+            del secrets[name]
+            moved.append(name)
+        if moved:
+            self._write_file(secrets)
+        return moved
+
+
+def get_api_key(store: Optional[SecretStore] = None) -> str:
+    """
+    The Anthropic API key: ANTHROPIC_API_KEY if set, else the stored key
+
+    Returns:
+        API key, or "" when none is configured
+    """
+    return os.getenv(API_KEY_ENV_VAR) or (store or SecretStore()).get(API_KEY_NAME) or ""
+
+
+def api_key_status(store: SecretStore) -> Dict[str, Any]:
+    """
+    Whether an API key is configured and where it comes from (never the key itself)
+
+    Returns:
+        {"configured", "source" ("environment" | "keyring" | "file" | None), "keyring_available"}
+    """
+    if os.getenv(API_KEY_ENV_VAR):
+        source = "environment"
+    else:
+        source = store.lookup(API_KEY_NAME)[1]
+    return {"configured": source is not None, "source": source, "keyring_available": store.keyring_available}
@@ -0,0 +1,1711 @@
+"""
+AI Tax CPA Agent - Command Line
+Headless access to stored returns: calculate (with what-ifs), export, back up, and move data without the API server
+
+Examples:
+    python cli.py returns
+    python cli.py --demo calculate demo --add-income self_employment=12000
+    python cli.py calculate ret_0123456789abcdef --add-income self_employment=12000 --add-deduction charitable=3000
+    python cli.py export ret_0123456789abcdef --format efile --output return.xml
+    python cli.py export ret_0123456789abcdef --format anonymized
+    python cli.py invoices add --client "Acme Inc" --amount 2500 --issued 2024-03-01 --paid 2024-03-20
+    python cli.py invoices reconcile --year 2024
+    python cli.py platform-income allocate ret_0123456789abcdef doc_0123456789abcdef --personal-sales 900 --personal-cost 1400
+    python cli.py platform-income reconcile ret_0123456789abcdef
+    python cli.py hobby-test questions
+    python cli.py estimated-payments add ret_0123456789abcdef --quarter Q1 --amount 2400 --paid-on 2025-04-10
+    python cli.py estimated-payments reminders ret_0123456789abcdef --as-of 2025-06-12
+    python cli.py hobby-test answer ret_0123456789abcdef --activity "Dog breeding" --answer manner=yes
+    python cli.py dashboard --within-days 14
+    python cli.py foreign-accounts add ret_0123456789abcdef --institution "Maple Bank" --country Canada --max-balance 14200
+    python cli.py foreign-accounts check ret_0123456789abcdef
+    python cli.py schedule-b add ret_0123456789abcdef --type interest --payer "First Bank" --amount 1850
+    python cli.py schedule-b show ret_0123456789abcdef
+    python cli.py early-distribution claim ret_0123456789abcdef inc_0123456789abcdef --code 1 --exception 09
+    python cli.py early-distribution evaluate ret_0123456789abcdef inc_0123456789abcdef --first-home-used 4000
+    python cli.py state-refund add ret_0123456789abcdef --payer "State of Ohio" --amount 640
+    python cli.py state-refund load ret_0123456789abcdef
+    python cli.py rentals add ret_0123456789abcdef --address "12 Elm St" --building-basis 275000 --in-service 2019-07-15 --rents 24000
+    python cli.py rentals schedule ret_0123456789abcdef rent_0123456789abcdef
+    python cli.py rentals exchange ret_0123456789abcdef rent_0123456789abcdef --date 2024-05-01 --address "8 Pine Rd" --value 520000
+    python cli.py at-risk set ret_0123456789abcdef biz_0123456789abcdef --amount 15000
+    python cli.py crypto receive ret_0123456789abcdef --kind staking --asset ETH --quantity 0.35 --value 1120.50 --date 2024-06-30
+    python cli.py crypto sell ret_0123456789abcdef --asset ETH --quantity 0.2 --proceeds 760 --date 2024-11-12
+    python cli.py holdings add ret_0123456789abcdef --symbol VTI --quantity 40 --basis 10800 --acquired 2023-03-14 --value 9400
+    python cli.py harvest-losses ret_0123456789abcdef --as-of 2024-12-02
+    python cli.py mileage add ret_0123456789abcdef --category medical --miles 42 --purpose "Clinic visits"
+    python cli.py dependents add ret_0123456789abcdef --name "Ruth Doe" --relationship mother --gross-income 4200 --support-percent 70
+    python cli.py dependents test ret_0123456789abcdef
+    python cli.py divorce set ret_0123456789abcdef --separated-date 2024-04-30 --paid-over-half-home-cost
+    python cli.py divorce report ret_0123456789abcdef
+    python cli.py decedent set ret_0123456789abcdef --deceased spouse --date-of-death 2024-08-12
+    python cli.py decedent options ret_0123456789abcdef
+    python cli.py injured-spouse ret_0123456789abcdef --injured spouse --past-due-debt 3200
+    python cli.py ip-pin set ret_0123456789abcdef --person spouse --pin 123456
+    python cli.py peer-comparison ret_0123456789abcdef
+    python cli.py students add ret_0123456789abcdef --name "Sam Doe" --dependent-id dep_0123456789abcdef
+    python cli.py students import-1098t ret_0123456789abcdef stu_0123456789abcdef 1098t.txt
+    python cli.py education-credits ret_0123456789abcdef
+    python cli.py adoptions add ret_0123456789abcdef --name "Mia Doe" --expenses 12500 --final-year 2024
+    python cli.py charitable-bunching ret_0123456789abcdef --annual-donation 6000 --years 6
+    python cli.py relocation ret_0123456789abcdef --to-state TX --new-property-tax 9500
+    python cli.py raise ret_0123456789abcdef --amount 15000
+    python cli.py raise ret_0123456789abcdef --new-salary 135000 --owner spouse
+    python cli.py residency add ret_0123456789abcdef --state NY --start 2024-03-04 --end 2024-03-08
+    python cli.py residency count ret_0123456789abcdef --home-state NJ
+    python cli.py document-requests ret_0123456789abcdef --prior-return ret_fedcba9876543210
+    python cli.py withholding-checkup ret_0123456789abcdef --ytd-wages 41000 --ytd-withholding 4100 --periods-remaining 12
+    python cli.py profit-loss ret_0123456789abcdef biz_0123456789abcdef --through-quarter 2 --format pdf
+    python cli.py --passphrase "$TAX_APP_PASSPHRASE" backup --dest backups
+    python cli.py --passphrase "$TAX_APP_PASSPHRASE" verify-backup backups/backup_20250301_120000_000000.zip
+    python cli.py lock-settings show
+    python cli.py --passphrase "$TAX_APP_PASSPHRASE" lock-settings upgrade --algorithm sha512
+    python cli.py export-data --output data.json --include-documents
+    python cli.py import-data data.json --on-conflict keep_both
+    python cli.py diagnostics --output diagnostics.json
+    python cli.py scenarios --dir my_scenarios
+    python cli.py security-events --action export --limit 20
+    python cli.py settings-history --field default_state
+"""
+import argparse
+import copy
+import json
+import os
+import sys
+import tempfile
+from datetime import date
+from decimal import Decimal
+from typing import Dict, List, Any, Optional, TextIO, Tuple
+
+from app import __version__
+from app.documents.document_requests import document_requests, return_documents
+from app.documents.parsers.form_1098_t import parse_1098_t
+from app.efile.mef import build_return_xml, validate_return_xml
+from app.forms.ledger_csv import build_return_csv
+from app.forms.package import build_return_package
+from app.forms.profit_loss import build_profit_loss_csv, render_profit_loss
+from app.forms.review_packet import build_review_packet
+from app.services.demo_data import seed_demo_data
+from app.services.diagnostics import generate_diagnostics
+from app.services.formatting import Formatter
+from app.services.invoice_reconciliation import reconcile_invoices
+from app.services.platform_reconciliation import reconcile_platform_income, require_1099_k
+from app.services.practice_dashboard import build_dashboard
+from app.services.job_queue import JobQueue
+from app.tax_engine.deadlines import DEFAULT_REMINDER_DAYS
+from app.tax_engine.early_distributions import (
+    DISTRIBUTION_CODES, EXCEPTION_CODES, PLAN_TYPES, calculate_form_5329, evaluate_exception,
+)
+from app.tax_engine.foreign_accounts import check_foreign_accounts
+from app.tax_engine.schedule_b import calculate_schedule_b
+from app.tax_engine.state_refund import state_refund_worksheet
+from app.tax_engine.profit_loss import business_profit_loss
+from app.tax_engine.hobby_loss import HOBBY_FACTORS
+from app.tax_engine.tax_calendar import due_reminders, return_events
+from app.tax_engine.raise_calculator import RAISE_OWNERS, raise_after_tax
+from app.tax_engine.relocation import compare_relocation
+from app.tax_engine.tax_loss_harvesting import harvesting_suggestions
+from app.tax_engine.charitable_bunching import compare_bunching
+from app.tax_engine.injured_spouse import injured_spouse_allocation
+from app.tax_engine.peer_comparison import compare_with_peers, load_soi_dataset
+from app.tax_engine.education_credits import calculate_education_credits
+from app.tax_engine.adoption_credit import calculate_adoption_credit
+from app.tax_engine.dependents import qualify_dependents
+from app.tax_engine.decedent import decedent_filing_options
+from app.tax_engine.divorce import divorce_year_report
+from app.tax_engine.vehicle_expenses import mileage_deductions
+from app.tax_engine.rental_depreciation import depreciation_schedule, rental_property_year
+from app.tax_engine.residency import return_residency_days
+from app.tax_engine.return_calculation import calculate_at_risk, calculate_return
+from app.tax_engine.scenarios import load_scenarios, run_scenario
+from app.tax_engine.withholding_checkup import PAY_PERIODS, withholding_checkup
+from app.tax_engine.rounding import round_amounts
+from app.utils.anonymize import anonymize_return
+from app.utils.app_lock import KDF_ALGORITHMS, AppLock
+from app.utils.app_log import get_recent_logs
+from app.utils.audit_log import AUDIT_ACTIONS, AUDIT_OUTCOMES, AuditLog
+from app.utils.backup import create_backup, verify_backup
+from app.utils.client_store import ClientStore
+from app.utils.conversation_store import ConversationStore
+from app.utils.data_transfer import CONFLICT_STRATEGIES, export_data, import_data
+from app.utils.document_store import DocumentStore
+from app.utils.invoice_store import INVOICE_STATUSES, InvoiceStore
+from app.utils.reminder_store import ReminderStore
+from app.utils.return_store import ReturnStore
+from app.utils.settings_store import SettingsStore
+
+# Used when --passphrase isn't given
+PASSPHRASE_ENV_VAR = "TAX_APP_PASSPHRASE"
+
+EXPORT_FORMATS = ["json", "package", "efile", "review-packet", "csv", "anonymized"]
+PROFIT_LOSS_FORMATS = ["json", "csv", "pdf"]
+
+# Dependent record fields settable from dependents add/update
+DEPENDENT_FIELDS = (
+    "name", "relationship", "birth_date", "months_lived_with", "self_support_percent", "support_percent",
+    "gross_income", "full_time_student", "disabled", "files_joint_return", "qualifying_child_of_another", "has_ssn",
+    "custodial_parent", "form_8332_years",
+)
+
+# Options of the schedule-b add and update commands, and the income source fields they set
+SCHEDULE_B_FIELDS = (
+    ("payer", "description"), ("amount", "amount"), ("withholding", "withholding"),
+    ("qualified", "qualified_dividends"), ("tax_exempt", "tax_exempt"), ("foreign_account", "foreign_account"),
+    ("owner", "owner"), ("tax_exempt_interest", "tax_exempt_interest"),
+    ("exempt_interest_dividends", "exempt_interest_dividends"), ("bond_state", "bond_state"),
+)
+
+# Exit codes
+EXIT_ERROR = 1
+EXIT_LOCKED = 2
+
+
+class CliError(Exception):
+    """A failure reported on stderr without a traceback"""
+
+    def __init__(self, message: str, exit_code: int = EXIT_ERROR):
+        super().__init__(message)
+        self.exit_code = exit_code
+
+
+def build_parser() -> argparse.ArgumentParser:
+    parser = argparse.ArgumentParser(prog="cli.py", description="Headless tax engine commands")
+    parser.add_argument("--data-dir", help="Directory holding the app's data (defaults to the current directory)")
+    parser.add_argument("--passphrase", help=f"App lock passphrase (or set {PASSPHRASE_ENV_VAR})")
+    parser.add_argument(
+        "--demo", action="store_true",
+        help="Run against demo data in a temporary folder that's removed afterwards; the demo return's ID is 'demo'",
+    )
+    commands = parser.add_subparsers(dest="command", required=True)
+
+    commands.add_parser("returns", help="List stored returns")
+
+    calculate = commands.add_parser("calculate", help="Calculate a return, optionally with what-if changes")
+    calculate.add_argument("return_id")
+    calculate.add_argument("--add-income", action="append", default=[], metavar="TYPE=AMOUNT",
+                           help="Extra income source for the what-if (repeatable)")
+    calculate.add_argument("--add-deduction", action="append", default=[], metavar="CATEGORY=AMOUNT",
+                           help="Extra itemized deduction for the what-if (repeatable)")
+    calculate.add_argument("--filing-status", help="Recalculate under another filing status")
+    calculate.add_argument("--save", action="store_true", help="Store the results on the return (not with what-ifs)")
+
+    export = commands.add_parser("export", help="Export a return")
+    export.add_argument("return_id")
+    export.add_argument("--format", choices=EXPORT_FORMATS, default="json")
+    export.add_argument("--output", help="File to write (defaults to <return id>_<year> with the format's extension)")
+
+    checkup = commands.add_parser("withholding-checkup", help="Project the year from a pay stub and size a W-4 change")
+    checkup.add_argument("return_id")
+    checkup.add_argument("--ytd-wages", type=float, required=True, help="Federal taxable wages to date")
+    checkup.add_argument("--ytd-withholding", type=float, required=True, help="Federal income tax withheld to date")
+    checkup.add_argument("--periods-remaining", type=int, required=True, help="Paychecks left this year")
+    checkup.add_argument("--pay-frequency", choices=list(PAY_PERIODS), default="biweekly")
+    checkup.add_argument("--owner", choices=["taxpayer", "spouse"], default="taxpayer", help="Whose pay stub it is")
+    checkup.add_argument("--target-refund", type=float, default=0, help="Refund to aim for (default breaks even)")
+
+    profit_loss = commands.add_parser("profit-loss", help="Quarterly and year-to-date profit and loss for a business")
+    profit_loss.add_argument("return_id")
+    profit_loss.add_argument("business_id")
+    profit_loss.add_argument("--through-quarter", type=int, choices=[1, 2, 3, 4], default=4,
+                             help="Last quarter to include")
+    profit_loss.add_argument("--format", choices=PROFIT_LOSS_FORMATS, default="json",
+                             help="json prints the report; csv and pdf write a file")
+    profit_loss.add_argument("--output", help="File to write (defaults to <return id>_<year>_<business id>_profit_loss)")
+
+    invoices = commands.add_parser("invoices", help="Track invoices and reconcile them with 1099-NEC forms")
+    actions = invoices.add_subparsers(dest="action", required=True)
+    add = actions.add_parser("add", help="Record an invoice")
+    add.add_argument("--client", required=True)
+    add.add_argument("--amount", type=float, required=True)
+    add.add_argument("--issued", required=True, metavar="YYYY-MM-DD", help="Issue date")
+    add.add_argument("--paid", metavar="YYYY-MM-DD", help="Date paid")
+    add.add_argument("--no-1099", dest="expect_1099", action="store_false", help="The client won't send a 1099-NEC")
+    add.add_argument("--description", default="")
+    listing = actions.add_parser("list", help="List invoices")
+    listing.add_argument("--year", type=int)
+    listing.add_argument("--client")
+    listing.add_argument("--status", choices=INVOICE_STATUSES)
+    update = actions.add_parser("update", help="Change an invoice")
+    update.add_argument("invoice_id")
+    update.add_argument("--client")
+    update.add_argument("--amount", type=float)
+    update.add_argument("--issued", metavar="YYYY-MM-DD")
+    update.add_argument("--paid", metavar="YYYY-MM-DD", help="Date paid (\"\" reopens the invoice)")
+    update.add_argument("--description")
+    delete = actions.add_parser("delete", help="Delete an invoice")
+    delete.add_argument("invoice_id")
+    reconcile = actions.add_parser("reconcile", help="Compare paid invoices with the 1099-NEC forms received")
+    reconcile.add_argument("--year", type=int, required=True)
+
+    platforms = commands.add_parser("platform-income", help="Reconcile 1099-K forms with recorded business income")
+    actions = platforms.add_subparsers(dest="action", required=True)
+    allocate = actions.add_parser("allocate", help="Split a 1099-K between personal-item sales and business receipts")
+    allocate.add_argument("return_id")
+    allocate.add_argument("document_id")
+    allocate.add_argument("--personal-sales", type=float, default=0, help="Gross from selling personal items")
+    allocate.add_argument("--personal-cost", type=float, default=0, help="What those personal items originally cost")
+    allocate.add_argument("--business-id", help="Business the rest of the gross belongs to")
+    reconcile = actions.add_parser("reconcile", help="Compare each 1099-K with the business income recorded for it")
+    reconcile.add_argument("return_id")
+
+    hobby = commands.add_parser("hobby-test", help="Nine-factor test of whether an activity is a business or a hobby")
+    actions = hobby.add_subparsers(dest="action", required=True)
+    actions.add_parser("questions", help="The nine factors and the question for each")
+    answer = actions.add_parser("answer", help="Record answers and get a Schedule C or hobby recommendation")
+    answer.add_argument("return_id")
+    answer.add_argument("--activity", required=True, help="What the activity is")
+    answer.add_argument("--answer", action="append", default=[], metavar="FACTOR=ANSWER",
+                        help=f"Answer to a factor's question: {', '.join(ReturnStore.HOBBY_ANSWERS)} (repeatable)")
+    answer.add_argument("--note", action="append", default=[], metavar="FACTOR=TEXT",
+                        help="Facts behind a factor's answer (repeatable)")
+    answer.add_argument("--profit-years", type=int, help="Years with a profit in the last 5 (7 for horses)")
+    answer.add_argument("--horse", action="store_true", help="Breeding, training, showing, or racing horses")
+    answer.add_argument("--business-id", help="Business the activity is reported as")
+    listing = actions.add_parser("list", help="Determinations recorded on a return")
+    listing.add_argument("return_id")
+    delete = actions.add_parser("delete", help="Remove a determination")
+    delete.add_argument("return_id")
+    delete.add_argument("determination_id")
+
+    estimates = commands.add_parser("estimated-payments", help="Log next year's estimated tax payments")
+    actions = estimates.add_subparsers(dest="action", required=True)
+    add = actions.add_parser("add", help="Log a payment (stops that quarter's reminders)")
+    add.add_argument("return_id")
+    add.add_argument("--quarter", required=True, choices=ReturnStore.ESTIMATED_QUARTERS)
+    add.add_argument("--amount", type=float, required=True)
+    add.add_argument("--paid-on", required=True, metavar="YYYY-MM-DD", help="Date paid")
+    add.add_argument("--description", default="", help="How it was paid")
+    listing = actions.add_parser("list", help="Payments logged on a return")
+    listing.add_argument("return_id")
+    delete = actions.add_parser("delete", help="Remove a logged payment")
+    delete.add_argument("return_id")
+    delete.add_argument("payment_id")
+    reminders = actions.add_parser("reminders", help="Estimated payment reminders due now")
+    reminders.add_argument("return_id")
+    reminders.add_argument("--as-of", metavar="YYYY-MM-DD", help="Date to count from (defaults to today)")
+
+    dashboard = commands.add_parser("dashboard", help="Every client's return statuses and deadlines (practitioner mode)")
+    dashboard.add_argument("--within-days", type=int, default=DEFAULT_REMINDER_DAYS, help="How many days ahead to look")
+    dashboard.add_argument("--as-of", metavar="YYYY-MM-DD", help="Date to count from (defaults to today)")
+
+    foreign = commands.add_parser("foreign-accounts", help="Record foreign accounts and check FBAR and Form 8938 thresholds")
+    actions = foreign.add_subparsers(dest="action", required=True)
+    add = actions.add_parser("add", help="Record a foreign financial account")
+    add.add_argument("return_id")
+    add.add_argument("--institution", required=True)
+    add.add_argument("--country", required=True)
+    add.add_argument("--max-balance", type=float, required=True, help="Highest balance during the year, in U.S. dollars")
+    add.add_argument("--year-end-balance", type=float, help="Balance on December 31, in U.S. dollars")
+    add.add_argument("--account-type", choices=ReturnStore.FOREIGN_ACCOUNT_TYPES)
+    add.add_argument("--owner", choices=ReturnStore.RECORD_OWNERS)
+    listing = actions.add_parser("list", help="List a return's foreign accounts")
+    listing.add_argument("return_id")
+    delete = actions.add_parser("delete", help="Remove a foreign account")
+    delete.add_argument("return_id")
+    delete.add_argument("account_id")
+    check = actions.add_parser("check", help="Whether an FBAR or Form 8938 is required")
+    check.add_argument("return_id")
+    check.add_argument("--abroad", action="store_true", help="The taxpayer lives abroad (bona fide residence or presence test)")
+
+    schedule_b = commands.add_parser("schedule-b", help="Interest and dividend payers, and the Schedule B they need")
+    actions = schedule_b.add_subparsers(dest="action", required=True)
+    add = actions.add_parser("add", help="Record interest or dividends from a payer (a 1099-INT or 1099-DIV)")
+    add.add_argument("return_id")
+    add.add_argument("--type", required=True, choices=ReturnStore.SCHEDULE_B_TYPES)
+    add.add_argument("--payer", required=True, help="Payer's name")
+    add.add_argument("--amount", type=float, required=True,
+                     help="Interest (1099-INT box 1) or ordinary dividends (1099-DIV box 1a)")
+    update = actions.add_parser("update", help="Change a payer's amounts or flags")
+    update.add_argument("return_id")
+    update.add_argument("source_id")
+    update.add_argument("--payer")
+    update.add_argument("--amount", type=float)
+    for sub in (add, update):
+        sub.add_argument("--withholding", type=float, help="Federal income tax withheld")
+        sub.add_argument("--qualified", type=float, help="Qualified dividends (1099-DIV box 1b)")
+        sub.add_argument("--tax-exempt", action=argparse.BooleanOptionalAction,
+                         help="Tax-exempt interest, such as municipal bonds (Form 1040 line 2a, not Schedule B)")
+        sub.add_argument("--tax-exempt-interest", type=float,
+                         help="Tax-exempt interest reported apart from the amount (1099-INT box 8)")
+        sub.add_argument("--exempt-interest-dividends", type=float,
+                         help="Exempt-interest dividends reported apart from the amount (1099-DIV box 12)")
+        sub.add_argument("--bond-state", type=str.upper,
+                         help="State that issued the bonds; other states' bond interest is added back on state returns")
+        sub.add_argument("--foreign-account", action=argparse.BooleanOptionalAction,
+                         help="Paid on a financial account in a foreign country (Part III)")
+        sub.add_argument("--owner", choices=ReturnStore.RECORD_OWNERS)
+    listing = actions.add_parser("list", help="List a return's interest and dividend payers")
+    listing.add_argument("return_id")
+    delete = actions.add_parser("delete", help="Remove a payer")
+    delete.add_argument("return_id")
+    delete.add_argument("source_id")
+    show = actions.add_parser("show", help="Schedule B lines and whether the return needs it")
+    show.add_argument("return_id")
+
+    early = commands.add_parser("early-distribution", help="Tax on early retirement distributions (Form 5329)")
+    actions = early.add_subparsers(dest="action", required=True)
+    claim = actions.add_parser("claim", help="Record a 1099-R's distribution code and the exception claimed")
+    claim.add_argument("return_id")
+    claim.add_argument("source_id", help="The retirement income source")
+    claim.add_argument("--code", dest="distribution_code", choices=DISTRIBUTION_CODES, help="1099-R box 7 code")
+    claim.add_argument("--plan-type", choices=PLAN_TYPES)
+    claim.add_argument("--exception", dest="exception_code", choices=EXCEPTION_CODES, help="Form 5329 exception number")
+    claim.add_argument("--exception-amount", type=float, help="Part the exception covers (defaults to all of it)")
+    form = actions.add_parser("form", help="Form 5329 Part I lines")
+    form.add_argument("return_id")
+    evaluate = actions.add_parser("evaluate", help="Whether the claimed exception applies")
+    evaluate.add_argument("return_id")
+    evaluate.add_argument("source_id")
+    evaluate.add_argument("--exception", dest="exception_code", choices=EXCEPTION_CODES,
+                          help="Exception to check (defaults to the one claimed)")
+    evaluate.add_argument("--age-at-separation", type=int, help="Age in the year employment ended (01)")
+    evaluate.add_argument("--public-safety", action="store_true", help="Qualified public safety employee (01)")
+    evaluate.add_argument("--weeks-unemployed", type=int, help="Consecutive weeks of unemployment compensation (07)")
+    evaluate.add_argument("--premiums", type=float, help="Health insurance premiums paid (07)")
+    evaluate.add_argument("--education-expenses", type=float, help="Qualified higher education expenses (08)")
+    evaluate.add_argument("--first-home-used", type=float, default=0, help="First home exception used before (09)")
+    evaluate.add_argument("--children", type=int, default=1, help="Births or adoptions (13)")
+
+    refund = commands.add_parser("state-refund", help="Whether last year's state income tax refund is taxable")
+    actions = refund.add_subparsers(dest="action", required=True)
+    add = actions.add_parser("add", help="Record a state or local income tax refund (1099-G box 2)")
+    add.add_argument("return_id")
+    add.add_argument("--payer", required=True, help="State or local government that paid it")
+    add.add_argument("--amount", type=float, required=True, help="Refund, credit, or offset received")
+    load = actions.add_parser("load", help="Copy last year's deduction figures from its stored return")
+    load.add_argument("return_id")
+    load.add_argument("--prior-return", help="Last year's return (defaults to the one this return was cloned from)")
+    show = actions.add_parser("show", help="The refund worksheet and how much of the refund is taxable")
+    show.add_argument("return_id")
+
+    rentals = commands.add_parser("rentals", help="Rental properties and their depreciation schedules")
+    actions = rentals.add_subparsers(dest="action", required=True)
+    add = actions.add_parser("add", help="Add a residential rental property")
+    add.add_argument("return_id")
+    add.add_argument("--address", required=True)
+    add.add_argument("--building-basis", type=float, required=True, help="Cost of the building, not the land")
+    add.add_argument("--in-service", required=True, metavar="YYYY-MM-DD", help="Date first ready to rent")
+    add.add_argument("--land-value", type=float)
+    add.add_argument("--rents", type=float, help="Rents received this year")
+    add.add_argument("--expenses", type=float, help="Operating expenses this year, before depreciation")
+    listing = actions.add_parser("list", help="List rental properties with this year's depreciation")
+    listing.add_argument("return_id")
+    schedule = actions.add_parser("schedule", help="A property's 27.5-year depreciation schedule")
+    schedule.add_argument("return_id")
+    schedule.add_argument("property_id")
+    sell = actions.add_parser("sell", help="Record a property's sale and show the depreciation recapture")
+    sell.add_argument("return_id")
+    sell.add_argument("property_id")
+    sell.add_argument("--date", required=True, metavar="YYYY-MM-DD")
+    sell.add_argument("--price", type=float, required=True)
+    sell.add_argument("--selling-expenses", type=float, default=0, help="Commissions and closing costs")
+    exchange = actions.add_parser("exchange", help="Exchange a property for another under section 1031")
+    exchange.add_argument("return_id")
+    exchange.add_argument("property_id")
+    exchange.add_argument("--date", required=True, metavar="YYYY-MM-DD", help="Date the property was transferred")
+    exchange.add_argument("--address", required=True, help="Replacement property address")
+    exchange.add_argument("--value", type=float, required=True, help="Fair market value of the replacement")
+    exchange.add_argument("--received", metavar="YYYY-MM-DD", help="Date the replacement was received")
+    exchange.add_argument("--land-value", type=float, default=0, help="Part of the replacement's value that is land")
+    exchange.add_argument("--boot", type=float, default=0, help="Cash or other property received besides the replacement")
+    exchange.add_argument("--expenses", type=float, default=0, help="Intermediary, commission, and closing costs")
+
+    at_risk = commands.add_parser("at-risk", help="At-risk loss limits for businesses and rentals")
+    actions = at_risk.add_subparsers(dest="action", required=True)
+    listing = actions.add_parser("list", help="Each activity's loss allowed under its amount at risk")
+    listing.add_argument("return_id")
+    setting = actions.add_parser("set", help="Set a business's or rental's amount at risk")
+    setting.add_argument("return_id")
+    setting.add_argument("activity_id", help="Business or rental property ID")
+    setting.add_argument("--amount", type=float, help="Cash, property, and recourse debt at risk at the start of the year")
+    setting.add_argument("--carryforward", type=float, help="Losses disallowed by the at-risk rules in earlier years")
+
+    crypto = commands.add_parser("crypto", help="Record crypto rewards as income and sell from their basis lots")
+    actions = crypto.add_subparsers(dest="action", required=True)
+    receive = actions.add_parser("receive", help="Record staking, mining, or airdrop income, or a purchase")
+    receive.add_argument("return_id")
+    receive.add_argument("--kind", required=True, choices=ReturnStore.CRYPTO_RECEIPT_KINDS)
+    receive.add_argument("--asset", required=True, help="Ticker or name")
+    receive.add_argument("--quantity", type=float, required=True)
+    receive.add_argument("--value", type=float, required=True,
+                         help="Total U.S. dollar value when received (price paid for a purchase)")
+    receive.add_argument("--date", required=True, metavar="YYYY-MM-DD")
+    receive.add_argument("--business", dest="business_id", help="Business the mining is reported on")
+    receive.add_argument("--owner", choices=ReturnStore.BUSINESS_OWNERS)
+    sell = actions.add_parser("sell", help="Sell, trade, or spend crypto from the oldest lots first")
+    sell.add_argument("return_id")
+    sell.add_argument("--asset", required=True)
+    sell.add_argument("--quantity", type=float, required=True)
+    sell.add_argument("--proceeds", type=float, required=True, help="Total U.S. dollars received")
+    sell.add_argument("--date", required=True, metavar="YYYY-MM-DD")
+    lots = actions.add_parser("lots", help="List a return's crypto basis lots")
+    lots.add_argument("return_id")
+
+    holdings = commands.add_parser("holdings", help="Open investment positions, for tax-loss harvesting")
+    actions = holdings.add_subparsers(dest="action", required=True)
+    add = actions.add_parser("add", help="Record a position")
+    add.add_argument("return_id")
+    add.add_argument("--symbol", required=True)
+    add.add_argument("--quantity", type=float, required=True)
+    add.add_argument("--basis", type=float, required=True, help="Total basis of the lot")
+    add.add_argument("--acquired", required=True, metavar="YYYY-MM-DD")
+    add.add_argument("--value", type=float, help="Current total value of the lot")
+    value = actions.add_parser("value", help="Update a position's current value")
+    value.add_argument("return_id")
+    value.add_argument("holding_id")
+    value.add_argument("market_value", type=float)
+    listing = actions.add_parser("list", help="List a return's positions")
+    listing.add_argument("return_id")
+    delete = actions.add_parser("delete", help="Remove a position")
+    delete.add_argument("return_id")
+    delete.add_argument("holding_id")
+
+    harvest = commands.add_parser("harvest-losses", help="Losses to harvest before year-end and the tax they'd save")
+    harvest.add_argument("return_id")
+    harvest.add_argument("--as-of", metavar="YYYY-MM-DD", help="Sale date to assume (defaults to today)")
+
+    mileage = commands.add_parser("mileage", help="Medical and charitable trips deducted on Schedule A")
+    actions = mileage.add_subparsers(dest="action", required=True)
+    add = actions.add_parser("add", help="Log a trip in a personal vehicle")
+    add.add_argument("return_id")
+    add.add_argument("--category", required=True, choices=ReturnStore.MILEAGE_DEDUCTION_CATEGORIES)
+    add.add_argument("--miles", type=float, required=True)
+    add.add_argument("--purpose", default="", help="Where and why")
+    add.add_argument("--date", metavar="YYYY-MM-DD")
+    add.add_argument("--parking-tolls", type=float, default=0)
+    listing = actions.add_parser("list", help="List trips with the deductions they add")
+    listing.add_argument("return_id")
+    delete = actions.add_parser("delete", help="Remove a trip")
+    delete.add_argument("return_id")
+    delete.add_argument("trip_id")
+
+    dependents = commands.add_parser("dependents", help="Dependents and the qualifying child and relative tests")
+    actions = dependents.add_subparsers(dest="action", required=True)
+    add = actions.add_parser("add", help="Add a dependent")
+    add.add_argument("return_id")
+    add.add_argument("--name", required=True)
+    add.add_argument("--relationship", required=True, help="son, daughter, parent, niece, ...")
+    update = actions.add_parser("update", help="Change a dependent's details")
+    update.add_argument("return_id")
+    update.add_argument("dependent_id")
+    update.add_argument("--name")
+    update.add_argument("--relationship")
+    for sub in (add, update):
+        sub.add_argument("--birth-date", metavar="YYYY-MM-DD")
+        sub.add_argument("--months", dest="months_lived_with", type=int, help="Months lived with the taxpayer")
+        sub.add_argument("--self-support-percent", type=float, help="Share of their own support they provided")
+        sub.add_argument("--support-percent", type=float, help="Share of their support the taxpayer provided")
+        sub.add_argument("--gross-income", type=float)
+        for flag in ("full-time-student", "disabled", "files-joint-return", "qualifying-child-of-another", "has-ssn"):
+            sub.add_argument(f"--{flag}", action=argparse.BooleanOptionalAction)
+        sub.add_argument("--custodial-parent", choices=ReturnStore.CUSTODIAL_PARENTS,
+                         help="Parent a child of divorced or separated parents lived with more")
+        sub.add_argument("--form-8332-year", dest="form_8332_years", type=int, action="append",
+                         help="Year the custodial parent released the claim on Form 8332 (repeatable)")
+    test = actions.add_parser("test", help="Which dependents qualify, and for which benefits")
+    test.add_argument("return_id")
+
+    divorce = commands.add_parser("divorce", help="Filing options for the year of a divorce or separation")
+    actions = divorce.add_subparsers(dest="action", required=True)
+    decree = actions.add_parser("set", help="Record the decree and separation dates")
+    decree.add_argument("return_id")
+    decree.add_argument("--decree-date", metavar="YYYY-MM-DD", help="Date the decree became final (omit while pending)")
+    decree.add_argument("--decree-type", choices=ReturnStore.DECREE_TYPES, default="divorce")
+    decree.add_argument("--separated-date", metavar="YYYY-MM-DD", help="Date the spouse last lived in the home")
+    decree.add_argument("--paid-over-half-home-cost", action=argparse.BooleanOptionalAction)
+    alimony = actions.add_parser("alimony", help="Record alimony paid or received")
+    alimony.add_argument("return_id")
+    alimony.add_argument("--direction", required=True, choices=ReturnStore.ALIMONY_DIRECTIONS)
+    alimony.add_argument("--amount", type=float, required=True)
+    alimony.add_argument("--instrument-date", metavar="YYYY-MM-DD",
+                         help="Date the divorce or separation instrument was executed (default: the decree date)")
+    alimony.add_argument("--modified-to-adopt-new-rule", action="store_true",
+                         help="Pre-2019 instrument modified to adopt the post-2018 rule")
+    alimony.add_argument("--recipient-ssn")
+    report = actions.add_parser("report", help="Allowed filing statuses, the tax under each, and who claims the children")
+    report.add_argument("return_id")
+
+    decedent = commands.add_parser("decedent", help="Final return and surviving spouse filing status after a death")
+    actions = decedent.add_subparsers(dest="action", required=True)
+    death = actions.add_parser("set", help="Record the death of the taxpayer or spouse")
+    death.add_argument("return_id")
+    death.add_argument("--deceased", required=True, choices=ReturnStore.DECEASED_PERSONS)
+    death.add_argument("--date-of-death", required=True, metavar="YYYY-MM-DD")
+    death.add_argument("--personal-representative", help="Executor or administrator who signs for the deceased")
+    death.add_argument("--remarried-date", metavar="YYYY-MM-DD", help="Date the surviving spouse remarried")
+    death.add_argument("--paid-over-half-home-cost", action=argparse.BooleanOptionalAction)
+    options = actions.add_parser("options", help="Allowed filing statuses and what the return must include")
+    options.add_argument("return_id")
+
+    injured = commands.add_parser("injured-spouse", help="Form 8379 allocation of a joint return between the spouses")
+    injured.add_argument("return_id")
+    injured.add_argument("--injured", choices=["taxpayer", "spouse"], default="spouse",
+                         help="The spouse who doesn't owe the debt")
+    injured.add_argument("--past-due-debt", type=float, help="The other spouse's debt the refund would be applied to")
+
+    ip_pin = commands.add_parser("ip-pin", help="Identity Protection PINs for the taxpayer and spouse (stored encrypted)")
+    actions = ip_pin.add_subparsers(dest="action", required=True)
+    pin = actions.add_parser("set", help="Store the IP PIN the IRS issued")
+    pin.add_argument("return_id")
+    pin.add_argument("--person", choices=ReturnStore.IP_PIN_PERSONS, default="taxpayer")
+    pin.add_argument("--pin", required=True, help="6-digit IP PIN")
+    pin.add_argument("--valid-year", type=int, help="Calendar year the PIN is for (defaults to the year after the tax year)")
+    show = actions.add_parser("show", help="Show the stored IP PINs")
+    show.add_argument("return_id")
+    show.add_argument("--reveal", action="store_true", help="Show the full PINs instead of the last 2 digits")
+    clear = actions.add_parser("clear", help="Remove an IP PIN")
+    clear.add_argument("return_id")
+    clear.add_argument("--person", choices=ReturnStore.IP_PIN_PERSONS, default="taxpayer")
+
+    peers = commands.add_parser("peer-comparison", help="Itemized deductions against IRS averages for the same AGI band")
+    peers.add_argument("return_id")
+    peers.add_argument("--dataset", help="SOI averages file to use instead of the embedded one")
+
+    students = commands.add_parser("students", help="Students and their education expenses, for the education credits")
+    actions = students.add_subparsers(dest="action", required=True)
+    add = actions.add_parser("add", help="Add a student")
+    add.add_argument("return_id")
+    add.add_argument("--name", required=True)
+    add.add_argument("--dependent-id")
+    add.add_argument("--institution")
+    add.add_argument("--less-than-half-time", action="store_true", help="Not enrolled at least half time")
+    add.add_argument("--graduate", action="store_true")
+    add.add_argument("--aotc-years", type=int, default=0, help="Earlier years the American opportunity credit was claimed")
+    expense = actions.add_parser("expense", help="Record tuition, fees, course materials, a scholarship, or a refund")
+    expense.add_argument("return_id")
+    expense.add_argument("student_id")
+    expense.add_argument("--kind", required=True, choices=ReturnStore.EDUCATION_EXPENSE_KINDS)
+    expense.add_argument("--amount", type=float, required=True)
+    expense.add_argument("--description", default="")
+    expense.add_argument("--date", metavar="YYYY-MM-DD")
+    expense.add_argument("--paid-to-institution", action="store_true", help="Course materials bought from the school")
+    form = actions.add_parser("import-1098t", help="Add a 1098-T's tuition and scholarships from its text")
+    form.add_argument("return_id")
+    form.add_argument("student_id")
+    form.add_argument("path", help="OCR or PDF-extracted text of the 1098-T")
+    listing = actions.add_parser("list", help="List students and their expenses")
+    listing.add_argument("return_id")
+
+    education = commands.add_parser("education-credits", help="American opportunity and lifetime learning credits")
+    education.add_argument("return_id")
+
+    adoptions = commands.add_parser("adoptions", help="Children being adopted and the adoption credit (Form 8839)")
+    actions = adoptions.add_subparsers(dest="action", required=True)
+    add = actions.add_parser("add", help="Add a child being adopted")
+    add.add_argument("return_id")
+    add.add_argument("--name", required=True)
+    update = actions.add_parser("update", help="Change an adoption's expenses or status")
+    update.add_argument("return_id")
+    update.add_argument("adoption_id")
+    update.add_argument("--name")
+    for sub in (add, update):
+        sub.add_argument("--expenses", type=float, help="Qualified adoption expenses paid this year")
+        sub.add_argument("--prior-year-expenses", type=float, help="Expenses paid in earlier years, not yet claimed")
+        sub.add_argument("--credit-claimed-before", type=float, help="Credit claimed for this child in earlier years")
+        sub.add_argument("--final-year", type=int, help="Year the adoption became final")
+        sub.add_argument("--foreign", action=argparse.BooleanOptionalAction,
+                         help="The child wasn't a U.S. citizen or resident")
+        sub.add_argument("--special-needs", action=argparse.BooleanOptionalAction,
+                         help="A state determined the child has special needs")
+    delete = actions.add_parser("delete", help="Remove an adoption")
+    delete.add_argument("return_id")
+    delete.add_argument("adoption_id")
+    listing = actions.add_parser("list", help="List the children being adopted")
+    listing.add_argument("return_id")
+    credit = actions.add_parser("credit", help="The adoption credit, phase-out, and carryforward")
+    credit.add_argument("return_id")
+
+    bunching = commands.add_parser("charitable-bunching", help="Compare annual giving with bunching into a donor-advised fund")
+    bunching.add_argument("return_id")
+    bunching.add_argument("--annual-donation", type=float, help="Yearly giving (defaults to the return's charitable deductions)")
+    bunching.add_argument("--years", type=int, default=4, help="Planning horizon")
+
+    relocation = commands.add_parser("relocation", help="Compare this year's taxes with living in another state")
+    relocation.add_argument("return_id")
+    relocation.add_argument("--to-state", required=True, help="State being considered")
+    relocation.add_argument("--from-state", help="Current state (defaults to the resident state return)")
+    relocation.add_argument("--local-rate", type=float, default=0, help="Current local income tax, percent of AGI")
+    relocation.add_argument("--new-local-rate", type=float, default=0, help="Local income tax after moving, percent of AGI")
+    relocation.add_argument("--property-tax", type=float, help="Current yearly property tax")
+    relocation.add_argument("--new-property-tax", type=float, help="Estimated yearly property tax after moving")
+
+    pay_raise = commands.add_parser("raise", help="What a raise or job offer is worth after taxes")
+    pay_raise.add_argument("return_id")
+    amount = pay_raise.add_mutually_exclusive_group(required=True)
+    amount.add_argument("--amount", type=float, help="Extra yearly pay (negative for a pay cut)")
+    amount.add_argument("--new-salary", type=float, help="Offered salary")
+    pay_raise.add_argument("--owner", choices=RAISE_OWNERS, default="taxpayer", help="Whose pay changes")
+    pay_raise.add_argument("--state", help="State of residence (defaults to the return's)")
+    pay_raise.add_argument("--local-rate", type=float, default=0, help="Local income tax, percent of AGI")
+
+    residency = commands.add_parser("residency", help="Log days in each state and count them against residency tests")
+    actions = residency.add_subparsers(dest="action", required=True)
+    add = actions.add_parser("add", help="Log days spent in a state")
+    add.add_argument("return_id")
+    add.add_argument("--state", required=True)
+    add.add_argument("--start", required=True, metavar="YYYY-MM-DD", help="First day there")
+    add.add_argument("--end", metavar="YYYY-MM-DD", help="Last day there (defaults to --start)")
+    add.add_argument("--note", default="")
+    delete = actions.add_parser("delete", help="Remove a travel log entry")
+    delete.add_argument("return_id")
+    delete.add_argument("entry_id")
+    count = actions.add_parser("count", help="Days per state, with statutory residency warnings")
+    count.add_argument("return_id")
+    count.add_argument("--home-state", help="State of domicile (defaults to the resident state return)")
+    count.add_argument("--as-of", metavar="YYYY-MM-DD", help="Count through this date (defaults to the whole year)")
+
+    requests = commands.add_parser("document-requests", help="Forms to ask for this year, received or outstanding")
+    requests.add_argument("return_id")
+    requests.add_argument("--prior-return", help="Last year's return (defaults to the one this was cloned from)")
+
+    backup = commands.add_parser("backup", help="Back up the app's data folders into a zip archive")
+    backup.add_argument("--dest", default="backups", help="Folder the archive is written to")
+
+    verify = commands.add_parser(
+        "verify-backup", help="Check a backup archive's checksums and that it could be restored (nothing is restored)"
+    )
+    verify.add_argument("archive")
+
+    lock_settings = commands.add_parser(
+        "lock-settings", help="How the app passphrase is hashed, and upgrading a lock file made with weaker settings"
+    )
+    actions = lock_settings.add_subparsers(dest="action", required=True)
+    actions.add_parser("show", help="The key derivation settings and whether an upgrade is recommended")
+    upgrade = actions.add_parser("upgrade", help="Re-hash the passphrase (from --passphrase) with stronger settings")
+    upgrade.add_argument("--algorithm", choices=KDF_ALGORITHMS, help="PBKDF2 HMAC digest (defaults to the current one)")
+    upgrade.add_argument("--iterations", type=int, help="Defaults to the recommendation for the algorithm")
+
+    export_all = commands.add_parser("export-data", help="Export all returns, documents, conversations, and settings as JSON")
+    export_all.add_argument("--output", default="tax_data_export.json", help="File to write")
+    export_all.add_argument("--include-documents", action="store_true", help="Embed the document files")
+
+    import_all = commands.add_parser("import-data", help="Import a file written by export-data")
+    import_all.add_argument("path")
+    import_all.add_argument("--on-conflict", choices=CONFLICT_STRATEGIES, default="skip",
+                            help="What to do with records whose IDs already exist")
+    import_all.add_argument("--skip-settings", action="store_true", help="Keep the current settings")
+
+    diagnostics = commands.add_parser("diagnostics", help="Write a scrubbed diagnostic report for a bug report")
+    diagnostics.add_argument("--output", default="diagnostics.json", help="File to write")
+
+    events = commands.add_parser(
+        "security-events", help="Sensitive actions (exports, reveals, key changes, deletions) and their outcome"
+    )
+    events.add_argument("--limit", type=int, default=50, help="Maximum events (newest first)")
+    events.add_argument("--since", metavar="YYYY-MM-DD", help="Only events on or after this date")
+    events.add_argument("--action", choices=AUDIT_ACTIONS)
+    events.add_argument("--outcome", choices=AUDIT_OUTCOMES)
+
+    history = commands.add_parser(
+        "settings-history", help="Changes to the settings with their previous values and where they came from"
+    )
+    history.add_argument("--limit", type=int, default=50, help="Maximum entries (newest first)")
+    history.add_argument("--field", help="Only changes to this setting, e.g. default_tax_year")
+
+    scenarios = commands.add_parser(
+        "scenarios", help="Run the golden tax scenarios and report any result that differs from the expected one"
+    )
+    scenarios.add_argument("--dir", help="Folder of scenario JSON files (defaults to the built-in ones)")
+
+    seed = commands.add_parser("seed-demo", help="Add a made-up household's return to the data folder to try things on")
+    seed.add_argument("--year", type=int, default=2024, help="Tax year of the demo return")
+
+    return parser
+
+
+def main(argv: Optional[List[str]] = None, stdout: Optional[TextIO] = None, stderr: Optional[TextIO] = None) -> int:
+    """
+    Run a command
+
+    Results are printed as JSON; errors go to stderr.
+
+    Returns:
+        Exit code (0 on success, 2 when the app is locked)
+    """
+    stdout = stdout or sys.stdout
+    stderr = stderr or sys.stderr
+    args = build_parser().parse_args(argv)
+    if args.demo:
+        if args.data_dir:
+            print("Error: --demo can't be combined with --data-dir", file=stderr)
+            return EXIT_ERROR
+        return _run_demo(args, stdout, stderr)
+    if args.data_dir:
+        os.chdir(args.data_dir)
+    return _run(args, stdout, stderr)
+
+
+def _run_demo(args: argparse.Namespace, stdout: TextIO, stderr: TextIO) -> int:
+    """Run a command against freshly seeded demo data, leaving the real data folder untouched"""
+    cwd = os.getcwd()
+    with tempfile.TemporaryDirectory(prefix="tax_demo_") as demo_dir:
+        os.chdir(demo_dir)
+        try:
+            demo = seed_demo_data(ReturnStore())
+            if getattr(args, "return_id", None) == "demo":
+                args.return_id = demo["return_id"]
+            return _run(args, stdout, stderr)
+        finally:
+            os.chdir(cwd)
+
+
+def _run(args: argparse.Namespace, stdout: TextIO, stderr: TextIO) -> int:
+    action = _audit_action(args)
+    try:
+        _unlock(args.passphrase or os.getenv(PASSPHRASE_ENV_VAR))
+        result = COMMANDS[args.command](args)
+    except CliError as e:
+        if action:
+            outcome = "denied" if e.exit_code == EXIT_LOCKED else "failed"
+            AuditLog().record(action, _audit_target(args), outcome, source="cli", detail=str(e))
+        print(f"Error: {e}", file=stderr)
+        return e.exit_code
+    if action:
+        AuditLog().record(action, _audit_target(args), "success", source="cli")
+    print(json.dumps(result, indent=2, default=str), file=stdout)
+    return 0
+
+
+def _audit_action(args: argparse.Namespace) -> Optional[str]:
+    """The audit log action a command counts as, or None if it isn't logged"""
+    if args.command == "export":
+        # e-file XML carries the IP PINs and the refund account in full
+        return "reveal" if args.format == "efile" else "export"
+    if args.command in ("export-data", "backup"):
+        return "export"
+    if args.command == "import-data":
+        return "import"
+    if args.command == "lock-settings" and args.action == "upgrade":
+        return "passphrase_change"
+    if args.command == "ip-pin" and args.action == "show" and args.reveal:
+        return "reveal"
+    if getattr(args, "action", None) in ("delete", "clear"):
+        return "deletion"
+    return None
+
+
+def _audit_target(args: argparse.Namespace) -> str:
+    """The command that was run, without its option values (which can hold PINs and passphrases)"""
+    parts = [args.command, getattr(args, "action", None), getattr(args, "return_id", None)]
+    return " ".join(part for part in parts if part)
+
+
+def _unlock(passphrase: Optional[str]) -> None:
+    """Commands run only once the app lock (if one is set) is opened"""
+    lock = AppLock()
+    if not lock.enabled:
+        return
+    if not passphrase:
+        raise CliError(f"The app is locked; pass --passphrase or set {PASSPHRASE_ENV_VAR}", EXIT_LOCKED)
+    try:
+        lock.unlock(passphrase)
+    except ValueError as e:
+        raise CliError(str(e), EXIT_LOCKED)
+
+
+def _get_return(store: ReturnStore, return_id: str) -> Dict[str, Any]:
+    try:
+        tax_return = store.get_return(return_id)
+    except ValueError:
+        tax_return = None
+    if tax_return is None:
+        raise CliError(f"Return not found: {return_id}")
+    return tax_return
+
+
+def _parse_amounts(values: List[str], allowed: List[str], label: str) -> List[Tuple[str, float]]:
+    """Parse NAME=AMOUNT arguments"""
+    parsed = []
+    for value in values:
+        name, _, amount = value.partition("=")
+        if name not in allowed:
+            raise CliError(f"Invalid {label}: {name}. Must be one of: {', '.join(allowed)}")
+        try:
+            parsed.append((name, float(amount)))
+        except ValueError:
+            raise CliError(f"Invalid amount in {value!r}")
+    return parsed
+
+
+def cmd_returns(args: argparse.Namespace) -> List[Dict[str, Any]]:
+    return ReturnStore().list_returns()
+
+
+def cmd_calculate(args: argparse.Namespace) -> Dict[str, Any]:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    incomes = _parse_amounts(args.add_income, ReturnStore.INCOME_TYPES, "income type")
+    deductions = _parse_amounts(args.add_deduction, ReturnStore.DEDUCTION_CATEGORIES, "deduction category")
+    what_if = bool(incomes or deductions or args.filing_status)
+    if what_if and args.save:
+        raise CliError("--save can't be combined with what-if changes")
+
+    scenario = copy.deepcopy(tax_return)
+    for index, (source_type, amount) in enumerate(incomes, start=1):
+        scenario.setdefault("income_sources", []).append(
+            {"id": f"whatif_{index}", "type": source_type, "amount": amount, "withholding": 0, "description": "What-if"}
+        )
+    for index, (category, amount) in enumerate(deductions, start=1):
+        scenario.setdefault("deductions", []).append(
+            {"id": f"whatif_{index}", "category": category, "amount": amount, "description": "What-if"}
+        )
+    if args.filing_status:
+        scenario["filing_status"] = args.filing_status
+
+    rounding = SettingsStore().get_settings().rounding_policy
+    try:
+        calculation = calculate_return(scenario, rounding=rounding)
+        if args.save:
+            store.save_calculation(args.return_id, calculation)
+    except ValueError as e:
+        raise CliError(str(e))
+
+    if not what_if:
+        return calculation
+    baseline = calculate_return(tax_return, rounding=rounding)
+    return {
+        "baseline": baseline,
+        "what_if": calculation,
+        "total_tax_change": round(calculation["total_tax"] - baseline["total_tax"], 2),
+        "refund_or_owed_change": round(calculation["refund_or_owed"] - baseline["refund_or_owed"], 2),
+    }
+
+
+def cmd_export(args: argparse.Namespace) -> Dict[str, Any]:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    stem = f"{args.return_id}_{tax_return['tax_year']}"
+    formatter = Formatter.from_settings(SettingsStore().get_settings())
+
+    try:
+        if args.format == "json":
+            content = json.dumps(tax_return, indent=2, ensure_ascii=False).encode("utf-8")
+            filename = f"{stem}.json"
+        elif args.format == "package":
+            content = build_return_package(tax_return, formatter=formatter)
+            filename = f"{stem}.zip"
+        elif args.format == "efile":
+            direct_deposit = store.get_direct_deposit(args.return_id, reveal=True)
+            ip_pins = store.get_ip_pins(args.return_id, reveal=True)
+            content = build_return_xml(
+                tax_return, calculate_return(tax_return), direct_deposit=direct_deposit, ip_pins=ip_pins
+            )
+            errors = validate_return_xml(content)
+            if errors:
+                raise CliError("Return failed e-file validation: " + "; ".join(errors))
+            filename = f"{stem}_mef.xml"
+        elif args.format == "csv":
+            content = build_return_csv(tax_return, formatter=formatter).encode("utf-8")
+            filename = f"{stem}_ledger.csv"
+        elif args.format == "anonymized":
+            content = json.dumps(anonymize_return(tax_return), indent=2, ensure_ascii=False).encode("utf-8")
+            filename = f"{stem}_anonymized.json"
+        else:
+            prior_return = None
+            if tax_return.get("cloned_from"):
+                prior_return = store.get_return(tax_return["cloned_from"])
+            content = build_review_packet(
+                tax_return, DocumentStore(), prior_return,
+                direct_deposit=store.get_direct_deposit(args.return_id),
+                ip_pins=store.get_ip_pins(args.return_id), formatter=formatter,
+            )
+            filename = f"{stem}_review.pdf"
+    except ValueError as e:
+        raise CliError(str(e))
+
+    output = args.output or filename
+    with open(output, "wb") as f:
+        f.write(content)
+    return {"format": args.format, "path": os.path.abspath(output), "bytes": len(content)}
+
+
+def cmd_withholding_checkup(args: argparse.Namespace) -> Dict[str, Any]:
+    tax_return = _get_return(ReturnStore(), args.return_id)
+    try:
+        result = withholding_checkup(
+            tax_return,
+            ytd_wages=Decimal(str(args.ytd_wages)),
+            ytd_withholding=Decimal(str(args.ytd_withholding)),
+            periods_remaining=args.periods_remaining,
+            pay_frequency=args.pay_frequency,
+            owner=args.owner,
+            target_refund=Decimal(str(args.target_refund)),
+        )
+    except ValueError as e:
+        raise CliError(str(e))
+    return round_amounts(result, SettingsStore().get_settings().rounding_policy)
+
+
+def cmd_profit_loss(args: argparse.Namespace) -> Dict[str, Any]:
+    tax_return = _get_return(ReturnStore(), args.return_id)
+    business = next((b for b in tax_return.get("businesses", []) if b["id"] == args.business_id), None)
+    if business is None:
+        raise CliError(f"Business not found: {args.business_id}")
+    settings = SettingsStore().get_settings()
+    try:
+        report = business_profit_loss(
+            business, tax_return["income_sources"], tax_return["tax_year"], through_quarter=args.through_quarter
+        )
+    except ValueError as e:
+        raise CliError(str(e))
+    if args.format == "json":
+        return round_amounts(report, settings.rounding_policy)
+
+    formatter = Formatter.from_settings(settings)
+    if args.format == "csv":
+        content = build_profit_loss_csv(report, formatter=formatter).encode("utf-8")
+    else:
+        content = render_profit_loss(report, formatter=formatter)
+    output = args.output or f"{args.return_id}_{tax_return['tax_year']}_{args.business_id}_profit_loss.{args.format}"
+    with open(output, "wb") as f:
+        f.write(content)
+    return {"format": args.format, "path": os.path.abspath(output), "bytes": len(content)}
+
+
+def cmd_invoices(args: argparse.Namespace) -> Any:
+    store = InvoiceStore()
+    try:
+        if args.action == "add":
+            return store.create_invoice(
+                args.client, args.amount, args.issued, paid_date=args.paid,
+                expect_1099=args.expect_1099, description=args.description,
+            )
+        if args.action == "list":
+            return store.list_invoices(year=args.year, client=args.client, status=args.status)
+        if args.action == "update":
+            changes = {
+                field: value for field, value in [
+                    ("client", args.client), ("amount", args.amount), ("issue_date", args.issued),
+                    ("paid_date", args.paid), ("description", args.description),
+                ] if value is not None
+            }
+            if changes.get("paid_date") == "":
+                changes["paid_date"] = None
+            return store.update_invoice(args.invoice_id, **changes)
+        if args.action == "delete":
+            if not store.delete_invoice(args.invoice_id):
+                raise KeyError(args.invoice_id)
+            return {"invoice_id": args.invoice_id, "deleted": True}
+    except KeyError:
+        raise CliError(f"Invoice not found: {args.invoice_id}")
+    except ValueError as e:
+        raise CliError(str(e))
+
+    report = reconcile_invoices(store.list_invoices(), DocumentStore().list_documents(include_archived=True), args.year)
+    return round_amounts(report, SettingsStore().get_settings().rounding_policy)
+
+
+def cmd_platform_income(args: argparse.Namespace) -> Any:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    document_store = DocumentStore()
+    try:
+        if args.action == "allocate":
+            require_1099_k(document_store, args.document_id)
+            return store.set_platform_allocation(
+                args.return_id, args.document_id, args.personal_sales, args.personal_cost, args.business_id
+            )
+    except KeyError as e:
+        raise CliError(e.args[0])
+    except ValueError as e:
+        raise CliError(str(e))
+    report = reconcile_platform_income(tax_return, document_store.list_documents(return_id=args.return_id))
+    return round_amounts(report, SettingsStore().get_settings().rounding_policy)
+
+
+def cmd_hobby_test(args: argparse.Namespace) -> Any:
+    if args.action == "questions":
+        return [{"factor": name, "title": title, "question": question} for name, title, question in HOBBY_FACTORS]
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    if args.action == "list":
+        return tax_return.get("hobby_determinations", [])
+    try:
+        if args.action == "delete":
+            store.delete_hobby_determination(args.return_id, args.determination_id)
+            return {"determination_id": args.determination_id, "deleted": True}
+        answers = dict(value.partition("=")[::2] for value in args.answer)
+        notes = dict(value.partition("=")[::2] for value in args.note)
+        return store.save_hobby_determination(
+            args.return_id, args.activity, answers, notes, args.profit_years, args.horse, args.business_id
+        )
+    except KeyError as e:
+        raise CliError(e.args[0])
+    except ValueError as e:
+        raise CliError(str(e))
+
+
+def cmd_estimated_payments(args: argparse.Namespace) -> Any:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    if args.action == "list":
+        return tax_return.get("estimated_payments", [])
+    if args.action == "reminders":
+        try:
+            as_of = date.fromisoformat(args.as_of) if args.as_of else None
+        except ValueError:
+            raise CliError(f"Invalid date: {args.as_of}")
+        events = [e for e in return_events(tax_return) if e["kind"] == "estimated_payment"]
+        return due_reminders(events, ReminderStore().get_states(), as_of=as_of)
+    try:
+        if args.action == "delete":
+            store.delete_estimated_payment(args.return_id, args.payment_id)
+            return {"payment_id": args.payment_id, "deleted": True}
+        return store.add_estimated_payment(args.return_id, args.quarter, args.amount, args.paid_on, args.description)
+    except KeyError:
+        raise CliError(f"Estimated payment not found: {args.payment_id}")
+    except ValueError as e:
+        raise CliError(str(e))
+
+
+def cmd_dashboard(args: argparse.Namespace) -> Dict[str, Any]:
+    settings = SettingsStore().get_settings()
+    if not settings.practitioner_mode:
+        raise CliError("Practitioner mode is off; turn on practitioner_mode in settings to manage clients")
+    try:
+        as_of = date.fromisoformat(args.as_of) if args.as_of else None
+    except ValueError:
+        raise CliError(f"Invalid date: {args.as_of}")
+    store = ReturnStore()
+    tax_returns = [store.get_return(summary["return_id"]) for summary in store.list_returns()]
+    dashboard = build_dashboard(
+        ClientStore().list_clients(), [r for r in tax_returns if r], DocumentStore().list_documents(),
+        as_of=as_of, within_days=args.within_days,
+    )
+    return round_amounts(dashboard, settings.rounding_policy)
+
+
+def cmd_foreign_accounts(args: argparse.Namespace) -> Any:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    try:
+        if args.action == "add":
+            fields = {
+                name: value for name, value in [
+                    ("year_end_balance", args.year_end_balance), ("account_type", args.account_type), ("owner", args.owner),
+                ] if value is not None
+            }
+            return store.add_foreign_account(args.return_id, args.institution, args.country, args.max_balance, **fields)
+        if args.action == "delete":
+            store.delete_foreign_account(args.return_id, args.account_id)
+            return {"account_id": args.account_id, "deleted": True}
+    except KeyError:
+        raise CliError(f"Foreign account not found: {args.account_id}")
+    except ValueError as e:
+        raise CliError(str(e))
+
+    accounts = tax_return.get("foreign_accounts", [])
+    if args.action == "list":
+        return accounts
+    return check_foreign_accounts(accounts, tax_return["filing_status"], lives_abroad=args.abroad)
+
+
+def cmd_schedule_b(args: argparse.Namespace) -> Any:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    policy = SettingsStore().get_settings().rounding_policy
+    if args.action == "show":
+        return round_amounts(calculate_schedule_b(tax_return), policy)
+    payers = [s for s in tax_return["income_sources"] if s["type"] in ReturnStore.SCHEDULE_B_TYPES]
+    if args.action == "list":
+        return payers
+    if args.action in ("update", "delete") and not any(s["id"] == args.source_id for s in payers):
+        raise CliError(f"Payer not found: {args.source_id}")
+
+    fields = {
+        field: getattr(args, option) for option, field in SCHEDULE_B_FIELDS
+        if getattr(args, option, None) is not None
+    }
+    try:
+        if args.action == "add":
+            description = fields.pop("description")
+            return store.add_income_source(
+                args.return_id, args.type, fields.pop("amount"), description=description,
+                withholding=fields.pop("withholding", 0), **fields,
+            )
+        if args.action == "update":
+            return store.update_income_source(args.return_id, args.source_id, **fields)
+        store.delete_income_source(args.return_id, args.source_id)
+        return {"source_id": args.source_id, "deleted": True}
+    except ValueError as e:
+        raise CliError(str(e))
+
+
+def cmd_early_distribution(args: argparse.Namespace) -> Any:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    policy = SettingsStore().get_settings().rounding_policy
+    if args.action == "form":
+        return round_amounts(calculate_form_5329(tax_return), policy)
+    if not any(s["id"] == args.source_id and s["type"] == "retirement" for s in tax_return["income_sources"]):
+        raise CliError(f"Retirement distribution not found: {args.source_id}")
+    try:
+        if args.action == "claim":
+            changes = {
+                name: getattr(args, name)
+                for name in ("distribution_code", "plan_type", "exception_code", "exception_amount")
+                if getattr(args, name) is not None
+            }
+            if not changes:
+                raise CliError("Give the code, plan type, or exception to record")
+            return store.update_income_source(args.return_id, args.source_id, **changes)
+        agi = Decimal(str(calculate_return(tax_return)["agi"]))
+        result = evaluate_exception(
+            tax_return, args.source_id, agi,
+            exception_code=args.exception_code,
+            age_at_separation=args.age_at_separation,
+            public_safety_employee=args.public_safety,
+            weeks_unemployed=args.weeks_unemployed,
+            health_insurance_premiums=Decimal(str(args.premiums)) if args.premiums is not None else None,
+            education_expenses=(
+                Decimal(str(args.education_expenses)) if args.education_expenses is not None else None
+            ),
+            first_home_used_before=Decimal(str(args.first_home_used)),
+            children=args.children,
+        )
+    except ValueError as e:
+        raise CliError(str(e))
+    return round_amounts(result, policy)
+
+
+def cmd_state_refund(args: argparse.Namespace) -> Any:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    try:
+        if args.action == "add":
+            return store.add_income_source(args.return_id, "state_refund", args.amount, description=args.payer)
+        if args.action == "load":
+            return store.load_prior_year_deductions(args.return_id, args.prior_return)
+    except KeyError as e:
+        raise CliError(e.args[0])
+    except ValueError as e:
+        raise CliError(str(e))
+    return round_amounts(state_refund_worksheet(tax_return), SettingsStore().get_settings().rounding_policy)
+
+
+def cmd_rentals(args: argparse.Namespace) -> Any:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    year = tax_return["tax_year"]
+    policy = SettingsStore().get_settings().rounding_policy
+    try:
+        if args.action == "add":
+            fields = {
+                name: value for name, value in [
+                    ("land_value", args.land_value), ("rents", args.rents), ("expenses", args.expenses),
+                ] if value is not None
+            }
+            return store.add_rental_property(args.return_id, args.address, args.building_basis, args.in_service, **fields)
+        if args.action == "sell":
+            rental = store.update_rental_property(
+                args.return_id, args.property_id,
+                date_sold=args.date, sale_price=args.price, selling_expenses=args.selling_expenses,
+            )
+            return round_amounts(rental_property_year(rental, year), policy)
+        if args.action == "exchange":
+            return store.record_like_kind_exchange(
+                args.return_id, args.property_id, args.date, args.address, args.value,
+                replacement_date=args.received, replacement_land_value=args.land_value,
+                boot_received=args.boot, exchange_expenses=args.expenses,
+            )
+    except KeyError:
+        raise CliError(f"Rental property not found: {args.property_id}")
+    except ValueError as e:
+        raise CliError(str(e))
+
+    rentals = tax_return.get("rental_properties", [])
+    if args.action == "list":
+        return round_amounts([{**r, "current_year": rental_property_year(r, year)} for r in rentals], policy)
+    rental = next((r for r in rentals if r["id"] == args.property_id), None)
+    if rental is None:
+        raise CliError(f"Rental property not found: {args.property_id}")
+    schedule = depreciation_schedule(Decimal(str(rental["building_basis"])), rental["placed_in_service"], rental.get("date_sold"))
+    return round_amounts(schedule, policy)
+
+
+def cmd_at_risk(args: argparse.Namespace) -> Any:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    try:
+        if args.action == "set":
+            return store.set_at_risk(args.return_id, args.activity_id, at_risk_amount=args.amount,
+                                     carryforward=args.carryforward)
+        return round_amounts(calculate_at_risk(tax_return), SettingsStore().get_settings().rounding_policy)
+    except KeyError:
+        raise CliError(f"Activity not found: {args.activity_id}")
+    except ValueError as e:
+        raise CliError(str(e))
+
+
+def cmd_crypto(args: argparse.Namespace) -> Any:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    try:
+        if args.action == "receive":
+            return store.add_crypto_receipt(
+                args.return_id, args.kind, args.asset, args.quantity, args.value, args.date,
+                business_id=args.business_id, owner=args.owner,
+            )
+        if args.action == "sell":
+            return store.dispose_crypto(args.return_id, args.asset, args.quantity, args.proceeds, args.date)
+    except ValueError as e:
+        raise CliError(str(e))
+    return tax_return.get("crypto_lots", [])
+
+
+def cmd_holdings(args: argparse.Namespace) -> Any:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    try:
+        if args.action == "add":
+            return store.add_holding(args.return_id, args.symbol, args.quantity, args.basis, args.acquired,
+                                     market_value=args.value)
+        if args.action == "value":
+            return store.update_holding(args.return_id, args.holding_id, market_value=args.market_value)
+        if args.action == "delete":
+            store.delete_holding(args.return_id, args.holding_id)
+            return {"holding_id": args.holding_id, "deleted": True}
+    except KeyError:
+        raise CliError(f"Holding not found: {args.holding_id}")
+    except ValueError as e:
+        raise CliError(str(e))
+    return tax_return.get("holdings", [])
+
+
+def cmd_harvest_losses(args: argparse.Namespace) -> Dict[str, Any]:
+    tax_return = _get_return(ReturnStore(), args.return_id)
+    try:
+        as_of = date.fromisoformat(args.as_of) if args.as_of else None
+    except ValueError:
+        raise CliError(f"Invalid date: {args.as_of}")
+    try:
+        result = harvesting_suggestions(tax_return, tax_return.get("holdings", []), as_of=as_of)
+    except ValueError as e:
+        raise CliError(str(e))
+    return round_amounts(result, SettingsStore().get_settings().rounding_policy)
+
+
+def cmd_mileage(args: argparse.Namespace) -> Any:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    try:
+        if args.action == "add":
+            fields = {"date": args.date} if args.date else {}
+            if args.parking_tolls:
+                fields["parking_tolls"] = args.parking_tolls
+            return store.add_personal_mileage(args.return_id, args.category, args.miles, purpose=args.purpose, **fields)
+        if args.action == "delete":
+            store.delete_personal_mileage(args.return_id, args.trip_id)
+            return {"trip_id": args.trip_id, "deleted": True}
+        deductions = mileage_deductions(tax_return)
+    except KeyError:
+        raise CliError(f"Trip not found: {args.trip_id}")
+    except ValueError as e:
+        raise CliError(str(e))
+    return {
+        "trips": tax_return.get("mileage_log", []),
+        "deductions": round_amounts(deductions, SettingsStore().get_settings().rounding_policy),
+    }
+
+
+def cmd_dependents(args: argparse.Namespace) -> Any:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    try:
+        if args.action == "test":
+            return qualify_dependents(tax_return)
+        fields = {k: getattr(args, k) for k in DEPENDENT_FIELDS if getattr(args, k) is not None}
+        if args.action == "add":
+            return store.add_dependent(args.return_id, fields.pop("name"), fields.pop("relationship"), **fields)
+        return store.update_dependent(args.return_id, args.dependent_id, **fields)
+    except KeyError:
+        raise CliError(f"Dependent not found: {args.dependent_id}")
+    except ValueError as e:
+        raise CliError(str(e))
+
+
+def cmd_divorce(args: argparse.Namespace) -> Any:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    try:
+        if args.action == "set":
+            return store.set_divorce(
+                args.return_id, args.decree_date, args.decree_type, args.separated_date, args.paid_over_half_home_cost
+            )
+        if args.action == "alimony":
+            fields = {"recipient_ssn": args.recipient_ssn} if args.recipient_ssn else {}
+            if args.modified_to_adopt_new_rule:
+                fields["modified_to_adopt_new_rule"] = True
+            return store.add_alimony(args.return_id, args.direction, args.amount, args.instrument_date, **fields)
+        report = divorce_year_report(tax_return)
+    except ValueError as e:
+        raise CliError(str(e))
+    return round_amounts(report, SettingsStore().get_settings().rounding_policy)
+
+
+def cmd_decedent(args: argparse.Namespace) -> Any:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    try:
+        if args.action == "set":
+            return store.set_death(
+                args.return_id, args.deceased, args.date_of_death, args.personal_representative,
+                args.remarried_date, args.paid_over_half_home_cost,
+            )
+        return decedent_filing_options(tax_return)
+    except ValueError as e:
+        raise CliError(str(e))
+
+
+def cmd_injured_spouse(args: argparse.Namespace) -> Dict[str, Any]:
+    tax_return = _get_return(ReturnStore(), args.return_id)
+    try:
+        result = injured_spouse_allocation(
+            tax_return,
+            injured_spouse=args.injured,
+            past_due_debt=Decimal(str(args.past_due_debt)) if args.past_due_debt is not None else None,
+        )
+    except ValueError as e:
+        raise CliError(str(e))
+    return round_amounts(result, SettingsStore().get_settings().rounding_policy)
+
+
+def cmd_ip_pin(args: argparse.Namespace) -> Any:
+    store = ReturnStore()
+    _get_return(store, args.return_id)
+    try:
+        if args.action == "set":
+            return store.set_ip_pin(args.return_id, args.person, args.pin, args.valid_year)
+        if args.action == "clear":
+            if not store.clear_ip_pin(args.return_id, args.person):
+                raise CliError(f"No IP PIN for the {args.person} on return {args.return_id}")
+            return {"return_id": args.return_id, "person": args.person, "cleared": True}
+        return store.get_ip_pins(args.return_id, reveal=args.reveal)
+    except ValueError as e:
+        raise CliError(str(e))
+
+
+def cmd_peer_comparison(args: argparse.Namespace) -> Dict[str, Any]:
+    tax_return = _get_return(ReturnStore(), args.return_id)
+    try:
+        result = compare_with_peers(tax_return, load_soi_dataset(args.dataset))
+    except ValueError as e:
+        raise CliError(str(e))
+    return round_amounts(result, SettingsStore().get_settings().rounding_policy)
+
+
+def cmd_students(args: argparse.Namespace) -> Any:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    try:
+        if args.action == "add":
+            fields = {"dependent_id": args.dependent_id, "institution": args.institution}
+            return store.add_student(
+                args.return_id, args.name, **{k: v for k, v in fields.items() if v},
+                half_time=not args.less_than_half_time, graduate=args.graduate, aotc_years_claimed=args.aotc_years,
+            )
+        if args.action == "expense":
+            fields = {"date": args.date} if args.date else {}
+            if args.paid_to_institution:
+                fields["paid_to_institution"] = True
+            return store.add_education_expense(
+                args.return_id, args.student_id, args.kind, args.amount, args.description, **fields
+            )
+        if args.action == "import-1098t":
+            try:
+                with open(args.path, "r", encoding="utf-8") as f:
+                    text = f.read()
+            except OSError as e:
+                raise CliError(f"Can't read {args.path}: {e}")
+            extraction = parse_1098_t(text).to_dict()
+            return {
+                "student": store.import_1098t(args.return_id, args.student_id, extraction),
+                "warnings": extraction["warnings"],
+            }
+    except KeyError:
+        raise CliError(f"Student not found: {args.student_id}")
+    except ValueError as e:
+        raise CliError(str(e))
+    return tax_return.get("students", [])
+
+
+def cmd_education_credits(args: argparse.Namespace) -> Dict[str, Any]:
+    tax_return = _get_return(ReturnStore(), args.return_id)
+    try:
+        calculation = calculate_return(tax_return)
+        credits = calculation["education_credits"] or calculate_education_credits(
+            [], Decimal(str(calculation["agi"])), tax_return["filing_status"], tax_return["tax_year"]
+        )
+    except ValueError as e:
+        raise CliError(str(e))
+    return round_amounts(credits, SettingsStore().get_settings().rounding_policy)
+
+
+def cmd_adoptions(args: argparse.Namespace) -> Any:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    if args.action == "list":
+        return tax_return.get("adoptions", [])
+    try:
+        if args.action == "credit":
+            calculation = calculate_return(tax_return)
+            credit = calculation["adoption_credit"] or calculate_adoption_credit(
+                [], Decimal(str(calculation["agi"])), tax_return["filing_status"], tax_return["tax_year"]
+            )
+            return round_amounts(credit, SettingsStore().get_settings().rounding_policy)
+        if args.action == "delete":
+            store.delete_adoption(args.return_id, args.adoption_id)
+            return {"adoption_id": args.adoption_id, "deleted": True}
+        fields = {
+            name: getattr(args, name)
+            for name in ("name", "expenses", "prior_year_expenses", "credit_claimed_before", "final_year", "foreign",
+                         "special_needs")
+            if getattr(args, name) is not None
+        }
+        if args.action == "add":
+            return store.add_adoption(args.return_id, fields.pop("name"), **fields)
+        return store.update_adoption(args.return_id, args.adoption_id, **fields)
+    except KeyError:
+        raise CliError(f"Adoption not found: {args.adoption_id}")
+    except ValueError as e:
+        raise CliError(str(e))
+
+
+def cmd_charitable_bunching(args: argparse.Namespace) -> Dict[str, Any]:
+    tax_return = _get_return(ReturnStore(), args.return_id)
+    try:
+        result = compare_bunching(
+            tax_return,
+            annual_donation=Decimal(str(args.annual_donation)) if args.annual_donation is not None else None,
+            years=args.years,
+        )
+    except ValueError as e:
+        raise CliError(str(e))
+    return round_amounts(result, SettingsStore().get_settings().rounding_policy)
+
+
+def cmd_relocation(args: argparse.Namespace) -> Dict[str, Any]:
+    tax_return = _get_return(ReturnStore(), args.return_id)
+
+    def optional(value: Optional[float]) -> Optional[Decimal]:
+        return Decimal(str(value)) if value is not None else None
+
+    try:
+        result = compare_relocation(
+            tax_return,
+            args.to_state,
+            from_state=args.from_state,
+            local_tax_rate=Decimal(str(args.local_rate)),
+            new_local_tax_rate=Decimal(str(args.new_local_rate)),
+            property_tax=optional(args.property_tax),
+            new_property_tax=optional(args.new_property_tax),
+        )
+    except ValueError as e:
+        raise CliError(str(e))
+    return round_amounts(result, SettingsStore().get_settings().rounding_policy)
+
+
+def cmd_raise(args: argparse.Namespace) -> Dict[str, Any]:
+    tax_return = _get_return(ReturnStore(), args.return_id)
+
+    def optional(value: Optional[float]) -> Optional[Decimal]:
+        return Decimal(str(value)) if value is not None else None
+
+    try:
+        result = raise_after_tax(
+            tax_return,
+            raise_amount=optional(args.amount),
+            new_salary=optional(args.new_salary),
+            owner=args.owner,
+            state=args.state,
+            local_tax_rate=Decimal(str(args.local_rate)),
+        )
+    except ValueError as e:
+        raise CliError(str(e))
+    return round_amounts(result, SettingsStore().get_settings().rounding_policy)
+
+
+def cmd_residency(args: argparse.Namespace) -> Any:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    try:
+        if args.action == "add":
+            return store.add_travel_days(args.return_id, args.state, args.start, args.end, note=args.note)
+        if args.action == "delete":
+            store.delete_travel_days(args.return_id, args.entry_id)
+            return {"entry_id": args.entry_id, "deleted": True}
+    except KeyError:
+        raise CliError(f"Travel log entry not found: {args.entry_id}")
+    except ValueError as e:
+        raise CliError(str(e))
+
+    try:
+        as_of = date.fromisoformat(args.as_of) if args.as_of else None
+    except ValueError:
+        raise CliError(f"Invalid date: {args.as_of}")
+    return return_residency_days(tax_return, home_state=args.home_state, as_of=as_of)
+
+
+def cmd_document_requests(args: argparse.Namespace) -> Dict[str, Any]:
+    store = ReturnStore()
+    tax_return = _get_return(store, args.return_id)
+    if args.prior_return:
+        _get_return(store, args.prior_return)
+    items = document_requests(tax_return, *return_documents(DocumentStore(), tax_return, args.prior_return))
+    try:
+        store.save_document_requests(args.return_id, items)
+    except ValueError as e:
+        raise CliError(str(e))
+    return {
+        "requests": items,
+        "received": sum(1 for item in items if item["received"]),
+        "outstanding": [item["title"] for item in items if not item["received"]],
+    }
+
+
+def cmd_backup(args: argparse.Namespace) -> Dict[str, Any]:
+    return create_backup(args.dest)
+
+
+def cmd_verify_backup(args: argparse.Namespace) -> Dict[str, Any]:
+    try:
+        result = verify_backup(args.archive, passphrase=args.passphrase or os.getenv(PASSPHRASE_ENV_VAR))
+    except (OSError, ValueError) as e:
+        raise CliError(str(e))
+    if not result["valid"]:
+        problems = "; ".join(f"{p['file']}: {p['problem']}" for p in result["problems"])
+        raise CliError(f"Backup failed verification: {problems}")
+    return result
+
+
+def cmd_lock_settings(args: argparse.Namespace) -> Dict[str, Any]:
+    lock = AppLock()
+    try:
+        if args.action == "show":
+            return lock.kdf_settings()
+        return lock.upgrade_kdf(
+            args.passphrase or os.getenv(PASSPHRASE_ENV_VAR) or "", algorithm=args.algorithm, iterations=args.iterations
+        )
+    except ValueError as e:
+        raise CliError(str(e))
+
+
+def _stores() -> Tuple[ReturnStore, DocumentStore, SettingsStore, ConversationStore, ReminderStore]:
+    return ReturnStore(), DocumentStore(), SettingsStore(), ConversationStore(), ReminderStore()
+
+
+def cmd_export_data(args: argparse.Namespace) -> Dict[str, Any]:
+    envelope = export_data(*_stores(), include_documents=args.include_documents)
+    with open(args.output, "w", encoding="utf-8") as f:
+        json.dump(envelope, f, indent=2, ensure_ascii=False)
+    return {"path": os.path.abspath(args.output), "counts": envelope["counts"]}
+
+
+def cmd_import_data(args: argparse.Namespace) -> Dict[str, Any]:
+    try:
+        with open(args.path, "r", encoding="utf-8") as f:
+            envelope = json.load(f)
+    except (OSError, json.JSONDecodeError) as e:
+        raise CliError(f"Can't read {args.path}: {e}")
+    try:
+        return import_data(
+            envelope, *_stores(), on_conflict=args.on_conflict, include_settings=not args.skip_settings
+        )
+    except ValueError as e:
+        raise CliError(str(e))
+
+
+def cmd_security_events(args: argparse.Namespace) -> List[Dict[str, Any]]:
+    return AuditLog().entries(limit=args.limit, since=args.since, action=args.action, outcome=args.outcome)
+
+
+def cmd_settings_history(args: argparse.Namespace) -> List[Dict[str, Any]]:
+    try:
+        return SettingsStore().get_settings_history(limit=args.limit, field=args.field)
+    except ValueError as e:
+        raise CliError(str(e))
+
+
+def cmd_scenarios(args: argparse.Namespace) -> Dict[str, Any]:
+    try:
+        scenarios = load_scenarios(args.dir)
+    except (OSError, ValueError) as e:
+        raise CliError(str(e))
+    results = []
+    for scenario in scenarios:
+        try:
+            result = run_scenario(scenario)
+        except (KeyError, ValueError) as e:
+            raise CliError(f"{scenario['file']}: {e}")
+        results.append({"file": scenario["file"], "name": result["name"], "mismatches": result["mismatches"]})
+    failed = [r["file"] for r in results if r["mismatches"]]
+    if failed:
+        raise CliError(f"Scenarios don't match their expected results: {', '.join(failed)}")
+    return {"passed": len(results), "scenarios": results}
+
+
+def cmd_seed_demo(args: argparse.Namespace) -> Dict[str, Any]:
+    return seed_demo_data(ReturnStore(), tax_year=args.year)
+
+
+def cmd_diagnostics(args: argparse.Namespace) -> Dict[str, Any]:
+    returns, documents, settings, conversations, reminders = _stores()
+    report = generate_diagnostics(
+        __version__, returns, documents, conversations, settings, reminders,
+        job_queue=JobQueue(), app_lock=AppLock(), log_entries=get_recent_logs(),
+    )
+    with open(args.output, "w", encoding="utf-8") as f:
+        json.dump(report, f, indent=2)
+    return {"path": os.path.abspath(args.output), "counts": report["counts"]}
+
+
+COMMANDS = {
+    "returns": cmd_returns,
+    "calculate": cmd_calculate,
+    "export": cmd_export,
+    "withholding-checkup": cmd_withholding_checkup,
+    "profit-loss": cmd_profit_loss,
+    "invoices": cmd_invoices,
+    "platform-income": cmd_platform_income,
+    "hobby-test": cmd_hobby_test,
+    "estimated-payments": cmd_estimated_payments,
+    "dashboard": cmd_dashboard,
+    "foreign-accounts": cmd_foreign_accounts,
+    "schedule-b": cmd_schedule_b,
+    "early-distribution": cmd_early_distribution,
+    "state-refund": cmd_state_refund,
+    "rentals": cmd_rentals,
+    "at-risk": cmd_at_risk,
+    "crypto": cmd_crypto,
+    "holdings": cmd_holdings,
+    "harvest-losses": cmd_harvest_losses,
+    "mileage": cmd_mileage,
+    "dependents": cmd_dependents,
+    "divorce": cmd_divorce,
+    "decedent": cmd_decedent,
+    "injured-spouse": cmd_injured_spouse,
+    "ip-pin": cmd_ip_pin,
+    "peer-comparison": cmd_peer_comparison,
+    "students": cmd_students,
+    "education-credits": cmd_education_credits,
+    "adoptions": cmd_adoptions,
+    "charitable-bunching": cmd_charitable_bunching,
+    "relocation": cmd_relocation,
+    "raise": cmd_raise,
+    "residency": cmd_residency,
+    "document-requests": cmd_document_requests,
+    "backup": cmd_backup,
+    "verify-backup": cmd_verify_backup,
+    "lock-settings": cmd_lock_settings,
+    "export-data": cmd_export_data,
+    "import-data": cmd_import_data,
+    "diagnostics": cmd_diagnostics,
+    "security-events": cmd_security_events,
+    "settings-history": cmd_settings_history,
+    "scenarios": cmd_scenarios,
+    "seed-demo": cmd_seed_demo,
+}
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -4,26 +4,130 @@ FastAPI backend with real tax calculations and proper security
 """
 from contextlib import asynccontextmanager
 
-from fastapi import FastAPI, HTTPException, Request, WebSocket, WebSocketDisconnect
+from fastapi import FastAPI, File, Form, HTTPException, Query, Request, UploadFile, WebSocket, WebSocketDisconnect
+from fastapi.encoders import jsonable_encoder
+from fastapi.exceptions import RequestValidationError
 from fastapi.middleware.cors import CORSMiddleware
 from fastapi.responses import JSONResponse
-from pydantic import BaseModel, Field, field_validator
-from typing import Dict, List, Any, Optional
+from fastapi.routing import APIRoute
+from starlette.exceptions import HTTPException as StarletteHTTPException
+from pydantic import BaseModel, Field, ValidationError, field_validator
+from typing import Dict, List, Any, Optional, Tuple
 from decimal import Decimal
 import os
 import time
+import base64
+import asyncio
 import logging
 from collections import defaultdict
-from datetime import datetime, timedelta
+from functools import lru_cache
+from datetime import date, datetime, timedelta
 
 from app.tax_engine.tax_calculator import TaxCalculator, FilingStatus
+from app.tax_engine.validation import summarize_return, validate_return
+from app.tax_engine.risk import assess_audit_risk
+from app.tax_engine.residency import return_residency_days
+from app.tax_engine.raise_calculator import RAISE_OWNERS, raise_after_tax
+from app.tax_engine.hobby_loss import HOBBY_FACTORS
+from app.tax_engine.relocation import compare_relocation
+from app.tax_engine.foreign_accounts import check_foreign_accounts
+from app.tax_engine.schedule_b import calculate_schedule_b
+from app.tax_engine.early_distributions import calculate_form_5329, evaluate_exception
+from app.tax_engine.state_refund import state_refund_worksheet
+from app.tax_engine.tax_loss_harvesting import harvesting_suggestions
+from app.tax_engine.charitable_bunching import compare_bunching
+from app.tax_engine.education_credits import calculate_education_credits
+from app.tax_engine.adoption_credit import calculate_adoption_credit
+from app.tax_engine.dependents import qualify_dependents
+from app.tax_engine.decedent import decedent_filing_options
+from app.tax_engine.divorce import divorce_year_report
+from app.tax_engine.rental_depreciation import depreciation_schedule, rental_property_year
+from app.tax_engine.withholding_checkup import PAY_PERIODS, withholding_checkup
+from app.i18n import SUPPORTED_LOCALES, catalog, display_names, normalize_locale
+from app.tax_engine.premium_tax_credit import PremiumTaxCreditCalculator
+from app.tax_engine.carryforwards import next_year_carryforwards
+from app.tax_engine.return_calculation import calculate_at_risk, calculate_business_schedules, calculate_return
+from app.tax_engine.state_tax import SUPPORTED_STATES, StateTaxCalculator
+from app.tax_engine.filing_comparison import compare_filing_separately
+from app.tax_engine.injured_spouse import injured_spouse_allocation
+from app.tax_engine.peer_comparison import compare_with_peers
+from app.tax_engine.rounding import round_amounts
+from app.tax_engine.vehicle_expenses import compare_vehicle_methods, mileage_deductions
+from app.tax_engine.donation_valuation import build_donation_batch, valuation_guide
+from app.tax_engine.profit_loss import business_profit_loss
+from app.tax_engine.deadlines import DEFAULT_REMINDER_DAYS, return_deadlines, upcoming_deadlines
+from app.tax_engine.tax_calendar import due_reminders, return_events
 from app.agents.tax_prep_agent import TaxPreparationAgent
 from app.agents.audit_agent import AuditDefenseAgent
 from app.agents.document_agent import DocumentAnalysisAgent
 from app.agents.voice_agent import VoiceAgent
+from app.agents.interview_agent import InterviewAgent
+from app import __version__
+from app.errors import (
+    AIServiceError,
+    AppError,
+    ConflictError,
+    ErrorKind,
+    InvalidInputError,
+    NotFoundError,
+    NotUnlockedError,
+    ReadOnlyError,
+    ReauthRequiredError,
+    StorageError,
+    error_body,
+    error_kind,
+)
+from app.services.diagnostics import generate_diagnostics
+from app.services.formatting import Formatter
+from app.services.invoice_reconciliation import reconcile_invoices
+from app.services.platform_reconciliation import reconcile_platform_income, require_1099_k
+from app.services.practice_dashboard import build_dashboard
+from app.services.job_queue import DEFAULT_MAX_ATTEMPTS, JobQueue
+from app.services.onboarding import Onboarding
+from app.services.progress import JobCancelled, ProgressBus
+from app.utils.activity_log import ActivityLog
+from app.utils.audit_log import AUDIT_ACTIONS, AUDIT_OUTCOMES, AuditLog
+from app.utils.anonymize import anonymize_return
+from app.utils.app_lock import AppLock
+from app.utils.app_log import LOG_LEVELS, configure_logging, get_recent_logs
+from app.utils.backup import create_backup
+from app.utils.data_transfer import CONFLICT_STRATEGIES, export_data, import_data
+from app.utils.field_encryption import DecryptionError, mask_ssns
+from app.utils.return_store import ReturnLockedError, ReturnStore
+from app.utils.conversation_store import ConversationStore
+from app.utils.embeddings import EmbeddingProvider, HttpEmbeddingProvider
+from app.utils.reminder_store import ReminderStore
+from app.utils.invoice_store import InvoiceStore
+from app.utils.client_store import ENGAGEMENT_STATUSES, ClientStore
+from app.utils.secret_store import API_KEY_NAME, SecretStore, api_key_status, get_api_key
+from app.utils.settings_store import AppSettings, SettingsStore
+from app.utils.document_store import DocumentStore, DuplicateDocumentError
+from app.agents.prompts import validate_prompt_addendum
+from app.documents.parsers import parse_document
+from app.documents.parsers.form_1095_a import parse_1095_a
+from app.documents.parsers.form_1098_t import parse_1098_t
+from app.documents.previews import PreviewUnavailableError, generate_thumbnail, page_count, render_page
+from app.documents.bulk_import import bulk_import, infer_document_type
+from app.documents.extraction import extract_document
+from app.documents.inbox import InboxWatcher
+from app.documents.broker_csv import parse_broker_csv
+from app.documents.document_requests import document_requests, return_documents
+from app.documents.txf import import_txf
+from app.documents.pages import merge_images, split_pdf
+from app.documents.apply_extraction import apply_extraction_to_return, create_deduction_from_receipt
+from app.forms.ledger_csv import build_return_csv
+from app.forms.package import build_return_package, calculate_schedule_a, taxpayer_name
+from app.forms.schedule_a import render_schedule_a
+from app.forms.schedule_c import render_schedule_c, render_schedule_se
+from app.forms.estimated_tax import remaining_payments, render_vouchers
+from app.forms.review_packet import build_review_packet
+from app.forms.profit_loss import build_profit_loss_csv, render_profit_loss
+from app.efile.mef import build_return_xml, validate_return_xml
+
+APP_VERSION = __version__
 
-# Configure logging
-logging.basicConfig(level=logging.INFO)
+# Configure logging (console plus rotating, scrubbed files in .logs)
+configure_logging()
 logger = logging.getLogger(__name__)
 
 @asynccontextmanager
@@ -32,29 +136,27 @@ async def lifespan(app: FastAPI):
     logger.info("=" * 60)
     logger.info("AI Tax CPA Agent API - Starting")
     logger.info("=" * 60)
-    logger.info(f"ANTHROPIC_API_KEY configured: {bool(os.getenv('ANTHROPIC_API_KEY'))}")
+    logger.info(f"Anthropic API key: {api_key_status(secret_store)['source'] or 'not configured'}")
     logger.info(f"Environment: {os.getenv('APP_ENV', 'development')}")
     logger.info("=" * 60)
+    if app_lock.unlocked:
+        _migrate_secrets()
+    await _configure_inbox_watcher()
+    await job_queue.start()
     yield
+    await job_queue.stop()
+    if inbox_watcher is not None:
+        await inbox_watcher.stop()
 
 
 # Initialize FastAPI app
 app = FastAPI(
     title="AI Tax CPA Agent",
-    version="1.0.0",
+    version=APP_VERSION,
     description="Production-ready AI tax preparation and CPA services (Demo/Educational)",
     lifespan=lifespan,
 )
 
-# CORS middleware
-app.add_middleware(
-    CORSMiddleware,
-    allow_origins=os.getenv("ALLOWED_ORIGINS", "http://localhost:3000").split(","),
-    allow_credentials=True,
-    allow_methods=["*"],
-    allow_headers=["*"],
-)
-
 
 # ============================================================================
 # RATE LIMITING
@@ -100,7 +202,8 @@ async def rate_limit_middleware(request: Request, call_next):
             status_code=429,
             content={
                 "error": "Rate limit exceeded",
-                "message": "Too many requests. Please try again later."
+                "message": "Too many requests. Please try again later.",
+                **error_body(ErrorKind.RATE_LIMITED, "Too many requests. Please try again later.", datetime.utcnow().isoformat()),
             }
         )
 
@@ -108,174 +211,5331 @@ async def rate_limit_middleware(request: Request, call_next):
     return response
 
 
-# ============================================================================
-# REQUEST/RESPONSE MODELS
-# ============================================================================
+# ============================================================================
+# APP LOCK
+# ============================================================================
+
+app_lock = AppLock()
+
+# API key storage: OS keyring, or an encrypted file where there is none
+secret_store = SecretStore()
+
+# Reachable while locked: health/disclaimer and the lock endpoints themselves
+UNLOCK_EXEMPT_PATHS = {"/", "/api/disclaimer", "/api/lock", "/api/lock/passphrase", "/api/lock/disable", "/api/unlock"}
+
+# Records what is viewed while the app is in review mode
+activity_log = ActivityLog()
+
+# POSTs allowed in review mode: locking/unlocking, leaving review, and calculations that store nothing
+REVIEW_ALLOWED_PATHS = {
+    "/api/lock",
+    "/api/lock/verify",
+    "/api/unlock",
+    "/api/review/end",
+    "/api/tax/calculate",
+    "/api/tax/quarterly",
+    "/api/tax/premium-tax-credit",
+    "/api/documents/parse",
+}
+
+# Not logged as views: polled lock status and the log itself, and endpoints that log their own entry
+REVIEW_UNLOGGED_PATHS = {"/api/lock", "/api/lock/verify", "/api/review/activity", "/api/unlock", "/api/review/end"}
+
+# Records every sensitive request and its outcome
+audit_log = AuditLog()
+
+# OpenAPI extension on a route naming the audit log action its requests count as; set it with audited()
+AUDIT_ACTION_EXTENSION = "x-audit-action"
+
+
+def audited(action: str) -> dict:
+    """Route decorator arguments that record every request to the route in the audit log as this action"""
+    return {"openapi_extra": {AUDIT_ACTION_EXTENSION: action}}
+
+
+# Actions that need the passphrase to have been entered recently: exports, importing over stored data, revealing
+# SSNs, account numbers, and IP PINs, and changing the API key
+REAUTH_ACTIONS = {"export", "import", "reveal", "key_change"}
+
+# Settings that decide where the API key or chat history is sent or how long a passphrase entry lasts; changing
+# them counts as a key change
+REAUTH_SETTINGS = {"reauth_minutes", "ai_base_url", "ai_proxy_url", "embedding_url"}
+
+
+@lru_cache(maxsize=None)
+def audited_routes() -> List[tuple]:
+    """(methods, path pattern, action) for every route tagged with audited(), read from the app's route table"""
+    return [
+        (route.methods, route.path_regex, route.openapi_extra[AUDIT_ACTION_EXTENSION])
+        for route in app.routes
+        if isinstance(route, APIRoute) and AUDIT_ACTION_EXTENSION in (route.openapi_extra or {})
+    ]
+
+
+def audited_action(method: str, path: str) -> Optional[str]:
+    """The audit log action a request counts as, or None if it isn't logged; any other API deletion is logged too"""
+    for methods, pattern, action in audited_routes():
+        if method in methods and pattern.match(path):
+            return action
+    if method == "DELETE" and path.startswith("/api/"):
+        return "deletion"
+    return None
+
+
+def is_sensitive_request(method: str, path: str) -> bool:
+    """Whether a request needs the passphrase to have been entered recently"""
+    return audited_action(method, path) in REAUTH_ACTIONS
+
+
+@app.middleware("http")
+async def review_mode_middleware(request: Request, call_next):
+    """
+    In review mode, refuse every change and every export or reveal, and log every view
+
+    Mutating requests and anything that would need the passphrase re-entered
+    get a 403 with kind "read_only" and are logged as blocked, so the owner
+    can see what was attempted.
+    """
+    path = request.url.path
+    if not app_lock.review_mode or not path.startswith("/api/"):
+        return await call_next(request)
+
+    mutating = request.method not in ("GET", "HEAD", "OPTIONS") and path not in REVIEW_ALLOWED_PATHS
+    if mutating or is_sensitive_request(request.method, path):
+        activity_log.record("blocked", path, method=request.method, status_code=403, query=request.url.query)
+        error = ReadOnlyError(
+            "The app is in review mode; changes and exports are disabled until the owner ends the review."
+        )
+        return JSONResponse(
+            status_code=error.status_code,
+            content=error_body(error.kind, error.detail, datetime.utcnow().isoformat()),
+        )
+
+    response = await call_next(request)
+    if path not in REVIEW_UNLOGGED_PATHS:
+        activity_log.record(
+            "viewed", path, method=request.method, status_code=response.status_code, query=request.url.query
+        )
+    return response
+
+
+@app.middleware("http")
+async def require_unlocked_middleware(request: Request, call_next):
+    """
+    Refuse every API request while the app is locked
+
+    The 423 response carries kind "not_unlocked" and an X-Unlock-Required
+    header so the frontend can show the unlock screen from any call.
+    CORS preflights (OPTIONS) carry no data and always go through.
+    """
+    path = request.url.path
+    if (
+        request.method != "OPTIONS"
+        and path.startswith("/api/")
+        and path not in UNLOCK_EXEMPT_PATHS
+        and not app_lock.unlocked
+    ):
+        error = NotUnlockedError("The app is locked. Enter the passphrase to unlock it.")
+        return JSONResponse(
+            status_code=error.status_code,
+            content=error_body(error.kind, error.detail, datetime.utcnow().isoformat()),
+            headers={"X-Unlock-Required": "true"},
+        )
+    return await call_next(request)
+
+
+@app.middleware("http")
+async def require_recent_auth_middleware(request: Request, call_next):
+    """
+    Ask for the passphrase again before a sensitive request if it was entered too long ago
+
+    Being unlocked isn't enough for exports and API key changes: the
+    passphrase must have been entered within the reauth_minutes setting.
+    The 401 response carries kind "reauth_required" and an
+    X-Reauth-Required header; POST /api/lock/verify clears it. In review
+    mode the review middleware refuses these outright instead.
+    """
+    if (
+        app_lock.unlocked
+        and not app_lock.review_mode
+        and is_sensitive_request(request.method, request.url.path)
+        and not app_lock.verified_within(settings_store.get_settings().reauth_minutes)
+    ):
+        error = ReauthRequiredError("Enter the passphrase again to continue.")
+        return JSONResponse(
+            status_code=error.status_code,
+            content=error_body(error.kind, error.detail, datetime.utcnow().isoformat()),
+            headers={"X-Reauth-Required": "true"},
+        )
+    return await call_next(request)
+
+
+@app.middleware("http")
+async def audit_log_middleware(request: Request, call_next):
+    """
+    Record every sensitive request in the audit log with its outcome
+
+    Runs outside the lock checks, so requests refused because the app is
+    locked or the passphrase is needed again are logged as denied.
+    """
+    action = audited_action(request.method, request.url.path)
+    if action is None:
+        return await call_next(request)
+    response = await call_next(request)
+    if response.status_code < 400:
+        outcome = "success"
+    elif response.status_code in (401, 403, 423):
+        outcome = "denied"
+    else:
+        outcome = "failed"
+    audit_log.record(
+        action, f"{request.method} {request.url.path}", outcome, source="api", detail=str(response.status_code)
+    )
+    return response
+
+
+# CORS middleware, added last so it runs first: preflights are answered before the lock checks, and the
+# frontend can read the rate limit, lock, and re-auth refusals along with their headers
+app.add_middleware(
+    CORSMiddleware,
+    allow_origins=os.getenv("ALLOWED_ORIGINS", "http://localhost:3000").split(","),
+    allow_credentials=True,
+    allow_methods=["*"],
+    allow_headers=["*"],
+    expose_headers=["X-Unlock-Required", "X-Reauth-Required"],
+)
+
+
+# ============================================================================
+# JOB PROGRESS
+# ============================================================================
+
+progress_bus = ProgressBus()
+# Persistent jobs (run by workers started at app startup); they report progress on progress_bus
+job_queue = JobQueue(progress=progress_bus, max_concurrent=int(os.getenv("JOB_WORKERS", "2")))
+
+
+async def _run_job(job_id: str, func, *args, **kwargs) -> Dict[str, Any]:
+    """
+    Run a blocking operation in a worker thread and record its outcome on the job
+
+    The operation should report progress through progress_bus (which is where
+    a cancel request stops it). A ValueError fails the job with its message.
+    """
+    try:
+        result = await asyncio.to_thread(func, *args, **kwargs)
+    except JobCancelled:
+        return progress_bus.finish(job_id)
+    except ValueError as e:
+        return progress_bus.fail(job_id, str(e))
+    except Exception as e:
+        logger.error(f"Job {job_id} failed: {e}", exc_info=True)
+        return progress_bus.fail(job_id, "Internal error")
+    return progress_bus.finish(job_id, result)
+
+
+# ============================================================================
+# REQUEST/RESPONSE MODELS
+# ============================================================================
+
+class TaxReturnRequest(BaseModel):
+    """Request model for tax calculation"""
+    entity_type: str = Field(..., description="Type of entity (1040, 1120, etc.)")
+    gross_income: float = Field(..., gt=0, description="Gross income (must be positive)")
+    filing_status: str = Field(
+        default="single",
+        description="Filing status: single, married_joint, married_separate, head_of_household, qualifying_surviving_spouse"
+    )
+    itemized_deductions: Optional[float] = Field(
+        None, ge=0, description="Itemized deductions if applicable"
+    )
+    dependents: int = Field(default=0, ge=0, description="Number of dependents")
+
+    @field_validator("gross_income")
+    @classmethod
+    def validate_gross_income(cls, v):
+        if v < 0:
+            raise ValueError("Gross income cannot be negative")
+        if v > 1_000_000_000:  # Sanity check
+            raise ValueError("Gross income exceeds reasonable limit")
+        return v
+
+    @field_validator("filing_status")
+    @classmethod
+    def validate_filing_status(cls, v):
+        valid_statuses = [s.value for s in FilingStatus]
+        if v.lower() not in valid_statuses:
+            raise ValueError(f"Filing status must be one of: {', '.join(valid_statuses)}")
+        return v.lower()
+
+
+class DocumentAnalysisRequest(BaseModel):
+    """Request model for document analysis"""
+    document_type: str = Field(..., description="Type of document (W-2, 1099, receipt, etc.)")
+    document_data: Dict[str, Any] = Field(..., description="Document data as JSON")
+    image_base64: Optional[str] = Field(None, description="Base64 encoded image (optional)")
+
+
+class DocumentParseRequest(BaseModel):
+    """Request model for offline document parsing"""
+    document_type: str = Field(..., description="Type of document (W-2, 1099-INT, 1099-DIV, 1099-NEC, 1099-MISC, 1099-K, 1099-B, 1095-A, 1098-T)")
+    text: str = Field(..., min_length=1, max_length=200_000, description="OCR or PDF-extracted text")
+    document_id: Optional[str] = Field(None, description="Stored document to save the extraction on")
+
+
+class AuditDefenseRequest(BaseModel):
+    """Request model for audit defense"""
+    notice_text: str = Field(..., min_length=10, description="IRS audit notice text")
+    client_documents: Dict[str, Any] = Field(default_factory=dict, description="Available client documents")
+
+
+class QuarterlyEstimateRequest(BaseModel):
+    """Request model for quarterly tax estimate"""
+    estimated_annual_income: float = Field(..., gt=0, description="Estimated annual income")
+    filing_status: str = Field(..., description="Filing status")
+    withholding_to_date: float = Field(default=0, ge=0, description="Tax already withheld")
+    self_employment_income: float = Field(
+        default=0, ge=0, description="Net self-employment profit included in the annual income (adds SE tax)"
+    )
+
+
+class PremiumTaxCreditRequest(BaseModel):
+    """Request model for Form 8962 premium tax credit reconciliation"""
+    household_income: float = Field(..., ge=0, le=100_000_000, description="Household modified AGI")
+    family_size: int = Field(..., ge=1, le=20, description="Tax family size")
+    filing_status: str = Field(..., description="Filing status")
+    state: Optional[str] = Field(None, min_length=2, max_length=2, description="State of residence (AK/HI have their own poverty line)")
+    form_1095a_text: List[str] = Field(..., min_length=1, description="OCR/PDF text of each Form 1095-A")
+    married_separate_exception: bool = Field(default=False, description="MFS domestic abuse/abandonment relief")
+
+
+class BulkImportRequest(BaseModel):
+    """Request model for importing a folder of documents"""
+    folder: str = Field(..., min_length=1, description="Folder to import from")
+    default_return_id: Optional[str] = Field(None, description="Return to attach the documents to")
+    recursive: bool = Field(default=True, description="Include subfolders")
+    background: bool = Field(default=False, description="Queue the import as a background job and return the job")
+
+
+class MergeDocumentsRequest(BaseModel):
+    """Request model for merging a duplicate document"""
+    duplicate_id: str = Field(..., description="Duplicate document to fold into this one")
+
+
+class MergePagesRequest(BaseModel):
+    """Request model for combining page images into one document"""
+    document_ids: List[str] = Field(..., min_length=2, max_length=200, description="Image documents in page order")
+    filename: Optional[str] = Field(None, description="Name for the combined PDF")
+
+
+class SplitDocumentRequest(BaseModel):
+    """Request model for splitting a PDF into separate documents"""
+    ranges: Optional[List[Tuple[int, int]]] = Field(
+        None, description="1-based inclusive [first, last] page ranges; one document per page if omitted"
+    )
+
+
+class DocumentNotesRequest(BaseModel):
+    """Request model for a document's notes"""
+    notes: str = Field(..., max_length=10_000, description="Free-form notes (empty clears them)")
+
+
+class AnnotationRequest(BaseModel):
+    """Request model for adding a document annotation"""
+    text: str = Field(..., min_length=1, max_length=2000, description="Annotation text")
+    page: Optional[int] = Field(None, ge=1, description="1-based page the note refers to")
+
+
+class AnnotationUpdateRequest(BaseModel):
+    """Request model for editing a document annotation"""
+    text: Optional[str] = Field(None, min_length=1, max_length=2000, description="New text")
+    page: Optional[int] = Field(None, ge=1, description="New page")
+
+
+class ArchiveDocumentRequest(BaseModel):
+    """Request model for archiving or restoring a document"""
+    archived: bool = Field(default=True, description="False restores the document")
+
+
+class PurgeDocumentsRequest(BaseModel):
+    """Request model for the document retention purge"""
+    retention_years: Optional[int] = Field(None, ge=1, le=100, description="Defaults to the retention setting")
+    export_dir: Optional[str] = Field(None, description="Export files here before purging them")
+    dry_run: bool = Field(default=True, description="Only list what would be purged")
+
+
+class ApplyExtractionRequest(BaseModel):
+    """Request model for applying a document's extracted data to a return"""
+    return_id: str = Field(..., description="Return to apply the data to")
+    preview: bool = Field(default=False, description="Only show the before/after diff")
+
+
+class ReceiptDeductionRequest(BaseModel):
+    """Request model for creating a deduction from a receipt"""
+    return_id: Optional[str] = Field(None, description="Return to deduct on (defaults to the receipt's return)")
+    category: Optional[str] = Field(None, description="Deduction category, overriding the extracted one")
+
+
+class CreateReturnRequest(BaseModel):
+    """Request model for creating a stored tax return"""
+    tax_year: int = Field(default=2024, description="Tax year")
+    filing_status: str = Field(default="single", description="Initial filing status")
+    taxpayer: Dict[str, Any] = Field(default_factory=dict, description="Taxpayer info (name, address, ...)")
+    client_id: Optional[str] = Field(None, description="Practitioner client the return is for")
+
+    @field_validator("filing_status")
+    @classmethod
+    def validate_filing_status(cls, v):
+        valid_statuses = [s.value for s in FilingStatus]
+        if v.lower() not in valid_statuses:
+            raise ValueError(f"Filing status must be one of: {', '.join(valid_statuses)}")
+        return v.lower()
+
+
+class BrokerCsvImportRequest(BaseModel):
+    """Request model for importing a broker gain/loss CSV"""
+    csv_text: str = Field(..., min_length=1, max_length=5_000_000, description="CSV export contents")
+    layout: Optional[str] = Field(None, description="fidelity, schwab, or vanguard (detected if omitted)")
+    mapping: Optional[Dict[str, str]] = Field(
+        None, description="Generic mode: lot field (date_sold, proceeds, cost_basis, ...) -> column header"
+    )
+    preview: bool = Field(default=False, description="Parse only; don't add the lots to the return")
+
+
+class ScheduleARequest(BaseModel):
+    """Request model for generating Schedule A"""
+    agi: Optional[float] = Field(None, ge=0, description="AGI override (defaults to the return's total income)")
+
+
+class ExtensionRequest(BaseModel):
+    """Request model for recording a filed extension"""
+    filed_on: Optional[date] = Field(None, description="Date the extension was filed (defaults to today)")
+    confirmation: Optional[str] = Field(None, max_length=100, description="Confirmation number")
+    states: List[str] = Field(default_factory=list, description="State returns extended along with the federal one")
+
+
+class EstimatedPaymentRequest(BaseModel):
+    """Request model for logging a payment of next year's estimated tax"""
+    quarter: str = Field(..., description="Q1, Q2, Q3, or Q4")
+    amount: float = Field(..., gt=0, description="Amount paid")
+    paid_date: date = Field(..., description="Date paid")
+    description: str = Field(default="", max_length=200, description="How it was paid")
+
+
+class OwnerRequest(BaseModel):
+    """Request model for attributing a record to a spouse on a joint return"""
+    owner: str = Field(..., description="taxpayer, spouse, or joint")
+
+
+class BusinessRequest(BaseModel):
+    """Request model for adding a sole proprietorship (Schedule C)"""
+    name: str = Field(..., min_length=1, max_length=200, description="Business name")
+    owner: str = Field(default="taxpayer", description="taxpayer or spouse")
+    description: Optional[str] = Field(None, max_length=200, description="Principal business or profession")
+    principal_business_code: Optional[str] = Field(None, pattern=r"^\d{6}$", description="NAICS code")
+    ein: Optional[str] = Field(None, pattern=r"^\d{2}-\d{7}$", description="Employer ID number")
+    gross_receipts: float = Field(default=0, ge=0, description="Receipts not reported on a linked 1099")
+    returns_allowances: float = Field(default=0, ge=0)
+    cost_of_goods_sold: float = Field(default=0, ge=0)
+    other_income: float = Field(default=0, ge=0)
+    home_office: float = Field(default=0, ge=0, description="Business use of home (line 30)")
+
+
+class BusinessExpenseRequest(BaseModel):
+    """Request model for adding a business expense"""
+    category: str = Field(..., description="Schedule C expense category (advertising, supplies, meals, ...)")
+    amount: float = Field(..., ge=0)
+    description: str = Field(default="", max_length=500)
+    date: Optional[str] = Field(None, description="Expense date (YYYY-MM-DD)")
+
+
+class VehicleRequest(BaseModel):
+    """Request model for adding a business vehicle"""
+    description: str = Field(..., min_length=1, max_length=200, description="Year, make, and model")
+    placed_in_service: Optional[date] = Field(None, description="Date first used for business")
+    cost: float = Field(default=0, ge=0, description="Depreciable basis (purchase price)")
+    total_miles: Optional[float] = Field(None, ge=0, description="All miles driven this year (odometer)")
+    leased: bool = Field(default=False)
+    heavy_vehicle: bool = Field(default=False, description="Over 6,000 lbs, so the depreciation caps don't apply")
+    method_history: Dict[str, str] = Field(
+        default_factory=dict, description="Method used in earlier years, e.g. {\"2023\": \"actual\"}"
+    )
+
+
+class MileageRequest(BaseModel):
+    """Request model for logging a trip"""
+    miles: float = Field(..., gt=0, le=10000)
+    business: bool = Field(default=True, description="False for personal trips")
+    purpose: str = Field(default="", max_length=500)
+    date: Optional[str] = Field(None, description="Date (YYYY-MM-DD)")
+    category: Optional[str] = Field(None, description="medical or charitable to deduct a personal trip on Schedule A")
+    parking_tolls: float = Field(default=0, ge=0, description="Parking and tolls on a medical or charitable trip")
+
+
+class PersonalMileageRequest(BaseModel):
+    """Request model for a medical or charitable trip in a personal vehicle"""
+    category: str = Field(..., description="medical or charitable")
+    miles: float = Field(..., gt=0, le=10000)
+    purpose: str = Field(default="", max_length=500)
+    date: Optional[str] = Field(None, description="Date (YYYY-MM-DD)")
+    parking_tolls: float = Field(default=0, ge=0)
+
+
+class VehicleExpenseRequest(BaseModel):
+    """Request model for an actual vehicle expense"""
+    kind: str = Field(..., description="fuel, repairs, tires, insurance, registration, lease, parking_tolls, or other")
+    amount: float = Field(..., ge=0)
+    description: str = Field(default="", max_length=500)
+    date: Optional[str] = Field(None, description="Date (YYYY-MM-DD)")
+
+
+class VehicleMethodRequest(BaseModel):
+    """Request model for choosing a vehicle's expense method"""
+    method: str = Field(..., description="standard_mileage or actual")
+
+
+class DonationItemRequest(BaseModel):
+    """One donated item (or several of the same item)"""
+    item: Optional[str] = Field(None, description="Valuation guide item, e.g. sofa or coat")
+    quantity: int = Field(default=1, ge=1, le=1000)
+    condition: str = Field(default="good", description="good, very_good, or like_new")
+    value: Optional[float] = Field(None, ge=0, description="Per-item value overriding the guide")
+    description: str = Field(default="", max_length=200)
+
+
+class DonationBatchRequest(BaseModel):
+    """Request model for a batch of donated goods"""
+    donee: str = Field(..., min_length=1, max_length=200, description="Charity receiving the goods")
+    date: Optional[str] = Field(None, description="Donation date (YYYY-MM-DD)")
+    items: List[DonationItemRequest] = Field(..., min_length=1, max_length=200)
+    preview: bool = Field(default=False, description="Value the batch without adding the deduction")
+
+
+class InvoiceRequest(BaseModel):
+    """Request model for recording an invoice"""
+    client: str = Field(..., min_length=1, max_length=200, description="Client billed (as it appears on their 1099)")
+    amount: float = Field(..., ge=0)
+    issue_date: date = Field(..., description="Date issued")
+    paid_date: Optional[date] = Field(None, description="Date paid (leave out while open)")
+    expect_1099: bool = Field(default=True, description="Whether the client should send a 1099-NEC")
+    description: str = Field(default="", max_length=500)
+    business_id: Optional[str] = Field(None, description="Business the income belongs to")
+
+
+class InvoiceUpdateRequest(BaseModel):
+    """Request model for changing an invoice; only the fields sent are changed"""
+    client: Optional[str] = Field(None, min_length=1, max_length=200)
+    amount: Optional[float] = Field(None, ge=0)
+    issue_date: Optional[date] = None
+    paid_date: Optional[date] = Field(None, description="Date paid (null reopens the invoice)")
+    expect_1099: Optional[bool] = None
+    description: Optional[str] = Field(None, max_length=500)
+    business_id: Optional[str] = None
+
+
+class ClientRequest(BaseModel):
+    """Request model for adding a practitioner client"""
+    name: str = Field(..., min_length=1, max_length=200)
+    email: str = Field(default="", max_length=200)
+    phone: str = Field(default="", max_length=50)
+    address: str = Field(default="", max_length=500)
+    engagement_status: str = Field(default="engaged", description=f"One of: {', '.join(ENGAGEMENT_STATUSES)}")
+    notes: str = Field(default="", max_length=5000)
+
+
+class ClientUpdateRequest(BaseModel):
+    """Request model for changing a client; only the fields sent are changed"""
+    name: Optional[str] = Field(None, min_length=1, max_length=200)
+    email: Optional[str] = Field(None, max_length=200)
+    phone: Optional[str] = Field(None, max_length=50)
+    address: Optional[str] = Field(None, max_length=500)
+    engagement_status: Optional[str] = None
+    notes: Optional[str] = Field(None, max_length=5000)
+
+
+class ReturnClientRequest(BaseModel):
+    """Request model for linking a return to a client"""
+    client_id: Optional[str] = Field(None, description="Client to link (null unlinks)")
+
+
+class WithholdingCheckupRequest(BaseModel):
+    """Request model for a mid-year withholding checkup from a pay stub"""
+    ytd_wages: float = Field(..., gt=0, description="Federal taxable wages to date")
+    ytd_withholding: float = Field(..., ge=0, description="Federal income tax withheld to date")
+    periods_remaining: int = Field(..., ge=0, description="Paychecks left this year")
+    pay_frequency: str = Field("biweekly", description=f"One of: {', '.join(PAY_PERIODS)}")
+    owner: str = Field("taxpayer", description="Whose job the pay stub is from (taxpayer or spouse)")
+    target_refund: float = Field(0, ge=0, description="Refund to aim for (0 breaks even)")
+
+
+class EstimatedTaxVoucherRequest(BaseModel):
+    """Request model for generating 1040-ES vouchers for a return"""
+    estimated_annual_income: Optional[float] = Field(
+        None, gt=0, description="Estimated annual income (defaults to the return's total income)"
+    )
+    withholding_to_date: Optional[float] = Field(
+        None, ge=0, description="Tax already withheld (defaults to the return's withholding)"
+    )
+    self_employment_income: Optional[float] = Field(
+        None, ge=0, description="Net self-employment profit (defaults to the return's self-employment income)"
+    )
+    as_of: Optional[date] = Field(None, description="Only quarters due on or after this date (defaults to today)")
+
+
+class ReturnStatusRequest(BaseModel):
+    """Request model for moving a return through the filing workflow"""
+    status: str = Field(..., description="draft, in_progress, review, filed, or amended")
+
+
+class TxfImportRequest(BaseModel):
+    """Request model for creating a return from a TXF export"""
+    txf_text: str = Field(..., min_length=1, max_length=5_000_000, description="TXF file contents")
+    tax_year: int = Field(default=2024, description="Tax year of the new return")
+    filing_status: str = Field(default="single", description="Filing status of the new return")
+    taxpayer: Dict[str, Any] = Field(default_factory=dict, description="Taxpayer info (name, address, ...)")
+
+    @field_validator("filing_status")
+    @classmethod
+    def validate_filing_status(cls, v):
+        valid_statuses = [s.value for s in FilingStatus]
+        if v.lower() not in valid_statuses:
+            raise ValueError(f"Filing status must be one of: {', '.join(valid_statuses)}")
+        return v.lower()
+
+
+class TravelDaysRequest(BaseModel):
+    """Request model for logging days spent in a state"""
+    state: str = Field(..., min_length=2, max_length=2, description="Two-letter state code")
+    start_date: str = Field(..., description="First day there (YYYY-MM-DD)")
+    end_date: Optional[str] = Field(None, description="Last day there (defaults to start_date)")
+    note: str = Field(default="", max_length=500)
+
+
+class ForeignAccountRequest(BaseModel):
+    """Request model for recording a foreign financial account"""
+    institution: str = Field(..., min_length=1, max_length=200)
+    country: str = Field(..., min_length=1, max_length=100)
+    max_balance: float = Field(..., ge=0, description="Highest balance during the year, in U.S. dollars")
+    year_end_balance: Optional[float] = Field(None, ge=0, description="Balance on December 31, in U.S. dollars")
+    account_type: Optional[str] = Field(None, description="bank, securities, pension, insurance, or other")
+    account_number_last4: Optional[str] = Field(None, max_length=4)
+    owner: Optional[str] = Field(None, description="taxpayer, spouse, or joint")
+
+
+class ForeignAccountUpdateRequest(BaseModel):
+    """Request model for changing a foreign account"""
+    institution: Optional[str] = Field(None, min_length=1, max_length=200)
+    country: Optional[str] = Field(None, min_length=1, max_length=100)
+    max_balance: Optional[float] = Field(None, ge=0)
+    year_end_balance: Optional[float] = Field(None, ge=0)
+    account_type: Optional[str] = None
+    account_number_last4: Optional[str] = Field(None, max_length=4)
+    owner: Optional[str] = None
+
+
+class ScheduleBPayerRequest(BaseModel):
+    """Request model for recording interest or dividends from a payer"""
+    type: str = Field(..., description="interest or dividends")
+    payer: str = Field(..., min_length=1, max_length=200)
+    amount: float = Field(..., ge=0, description="Interest (1099-INT box 1) or ordinary dividends (1099-DIV box 1a)")
+    withholding: float = Field(default=0, ge=0)
+    qualified_dividends: Optional[float] = Field(None, ge=0, description="1099-DIV box 1b")
+    tax_exempt: Optional[bool] = Field(None, description="Tax-exempt interest (Form 1040 line 2a, not Schedule B)")
+    foreign_account: Optional[bool] = Field(None, description="Paid on a financial account in a foreign country")
+    tax_exempt_interest: Optional[float] = Field(None, ge=0, description="1099-INT box 8")
+    exempt_interest_dividends: Optional[float] = Field(None, ge=0, description="1099-DIV box 12")
+    bond_state: Optional[str] = Field(None, description="State that issued the bonds (for state add-back)")
+    owner: Optional[str] = Field(None, description="taxpayer, spouse, or joint")
+
+
+class ScheduleBPayerUpdateRequest(BaseModel):
+    """Request model for changing a Schedule B payer"""
+    payer: Optional[str] = Field(None, min_length=1, max_length=200)
+    amount: Optional[float] = Field(None, ge=0)
+    withholding: Optional[float] = Field(None, ge=0)
+    qualified_dividends: Optional[float] = Field(None, ge=0)
+    tax_exempt: Optional[bool] = None
+    foreign_account: Optional[bool] = None
+    tax_exempt_interest: Optional[float] = Field(None, ge=0)
+    exempt_interest_dividends: Optional[float] = Field(None, ge=0)
+    bond_state: Optional[str] = None
+    owner: Optional[str] = None
+
+
+class EarlyDistributionRequest(BaseModel):
+    """Request model for recording a 1099-R's distribution code and the Form 5329 exception claimed"""
+    distribution_code: Optional[str] = Field(None, description="1099-R box 7 code")
+    plan_type: Optional[str] = Field(None, description="ira or employer_plan")
+    exception_code: Optional[str] = Field(None, description="Form 5329 exception number (01-13)")
+    exception_amount: Optional[float] = Field(None, ge=0, description="Part the exception covers (defaults to all)")
+
+
+class ExceptionEvaluationRequest(BaseModel):
+    """Request model for checking whether an early distribution exception applies"""
+    exception_code: Optional[str] = Field(None, description="Exception to check (defaults to the one claimed)")
+    age_at_separation: Optional[int] = Field(None, ge=0, le=120, description="Age in the year employment ended (01)")
+    public_safety_employee: bool = Field(default=False, description="Qualified public safety employee (01)")
+    weeks_unemployed: Optional[int] = Field(None, ge=0, le=53, description="Weeks of unemployment compensation (07)")
+    health_insurance_premiums: Optional[float] = Field(None, ge=0, description="Premiums paid (07)")
+    education_expenses: Optional[float] = Field(None, ge=0, description="Qualified higher education expenses (08)")
+    first_home_used_before: float = Field(default=0, ge=0, description="First home exception used before (09)")
+    children: int = Field(default=1, ge=1, le=20, description="Births or adoptions (13)")
+
+
+class StateRefundRequest(BaseModel):
+    """Request model for recording a state or local income tax refund"""
+    payer: str = Field(..., min_length=1, max_length=200, description="State or local government that paid it")
+    amount: float = Field(..., ge=0, description="Refund, credit, or offset received (1099-G box 2)")
+
+
+class PriorYearDeductionsRequest(BaseModel):
+    """Request model for loading last year's deduction figures"""
+    prior_return_id: Optional[str] = Field(None, description="Last year's return (defaults to the one cloned from)")
+
+
+class RentalPropertyRequest(BaseModel):
+    """Request model for adding a residential rental property"""
+    address: str = Field(..., min_length=1, max_length=300)
+    building_basis: float = Field(..., ge=0, description="Cost of the building, not the land, plus improvements")
+    placed_in_service: str = Field(..., description="Date first ready to rent (YYYY-MM-DD)")
+    land_value: Optional[float] = Field(None, ge=0, description="Part of the cost that was land (not depreciated)")
+    rents: Optional[float] = Field(None, ge=0, description="Rents received this year")
+    expenses: Optional[float] = Field(None, ge=0, description="Operating expenses this year, before depreciation")
+    owner: Optional[str] = Field(None, description="taxpayer, spouse, or joint")
+
+
+class RentalPropertyUpdateRequest(BaseModel):
+    """Request model for changing a rental property or recording its sale"""
+    address: Optional[str] = Field(None, min_length=1, max_length=300)
+    building_basis: Optional[float] = Field(None, ge=0)
+    placed_in_service: Optional[str] = None
+    land_value: Optional[float] = Field(None, ge=0)
+    rents: Optional[float] = Field(None, ge=0)
+    expenses: Optional[float] = Field(None, ge=0)
+    owner: Optional[str] = None
+    date_sold: Optional[str] = Field(None, description="YYYY-MM-DD")
+    sale_price: Optional[float] = Field(None, ge=0)
+    selling_expenses: Optional[float] = Field(None, ge=0, description="Commissions and closing costs")
+
+
+class LikeKindExchangeRequest(BaseModel):
+    """Request model for exchanging a rental under section 1031"""
+    exchange_date: str = Field(..., description="Date the property was transferred (YYYY-MM-DD)")
+    address: str = Field(..., min_length=1, max_length=300, description="Replacement property address")
+    replacement_value: float = Field(..., gt=0, description="Fair market value of the replacement")
+    replacement_date: Optional[str] = Field(None, description="Date the replacement was received (defaults to exchange_date)")
+    replacement_land_value: float = Field(default=0, ge=0, description="Part of the replacement's value that is land")
+    boot_received: float = Field(default=0, ge=0, description="Cash or other property received besides the replacement")
+    exchange_expenses: float = Field(default=0, ge=0, description="Intermediary, commission, and closing costs")
+
+
+class CryptoReceiptRequest(BaseModel):
+    """Request model for recording crypto received"""
+    kind: str = Field(..., description="staking, mining, airdrop, or purchase")
+    asset: str = Field(..., min_length=1, max_length=20, description="Ticker or name")
+    quantity: float = Field(..., gt=0)
+    fair_market_value: float = Field(
+        ..., ge=0, description="Total U.S. dollar value when received (price paid for a purchase)"
+    )
+    date_received: str = Field(..., description="YYYY-MM-DD")
+    business_id: Optional[str] = Field(None, description="Business the mining is reported on")
+    owner: Optional[str] = Field(None, description="taxpayer or spouse")
+
+
+class CryptoDisposalRequest(BaseModel):
+    """Request model for selling, trading, or spending crypto"""
+    asset: str = Field(..., min_length=1, max_length=20)
+    quantity: float = Field(..., gt=0)
+    proceeds: float = Field(..., ge=0, description="Total U.S. dollars received, or the value of what was bought")
+    date_sold: str = Field(..., description="YYYY-MM-DD")
+
+
+class HoldingRequest(BaseModel):
+    """Request model for recording an open investment position"""
+    symbol: str = Field(..., min_length=1, max_length=20)
+    quantity: float = Field(..., gt=0)
+    cost_basis: float = Field(..., ge=0, description="Total basis of the lot")
+    date_acquired: str = Field(..., description="YYYY-MM-DD")
+    market_value: Optional[float] = Field(None, ge=0, description="Current total value of the lot")
+
+
+class HoldingUpdateRequest(BaseModel):
+    """Request model for changing a holding"""
+    symbol: Optional[str] = Field(None, min_length=1, max_length=20)
+    quantity: Optional[float] = Field(None, gt=0)
+    cost_basis: Optional[float] = Field(None, ge=0)
+    date_acquired: Optional[str] = None
+    market_value: Optional[float] = Field(None, ge=0)
+
+
+class DependentRequest(BaseModel):
+    """Request model for adding a dependent with the facts the dependent tests use"""
+    name: str = Field(..., min_length=1, max_length=200)
+    relationship: str = Field(..., min_length=1, max_length=50, description="son, daughter, parent, niece, ...")
+    birth_date: Optional[str] = Field(None, description="Date of birth (YYYY-MM-DD)")
+    months_lived_with: Optional[int] = Field(None, ge=0, le=12, description="Months lived with the taxpayer")
+    full_time_student: Optional[bool] = None
+    disabled: Optional[bool] = Field(None, description="Permanently and totally disabled")
+    self_support_percent: Optional[float] = Field(None, ge=0, le=100, description="Share of their support they provided")
+    support_percent: Optional[float] = Field(None, ge=0, le=100, description="Share of their support the taxpayer provided")
+    gross_income: Optional[float] = Field(None, ge=0)
+    files_joint_return: Optional[bool] = None
+    qualifying_child_of_another: Optional[bool] = Field(None, description="Someone else's qualifying child")
+    has_ssn: Optional[bool] = Field(None, description="Has a social security number valid for employment")
+    custodial_parent: Optional[str] = Field(None, description="taxpayer or other_parent, for children of divorced or separated parents")
+    form_8332_years: Optional[List[int]] = Field(None, description="Years the custodial parent released the claim on Form 8332")
+
+
+class DependentUpdateRequest(BaseModel):
+    """Request model for changing a dependent"""
+    name: Optional[str] = Field(None, min_length=1, max_length=200)
+    relationship: Optional[str] = Field(None, min_length=1, max_length=50)
+    birth_date: Optional[str] = None
+    months_lived_with: Optional[int] = Field(None, ge=0, le=12)
+    full_time_student: Optional[bool] = None
+    disabled: Optional[bool] = None
+    self_support_percent: Optional[float] = Field(None, ge=0, le=100)
+    support_percent: Optional[float] = Field(None, ge=0, le=100)
+    gross_income: Optional[float] = Field(None, ge=0)
+    files_joint_return: Optional[bool] = None
+    qualifying_child_of_another: Optional[bool] = None
+    has_ssn: Optional[bool] = None
+    custodial_parent: Optional[str] = None
+    form_8332_years: Optional[List[int]] = None
+
+
+class DivorceRequest(BaseModel):
+    """Request model for a divorce or separation"""
+    decree_date: Optional[str] = Field(None, description="Date the decree became final (YYYY-MM-DD); omit while pending")
+    decree_type: str = Field(default="divorce", description="divorce or separate_maintenance")
+    separated_date: Optional[str] = Field(None, description="Date the spouse last lived in the home (YYYY-MM-DD)")
+    paid_over_half_home_cost: Optional[bool] = Field(None, description="Paid more than half the cost of keeping up the home")
+
+
+class DeathRequest(BaseModel):
+    """Request model for the death of the taxpayer or spouse"""
+    deceased: str = Field(..., description="taxpayer or spouse")
+    date_of_death: str = Field(..., description="Date of death (YYYY-MM-DD)")
+    personal_representative: Optional[str] = Field(None, max_length=200, description="Executor or administrator who signs")
+    remarried_date: Optional[str] = Field(None, description="Date the surviving spouse remarried (YYYY-MM-DD)")
+    paid_over_half_home_cost: Optional[bool] = Field(None, description="Surviving spouse paid more than half the cost of the home")
+
+
+class InjuredSpouseRequest(BaseModel):
+    """Request model for the Form 8379 injured spouse allocation"""
+    injured_spouse: str = Field(default="spouse", description="taxpayer or spouse: the one who doesn't owe the debt")
+    past_due_debt: Optional[float] = Field(None, ge=0, description="The other spouse's debt the refund would be applied to")
+
+
+class AlimonyRequest(BaseModel):
+    """Request model for alimony paid or received"""
+    direction: str = Field(..., description="paid or received")
+    amount: float = Field(..., ge=0, description="Total for the year")
+    instrument_date: Optional[str] = Field(
+        None, description="Date the divorce or separation instrument was executed (YYYY-MM-DD); defaults to the decree date"
+    )
+    modified_to_adopt_new_rule: bool = Field(default=False, description="Pre-2019 instrument modified to adopt the post-2018 rule")
+    recipient_ssn: Optional[str] = Field(None, description="Recipient's SSN, needed to deduct alimony paid")
+    owner: Optional[str] = Field(None, description="taxpayer, spouse, or joint on a joint return")
+    description: str = Field(default="", max_length=500)
+
+
+class StudentRequest(BaseModel):
+    """Request model for adding a student for the education credits"""
+    name: str = Field(..., min_length=1, max_length=200)
+    dependent_id: Optional[str] = Field(None, description="Dependent record, when the student is a dependent")
+    institution: Optional[str] = Field(None, max_length=200)
+    half_time: bool = Field(default=True, description="Enrolled at least half time for one academic period")
+    graduate: bool = Field(default=False, description="Graduate student")
+    aotc_years_claimed: int = Field(default=0, ge=0, le=4, description="Earlier years the American opportunity credit was claimed")
+    drug_felony: bool = Field(default=False, description="Felony drug conviction")
+
+
+class StudentUpdateRequest(BaseModel):
+    """Request model for changing a student"""
+    name: Optional[str] = Field(None, min_length=1, max_length=200)
+    dependent_id: Optional[str] = None
+    institution: Optional[str] = Field(None, max_length=200)
+    half_time: Optional[bool] = None
+    graduate: Optional[bool] = None
+    aotc_years_claimed: Optional[int] = Field(None, ge=0, le=4)
+    drug_felony: Optional[bool] = None
+
+
+class AdoptionRequest(BaseModel):
+    """Request model for adding a child being adopted, for the adoption credit"""
+    name: str = Field(..., min_length=1, max_length=200)
+    expenses: float = Field(default=0, ge=0, description="Qualified adoption expenses paid this year")
+    prior_year_expenses: float = Field(default=0, ge=0, description="Expenses paid in earlier years, not yet claimed")
+    credit_claimed_before: float = Field(default=0, ge=0, description="Credit claimed for this child in earlier years")
+    final_year: Optional[int] = Field(None, ge=1900, le=2100, description="Year the adoption became final")
+    foreign: bool = Field(default=False, description="The child wasn't a U.S. citizen or resident")
+    special_needs: bool = Field(default=False, description="A state determined the child has special needs")
+    dependent_id: Optional[str] = Field(None, description="Dependent record for the child")
+
+
+class AdoptionUpdateRequest(BaseModel):
+    """Request model for changing an adoption"""
+    name: Optional[str] = Field(None, min_length=1, max_length=200)
+    expenses: Optional[float] = Field(None, ge=0)
+    prior_year_expenses: Optional[float] = Field(None, ge=0)
+    credit_claimed_before: Optional[float] = Field(None, ge=0)
+    final_year: Optional[int] = Field(None, ge=1900, le=2100)
+    foreign: Optional[bool] = None
+    special_needs: Optional[bool] = None
+    dependent_id: Optional[str] = None
+
+
+class EducationExpenseRequest(BaseModel):
+    """Request model for a student's education expense, scholarship, or refund"""
+    kind: str = Field(..., description="tuition, fees, course_materials, scholarship, or refund")
+    amount: float = Field(..., ge=0)
+    description: str = Field(default="", max_length=500)
+    date: Optional[str] = Field(None, description="Date paid (YYYY-MM-DD)")
+    paid_to_institution: bool = Field(default=False, description="Course materials that had to be bought from the school")
+
+
+class Form1098TImportRequest(BaseModel):
+    """Request model for importing a 1098-T into a student's expenses"""
+    text: Optional[str] = Field(None, min_length=1, max_length=200_000, description="OCR or PDF-extracted text")
+    document_id: Optional[str] = Field(None, description="Stored document already parsed as a 1098-T")
+
+
+class CharitableBunchingRequest(BaseModel):
+    """Request model for comparing annual giving with bunching into a donor-advised fund"""
+    annual_donation: Optional[float] = Field(
+        None, gt=0, description="Yearly giving (defaults to the return's charitable deductions)"
+    )
+    years: int = Field(default=4, ge=2, le=20, description="Planning horizon")
+
+
+class RelocationRequest(BaseModel):
+    """Request model for comparing taxes after a move to another state"""
+    to_state: str = Field(..., min_length=2, max_length=2, description="State being considered")
+    from_state: Optional[str] = Field(
+        None, min_length=2, max_length=2, description="Current state (defaults to the resident state return)"
+    )
+    local_tax_rate: float = Field(default=0, ge=0, le=20, description="Current local income tax, percent of AGI")
+    new_local_tax_rate: float = Field(default=0, ge=0, le=20, description="Local income tax after moving, percent of AGI")
+    property_tax: Optional[float] = Field(
+        None, ge=0, description="Current yearly property tax (defaults to the return's real estate tax deductions)"
+    )
+    new_property_tax: Optional[float] = Field(None, ge=0, description="Estimated yearly property tax after moving")
+
+
+class RaiseRequest(BaseModel):
+    """Request model for the after-tax value of a raise or job offer"""
+    raise_amount: Optional[float] = Field(None, description="Extra yearly pay (negative for a pay cut)")
+    new_salary: Optional[float] = Field(None, ge=0, description="Offered salary, instead of raise_amount")
+    owner: str = Field(default="taxpayer", description=f"Whose pay changes: {', '.join(RAISE_OWNERS)}")
+    state: Optional[str] = Field(
+        None, min_length=2, max_length=2, description="State of residence (defaults to the return's)"
+    )
+    local_tax_rate: float = Field(default=0, ge=0, le=20, description="Local income tax, percent of AGI")
+
+
+class StateReturnRequest(BaseModel):
+    """Request model for attaching a state return"""
+    state: str = Field(..., min_length=2, max_length=2, description="Two-letter state code")
+    residency: str = Field(default="resident", description="resident, part_year, or nonresident")
+    allocated_income: Optional[float] = Field(
+        None, ge=0, description="Income sourced to the state (defaults to its W-2 state wages)"
+    )
+    withholding: Optional[float] = Field(
+        None, ge=0, description="State tax withheld (defaults to its W-2 state withholding)"
+    )
+
+
+class StateReturnUpdateRequest(BaseModel):
+    """Request model for changing a state return"""
+    residency: Optional[str] = None
+    allocated_income: Optional[float] = Field(None, ge=0)
+    withholding: Optional[float] = Field(None, ge=0)
+
+
+class CloneReturnRequest(BaseModel):
+    """Request model for starting next year's return from a prior one"""
+    tax_year: int = Field(..., description="Tax year of the new return")
+
+
+class CarryforwardsRequest(BaseModel):
+    """Request model for carryforwards into a return"""
+    capital_loss: Optional[float] = Field(None, ge=0, description="Capital loss carryover")
+    nol: Optional[float] = Field(None, ge=0, description="Net operating loss carryforward")
+    amt_credit: Optional[float] = Field(None, ge=0, description="Minimum tax credit carryforward (Form 8801)")
+    adoption_credit: Optional[float] = Field(None, ge=0, description="Unused adoption credit (Form 8839)")
+
+
+class AtRiskRequest(BaseModel):
+    """Request model for an activity's at-risk amount"""
+    at_risk_amount: Optional[float] = Field(
+        None, ge=0, description="Cash, property, and recourse debt at risk at the start of the year"
+    )
+    carryforward: Optional[float] = Field(None, ge=0, description="Losses disallowed by the at-risk rules in earlier years")
+
+
+class PlatformAllocationRequest(BaseModel):
+    """Request model for splitting a 1099-K between personal sales and business receipts"""
+    personal_sales: float = Field(default=0, ge=0, description="Part of the gross from selling personal items")
+    personal_cost: float = Field(default=0, ge=0, description="What those personal items originally cost")
+    business_id: Optional[str] = Field(None, description="Business the rest of the gross belongs to")
+
+
+class HobbyDeterminationRequest(BaseModel):
+    """Request model for the hobby-or-business questionnaire"""
+    activity: str = Field(..., min_length=1, max_length=200, description="What the activity is")
+    answers: Dict[str, str] = Field(default_factory=dict, description="Factor name to yes, no, or unsure")
+    notes: Dict[str, str] = Field(default_factory=dict, description="Factor name to the facts behind the answer")
+    profit_years: Optional[int] = Field(None, ge=0, description="Years with a profit in the last 5 (7 for horses)")
+    horse_activity: bool = Field(default=False, description="Breeding, training, showing, or racing horses")
+    business_id: Optional[str] = Field(None, description="Business the activity is reported as")
+
+
+class DirectDepositRequest(BaseModel):
+    """Request model for the refund direct deposit account"""
+    routing_number: str = Field(..., description="9-digit ABA routing number")
+    account_number: str = Field(..., description="Bank account number")
+    account_type: str = Field(default="checking", description="checking or savings")
+
+
+class IpPinRequest(BaseModel):
+    """Request model for an Identity Protection PIN"""
+    pin: str = Field(..., description="6-digit IP PIN from the IRS")
+    valid_year: Optional[int] = Field(None, description="Calendar year the PIN is for (defaults to the year after the tax year)")
+
+
+class ChecklistItemUpdateRequest(BaseModel):
+    """Request model for checking off a checklist item or updating its notes"""
+    checked: Optional[bool] = Field(None, description="Whether the item is done")
+    notes: Optional[str] = Field(None, max_length=2000, description="Follow-up notes")
+
+
+class ChecklistItemRequest(BaseModel):
+    """Request model for adding a checklist item by hand"""
+    title: str = Field(..., min_length=1, max_length=200, description="What's needed")
+    kind: str = Field(default="document", description="document, information, or task")
+    form: Optional[str] = Field(None, description="Form number, if it's a tax form")
+    reason: str = Field(default="", max_length=500, description="Why it's needed")
+    notes: str = Field(default="", max_length=2000, description="Follow-up notes")
+
+
+class ReturnNotesRequest(BaseModel):
+    """Request model for a return's preparation notes"""
+    notes: str = Field(..., max_length=20_000, description="Free-form notes (empty clears them)")
+
+
+class JobRequest(BaseModel):
+    """Request model for queueing a background job"""
+    kind: str = Field(..., description="bulk_import, backup, reindex_embeddings, review_packet, or extract_document")
+    payload: Dict[str, Any] = Field(default_factory=dict, description="Arguments for the job")
+    description: str = Field(default="", max_length=500, description="Shown in the job list")
+    max_attempts: int = Field(default=DEFAULT_MAX_ATTEMPTS, ge=1, le=10, description="Runs before giving up")
+
+
+class ImportDataRequest(BaseModel):
+    """Request model for importing a full data export"""
+    export: Dict[str, Any] = Field(..., description="Envelope from GET /api/data/export")
+    on_conflict: str = Field(default="skip", description=f"{', '.join(CONFLICT_STRATEGIES)}: what to do with IDs that already exist")
+    include_settings: bool = Field(default=True, description="Also replace the settings with the exported ones")
+
+
+class SnoozeRequest(BaseModel):
+    """Request model for snoozing a reminder"""
+    until: Optional[date] = Field(None, description="Date the reminder shows again")
+    days: Optional[int] = Field(None, ge=1, le=365, description="Or: snooze for this many days")
+
+
+class PassphraseRequest(BaseModel):
+    """Request model for setting or changing the app lock passphrase"""
+    passphrase: str = Field(..., max_length=1024, description="New passphrase")
+    current_passphrase: Optional[str] = Field(None, max_length=1024, description="Required when changing it")
+
+
+class ApiKeyRequest(BaseModel):
+    """Request model for saving the Anthropic API key"""
+    api_key: str = Field(..., min_length=1, max_length=1024, description="Anthropic API key")
+
+
+class OnboardingPassphraseRequest(BaseModel):
+    """Request model for the setup wizard's passphrase step"""
+    passphrase: Optional[str] = Field(None, max_length=1024, description="App lock passphrase (kept if one is already set)")
+
+
+class OnboardingProfileRequest(BaseModel):
+    """Request model for the setup wizard's profile step"""
+    name: str = Field(..., min_length=1, max_length=200, description="Taxpayer's name")
+    filing_status: str = Field(default="single", description="Usual filing status")
+    spouse_name: Optional[str] = Field(None, max_length=200, description="Spouse's name")
+    address: Optional[str] = Field(None, max_length=200, description="Street address")
+    city: Optional[str] = Field(None, max_length=100, description="City")
+    state: Optional[str] = Field(None, description="Two-letter state")
+    zip: Optional[str] = Field(None, max_length=10, description="ZIP code")
+
+
+class OnboardingDefaultsRequest(BaseModel):
+    """Request model for the setup wizard's defaults step"""
+    tax_year: int = Field(..., description="Tax year new returns start in")
+    state: Optional[str] = Field(None, description="Two-letter state of residence")
+
+
+class OnboardingImportRequest(BaseModel):
+    """Request model for importing last year's return in the setup wizard"""
+    txf_text: str = Field(..., min_length=1, max_length=5_000_000, description="TXF export of last year's return")
+
+
+class UnlockRequest(BaseModel):
+    """Request model for unlocking the app or turning the lock off"""
+    passphrase: str = Field(..., max_length=1024, description="Current passphrase")
+    review_mode: bool = Field(default=False, description="Unlock read-only, logging what is viewed")
+
+
+class InterviewAnswerRequest(BaseModel):
+    """Request model for answering an interview question"""
+    answer: str = Field(..., min_length=1, max_length=4000, description="Taxpayer's answer")
+
+
+class VoiceChatRequest(BaseModel):
+    """Request model for voice agent text chat"""
+    message: str = Field(..., min_length=1, max_length=2000, description="User message text")
+    session_id: Optional[str] = Field(None, description="Session ID for conversation continuity")
+    context: Dict[str, Any] = Field(default_factory=dict, description="Conversation context")
+
+
+# ============================================================================
+# HEALTH CHECK & INFO
+# ============================================================================
+
+@app.get("/")
+async def root():
+    """API health check"""
+    return {
+        "status": "online",
+        "service": "AI Tax CPA Agent",
+        "version": APP_VERSION,
+        "disclaimer": TaxCalculator.LEGAL_DISCLAIMER.strip(),
+        "endpoints": {
+            "tax_calculation": "/api/tax/calculate",
+            "documents": "/api/documents",
+            "document_analysis": "/api/documents/analyze",
+            "document_parsing": "/api/documents/parse",
+            "audit_defense": "/api/audit/analyze",
+            "tax_returns": "/api/returns",
+            "voice_agent": "/api/voice/chat (not implemented)",
+            "chat_search": "/api/voice/search",
+            "app_lock": "/api/lock",
+            "jobs": "/api/jobs",
+            "tax_calendar": "/api/calendar",
+        }
+    }
+
+
+@app.get("/api/disclaimer")
+async def get_disclaimer():
+    """Get legal disclaimer"""
+    return {
+        "disclaimer": TaxCalculator.LEGAL_DISCLAIMER.strip()
+    }
+
+
+# ============================================================================
+# APP LOCK ENDPOINTS
+# ============================================================================
+
+def _lock_response(status: Dict[str, Any]) -> Dict[str, Any]:
+    return {"success": True, "data": status, "timestamp": datetime.utcnow().isoformat()}
+
+
+@app.get("/api/lock")
+async def get_lock_status():
+    """Whether a passphrase is set and the app is unlocked"""
+    return _lock_response(app_lock.status())
+
+
+@app.post("/api/lock")
+async def lock_app():
+    """Lock the app; every other API call returns 423 until it is unlocked"""
+    try:
+        return _lock_response(app_lock.lock())
+    except ValueError as e:
+        raise ConflictError(str(e))
+
+
+@app.post("/api/unlock", **audited("authentication"))
+async def unlock_app(request: UnlockRequest):
+    """Unlock the app with its passphrase, optionally straight into review mode"""
+    was_reviewing = app_lock.review_mode
+    try:
+        status = app_lock.unlock(request.passphrase, review_mode=request.review_mode)
+    except ValueError as e:
+        logger.warning(f"Unlock failed: {str(e)}")
+        raise InvalidInputError(str(e))
+    _migrate_secrets()
+    if status["review_mode"] and not was_reviewing:
+        activity_log.record("review_started", "/api/unlock", method="POST")
+    elif was_reviewing and not status["review_mode"]:
+        activity_log.record("review_ended", "/api/unlock", method="POST")
+    return _lock_response(status)
+
+
+@app.post("/api/lock/verify", **audited("authentication"))
+async def verify_passphrase(request: UnlockRequest):
+    """Re-enter the passphrase while unlocked so exports and API key changes are allowed again"""
+    try:
+        return _lock_response(app_lock.verify(request.passphrase))
+    except ValueError as e:
+        logger.warning(f"Passphrase verification failed: {str(e)}")
+        raise InvalidInputError(str(e))
+
+
+@app.put("/api/lock/passphrase", **audited("passphrase_change"))
+async def set_lock_passphrase(request: PassphraseRequest):
+    """Set the app passphrase, or change it (the current one is required)"""
+    try:
+        return _lock_response(app_lock.set_passphrase(request.passphrase, request.current_passphrase))
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+
+@app.post("/api/lock/disable", **audited("passphrase_change"))
+async def disable_lock(request: UnlockRequest):
+    """Remove the passphrase so the app no longer locks"""
+    try:
+        return _lock_response(app_lock.remove_passphrase(request.passphrase))
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+
+# ============================================================================
+# REVIEW MODE ENDPOINTS
+# ============================================================================
+
+@app.post("/api/review/start")
+async def start_review():
+    """
+    Make the app read-only so it can be handed to an accountant
+
+    Every change is refused and every view is logged until the passphrase is
+    entered at POST /api/review/end.
+    """
+    if app_lock.review_mode:
+        return _lock_response(app_lock.status())
+    try:
+        status = app_lock.start_review()
+    except ValueError as e:
+        raise ConflictError(str(e))
+    activity_log.record("review_started", "/api/review/start", method="POST")
+    return _lock_response(status)
+
+
+@app.post("/api/review/end")
+async def end_review(request: UnlockRequest):
+    """Leave review mode (needs the passphrase)"""
+    try:
+        status = app_lock.end_review(request.passphrase)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+    activity_log.record("review_ended", "/api/review/end", method="POST")
+    return _lock_response(status)
+
+
+@app.get("/api/review/activity")
+async def get_review_activity(
+    limit: int = Query(200, ge=1, le=5000, description="Maximum entries"),
+    since: Optional[str] = Query(None, description="Only entries at or after this ISO timestamp"),
+):
+    """What was viewed (and which changes were refused) in review mode, newest first"""
+    return {
+        "success": True,
+        "data": activity_log.entries(limit=limit, since=since),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/review/activity")
+async def clear_review_activity():
+    """Delete the review activity log"""
+    return {
+        "success": True,
+        "data": {"deleted": activity_log.clear()},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/security-events")
+async def get_security_events(
+    limit: int = Query(200, ge=1, le=5000, description="Maximum events"),
+    since: Optional[str] = Query(None, description="Only events at or after this ISO timestamp"),
+    action: Optional[str] = Query(None, description=f"Only this action: {', '.join(AUDIT_ACTIONS)}"),
+    outcome: Optional[str] = Query(None, description=f"Only this outcome: {', '.join(AUDIT_OUTCOMES)}"),
+):
+    """Sensitive actions from the API and command line (exports, reveals, key changes, deletions), newest first"""
+    if action is not None and action not in AUDIT_ACTIONS:
+        raise InvalidInputError(f"Invalid action: {action}. Must be one of: {', '.join(AUDIT_ACTIONS)}")
+    if outcome is not None and outcome not in AUDIT_OUTCOMES:
+        raise InvalidInputError(f"Invalid outcome: {outcome}. Must be one of: {', '.join(AUDIT_OUTCOMES)}")
+    return {
+        "success": True,
+        "data": audit_log.entries(limit=limit, since=since, action=action, outcome=outcome),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+# ============================================================================
+# SETTINGS ENDPOINTS
+# ============================================================================
+
+settings_store = SettingsStore()
+
+
+@app.get("/api/settings")
+async def get_settings():
+    """Get application settings"""
+    return {
+        "success": True,
+        "data": settings_store.get_settings().model_dump(),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.put("/api/settings")
+async def update_settings(changes: Dict[str, Any]):
+    """
+    Update application settings (partial update)
+
+    A prompt addendum that tries to remove the safety rules or disclaimer is rejected.
+    Changing the AI endpoint, proxy, or re-auth window needs the passphrase
+    entered recently, like an API key change, and is recorded in the audit log.
+    """
+    current = settings_store.get_settings()
+    guarded = sorted(f for f in REAUTH_SETTINGS & changes.keys() if changes[f] != getattr(current, f))
+    if guarded and not app_lock.verified_within(current.reauth_minutes):
+        audit_log.record("key_change", "PUT /api/settings", "denied", source="api", detail="401")
+        error = ReauthRequiredError("Enter the passphrase again to change " + ", ".join(guarded) + ".")
+        error.headers = {"X-Reauth-Required": "true"}
+        raise error
+
+    try:
+        if "prompt_addendum" in changes:
+            changes["prompt_addendum"] = validate_prompt_addendum(changes["prompt_addendum"] or "")
+        settings = settings_store.update_settings(changes)
+    except ValidationError as e:
+        raise InvalidInputError(f"Invalid settings: {e.errors()[0]['msg']}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    if guarded:
+        audit_log.record("key_change", "PUT /api/settings", "success", source="api", detail=", ".join(guarded))
+    if "inbox_folder" in changes or "inbox_poll_seconds" in changes:
+        await _configure_inbox_watcher()
+    if "embedding_url" in changes or "embedding_model" in changes:
+        conversation_store.embedder = _embedding_provider(settings)
+
+    return {
+        "success": True,
+        "data": settings.model_dump(),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/settings/history")
+async def get_settings_history(
+    limit: int = Query(200, ge=1, le=5000, description="Maximum entries"),
+    field: Optional[str] = Query(None, description="Only changes to this setting"),
+):
+    """Changes to the settings with their previous values and where they came from, newest first"""
+    try:
+        history = settings_store.get_settings_history(limit=limit, field=field)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+    return {
+        "success": True,
+        "data": history,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/settings/api-key")
+async def get_api_key_status():
+    """Whether an Anthropic API key is configured and where it is kept (never the key itself)"""
+    return {
+        "success": True,
+        "data": api_key_status(secret_store),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.put("/api/settings/api-key", **audited("key_change"))
+async def set_api_key(request: ApiKeyRequest):
+    """
+    Save the Anthropic API key in the OS keyring (or the encrypted file fallback)
+
+    ANTHROPIC_API_KEY, when set, still takes precedence.
+    """
+    api_key = request.api_key.strip()
+    if not api_key:
+        raise InvalidInputError("API key is empty")
+    location = secret_store.set(API_KEY_NAME, api_key)
+    logger.info(f"Anthropic API key saved to {location}")
+    return {
+        "success": True,
+        "data": api_key_status(secret_store),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/settings/api-key", **audited("key_change"))
+async def delete_api_key():
+    """Remove the saved Anthropic API key"""
+    if not secret_store.delete(API_KEY_NAME):
+        raise NotFoundError("No API key is saved")
+    return {
+        "success": True,
+        "data": api_key_status(secret_store),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+def _migrate_secrets() -> None:
+    """Move secrets saved before a keyring was available into it"""
+    for name in secret_store.migrate_to_keyring():
+        logger.info(f"Moved {name} to the OS keyring")
+
+
+def _formatter() -> Formatter:
+    """Number and date formatting from settings"""
+    return Formatter.from_settings(settings_store.get_settings())
+
+
+def _rounding() -> str:
+    """Rounding policy for tax results from settings"""
+    return settings_store.get_settings().rounding_policy
+
+
+def _locale(locale: Optional[str]) -> str:
+    """Requested locale, or the one in settings"""
+    try:
+        return normalize_locale(locale or settings_store.get_settings().locale)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+
+@app.get("/api/i18n")
+async def get_translations(locale: Optional[str] = Query(None, description="Locale (defaults to the locale setting)")):
+    """Display names and message catalog for the frontend"""
+    locale = _locale(locale)
+    return {
+        "success": True,
+        "data": {
+            "locale": locale,
+            "supported_locales": SUPPORTED_LOCALES,
+            "display_names": display_names(locale),
+            "messages": catalog(locale),
+        },
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+# ============================================================================
+# DATA EXPORT/IMPORT ENDPOINTS
+# ============================================================================
+
+@app.get("/api/data/export", **audited("export"))
+async def export_all_data(include_documents: bool = Query(False, description="Embed document files as base64")):
+    """
+    Export every return, document, conversation, setting, and reminder as one versioned JSON envelope
+
+    Bank details are included in plain text so the export can be restored on another machine.
+    """
+    envelope = export_data(
+        return_store, document_store, settings_store, conversation_store, reminder_store,
+        include_documents=include_documents,
+    )
+    return {
+        "success": True,
+        "data": envelope,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/data/import", **audited("import"))
+async def import_all_data(request: ImportDataRequest):
+    """
+    Import a data export, skipping, overwriting, or keeping both copies of records that already exist
+
+    Needs the passphrase entered recently, since it can replace every
+    return and the settings.
+    """
+    try:
+        report = import_data(
+            request.export, return_store, document_store, settings_store, conversation_store, reminder_store,
+            on_conflict=request.on_conflict, include_settings=request.include_settings,
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    if report["settings"]:
+        await _configure_inbox_watcher()
+        conversation_store.embedder = _embedding_provider(settings_store.get_settings())
+    return {
+        "success": True,
+        "data": report,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+# ============================================================================
+# SUPPORT ENDPOINTS
+# ============================================================================
+
+@app.get("/api/support/diagnostics")
+async def get_diagnostics(log_lines: int = Query(200, ge=0, le=500, description="Recent log lines to include")):
+    """
+    Diagnostic report to attach to a bug report
+
+    Holds versions, record counts, and scrubbed recent log entries; never return
+    contents, SSNs, bank details, or keys.
+    """
+    report = generate_diagnostics(
+        APP_VERSION, return_store, document_store, conversation_store, settings_store, reminder_store,
+        job_queue=job_queue, app_lock=app_lock, log_entries=get_recent_logs(limit=log_lines) if log_lines else [],
+    )
+    return {
+        "success": True,
+        "data": {
+            "filename": f"diagnostics_{datetime.utcnow().strftime('%Y%m%d_%H%M%S')}.json",
+            "mime_type": "application/json",
+            "report": report,
+        },
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/logs")
+async def get_logs(
+    level: Optional[str] = Query(None, description=f"Minimum level: {', '.join(LOG_LEVELS)}"),
+    limit: int = Query(200, ge=1, le=5000, description="Maximum entries"),
+):
+    """Recent application log entries, newest first (already scrubbed of sensitive data)"""
+    try:
+        entries = get_recent_logs(level=level, limit=limit)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+    return {
+        "success": True,
+        "data": entries,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+# ============================================================================
+# TAX CALCULATION ENDPOINTS
+# ============================================================================
+
+@app.post("/api/tax/calculate")
+async def calculate_tax(request: TaxReturnRequest):
+    """
+    Calculate federal income tax using real IRS 2024 tax brackets
+
+    Returns tax liability with detailed breakdown and legal disclaimer.
+    """
+    try:
+        calculator = TaxCalculator(tax_year=2024)
+
+        if request.entity_type == "1040":
+            # Individual tax return
+            result = calculator.calculate_individual_tax(
+                gross_income=Decimal(str(request.gross_income)),
+                filing_status=request.filing_status,
+                itemized_deductions=Decimal(str(request.itemized_deductions)) if request.itemized_deductions else None,
+                dependents=request.dependents,
+            )
+        elif request.entity_type == "1120":
+            # Corporate tax return
+            result = calculator.calculate_corporate_tax(
+                net_income=Decimal(str(request.gross_income)),
+                entity_type="C-Corp",
+            )
+        else:
+            raise InvalidInputError(
+                f"Unsupported entity type: {request.entity_type}. Use 1040 or 1120."
+            )
+
+        return {
+            "success": True,
+            "data": round_amounts(result, _rounding()),
+            "timestamp": datetime.utcnow().isoformat(),
+        }
+
+    except HTTPException:
+        raise
+    except ValueError as e:
+        logger.error(f"Validation error in tax calculation: {str(e)}")
+        raise InvalidInputError(str(e))
+    except Exception as e:
+        # Sanitize error message to avoid leaking sensitive info
+        logger.error(f"Error in tax calculation: {str(e)}")
+        raise AppError(
+            "An error occurred during tax calculation. Please check your input and try again."
+        )
+
+
+@app.post("/api/tax/quarterly")
+async def estimate_quarterly(request: QuarterlyEstimateRequest):
+    """Estimate quarterly tax payments"""
+    try:
+        calculator = TaxCalculator(tax_year=2024)
+
+        result = calculator.estimate_quarterly_payments(
+            estimated_annual_income=Decimal(str(request.estimated_annual_income)),
+            filing_status=request.filing_status,
+            withholding_to_date=Decimal(str(request.withholding_to_date)),
+            self_employment_income=Decimal(str(request.self_employment_income)),
+        )
+
+        return {
+            "success": True,
+            "data": round_amounts(result, _rounding()),
+            "timestamp": datetime.utcnow().isoformat(),
+        }
+
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+    except Exception as e:
+        logger.error(f"Error in quarterly estimation: {str(e)}")
+        raise AppError("An error occurred. Please try again.")
+
+
+@app.post("/api/tax/premium-tax-credit")
+async def calculate_premium_tax_credit(request: PremiumTaxCreditRequest):
+    """
+    Reconcile advance premium tax credit (Form 8962) from 1095-A statements
+
+    Returns the net credit or the excess advance payments to repay.
+    """
+    try:
+        parsed = [parse_1095_a(text) for text in request.form_1095a_text]
+        calculator = PremiumTaxCreditCalculator(tax_year=2024)
+
+        result = round_amounts(calculator.calculate(
+            household_income=Decimal(str(request.household_income)),
+            family_size=request.family_size,
+            filing_status=request.filing_status,
+            statements=[p.record for p in parsed],
+            state=request.state,
+            married_separate_exception=request.married_separate_exception,
+        ), _rounding())
+        result["statements"] = [p.to_dict() for p in parsed]
+
+        return {
+            "success": True,
+            "data": result,
+            "timestamp": datetime.utcnow().isoformat(),
+        }
+
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+    except Exception as e:
+        logger.error(f"Error in premium tax credit calculation: {str(e)}")
+        raise AppError("An error occurred. Please try again.")
+
+
+# ============================================================================
+# TAX RETURN ENDPOINTS
+# ============================================================================
+
+return_store = ReturnStore()
+
+
+def _get_return_or_404(return_id: str) -> Dict[str, Any]:
+    """Load a stored return or raise 404"""
+    tax_return = return_store.get_return(return_id)
+    if tax_return is None:
+        raise NotFoundError(f"Return not found: {return_id}")
+    return tax_return
+
+
+def _return_view(tax_return: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    A return as API responses carry it
+
+    Alimony recipients' SSNs are decrypted, and every SSN is masked unless
+    the passphrase was entered within the reauth_minutes setting.
+    """
+    verified = app_lock.verified_within(settings_store.get_settings().reauth_minutes)
+    if tax_return.get("alimony"):
+        tax_return = {**tax_return, "alimony": return_store.get_alimony(tax_return["return_id"], reveal=verified)}
+    return tax_return if verified else mask_ssns(tax_return)
+
+
+def _require_editable(tax_return: Dict[str, Any]) -> None:
+    """Raise 409 when the return's status makes it read-only"""
+    if tax_return.get("status") in ReturnStore.LOCKED_STATUSES:
+        error = ReturnLockedError(tax_return["return_id"], tax_return["status"])
+        raise ConflictError(str(error))
+
+
+def _require_ai_configured() -> None:
+    """Raise 503 when the Anthropic API key is missing"""
+    if not get_api_key(secret_store):
+        raise AIServiceError(
+            "AI service not configured. Please save an API key in settings or set ANTHROPIC_API_KEY.",
+            status_code=503,
+        )
+
+
+@app.post("/api/returns")
+async def create_return(request: CreateReturnRequest):
+    """Create a new draft tax return"""
+    if request.client_id:
+        _get_client_or_404(request.client_id)
+    try:
+        tax_return = return_store.create_return(
+            tax_year=request.tax_year,
+            filing_status=request.filing_status,
+            taxpayer=request.taxpayer,
+            client_id=request.client_id,
+        )
+        return {
+            "success": True,
+            "data": _return_view(tax_return),
+            "timestamp": datetime.utcnow().isoformat(),
+        }
+    except Exception as e:
+        logger.error(f"Error creating return: {str(e)}")
+        raise StorageError("An error occurred. Please try again.")
+
+
+@app.post("/api/returns/import/txf")
+async def import_txf_return(request: TxfImportRequest):
+    """
+    Create a return from a TXF export (TurboTax and other tax software)
+
+    W-2s, interest, dividends, Schedule A deductions, and Form 8949 sales are
+    imported; other records are reported as warnings.
+    """
+    try:
+        result = import_txf(
+            return_store, request.txf_text,
+            tax_year=request.tax_year, filing_status=request.filing_status, taxpayer=request.taxpayer,
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": result,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/deadlines/upcoming")
+async def get_upcoming_deadlines(
+    within_days: int = Query(DEFAULT_REMINDER_DAYS, ge=0, le=366, description="How many days ahead to look"),
+    as_of: Optional[date] = Query(None, description="Date to count from (defaults to today)"),
+):
+    """
+    Reminders for unmet filing and payment deadlines across all returns
+
+    Includes overdue deadlines as well as those due within the window.
+    """
+    tax_returns = [return_store.get_return(summary["return_id"]) for summary in return_store.list_returns()]
+    reminders = upcoming_deadlines([r for r in tax_returns if r], as_of=as_of, within_days=within_days)
+    return {
+        "success": True,
+        "data": reminders,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+reminder_store = ReminderStore()
+
+
+def _calendar_events(return_id: Optional[str] = None) -> List[Dict[str, Any]]:
+    """Calendar events for one return, or every stored return"""
+    if return_id:
+        tax_returns = [_get_return_or_404(return_id)]
+    else:
+        tax_returns = [return_store.get_return(summary["return_id"]) for summary in return_store.list_returns()]
+    events = []
+    for tax_return in tax_returns:
+        documents = document_store.list_documents(return_id=tax_return["return_id"], include_archived=True)
+        events.extend(return_events(tax_return, documents))
+    events.sort(key=lambda e: (e["date"], e["event_id"]))
+    return events
+
+
+@app.get("/api/calendar")
+async def get_tax_calendar(
+    return_id: Optional[str] = Query(None, description="Only events for this return"),
+    start: Optional[date] = Query(None, description="First date to include"),
+    end: Optional[date] = Query(None, description="Last date to include"),
+):
+    """
+    Tax calendar: filing, payment, and extension deadlines, quarterly estimates,
+    the dates income forms should have arrived by, and IP PIN expiry
+    """
+    states = reminder_store.get_states()
+    events = [
+        {**event, **states.get(event["event_id"], {"snoozed_until": None, "dismissed_at": None})}
+        for event in _calendar_events(return_id)
+        if (start is None or event["date"] >= start.isoformat()) and (end is None or event["date"] <= end.isoformat())
+    ]
+    return {
+        "success": True,
+        "data": events,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/reminders")
+async def get_reminders(as_of: Optional[date] = Query(None, description="Date to count from (defaults to today)")):
+    """
+    Calendar reminders to notify the user about now
+
+    The frontend polls this and shows each reminder as a notification until
+    it is snoozed, dismissed, or the event is met. Estimated payment
+    reminders escalate: a dismissed one comes back three days out and again
+    on the due date.
+    """
+    reminders = due_reminders(_calendar_events(), reminder_store.get_states(), as_of=as_of)
+    return {
+        "success": True,
+        "data": reminders,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+def _require_event(event_id: str) -> None:
+    if event_id not in {event["event_id"] for event in _calendar_events()}:
+        raise NotFoundError("Calendar event not found")
+
+
+@app.post("/api/reminders/{event_id}/snooze")
+async def snooze_reminder(event_id: str, request: SnoozeRequest):
+    """Hide a reminder until a date (or for a number of days)"""
+    _require_event(event_id)
+    if (request.until is None) == (request.days is None):
+        raise InvalidInputError("Give either until or days")
+    until = request.until or date.today() + timedelta(days=request.days)
+    try:
+        state = reminder_store.snooze(event_id, until)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+    return {
+        "success": True,
+        "data": state,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/reminders/{event_id}/dismiss")
+async def dismiss_reminder(event_id: str):
+    """Stop reminding about an event"""
+    _require_event(event_id)
+    return {
+        "success": True,
+        "data": reminder_store.dismiss(event_id),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/reminders/{event_id}/restore")
+async def restore_reminder(event_id: str):
+    """Undo a snooze or dismissal"""
+    _require_event(event_id)
+    return {
+        "success": True,
+        "data": reminder_store.restore(event_id),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns")
+async def list_returns(client_id: Optional[str] = Query(None, description="Only returns for this client")):
+    """List stored tax returns"""
+    return {
+        "success": True,
+        "data": return_store.list_returns(client_id=client_id),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}")
+async def get_return(return_id: str):
+    """
+    Get a stored tax return
+
+    SSNs, including alimony recipients' decrypted SSNs, are masked unless
+    the passphrase was entered within the reauth_minutes setting
+    (POST /api/lock/verify).
+    """
+    return {
+        "success": True,
+        "data": _return_view(_get_return_or_404(return_id)),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/recalculate")
+async def recalculate_return(return_id: str):
+    """
+    Recalculate a return from its stored records
+
+    Runs the engine over income sources, businesses (SE tax), capital
+    transactions, deductions, dependent credits, and withholding, and saves
+    calculated_tax and refund_or_owed on the return.
+    """
+    tax_return = _get_return_or_404(return_id)
+    _require_editable(tax_return)
+
+    try:
+        calculation = calculate_return(tax_return, rounding=_rounding())
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+    return_store.save_calculation(return_id, calculation)
+
+    return {
+        "success": True,
+        "data": calculation,
+        "disclaimer": TaxCalculator.LEGAL_DISCLAIMER.strip(),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/states")
+async def list_state_returns(return_id: str):
+    """List the state returns attached to a return"""
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": tax_return.get("state_returns", []),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/states")
+async def add_state_return(return_id: str, request: StateReturnRequest):
+    """Attach a resident, part-year, or nonresident state return"""
+    _require_editable(_get_return_or_404(return_id))
+    if request.state.upper() not in SUPPORTED_STATES:
+        raise InvalidInputError(
+            f"Unsupported state: {request.state}. Supported: {', '.join(SUPPORTED_STATES)}",
+        )
+
+    fields = request.model_dump(exclude={"state", "residency"}, exclude_none=True)
+    try:
+        record = return_store.add_state_return(return_id, request.state, request.residency, **fields)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": record,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.patch("/api/returns/{return_id}/states/{state_return_id}")
+async def update_state_return(return_id: str, state_return_id: str, request: StateReturnUpdateRequest):
+    """Change a state return's residency, allocated income, or withholding"""
+    _require_editable(_get_return_or_404(return_id))
+
+    try:
+        record = return_store.update_state_return(
+            return_id, state_return_id, **request.model_dump(exclude_unset=True)
+        )
+    except KeyError:
+        raise NotFoundError(f"State return not found: {state_return_id}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": record,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/returns/{return_id}/states/{state_return_id}")
+async def delete_state_return(return_id: str, state_return_id: str):
+    """Remove a state return"""
+    _require_editable(_get_return_or_404(return_id))
+
+    try:
+        return_store.delete_state_return(return_id, state_return_id)
+    except KeyError:
+        raise NotFoundError(f"State return not found: {state_return_id}")
+
+    return {
+        "success": True,
+        "data": {"state_return_id": state_return_id, "deleted": True},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/foreign-accounts")
+async def list_foreign_accounts(return_id: str):
+    """List a return's foreign financial accounts"""
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": tax_return.get("foreign_accounts", []),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/foreign-accounts")
+async def add_foreign_account(return_id: str, request: ForeignAccountRequest):
+    """Record a financial account held outside the U.S."""
+    _require_editable(_get_return_or_404(return_id))
+    fields = request.model_dump(exclude={"institution", "country", "max_balance"}, exclude_none=True)
+    try:
+        account = return_store.add_foreign_account(
+            return_id, request.institution, request.country, request.max_balance, **fields
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": account,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/foreign-accounts/check")
+async def check_return_foreign_accounts(
+    return_id: str,
+    lives_abroad: bool = Query(False, description="Taxpayer meets the bona fide residence or presence abroad test"),
+):
+    """Whether the return's foreign accounts require an FBAR or Form 8938"""
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": check_foreign_accounts(
+            tax_return.get("foreign_accounts", []), tax_return["filing_status"], lives_abroad=lives_abroad
+        ),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.patch("/api/returns/{return_id}/foreign-accounts/{account_id}")
+async def update_foreign_account(return_id: str, account_id: str, request: ForeignAccountUpdateRequest):
+    """Change a foreign account's balances or details"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        account = return_store.update_foreign_account(
+            return_id, account_id, **request.model_dump(exclude_unset=True)
+        )
+    except KeyError:
+        raise NotFoundError(f"Foreign account not found: {account_id}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": account,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/returns/{return_id}/foreign-accounts/{account_id}")
+async def delete_foreign_account(return_id: str, account_id: str):
+    """Remove a foreign account"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        return_store.delete_foreign_account(return_id, account_id)
+    except KeyError:
+        raise NotFoundError(f"Foreign account not found: {account_id}")
+
+    return {
+        "success": True,
+        "data": {"account_id": account_id, "deleted": True},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+def _schedule_b_payers(tax_return: Dict[str, Any]) -> List[Dict[str, Any]]:
+    return [s for s in tax_return.get("income_sources", []) if s["type"] in ReturnStore.SCHEDULE_B_TYPES]
+
+
+@app.get("/api/returns/{return_id}/schedule-b")
+async def get_schedule_b(return_id: str):
+    """Schedule B interest and dividend lines, Part III answers, and whether the return needs it"""
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": round_amounts(calculate_schedule_b(tax_return), _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/schedule-b/payers")
+async def list_schedule_b_payers(return_id: str):
+    """List a return's interest and dividend payers"""
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": _schedule_b_payers(tax_return),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/schedule-b/payers")
+async def add_schedule_b_payer(return_id: str, request: ScheduleBPayerRequest):
+    """Record interest or dividends from a payer (a 1099-INT or 1099-DIV)"""
+    _require_editable(_get_return_or_404(return_id))
+    if request.type not in ReturnStore.SCHEDULE_B_TYPES:
+        raise InvalidInputError(f"Payer type must be one of: {', '.join(ReturnStore.SCHEDULE_B_TYPES)}")
+    fields = request.model_dump(exclude={"type", "payer", "amount", "withholding"}, exclude_none=True)
+    try:
+        source = return_store.add_income_source(
+            return_id, request.type, request.amount, description=request.payer, withholding=request.withholding,
+            **fields,
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": source,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.patch("/api/returns/{return_id}/schedule-b/payers/{source_id}")
+async def update_schedule_b_payer(return_id: str, source_id: str, request: ScheduleBPayerUpdateRequest):
+    """Change a payer's amounts or flags"""
+    tax_return = _get_return_or_404(return_id)
+    _require_editable(tax_return)
+    if not any(s["id"] == source_id for s in _schedule_b_payers(tax_return)):
+        raise NotFoundError(f"Payer not found: {source_id}")
+    changes = request.model_dump(exclude_unset=True)
+    if "payer" in changes:
+        changes["description"] = changes.pop("payer")
+    try:
+        source = return_store.update_income_source(return_id, source_id, **changes)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": source,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/returns/{return_id}/schedule-b/payers/{source_id}")
+async def delete_schedule_b_payer(return_id: str, source_id: str):
+    """Remove a payer"""
+    tax_return = _get_return_or_404(return_id)
+    _require_editable(tax_return)
+    if not any(s["id"] == source_id for s in _schedule_b_payers(tax_return)):
+        raise NotFoundError(f"Payer not found: {source_id}")
+    return_store.delete_income_source(return_id, source_id)
+
+    return {
+        "success": True,
+        "data": {"source_id": source_id, "deleted": True},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/form-5329")
+async def get_form_5329(return_id: str):
+    """Form 5329 Part I: the additional tax on the return's early retirement distributions"""
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": round_amounts(calculate_form_5329(tax_return), _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+def _require_retirement_source(tax_return: Dict[str, Any], source_id: str) -> None:
+    if not any(s["id"] == source_id and s["type"] == "retirement" for s in tax_return.get("income_sources", [])):
+        raise NotFoundError(f"Retirement distribution not found: {source_id}")
+
+
+@app.patch("/api/returns/{return_id}/early-distributions/{source_id}")
+async def set_early_distribution(return_id: str, source_id: str, request: EarlyDistributionRequest):
+    """Record a retirement distribution's 1099-R code, plan type, and the exception claimed"""
+    tax_return = _get_return_or_404(return_id)
+    _require_editable(tax_return)
+    _require_retirement_source(tax_return, source_id)
+    try:
+        source = return_store.update_income_source(return_id, source_id, **request.model_dump(exclude_unset=True))
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": source,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/early-distributions/{source_id}/evaluate")
+async def evaluate_early_distribution_exception(return_id: str, source_id: str, request: ExceptionEvaluationRequest):
+    """Whether an exception to the early distribution tax applies, and how much it covers"""
+    tax_return = _get_return_or_404(return_id)
+    _require_retirement_source(tax_return, source_id)
+    try:
+        agi = Decimal(str(calculate_return(tax_return)["agi"]))
+        result = evaluate_exception(
+            tax_return, source_id, agi,
+            exception_code=request.exception_code,
+            age_at_separation=request.age_at_separation,
+            public_safety_employee=request.public_safety_employee,
+            weeks_unemployed=request.weeks_unemployed,
+            health_insurance_premiums=(
+                Decimal(str(request.health_insurance_premiums)) if request.health_insurance_premiums is not None
+                else None
+            ),
+            education_expenses=(
+                Decimal(str(request.education_expenses)) if request.education_expenses is not None else None
+            ),
+            first_home_used_before=Decimal(str(request.first_home_used_before)),
+            children=request.children,
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": round_amounts(result, _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/state-refund")
+async def get_state_refund(return_id: str):
+    """The state and local income tax refund worksheet: how much of the refund is taxable"""
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": round_amounts(state_refund_worksheet(tax_return), _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/state-refund")
+async def add_state_refund(return_id: str, request: StateRefundRequest):
+    """Record a state or local income tax refund (1099-G box 2)"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        source = return_store.add_income_source(return_id, "state_refund", request.amount, description=request.payer)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": source,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/state-refund/prior-year")
+async def load_prior_year_deductions(return_id: str, request: PriorYearDeductionsRequest):
+    """Copy last year's deduction figures from its stored return, for the refund worksheet"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        figures = return_store.load_prior_year_deductions(return_id, request.prior_return_id)
+    except KeyError as e:
+        raise NotFoundError(e.args[0])
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": figures,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+def _get_rental_property_or_404(tax_return: Dict[str, Any], property_id: str) -> Dict[str, Any]:
+    for rental in tax_return.get("rental_properties", []):
+        if rental["id"] == property_id:
+            return rental
+    raise NotFoundError(f"Rental property not found: {property_id}")
+
+
+@app.get("/api/returns/{return_id}/rentals")
+async def list_rental_properties(return_id: str):
+    """List a return's rental properties with this year's depreciation"""
+    tax_return = _get_return_or_404(return_id)
+    rentals = [
+        {**rental, "current_year": rental_property_year(rental, tax_return["tax_year"])}
+        for rental in tax_return.get("rental_properties", [])
+    ]
+    return {
+        "success": True,
+        "data": round_amounts(rentals, _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/rentals")
+async def add_rental_property(return_id: str, request: RentalPropertyRequest):
+    """Add a residential rental property"""
+    _require_editable(_get_return_or_404(return_id))
+    fields = request.model_dump(exclude={"address", "building_basis", "placed_in_service"}, exclude_none=True)
+    try:
+        rental = return_store.add_rental_property(
+            return_id, request.address, request.building_basis, request.placed_in_service, **fields
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": rental,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/rentals/{property_id}/depreciation")
+async def get_rental_depreciation(return_id: str, property_id: str):
+    """
+    A rental's 27.5-year depreciation schedule
+
+    Includes this year's figures and, if it was sold this year, the gain and
+    depreciation recapture.
+    """
+    tax_return = _get_return_or_404(return_id)
+    rental = _get_rental_property_or_404(tax_return, property_id)
+    schedule = depreciation_schedule(
+        Decimal(str(rental["building_basis"])), rental["placed_in_service"], rental.get("date_sold")
+    )
+    return {
+        "success": True,
+        "data": round_amounts({
+            "property_id": property_id,
+            "schedule": schedule,
+            "current_year": rental_property_year(rental, tax_return["tax_year"]),
+        }, _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.patch("/api/returns/{return_id}/rentals/{property_id}")
+async def update_rental_property(return_id: str, property_id: str, request: RentalPropertyUpdateRequest):
+    """Change a rental property's figures, or record its sale (date_sold, sale_price)"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        rental = return_store.update_rental_property(return_id, property_id, **request.model_dump(exclude_unset=True))
+    except KeyError:
+        raise NotFoundError(f"Rental property not found: {property_id}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": rental,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/rentals/{property_id}/exchange")
+async def exchange_rental_property(return_id: str, property_id: str, request: LikeKindExchangeRequest):
+    """
+    Exchange a rental for another under section 1031
+
+    Closes out the property with only the boot-sized part of its gain
+    recognized and adds the replacement with the deferred gain carried in its
+    basis.
+    """
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        exchange = return_store.record_like_kind_exchange(return_id, property_id, **request.model_dump())
+    except KeyError:
+        raise NotFoundError(f"Rental property not found: {property_id}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": exchange,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/returns/{return_id}/rentals/{property_id}")
+async def delete_rental_property(return_id: str, property_id: str):
+    """Remove a rental property"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        return_store.delete_rental_property(return_id, property_id)
+    except KeyError:
+        raise NotFoundError(f"Rental property not found: {property_id}")
+
+    return {
+        "success": True,
+        "data": {"property_id": property_id, "deleted": True},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/crypto/lots")
+async def list_crypto_lots(return_id: str):
+    """List a return's crypto basis lots"""
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": tax_return.get("crypto_lots", []),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/crypto/receipts")
+async def add_crypto_receipt(return_id: str, request: CryptoReceiptRequest):
+    """
+    Record crypto received
+
+    Staking, mining, and airdrop rewards are ordinary income at their fair
+    market value when received; that value is the basis of the new lot.
+    """
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        receipt = return_store.add_crypto_receipt(return_id, **request.model_dump())
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": receipt,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/crypto/disposals")
+async def dispose_crypto(return_id: str, request: CryptoDisposalRequest):
+    """Sell, trade, or spend crypto; adds capital transactions from the oldest lots first"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        transactions = return_store.dispose_crypto(return_id, **request.model_dump())
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": transactions,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/holdings")
+async def list_holdings(return_id: str):
+    """List a return's open investment positions"""
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": tax_return.get("holdings", []),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/holdings")
+async def add_holding(return_id: str, request: HoldingRequest):
+    """Record an open investment position"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        holding = return_store.add_holding(return_id, **request.model_dump())
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": holding,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.patch("/api/returns/{return_id}/holdings/{holding_id}")
+async def update_holding(return_id: str, holding_id: str, request: HoldingUpdateRequest):
+    """Change a holding, usually its current market value"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        holding = return_store.update_holding(return_id, holding_id, **request.model_dump(exclude_unset=True))
+    except KeyError:
+        raise NotFoundError(f"Holding not found: {holding_id}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": holding,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/returns/{return_id}/holdings/{holding_id}")
+async def delete_holding(return_id: str, holding_id: str):
+    """Remove a holding"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        return_store.delete_holding(return_id, holding_id)
+    except KeyError:
+        raise NotFoundError(f"Holding not found: {holding_id}")
+
+    return {
+        "success": True,
+        "data": {"holding_id": holding_id, "deleted": True},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/tax-loss-harvesting")
+async def get_tax_loss_harvesting(
+    return_id: str,
+    as_of: Optional[str] = Query(None, description="Sale date to assume (YYYY-MM-DD, defaults to today)"),
+):
+    """
+    Holdings to sell at a loss before year-end
+
+    Flags positions with shares bought in the last 30 days (wash sales) and
+    projects the tax saved by selling the rest at their current value.
+    """
+    tax_return = _get_return_or_404(return_id)
+    try:
+        result = harvesting_suggestions(
+            tax_return, tax_return.get("holdings", []), as_of=date.fromisoformat(as_of) if as_of else None
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": round_amounts(result, _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/dependents")
+async def add_dependent(return_id: str, request: DependentRequest):
+    """Add a dependent to a return"""
+    _require_editable(_get_return_or_404(return_id))
+    fields = request.model_dump(exclude_none=True, exclude={"name", "relationship"})
+    try:
+        dependent = return_store.add_dependent(return_id, request.name, request.relationship, **fields)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": dependent,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.patch("/api/returns/{return_id}/dependents/{dependent_id}")
+async def update_dependent(return_id: str, dependent_id: str, request: DependentUpdateRequest):
+    """Change a dependent's details"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        dependent = return_store.update_dependent(return_id, dependent_id, **request.model_dump(exclude_unset=True))
+    except KeyError:
+        raise NotFoundError(f"Dependent not found: {dependent_id}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": dependent,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/dependents/qualification")
+async def get_dependent_qualification(return_id: str):
+    """
+    Qualifying child and qualifying relative tests for the return's dependents
+
+    Reports each test's result (pass, fail, or unknown when the record is
+    missing the fact), whether the dependent qualifies, and for which
+    benefits: child tax credit, credit for other dependents, earned income
+    credit, and head of household.
+    """
+    tax_return = _get_return_or_404(return_id)
+    try:
+        qualification = qualify_dependents(tax_return)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": qualification,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.put("/api/returns/{return_id}/divorce")
+async def set_divorce(return_id: str, request: DivorceRequest):
+    """Record a divorce or separation (decree and separation dates)"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        divorce = return_store.set_divorce(return_id, **request.model_dump())
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": divorce,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/alimony")
+async def add_alimony(return_id: str, request: AlimonyRequest):
+    """Record alimony paid or received"""
+    _require_editable(_get_return_or_404(return_id))
+    fields = request.model_dump(exclude_none=True, exclude={"direction", "amount", "instrument_date"})
+    try:
+        record = return_store.add_alimony(
+            return_id, request.direction, request.amount, request.instrument_date, **fields
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": record,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/returns/{return_id}/alimony/{alimony_id}")
+async def delete_alimony(return_id: str, alimony_id: str):
+    """Remove an alimony record"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        return_store.delete_alimony(return_id, alimony_id)
+    except KeyError:
+        raise NotFoundError(f"Alimony not found: {alimony_id}")
+
+    return {
+        "success": True,
+        "data": {"alimony_id": alimony_id, "deleted": True},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/divorce-year")
+async def get_divorce_year(return_id: str):
+    """
+    Filing options for the year of a divorce or separation
+
+    Shows which filing statuses are allowed and why, the tax under each,
+    who claims each child (Form 8332), and how the alimony is treated.
+    """
+    tax_return = _get_return_or_404(return_id)
+    try:
+        report = round_amounts(divorce_year_report(tax_return), _rounding())
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": report,
+        "disclaimer": TaxCalculator.LEGAL_DISCLAIMER.strip(),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.put("/api/returns/{return_id}/death")
+async def set_death(return_id: str, request: DeathRequest):
+    """Record the death of the taxpayer or spouse"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        death = return_store.set_death(return_id, **request.model_dump())
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": death,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/decedent")
+async def get_decedent_filing_options(return_id: str):
+    """
+    Filing statuses allowed after the taxpayer's or spouse's death
+
+    Covers the final return (joint with the surviving spouse in the year of
+    death) and the surviving spouse's later returns (qualifying surviving
+    spouse for two years when eligible), with what each return must include.
+    """
+    tax_return = _get_return_or_404(return_id)
+    try:
+        options = decedent_filing_options(tax_return)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": options,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/injured-spouse")
+async def allocate_injured_spouse(return_id: str, request: InjuredSpouseRequest):
+    """
+    Allocate a joint return between the spouses for Form 8379
+
+    Shows the injured spouse's share of the refund, and with a past-due debt
+    how much of the refund the offset takes with and without the form.
+    Nothing is saved.
+    """
+    tax_return = _get_return_or_404(return_id)
+    try:
+        result = injured_spouse_allocation(
+            tax_return,
+            injured_spouse=request.injured_spouse,
+            past_due_debt=Decimal(str(request.past_due_debt)) if request.past_due_debt is not None else None,
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": round_amounts(result, _rounding()),
+        "disclaimer": TaxCalculator.LEGAL_DISCLAIMER.strip(),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/peer-comparison")
+async def get_peer_comparison(return_id: str):
+    """
+    Compare a return's itemized deductions with IRS averages for its AGI band
+
+    Averages come from the IRS Statistics of Income table embedded with the
+    app; categories at twice the average or more are flagged.
+    """
+    tax_return = _get_return_or_404(return_id)
+    try:
+        result = compare_with_peers(tax_return)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": round_amounts(result, _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/students")
+async def list_students(return_id: str):
+    """List a return's students with their education expenses"""
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": tax_return.get("students", []),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/students")
+async def add_student(return_id: str, request: StudentRequest):
+    """Add a student whose expenses count toward the education credits"""
+    _require_editable(_get_return_or_404(return_id))
+    fields = request.model_dump(exclude_none=True, exclude={"name"})
+    try:
+        student = return_store.add_student(return_id, request.name, **fields)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": student,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.patch("/api/returns/{return_id}/students/{student_id}")
+async def update_student(return_id: str, student_id: str, request: StudentUpdateRequest):
+    """Change a student's enrollment or credit history"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        student = return_store.update_student(return_id, student_id, **request.model_dump(exclude_unset=True))
+    except KeyError:
+        raise NotFoundError(f"Student not found: {student_id}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": student,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/returns/{return_id}/students/{student_id}")
+async def delete_student(return_id: str, student_id: str):
+    """Remove a student and their expenses"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        return_store.delete_student(return_id, student_id)
+    except KeyError:
+        raise NotFoundError(f"Student not found: {student_id}")
+
+    return {
+        "success": True,
+        "data": {"student_id": student_id, "deleted": True},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/students/{student_id}/expenses")
+async def add_education_expense(return_id: str, student_id: str, request: EducationExpenseRequest):
+    """Record tuition, fees, or course materials, or a scholarship or refund that reduces them"""
+    _require_editable(_get_return_or_404(return_id))
+    fields = request.model_dump(exclude_none=True, exclude={"kind", "amount", "description"})
+    try:
+        expense = return_store.add_education_expense(
+            return_id, student_id, request.kind, request.amount, request.description, **fields
+        )
+    except KeyError:
+        raise NotFoundError(f"Student not found: {student_id}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": expense,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/returns/{return_id}/students/{student_id}/expenses/{expense_id}")
+async def delete_education_expense(return_id: str, student_id: str, expense_id: str):
+    """Remove a student's education expense"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        return_store.delete_education_expense(return_id, student_id, expense_id)
+    except KeyError as e:
+        raise NotFoundError(str(e.args[0]))
+
+    return {
+        "success": True,
+        "data": {"expense_id": expense_id, "deleted": True},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/students/{student_id}/form-1098-t")
+async def import_form_1098t(return_id: str, student_id: str, request: Form1098TImportRequest):
+    """
+    Import a 1098-T into a student's education expenses
+
+    Send the statement's text, or the ID of a stored document already parsed
+    as a 1098-T. Importing the same document again replaces its expenses.
+    """
+    _require_editable(_get_return_or_404(return_id))
+    if request.document_id:
+        extraction = _get_document_or_404(request.document_id).get("extraction")
+        if not extraction:
+            raise InvalidInputError("Document has no extracted data; parse it first")
+    elif request.text:
+        extraction = parse_1098_t(request.text).to_dict()
+    else:
+        raise InvalidInputError("Send the 1098-T text or a parsed document_id")
+
+    try:
+        student = return_store.import_1098t(return_id, student_id, extraction, document_id=request.document_id)
+    except KeyError:
+        raise NotFoundError(f"Student not found: {student_id}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": {"student": student, "extraction": extraction},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/education-credits")
+async def get_education_credits(return_id: str):
+    """
+    American opportunity and lifetime learning credits for the return's students
+
+    Shows which credit each student takes, the phase-out, and how much of
+    the credit is refundable and how much offsets tax.
+    """
+    tax_return = _get_return_or_404(return_id)
+    try:
+        calculation = calculate_return(tax_return)
+        credits = calculation["education_credits"] or calculate_education_credits(
+            [], Decimal(str(calculation["agi"])), tax_return["filing_status"], tax_return["tax_year"]
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": round_amounts(credits, _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/adoptions")
+async def list_adoptions(return_id: str):
+    """List the children being adopted on a return"""
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": tax_return.get("adoptions", []),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/adoptions")
+async def add_adoption(return_id: str, request: AdoptionRequest):
+    """Add a child being adopted and the qualified adoption expenses paid"""
+    _require_editable(_get_return_or_404(return_id))
+    fields = request.model_dump(exclude_none=True, exclude={"name"})
+    try:
+        adoption = return_store.add_adoption(return_id, request.name, **fields)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": adoption,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.patch("/api/returns/{return_id}/adoptions/{adoption_id}")
+async def update_adoption(return_id: str, adoption_id: str, request: AdoptionUpdateRequest):
+    """Change an adoption's expenses, final year, or status"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        adoption = return_store.update_adoption(return_id, adoption_id, **request.model_dump(exclude_unset=True))
+    except KeyError:
+        raise NotFoundError(f"Adoption not found: {adoption_id}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": adoption,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/returns/{return_id}/adoptions/{adoption_id}")
+async def delete_adoption(return_id: str, adoption_id: str):
+    """Remove an adoption"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        return_store.delete_adoption(return_id, adoption_id)
+    except KeyError:
+        raise NotFoundError(f"Adoption not found: {adoption_id}")
+
+    return {
+        "success": True,
+        "data": {"adoption_id": adoption_id, "deleted": True},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/adoption-credit")
+async def get_adoption_credit(return_id: str):
+    """
+    The adoption credit (Form 8839): each child's credit, the phase-out, and
+    what the tax can't absorb carried forward
+    """
+    tax_return = _get_return_or_404(return_id)
+    try:
+        calculation = calculate_return(tax_return)
+        credit = calculation["adoption_credit"] or calculate_adoption_credit(
+            [], Decimal(str(calculation["agi"])), tax_return["filing_status"], tax_return["tax_year"]
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": round_amounts(credit, _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/charitable-bunching")
+async def compare_charitable_bunching(return_id: str, request: CharitableBunchingRequest):
+    """
+    Compare giving every year with bunching two years into a donor-advised fund
+
+    Reports each strategy's tax year by year and the total saved over the
+    horizon. Nothing is saved.
+    """
+    tax_return = _get_return_or_404(return_id)
+    try:
+        result = compare_bunching(
+            tax_return,
+            annual_donation=Decimal(str(request.annual_donation)) if request.annual_donation is not None else None,
+            years=request.years,
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": round_amounts(result, _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/relocation")
+async def compare_state_relocation(return_id: str, request: RelocationRequest):
+    """
+    Compare this year's taxes with living in another state
+
+    State, local, and property taxes for each location, with the federal tax
+    recalculated for the change in the SALT deduction. Nothing is saved.
+    """
+    tax_return = _get_return_or_404(return_id)
+
+    def optional(value: Optional[float]) -> Optional[Decimal]:
+        return Decimal(str(value)) if value is not None else None
+
+    try:
+        result = compare_relocation(
+            tax_return,
+            request.to_state,
+            from_state=request.from_state,
+            local_tax_rate=Decimal(str(request.local_tax_rate)),
+            new_local_tax_rate=Decimal(str(request.new_local_tax_rate)),
+            property_tax=optional(request.property_tax),
+            new_property_tax=optional(request.new_property_tax),
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": round_amounts(result, _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/raise")
+async def value_raise(return_id: str, request: RaiseRequest):
+    """
+    What a raise or job offer is worth after taxes
+
+    Federal, state, local, and FICA taxes on the extra pay, with the credits
+    it phases out and the marginal rate across slices of the raise. Nothing
+    is saved.
+    """
+    tax_return = _get_return_or_404(return_id)
+
+    def optional(value: Optional[float]) -> Optional[Decimal]:
+        return Decimal(str(value)) if value is not None else None
+
+    try:
+        result = raise_after_tax(
+            tax_return,
+            raise_amount=optional(request.raise_amount),
+            new_salary=optional(request.new_salary),
+            owner=request.owner,
+            state=request.state,
+            local_tax_rate=Decimal(str(request.local_tax_rate)),
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": round_amounts(result, _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/travel")
+async def list_travel_days(return_id: str):
+    """List the return's travel log"""
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": tax_return.get("travel_log", []),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/travel")
+async def add_travel_days(return_id: str, request: TravelDaysRequest):
+    """Log days spent in a state"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        entry = return_store.add_travel_days(
+            return_id, request.state, request.start_date, request.end_date, note=request.note
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": entry,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/returns/{return_id}/travel/{entry_id}")
+async def delete_travel_days(return_id: str, entry_id: str):
+    """Remove a travel log entry"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        return_store.delete_travel_days(return_id, entry_id)
+    except KeyError:
+        raise NotFoundError(f"Travel log entry not found: {entry_id}")
+
+    return {
+        "success": True,
+        "data": {"entry_id": entry_id, "deleted": True},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/residency-days")
+async def get_residency_days(
+    return_id: str,
+    home_state: Optional[str] = Query(None, description="State of domicile (defaults to the resident state return)"),
+    as_of: Optional[date] = Query(None, description="Count through this date (defaults to the whole year)"),
+):
+    """
+    Days spent in each state against statutory residency thresholds
+
+    Warns when a state's day count meets or nears its threshold and when
+    two states could both claim the taxpayer as a resident.
+    """
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": return_residency_days(tax_return, home_state=home_state, as_of=as_of),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/states/calculate")
+async def calculate_state_returns(return_id: str):
+    """
+    Compute tax and balance for every state return
+
+    Part-year and nonresident tax is prorated by allocated income; the resident
+    state credits tax paid to the others. Results are saved on each state return.
+    """
+    tax_return = _get_return_or_404(return_id)
+    _require_editable(tax_return)
+    if not tax_return.get("state_returns"):
+        raise InvalidInputError("Return has no state returns")
+
+    try:
+        agi = Decimal(str(calculate_return(tax_return)["agi"]))
+        results = StateTaxCalculator(tax_return["tax_year"]).calculate(
+            tax_return["state_returns"], agi, tax_return["filing_status"], tax_return.get("income_sources", [])
+        )
+        results = round_amounts(results, _rounding())
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+    return_store.save_state_calculations(return_id, results)
+
+    return {
+        "success": True,
+        "data": results,
+        "disclaimer": TaxCalculator.LEGAL_DISCLAIMER.strip(),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/status")
+async def set_return_status(return_id: str, request: ReturnStatusRequest):
+    """
+    Move a return through draft -> in_progress -> review -> filed
+
+    Filed returns are read-only; changing one means moving it to amended,
+    which keeps a copy of the filed figures.
+    """
+    _get_return_or_404(return_id)
+
+    try:
+        tax_return = return_store.set_status(return_id, request.status)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": _return_view(tax_return),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/clone")
+async def clone_return(return_id: str, request: CloneReturnRequest):
+    """
+    Start next year's return from this one
+
+    Copies taxpayer info, dependents, income sources and businesses (amounts
+    cleared), and recurring deductions, and carries forward capital losses,
+    NOLs, AMT credits, unused adoption credit, and each activity's at-risk amount
+    and disallowed losses.
+    """
+    tax_return = _get_return_or_404(return_id)
+    activities = tax_return.get("businesses", []) + tax_return.get("rental_properties", [])
+
+    try:
+        at_risk = None
+        if any("at_risk_amount" in a or "at_risk_carryforward" in a for a in activities):
+            at_risk = calculate_at_risk(tax_return)
+        calculation = None
+        if tax_return.get("adoptions") or (tax_return.get("carryforwards") or {}).get("adoption_credit"):
+            calculation = calculate_return(tax_return)
+        cloned = return_store.clone_return(
+            return_id, request.tax_year, carryforwards=next_year_carryforwards(tax_return, calculation),
+            at_risk=at_risk,
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": _return_view(cloned),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.put("/api/returns/{return_id}/carryforwards")
+async def set_carryforwards(return_id: str, request: CarryforwardsRequest):
+    """Set the carryforwards coming into a return from prior years"""
+    _require_editable(_get_return_or_404(return_id))
+
+    try:
+        carryforwards = return_store.set_carryforwards(return_id, **request.model_dump(exclude_none=True))
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": carryforwards,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/at-risk")
+async def get_at_risk(return_id: str):
+    """
+    At-risk limits for the return's businesses and rentals (Form 6198)
+
+    Losses beyond an activity's amount at risk are disallowed this year and
+    carried forward with it.
+    """
+    tax_return = _get_return_or_404(return_id)
+    try:
+        activities = calculate_at_risk(tax_return)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": round_amounts(activities, _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.put("/api/returns/{return_id}/at-risk/{activity_id}")
+async def set_at_risk(return_id: str, activity_id: str, request: AtRiskRequest):
+    """Set a business's or rental's amount at risk and at-risk loss carryforward"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        activity = return_store.set_at_risk(return_id, activity_id, **request.model_dump())
+    except KeyError:
+        raise NotFoundError(f"Activity not found: {activity_id}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": activity,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/platform-income")
+async def get_platform_income(return_id: str):
+    """
+    Compare the return's 1099-K forms with the business income recorded for them
+
+    Flags platforms with no income recorded, less recorded than the gross,
+    and a 1099-NEC from the same platform on top of the 1099-K.
+    """
+    tax_return = _get_return_or_404(return_id)
+    report = reconcile_platform_income(tax_return, document_store.list_documents(return_id=return_id))
+    return {
+        "success": True,
+        "data": round_amounts(report, _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.put("/api/returns/{return_id}/platform-income/{document_id}")
+async def set_platform_allocation(return_id: str, document_id: str, request: PlatformAllocationRequest):
+    """Split a 1099-K's gross between personal-item sales and a business's receipts"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        require_1099_k(document_store, document_id)
+        allocation = return_store.set_platform_allocation(return_id, document_id, **request.model_dump())
+    except KeyError as e:
+        raise NotFoundError(e.args[0])
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": allocation,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/hobby-test/questions")
+async def get_hobby_test_questions():
+    """The nine factors for whether an activity is engaged in for profit, with the question for each"""
+    return {
+        "success": True,
+        "data": [{"factor": name, "title": title, "question": question} for name, title, question in HOBBY_FACTORS],
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/hobby-determinations")
+async def list_hobby_determinations(return_id: str):
+    """Hobby-or-business determinations recorded on a return, with their rationale"""
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": tax_return.get("hobby_determinations", []),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/hobby-determinations")
+async def add_hobby_determination(return_id: str, request: HobbyDeterminationRequest):
+    """
+    Record answers to the nine-factor test and get a Schedule C or hobby recommendation
+
+    The answers, recommendation, and rationale are stored on the return.
+    """
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        determination = return_store.save_hobby_determination(return_id, **request.model_dump())
+    except KeyError as e:
+        raise NotFoundError(e.args[0])
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": determination,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/returns/{return_id}/hobby-determinations/{determination_id}")
+async def delete_hobby_determination(return_id: str, determination_id: str):
+    """Remove a hobby-or-business determination"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        return_store.delete_hobby_determination(return_id, determination_id)
+    except KeyError:
+        raise NotFoundError(f"Determination not found: {determination_id}")
+
+    return {
+        "success": True,
+        "data": {"determination_id": determination_id, "deleted": True},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.put("/api/returns/{return_id}/notes")
+async def set_return_notes(return_id: str, request: ReturnNotesRequest):
+    """Replace a return's preparation notes"""
+    _require_editable(_get_return_or_404(return_id))
+    return {
+        "success": True,
+        "data": _return_view(return_store.set_notes(return_id, request.notes)),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/deadlines")
+async def get_return_deadlines(return_id: str):
+    """Federal and state filing and payment deadlines for a return"""
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": {
+            "extension": tax_return.get("extension"),
+            "deadlines": return_deadlines(tax_return),
+        },
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/extension")
+async def record_extension(return_id: str, request: ExtensionRequest):
+    """Record a filed extension, moving the return's filing deadlines"""
+    _require_editable(_get_return_or_404(return_id))
+
+    try:
+        extension = return_store.set_extension(
+            return_id,
+            filed_on=(request.filed_on or date.today()).isoformat(),
+            confirmation=request.confirmation,
+            states=request.states,
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": {
+            "extension": extension,
+            "deadlines": return_deadlines(return_store.get_return(return_id)),
+        },
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/estimated-payments")
+async def list_estimated_payments(return_id: str):
+    """Estimated tax payments on a return: next year's logged here, and this year's carried over from last year"""
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": tax_return.get("estimated_payments", []),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/estimated-payments")
+async def add_estimated_payment(return_id: str, request: EstimatedPaymentRequest):
+    """
+    Log a payment of next year's estimated tax
+
+    The quarter's calendar event is met, so its reminders stop. Filed
+    returns accept payments too.
+    """
+    _get_return_or_404(return_id)
+    try:
+        payment = return_store.add_estimated_payment(
+            return_id, request.quarter, request.amount, request.paid_date.isoformat(), request.description
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": payment,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/returns/{return_id}/estimated-payments/{payment_id}")
+async def delete_estimated_payment(return_id: str, payment_id: str):
+    """Remove a logged estimated tax payment, restarting that quarter's reminders"""
+    _get_return_or_404(return_id)
+    try:
+        return_store.delete_estimated_payment(return_id, payment_id)
+    except KeyError:
+        raise NotFoundError(f"Estimated payment not found: {payment_id}")
+
+    return {
+        "success": True,
+        "data": {"payment_id": payment_id, "deleted": True},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/direct-deposit")
+async def get_direct_deposit(return_id: str):
+    """Get a return's refund deposit account with the numbers masked"""
+    _get_return_or_404(return_id)
+    details = return_store.get_direct_deposit(return_id)
+    return {
+        "success": True,
+        "data": details,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.put("/api/returns/{return_id}/direct-deposit")
+async def set_direct_deposit(return_id: str, request: DirectDepositRequest):
+    """Set the account a return's refund is deposited to (stored encrypted)"""
+    _require_editable(_get_return_or_404(return_id))
+
+    try:
+        details = return_store.set_direct_deposit(return_id, **request.model_dump())
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": details,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/returns/{return_id}/direct-deposit")
+async def delete_direct_deposit(return_id: str):
+    """Remove a return's refund deposit account"""
+    _require_editable(_get_return_or_404(return_id))
+
+    if not return_store.clear_direct_deposit(return_id):
+        raise NotFoundError(f"Direct deposit account not found on return: {return_id}")
+
+    return {
+        "success": True,
+        "data": {"return_id": return_id},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/ip-pins")
+async def get_ip_pins(return_id: str):
+    """Get the taxpayer's and spouse's IP PINs with all but the last 2 digits masked"""
+    _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": return_store.get_ip_pins(return_id),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.put("/api/returns/{return_id}/ip-pins/{person}")
+async def set_ip_pin(return_id: str, person: str, request: IpPinRequest):
+    """Set the taxpayer's or spouse's Identity Protection PIN (stored encrypted)"""
+    _require_editable(_get_return_or_404(return_id))
+
+    try:
+        details = return_store.set_ip_pin(return_id, person, **request.model_dump())
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": details,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/returns/{return_id}/ip-pins/{person}")
+async def delete_ip_pin(return_id: str, person: str):
+    """Remove the taxpayer's or spouse's IP PIN"""
+    _require_editable(_get_return_or_404(return_id))
+
+    if not return_store.clear_ip_pin(return_id, person):
+        raise NotFoundError(f"IP PIN not found for {person} on return: {return_id}")
+
+    return {
+        "success": True,
+        "data": {"return_id": return_id, "person": person},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/interview/start")
+async def start_interview(return_id: str):
+    """
+    Start the AI-guided interview for a return
+
+    Walks through filing status, dependents, income sources, and life events.
+    """
+    _require_ai_configured()
+    _require_editable(_get_return_or_404(return_id))
+
+    try:
+        agent = InterviewAgent(return_store=return_store)
+        result = agent.start_interview(return_id)
+
+        return {
+            "success": True,
+            "data": result,
+            "disclaimer": TaxCalculator.LEGAL_DISCLAIMER.strip(),
+            "timestamp": datetime.utcnow().isoformat(),
+        }
+
+    except Exception as e:
+        logger.error(f"Error starting interview: {str(e)}")
+        raise AIServiceError("An error occurred. Please try again.")
+
+
+@app.post("/api/returns/{return_id}/interview/answer")
+async def answer_interview(return_id: str, request: InterviewAnswerRequest):
+    """
+    Answer the current interview question
+
+    The AI records answers as income, deduction, and dependent records on the return.
+    """
+    _require_ai_configured()
+    _require_editable(_get_return_or_404(return_id))
+
+    try:
+        agent = InterviewAgent(return_store=return_store)
+        result = await agent.answer(return_id, request.answer)
+
+        return {
+            "success": True,
+            "data": result,
+            "disclaimer": TaxCalculator.LEGAL_DISCLAIMER.strip(),
+            "timestamp": datetime.utcnow().isoformat(),
+        }
+
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+    except Exception as e:
+        logger.error(f"Error in interview: {str(e)}")
+        raise AIServiceError(
+            "An error occurred during the interview. Please try again."
+        )
+
+
+@app.get("/api/returns/{return_id}/interview")
+async def get_interview_progress(return_id: str):
+    """Get interview progress for a return"""
+    tax_return = _get_return_or_404(return_id)
+    if not tax_return.get("interview"):
+        raise InvalidInputError("Interview has not been started for this return")
+
+    return {
+        "success": True,
+        "data": InterviewAgent.progress_summary(tax_return),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/validation")
+async def get_return_validation(return_id: str, locale: Optional[str] = Query(None, description="Message language")):
+    """Deterministic validation findings for a return, in the requested or configured language"""
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": validate_return(tax_return, locale=_locale(locale)),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/audit-risk")
+async def get_audit_risk(return_id: str):
+    """
+    Rules-based audit risk score for a return
+
+    Weighted findings for known audit triggers; prior years' returns (the
+    chain this one was cloned from) supply the Schedule C loss history.
+    """
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": assess_audit_risk(tax_return, return_store.prior_returns(tax_return)),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/review")
+async def review_return(return_id: str):
+    """
+    AI review of a stored return
+
+    Runs deterministic validation first, then asks the AI for missed deductions,
+    inconsistencies, and audit red flags using a redacted summary of the return.
+    """
+    _require_ai_configured()
+    tax_return = _get_return_or_404(return_id)
+
+    try:
+        validation = validate_return(tax_return)
+        agent = TaxPreparationAgent()
+        result = await agent.review_stored_return(
+            return_summary=summarize_return(tax_return),
+            validation_findings=validation,
+        )
+
+        return {
+            "success": True,
+            "data": result,
+            "disclaimer": TaxCalculator.LEGAL_DISCLAIMER.strip(),
+            "timestamp": datetime.utcnow().isoformat(),
+        }
+
+    except Exception as e:
+        logger.error(f"Error in return review: {str(e)}")
+        raise AIServiceError(
+            "An error occurred during the return review. Please try again."
+        )
+
+
+@app.post("/api/returns/{return_id}/capital-transactions/import")
+async def import_capital_transactions(return_id: str, request: BrokerCsvImportRequest):
+    """
+    Import a broker realized gain/loss CSV as capital transaction lots
+
+    Fidelity, Schwab, and Vanguard exports are recognized; other brokers can be
+    imported by mapping their columns.
+    """
+    tax_return = _get_return_or_404(return_id)
+    if not request.preview:
+        _require_editable(tax_return)
+
+    try:
+        parsed = parse_broker_csv(request.csv_text, layout=request.layout, mapping=request.mapping)
+        if not request.preview and parsed["transactions"]:
+            parsed["transactions"] = return_store.add_capital_transactions(
+                return_id, parsed["transactions"], source="broker_csv", broker=parsed["layout"]
+            )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": {**parsed, "imported": 0 if request.preview else len(parsed["transactions"])},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/forms/schedule-a")
+async def generate_schedule_a(return_id: str, request: ScheduleARequest):
+    """
+    Generate Schedule A from the return's deductions
+
+    Applies the medical AGI floor, the SALT cap, and the charitable AGI limits,
+    and returns the filled lines with the form as a base64 PDF.
+    """
+    tax_return = _get_return_or_404(return_id)
+
+    try:
+        schedule = calculate_schedule_a(
+            tax_return, agi=Decimal(str(request.agi)) if request.agi is not None else None
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+    pdf = render_schedule_a(schedule, taxpayer_name(tax_return), formatter=_formatter())
+
+    return {
+        "success": True,
+        "data": {
+            **schedule,
+            "mime_type": "application/pdf",
+            "pdf_base64": base64.b64encode(pdf).decode(),
+        },
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.put("/api/returns/{return_id}/income-sources/{source_id}/owner")
+async def set_income_source_owner(return_id: str, source_id: str, request: OwnerRequest):
+    """Attribute an income source to the taxpayer, the spouse, or both"""
+    _require_editable(_get_return_or_404(return_id))
+
+    try:
+        source = return_store.update_income_source(return_id, source_id, owner=request.owner)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+    except KeyError:
+        raise NotFoundError(f"Income source not found: {source_id}")
+
+    return {
+        "success": True,
+        "data": source,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.put("/api/returns/{return_id}/deductions/{deduction_id}/owner")
+async def set_deduction_owner(return_id: str, deduction_id: str, request: OwnerRequest):
+    """Attribute a deduction to the taxpayer, the spouse, or both"""
+    _require_editable(_get_return_or_404(return_id))
+
+    try:
+        deduction = return_store.update_deduction(return_id, deduction_id, owner=request.owner)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+    except KeyError:
+        raise NotFoundError(f"Deduction not found: {deduction_id}")
+
+    return {
+        "success": True,
+        "data": deduction,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/filing-comparison")
+async def compare_filing_status(return_id: str):
+    """
+    Compare a joint return with filing it as two married-separate returns
+
+    Income and deductions are split by their owner (joint ones evenly).
+    """
+    tax_return = _get_return_or_404(return_id)
+
+    try:
+        comparison = round_amounts(compare_filing_separately(tax_return), _rounding())
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": comparison,
+        "disclaimer": TaxCalculator.LEGAL_DISCLAIMER.strip(),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/businesses")
+async def add_business(return_id: str, request: BusinessRequest):
+    """Add a sole proprietorship to a return"""
+    _require_editable(_get_return_or_404(return_id))
+
+    fields = request.model_dump(exclude={"name", "owner"}, exclude_none=True)
+    try:
+        business = return_store.add_business(return_id, request.name, owner=request.owner, **fields)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": business,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/businesses/{business_id}/expenses")
+async def add_business_expense(return_id: str, business_id: str, request: BusinessExpenseRequest):
+    """Add an expense to a business"""
+    _require_editable(_get_return_or_404(return_id))
+
+    fields = {"date": request.date} if request.date else {}
+    try:
+        expense = return_store.add_business_expense(
+            return_id, business_id, request.category, request.amount, request.description, **fields
+        )
+    except KeyError:
+        raise NotFoundError(f"Business not found: {business_id}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": expense,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/businesses/{business_id}/vehicles")
+async def add_vehicle(return_id: str, business_id: str, request: VehicleRequest):
+    """Add a vehicle used in a business"""
+    _require_editable(_get_return_or_404(return_id))
+
+    fields = request.model_dump(exclude={"description"}, exclude_none=True)
+    if request.placed_in_service:
+        fields["placed_in_service"] = request.placed_in_service.isoformat()
+    try:
+        vehicle = return_store.add_vehicle(return_id, business_id, request.description, **fields)
+    except KeyError:
+        raise NotFoundError(f"Business not found: {business_id}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": vehicle,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/vehicles/{vehicle_id}/mileage")
+async def add_mileage(return_id: str, vehicle_id: str, request: MileageRequest):
+    """Log a business or personal trip; a medical or charitable trip is also deducted on Schedule A"""
+    _require_editable(_get_return_or_404(return_id))
+
+    fields = {"date": request.date} if request.date else {}
+    if request.parking_tolls:
+        fields["parking_tolls"] = request.parking_tolls
+    try:
+        trip = return_store.add_mileage(
+            return_id, vehicle_id, request.miles, business=request.business and request.category is None,
+            purpose=request.purpose, category=request.category, **fields
+        )
+    except KeyError:
+        raise NotFoundError(f"Vehicle not found: {vehicle_id}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": trip,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/vehicles/{vehicle_id}/expenses")
+async def add_vehicle_expense(return_id: str, vehicle_id: str, request: VehicleExpenseRequest):
+    """Record an actual vehicle expense (fuel, repairs, insurance, ...)"""
+    _require_editable(_get_return_or_404(return_id))
+
+    fields = {"date": request.date} if request.date else {}
+    try:
+        expense = return_store.add_vehicle_expense(
+            return_id, vehicle_id, request.kind, request.amount, request.description, **fields
+        )
+    except KeyError:
+        raise NotFoundError(f"Vehicle not found: {vehicle_id}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": expense,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/vehicles/comparison")
+async def compare_vehicle_expenses(return_id: str):
+    """
+    Compare standard mileage with actual expenses for every business vehicle
+
+    Each result says which methods the switching rules still allow and which
+    gives the larger deduction.
+    """
+    tax_return = _get_return_or_404(return_id)
+    vehicles = [v for b in tax_return.get("businesses", []) for v in b.get("vehicles", [])]
+    try:
+        comparisons = [compare_vehicle_methods(v, tax_return["tax_year"]) for v in vehicles]
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": round_amounts(comparisons, _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.put("/api/returns/{return_id}/vehicles/{vehicle_id}/method")
+async def set_vehicle_method(return_id: str, vehicle_id: str, request: VehicleMethodRequest):
+    """Choose a vehicle's method for this year; Schedule C uses it for lines 9 and 13"""
+    _require_editable(_get_return_or_404(return_id))
+
+    try:
+        vehicle = return_store.set_vehicle_method(return_id, vehicle_id, request.method)
+    except KeyError:
+        raise NotFoundError(f"Vehicle not found: {vehicle_id}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": vehicle,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/mileage")
+async def get_personal_mileage(return_id: str):
+    """
+    Medical and charitable trips with the Schedule A amounts they add
+
+    Trips logged on business vehicles with a category are included in the
+    deductions alongside the return's personal-vehicle log.
+    """
+    tax_return = _get_return_or_404(return_id)
+    try:
+        deductions = mileage_deductions(tax_return)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": {
+            "trips": tax_return.get("mileage_log", []),
+            "deductions": round_amounts(deductions, _rounding()),
+        },
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/mileage")
+async def add_personal_mileage(return_id: str, request: PersonalMileageRequest):
+    """Log a medical or charitable trip in a personal vehicle"""
+    _require_editable(_get_return_or_404(return_id))
+
+    fields = {"date": request.date} if request.date else {}
+    if request.parking_tolls:
+        fields["parking_tolls"] = request.parking_tolls
+    try:
+        trip = return_store.add_personal_mileage(
+            return_id, request.category, request.miles, purpose=request.purpose, **fields
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": trip,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/returns/{return_id}/mileage/{trip_id}")
+async def delete_personal_mileage(return_id: str, trip_id: str):
+    """Remove a trip from the personal mileage log"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        return_store.delete_personal_mileage(return_id, trip_id)
+    except KeyError:
+        raise NotFoundError(f"Trip not found: {trip_id}")
+
+    return {
+        "success": True,
+        "data": {"trip_id": trip_id, "deleted": True},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/donations/valuation-guide")
+async def get_donation_valuation_guide(category: Optional[str] = None):
+    """Fair market value ranges for donated clothing, furniture, and electronics"""
+    try:
+        guide = valuation_guide(category)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": guide,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/donations")
+async def add_donation_batch(return_id: str, request: DonationBatchRequest):
+    """
+    Value a batch of donated goods and add it as a noncash charitable deduction
+
+    The deduction carries the valued items as its itemized list; with
+    preview set, only the valuation and warnings are returned.
+    """
+    tax_return = _get_return_or_404(return_id)
+    try:
+        batch = build_donation_batch([item.model_dump(exclude_none=True) for item in request.items])
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    deduction = None
+    if not request.preview:
+        _require_editable(tax_return)
+        fields = {"date": request.date} if request.date else {}
+        deduction = return_store.add_deduction(
+            return_id, "charitable", batch["total"], f"Donated goods to {request.donee}",
+            non_cash=True, donee=request.donee, items=batch["items"], **fields,
+        )
+
+    return {
+        "success": True,
+        "data": {"batch": batch, "deduction": deduction},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/forms/schedule-c")
+async def generate_schedule_c(return_id: str):
+    """
+    Generate Schedule C for each business and Schedule SE for each owner
+
+    Expenses are mapped to Schedule C lines by category; the forms come back
+    as base64 PDFs.
+    """
+    tax_return = _get_return_or_404(return_id)
+    if not tax_return.get("businesses"):
+        raise InvalidInputError("Return has no businesses")
+
+    try:
+        schedules = calculate_business_schedules(tax_return)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    formatter = _formatter()
+    businesses = []
+    for record, schedule in zip(tax_return["businesses"], schedules["businesses"]):
+        pdf = render_schedule_c(schedule, record, taxpayer_name(tax_return, schedule["owner"]), formatter=formatter)
+        businesses.append({**schedule, "pdf_base64": base64.b64encode(pdf).decode()})
+    self_employment = []
+    for schedule in schedules["self_employment"]:
+        pdf = render_schedule_se(schedule, taxpayer_name(tax_return, schedule["owner"]), formatter=formatter)
+        self_employment.append({**schedule, "pdf_base64": base64.b64encode(pdf).decode()})
+
+    return {
+        "success": True,
+        "data": {"mime_type": "application/pdf", "businesses": businesses, "self_employment": self_employment},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/withholding-checkup")
+async def run_withholding_checkup(return_id: str, request: WithholdingCheckupRequest):
+    """
+    Project the year's refund or balance from a pay stub
+
+    The pay stub's wages replace the owner's wages on the return (nothing is
+    saved); returns the recommended withholding change per remaining paycheck.
+    """
+    tax_return = _get_return_or_404(return_id)
+    try:
+        result = withholding_checkup(
+            tax_return,
+            ytd_wages=Decimal(str(request.ytd_wages)),
+            ytd_withholding=Decimal(str(request.ytd_withholding)),
+            periods_remaining=request.periods_remaining,
+            pay_frequency=request.pay_frequency,
+            owner=request.owner,
+            target_refund=Decimal(str(request.target_refund)),
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": round_amounts(result, _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/forms/1040-es", **audited("export"))
+async def generate_estimated_tax_vouchers(return_id: str, request: EstimatedTaxVoucherRequest):
+    """
+    Generate 1040-ES payment vouchers for the quarters still to be paid
+
+    Amounts come from the quarterly estimator; the vouchers are pre-filled
+    with the return's taxpayer info.
+    """
+    tax_return = _get_return_or_404(return_id)
+    summary = summarize_return(tax_return)
+    income = request.estimated_annual_income or summary["total_income"]
+    withholding = request.withholding_to_date
+    if withholding is None:
+        withholding = summary["total_withholding"]
+    self_employment_income = request.self_employment_income
+    if self_employment_income is None:
+        self_employment_income = min(income, summary["income_by_type"].get("self_employment", 0))
+
+    try:
+        schedule = TaxCalculator(tax_year=tax_return["tax_year"]).estimate_quarterly_payments(
+            estimated_annual_income=Decimal(str(income)),
+            filing_status=tax_return["filing_status"],
+            withholding_to_date=Decimal(str(withholding)),
+            self_employment_income=Decimal(str(self_employment_income)),
+        )
+        payments = remaining_payments(schedule, request.as_of)
+        pdf = render_vouchers(
+            payments, tax_return["tax_year"], tax_return.get("taxpayer") or {}, formatter=_formatter()
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": {
+            **schedule,
+            "vouchers": payments,
+            "mime_type": "application/pdf",
+            "pdf_base64": base64.b64encode(pdf).decode(),
+        },
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/efile", **audited("reveal"))
+async def export_efile(return_id: str):
+    """
+    Export a reviewed return as IRS Modernized e-File XML
+
+    The XML is checked against the Form 1040 schema subset; a return that
+    fails validation isn't exported.
+    """
+    tax_return = _get_return_or_404(return_id)
+    if tax_return.get("status", "draft") in ("draft", "in_progress"):
+        raise InvalidInputError("Move the return to review before exporting it for e-file")
+
+    direct_deposit = return_store.get_direct_deposit(return_id, reveal=True)
+    ip_pins = return_store.get_ip_pins(return_id, reveal=True)
+    try:
+        xml = build_return_xml(
+            tax_return, calculate_return(tax_return), direct_deposit=direct_deposit, ip_pins=ip_pins
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+    errors = validate_return_xml(xml)
+    if errors:
+        raise InvalidInputError("Return failed e-file validation: " + "; ".join(errors), status_code=422)
+
+    return {
+        "success": True,
+        "data": {
+            "filename": f"{return_id}_{tax_return['tax_year']}_mef.xml",
+            "mime_type": "application/xml",
+            "xml": xml.decode("utf-8"),
+        },
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/export", **audited("export"))
+async def export_return(return_id: str):
+    """Export a return with its generated forms as a base64 zip package"""
+    tax_return = _get_return_or_404(return_id)
+
+    try:
+        package = build_return_package(tax_return, formatter=_formatter())
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": {
+            "filename": f"{return_id}_{tax_return['tax_year']}.zip",
+            "mime_type": "application/zip",
+            "package_base64": base64.b64encode(package).decode(),
+        },
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/export/csv", **audited("export"))
+async def export_return_csv(return_id: str):
+    """Export a return's income sources and deductions as CSV, in the configured number and date formats"""
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": {
+            "filename": f"{return_id}_{tax_return['tax_year']}_ledger.csv",
+            "mime_type": "text/csv",
+            "csv": build_return_csv(tax_return, formatter=_formatter()),
+        },
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/returns/{return_id}/export/anonymized", **audited("export"))
+async def export_return_anonymized(return_id: str):
+    """
+    Export a return with names, SSNs, EINs, and addresses replaced by placeholders
+
+    Amounts and structure are kept, so the situation can be shared on a
+    forum or with an advisor; contact details, account numbers, IP PINs,
+    and notes are removed.
+    """
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": {
+            "filename": f"{return_id}_{tax_return['tax_year']}_anonymized.json",
+            "mime_type": "application/json",
+            **anonymize_return(tax_return),
+        },
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+PROFIT_LOSS_FORMATS = ["json", "csv", "pdf"]
+
+
+@app.get("/api/returns/{return_id}/businesses/{business_id}/profit-loss", **audited("export"))
+async def get_profit_loss(
+    return_id: str,
+    business_id: str,
+    through_quarter: int = Query(4, ge=1, le=4, description="Last quarter to include"),
+    format: str = Query("json", description=f"Output: {', '.join(PROFIT_LOSS_FORMATS)}"),
+):
+    """
+    Quarterly and year-to-date profit and loss by category for a business
+
+    As JSON, or as a CSV or base64 PDF in the configured number and date formats.
+    """
+    tax_return = _get_return_or_404(return_id)
+    if format not in PROFIT_LOSS_FORMATS:
+        raise InvalidInputError(f"Invalid format: {format}. Must be one of: {', '.join(PROFIT_LOSS_FORMATS)}")
+    business = next((b for b in tax_return.get("businesses", []) if b["id"] == business_id), None)
+    if business is None:
+        raise NotFoundError(f"Business not found: {business_id}")
+    try:
+        report = business_profit_loss(
+            business, tax_return["income_sources"], tax_return["tax_year"], through_quarter=through_quarter
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    stem = f"{return_id}_{tax_return['tax_year']}_{business_id}_profit_loss"
+    if format == "csv":
+        data = {
+            "filename": f"{stem}.csv",
+            "mime_type": "text/csv",
+            "csv": build_profit_loss_csv(report, formatter=_formatter()),
+        }
+    elif format == "pdf":
+        data = {
+            "filename": f"{stem}.pdf",
+            "mime_type": "application/pdf",
+            "pdf_base64": base64.b64encode(render_profit_loss(report, formatter=_formatter())).decode(),
+        }
+    else:
+        data = round_amounts(report, _rounding())
+
+    return {
+        "success": True,
+        "data": data,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+def _build_review_packet(tax_return: Dict[str, Any]) -> bytes:
+    """Review packet PDF, compared against the return it was cloned from"""
+    prior_return = None
+    if tax_return.get("cloned_from"):
+        try:
+            prior_return = return_store.get_return(tax_return["cloned_from"])
+        except KeyError:
+            pass
+    direct_deposit = return_store.get_direct_deposit(tax_return["return_id"])
+    return build_review_packet(
+        tax_return, document_store, prior_return, direct_deposit=direct_deposit,
+        ip_pins=return_store.get_ip_pins(tax_return["return_id"]), formatter=_formatter(),
+    )
+
+
+def _review_packet_filename(tax_return: Dict[str, Any]) -> str:
+    return f"{tax_return['return_id']}_{tax_return['tax_year']}_review.pdf"
+
+
+@app.get("/api/returns/{return_id}/review-packet", **audited("export"))
+async def get_review_packet(return_id: str):
+    """
+    Print a return for human review as one base64 PDF
+
+    Cover summary, comparison with the return it was cloned from, deduction
+    detail with receipt thumbnails, and the generated forms.
+    """
+    tax_return = _get_return_or_404(return_id)
+    try:
+        pdf = _build_review_packet(tax_return)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": {
+            "filename": _review_packet_filename(tax_return),
+            "mime_type": "application/pdf",
+            "pdf_base64": base64.b64encode(pdf).decode(),
+        },
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/checklist/generate")
+async def generate_checklist(return_id: str):
+    """
+    Generate a personalized checklist of forms and documents for a return
+
+    Replaces any existing checklist on the return.
+    """
+    _require_ai_configured()
+    tax_return = _get_return_or_404(return_id)
+    _require_editable(tax_return)
+
+    try:
+        agent = TaxPreparationAgent()
+        items = await agent.generate_checklist(
+            return_summary=summarize_return(tax_return),
+            document_types=[doc["document_type"] for doc in document_store.list_documents(return_id=return_id)],
+        )
+        checklist = return_store.save_checklist(return_id, items)
+
+        return {
+            "success": True,
+            "data": checklist,
+            "disclaimer": TaxCalculator.LEGAL_DISCLAIMER.strip(),
+            "timestamp": datetime.utcnow().isoformat(),
+        }
+
+    except Exception as e:
+        logger.error(f"Error generating checklist: {str(e)}")
+        raise AIServiceError(
+            "An error occurred while generating the checklist. Please try again."
+        )
+
+
+@app.get("/api/returns/{return_id}/checklist")
+async def get_checklist(return_id: str):
+    """Get the stored checklist for a return"""
+    tax_return = _get_return_or_404(return_id)
+    return {
+        "success": True,
+        "data": {
+            **tax_return.get("checklist", {"generated_at": None, "items": []}),
+            "progress": ReturnStore.checklist_progress(tax_return),
+        },
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/checklist/seed")
+async def seed_checklist(return_id: str):
+    """
+    Add the standard preparation checklist to a return
+
+    Template items plus a document for each income source and deduction;
+    items already on the checklist aren't duplicated.
+    """
+    _require_editable(_get_return_or_404(return_id))
+    checklist = return_store.seed_checklist(return_id)
+    return {
+        "success": True,
+        "data": checklist,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/checklist/document-requests")
+async def generate_document_requests(return_id: str, prior_return_id: Optional[str] = None):
+    """
+    Add a request for every form expected this year to the checklist
+
+    Expected forms are last year's documents plus one per income source on
+    the return; each request is checked off once a matching document is
+    stored. Run it again after uploads to update what's outstanding.
+    prior_return_id defaults to the return this one was cloned from.
+    """
+    tax_return = _get_return_or_404(return_id)
+    _require_editable(tax_return)
+    if prior_return_id:
+        _get_return_or_404(prior_return_id)
+
+    items = document_requests(tax_return, *return_documents(document_store, tax_return, prior_return_id))
+    checklist = return_store.save_document_requests(return_id, items)
+    return {
+        "success": True,
+        "data": {
+            "requests": items,
+            "received": sum(1 for item in items if item["received"]),
+            "outstanding": [item["title"] for item in items if not item["received"]],
+            "checklist": checklist,
+        },
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/returns/{return_id}/checklist/items")
+async def add_checklist_item(return_id: str, request: ChecklistItemRequest):
+    """Add an item to a return's checklist by hand"""
+    _require_editable(_get_return_or_404(return_id))
+
+    try:
+        item = return_store.add_checklist_item(return_id, **request.model_dump())
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": item,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.patch("/api/returns/{return_id}/checklist/{item_id}")
+async def update_checklist_item(return_id: str, item_id: str, request: ChecklistItemUpdateRequest):
+    """Check off (or uncheck) a checklist item or update its notes"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        item = return_store.set_checklist_item(return_id, item_id, request.checked, request.notes)
+    except KeyError:
+        raise NotFoundError(f"Checklist item not found: {item_id}")
+
+    return {
+        "success": True,
+        "data": item,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/returns/{return_id}/checklist/{item_id}")
+async def delete_checklist_item(return_id: str, item_id: str):
+    """Remove a checklist item"""
+    _require_editable(_get_return_or_404(return_id))
+    try:
+        return_store.delete_checklist_item(return_id, item_id)
+    except KeyError:
+        raise NotFoundError(f"Checklist item not found: {item_id}")
+
+    return {
+        "success": True,
+        "data": {"item_id": item_id},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+# ============================================================================
+# ONBOARDING ENDPOINTS
+# ============================================================================
+
+onboarding = Onboarding(
+    app_lock=app_lock, settings_store=settings_store, secret_store=secret_store, return_store=return_store
+)
+
+
+def _onboarding_step(action, *args: Any, **kwargs: Any) -> Dict[str, Any]:
+    """Run a wizard step, turning a refused step into a 400"""
+    try:
+        progress = action(*args, **kwargs)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+    return {
+        "success": True,
+        "data": progress,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/onboarding")
+async def get_onboarding():
+    """
+    First-time setup progress
+
+    The frontend resumes the wizard at current_step; complete is true once
+    every step is done or skipped.
+    """
+    return {
+        "success": True,
+        "data": onboarding.status(),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/onboarding/passphrase")
+async def onboarding_passphrase(request: OnboardingPassphraseRequest):
+    """Set the app lock passphrase (an existing one is kept)"""
+    return _onboarding_step(onboarding.set_passphrase, request.passphrase)
+
+
+@app.put("/api/onboarding/profile")
+async def onboarding_profile(request: OnboardingProfileRequest):
+    """Save who the returns are for"""
+    return _onboarding_step(onboarding.set_profile, **request.model_dump(exclude_none=True))
+
+
+@app.put("/api/onboarding/defaults")
+async def onboarding_defaults(request: OnboardingDefaultsRequest):
+    """Set the default tax year and state of residence"""
+    return _onboarding_step(onboarding.set_defaults, request.tax_year, request.state)
+
+
+@app.put("/api/onboarding/api-key", **audited("key_change"))
+async def onboarding_api_key(request: ApiKeyRequest):
+    """Save the Anthropic API key"""
+    return _onboarding_step(onboarding.set_api_key, request.api_key)
+
+
+@app.post("/api/onboarding/import")
+async def onboarding_import(request: OnboardingImportRequest):
+    """Import last year's return from a TXF export and start this year's from it"""
+    return _onboarding_step(onboarding.import_prior_year, request.txf_text)
+
+
+@app.post("/api/onboarding/{step}/skip")
+async def onboarding_skip(step: str):
+    """Skip an optional step (the API key or the import)"""
+    return _onboarding_step(onboarding.skip, step)
+
+
+@app.delete("/api/onboarding")
+async def reset_onboarding():
+    """Start the wizard over; what earlier steps saved is kept"""
+    return _onboarding_step(onboarding.reset)
+
+
+# ============================================================================
+# INVOICE ENDPOINTS
+# ============================================================================
+
+invoice_store = InvoiceStore()
+
+
+def _invoice_fields(request: BaseModel, exclude_unset: bool = False) -> Dict[str, Any]:
+    """Request fields for InvoiceStore, with dates as ISO strings"""
+    fields = request.model_dump(exclude_unset=exclude_unset)
+    for name in ("issue_date", "paid_date"):
+        if fields.get(name) is not None:
+            fields[name] = fields[name].isoformat()
+    return fields
+
+
+@app.post("/api/invoices")
+async def create_invoice(request: InvoiceRequest):
+    """Record an invoice sent to a client"""
+    try:
+        invoice = invoice_store.create_invoice(**_invoice_fields(request))
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": invoice,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/invoices")
+async def list_invoices(
+    year: Optional[int] = Query(None, description="Only invoices issued or paid in this year"),
+    client: Optional[str] = Query(None, description="Only this client"),
+    status: Optional[str] = Query(None, description="open or paid"),
+):
+    """List invoices, oldest first"""
+    try:
+        invoices = invoice_store.list_invoices(year=year, client=client, status=status)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": invoices,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/invoices/reconciliation")
+async def get_invoice_reconciliation(tax_year: int = Query(..., ge=2000, le=2100, description="Year to reconcile")):
+    """
+    Compare income paid on invoices with the 1099-NEC forms received, by client
+
+    Flags missing forms, forms that disagree with what was paid, and forms
+    from payers with no invoices.
+    """
+    report = reconcile_invoices(
+        invoice_store.list_invoices(), document_store.list_documents(include_archived=True), tax_year
+    )
+    return {
+        "success": True,
+        "data": round_amounts(report, _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/invoices/{invoice_id}")
+async def get_invoice(invoice_id: str):
+    """Get one invoice"""
+    invoice = invoice_store.get_invoice(invoice_id)
+    if invoice is None:
+        raise NotFoundError(f"Invoice not found: {invoice_id}")
+    return {
+        "success": True,
+        "data": invoice,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.put("/api/invoices/{invoice_id}")
+async def update_invoice(invoice_id: str, request: InvoiceUpdateRequest):
+    """Change an invoice, e.g. record the date it was paid"""
+    try:
+        invoice = invoice_store.update_invoice(invoice_id, **_invoice_fields(request, exclude_unset=True))
+    except KeyError:
+        raise NotFoundError(f"Invoice not found: {invoice_id}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": invoice,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/invoices/{invoice_id}")
+async def delete_invoice(invoice_id: str):
+    """Delete an invoice"""
+    if not invoice_store.delete_invoice(invoice_id):
+        raise NotFoundError(f"Invoice not found: {invoice_id}")
+    return {
+        "success": True,
+        "data": {"invoice_id": invoice_id, "deleted": True},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+# ============================================================================
+# PRACTITIONER CLIENT ENDPOINTS
+# ============================================================================
+
+client_store = ClientStore()
+
+
+def _require_practitioner_mode() -> None:
+    """Raise 409 unless practitioner mode is on in settings"""
+    if not settings_store.get_settings().practitioner_mode:
+        raise ConflictError("Practitioner mode is off; turn on practitioner_mode in settings to manage clients")
+
+
+def _get_client_or_404(client_id: str) -> Dict[str, Any]:
+    _require_practitioner_mode()
+    client = client_store.get_client(client_id)
+    if client is None:
+        raise NotFoundError(f"Client not found: {client_id}")
+    return client
+
+
+def _client_documents(client_id: str, returns: List[Dict[str, Any]]) -> List[Dict[str, Any]]:
+    """Documents uploaded for a client or attached to one of their returns"""
+    return_ids = {r["return_id"] for r in returns}
+    return [
+        d for d in document_store.list_documents()
+        if d.get("client_id") == client_id or d.get("return_id") in return_ids
+    ]
+
+
+@app.post("/api/clients")
+async def create_client(request: ClientRequest):
+    """Add a client"""
+    _require_practitioner_mode()
+    try:
+        client = client_store.create_client(**request.model_dump())
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": client,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/clients")
+async def list_clients(engagement_status: Optional[str] = Query(None, description="Only clients at this status")):
+    """List clients by name"""
+    _require_practitioner_mode()
+    try:
+        clients = client_store.list_clients(engagement_status=engagement_status)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": clients,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/clients/dashboard")
+async def get_practice_dashboard(
+    within_days: int = Query(DEFAULT_REMINDER_DAYS, ge=0, le=366, description="How many days ahead to look"),
+    as_of: Optional[date] = Query(None, description="Date to count from (defaults to today)"),
+):
+    """
+    Every client's engagement status, return statuses, and deadlines
+
+    Also counts clients by engagement status and returns by status, and lists
+    the deadlines coming up across all clients.
+    """
+    _require_practitioner_mode()
+    tax_returns = [return_store.get_return(summary["return_id"]) for summary in return_store.list_returns()]
+    dashboard = build_dashboard(
+        client_store.list_clients(), [r for r in tax_returns if r],
+        document_store.list_documents(), as_of=as_of, within_days=within_days,
+    )
+    return {
+        "success": True,
+        "data": round_amounts(dashboard, _rounding()),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/clients/{client_id}")
+async def get_client(client_id: str):
+    """Get a client with their returns and documents"""
+    client = _get_client_or_404(client_id)
+    returns = return_store.list_returns(client_id=client_id)
+    return {
+        "success": True,
+        "data": {**client, "returns": returns, "documents": _client_documents(client_id, returns)},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.put("/api/clients/{client_id}")
+async def update_client(client_id: str, request: ClientUpdateRequest):
+    """Change a client's contact info, engagement status, or notes"""
+    _get_client_or_404(client_id)
+    try:
+        client = client_store.update_client(client_id, **request.model_dump(exclude_unset=True))
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": client,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.delete("/api/clients/{client_id}")
+async def delete_client(client_id: str):
+    """Delete a client who has no returns (unlink or delete those first)"""
+    _get_client_or_404(client_id)
+    returns = return_store.list_returns(client_id=client_id)
+    if returns:
+        raise ConflictError(f"Client has {len(returns)} returns; unlink or delete them first")
+    client_store.delete_client(client_id)
+    return {
+        "success": True,
+        "data": {"client_id": client_id, "deleted": True},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.put("/api/returns/{return_id}/client")
+async def set_return_client(return_id: str, request: ReturnClientRequest):
+    """Link a return to a client, or unlink it"""
+    _get_return_or_404(return_id)
+    if request.client_id:
+        _get_client_or_404(request.client_id)
+    tax_return = return_store.assign_client(return_id, request.client_id)
+    return {
+        "success": True,
+        "data": {"return_id": return_id, "client_id": tax_return.get("client_id")},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+# ============================================================================
+# DOCUMENT STORAGE ENDPOINTS
+# ============================================================================
+
+document_store = DocumentStore()
+inbox_watcher: Optional[InboxWatcher] = None
+
+
+async def _configure_inbox_watcher() -> None:
+    """(Re)start the inbox watcher from the current settings"""
+    global inbox_watcher
+    if inbox_watcher is not None:
+        await inbox_watcher.stop()
+        inbox_watcher = None
+
+    settings = settings_store.get_settings()
+    if settings.inbox_folder:
+        inbox_watcher = InboxWatcher(
+            document_store, settings.inbox_folder, settings.inbox_poll_seconds,
+            queue_extraction=_queue_document_extraction,
+        )
+        inbox_watcher.start()
+        logger.info(f"Watching tax inbox: {settings.inbox_folder}")
+
+
+def _get_document_or_404(document_id: str) -> Dict[str, Any]:
+    """Load a document record or raise 404"""
+    document = document_store.get_document(document_id)
+    if document is None:
+        raise NotFoundError(f"Document not found: {document_id}")
+    return document
+
+
+def _read_document_or_404(document_id: str) -> bytes:
+    """Read a document's stored content or raise 404"""
+    _get_document_or_404(document_id)
+    try:
+        return document_store.read_document(document_id)
+    except FileNotFoundError:
+        raise NotFoundError(
+            "Document file is missing from storage. Run /api/documents/relink to repair it."
+        )
+
+
+@app.post("/api/documents/upload")
+async def upload_document(
+    file: UploadFile = File(...),
+    document_type: str = Form("unknown"),
+    return_id: Optional[str] = Form(None),
+    tax_year: Optional[int] = Form(None),
+    allow_duplicate: bool = Form(False),
+    client_id: Optional[str] = Form(None),
+):
+    """
+    Upload a document into managed storage
+
+    The file is copied into the app's document store, so the record never depends on
+    the original location. Without a document_type, the type is inferred. Content that is already stored is rejected with 409 unless
+    allow_duplicate is set, in which case the copy is flagged with duplicate_of.
+    """
+    if return_id:
+        _get_return_or_404(return_id)
+    if client_id:
+        _get_client_or_404(client_id)
+
+    content = await file.read()
+    if not content:
+        raise InvalidInputError("Uploaded file is empty")
+    if len(content) > DocumentStore.MAX_DOCUMENT_BYTES:
+        raise InvalidInputError("Uploaded file is too large (max 25 MB)", status_code=413)
+
+    if document_type == "unknown":
+        document_type, _ = infer_document_type(file.filename or "", content)
+
+    try:
+        document = document_store.upload_document(
+            filename=file.filename or "document",
+            content=content,
+            document_type=document_type,
+            return_id=return_id,
+            allow_duplicate=allow_duplicate,
+            **({"tax_year": tax_year} if tax_year else {}),
+            **({"client_id": client_id} if client_id else {}),
+        )
+    except DuplicateDocumentError as e:
+        raise ConflictError(str(e))
+
+    if generate_thumbnail(document_store, document, content) is not None:
+        document = document_store.get_document(document["document_id"])
+
+    return {
+        "success": True,
+        "data": document,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/documents/bulk-import")
+async def bulk_import_documents(request: BulkImportRequest):
+    """
+    Import every file in a folder into document storage
+
+    Document types are inferred from file names and content; returns a per-file report.
+    """
+    if request.default_return_id:
+        _get_return_or_404(request.default_return_id)
+
+    if not os.path.isdir(request.folder):
+        raise InvalidInputError(f"Folder not found: {request.folder}")
+
+    if request.background:
+        job = job_queue.enqueue(
+            "bulk_import",
+            {"folder": request.folder, "default_return_id": request.default_return_id, "recursive": request.recursive},
+            description=f"Import {request.folder}",
+        )
+        return {
+            "success": True,
+            "data": job,
+            "timestamp": datetime.utcnow().isoformat(),
+        }
+
+    job = progress_bus.start("bulk_import", f"Import {request.folder}")
+    job = await _run_job(
+        job["job_id"],
+        bulk_import,
+        document_store,
+        request.folder,
+        default_return_id=request.default_return_id,
+        recursive=request.recursive,
+        progress=progress_bus.reporter(job["job_id"]),
+    )
+    if job["status"] == "failed":
+        raise InvalidInputError(job["error"])
+    if job["status"] == "cancelled":
+        raise ConflictError("Import was cancelled")
+    return {
+        "success": True,
+        "data": job["result"],
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/documents")
+async def list_documents(
+    return_id: Optional[str] = Query(None, description="Only documents for this return"),
+    query: Optional[str] = Query(None, max_length=200, description="Search names, OCR text, and extracted fields"),
+    include_archived: bool = Query(False, description="Include archived documents"),
+):
+    """List uploaded documents, optionally searching their content (e.g. an EIN or vendor)"""
+    return {
+        "success": True,
+        "data": document_store.list_documents(return_id=return_id, query=query, include_archived=include_archived),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/documents/inbox")
+async def get_inbox_status(since: Optional[str] = Query(None, description="Only events after this ISO timestamp")):
+    """
+    Tax inbox status and recent import events
+
+    The UI polls this to be notified of documents imported from the inbox folder.
+    """
+    return {
+        "success": True,
+        "data": {
+            "enabled": inbox_watcher is not None,
+            "folder": str(inbox_watcher.folder) if inbox_watcher else None,
+            "running": bool(inbox_watcher and inbox_watcher.running),
+            "events": inbox_watcher.recent_events(since) if inbox_watcher else [],
+        },
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/documents/inbox/scan")
+async def scan_inbox():
+    """Scan the tax inbox now instead of waiting for the next poll"""
+    if inbox_watcher is None:
+        raise InvalidInputError("No inbox folder configured. Set inbox_folder in settings.")
+    events = await asyncio.to_thread(inbox_watcher.scan, require_stable=False)
+    return {
+        "success": True,
+        "data": events,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/documents/merge-pages")
+async def merge_document_pages(request: MergePagesRequest):
+    """
+    Combine page images (e.g. phone photos) into one multi-page PDF document
+
+    The source images are archived and their OCR text is kept page by page.
+    """
+    for document_id in request.document_ids:
+        _get_document_or_404(document_id)
+
+    try:
+        merged = merge_images(document_store, request.document_ids, request.filename)
+    except PreviewUnavailableError as e:
+        raise InvalidInputError(str(e), status_code=422)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    content = document_store.read_document(merged["document_id"])
+    if generate_thumbnail(document_store, merged, content) is not None:
+        merged = document_store.get_document(merged["document_id"])
+
+    return {
+        "success": True,
+        "data": merged,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/documents/retention/purge")
+async def purge_expired_documents(request: PurgeDocumentsRequest):
+    """
+    Export and remove documents past the retention window
+
+    Runs as a dry run unless dry_run is false. Purged documents stay listed in
+    the purged index.
+    """
+    retention_years = request.retention_years or settings_store.get_settings().document_retention_years
+    try:
+        result = document_store.purge_expired(
+            retention_years=retention_years, export_dir=request.export_dir, dry_run=request.dry_run
+        )
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": {**result, "retention_years": retention_years},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/documents/retention/purged")
+async def list_purged_documents():
+    """Index of documents removed by the retention purge"""
+    return {
+        "success": True,
+        "data": document_store.list_purged(),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/documents/duplicates")
+async def list_duplicate_documents():
+    """List groups of documents with identical content"""
+    return {
+        "success": True,
+        "data": document_store.find_duplicates(),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/documents/relink")
+async def relink_documents():
+    """
+    Repair document records that point outside managed storage
+
+    Legacy records referencing an external path are copied into the store.
+    """
+    return {
+        "success": True,
+        "data": document_store.relink_documents(),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/documents/{document_id}")
+async def get_document(document_id: str):
+    """Get a document record"""
+    return {
+        "success": True,
+        "data": _get_document_or_404(document_id),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/documents/{document_id}/thumbnail")
+async def get_document_thumbnail(document_id: str):
+    """Get a document's first-page thumbnail as base64 PNG"""
+    document = _get_document_or_404(document_id)
+    png = document_store.get_thumbnail(document_id)
+    if png is None:
+        png = generate_thumbnail(document_store, document, _read_document_or_404(document_id))
+    if png is None:
+        raise InvalidInputError(f"Preview not available for {document['mime_type']} files", status_code=422)
+
+    return {
+        "success": True,
+        "data": {"mime_type": "image/png", "image_base64": base64.b64encode(png).decode()},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/documents/{document_id}/preview")
+async def get_document_preview(document_id: str, page: int = Query(1, ge=1, description="1-based page number")):
+    """
+    Render one page of a document as base64 PNG
+
+    Lets the UI show documents without direct access to the stored files.
+    """
+    document = _get_document_or_404(document_id)
+    content = _read_document_or_404(document_id)
+
+    try:
+        png = render_page(content, document["mime_type"], page=page)
+        pages = page_count(content, document["mime_type"])
+    except PreviewUnavailableError as e:
+        raise InvalidInputError(str(e), status_code=422)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": {
+            "page": page,
+            "page_count": pages,
+            "mime_type": "image/png",
+            "image_base64": base64.b64encode(png).decode(),
+        },
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/documents/{document_id}/split")
+async def split_document(document_id: str, request: SplitDocumentRequest):
+    """
+    Split a PDF (e.g. a scanned stack of forms) into separate documents
+
+    The original is archived; each part keeps its pages' OCR text.
+    """
+    _read_document_or_404(document_id)
 
-class TaxReturnRequest(BaseModel):
-    """Request model for tax calculation"""
-    entity_type: str = Field(..., description="Type of entity (1040, 1120, etc.)")
-    gross_income: float = Field(..., gt=0, description="Gross income (must be positive)")
-    filing_status: str = Field(
-        default="single",
-        description="Filing status: single, married_joint, married_separate, head_of_household"
-    )
-    itemized_deductions: Optional[float] = Field(
-        None, ge=0, description="Itemized deductions if applicable"
-    )
-    dependents: int = Field(default=0, ge=0, description="Number of dependents")
+    try:
+        parts = split_pdf(document_store, document_id, request.ranges)
+    except PreviewUnavailableError as e:
+        raise InvalidInputError(str(e), status_code=422)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
 
-    @field_validator("gross_income")
-    @classmethod
-    def validate_gross_income(cls, v):
-        if v < 0:
-            raise ValueError("Gross income cannot be negative")
-        if v > 1_000_000_000:  # Sanity check
-            raise ValueError("Gross income exceeds reasonable limit")
-        return v
+    for index, part in enumerate(parts):
+        if generate_thumbnail(document_store, part, document_store.read_document(part["document_id"])) is not None:
+            parts[index] = document_store.get_document(part["document_id"])
 
-    @field_validator("filing_status")
-    @classmethod
-    def validate_filing_status(cls, v):
-        valid_statuses = ["single", "married_joint", "married_separate", "head_of_household"]
-        if v.lower() not in valid_statuses:
-            raise ValueError(f"Filing status must be one of: {', '.join(valid_statuses)}")
-        return v.lower()
+    return {
+        "success": True,
+        "data": parts,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
 
 
-class DocumentAnalysisRequest(BaseModel):
-    """Request model for document analysis"""
-    document_type: str = Field(..., description="Type of document (W-2, 1099, receipt, etc.)")
-    document_data: Dict[str, Any] = Field(..., description="Document data as JSON")
-    image_base64: Optional[str] = Field(None, description="Base64 encoded image (optional)")
+@app.put("/api/documents/{document_id}/notes")
+async def set_document_notes(document_id: str, request: DocumentNotesRequest):
+    """Replace a document's notes"""
+    _get_document_or_404(document_id)
+    return {
+        "success": True,
+        "data": document_store.set_notes(document_id, request.notes),
+        "timestamp": datetime.utcnow().isoformat(),
+    }
 
 
-class AuditDefenseRequest(BaseModel):
-    """Request model for audit defense"""
-    notice_text: str = Field(..., min_length=10, description="IRS audit notice text")
-    client_documents: Dict[str, Any] = Field(default_factory=dict, description="Available client documents")
+@app.post("/api/documents/{document_id}/annotations")
+async def add_document_annotation(document_id: str, request: AnnotationRequest):
+    """Attach a note to a document (e.g. "this is the corrected W-2, ignore the other one")"""
+    _get_document_or_404(document_id)
+    try:
+        annotation = document_store.add_annotation(document_id, request.text, request.page)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
 
+    return {
+        "success": True,
+        "data": annotation,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
 
-class QuarterlyEstimateRequest(BaseModel):
-    """Request model for quarterly tax estimate"""
-    estimated_annual_income: float = Field(..., gt=0, description="Estimated annual income")
-    filing_status: str = Field(..., description="Filing status")
-    withholding_to_date: float = Field(default=0, ge=0, description="Tax already withheld")
 
+@app.patch("/api/documents/{document_id}/annotations/{annotation_id}")
+async def update_document_annotation(document_id: str, annotation_id: str, request: AnnotationUpdateRequest):
+    """Edit an annotation"""
+    _get_document_or_404(document_id)
+    try:
+        annotation = document_store.update_annotation(
+            document_id, annotation_id, **request.model_dump(exclude_unset=True)
+        )
+    except KeyError:
+        raise NotFoundError(f"Annotation not found: {annotation_id}")
+    except ValueError as e:
+        raise InvalidInputError(str(e))
 
-class VoiceChatRequest(BaseModel):
-    """Request model for voice agent text chat"""
-    message: str = Field(..., min_length=1, max_length=2000, description="User message text")
-    session_id: Optional[str] = Field(None, description="Session ID for conversation continuity")
-    context: Dict[str, Any] = Field(default_factory=dict, description="Conversation context")
+    return {
+        "success": True,
+        "data": annotation,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
 
 
-# ============================================================================
-# HEALTH CHECK & INFO
-# ============================================================================
+@app.delete("/api/documents/{document_id}/annotations/{annotation_id}")
+async def delete_document_annotation(document_id: str, annotation_id: str):
+    """Remove an annotation"""
+    _get_document_or_404(document_id)
+    try:
+        document_store.delete_annotation(document_id, annotation_id)
+    except KeyError:
+        raise NotFoundError(f"Annotation not found: {annotation_id}")
 
-@app.get("/")
-async def root():
-    """API health check"""
     return {
-        "status": "online",
-        "service": "AI Tax CPA Agent",
-        "version": "1.0.0",
-        "disclaimer": TaxCalculator.LEGAL_DISCLAIMER.strip(),
-        "endpoints": {
-            "tax_calculation": "/api/tax/calculate",
-            "document_analysis": "/api/documents/analyze",
-            "audit_defense": "/api/audit/analyze",
-            "voice_agent": "/api/voice/chat (not implemented)",
-        }
+        "success": True,
+        "data": {"annotation_id": annotation_id, "deleted": True},
+        "timestamp": datetime.utcnow().isoformat(),
     }
 
 
-@app.get("/api/disclaimer")
-async def get_disclaimer():
-    """Get legal disclaimer"""
+@app.post("/api/documents/{document_id}/archive")
+async def archive_document(document_id: str, request: ArchiveDocumentRequest):
+    """Archive a document (hidden from the default list) or restore it"""
+    _get_document_or_404(document_id)
     return {
-        "disclaimer": TaxCalculator.LEGAL_DISCLAIMER.strip()
+        "success": True,
+        "data": document_store.set_archived(document_id, request.archived),
+        "timestamp": datetime.utcnow().isoformat(),
     }
 
 
-# ============================================================================
-# TAX CALCULATION ENDPOINTS
-# ============================================================================
+@app.post("/api/documents/{document_id}/merge")
+async def merge_documents(document_id: str, request: MergeDocumentsRequest):
+    """Merge a duplicate document into this one, keeping its name as an alias"""
+    _get_document_or_404(document_id)
+    _get_document_or_404(request.duplicate_id)
 
-@app.post("/api/tax/calculate")
-async def calculate_tax(request: TaxReturnRequest):
+    try:
+        merged = document_store.merge_documents(document_id, request.duplicate_id)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    return {
+        "success": True,
+        "data": merged,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/documents/{document_id}/apply")
+async def apply_document_to_return(document_id: str, request: ApplyExtractionRequest):
     """
-    Calculate federal income tax using real IRS 2024 tax brackets
+    Apply a document's extracted data to a return's income
 
-    Returns tax liability with detailed breakdown and legal disclaimer.
+    Returns the before/after diff; with preview=true nothing is saved. The income
+    source and document are linked so each figure can be traced to its form.
     """
+    _get_document_or_404(document_id)
+    _get_return_or_404(request.return_id)
+
     try:
-        calculator = TaxCalculator(tax_year=2024)
+        result = apply_extraction_to_return(
+            document_store, return_store, document_id, request.return_id, preview=request.preview
+        )
+    except ReturnLockedError as e:
+        raise ConflictError(str(e))
+    except ValueError as e:
+        raise InvalidInputError(str(e))
 
-        if request.entity_type == "1040":
-            # Individual tax return
-            result = calculator.calculate_individual_tax(
-                gross_income=Decimal(str(request.gross_income)),
-                filing_status=request.filing_status,
-                itemized_deductions=Decimal(str(request.itemized_deductions)) if request.itemized_deductions else None,
-                dependents=request.dependents,
-            )
-        elif request.entity_type == "1120":
-            # Corporate tax return
-            result = calculator.calculate_corporate_tax(
-                net_income=Decimal(str(request.gross_income)),
-                entity_type="C-Corp",
-            )
-        else:
-            raise HTTPException(
-                status_code=400,
-                detail=f"Unsupported entity type: {request.entity_type}. Use 1040 or 1120."
-            )
+    return {
+        "success": True,
+        "data": result,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
 
-        return {
-            "success": True,
-            "data": result,
-            "timestamp": datetime.utcnow().isoformat(),
-        }
 
-    except HTTPException:
-        raise
-    except ValueError as e:
-        logger.error(f"Validation error in tax calculation: {str(e)}")
-        raise HTTPException(status_code=400, detail=str(e))
-    except Exception as e:
-        # Sanitize error message to avoid leaking sensitive info
-        logger.error(f"Error in tax calculation: {str(e)}")
-        raise HTTPException(
-            status_code=500,
-            detail="An error occurred during tax calculation. Please check your input and try again."
-        )
+@app.post("/api/documents/{document_id}/deduction")
+async def create_receipt_deduction(document_id: str, request: ReceiptDeductionRequest):
+    """
+    Create the deduction for an extracted receipt
 
+    The deduction's receipt_id links it to the document; repeating this updates it.
+    """
+    _get_document_or_404(document_id)
+    if request.return_id:
+        _get_return_or_404(request.return_id)
 
-@app.post("/api/tax/quarterly")
-async def estimate_quarterly(request: QuarterlyEstimateRequest):
-    """Estimate quarterly tax payments"""
     try:
-        calculator = TaxCalculator(tax_year=2024)
-
-        result = calculator.estimate_quarterly_payments(
-            estimated_annual_income=Decimal(str(request.estimated_annual_income)),
-            filing_status=request.filing_status,
-            withholding_to_date=Decimal(str(request.withholding_to_date)),
+        result = create_deduction_from_receipt(
+            document_store, return_store, document_id, request.return_id, request.category
         )
+    except ReturnLockedError as e:
+        raise ConflictError(str(e))
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+    except KeyError as e:
+        raise NotFoundError(str(e.args[0]))
 
-        return {
-            "success": True,
-            "data": result,
-            "timestamp": datetime.utcnow().isoformat(),
-        }
+    return {
+        "success": True,
+        "data": result,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
 
-    except ValueError as e:
-        raise HTTPException(status_code=400, detail=str(e))
-    except Exception as e:
-        logger.error(f"Error in quarterly estimation: {str(e)}")
-        raise HTTPException(status_code=500, detail="An error occurred. Please try again.")
+
+@app.delete("/api/documents/{document_id}")
+async def delete_document(document_id: str):
+    """Delete a document and its stored file"""
+    _get_document_or_404(document_id)
+    document_store.delete_document(document_id)
+    return {
+        "success": True,
+        "data": {"document_id": document_id, "deleted": True},
+        "timestamp": datetime.utcnow().isoformat(),
+    }
 
 
 # ============================================================================
@@ -290,12 +5550,7 @@ async def analyze_document(request: DocumentAnalysisRequest):
     Extracts structured data and provides tax implications.
     """
     try:
-        # Check for API key
-        if not os.getenv("ANTHROPIC_API_KEY"):
-            raise HTTPException(
-                status_code=503,
-                detail="AI service not configured. Please set ANTHROPIC_API_KEY environment variable."
-            )
+        _require_ai_configured()
 
         agent = DocumentAnalysisAgent()
 
@@ -317,12 +5572,47 @@ async def analyze_document(request: DocumentAnalysisRequest):
     except Exception as e:
         logger.error(f"Error in document analysis: {str(e)}")
         # Sanitize error - don't leak API keys or sensitive data
-        raise HTTPException(
-            status_code=500,
-            detail="An error occurred during document analysis. Please try again."
+        raise AIServiceError(
+            "An error occurred during document analysis. Please try again."
         )
 
 
+@app.post("/api/documents/parse")
+async def parse_document_text(request: DocumentParseRequest):
+    """
+    Extract form fields from document text without calling the AI
+
+    Each field carries a confidence score; low-confidence results are marked for review.
+    """
+    if request.document_id:
+        _get_document_or_404(request.document_id)
+
+    try:
+        result = parse_document(request.document_type, request.text)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+
+    extraction = result.to_dict()
+    if request.document_id:
+        document = document_store.save_extraction(request.document_id, extraction, text=request.text)
+        if (
+            result.form == "receipt"
+            and settings_store.get_settings().auto_deduct_receipts
+            and document.get("return_id")
+            and result.value("amount") is not None
+            and result.value("category")
+        ):
+            extraction["deduction"] = create_deduction_from_receipt(
+                document_store, return_store, request.document_id
+            )["deduction"]
+
+    return {
+        "success": True,
+        "data": extraction,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
 # ============================================================================
 # AUDIT DEFENSE ENDPOINTS
 # ============================================================================
@@ -335,11 +5625,7 @@ async def analyze_audit(request: AuditDefenseRequest):
     Provides professional analysis and response recommendations.
     """
     try:
-        if not os.getenv("ANTHROPIC_API_KEY"):
-            raise HTTPException(
-                status_code=503,
-                detail="AI service not configured. Please set ANTHROPIC_API_KEY environment variable."
-            )
+        _require_ai_configured()
 
         agent = AuditDefenseAgent()
 
@@ -359,12 +5645,171 @@ async def analyze_audit(request: AuditDefenseRequest):
         raise
     except Exception as e:
         logger.error(f"Error in audit analysis: {str(e)}")
-        raise HTTPException(
-            status_code=500,
-            detail="An error occurred during audit analysis. Please try again."
+        raise AIServiceError(
+            "An error occurred during audit analysis. Please try again."
         )
 
 
+# ============================================================================
+# JOB ENDPOINTS
+# ============================================================================
+
+def _bulk_import_job(payload: Dict[str, Any], progress) -> Dict[str, Any]:
+    return bulk_import(
+        document_store,
+        payload["folder"],
+        default_return_id=payload.get("default_return_id"),
+        recursive=payload.get("recursive", True),
+        progress=progress,
+    )
+
+
+def _reindex_embeddings_job(payload: Dict[str, Any], progress) -> Dict[str, int]:
+    return conversation_store.reindex_embeddings(progress=progress)
+
+
+def _review_packet_job(payload: Dict[str, Any], progress) -> Dict[str, Any]:
+    """Build a review packet and file it with the return's documents"""
+    tax_return = return_store.get_return(payload["return_id"])
+    pdf = _build_review_packet(tax_return)
+    progress({"percent": 90, "message": "Saving review packet"})
+    document = document_store.upload_document(
+        _review_packet_filename(tax_return),
+        content=pdf,
+        document_type="review_packet",
+        return_id=tax_return["return_id"],
+        allow_duplicate=True,
+    )
+    return {"document_id": document["document_id"], "filename": document["filename"]}
+
+
+def _backup_job(payload: Dict[str, Any], progress) -> Dict[str, Any]:
+    return create_backup(payload.get("dest", "backups"), progress=progress)
+
+
+def _extract_document_job(payload: Dict[str, Any], progress) -> Dict[str, Any]:
+    return extract_document(document_store, payload["document_id"])
+
+
+def _queue_document_extraction(document_id: str) -> Dict[str, Any]:
+    """Queue reading a newly imported document's form fields"""
+    return job_queue.enqueue("extract_document", {"document_id": document_id}, f"Extract {document_id}")
+
+
+job_queue.register("bulk_import", _bulk_import_job)
+job_queue.register("backup", _backup_job)
+job_queue.register("reindex_embeddings", _reindex_embeddings_job)
+job_queue.register("review_packet", _review_packet_job)
+job_queue.register("extract_document", _extract_document_job)
+
+
+@app.get("/api/jobs")
+async def list_jobs(
+    status: Optional[str] = Query(None, description="Only jobs with this status"),
+    kind: Optional[str] = Query(None, description="Only jobs of this kind"),
+):
+    """Queued and running background jobs plus in-request operations (bulk imports, ...), newest first"""
+    jobs = job_queue.list_jobs(status=status, kind=kind)
+    queued_ids = {job["job_id"] for job in jobs}
+    jobs += [
+        job for job in progress_bus.list_jobs(status=status)
+        if job["job_id"] not in queued_ids and (kind is None or job["kind"] == kind)
+    ]
+    jobs.sort(key=lambda job: job["created_at"], reverse=True)
+    return {
+        "success": True,
+        "data": jobs,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/jobs")
+async def enqueue_job(request: JobRequest):
+    """Queue a background job; it survives restarts and failed runs are retried with backoff"""
+    if request.kind == "review_packet":
+        _get_return_or_404(str(request.payload.get("return_id", "")))
+    if request.kind == "bulk_import" and not os.path.isdir(str(request.payload.get("folder", ""))):
+        raise InvalidInputError(f"Folder not found: {request.payload.get('folder')}")
+    try:
+        job = job_queue.enqueue(request.kind, request.payload, request.description, request.max_attempts)
+    except ValueError as e:
+        raise InvalidInputError(str(e))
+    return {
+        "success": True,
+        "data": job,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.get("/api/jobs/{job_id}")
+async def get_job(job_id: str):
+    """Status and progress of a job"""
+    try:
+        job = job_queue.get_job(job_id)
+    except KeyError:
+        try:
+            job = progress_bus.get_job(job_id)
+        except KeyError:
+            raise NotFoundError("Job not found")
+    return {
+        "success": True,
+        "data": job,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/jobs/{job_id}/cancel")
+async def cancel_job(job_id: str):
+    """Cancel a queued job, or ask a running one to stop; it is reported as cancelled once it does"""
+    try:
+        try:
+            job = job_queue.cancel_job(job_id)
+        except KeyError:
+            job = progress_bus.cancel_job(job_id)
+    except KeyError:
+        raise NotFoundError("Job not found")
+    except ValueError as e:
+        raise ConflictError(str(e))
+    return {
+        "success": True,
+        "data": job,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.post("/api/jobs/{job_id}/retry")
+async def retry_job(job_id: str):
+    """Queue a failed or cancelled background job again"""
+    try:
+        job = job_queue.retry_job(job_id)
+    except KeyError:
+        raise NotFoundError("Job not found")
+    except ValueError as e:
+        raise ConflictError(str(e))
+    return {
+        "success": True,
+        "data": job,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
+@app.websocket("/ws/jobs")
+async def jobs_websocket(websocket: WebSocket):
+    """Pushes a job://progress event for every job change"""
+    if not app_lock.unlocked:
+        await websocket.close(code=4423, reason="The app is locked")
+        return
+    queue = progress_bus.subscribe()
+    try:
+        await websocket.accept()
+        while True:
+            await websocket.send_json(await queue.get())
+    except WebSocketDisconnect:
+        pass
+    finally:
+        progress_bus.unsubscribe(queue)
+
+
 # ============================================================================
 # VOICE AGENT ENDPOINTS (Text-based chat implemented, audio not implemented)
 # ============================================================================
@@ -378,11 +5823,7 @@ async def voice_chat(request: VoiceChatRequest):
     Audio/speech features require external STT/TTS services (not implemented).
     """
     try:
-        if not os.getenv("ANTHROPIC_API_KEY"):
-            raise HTTPException(
-                status_code=503,
-                detail="AI service not configured. Please set ANTHROPIC_API_KEY environment variable."
-            )
+        _require_ai_configured()
 
         agent = VoiceAgent(session_id=request.session_id)
 
@@ -403,12 +5844,49 @@ async def voice_chat(request: VoiceChatRequest):
         raise
     except Exception as e:
         logger.error(f"Error in voice chat: {str(e)}")
-        raise HTTPException(
-            status_code=500,
-            detail="An error occurred during the conversation. Please try again."
+        raise AIServiceError(
+            "An error occurred during the conversation. Please try again."
         )
 
 
+def _embedding_provider(settings: AppSettings) -> Optional[EmbeddingProvider]:
+    """
+    The embedding provider the settings point at, or None for keyword search
+
+    Hosted providers take a key from EMBEDDING_API_KEY; local ones need none.
+    """
+    if not (settings.embedding_url and settings.embedding_model):
+        return None
+    return HttpEmbeddingProvider(
+        settings.embedding_url, settings.embedding_model, api_key=os.getenv("EMBEDDING_API_KEY")
+    )
+
+
+conversation_store = ConversationStore(embedder=_embedding_provider(settings_store.get_settings()))
+
+
+@app.get("/api/voice/search")
+async def search_chat(
+    query: str = Query(..., min_length=1, max_length=500),
+    limit: int = Query(5, ge=1, le=50),
+    since: Optional[str] = Query(None, description="ISO timestamp; skip older messages"),
+):
+    """
+    Search stored chat history for past AI answers
+
+    Semantic when embedding_url and embedding_model are set (answers are
+    ranked by meaning, so paraphrases match); otherwise, or if the embedding
+    provider is down, a local keyword search. Each result's "match" says
+    which was used. Neither needs the Anthropic API key.
+    """
+    results = conversation_store.search_messages(query, limit=limit, since=since)
+    return {
+        "success": True,
+        "data": results,
+        "timestamp": datetime.utcnow().isoformat(),
+    }
+
+
 @app.websocket("/ws/voice")
 async def voice_websocket(websocket: WebSocket):
     """
@@ -429,6 +5907,49 @@ async def voice_websocket(websocket: WebSocket):
 # ERROR HANDLERS
 # ============================================================================
 
+@app.exception_handler(StarletteHTTPException)
+async def http_exception_handler(request: Request, exc: StarletteHTTPException):
+    """Error responses carry the error kind next to the detail"""
+    if exc.status_code >= 400 and exc.status_code != 404:
+        logger.warning(f"{request.method} {request.url.path} failed ({exc.status_code}): {exc.detail}")
+    return JSONResponse(
+        status_code=exc.status_code,
+        content=error_body(error_kind(exc), exc.detail, datetime.utcnow().isoformat()),
+        headers=getattr(exc, "headers", None),
+    )
+
+
+@app.exception_handler(RequestValidationError)
+async def request_validation_handler(request: Request, exc: RequestValidationError):
+    """Invalid request bodies and parameters are validation errors"""
+    return JSONResponse(
+        status_code=422,
+        content=error_body(ErrorKind.VALIDATION, jsonable_encoder(exc.errors()), datetime.utcnow().isoformat()),
+    )
+
+
+@app.exception_handler(DecryptionError)
+async def decryption_error_handler(request: Request, exc: DecryptionError):
+    """Encrypted fields that can't be read (key changed or data corrupted)"""
+    logger.error(f"Decryption failed: {str(exc)}")
+    return JSONResponse(
+        status_code=500,
+        content=error_body(ErrorKind.CRYPTO, str(exc), datetime.utcnow().isoformat()),
+    )
+
+
+@app.exception_handler(OSError)
+async def storage_error_handler(request: Request, exc: OSError):
+    """Stored data that couldn't be read or written"""
+    logger.error(f"Storage error: {str(exc)}")
+    return JSONResponse(
+        status_code=500,
+        content=error_body(
+            ErrorKind.DB, "Stored data couldn't be read or written. Please try again.", datetime.utcnow().isoformat()
+        ),
+    )
+
+
 @app.exception_handler(Exception)
 async def global_exception_handler(request: Request, exc: Exception):
     """Global error handler - sanitizes errors to prevent info leakage"""
@@ -440,7 +5961,7 @@ async def global_exception_handler(request: Request, exc: Exception):
         content={
             "error": "Internal Server Error",
             "message": "An unexpected error occurred. Please try again later.",
-            "timestamp": datetime.utcnow().isoformat(),
+            **error_body(ErrorKind.INTERNAL, "An unexpected error occurred. Please try again later.", datetime.utcnow().isoformat()),
         }
     )
 
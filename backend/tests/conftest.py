@@ -0,0 +1,15 @@
+"""Shared test fixtures."""
+import pytest
+
+
+@pytest.fixture(autouse=True)
+def fresh_rate_limiter(monkeypatch):
+    """Give every test its own API rate limit so the suite's many requests aren't throttled."""
+    try:
+        import main
+    except ImportError:
+        # fastapi isn't installed; only the API tests import main
+        return None
+    limiter = main.RateLimiter(requests_per_minute=main.rate_limiter.requests_per_minute)
+    monkeypatch.setattr(main, "rate_limiter", limiter)
+    return limiter
@@ -0,0 +1,189 @@
+"""Tests for the per-form 1099 parsers."""
+from decimal import Decimal
+
+from app.documents.parsers import parse_document
+from app.documents.parsers.form_1099_b import Form1099B, parse_1099_b
+from app.documents.parsers.form_1099_div import Form1099Div, parse_1099_div
+from app.documents.parsers.form_1099_int import Form1099Int, parse_1099_int
+from app.documents.parsers.form_1099_k import Form1099K, parse_1099_k
+from app.documents.parsers.form_1099_misc import Form1099Misc, parse_1099_misc
+from app.documents.parsers.form_1099_nec import Form1099Nec, parse_1099_nec
+
+PAYER = """PAYER'S name, street address, city or town, state, ZIP
+First National Bank
+PAYER'S TIN 98-7654321
+"""
+
+
+# ── 1099-INT ──────────────────────────────────────────────────
+
+def test_1099_int():
+    text = PAYER + """
+1 Interest income $1,234.56
+2 Early withdrawal penalty 25.00
+4 Federal income tax withheld 0.00
+8 Tax-exempt interest 300.00
+"""
+    result = parse_1099_int(text)
+    record = result.record
+    assert isinstance(record, Form1099Int)
+    assert record.interest_income == Decimal("1234.56")
+    assert record.early_withdrawal_penalty == Decimal("25.00")
+    assert record.tax_exempt_interest == Decimal("300.00")
+    assert record.payer_name == "First National Bank"
+    assert record.payer_tin == "98-7654321"
+    assert result.warnings == []
+
+
+def test_1099_int_private_bond_interest_exceeds_exempt():
+    result = parse_1099_int("8 Tax-exempt interest 100.00\n9 Specified private activity bond interest 500.00")
+    assert result.fields["private_activity_bond_interest"].confidence == 0.5
+    assert result.warnings
+
+
+# ── 1099-DIV ──────────────────────────────────────────────────
+
+def test_1099_div_separates_ordinary_and_qualified():
+    text = PAYER + """
+1a Total ordinary dividends 2,000.00
+1b Qualified dividends 1,500.00
+2a Total capital gain distr. 400.00
+2b Unrecap. Sec. 1250 gain 50.00
+5 Section 199A dividends 120.00
+"""
+    result = parse_1099_div(text)
+    record = result.record
+    assert isinstance(record, Form1099Div)
+    assert record.ordinary_dividends == Decimal("2000.00")
+    assert record.qualified_dividends == Decimal("1500.00")
+    assert record.nonqualified_dividends == Decimal("500.00")
+    assert record.capital_gain_distributions == Decimal("400.00")
+    assert record.unrecaptured_1250_gain == Decimal("50.00")
+    assert record.section_199a_dividends == Decimal("120.00")
+    assert result.warnings == []
+
+
+def test_1099_div_qualified_over_ordinary_is_flagged():
+    result = parse_1099_div("1a Total ordinary dividends 100.00\n1b Qualified dividends 900.00")
+    assert result.fields["qualified_dividends"].confidence == 0.5
+    assert any("1b" in w for w in result.warnings)
+
+
+# ── 1099-NEC / MISC ───────────────────────────────────────────
+
+def test_1099_nec():
+    text = PAYER + """
+1 Nonemployee compensation 18,500.00
+2 Payer made direct sales totaling $5,000 or more of consumer products [ ]
+4 Federal income tax withheld 0.00
+"""
+    result = parse_1099_nec(text)
+    assert isinstance(result.record, Form1099Nec)
+    assert result.record.nonemployee_compensation == Decimal("18500.00")
+    assert result.record.direct_sales_over_5000 is False
+
+
+def test_1099_misc():
+    text = PAYER + "1 Rents 12,000.00\n2 Royalties 850.00\n3 Other income 400.00\n"
+    result = parse_1099_misc(text)
+    assert isinstance(result.record, Form1099Misc)
+    assert result.record.rents == Decimal("12000.00")
+    assert result.record.royalties == Decimal("850.00")
+    assert result.record.other_income == Decimal("400.00")
+    assert result.record.medical_payments == Decimal("0")
+
+
+def test_1099_misc_without_income_boxes_warns():
+    assert parse_1099_misc(PAYER).warnings
+
+
+# ── 1099-K ────────────────────────────────────────────────────
+
+FILER = """FILER'S name, street address, city or town, state or province, country, ZIP
+Gig Market Inc
+FILER'S TIN 12-3456789
+"""
+
+
+def test_1099_k():
+    text = FILER + """
+[X] Third party network
+1a Gross amount of payment card/third party network transactions $8,200.00
+2 Merchant category code 5999
+3 Number of payment transactions 1,143
+4 Federal income tax withheld 0.00
+5a January 500.00
+5b February 700.00
+5l December 7,000.00
+"""
+    result = parse_1099_k(text)
+    record = result.record
+    assert isinstance(record, Form1099K)
+    assert record.payer_name == "Gig Market Inc" and record.payer_tin == "12-3456789"
+    assert record.third_party_network is True
+    assert record.gross_amount == Decimal("8200.00")
+    assert (record.merchant_category_code, record.transaction_count) == ("5999", 1143)
+    assert record.monthly_total == Decimal("8200.00")
+    assert result.warnings == []
+
+
+def test_1099_k_months_that_dont_add_up_are_flagged():
+    result = parse_1099_k("1a Gross amount of payment card/third party network transactions 900.00\n5c March 400.00")
+    assert result.fields["gross_amount"].confidence == 0.6
+    assert any("5a-5l" in w for w in result.warnings)
+    assert parse_1099_k(FILER).warnings
+
+
+# ── 1099-B ────────────────────────────────────────────────────
+
+def test_1099_b_long_term_gain():
+    text = PAYER + """
+1a Description of property
+100 sh XYZ Corp
+1b Date acquired 03/15/2021
+1c Date sold or disposed 06/01/2024
+1d Proceeds 15,000.00
+1e Cost or other basis 9,000.00
+"""
+    result = parse_1099_b(text)
+    record = result.record
+    assert isinstance(record, Form1099B)
+    assert record.description == "100 sh XYZ Corp"
+    assert record.date_acquired == "03/15/2021"
+    assert record.term == "long"
+    assert record.gain_or_loss == Decimal("6000.00")
+
+
+def test_1099_b_wash_sale_adjusts_loss():
+    text = """
+1b Date acquired 01/10/2024
+1c Date sold 02/10/2024
+1d Proceeds 4,000.00
+1e Cost or other basis 5,000.00
+1g Wash sale loss disallowed 300.00
+"""
+    record = parse_1099_b(text).record
+    assert record.term == "short"
+    assert record.gain_or_loss == Decimal("-700.00")
+
+
+def test_1099_b_checkbox_term_and_various():
+    text = "[X] Short-term\n1b Date acquired VARIOUS\n1d Proceeds 500.00\n1e Cost or other basis 450.00"
+    record = parse_1099_b(text).record
+    assert record.term == "short"
+    assert record.date_acquired == "VARIOUS"
+
+
+def test_1099_b_missing_basis_warns():
+    result = parse_1099_b("1d Proceeds 500.00")
+    assert "gain_or_loss" not in result.fields
+    assert any("basis" in w for w in result.warnings)
+
+
+# ── Dispatch ──────────────────────────────────────────────────
+
+def test_dispatch_and_record_serialization():
+    data = parse_document("1099div", "1a Total ordinary dividends 2,000.00").to_dict()
+    assert data["form"] == "1099-DIV"
+    assert data["record"]["ordinary_dividends"] == 2000.0
+    assert data["record"]["qualified_dividends"] == 0.0
@@ -0,0 +1,17 @@
+"""Tests for the review activity log."""
+from app.utils.activity_log import ActivityLog
+
+
+def test_entries_newest_first(tmp_path):
+    log = ActivityLog(log_path=str(tmp_path / "activity.jsonl"))
+    assert log.entries() == []
+    first = log.record("viewed", "/api/returns", status_code=200)
+    log.record("blocked", "/api/returns", method="POST", status_code=403)
+
+    entries = log.entries()
+    assert [e["action"] for e in entries] == ["blocked", "viewed"]
+    assert log.entries(limit=1)[0]["method"] == "POST"
+    assert len(log.entries(since=first["at"])) == 2
+
+    assert log.clear() == 2
+    assert log.entries() == []
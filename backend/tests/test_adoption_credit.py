@@ -0,0 +1,69 @@
+"""Tests for the adoption credit (Form 8839)."""
+from decimal import Decimal
+
+import pytest
+
+from app.tax_engine.adoption_credit import calculate_adoption_credit, expenses_this_year
+from app.tax_engine.carryforwards import next_year_carryforwards
+from app.tax_engine.return_calculation import calculate_return
+
+
+def child(name, **fields):
+    return {"id": f"adopt_{name.lower()}", "name": name, **fields}
+
+
+def test_expense_timing():
+    # Domestic: last year's expenses count now, this year's wait until next year or the final year
+    pending = expenses_this_year(child("Mia", expenses=3000, prior_year_expenses=2000), 2024)
+    assert (pending["counted"], pending["waiting"]) == (2000, 3000)
+    final = expenses_this_year(child("Mia", expenses=3000, prior_year_expenses=2000, final_year=2024), 2024)
+    assert (final["counted"], final["waiting"]) == (5000, 0)
+    # Foreign: nothing counts until the adoption is final
+    foreign = expenses_this_year(child("Leo", expenses=3000, prior_year_expenses=2000, foreign=True), 2024)
+    assert (foreign["counted"], foreign["waiting"]) == (0, 5000)
+
+
+def test_per_child_limit_and_phaseout():
+    adoptions = [
+        child("Mia", expenses=20000, final_year=2024),
+        child("Leo", expenses=4000, final_year=2024, credit_claimed_before=14000),
+        child("Ava", final_year=2024, special_needs=True),
+    ]
+    result = calculate_adoption_credit(adoptions, Decimal("100000"), "single", 2024)
+    assert [c["lines"]["6"] for c in result["children"]] == [16810.0, 2810.0, 16810.0]
+    assert result["credit"] == 36430.0 and result["carryforward"] == 0
+
+    # Halfway through the $252,150-$292,150 range
+    result = calculate_adoption_credit(adoptions[2:], Decimal("272150"), "single", 2024)
+    assert result["phaseout_percent"] == 50.0
+    assert result["lines"]["12"] == 8405.0
+
+    assert calculate_adoption_credit(adoptions, Decimal("80000"), "married_separate", 2024)["credit"] == 0
+    with pytest.raises(ValueError):
+        calculate_adoption_credit(adoptions, Decimal("80000"), "single", 2023)
+
+
+def test_unused_credit_carries_forward():
+    tax_return = {
+        "return_id": "ret_0000000000000000",
+        "tax_year": 2024,
+        "filing_status": "single",
+        "dependents": [],
+        "income_sources": [{"type": "wages", "amount": 100000, "withholding": 14000}],
+        "deductions": [],
+        "adoptions": [child("Mia", expenses=12500, prior_year_expenses=2000, final_year=2024)],
+        "carryforwards": {},
+    }
+    calculation = calculate_return(tax_return)
+    # $14,500 of credit against $13,841 of tax
+    assert calculation["income_tax"] == 13841.0
+    assert calculation["adoption_credit"]["credit"] == 13841.0
+    assert calculation["total_tax"] == 0
+    assert next_year_carryforwards(tax_return, calculation) == {"adoption_credit": 659.0}
+
+    # Next year the carried credit is used first against that year's tax
+    tax_return.update(adoptions=[], carryforwards={"adoption_credit": 659})
+    calculation = calculate_return(tax_return)
+    assert calculation["adoption_credit"]["lines"]["13"] == 659.0
+    assert calculation["total_tax"] == 13841.0 - 659
+    assert calculate_return({**tax_return, "carryforwards": {}})["adoption_credit"] is None
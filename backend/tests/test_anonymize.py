@@ -0,0 +1,72 @@
+"""Tests for the anonymized return export."""
+from app.tax_engine.return_calculation import calculate_return
+from app.utils.anonymize import ANONYMIZED_FORMAT, anonymize_return
+
+
+def make_return():
+    return {
+        "return_id": "ret_0000000000000000",
+        "tax_year": 2024,
+        "filing_status": "married_joint",
+        "taxpayer": {
+            "name": "Pat Doe", "ssn": "123-45-6789", "spouse_name": "Sam Doe", "spouse_ssn": "987654321",
+            "address": "12 Elm St", "city": "Newark", "state": "NJ", "zip": "07102", "email": "pat@example.com",
+        },
+        "dependents": [{"id": "dep_1", "name": "Kim Doe", "ssn": "111223333", "birth_date": "2015-06-01",
+                        "relationship": "child", "months_lived_with": 12}],
+        "students": [{"id": "stu_1", "name": "Kim Doe", "dependent_id": "dep_1", "institution": "State College"}],
+        "income_sources": [
+            {"id": "inc_1", "type": "wages", "amount": 90000, "withholding": 9000,
+             "description": "W-2", "employer_ein": "12-7654321", "payer_name": "Acme Corp"},
+            {"id": "inc_2", "type": "wages", "amount": 30000, "withholding": 2000, "owner": "spouse",
+             "employer_ein": "127654321", "payer_name": "ACME Corp"},
+        ],
+        "deductions": [{"id": "ded_1", "category": "mortgage_interest", "amount": 24000, "lender_name": "First Bank"}],
+        "businesses": [{"id": "biz_1", "name": "Pottery Studio", "owner": "taxpayer", "ein": "", "expenses": []}],
+        "direct_deposit": {"routing_number": "gAAAA", "account_number": "gAAAA"},
+        "notes": "Call Pat at 555-0100",
+    }
+
+
+def test_replaces_identity_consistently():
+    result = anonymize_return(make_return())
+    assert result["format"] == ANONYMIZED_FORMAT
+    anonymized = result["return"]
+    taxpayer = anonymized["taxpayer"]
+    assert (taxpayer["name"], taxpayer["spouse_name"], taxpayer["address"]) == ("Name 1", "Name 2", "Address 1")
+    assert taxpayer["ssn"] == "000000001" and taxpayer["spouse_ssn"] == "000000002"
+    assert taxpayer["state"] == "NJ"
+    # The dependent and their student record keep matching, as do W-2s from the same employer
+    assert anonymized["dependents"][0]["name"] == anonymized["students"][0]["name"] == "Name 3"
+    assert anonymized["students"][0]["institution"] == "Institution 1"
+    sources = anonymized["income_sources"]
+    assert sources[0]["employer_ein"] == sources[1]["employer_ein"] == "000000004"
+    assert sources[0]["payer_name"] == sources[1]["payer_name"] == "Payer 1"
+    assert anonymized["businesses"][0]["name"] == "Business 1"
+    assert anonymized["businesses"][0]["ein"] == ""
+    assert anonymized["deductions"][0]["lender_name"] == "Lender 1"
+
+
+def test_removes_contact_details_and_secrets():
+    result = anonymize_return(make_return())
+    anonymized = result["return"]
+    assert "direct_deposit" not in anonymized
+    assert anonymized["notes"] == ""
+    assert anonymized["taxpayer"]["city"] == anonymized["taxpayer"]["email"] == ""
+    assert result["summary"]["removed"] == [
+        "taxpayer.city", "taxpayer.zip", "taxpayer.email", "direct_deposit", "notes",
+    ]
+    assert result["summary"]["identifiers"] == 4
+    text = str(anonymized)
+    for secret in ("Pat Doe", "6789", "Elm St", "Acme", "Pottery", "555-0100"):
+        assert secret not in text
+
+
+def test_amounts_and_structure_are_kept():
+    original = make_return()
+    anonymized = anonymize_return(original)["return"]
+    calculations = calculate_return(anonymized), calculate_return(original)
+    for figure in ("agi", "taxable_income", "total_tax", "refund_or_owed"):
+        assert calculations[0][figure] == calculations[1][figure]
+    assert anonymized["dependents"][0]["birth_date"] == "2015-06-01"
+    assert original["taxpayer"]["name"] == "Pat Doe"
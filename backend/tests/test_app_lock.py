@@ -0,0 +1,125 @@
+"""Tests for the app passphrase lock."""
+import base64
+import json
+from datetime import timedelta
+
+import pytest
+
+from app.utils.app_lock import PBKDF2_ITERATIONS, AppLock
+
+
+@pytest.fixture
+def lock(tmp_path):
+    return AppLock(lock_path=str(tmp_path / "lock.json"))
+
+
+def test_unlocked_without_passphrase(lock):
+    assert lock.status() == {"enabled": False, "unlocked": True, "review_mode": False, "review_started_at": None}
+    with pytest.raises(ValueError):
+        lock.lock()
+
+
+def test_lock_and_unlock(lock):
+    lock.set_passphrase("correct horse")
+    assert lock.unlocked
+    lock.lock()
+    assert not lock.unlocked
+    with pytest.raises(ValueError):
+        lock.unlock("wrong horse")
+    assert not lock.unlocked
+    lock.unlock("correct horse")
+    assert lock.unlocked
+
+
+def test_starts_locked_when_passphrase_set(lock, tmp_path):
+    lock.set_passphrase("correct horse")
+    assert b"correct horse" not in (tmp_path / "lock.json").read_bytes()
+    restarted = AppLock(lock_path=str(tmp_path / "lock.json"))
+    assert restarted.status() == {"enabled": True, "unlocked": False, "review_mode": False, "review_started_at": None}
+
+
+def test_change_and_remove_passphrase(lock):
+    with pytest.raises(ValueError):
+        lock.set_passphrase("short")
+    lock.set_passphrase("correct horse")
+    with pytest.raises(ValueError):
+        lock.set_passphrase("battery staple")
+    lock.set_passphrase("battery staple", current="correct horse")
+    with pytest.raises(ValueError):
+        lock.remove_passphrase("correct horse")
+    lock.remove_passphrase("battery staple")
+    assert lock.status() == {"enabled": False, "unlocked": True, "review_mode": False, "review_started_at": None}
+
+
+def test_review_mode(lock):
+    with pytest.raises(ValueError):
+        lock.start_review()  # nothing would stop the accountant from leaving it
+    lock.set_passphrase("correct horse")
+    assert lock.start_review()["review_mode"]
+    assert not lock.verified_within(5)  # the owner's entry doesn't carry over to the reviewer
+    lock.verify("correct horse")
+    assert not lock.verified_within(5)
+    with pytest.raises(ValueError):
+        lock.end_review("wrong horse")
+    assert lock.review_mode
+    lock.end_review("correct horse")
+    assert not lock.review_mode and lock.verified_within(5)
+
+    lock.lock()
+    lock.unlock("correct horse", review_mode=True)
+    assert lock.review_mode and lock.status()["review_started_at"]
+    assert not lock.verified_within(5)
+    lock.lock()
+    lock.unlock("correct horse")
+    assert not lock.review_mode
+
+
+def test_recent_verification(lock):
+    assert lock.verified_within(5)  # no passphrase to re-enter
+    lock.set_passphrase("correct horse")
+    now = lock.verified_at
+    assert lock.verified_within(5, now=now + timedelta(minutes=5))
+    assert not lock.verified_within(5, now=now + timedelta(minutes=6))
+
+    with pytest.raises(ValueError):
+        lock.verify("wrong horse")
+    lock.verify("correct horse")
+    assert lock.verified_at > now
+
+    lock.lock()
+    assert not lock.verified_within(5)
+    with pytest.raises(ValueError, match="locked"):
+        lock.verify("correct horse")
+    lock.unlock("correct horse")
+    assert lock.verified_within(5)
+
+
+def test_upgrade_kdf_settings(lock, tmp_path):
+    with pytest.raises(ValueError, match="No passphrase"):
+        lock.kdf_settings()
+    # A lock file from an older version: fewer iterations and no algorithm recorded
+    salt = b"0123456789abcdef"
+    lock.lock_path.write_text(json.dumps({
+        "salt": base64.b64encode(salt).decode(), "iterations": 1000,
+        "hash": base64.b64encode(AppLock._derive("correct horse", salt, 1000)).decode(),
+    }))
+    assert lock.kdf_settings() == {
+        "algorithm": "sha256", "iterations": 1000, "recommended_iterations": PBKDF2_ITERATIONS,
+        "upgrade_recommended": True,
+    }
+    with pytest.raises(ValueError, match="Incorrect"):
+        lock.upgrade_kdf("wrong horse")
+    assert lock.upgrade_kdf("correct horse")["upgrade_recommended"] is False
+
+    with pytest.raises(ValueError, match="between 210,000"):
+        lock.upgrade_kdf("correct horse", algorithm="sha512", iterations=5000)
+    with pytest.raises(ValueError, match="Invalid algorithm"):
+        lock.upgrade_kdf("correct horse", algorithm="md5")
+    settings = lock.upgrade_kdf("correct horse", algorithm="sha512")
+    assert (settings["algorithm"], settings["iterations"]) == ("sha512", 210_000)
+
+    # Changing the passphrase keeps the upgraded settings
+    lock.set_passphrase("battery staple", current="correct horse")
+    assert lock.kdf_settings()["algorithm"] == "sha512"
+    lock.lock()
+    lock.unlock("battery staple")
@@ -0,0 +1,52 @@
+"""Tests for the rotating application log."""
+import logging
+from pathlib import Path
+
+import pytest
+
+from app.utils.app_log import configure_logging, get_recent_logs
+
+
+@pytest.fixture
+def log_dir(tmp_path):
+    """Log to a temp folder, restoring the root logger afterwards."""
+    root = logging.getLogger()
+    handlers, level = list(root.handlers), root.level
+    configure_logging(str(tmp_path / "logs"))
+    yield str(tmp_path / "logs")
+    for handler in list(root.handlers):
+        if handler not in handlers:
+            root.removeHandler(handler)
+            handler.close()
+    root.setLevel(level)
+
+
+def test_entries_are_scrubbed_and_filtered(log_dir):
+    logger = logging.getLogger("test_app_log")
+    logger.info("Imported W-2 for 123-45-6789")
+    logger.warning("Unlock failed: Incorrect passphrase")
+    try:
+        raise ValueError("account 000123456789 rejected")
+    except ValueError:
+        logger.exception("Deposit failed")
+
+    entries = get_recent_logs(log_dir=log_dir)
+    assert [e["level"] for e in entries[:3]] == ["ERROR", "WARNING", "INFO"]
+    assert entries[2]["message"] == "Imported W-2 for [SSN]"
+    assert "000123456789" not in entries[0]["exception"]
+    assert [e["level"] for e in get_recent_logs(level="warning", log_dir=log_dir)] == ["ERROR", "WARNING"]
+    assert len(get_recent_logs(limit=1, log_dir=log_dir)) == 1
+    with pytest.raises(ValueError):
+        get_recent_logs(level="LOUD", log_dir=log_dir)
+
+
+def test_reads_across_rotated_files(log_dir):
+    handler = configure_logging(log_dir)
+    handler.maxBytes = 300
+    logger = logging.getLogger("test_app_log")
+    for index in range(10):
+        logger.info("Entry %d", index)
+
+    assert (Path(log_dir) / "app.log.1").exists()
+    messages = [e["message"] for e in get_recent_logs(log_dir=log_dir)]
+    assert messages == [f"Entry {index}" for index in range(9, -1, -1)]
@@ -0,0 +1,164 @@
+"""Tests for applying extracted document data to a return."""
+import pytest
+
+from app.documents.apply_extraction import (
+    apply_extraction_to_return,
+    create_deduction_from_receipt,
+    income_source_from_extraction,
+)
+from app.documents.parsers import parse_document
+from app.utils.document_store import DocumentStore
+from app.utils.return_store import ReturnStore
+
+W2_TEXT = """
+Form W-2 Wage and Tax Statement 2024
+b Employer identification number (EIN)
+12-3456789
+c Employer's name, address, and ZIP code
+Acme Widgets Inc
+1 Wages, tips, other compensation 85,000.00
+2 Federal income tax withheld 12,000.00
+"""
+
+
+@pytest.fixture
+def stores(tmp_path):
+    return (
+        DocumentStore(storage_dir=str(tmp_path / "documents")),
+        ReturnStore(storage_dir=str(tmp_path / "returns")),
+    )
+
+
+@pytest.fixture
+def extracted_w2(stores):
+    document_store, _ = stores
+    document = document_store.upload_document("w2.pdf", content=b"w2", document_type="W-2")
+    document_store.save_extraction(document["document_id"], parse_document("W-2", W2_TEXT).to_dict())
+    return document["document_id"]
+
+
+def test_w2_maps_to_wages():
+    source = income_source_from_extraction(parse_document("W-2", W2_TEXT).to_dict())
+    assert source["type"] == "wages"
+    assert source["amount"] == 85000.0
+    assert source["withholding"] == 12000.0
+    assert source["description"] == "Acme Widgets Inc"
+    assert source["employer_ein"] == "12-3456789"
+
+
+def test_unsupported_form_rejected():
+    with pytest.raises(ValueError, match="can't be applied"):
+        income_source_from_extraction({"form": "1095-A", "fields": {}})
+
+
+def test_missing_amount_rejected():
+    with pytest.raises(ValueError, match="wages was not extracted"):
+        income_source_from_extraction({"form": "W-2", "fields": {}})
+
+
+def test_preview_changes_nothing(stores, extracted_w2):
+    document_store, return_store = stores
+    return_id = return_store.create_return()["return_id"]
+
+    result = apply_extraction_to_return(document_store, return_store, extracted_w2, return_id, preview=True)
+    assert result["action"] == "added"
+    assert {c["field"]: c["after"] for c in result["changes"]} == {
+        "income_by_type.wages": 85000.0,
+        "total_income": 85000.0,
+        "total_withholding": 12000.0,
+    }
+    assert return_store.get_return(return_id)["income_sources"] == []
+    assert "applied_to" not in document_store.get_document(extracted_w2)
+
+
+def test_apply_links_source_and_document(stores, extracted_w2):
+    document_store, return_store = stores
+    return_id = return_store.create_return()["return_id"]
+
+    result = apply_extraction_to_return(document_store, return_store, extracted_w2, return_id)
+    source = return_store.get_return(return_id)["income_sources"][0]
+    assert source["document_id"] == extracted_w2
+    assert result["income_source"]["after"]["id"] == source["id"]
+
+    applied = document_store.get_document(extracted_w2)["applied_to"]
+    assert applied[0]["return_id"] == return_id
+    assert applied[0]["income_source_id"] == source["id"]
+
+
+def test_reapply_updates_instead_of_duplicating(stores, extracted_w2):
+    document_store, return_store = stores
+    return_id = return_store.create_return()["return_id"]
+    apply_extraction_to_return(document_store, return_store, extracted_w2, return_id)
+
+    again = apply_extraction_to_return(document_store, return_store, extracted_w2, return_id)
+    assert again["action"] == "unchanged"
+    assert again["changes"] == []
+
+    corrected = parse_document("W-2", W2_TEXT.replace("85,000.00", "86,000.00")).to_dict()
+    document_store.save_extraction(extracted_w2, corrected)
+    updated = apply_extraction_to_return(document_store, return_store, extracted_w2, return_id)
+    assert updated["action"] == "updated"
+    assert updated["income_source"]["before"]["amount"] == 85000.0
+    assert {"field": "total_income", "before": 85000.0, "after": 86000.0, "difference": 1000.0} in updated["changes"]
+    assert len(return_store.get_return(return_id)["income_sources"]) == 1
+    assert len(document_store.get_document(extracted_w2)["applied_to"]) == 1
+
+
+def test_requires_extraction(stores):
+    document_store, return_store = stores
+    document = document_store.upload_document("scan.pdf", content=b"scan")
+    return_id = return_store.create_return()["return_id"]
+    with pytest.raises(ValueError, match="parse it first"):
+        apply_extraction_to_return(document_store, return_store, document["document_id"], return_id)
+
+
+# ── Receipts ────────────────────────────────────────────────────
+
+RECEIPT_TEXT = "Springfield Dental Clinic\n02/02/2024\nCleaning 150.00\nTotal 150.00\n"
+
+
+@pytest.fixture
+def extracted_receipt(stores):
+    document_store, return_store = stores
+    return_id = return_store.create_return()["return_id"]
+    document = document_store.upload_document("receipt.jpg", content=b"receipt", return_id=return_id)
+    document_store.save_extraction(document["document_id"], parse_document("receipt", RECEIPT_TEXT).to_dict())
+    return document["document_id"], return_id
+
+
+def test_receipt_creates_linked_deduction(stores, extracted_receipt):
+    document_store, return_store = stores
+    document_id, return_id = extracted_receipt
+
+    result = create_deduction_from_receipt(document_store, return_store, document_id)
+    assert result["action"] == "added"
+    deduction = return_store.get_return(return_id)["deductions"][0]
+    assert deduction["receipt_id"] == document_id
+    assert deduction["category"] == "medical"
+    assert deduction["amount"] == 150.0
+    assert deduction["date"] == "2024-02-02"
+    assert {"field": "total_itemized_deductions", "before": 0.0, "after": 150.0, "difference": 150.0} in result["changes"]
+    assert document_store.get_document(document_id)["applied_to"][0]["deduction_id"] == deduction["id"]
+
+
+def test_receipt_rerun_and_category_override(stores, extracted_receipt):
+    document_store, return_store = stores
+    document_id, return_id = extracted_receipt
+    create_deduction_from_receipt(document_store, return_store, document_id)
+
+    assert create_deduction_from_receipt(document_store, return_store, document_id)["action"] == "unchanged"
+    result = create_deduction_from_receipt(document_store, return_store, document_id, category="other")
+    assert result["action"] == "updated"
+    assert [d["category"] for d in return_store.get_return(return_id)["deductions"]] == ["other"]
+
+
+def test_receipt_without_category_needs_one(stores):
+    document_store, return_store = stores
+    return_id = return_store.create_return()["return_id"]
+    document = document_store.upload_document("r.jpg", content=b"r")
+    document_store.save_extraction(document["document_id"], parse_document("receipt", "Shop\nTotal 9.00").to_dict())
+
+    with pytest.raises(ValueError, match="category"):
+        create_deduction_from_receipt(document_store, return_store, document["document_id"], return_id)
+    result = create_deduction_from_receipt(document_store, return_store, document["document_id"], return_id, "other")
+    assert result["deduction"]["amount"] == 9.0
@@ -0,0 +1,35 @@
+"""Tests for the at-risk loss limitation."""
+from decimal import Decimal
+
+from app.tax_engine.at_risk import at_risk_activities, limit_to_at_risk
+
+
+def test_loss_limited_to_amount_at_risk():
+    result = limit_to_at_risk(Decimal("-10000"), Decimal("4000"))
+    assert result == {"allowed": -4000.0, "carryforward": 6000.0, "ending_at_risk": 0.0}
+
+
+def test_profit_frees_up_carried_losses():
+    # $5,000 of profit is offset by the carried loss; $1,000 more is allowed against the $1,000 at risk
+    result = limit_to_at_risk(Decimal("5000"), Decimal("1000"), Decimal("8000"))
+    assert result == {"allowed": -1000.0, "carryforward": 2000.0, "ending_at_risk": 0.0}
+
+    result = limit_to_at_risk(Decimal("5000"), Decimal("1000"), Decimal("3000"))
+    assert result == {"allowed": 2000.0, "carryforward": 0.0, "ending_at_risk": 3000.0}
+
+
+def test_no_at_risk_amount_means_unlimited():
+    result = limit_to_at_risk(Decimal("-10000"), None, Decimal("500"))
+    assert result == {"allowed": -10500.0, "carryforward": 0.0, "ending_at_risk": None}
+
+
+def test_activities():
+    businesses = [{"id": "biz_1", "name": "Studio", "at_risk_amount": 2500}]
+    schedules = [{"business_id": "biz_1", "name": "Studio", "net_profit": -4000.0}]
+    rentals = [{"id": "rent_1", "address": "12 Elm St"}]
+    years = [{"property_id": "rent_1", "address": "12 Elm St", "net_income": -3000.0}]
+
+    business, rental = at_risk_activities(businesses, schedules, rentals, years)
+    assert (business["activity_type"], business["allowed"], business["carryforward"], business["limited"]) == (
+        "business", -2500.0, 1500.0, True)
+    assert (rental["activity_type"], rental["allowed"], rental["limited"]) == ("rental", -3000.0, False)
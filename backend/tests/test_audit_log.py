@@ -0,0 +1,23 @@
+"""Tests for the security audit log."""
+import pytest
+
+from app.utils.audit_log import AuditLog
+
+
+def test_events_newest_first_and_filtered(tmp_path):
+    log = AuditLog(log_path=str(tmp_path / "audit.jsonl"))
+    assert log.entries() == []
+    first = log.record("export", "GET /api/data/export", "success", detail="200")
+    log.record("reveal", "ip-pin show ret_1", "denied", source="cli", detail="The app is locked")
+    log.record("deletion", "DELETE /api/documents/doc_1", "failed", detail="404")
+
+    assert [e["action"] for e in log.entries()] == ["deletion", "reveal", "export"]
+    assert log.entries(limit=1)[0]["target"] == "DELETE /api/documents/doc_1"
+    assert log.entries(action="reveal")[0]["source"] == "cli"
+    assert [e["action"] for e in log.entries(outcome="success")] == ["export"]
+    assert len(log.entries(since=first["at"])) == 3
+
+    with pytest.raises(ValueError, match="Invalid action"):
+        log.record("viewed", "GET /api/returns", "success")
+    with pytest.raises(ValueError, match="Invalid outcome"):
+        log.record("export", "GET /api/data/export", "maybe")
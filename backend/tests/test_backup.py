@@ -0,0 +1,96 @@
+"""Tests for data backups."""
+import hashlib
+import json
+import zipfile
+
+import pytest
+
+from app.utils.app_lock import AppLock
+from app.utils.backup import create_backup, verify_backup
+from app.utils.field_encryption import FieldCipher
+from app.utils.return_store import ReturnStore
+
+pytest.importorskip("cryptography")
+from cryptography.fernet import Fernet  # noqa: E402
+
+
+def test_backup_archive_and_manifest(tmp_path):
+    data = tmp_path / "data"
+    (data / ".tax_returns").mkdir(parents=True)
+    (data / ".tax_returns" / "ret_0000000000000001.json").write_text('{"return_id": "ret_0000000000000001"}')
+    (data / ".tax_returns" / ".field_encryption.key").write_bytes(b"secret")
+    (data / ".app_settings.json").write_text("{}")
+
+    events = []
+    result = create_backup(str(tmp_path / "backups"), base_dir=str(data), progress=events.append)
+    assert result["files"] == 2
+    assert result["excluded"] == [".tax_returns/.field_encryption.key"]
+    assert events[-1]["percent"] == 100
+
+    with zipfile.ZipFile(result["path"]) as archive:
+        manifest = json.loads(archive.read("manifest.json"))
+        content = archive.read(".tax_returns/ret_0000000000000001.json")
+    entry = manifest["files"][".tax_returns/ret_0000000000000001.json"]
+    assert entry["sha256"] == hashlib.sha256(content).hexdigest()
+    assert manifest["version"] == 1
+
+
+def test_backup_with_no_data(tmp_path):
+    result = create_backup(str(tmp_path / "backups"), base_dir=str(tmp_path / "empty"))
+    assert (result["files"], result["bytes"]) == (0, 0)
+
+
+def make_data(root):
+    (root / ".tax_returns").mkdir(parents=True)
+    store = ReturnStore(storage_dir=str(root / ".tax_returns"))
+    tax_return = store.create_return()
+    store.set_ip_pin(tax_return["return_id"], "taxpayer", "482913")
+    AppLock(lock_path=str(root / ".app_lock.json")).set_passphrase("correct horse")
+    return store
+
+
+def test_verify_backup(tmp_path):
+    data = tmp_path / "data"
+    make_data(data)
+    archive = create_backup(str(tmp_path / "backups"), base_dir=str(data))["path"]
+    key_path = str(data / ".tax_returns" / ".field_encryption.key")
+
+    result = verify_backup(archive, passphrase="correct horse", key_path=key_path)
+    assert result["valid"] is True and result["problems"] == []
+    assert result["passphrase"] == "verified"
+    assert result["encrypted_fields"] == {"checked": 1, "unreadable": 0}
+    assert verify_backup(archive, passphrase="wrong", key_path=key_path)["passphrase"] == "wrong"
+
+    # Another install's key can't read the encrypted fields
+    other = verify_backup(archive, cipher=FieldCipher(key=Fernet.generate_key()))
+    assert other["encrypted_fields"]["unreadable"] == 1 and other["passphrase"] == "not_checked"
+    missing_key = verify_backup(archive, key_path=str(tmp_path / "none.key"))
+    assert any("No encryption key" in warning for warning in missing_key["warnings"])
+    assert not (tmp_path / "none.key").exists()
+
+
+def test_verify_backup_finds_damage(tmp_path):
+    data = tmp_path / "data"
+    (data / ".tax_returns").mkdir(parents=True)
+    (data / ".tax_returns" / "ret_0000000000000001.json").write_text('{"return_id": "ret_0000000000000001"}')
+    (data / ".app_settings.json").write_text("{}")
+    archive = create_backup(str(tmp_path / "backups"), base_dir=str(data))["path"]
+
+    damaged = tmp_path / "damaged.zip"
+    with zipfile.ZipFile(archive) as source, zipfile.ZipFile(damaged, "w") as target:
+        for name in source.namelist():
+            content = source.read(name)
+            if name == ".app_settings.json":
+                content = b"{not json"
+            target.writestr(name, content)
+        target.writestr("../outside.json", "{}")
+    result = verify_backup(str(damaged), key_path=str(tmp_path / "none.key"))
+    assert result["valid"] is False
+    assert result["problems"] == [
+        {"file": ".app_settings.json", "problem": "Checksum doesn't match the manifest"},
+        {"file": "../outside.json", "problem": "Not listed in the manifest"},
+    ]
+    assert result["passphrase"] == "no_lock"
+
+    with pytest.raises(ValueError, match="Not a backup archive"):
+        verify_backup(str(data / ".app_settings.json"))
@@ -0,0 +1,96 @@
+"""Tests for the broker gain/loss CSV importer."""
+import pytest
+
+from app.documents.broker_csv import detect_layout, parse_broker_csv
+
+FIDELITY_CSV = """Brokerage Account X12345678
+Realized Gain/Loss 2024
+
+Symbol(CUSIP),Security Description,Quantity,Date Acquired,Date Sold,Proceeds,Cost Basis,Wash Sale Loss Disallowed,Term
+AAPL,APPLE INC,10,01/15/2023,03/01/2024,"$1,800.00","$1,500.00",,Long-term
+MSFT,MICROSOFT CORP,5.5,06/01/2024,07/01/2024,"$2,000.00","$2,300.00",$100.00,Short-term
+Total,,,,,"$3,800.00","$3,800.00",,
+"The information above is provided for your convenience"
+"""
+
+SCHWAB_CSV = """Symbol,Name,Closed Date,Opened Date,Quantity,Proceeds,Cost Basis (CB),Gain/Loss ($),Term,Disallowed Loss
+VTI,VANGUARD TOTAL STOCK MKT ETF,2024-11-01,2024-02-01,3,$750.00,$800.00,($50.00),Short Term,--
+"""
+
+VANGUARD_CSV = """Symbol,Name,Shares,Date acquired,Date sold,Proceeds,Cost basis,Holding period
+VFIAX,500 Index Admiral,1.2345,VARIOUS,12/15/2024,600.00,400.00,Long
+"""
+
+
+def test_detects_layouts():
+    assert detect_layout(["Date Sold", "Proceeds", "Cost Basis", "Quantity", "Security Description"]) == "fidelity"
+    assert detect_layout(["Closed Date", "Proceeds", "Cost Basis (CB)"]) == "schwab"
+    assert detect_layout(["Shares", "Date sold", "Proceeds", "Cost basis", "Holding period"]) == "vanguard"
+    assert detect_layout(["Foo", "Bar"]) is None
+
+
+def test_fidelity_skips_preamble_and_totals():
+    parsed = parse_broker_csv(FIDELITY_CSV)
+    assert parsed["layout"] == "fidelity"
+    assert len(parsed["transactions"]) == 2
+
+    aapl, msft = parsed["transactions"]
+    assert aapl["date_acquired"] == "2023-01-15"
+    assert aapl["proceeds"] == 1800.0
+    assert aapl["gain_or_loss"] == 300.0
+    assert aapl["term"] == "long"
+    # Disallowed wash sale loss is added back
+    assert msft["wash_sale_disallowed"] == 100.0
+    assert msft["gain_or_loss"] == -200.0
+    assert msft["quantity"] == 5.5
+
+
+def test_schwab_negative_and_placeholder_amounts():
+    lot = parse_broker_csv(SCHWAB_CSV)["transactions"][0]
+    assert lot["date_sold"] == "2024-11-01"
+    assert lot["gain_or_loss"] == -50.0
+    assert lot["wash_sale_disallowed"] == 0.0
+    assert lot["term"] == "short"
+
+
+def test_vanguard_various_dates_and_fractional_shares():
+    lot = parse_broker_csv(VANGUARD_CSV)["transactions"][0]
+    assert lot["date_acquired"] == "VARIOUS"
+    assert lot["quantity"] == 1.2345
+    assert lot["term"] == "long"
+
+
+def test_term_from_holding_period():
+    csv_text = "Date Acquired,Date Sold,Proceeds,Cost Basis\n02/29/2024,03/01/2025,10,5\n01/01/2024,01/01/2025,10,5\n"
+    lots = parse_broker_csv(csv_text)["transactions"]
+    assert [lot["term"] for lot in lots] == ["long", "short"]
+
+
+def test_generic_mapping():
+    csv_text = "Sold On,Sale Amount,Basis,Ticker\n04/01/2024,100.00,,XYZ\n"
+    parsed = parse_broker_csv(csv_text, mapping={
+        "date_sold": "Sold On", "proceeds": "Sale Amount", "cost_basis": "Basis", "symbol": "Ticker",
+    })
+    lot = parsed["transactions"][0]
+    assert parsed["layout"] == "generic"
+    assert lot["description"] == "XYZ"
+    assert lot["cost_basis"] is None
+    assert lot["gain_or_loss"] is None
+
+    with pytest.raises(ValueError, match="Mapping must include"):
+        parse_broker_csv(csv_text, mapping={"date_sold": "Sold On"})
+
+
+def test_bad_rows_reported():
+    csv_text = "Date Sold,Proceeds,Cost Basis\nlast tuesday,10,5\n01/02/2024,ten,5\n"
+    parsed = parse_broker_csv(csv_text)
+    assert parsed["transactions"] == []
+    assert len(parsed["warnings"]) == 2
+    assert parsed["warnings"][0].startswith("Row 2:")
+
+
+def test_unrecognized_layout():
+    with pytest.raises(ValueError, match="Couldn't recognize"):
+        parse_broker_csv("a,b,c\n1,2,3\n")
+    with pytest.raises(ValueError, match="Unknown broker layout"):
+        parse_broker_csv(SCHWAB_CSV, layout="etrade")
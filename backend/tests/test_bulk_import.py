@@ -0,0 +1,84 @@
+"""Tests for bulk folder import."""
+import pytest
+
+from app.documents.bulk_import import bulk_import, infer_document_type
+from app.utils.document_store import DocumentStore
+
+
+@pytest.fixture
+def store(tmp_path):
+    """Create a document store with a temp directory."""
+    return DocumentStore(storage_dir=str(tmp_path / "documents"))
+
+
+@pytest.fixture
+def folder(tmp_path):
+    """A folder of mixed tax documents."""
+    root = tmp_path / "taxes-2024"
+    (root / "bank").mkdir(parents=True)
+    (root / "w2_acme.pdf").write_bytes(b"%PDF acme w2")
+    (root / "bank" / "chase-1099-INT.pdf").write_bytes(b"%PDF interest")
+    (root / "scan_0001.txt").write_bytes(b"Form 1099-DIV Dividends and Distributions 2024")
+    (root / "notes.txt").write_bytes(b"call the accountant")
+    (root / "empty.pdf").write_bytes(b"")
+    (root / ".DS_Store").write_bytes(b"junk")
+    return root
+
+
+@pytest.mark.parametrize("filename, expected", [
+    ("W2_2024.pdf", "W-2"),
+    ("acme w-2.pdf", "W-2"),
+    ("1099int.pdf", "1099-INT"),
+    ("Schwab 1099-B.pdf", "1099-B"),
+    ("paypal_1099k_2024.pdf", "1099-K"),
+    ("1095-a marketplace.pdf", "1095-A"),
+    ("1098_mortgage.pdf", "1098"),
+    ("costco receipt.jpg", "receipt"),
+    ("1098-T tuition.pdf", "1098-T"),
+    ("1098-E student loan.pdf", "unknown"),
+    ("w22.pdf", "unknown"),
+])
+def test_infer_type_from_filename(filename, expected):
+    assert infer_document_type(filename)[0] == expected
+
+
+def test_infer_type_from_content():
+    assert infer_document_type("scan.txt", b"Wage and Tax Statement 2024") == ("W-2", "content")
+    assert infer_document_type("scan.txt", b"nothing here") == ("unknown", "default")
+
+
+def test_bulk_import_report(store, folder):
+    events = []
+    report = bulk_import(store, str(folder), default_return_id="ret_0000000000000001", progress=events.append)
+
+    assert report["total"] == 5  # hidden file skipped
+    assert report["imported"] == 4
+    assert report["failed"] == 1
+
+    by_file = {r["file"]: r for r in report["results"]}
+    assert by_file["w2_acme.pdf"]["document_type"] == "W-2"
+    assert by_file["bank/chase-1099-INT.pdf"]["document_type"] == "1099-INT"
+    assert by_file["scan_0001.txt"]["type_source"] == "content"
+    assert by_file["empty.pdf"]["status"] == "failed"
+
+    assert [e["current"] for e in events] == [1, 2, 3, 4, 5]
+    assert events[-1]["percent"] == 100
+    assert all(d["return_id"] == "ret_0000000000000001" for d in store.list_documents())
+
+
+def test_reimport_reports_duplicates(store, folder):
+    bulk_import(store, str(folder))
+    report = bulk_import(store, str(folder))
+    assert report["imported"] == 0
+    assert report["duplicate"] == 4
+    assert len(store.list_documents()) == 4
+
+
+def test_non_recursive(store, folder):
+    report = bulk_import(store, str(folder), recursive=False)
+    assert "bank/chase-1099-INT.pdf" not in {r["file"] for r in report["results"]}
+
+
+def test_missing_folder(store, tmp_path):
+    with pytest.raises(ValueError, match="Folder not found"):
+        bulk_import(store, str(tmp_path / "nope"))
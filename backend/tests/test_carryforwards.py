@@ -0,0 +1,45 @@
+"""Tests for next-year carryforwards."""
+from app.tax_engine.carryforwards import next_year_carryforwards
+
+
+def make_return(lots, filing_status="single", carryforwards=None):
+    return {
+        "filing_status": filing_status,
+        "capital_transactions": [{"gain_or_loss": amount} for amount in lots],
+        "carryforwards": carryforwards or {},
+    }
+
+
+def test_capital_loss_beyond_limit_carries_forward():
+    assert next_year_carryforwards(make_return([-10000, 2000])) == {"capital_loss": 5000.0}
+    assert next_year_carryforwards(make_return([-10000], "married_separate")) == {"capital_loss": 8500.0}
+    assert next_year_carryforwards(make_return([-2500])) == {}
+
+
+def test_prior_carryover_offsets_gains():
+    # A $6,000 loss carried in uses up a $2,000 gain and $3,000 of other income
+    assert next_year_carryforwards(make_return([2000], carryforwards={"capital_loss": 6000})) == {"capital_loss": 1000.0}
+
+
+def test_rental_sale_gain_offsets_capital_loss():
+    tax_return = make_return([-20000])
+    tax_return.update(tax_year=2024, rental_properties=[{
+        "building_basis": 275000, "placed_in_service": "2020-01-15", "date_sold": "2024-06-10", "sale_price": 240000,
+    }])
+    # $9,166.67 gain on the rental
+    assert next_year_carryforwards(tax_return) == {"capital_loss": 7833.33}
+
+
+def test_nol_and_amt_credit_carry_over_unchanged():
+    result = next_year_carryforwards(make_return([None], carryforwards={"nol": 12000, "amt_credit": 800}))
+    assert result == {"nol": 12000.0, "amt_credit": 800.0}
+
+
+def test_capital_gain_income_offsets_capital_loss():
+    from app.tax_engine.return_calculation import calculate_return
+    tax_return = make_return([-10000])
+    tax_return.update(tax_year=2024, income_sources=[
+        {"type": "wages", "amount": 60000}, {"type": "capital_gains", "amount": 5000},
+    ])
+    assert next_year_carryforwards(tax_return) == {"capital_loss": 2000.0}
+    assert calculate_return(tax_return)["capital_loss_carryforward"] == 2000.0
@@ -0,0 +1,71 @@
+"""Tests for the Claude client setup and request scheduler (no API calls are made)."""
+import asyncio
+import threading
+import time
+
+import pytest
+
+from app.agents.claude_client import RequestScheduler, client_options
+from app.utils.settings_store import AppSettings
+
+
+def test_concurrency_is_capped():
+    scheduler = RequestScheduler(max_concurrency=2, requests_per_minute=100)
+    lock = threading.Lock()
+    peak = {"current": 0, "max": 0}
+
+    def slow_call():
+        with lock:
+            peak["current"] += 1
+            peak["max"] = max(peak["max"], peak["current"])
+        time.sleep(0.05)
+        with lock:
+            peak["current"] -= 1
+        return "ok"
+
+    async def main():
+        return await asyncio.gather(*[scheduler.run(slow_call) for _ in range(6)])
+
+    assert asyncio.run(main()) == ["ok"] * 6
+    assert peak["max"] == 2
+    assert scheduler.get_stats()["in_flight"] == 0
+    assert scheduler.get_stats()["queued"] == 0
+
+
+def test_requests_per_window_are_queued():
+    scheduler = RequestScheduler(max_concurrency=5, requests_per_minute=2, window_seconds=0.2)
+    starts = []
+
+    async def main():
+        await asyncio.gather(*[scheduler.run(lambda: starts.append(time.monotonic())) for _ in range(3)])
+
+    asyncio.run(main())
+    starts.sort()
+    assert starts[2] - starts[0] >= 0.19
+
+
+def test_arguments_and_errors_pass_through():
+    scheduler = RequestScheduler()
+
+    def add(a, b=0):
+        return a + b
+
+    def boom():
+        raise RuntimeError("api down")
+
+    assert asyncio.run(scheduler.run(add, 2, b=3)) == 5
+    with pytest.raises(RuntimeError, match="api down"):
+        asyncio.run(scheduler.run(boom))
+    assert scheduler.get_stats()["in_flight"] == 0
+
+
+def test_invalid_limits_rejected():
+    with pytest.raises(ValueError):
+        RequestScheduler(max_concurrency=0)
+
+
+def test_client_options_from_settings():
+    assert client_options(AppSettings()) == {}
+    options = client_options(AppSettings(ai_base_url="https://gateway.example.com", ai_proxy_url="http://proxy:8080"))
+    assert options["base_url"] == "https://gateway.example.com"
+    assert "http_client" in options
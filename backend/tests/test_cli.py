@@ -0,0 +1,678 @@
+"""Tests for the headless command line."""
+import io
+import json
+import zipfile
+
+import pytest
+
+import cli
+from app.tax_engine.return_calculation import calculate_return
+from app.utils.app_lock import AppLock
+from app.utils.client_store import ClientStore
+from app.utils.document_store import DocumentStore
+from app.utils.return_store import ReturnStore
+from app.utils.settings_store import SettingsStore
+
+
+@pytest.fixture
+def data_dir(tmp_path, monkeypatch):
+    """Run commands against an empty data folder."""
+    monkeypatch.chdir(tmp_path)
+    monkeypatch.delenv(cli.PASSPHRASE_ENV_VAR, raising=False)
+    return tmp_path
+
+
+def run(*argv):
+    stdout, stderr = io.StringIO(), io.StringIO()
+    code = cli.main(list(argv), stdout=stdout, stderr=stderr)
+    return code, json.loads(stdout.getvalue()) if code == 0 else stderr.getvalue()
+
+
+def make_return():
+    store = ReturnStore()
+    tax_return = store.create_return(taxpayer={"name": "Pat Doe"})
+    store.add_income_source(tax_return["return_id"], "wages", 60000, withholding=7000)
+    return tax_return["return_id"]
+
+
+def test_calculate_and_save(data_dir):
+    return_id = make_return()
+    code, calculation = run("calculate", return_id, "--save")
+    assert code == 0
+    assert calculation["agi"] == 60000
+    assert ReturnStore().get_return(return_id)["calculated_tax"] == calculation["total_tax"]
+
+
+def test_what_if_is_not_saved(data_dir):
+    return_id = make_return()
+    code, result = run("calculate", return_id, "--add-income", "self_employment=10000", "--add-deduction", "charitable=500")
+    assert code == 0
+    assert result["what_if"]["agi"] > result["baseline"]["agi"]
+    assert result["total_tax_change"] > 0
+    assert ReturnStore().get_return(return_id).get("calculated_tax") is None
+
+    code, error = run("calculate", return_id, "--add-income", "lottery=100")
+    assert code == cli.EXIT_ERROR and "Invalid income type" in error
+    code, error = run("calculate", return_id, "--filing-status", "head_of_household", "--save")
+    assert code == cli.EXIT_ERROR
+
+
+def test_export_formats(data_dir):
+    return_id = make_return()
+    code, result = run("export", return_id)
+    assert code == 0
+    assert json.loads((data_dir / f"{return_id}_2024.json").read_text())["return_id"] == return_id
+
+    code, result = run("export", return_id, "--format", "review-packet", "--output", "packet.pdf")
+    assert code == 0
+    assert (data_dir / "packet.pdf").read_bytes().startswith(b"%PDF")
+
+    SettingsStore().update_settings({"currency_format": "eu"})
+    code, result = run("export", return_id, "--format", "csv")
+    assert code == 0
+    assert "income;" in (data_dir / f"{return_id}_2024_ledger.csv").read_text()
+    assert ";60.000,00;7.000,00" in (data_dir / f"{return_id}_2024_ledger.csv").read_text()
+    code, result = run("export", return_id, "--format", "anonymized")
+    anonymized = json.loads((data_dir / f"{return_id}_2024_anonymized.json").read_text())
+    assert code == 0 and anonymized["return"]["taxpayer"]["name"] == "Name 1"
+    code, error = run("export", "ret_0000000000000000")
+    assert code == cli.EXIT_ERROR and "Return not found" in error
+
+
+def test_profit_loss(data_dir):
+    return_id = make_return()
+    store = ReturnStore()
+    business = store.add_business(return_id, "Studio")
+    store.add_income_source(return_id, "self_employment", 8000, business_id=business["id"], date="2024-02-01")
+    store.add_business_expense(return_id, business["id"], "supplies", 500, date="2024-05-01")
+
+    code, report = run("profit-loss", return_id, business["id"], "--through-quarter", "2")
+    assert code == 0
+    assert report["net_profit"] == {"q1": 8000.0, "q2": -500.0, "undated": 0.0, "ytd": 7500.0}
+
+    code, result = run("profit-loss", return_id, business["id"], "--format", "csv")
+    assert code == 0
+    assert "Net profit (loss)" in (data_dir / f"{return_id}_2024_{business['id']}_profit_loss.csv").read_text()
+    code, result = run("profit-loss", return_id, business["id"], "--format", "pdf", "--output", "pl.pdf")
+    assert (data_dir / "pl.pdf").read_bytes().startswith(b"%PDF")
+    code, error = run("profit-loss", return_id, "biz_missing")
+    assert code == cli.EXIT_ERROR and "Business not found" in error
+
+
+def test_invoices(data_dir):
+    code, invoice = run("invoices", "add", "--client", "Acme Inc", "--amount", "2500", "--issued", "2024-03-01")
+    assert code == 0
+    code, invoices = run("invoices", "list", "--status", "open")
+    assert [i["id"] for i in invoices] == [invoice["id"]]
+    code, updated = run("invoices", "update", invoice["id"], "--paid", "2024-03-20")
+    assert updated["paid_date"] == "2024-03-20"
+
+    code, report = run("invoices", "reconcile", "--year", "2024")
+    assert code == 0
+    assert report["clients"][0]["status"] == "missing_1099"
+    code, error = run("invoices", "update", invoice["id"], "--paid", "2024-01-01")
+    assert code == cli.EXIT_ERROR and "before the issue date" in error
+    code, result = run("invoices", "delete", invoice["id"])
+    assert result["deleted"] is True
+    code, error = run("invoices", "delete", invoice["id"])
+    assert code == cli.EXIT_ERROR and "Invoice not found" in error
+
+
+def test_platform_income(data_dir):
+    return_id = make_return()
+    document_store = DocumentStore()
+    document = document_store.upload_document("paypal_1099k.pdf", content=b"1099-K", document_type="1099-K",
+                                              tax_year=2024, return_id=return_id)
+    document_store.save_extraction(document["document_id"], {"form": "1099-K", "fields": {
+        "payer_name": {"value": "PayApp"}, "gross_amount": {"value": 2600},
+    }})
+    code, report = run("platform-income", "reconcile", return_id)
+    assert code == 0 and report["platforms"][0]["status"] == "unreported"
+
+    code, allocation = run("platform-income", "allocate", return_id, document["document_id"],
+                           "--personal-sales", "2600", "--personal-cost", "3100")
+    assert code == 0 and allocation["personal_sales"] == 2600
+    code, report = run("platform-income", "reconcile", return_id)
+    assert report["platforms"][0]["status"] == "personal" and report["issues"] == 0
+    code, error = run("platform-income", "allocate", return_id, "doc_missing")
+    assert code == cli.EXIT_ERROR and "Document not found" in error
+
+
+def test_hobby_test(data_dir):
+    return_id = make_return()
+    code, questions = run("hobby-test", "questions")
+    assert code == 0 and len(questions) == 9 and questions[0]["factor"] == "manner"
+    code, determination = run("hobby-test", "answer", return_id, "--activity", "Dog breeding",
+                              "--answer", "manner=no", "--answer", "personal_pleasure=no",
+                              "--note", "personal_pleasure=Shows dogs on weekends for fun")
+    assert code == 0 and determination["recommendation"] == "hobby"
+    assert "(Shows dogs on weekends for fun)" in determination["rationale"][8]
+    code, determinations = run("hobby-test", "list", return_id)
+    assert [d["id"] for d in determinations] == [determination["id"]]
+    code, error = run("hobby-test", "answer", return_id, "--activity", "Dog breeding", "--answer", "manner")
+    assert code == cli.EXIT_ERROR and "Invalid answer" in error
+    code, result = run("hobby-test", "delete", return_id, determination["id"])
+    assert result["deleted"] is True
+    code, error = run("hobby-test", "delete", return_id, determination["id"])
+    assert code == cli.EXIT_ERROR and "Determination not found" in error
+
+
+def test_estimated_payments(data_dir):
+    return_id = make_return()
+    ReturnStore().add_income_source(return_id, "self_employment", 30000, description="Consulting")
+    code, reminders = run("estimated-payments", "reminders", return_id, "--as-of", "2025-04-12")
+    assert code == 0 and [(r["title"][:7], r["stage"]) for r in reminders] == [("2025 Q1", "three_days")]
+    code, payment = run("estimated-payments", "add", return_id, "--quarter", "Q1", "--amount", "2400",
+                        "--paid-on", "2025-04-10")
+    assert code == 0 and payment["tax_year"] == 2025
+    code, reminders = run("estimated-payments", "reminders", return_id, "--as-of", "2025-04-12")
+    assert reminders == []
+    code, payments = run("estimated-payments", "list", return_id)
+    assert [p["id"] for p in payments] == [payment["id"]]
+    code, error = run("estimated-payments", "add", return_id, "--quarter", "Q1", "--amount", "0",
+                      "--paid-on", "2025-04-10")
+    assert code == cli.EXIT_ERROR and "positive" in error
+    code, result = run("estimated-payments", "delete", return_id, payment["id"])
+    assert result["deleted"] is True
+    code, error = run("estimated-payments", "delete", return_id, payment["id"])
+    assert code == cli.EXIT_ERROR and "Estimated payment not found" in error
+
+
+def test_dashboard(data_dir):
+    code, error = run("dashboard")
+    assert code == cli.EXIT_ERROR and "Practitioner mode is off" in error
+
+    SettingsStore().update_settings({"practitioner_mode": True})
+    client = ClientStore().create_client("Smith Household")
+    ReturnStore().create_return(client_id=client["id"])
+    code, dashboard = run("dashboard", "--as-of", "2025-04-10")
+    assert code == 0
+    assert dashboard["clients"][0]["next_deadline"]["due_date"] == "2025-04-15"
+    assert dashboard["upcoming_deadlines"][0]["client_name"] == "Smith Household"
+    code, error = run("dashboard", "--as-of", "April")
+    assert code == cli.EXIT_ERROR
+
+
+def test_withholding_checkup(data_dir):
+    return_id = make_return()
+    code, result = run("withholding-checkup", return_id, "--ytd-wages", "42000", "--ytd-withholding", "3500",
+                       "--periods-remaining", "12")
+    assert code == 0
+    assert result["projected_wages"] == 78000
+    assert result["recommended_adjustment_per_paycheck"] > 0
+    # The stored return keeps its own wages
+    assert ReturnStore().get_return(return_id)["income_sources"][0]["amount"] == 60000
+    code, error = run("withholding-checkup", return_id, "--ytd-wages", "1000", "--ytd-withholding", "0",
+                      "--periods-remaining", "30")
+    assert code == cli.EXIT_ERROR and "remaining" in error
+
+
+def test_foreign_accounts(data_dir):
+    return_id = make_return()
+    code, account = run("foreign-accounts", "add", return_id, "--institution", "Maple Bank", "--country", "Canada",
+                        "--max-balance", "14200", "--year-end-balance", "9800")
+    assert code == 0
+    code, result = run("foreign-accounts", "check", return_id)
+    assert code == 0
+    assert result["fbar"]["required"] is True and result["form_8938"]["required"] is False
+    code, accounts = run("foreign-accounts", "list", return_id)
+    assert [a["id"] for a in accounts] == [account["id"]]
+    code, error = run("foreign-accounts", "delete", return_id, "fa_missing")
+    assert code == cli.EXIT_ERROR and "not found" in error
+
+
+
+def test_schedule_b(data_dir):
+    return_id = make_return()
+    code, payer = run("schedule-b", "add", return_id, "--type", "interest", "--payer", "First Bank", "--amount", "1850")
+    assert code == 0 and payer["description"] == "First Bank"
+    code, muni = run("schedule-b", "add", return_id, "--type", "interest", "--payer", "City Bonds", "--amount", "400",
+                     "--tax-exempt")
+    assert code == 0 and muni["tax_exempt"] is True
+    code, schedule = run("schedule-b", "show", return_id)
+    assert schedule["required"] is True
+    assert [line["payer"] for line in schedule["interest"]] == ["First Bank"]
+    assert schedule["tax_exempt_interest"] == 400
+
+    code, updated = run("schedule-b", "update", return_id, payer["id"], "--amount", "1200")
+    assert code == 0 and updated["amount"] == 1200
+    code, schedule = run("schedule-b", "show", return_id)
+    assert schedule["required"] is False
+
+    code, payers = run("schedule-b", "list", return_id)
+    assert [p["id"] for p in payers] == [payer["id"], muni["id"]]
+    code, _ = run("schedule-b", "delete", return_id, muni["id"])
+    assert code == 0
+    # Wages aren't a Schedule B payer
+    wages_id = ReturnStore().get_return(return_id)["income_sources"][0]["id"]
+    code, error = run("schedule-b", "delete", return_id, wages_id)
+    assert code == cli.EXIT_ERROR and "Payer not found" in error
+    code, error = run("schedule-b", "update", return_id, payer["id"], "--qualified", "100")
+    assert code == cli.EXIT_ERROR and "Only dividend income" in error
+
+
+def test_early_distribution(data_dir):
+    return_id = make_return()
+    source = ReturnStore().add_income_source(return_id, "retirement", 15000, description="Broker IRA")
+    code, claimed = run("early-distribution", "claim", return_id, source["id"], "--code", "1", "--plan-type", "ira",
+                        "--exception", "09")
+    assert code == 0 and claimed["exception_code"] == "09"
+    code, form = run("early-distribution", "form", return_id)
+    assert form["lines"]["2"] == 15000 and form["lines"]["4"] == 0
+
+    code, result = run("early-distribution", "evaluate", return_id, source["id"], "--first-home-used", "4000")
+    assert code == 0 and result["status"] == "partial" and result["additional_tax"] == 900
+    code, error = run("early-distribution", "claim", return_id, source["id"])
+    assert code == cli.EXIT_ERROR and "Give the code" in error
+    wages_id = ReturnStore().get_return(return_id)["income_sources"][0]["id"]
+    code, error = run("early-distribution", "evaluate", return_id, wages_id)
+    assert code == cli.EXIT_ERROR and "not found" in error
+
+
+def test_state_refund(data_dir):
+    store = ReturnStore()
+    prior_id = store.create_return(tax_year=2024)["return_id"]
+    store.add_income_source(prior_id, "wages", 90000)
+    store.add_deduction(prior_id, "state_local_tax", 6000)
+    store.add_deduction(prior_id, "mortgage_interest", 11000)
+    return_id = store.clone_return(prior_id, 2025)["return_id"]
+
+    code, refund = run("state-refund", "add", return_id, "--payer", "State of Ohio", "--amount", "900")
+    assert code == 0 and refund["type"] == "state_refund"
+    code, worksheet = run("state-refund", "show", return_id)
+    assert worksheet["status"] == "needs_prior_year"
+    store.save_calculation(prior_id, calculate_return(store.get_return(prior_id)))
+    code, figures = run("state-refund", "load", return_id)
+    assert code == 0 and figures["itemized_deductions"] == 17000
+    code, worksheet = run("state-refund", "show", return_id)
+    assert worksheet["status"] == "taxable" and worksheet["taxable_amount"] == 900
+
+    code, error = run("state-refund", "load", make_return())
+    assert code == cli.EXIT_ERROR and "No 2023 return" in error
+
+
+def test_rentals(data_dir):
+    return_id = make_return()
+    code, rental = run("rentals", "add", return_id, "--address", "12 Elm St", "--building-basis", "275000",
+                       "--in-service", "2020-01-15", "--rents", "24000", "--expenses", "8000")
+    assert code == 0
+    code, rentals = run("rentals", "list", return_id)
+    assert rentals[0]["current_year"]["depreciation"] == 10000
+    code, schedule = run("rentals", "schedule", return_id, rental["id"])
+    assert schedule[0]["months"] == 11.5 and len(schedule) == 28
+    code, result = run("rentals", "sell", return_id, rental["id"], "--date", "2024-06-10", "--price", "300000")
+    assert code == 0 and result["sale"]["unrecaptured_section_1250_gain"] == 44166.67
+    code, error = run("rentals", "exchange", return_id, rental["id"], "--date", "2024-07-01", "--address", "8 Pine Rd",
+                      "--value", "500000")
+    assert code == cli.EXIT_ERROR and "already sold" in error
+    code, error = run("rentals", "schedule", return_id, "rent_missing")
+    assert code == cli.EXIT_ERROR and "not found" in error
+
+
+def test_at_risk(data_dir):
+    return_id = make_return()
+    business = ReturnStore().add_business(return_id, "Studio", gross_receipts=1000)
+    code, record = run("at-risk", "set", return_id, business["id"], "--amount", "500")
+    assert code == 0 and record["at_risk_amount"] == 500
+    code, activities = run("at-risk", "list", return_id)
+    assert activities[0]["allowed"] == 1000 and activities[0]["ending_at_risk"] == 1500
+    code, error = run("at-risk", "set", return_id, "biz_missing", "--amount", "1")
+    assert code == cli.EXIT_ERROR and "not found" in error
+
+
+def test_crypto(data_dir):
+    return_id = make_return()
+    code, receipt = run("crypto", "receive", return_id, "--kind", "airdrop", "--asset", "UNI", "--quantity", "400",
+                        "--value", "2000", "--date", "2024-04-01")
+    assert code == 0 and receipt["income_source"]["amount"] == 2000
+    code, sales = run("crypto", "sell", return_id, "--asset", "UNI", "--quantity", "100", "--proceeds", "700",
+                      "--date", "2024-09-01")
+    assert code == 0 and sales[0]["gain_or_loss"] == 200
+    code, lots = run("crypto", "lots", return_id)
+    assert lots[0]["remaining"] == 300
+    code, error = run("crypto", "sell", return_id, "--asset", "UNI", "--quantity", "1000", "--proceeds", "1",
+                      "--date", "2024-09-02")
+    assert code == cli.EXIT_ERROR and "held" in error
+
+
+def test_harvest_losses(data_dir):
+    return_id = make_return()
+    code, holding = run("holdings", "add", return_id, "--symbol", "VTI", "--quantity", "40", "--basis", "10800",
+                        "--acquired", "2023-03-14", "--value", "9400")
+    assert code == 0
+    code, holding = run("holdings", "value", return_id, holding["id"], "9800")
+    assert holding["market_value"] == 9800
+    code, result = run("harvest-losses", return_id, "--as-of", "2024-12-02")
+    assert code == 0
+    assert result["candidates"][0]["unrealized_loss"] == 1000 and result["projected_tax_savings"] > 0
+    code, error = run("harvest-losses", return_id, "--as-of", "2025-01-05")
+    assert code == cli.EXIT_ERROR and "2024" in error
+
+
+def test_mileage(data_dir):
+    return_id = make_return()
+    code, trip = run("mileage", "add", return_id, "--category", "medical", "--miles", "100", "--parking-tolls", "9")
+    assert code == 0 and trip["parking_tolls"] == 9
+    code, result = run("mileage", "list", return_id)
+    assert result["deductions"][0]["amount"] == 30
+    code, result = run("mileage", "delete", return_id, trip["id"])
+    assert result["deleted"] is True
+    code, error = run("mileage", "delete", return_id, trip["id"])
+    assert code == cli.EXIT_ERROR and "Trip not found" in error
+
+
+def test_dependents(data_dir):
+    return_id = make_return()
+    code, dependent = run("dependents", "add", return_id, "--name", "Ruth Doe", "--relationship", "mother",
+                          "--gross-income", "4200")
+    assert code == 0 and dependent["gross_income"] == 4200
+    code, result = run("dependents", "test", return_id)
+    assert result["dependents"][0]["qualifying_relative"]["missing"] == ["support"]
+    code, dependent = run("dependents", "update", return_id, dependent["id"], "--support-percent", "70",
+                          "--no-has-ssn")
+    assert dependent["has_ssn"] is False
+    code, result = run("dependents", "test", return_id)
+    assert result["dependents"][0]["benefits"] == ["other_dependent_credit", "head_of_household"]
+    code, error = run("dependents", "update", return_id, "dep_missing", "--disabled")
+    assert code == cli.EXIT_ERROR and "Dependent not found" in error
+
+
+def test_divorce(data_dir):
+    return_id = make_return()
+    code, dependent = run("dependents", "add", return_id, "--name", "Kid", "--relationship", "son",
+                          "--birth-date", "2015-03-01", "--months", "12", "--custodial-parent", "taxpayer")
+    assert code == 0 and dependent["custodial_parent"] == "taxpayer"
+    code, divorce = run("divorce", "set", return_id, "--decree-date", "2024-09-30", "--paid-over-half-home-cost")
+    assert code == 0 and divorce["paid_over_half_home_cost"] is True
+    code, record = run("divorce", "alimony", return_id, "--direction", "received", "--amount", "6000",
+                       "--instrument-date", "2017-04-01")
+    assert record["direction"] == "received"
+    code, report = run("divorce", "report", return_id)
+    assert report["filing_status"]["allowed"] == ["single", "head_of_household"]
+    assert report["recommended_filing_status"] == "head_of_household"
+    assert report["alimony"]["taxable_received"] == 6000
+    code, record = run("divorce", "alimony", return_id, "--direction", "paid", "--amount", "2000")
+    assert code == 0 and record["instrument_date"] == "2024-09-30"
+    code, dependent = run("dependents", "update", return_id, dependent["id"], "--form-8332-year", "2024")
+    assert dependent["form_8332_years"] == [2024]
+    code, error = run("divorce", "set", return_id, "--decree-date", "30/09/2024")
+    assert code == cli.EXIT_ERROR and "Invalid decree_date" in error
+
+
+def test_decedent(data_dir):
+    return_id = make_return()
+    code, death = run("decedent", "set", return_id, "--deceased", "taxpayer", "--date-of-death", "2024-05-01",
+                      "--personal-representative", "Lee Doe")
+    assert code == 0 and death["personal_representative"] == "Lee Doe"
+    code, options = run("decedent", "options", return_id)
+    assert options["allowed"] == ["single"]
+    assert "Lee Doe signs as the personal representative" in options["requirements"]
+    code, error = run("decedent", "set", return_id, "--deceased", "spouse", "--date-of-death", "2024-05-01")
+    assert code == cli.EXIT_ERROR and "no spouse" in error
+
+
+def test_injured_spouse(data_dir):
+    store = ReturnStore()
+    tax_return = store.create_return(filing_status="married_joint", taxpayer={"name": "Pat Doe", "spouse_name": "Sam Doe"})
+    return_id = tax_return["return_id"]
+    store.add_income_source(return_id, "wages", 60000, withholding=4000)
+    store.add_income_source(return_id, "wages", 30000, withholding=5000, owner="spouse")
+    code, result = run("injured-spouse", return_id, "--past-due-debt", "2500")
+    assert code == 0 and result["injured_spouse"] == "spouse"
+    assert result["injured_spouse_refund"] > 0
+    assert result["offset"]["offset_with_form_8379"] < result["offset"]["offset_without_form_8379"]
+    code, error = run("injured-spouse", make_return())
+    assert code == cli.EXIT_ERROR and "married filing jointly" in error
+
+
+def test_ip_pin(data_dir):
+    return_id = make_return()
+    code, pin = run("ip-pin", "set", return_id, "--pin", "482913")
+    assert code == 0 and pin == {**pin, "pin": "****13", "valid_year": 2025}
+    code, pins = run("ip-pin", "show", return_id, "--reveal")
+    assert pins["taxpayer"]["pin"] == "482913"
+    code, error = run("ip-pin", "set", return_id, "--person", "spouse", "--pin", "482913")
+    assert code == cli.EXIT_ERROR and "no spouse" in error
+    code, cleared = run("ip-pin", "clear", return_id)
+    assert cleared["cleared"] is True
+    code, error = run("ip-pin", "clear", return_id)
+    assert code == cli.EXIT_ERROR and "No IP PIN" in error
+
+
+def test_peer_comparison(data_dir, tmp_path):
+    store = ReturnStore()
+    return_id = make_return()
+    store.add_income_source(return_id, "wages", 80000)
+    store.add_deduction(return_id, "mortgage_interest", 21000)
+    dataset = tmp_path / "soi.json"
+    dataset.write_text(json.dumps({"source": "Test table", "bands": [
+        {"label": "All returns", "min": None, "max": None, "averages": {"interest": 7000, "total": 14000}},
+    ]}))
+    code, result = run("peer-comparison", return_id, "--dataset", str(dataset))
+    assert code == 0 and result["flagged"] == ["interest"]
+    code, error = run("peer-comparison", return_id, "--dataset", str(tmp_path / "missing.json"))
+    assert code == cli.EXIT_ERROR and "Can't read SOI dataset" in error
+
+
+def test_students_and_education_credits(data_dir, tmp_path):
+    return_id = make_return()
+    code, student = run("students", "add", return_id, "--name", "Sam Doe")
+    assert code == 0 and student["half_time"] is True
+    form = tmp_path / "1098t.txt"
+    form.write_text("1 Payments received for qualified tuition and related expenses 3,000.00\n")
+    code, result = run("students", "import-1098t", return_id, student["id"], str(form))
+    assert result["student"]["expenses"][0]["amount"] == 3000
+    code, expense = run("students", "expense", return_id, student["id"], "--kind", "course_materials", "--amount", "500")
+    assert code == 0
+    code, credits = run("education-credits", return_id)
+    assert credits["aotc"] == 2375 and credits["refundable"] == 950
+    code, error = run("students", "expense", return_id, "stu_missing", "--kind", "fees", "--amount", "5")
+    assert code == cli.EXIT_ERROR and "Student not found" in error
+
+
+def test_adoptions(data_dir):
+    return_id = make_return()
+    code, adoption = run("adoptions", "add", return_id, "--name", "Mia Doe", "--expenses", "12500",
+                         "--final-year", "2024")
+    assert code == 0 and adoption["final_year"] == 2024
+    code, credit = run("adoptions", "credit", return_id)
+    # Limited to the $5,216 of tax; the rest carries forward
+    assert credit["credit"] == 5216 and credit["carryforward"] == 7284
+    code, updated = run("adoptions", "update", return_id, adoption["id"], "--special-needs")
+    assert updated["special_needs"] is True
+    code, adoptions = run("adoptions", "list", return_id)
+    assert [a["id"] for a in adoptions] == [adoption["id"]]
+    code, error = run("adoptions", "delete", return_id, "adopt_missing")
+    assert code == cli.EXIT_ERROR and "Adoption not found" in error
+
+
+def test_charitable_bunching(data_dir):
+    return_id = make_return()
+    code, result = run("charitable-bunching", return_id, "--annual-donation", "6000", "--years", "2")
+    assert code == 0
+    assert [row["donation"] for row in result["bunching"]["rows"]] == [12000, 0]
+    assert result["tax_saved"] == 0
+    code, error = run("charitable-bunching", return_id)
+    assert code == cli.EXIT_ERROR and "no charitable deductions" in error
+
+
+def test_relocation(data_dir):
+    return_id = make_return()
+    code, result = run("relocation", return_id, "--to-state", "TX", "--from-state", "IL")
+    assert code == 0
+    assert result["current"]["state_income_tax"] == 2970
+    assert result["annual_difference"] == -2970
+    code, error = run("relocation", return_id, "--to-state", "TX")
+    assert code == cli.EXIT_ERROR and "no resident state" in error
+
+
+def test_raise(data_dir):
+    return_id = make_return()
+    code, result = run("raise", return_id, "--amount", "10000", "--state", "IL")
+    # Part of the raise moves from the 12% to the 22% bracket
+    assert code == 0 and result["tax_changes"]["federal"] == 2025
+    assert result["after_tax_raise"] == 6715
+    code, result = run("raise", return_id, "--new-salary", "70000")
+    assert code == 0 and result["raise"] == 10000 and result["state"] is None
+    code, error = run("raise", return_id, "--amount", "500", "--owner", "spouse")
+    assert code == cli.EXIT_ERROR and "joint return" in error
+
+
+def test_residency(data_dir):
+    return_id = make_return()
+    code, entry = run("residency", "add", return_id, "--state", "NY", "--start", "2024-01-01", "--end", "2024-07-01")
+    assert code == 0
+    code, result = run("residency", "count", return_id, "--home-state", "NJ")
+    assert code == 0
+    assert result["states"][0] == {
+        "state": "NJ", "days": 183, "threshold": 183, "days_until_threshold": 0, "home_state": True, "status": "over",
+    }
+    assert any(w.startswith("Dual residency risk") for w in result["warnings"])
+
+    code, _ = run("residency", "delete", return_id, entry["id"])
+    assert code == 0
+    code, error = run("residency", "delete", return_id, entry["id"])
+    assert code == cli.EXIT_ERROR and "not found" in error
+
+
+def test_document_requests(data_dir):
+    store = ReturnStore()
+    prior = store.create_return(tax_year=2023)
+    DocumentStore().upload_document("1099-div.pdf", content=b"div", document_type="1099-DIV", return_id=prior["return_id"])
+    return_id = store.clone_return(prior["return_id"], 2024)["return_id"]
+    store.add_income_source(return_id, "wages", 60000, "Acme Corp")
+    DocumentStore().upload_document("w2.pdf", content=b"w2", document_type="W-2", return_id=return_id)
+
+    code, result = run("document-requests", return_id)
+    assert code == 0
+    assert result["received"] == 1
+    assert result["outstanding"] == ["1099-DIV (like last year's 1099-div.pdf)"]
+    assert len(store.get_return(return_id)["checklist"]["items"]) == 2
+    code, error = run("document-requests", return_id, "--prior-return", "ret_missing")
+    assert code == cli.EXIT_ERROR and "Return not found" in error
+
+
+def test_backup(data_dir):
+    make_return()
+    code, result = run("backup", "--dest", "backups")
+    assert code == 0
+    with zipfile.ZipFile(result["path"]) as archive:
+        names = archive.namelist()
+    assert "manifest.json" in names
+    assert any(name.startswith(".tax_returns/ret_") for name in names)
+    assert ".tax_returns/.field_encryption.key" not in names
+
+    code, verified = run("verify-backup", result["path"])
+    assert code == 0 and verified["valid"] is True and verified["passphrase"] == "no_lock"
+    with zipfile.ZipFile(result["path"], "a") as archive:
+        archive.writestr("stray.txt", "x")
+    code, error = run("verify-backup", result["path"])
+    assert code == cli.EXIT_ERROR and "stray.txt: Not listed in the manifest" in error
+    code, error = run("verify-backup", "missing.zip")
+    assert code == cli.EXIT_ERROR and "Not a backup archive" in error
+
+
+def test_locked_app_needs_passphrase(data_dir, monkeypatch):
+    return_id = make_return()
+    AppLock().set_passphrase("correct horse")
+    code, error = run("calculate", return_id)
+    assert code == cli.EXIT_LOCKED and "locked" in error
+    assert run("--passphrase", "wrong horse", "calculate", return_id)[0] == cli.EXIT_LOCKED
+    monkeypatch.setenv(cli.PASSPHRASE_ENV_VAR, "correct horse")
+    assert run("calculate", return_id)[0] == 0
+
+
+def test_lock_settings_upgrade(data_dir, monkeypatch):
+    code, error = run("lock-settings", "show")
+    assert code == cli.EXIT_ERROR and "No passphrase" in error
+    AppLock().set_passphrase("correct horse")
+    monkeypatch.setenv(cli.PASSPHRASE_ENV_VAR, "correct horse")
+    code, settings = run("lock-settings", "show")
+    assert code == 0 and settings["algorithm"] == "sha256" and settings["upgrade_recommended"] is False
+
+    code, error = run("lock-settings", "upgrade", "--iterations", "1000")
+    assert code == cli.EXIT_ERROR and "must be between" in error
+    code, settings = run("lock-settings", "upgrade", "--algorithm", "sha512")
+    assert code == 0 and (settings["algorithm"], settings["iterations"]) == ("sha512", 210_000)
+    assert run("calculate", make_return())[0] == 0
+    assert run("security-events", "--action", "passphrase_change")[1][0]["outcome"] == "success"
+
+
+def test_sensitive_commands_are_audited(data_dir, monkeypatch):
+    return_id = make_return()
+    run("ip-pin", "set", return_id, "--pin", "482913")
+    assert run("ip-pin", "show", return_id, "--reveal")[0] == 0
+    assert run("ip-pin", "show", return_id)[0] == 0
+    assert run("ip-pin", "clear", return_id, "--person", "spouse")[0] == cli.EXIT_ERROR
+    AppLock().set_passphrase("correct horse")
+    assert run("--passphrase", "wrong horse", "export-data", "--output", "data.json")[0] == cli.EXIT_LOCKED
+
+    monkeypatch.setenv(cli.PASSPHRASE_ENV_VAR, "correct horse")
+    code, events = run("security-events")
+    assert code == 0
+    assert [(e["action"], e["target"], e["outcome"]) for e in events] == [
+        ("export", "export-data", "denied"),
+        ("deletion", "ip-pin clear " + return_id, "failed"),
+        ("reveal", "ip-pin show " + return_id, "success"),
+    ]
+    assert all(e["source"] == "cli" and "482913" not in json.dumps(e) for e in events)
+    assert len(run("security-events", "--action", "reveal")[1]) == 1
+    assert len(run("security-events", "--outcome", "failed", "--limit", "5")[1]) == 1
+
+
+def test_settings_history(data_dir):
+    SettingsStore().update_settings({"default_state": "NJ"}, changed_by="onboarding")
+    SettingsStore().update_settings({"default_state": "NY"})
+    code, history = run("settings-history", "--field", "default_state", "--limit", "1")
+    assert code == 0 and len(history) == 1
+    assert history[0]["changed_by"] == "settings"
+    assert history[0]["changes"] == {"default_state": {"from": "NJ", "to": "NY"}}
+    code, error = run("settings-history", "--field", "theme")
+    assert code == cli.EXIT_ERROR and "Unknown setting" in error
+
+
+def test_scenarios(data_dir):
+    code, report = run("scenarios")
+    assert code == 0 and report["passed"] == len(report["scenarios"]) > 0
+
+    (data_dir / "wrong.json").write_text(json.dumps({
+        "name": "Wrong", "source": "n/a",
+        "return": {"income_sources": [{"type": "wages", "amount": 75000}]}, "expected": {"total_tax": 1},
+    }))
+    code, error = run("scenarios", "--dir", str(data_dir))
+    assert code == cli.EXIT_ERROR and "wrong.json" in error
+
+
+def test_demo_mode_leaves_data_folder_alone(data_dir):
+    code, calculation = run("--demo", "calculate", "demo")
+    assert code == 0 and calculation["filing_status"] == "married_joint"
+    code, result = run("--demo", "calculate", "demo", "--add-income", "self_employment=12000")
+    assert code == 0 and result["total_tax_change"] > 0
+    assert run("--demo", "returns")[1][0]["return_id"].startswith("ret_")
+    assert list(data_dir.iterdir()) == []
+    assert run("--demo", "--data-dir", str(data_dir), "returns")[0] == cli.EXIT_ERROR
+
+    code, seeded = run("seed-demo")
+    assert code == 0 and seeded["counts"]["dependents"] == 1
+    assert [r["return_id"] for r in ReturnStore().list_returns()] == [seeded["return_id"]]
+
+
+def test_export_and_import_data(data_dir):
+    return_id = make_return()
+    code, result = run("export-data", "--output", "data.json")
+    assert code == 0 and result["counts"]["returns"] == 1
+
+    code, report = run("import-data", "data.json", "--on-conflict", "keep_both")
+    assert code == 0 and report["returns"]["renamed"] == 1
+    assert len(ReturnStore().list_returns()) == 2
+    assert report["id_map"][return_id] != return_id
+    (data_dir / "bad.json").write_text("{}")
+    assert run("import-data", "bad.json")[0] == cli.EXIT_ERROR
+
+
+def test_diagnostics(data_dir):
+    make_return()
+    code, result = run("diagnostics", "--output", "diag.json")
+    assert code == 0 and result["counts"]["returns"] == 1
+    report = json.loads((data_dir / "diag.json").read_text())
+    assert "Pat Doe" not in json.dumps(report)
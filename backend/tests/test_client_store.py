@@ -0,0 +1,49 @@
+"""Tests for practitioner client storage."""
+import pytest
+
+from app.utils.client_store import ClientStore
+
+
+@pytest.fixture
+def store(tmp_path):
+    return ClientStore(str(tmp_path / "clients.json"))
+
+
+def test_create_list_and_filter(store):
+    smith = store.create_client(" Smith Household ", email="pat@example.com", engagement_status="awaiting_documents")
+    store.create_client("acme llc")
+
+    assert smith["id"].startswith("cli_")
+    assert smith["name"] == "Smith Household"
+    assert store.get_client(smith["id"])["email"] == "pat@example.com"
+    assert [c["name"] for c in store.list_clients()] == ["acme llc", "Smith Household"]
+    assert [c["id"] for c in store.list_clients(engagement_status="awaiting_documents")] == [smith["id"]]
+    with pytest.raises(ValueError):
+        store.list_clients(engagement_status="happy")
+
+
+@pytest.mark.parametrize("kwargs", [
+    {"name": "  "},
+    {"name": "Smith", "engagement_status": "busy"},
+    {"name": "Smith", "email": "not-an-email"},
+])
+def test_create_rejects_invalid(store, kwargs):
+    with pytest.raises(ValueError):
+        store.create_client(**kwargs)
+    assert store.list_clients() == []
+
+
+def test_update_and_delete(store):
+    client = store.create_client("Smith")
+    updated = store.update_client(client["id"], engagement_status="in_review", phone=" 555-0100 ")
+    assert (updated["engagement_status"], updated["phone"]) == ("in_review", "555-0100")
+
+    with pytest.raises(ValueError):
+        store.update_client(client["id"], id="cli_other")
+    with pytest.raises(ValueError):
+        store.update_client(client["id"], name="")
+    with pytest.raises(KeyError):
+        store.update_client("cli_missing", name="X")
+
+    assert store.delete_client(client["id"]) is True
+    assert store.delete_client(client["id"]) is False
@@ -5,6 +5,34 @@ import shutil
 from pathlib import Path
 
 from app.utils.conversation_store import ConversationStore
+from app.utils.embeddings import EmbeddingError, EmbeddingProvider, normalize
+
+# Words the fake model treats as meaning the same thing, one vector axis per concept
+CONCEPTS = [
+    {"home", "office", "workspace", "house"},
+    {"deduct", "deduction", "write", "off"},
+    {"mortgage", "interest", "loan"},
+    {"estimated", "quarterly", "payments"},
+]
+
+
+class ConceptEmbedder(EmbeddingProvider):
+    """Embeds text by which concepts its words belong to, so paraphrases land together"""
+
+    def __init__(self, model="concepts-v1", fail=False):
+        self.model = model
+        self.fail = fail
+        self.calls = []
+
+    def embed(self, texts):
+        if self.fail:
+            raise EmbeddingError("Embedding request failed: connection refused")
+        self.calls.append(texts)
+        vectors = []
+        for text in texts:
+            words = set(text.lower().replace("?", "").replace(".", "").split())
+            vectors.append(normalize([float(len(words & concept)) for concept in CONCEPTS] + [0.01]))
+        return vectors
 
 
 @pytest.fixture
@@ -13,6 +41,12 @@ def store(tmp_path):
     return ConversationStore(storage_dir=str(tmp_path / "conversations"))
 
 
+@pytest.fixture
+def semantic_store(tmp_path):
+    """A conversation store with an embedding provider."""
+    return ConversationStore(storage_dir=str(tmp_path / "conversations"), embedder=ConceptEmbedder())
+
+
 def test_save_and_retrieve_message(store):
     store.save_message("session-1", "user", "Hello")
     messages = store.get_messages("session-1")
@@ -66,3 +100,100 @@ def test_metadata_saved(store):
     store.save_message("s1", "user", "hello", metadata={"source": "api"})
     messages = store.get_messages("s1")
     assert messages[0]["metadata"]["source"] == "api"
+
+
+def test_messages_store_embedding(semantic_store, store):
+    semantic_store.save_message("s1", "assistant", "Home office deductions need exclusive use.")
+    embedding = semantic_store.get_messages("s1")[0]["embedding"]
+    assert embedding["model"] == "concepts-v1"
+    assert len(embedding["vector"]) == len(CONCEPTS) + 1
+
+    # Without a provider, or when it's down, the message is saved without one
+    store.save_message("s2", "assistant", "Mortgage interest is deductible.")
+    semantic_store.embedder.fail = True
+    semantic_store.save_message("s3", "assistant", "Mortgage interest is deductible.")
+    assert "embedding" not in store.get_messages("s2")[0]
+    assert "embedding" not in semantic_store.get_messages("s3")[0]
+
+
+def test_semantic_search_matches_paraphrases(semantic_store):
+    semantic_store.save_message("s1", "user", "Can I deduct my home office?")
+    semantic_store.save_message("s1", "assistant", "A home office deduction requires regular and exclusive use.")
+    semantic_store.save_message("s2", "user", "When is the next one due?")
+    semantic_store.save_message("s2", "assistant", "Quarterly estimated payments are due in April and June.")
+
+    # No words in common with the answer, but the same meaning
+    results = semantic_store.search_messages("can i write off my workspace at my house")
+    assert [r["session_id"] for r in results] == ["s1", "s2"]
+    assert results[0]["question"] == "Can I deduct my home office?"
+    assert results[0]["match"] == "semantic"
+    assert results[0]["score"] > 0.9 > results[1]["score"]
+    assert semantic_store.embedder.calls[-1] == ["can i write off my workspace at my house"]
+
+
+def test_semantic_search_embeds_messages_saved_without_one(store):
+    store.save_message("s1", "assistant", "Mortgage interest is deductible on Schedule A.")
+    store.embedder = ConceptEmbedder()
+    results = store.search_messages("loan interest")
+    assert results[0]["match"] == "semantic"
+    assert store.embedder.calls[-1] == ["loan interest", "Mortgage interest is deductible on Schedule A."]
+
+    # A model change re-embeds instead of comparing vectors from different models
+    store.save_message("s1", "assistant", "Quarterly estimated payments are due in April.")
+    store.embedder = ConceptEmbedder(model="concepts-v2")
+    store.search_messages("loan interest")
+    assert len(store.embedder.calls[-1]) == 3
+
+
+def test_semantic_search_falls_back_to_keywords(semantic_store):
+    semantic_store.save_message("s1", "assistant", "Mortgage interest is deductible on Schedule A.")
+    semantic_store.embedder.fail = True
+    results = semantic_store.search_messages("mortgage interest")
+    assert len(results) == 1 and results[0]["match"] == "keyword"
+
+
+def test_search_finds_relevant_answer(store):
+    store.save_message("s1", "user", "Can I deduct my home office?")
+    store.save_message("s1", "assistant", "A home office deduction requires regular and exclusive business use.")
+    store.save_message("s2", "user", "When are estimated payments due?")
+    store.save_message("s2", "assistant", "Quarterly estimated payments are due in April, June, September, and January.")
+
+    results = store.search_messages("what did the AI say about my home office")
+    assert results[0]["session_id"] == "s1"
+    assert results[0]["question"] == "Can I deduct my home office?"
+    assert results[0]["match"] == "keyword"
+    assert all(r["role"] == "assistant" for r in results)
+
+
+def test_search_matches_word_variants(store):
+    store.save_message("s1", "assistant", "Charitable contributions are deductible if you itemize.")
+    results = store.search_messages("deducting charity contribution")
+    assert len(results) == 1
+
+
+def test_search_since_filter(store, semantic_store):
+    for s in (store, semantic_store):
+        s.save_message("s1", "assistant", "Mileage rate for business driving is 67 cents.")
+        assert s.search_messages("mileage rate", since="2999-01-01") == []
+
+
+def test_search_unrelated_query_empty(store):
+    store.save_message("s1", "assistant", "Mortgage interest is deductible on Schedule A.")
+    assert store.search_messages("zebra") == []
+
+
+def test_reindex_embeddings(store):
+    store.save_message("s1", "user", "Can I deduct my home office?")
+    store.save_message("s2", "assistant", "Mortgage interest is deductible on Schedule A.")
+    with pytest.raises(ValueError, match="No embedding provider"):
+        store.reindex_embeddings()
+
+    store.embedder = ConceptEmbedder()
+    events = []
+    assert store.reindex_embeddings(progress=events.append) == {"conversations": 2, "messages": 2}
+    assert store.get_messages("s2")[0]["embedding"]["model"] == "concepts-v1"
+    assert events[-1]["percent"] == 100
+
+    store.embedder.fail = True
+    with pytest.raises(ValueError, match="connection refused"):
+        store.reindex_embeddings()
@@ -0,0 +1,67 @@
+"""Tests for crypto reward income and first-in, first-out basis lots."""
+from decimal import Decimal
+
+import pytest
+
+from app.tax_engine.crypto import classify_receipt, dispose_lots, holding_term
+
+
+def test_rewards_are_income_at_fair_market_value():
+    income, lot = classify_receipt("staking", " eth ", Decimal("0.35"), Decimal("1120.504"), "2024-06-30")
+    assert income == {
+        "type": "other",
+        "description": "Staking rewards: 0.35 ETH",
+        "amount": 1120.5,
+        "date": "2024-06-30",
+        "crypto_kind": "staking",
+    }
+    assert lot["asset"] == "ETH" and lot["cost_basis"] == 1120.5 and lot["remaining"] == 0.35
+
+
+def test_business_mining_is_self_employment():
+    income, _ = classify_receipt("mining", "BTC", Decimal("0.01"), Decimal("650"), "2024-02-01", "biz_1")
+    assert income["type"] == "self_employment" and income["business_id"] == "biz_1"
+    with pytest.raises(ValueError, match="Only mining"):
+        classify_receipt("airdrop", "UNI", Decimal("400"), Decimal("2000"), "2024-02-01", "biz_1")
+
+
+def test_purchase_creates_basis_without_income():
+    income, lot = classify_receipt("purchase", "BTC", Decimal("0.1"), Decimal("4200"), "2023-05-01")
+    assert income is None and lot["cost_basis"] == 4200.0
+
+
+def test_invalid_receipts():
+    with pytest.raises(ValueError, match="Invalid crypto receipt"):
+        classify_receipt("gift", "BTC", Decimal("1"), Decimal("1"), "2024-01-01")
+    with pytest.raises(ValueError, match="Quantity"):
+        classify_receipt("staking", "BTC", Decimal("0"), Decimal("1"), "2024-01-01")
+
+
+def test_dispose_first_in_first_out():
+    lots = [
+        {"id": "lot_b", "asset": "ETH", "quantity": 1.0, "remaining": 1.0, "cost_basis": 3000.0, "date_acquired": "2024-03-01"},
+        {"id": "lot_a", "asset": "ETH", "quantity": 2.0, "remaining": 2.0, "cost_basis": 3000.0, "date_acquired": "2023-01-15"},
+    ]
+    transactions = dispose_lots(lots, "eth", Decimal("2.5"), Decimal("10000"), "2024-06-01")
+
+    assert [t["crypto_lot_id"] for t in transactions] == ["lot_a", "lot_b"]
+    oldest, newest = transactions
+    assert (oldest["quantity"], oldest["proceeds"], oldest["cost_basis"], oldest["term"]) == (2.0, 8000.0, 3000.0, "long")
+    assert (newest["quantity"], newest["proceeds"], newest["cost_basis"], newest["term"]) == (0.5, 2000.0, 1500.0, "short")
+    assert newest["gain_or_loss"] == 500.0
+    assert [lot["remaining"] for lot in lots] == [0.5, 0.0]
+
+    with pytest.raises(ValueError, match="Only 0.5 ETH held"):
+        dispose_lots(lots, "ETH", Decimal("1"), Decimal("4000"), "2024-06-02")
+
+
+def test_lots_acquired_after_the_sale_are_not_used():
+    lots = [{"asset": "BTC", "quantity": 1.0, "remaining": 1.0, "cost_basis": 100.0, "date_acquired": "2024-09-01"}]
+    with pytest.raises(ValueError, match="held on 2024-08-01"):
+        dispose_lots(lots, "BTC", Decimal("1"), Decimal("100"), "2024-08-01")
+
+
+def test_holding_term():
+    assert holding_term("2023-06-01", "2024-06-01") == "short"
+    assert holding_term("2023-06-01", "2024-06-02") == "long"
+    assert holding_term("2024-02-29", "2025-03-01") == "long"
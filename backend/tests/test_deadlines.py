@@ -0,0 +1,58 @@
+"""Tests for filing deadlines and reminders."""
+from datetime import date
+
+from app.tax_engine.deadlines import next_business_day, return_deadlines, upcoming_deadlines
+
+
+def make_return(**fields):
+    return {"return_id": "ret_0000000000000001", "tax_year": 2024, "status": "in_progress", **fields}
+
+
+def by_key(deadlines):
+    return {(d["jurisdiction"], d["kind"]): d for d in deadlines}
+
+
+def test_weekends_and_emancipation_day():
+    # April 15, 2023 was a Saturday and Emancipation Day was observed Monday the 17th
+    assert next_business_day(date(2023, 4, 15)) == date(2023, 4, 18)
+    assert next_business_day(date(2025, 4, 15)) == date(2025, 4, 15)
+    assert next_business_day(date(2028, 4, 15)) == date(2028, 4, 18)
+
+
+def test_federal_and_state_deadlines():
+    deadlines = by_key(return_deadlines(make_return(state_returns=[
+        {"state": "VA", "residency": "resident"},
+        {"state": "TX", "residency": "nonresident"},
+    ])))
+    assert deadlines[("federal", "filing")]["due_date"] == "2025-04-15"
+    assert deadlines[("federal", "payment")]["due_date"] == "2025-04-15"
+    assert deadlines[("VA", "filing")]["due_date"] == "2025-05-01"
+    assert ("TX", "filing") not in deadlines
+
+
+def test_extension_moves_filing_not_payment():
+    tax_return = make_return(
+        extension={"filed_on": "2025-04-10", "states": ["CA"]},
+        state_returns=[{"state": "CA", "residency": "resident"}, {"state": "VA", "residency": "nonresident"}],
+    )
+    deadlines = by_key(return_deadlines(tax_return))
+    assert deadlines[("federal", "filing")]["due_date"] == "2025-10-15"
+    assert deadlines[("federal", "filing")]["extended"] is True
+    assert deadlines[("federal", "payment")]["due_date"] == "2025-04-15"
+    assert deadlines[("CA", "filing")]["due_date"] == "2025-10-15"
+    assert deadlines[("VA", "filing")]["due_date"] == "2025-05-01"
+
+
+def test_upcoming_reminders():
+    owing = make_return(refund_or_owed=-500.0)
+    refund = make_return(return_id="ret_0000000000000002", refund_or_owed=800.0)
+    filed = make_return(return_id="ret_0000000000000003", status="filed")
+
+    reminders = upcoming_deadlines([owing, refund, filed], as_of=date(2025, 4, 1), within_days=30)
+    assert [(r["return_id"][-1], r["kind"]) for r in reminders] == [("1", "filing"), ("1", "payment"), ("2", "filing")]
+    assert reminders[0]["days_remaining"] == 14
+    assert "in 14 days" in reminders[0]["message"]
+
+    assert upcoming_deadlines([owing], as_of=date(2025, 3, 1), within_days=30) == []
+    overdue = upcoming_deadlines([refund], as_of=date(2025, 4, 20))
+    assert overdue[0]["overdue"] is True and overdue[0]["days_remaining"] == -5
@@ -0,0 +1,118 @@
+"""Tests for final returns and surviving spouse filing status."""
+from decimal import Decimal
+
+import pytest
+
+from app.tax_engine.decedent import decedent_filing_options, next_year_filing_status
+from app.tax_engine.return_calculation import calculate_return
+from app.tax_engine.tax_calculator import TaxCalculator
+from app.tax_engine.validation import validate_return
+
+
+def son(**fields):
+    return {"id": "dep_1", "name": "Kid", "relationship": "son", "birth_date": "2015-03-01",
+            "months_lived_with": 12, **fields}
+
+
+def make_return(death, tax_year=2024, filing_status="married_joint", **fields):
+    return {
+        "return_id": "ret_0000000000000000",
+        "tax_year": tax_year,
+        "filing_status": filing_status,
+        "taxpayer": {"name": "Pat Doe", "spouse_name": "Sam Doe"},
+        "income_sources": [{"id": "inc_1", "type": "wages", "amount": 80000, "withholding": 8000}],
+        "deductions": [],
+        "dependents": [son()],
+        "death": death,
+        **fields,
+    }
+
+
+def test_year_of_death_files_jointly_as_surviving_spouse():
+    options = decedent_filing_options(make_return({"deceased": "spouse", "date_of_death": "2024-08-12"}))
+    assert options["allowed"] == ["married_joint", "married_separate"]
+    assert options["deceased_name"] == "Sam Doe"
+    assert any("Filing as surviving spouse" in r for r in options["requirements"])
+
+
+def test_remarried_in_year_of_death_files_separately():
+    death = {"deceased": "taxpayer", "date_of_death": "2024-02-01", "remarried_date": "2024-11-20",
+             "personal_representative": "Sam Doe"}
+    options = decedent_filing_options(make_return(death, filing_status="married_separate"))
+    assert options["allowed"] == ["married_separate"]
+    assert "Sam Doe signs as the personal representative" in options["requirements"]
+    assert any("Form 1310" in r for r in options["requirements"])
+
+
+def test_single_decedent_final_return():
+    tax_return = make_return({"deceased": "taxpayer", "date_of_death": "2024-05-01"}, filing_status="single",
+                             taxpayer={"name": "Pat Doe"}, dependents=[])
+    options = decedent_filing_options(tax_return)
+    assert options["allowed"] == ["single"]
+    assert any("Form 1310" in r for r in options["requirements"])
+
+
+@pytest.mark.parametrize("date_of_death, fields, allowed", [
+    ("2023-08-12", {}, ["qualifying_surviving_spouse", "single", "head_of_household"]),
+    ("2022-08-12", {}, ["qualifying_surviving_spouse", "single", "head_of_household"]),
+    ("2021-08-12", {}, ["single", "head_of_household"]),
+    ("2023-08-12", {"dependents": [son(months_lived_with=9)]}, ["single", "head_of_household"]),
+    ("2023-08-12", {"dependents": [son(relationship="foster child")]}, ["single", "head_of_household"]),
+])
+def test_qualifying_surviving_spouse_years(date_of_death, fields, allowed):
+    death = {"deceased": "spouse", "date_of_death": date_of_death, "paid_over_half_home_cost": True}
+    tax_return = make_return(death, filing_status="qualifying_surviving_spouse", **fields)
+    assert decedent_filing_options(tax_return)["allowed"] == allowed
+
+
+def test_remarried_survivor_is_married():
+    death = {"deceased": "spouse", "date_of_death": "2023-03-01", "remarried_date": "2024-06-01"}
+    options = decedent_filing_options(make_return(death))
+    assert options["allowed"] == ["married_joint", "married_separate"]
+
+
+def test_options_errors():
+    with pytest.raises(ValueError):
+        decedent_filing_options(make_return(None))
+    with pytest.raises(ValueError):
+        decedent_filing_options(make_return({"deceased": "taxpayer", "date_of_death": "2023-05-01"}))
+
+
+@pytest.mark.parametrize("tax_year, fields, expected", [
+    (2025, {}, "qualifying_surviving_spouse"),
+    (2026, {}, "qualifying_surviving_spouse"),
+    (2025, {"dependents": []}, "single"),
+    (2027, {"filing_status": "qualifying_surviving_spouse"}, "single"),
+    (2027, {"filing_status": "head_of_household"}, None),
+])
+def test_next_year_filing_status(tax_year, fields, expected):
+    tax_return = make_return({"deceased": "spouse", "date_of_death": "2024-08-12"}, **fields)
+    assert next_year_filing_status(tax_return, tax_year) == expected
+
+
+def test_surviving_spouse_uses_joint_rates():
+    calculator = TaxCalculator()
+    surviving = calculator.calculate_individual_tax(Decimal("80000"), "qualifying_surviving_spouse")
+    assert surviving["deduction_amount"] == 29200
+    joint = calculator.calculate_individual_tax(Decimal("80000"), "married_joint")
+    assert surviving["tax_liability"] == joint["tax_liability"]
+    death = {"deceased": "spouse", "date_of_death": "2023-08-12"}
+    calculation = calculate_return(make_return(death, filing_status="qualifying_surviving_spouse"))
+    assert calculation["income_tax"] == 5632
+
+
+@pytest.mark.parametrize("death, filing_status, code", [
+    ({"deceased": "taxpayer", "date_of_death": "2023-05-01"}, "single", "return_after_death"),
+    ({"deceased": "spouse", "date_of_death": "2023-05-01"}, "married_joint", "joint_after_death"),
+    ({"deceased": "spouse", "date_of_death": "2021-05-01"}, "qualifying_surviving_spouse",
+     "surviving_spouse_ineligible"),
+    (None, "qualifying_surviving_spouse", "surviving_spouse_ineligible"),
+])
+def test_validation(death, filing_status, code):
+    findings = validate_return(make_return(death, filing_status=filing_status))
+    assert code in {f["code"] for f in findings}
+
+
+def test_validation_allows_year_of_death_joint_return():
+    findings = validate_return(make_return({"deceased": "spouse", "date_of_death": "2024-08-12"}))
+    assert not [f for f in findings if f["severity"] == "error"]
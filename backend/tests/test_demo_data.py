@@ -0,0 +1,23 @@
+"""Tests for the demo data seeder."""
+from app.services.demo_data import seed_demo_data
+from app.tax_engine.return_calculation import calculate_return
+from app.utils.return_store import ReturnStore
+
+
+def test_demo_return_calculates(tmp_path):
+    store = ReturnStore(storage_dir=str(tmp_path / "returns"))
+    demo = seed_demo_data(store)
+    tax_return = store.get_return(demo["return_id"])
+    assert tax_return["filing_status"] == "married_joint"
+    assert demo["counts"] == {
+        "income_sources": 3, "businesses": 1, "business_expenses": 3, "deductions": 3, "dependents": 1,
+    }
+
+    calculation = calculate_return(tax_return)
+    # Wages and interest plus the studio's $16,170 net profit
+    assert calculation["total_income"] == 126090.0
+    assert calculation["self_employment_tax"] > 0
+    assert calculation["total_withholding"] == 9300.0
+
+    assert seed_demo_data(store, tax_year=2025)["return_id"] != demo["return_id"]
+    assert len(store.list_returns()) == 2
@@ -0,0 +1,106 @@
+"""Tests for the qualifying child and qualifying relative tests."""
+import pytest
+
+from app.tax_engine.dependents import normalize_relationship, qualify_dependent, qualify_dependents
+
+
+def dependent(relationship, **fields):
+    return {"id": "dep_1", "name": "Pat", "relationship": relationship, **fields}
+
+
+def test_young_child_qualifies_for_everything():
+    result = qualify_dependent(dependent("Son", birth_date="2015-03-01", months_lived_with=12), 2024)
+    assert result["age"] == 9
+    assert result["qualifying_child"]["qualifies"] is True
+    assert result["qualifying_relative"]["qualifies"] is False
+    assert result["benefits"] == ["child_tax_credit", "earned_income_credit", "head_of_household"]
+
+
+def test_older_child_gets_other_dependent_credit():
+    result = qualify_dependent(dependent("daughter", birth_date="2006-06-01", months_lived_with=12), 2024)
+    assert result["age"] == 18
+    assert result["benefits"] == ["other_dependent_credit", "earned_income_credit", "head_of_household"]
+
+
+@pytest.mark.parametrize("fields, qualifies", [
+    ({"full_time_student": True}, True),
+    ({}, False),
+    ({"disabled": True}, True),
+])
+def test_age_test_for_students_and_disabled(fields, qualifies):
+    result = qualify_dependent(dependent("son", birth_date="2002-01-15", months_lived_with=10, **fields), 2024)
+    assert result["qualifying_child"]["qualifies"] is qualifies
+
+
+def test_child_who_supports_themself_still_counts_for_eic():
+    result = qualify_dependent(
+        dependent("nephew", birth_date="2007-01-01", months_lived_with=12, self_support_percent=60), 2024
+    )
+    assert result["qualifying_child"]["tests"]["support"] == "fail"
+    assert result["is_dependent"] is False
+    assert result["benefits"] == ["earned_income_credit"]
+
+
+def test_child_without_ssn_gets_other_dependent_credit():
+    result = qualify_dependent(
+        dependent("son", birth_date="2020-01-01", months_lived_with=12, has_ssn=False), 2024
+    )
+    assert "child_tax_credit" not in result["benefits"]
+    assert "other_dependent_credit" in result["benefits"]
+    assert "social security number" in result["reasons"][0]
+
+
+def test_baby_born_during_the_year_passes_residency():
+    result = qualify_dependent(dependent("daughter", birth_date="2024-11-20", months_lived_with=1), 2024)
+    assert result["qualifying_child"]["tests"]["residency"] == "pass"
+
+
+def test_parent_is_qualifying_relative_without_living_together():
+    result = qualify_dependent(
+        dependent("Mother", gross_income=4200, support_percent=70, months_lived_with=0), 2024
+    )
+    assert result["qualifying_relative"]["qualifies"] is True
+    assert result["benefits"] == ["other_dependent_credit", "head_of_household"]
+
+
+def test_unrelated_household_member_needs_the_whole_year():
+    fields = {"gross_income": 0, "support_percent": 90}
+    all_year = qualify_dependent(dependent("friend", months_lived_with=12, **fields), 2024)
+    assert all_year["qualifying_relative"]["qualifies"] is True
+    # A household member who isn't a relative can't make the taxpayer head of household
+    assert all_year["benefits"] == ["other_dependent_credit"]
+    part_year = qualify_dependent(dependent("friend", months_lived_with=11, **fields), 2024)
+    assert part_year["is_dependent"] is False
+
+
+def test_failed_and_missing_tests_are_reported():
+    result = qualify_dependent(
+        dependent("daughter", birth_date="1995-01-01", months_lived_with=12, gross_income=9000), 2024
+    )
+    assert result["is_dependent"] is False
+    assert result["qualifying_relative"]["missing"] == ["support"]
+    assert result["reasons"] == [
+        "Not a qualifying child: fails the age test",
+        "Not a qualifying relative: fails the gross income test",
+    ]
+
+
+def test_normalize_relationship():
+    assert normalize_relationship("Son-in-law") == "son in law"
+    assert normalize_relationship(None) == ""
+
+
+def test_qualify_dependents_notes():
+    tax_return = {
+        "tax_year": 2024,
+        "filing_status": "head_of_household",
+        "dependents": [dependent("cousin")],
+    }
+    result = qualify_dependents(tax_return)
+    assert result["dependents"][0]["is_dependent"] is False
+    assert result["notes"] == [
+        "Some tests are unknown; add the missing facts to the dependent records",
+        "No dependent is a qualifying person for head of household",
+    ]
+    with pytest.raises(ValueError):
+        qualify_dependents({**tax_return, "tax_year": 2019})
@@ -0,0 +1,59 @@
+"""Tests for diagnostic reports and log scrubbing."""
+import json
+
+import pytest
+
+from app.services.diagnostics import generate_diagnostics
+from app.utils.conversation_store import ConversationStore
+from app.utils.document_store import DocumentStore
+from app.utils.reminder_store import ReminderStore
+from app.utils.return_store import ReturnStore
+from app.utils.scrubbing import scrub
+from app.utils.settings_store import SettingsStore
+
+
+@pytest.mark.parametrize("text, secret", [
+    ("Parsed W-2 for SSN 123-45-6789", "123-45-6789"),
+    ("Employer EIN 12-3456789", "12-3456789"),
+    ("Direct deposit to account 000123456789", "000123456789"),
+    ("Calling API with sk-ant-api03-abcDEF_123", "sk-ant-api03-abcDEF_123"),
+    ('Unlock failed: {"passphrase": "correct horse"}', "correct"),
+    ("Taxpayer: Jane Doe uploaded a 1099", "Jane Doe"),
+    ("Key loaded: " + "k" * 43 + "=", "k" * 43),
+    ("Sent to pat@example.com", "pat@example.com"),
+])
+def test_scrub_masks_sensitive_values(text, secret):
+    assert secret not in scrub(text)
+
+
+def test_scrub_keeps_amounts_and_ids():
+    text = "Return ret_0123456789abcdef: refund $1234.56 for 2024"
+    assert scrub(text) == text
+
+
+def test_report_has_counts_and_no_pii(tmp_path):
+    returns = ReturnStore(storage_dir=str(tmp_path / "returns"))
+    tax_return = returns.create_return(taxpayer={"name": "Pat Doe", "ssn": "123-45-6789"})
+    returns.set_direct_deposit(tax_return["return_id"], "011000015", "123456789")
+    documents = DocumentStore(storage_dir=str(tmp_path / "documents"))
+    documents.upload_document("w2.pdf", content=b"w2")
+    settings = SettingsStore(settings_path=str(tmp_path / "settings.json"))
+    settings.update_settings({"inbox_folder": str(tmp_path / "inbox")})
+
+    log_entries = [
+        {"at": "2025-01-02T03:04:05", "level": "ERROR", "logger": "main", "message": "Parse failed for 123-45-6789"},
+    ]
+
+    report = generate_diagnostics(
+        "1.0.0", returns, documents, ConversationStore(storage_dir=str(tmp_path / "conversations")),
+        settings, ReminderStore(state_path=str(tmp_path / "reminders.json")), log_entries=log_entries,
+    )
+    assert report["counts"]["returns"] == 1
+    assert report["counts"]["returns_by_status"] == {"draft": 1}
+    assert report["counts"]["documents"] == 1
+    assert report["settings"]["inbox_folder_set"] is True
+    assert report["recent_logs"][0]["message"] == "Parse failed for [SSN]"
+
+    dumped = json.dumps(report)
+    for secret in ("123-45-6789", "123456789", "Pat Doe", str(tmp_path)):
+        assert secret not in dumped
@@ -0,0 +1,118 @@
+"""Tests for divorce-year filing status, Form 8332 allocation, and alimony."""
+import pytest
+
+from app.tax_engine.alimony import alimony_is_taxable
+from app.tax_engine.divorce import divorce_year_report, filing_status_options
+from app.tax_engine.return_calculation import calculate_return
+
+
+def child(**fields):
+    return {"id": "dep_1", "name": "Kid", "relationship": "daughter", "birth_date": "2012-03-01",
+            "months_lived_with": 10, "custodial_parent": "taxpayer", **fields}
+
+
+def make_return(divorce, filing_status="married_separate", **fields):
+    return {
+        "return_id": "ret_0000000000000000",
+        "tax_year": 2024,
+        "filing_status": filing_status,
+        "taxpayer": {"name": "Pat Doe"},
+        "income_sources": [{"id": "inc_1", "type": "wages", "amount": 60000, "withholding": 7000}],
+        "deductions": [],
+        "businesses": [],
+        "dependents": [child()],
+        "divorce": divorce,
+        **fields,
+    }
+
+
+@pytest.mark.parametrize("divorce, allowed", [
+    ({"decree_date": "2024-11-01", "paid_over_half_home_cost": True}, ["single", "head_of_household"]),
+    ({"decree_date": "2025-02-01", "separated_date": "2024-03-01", "paid_over_half_home_cost": True},
+     ["married_joint", "married_separate", "head_of_household"]),
+    ({"separated_date": "2024-08-15", "paid_over_half_home_cost": True}, ["married_joint", "married_separate"]),
+    ({"decree_date": "2024-11-01"}, ["single"]),
+])
+def test_allowed_filing_statuses(divorce, allowed):
+    assert filing_status_options(make_return(divorce))["allowed"] == allowed
+
+
+def test_considered_unmarried_needs_a_child():
+    tax_return = make_return(
+        {"separated_date": "2024-03-01", "paid_over_half_home_cost": True},
+        dependents=[{"id": "dep_1", "name": "Ruth", "relationship": "mother", "gross_income": 0, "support_percent": 80}],
+    )
+    options = filing_status_options(tax_return)
+    assert options["allowed"] == ["married_joint", "married_separate"]
+    # An unmarried taxpayer can be head of household for a parent
+    tax_return["divorce"]["decree_date"] = "2024-06-01"
+    assert filing_status_options(tax_return)["allowed"] == ["single", "head_of_household"]
+
+
+def test_pending_decree_is_married():
+    options = filing_status_options(make_return({"decree_date": "2025-02-01"}))
+    assert options["marital_status"] == "married"
+    assert options["notes"] == ["The decree isn't final until 2025-02-01, so this year is filed as married"]
+
+
+def test_report_compares_taxpayer_options():
+    report = divorce_year_report(make_return({"separated_date": "2024-04-30", "paid_over_half_home_cost": True}))
+    options = {row["filing_status"]: row for row in report["options"]}
+    assert options["married_joint"]["calculated"] is False
+    assert options["married_separate"]["total_tax"] == 3216
+    assert options["head_of_household"]["total_tax"] == 2241
+    assert report["recommended_filing_status"] == "head_of_household"
+    assert "Married filing jointly needs the spouse's income, which isn't on this return" in report["notes"]
+
+
+@pytest.mark.parametrize("separated_date, recommended", [("2024-08-15", "married_joint"), ("2024-03-01", "head_of_household")])
+def test_joint_return_compared_with_both_spouses_separate(separated_date, recommended):
+    tax_return = make_return(
+        {"separated_date": separated_date, "paid_over_half_home_cost": True},
+        filing_status="married_joint",
+    )
+    tax_return["income_sources"].append({"id": "inc_2", "type": "wages", "amount": 20000, "withholding": 1000,
+                                         "owner": "spouse"})
+    report = divorce_year_report(tax_return)
+    joint = report["options"][0]
+    assert (joint["total_tax"], joint["spouse_separate_tax"]) == (3632, 540)
+    assert report["recommended_filing_status"] == recommended
+
+
+def test_form_8332_release_moves_the_child_tax_credit():
+    divorce = {"decree_date": "2024-11-01", "paid_over_half_home_cost": True}
+    released = make_return(divorce, filing_status="head_of_household", dependents=[child(form_8332_years=[2024])])
+    report = divorce_year_report(released)
+    assert report["dependents"][0]["claimed_by"] == "other_parent"
+    assert report["dependents"][0]["taxpayer_benefits"] == ["earned_income_credit", "head_of_household"]
+    assert calculate_return(released)["credits"]["child_tax_credit"] == 0
+    assert "head_of_household" in report["filing_status"]["allowed"]
+
+    noncustodial = make_return(divorce, filing_status="single",
+                               dependents=[child(custodial_parent="other_parent", form_8332_years=[2024])])
+    allocation = divorce_year_report(noncustodial)["dependents"][0]
+    assert allocation["claimed_by"] == "taxpayer" and allocation["attach_form_8332"] is True
+    assert allocation["taxpayer_benefits"] == ["child_tax_credit"]
+    assert calculate_return(noncustodial)["credits"]["child_tax_credit"] == 2000
+    assert filing_status_options(noncustodial)["allowed"] == ["single"]
+
+
+@pytest.mark.parametrize("record, taxable", [
+    ({"instrument_date": "2018-12-31"}, True),
+    ({"instrument_date": "2019-01-01"}, False),
+    ({"instrument_date": "2017-05-01", "modified_to_adopt_new_rule": True}, False),
+])
+def test_alimony_by_instrument_date(record, taxable):
+    assert alimony_is_taxable(record) is taxable
+
+
+def test_alimony_in_the_calculation():
+    alimony = [
+        {"id": "alim_1", "direction": "paid", "amount": 12000, "instrument_date": "2018-06-01", "recipient_ssn": "1"},
+        {"id": "alim_2", "direction": "received", "amount": 5000, "instrument_date": "2016-01-10"},
+        {"id": "alim_3", "direction": "received", "amount": 9000, "instrument_date": "2020-01-10"},
+    ]
+    calculation = calculate_return(make_return({}, filing_status="single", dependents=[], alimony=alimony))
+    assert calculation["income"]["alimony"] == 5000
+    assert calculation["adjustments"] == 12000
+    assert calculation["agi"] == 53000
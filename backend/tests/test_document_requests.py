@@ -0,0 +1,63 @@
+"""Tests for building a return's document request list."""
+from app.documents.document_requests import document_requests, return_documents
+from app.utils.document_store import DocumentStore
+
+
+def document(document_id, form, issuer=None, return_id=None, **extra):
+    fields = {"payer_name": {"value": issuer}} if issuer else {}
+    return {
+        "document_id": document_id, "document_type": form, "filename": f"{document_id}.pdf",
+        "return_id": return_id, "uploaded_at": "2024-02-01T00:00:00",
+        "extraction": {"form": form, "fields": fields}, **extra,
+    }
+
+
+def test_requests_from_income_and_prior_documents():
+    tax_return = {"income_sources": [
+        {"type": "wages", "description": "Acme Corp"},
+        {"type": "interest", "description": ""},
+    ]}
+    prior = [
+        document("doc_1", "W-2", "ACME CORP."),
+        document("doc_2", "1099-DIV", "Vanguard"),
+        document("doc_3", "receipt"),
+        document("doc_4", "1099-INT", "Credit Union"),
+    ]
+    current = [document("doc_9", "W-2", "Acme Corp")]
+
+    items = {item["title"]: item for item in document_requests(tax_return, prior, current)}
+    # Last year's W-2 from the same employer isn't requested twice, and last
+    # year's 1099-INT names the payer for the unnamed interest source
+    assert set(items) == {"W-2 from Acme Corp", "1099-INT from Credit Union", "1099-DIV from Vanguard"}
+    assert items["1099-INT from Credit Union"]["reason"] == "Income source on the return"
+    assert items["W-2 from Acme Corp"]["received"] is True
+    assert items["W-2 from Acme Corp"]["document_id"] == "doc_9"
+    assert items["1099-DIV from Vanguard"]["reason"] == "Received last year"
+    assert items["1099-DIV from Vanguard"]["received"] is False
+
+
+def test_issuer_must_match_when_known():
+    prior = [document("doc_1", "1099-DIV", "Vanguard"), document("doc_2", "1099-DIV", "Fidelity")]
+    current = [document("doc_3", "1099-DIV", "Fidelity Investments"), document("doc_4", "1099-DIV")]
+    items = {item["title"]: item for item in document_requests({"income_sources": []}, prior, current)}
+    # doc_3's issuer doesn't normalize to "fidelity", so the unnamed doc_4 fulfils both
+    assert items["1099-DIV from Vanguard"]["document_id"] == "doc_4"
+    assert items["1099-DIV from Fidelity"]["document_id"] == "doc_4"
+    assert document_requests({"income_sources": []}, prior, current[:1])[0]["received"] is False
+
+
+def test_return_documents_uses_cloned_return_and_client(tmp_path):
+    store = DocumentStore(str(tmp_path / "docs"))
+    store.upload_document("w2.pdf", content=b"2023 w2", document_type="W-2", return_id="ret_prior")
+    store.upload_document("1099.pdf", content=b"2023 1099", document_type="1099-INT",
+                          client_id="cli_1", tax_year=2023)
+    store.upload_document("new.pdf", content=b"2024 w2", document_type="W-2", return_id="ret_this")
+    store.upload_document("other.pdf", content=b"other", document_type="W-2", return_id="ret_other")
+
+    tax_return = {"return_id": "ret_this", "tax_year": 2024, "cloned_from": "ret_prior", "client_id": "cli_1"}
+    prior, current = return_documents(store, tax_return)
+    assert sorted(r["filename"] for r in prior) == ["1099.pdf", "w2.pdf"]
+    assert [r["filename"] for r in current] == ["new.pdf"]
+
+    prior, _ = return_documents(store, {**tax_return, "client_id": None}, prior_return_id="ret_other")
+    assert [r["filename"] for r in prior] == ["other.pdf"]
@@ -0,0 +1,266 @@
+"""Tests for managed document storage."""
+import json
+from datetime import date, datetime
+
+import pytest
+
+from app.utils.document_store import DocumentStore, DuplicateDocumentError
+
+
+@pytest.fixture
+def store(tmp_path):
+    """Create a document store with a temp directory."""
+    return DocumentStore(storage_dir=str(tmp_path / "documents"))
+
+
+def test_upload_copies_into_store(store, tmp_path):
+    source = tmp_path / "w2.pdf"
+    source.write_bytes(b"%PDF-1.4 fake w2")
+
+    record = store.upload_document("w2.pdf", source_path=str(source), document_type="W-2")
+    source.unlink()  # the original can disappear without breaking the record
+
+    assert record["file_path"].startswith("blobs/")
+    assert record["mime_type"] == "application/pdf"
+    assert store.read_document(record["document_id"]) == b"%PDF-1.4 fake w2"
+
+
+def test_move_removes_source(store, tmp_path):
+    source = tmp_path / "receipt.jpg"
+    source.write_bytes(b"jpeg")
+    store.upload_document("receipt.jpg", source_path=str(source), move=True)
+    assert not source.exists()
+
+
+def test_content_addressed_blobs_are_shared(store):
+    first = store.upload_document("a.pdf", content=b"same bytes")
+    second = store.upload_document("b.pdf", content=b"same bytes", allow_duplicate=True)
+    assert first["file_path"] == second["file_path"]
+
+    # Blob survives until the last record referencing it is deleted
+    assert store.delete_document(first["document_id"])
+    assert store.read_document(second["document_id"]) == b"same bytes"
+    assert store.delete_document(second["document_id"])
+    assert not (store.storage_dir / second["file_path"]).exists()
+
+
+def test_duplicate_content_rejected(store):
+    first = store.upload_document("w2.pdf", content=b"w2 bytes")
+    with pytest.raises(DuplicateDocumentError) as excinfo:
+        store.upload_document("w2 (1).pdf", content=b"w2 bytes")
+    assert excinfo.value.existing["document_id"] == first["document_id"]
+    assert len(store.list_documents()) == 1
+
+
+def test_allowed_duplicate_is_flagged(store):
+    first = store.upload_document("w2.pdf", content=b"w2 bytes")
+    second = store.upload_document("copy.pdf", content=b"w2 bytes", allow_duplicate=True)
+    assert second["duplicate_of"] == first["document_id"]
+
+    groups = store.find_duplicates()
+    assert len(groups) == 1
+    assert [d["document_id"] for d in groups[0]] == [first["document_id"], second["document_id"]]
+
+
+def test_merge_duplicates(store):
+    first = store.upload_document("w2.pdf", content=b"w2 bytes")
+    second = store.upload_document("copy.pdf", content=b"w2 bytes", return_id="ret_0000000000000001",
+                                   allow_duplicate=True)
+
+    merged = store.merge_documents(first["document_id"], second["document_id"])
+    assert merged["aliases"] == ["copy.pdf"]
+    assert merged["return_id"] == "ret_0000000000000001"
+    assert store.get_document(second["document_id"]) is None
+    assert store.find_duplicates() == []
+    assert store.read_document(first["document_id"]) == b"w2 bytes"
+
+
+def test_merge_requires_same_content(store):
+    first = store.upload_document("a.pdf", content=b"a")
+    second = store.upload_document("b.pdf", content=b"b")
+    with pytest.raises(ValueError, match="identical content"):
+        store.merge_documents(first["document_id"], second["document_id"])
+
+
+def test_thumbnail_stored_alongside_document(store):
+    record = store.upload_document("w2.png", content=b"image bytes")
+    assert store.get_thumbnail(record["document_id"]) is None
+
+    updated = store.save_thumbnail(record["document_id"], b"\x89PNG thumb")
+    assert updated["thumbnail_path"].startswith("thumbnails/")
+    assert store.get_thumbnail(record["document_id"]) == b"\x89PNG thumb"
+
+    store.delete_document(record["document_id"])
+    assert not (store.storage_dir / updated["thumbnail_path"]).exists()
+
+
+def test_list_documents_by_return(store):
+    store.upload_document("a.pdf", content=b"a", return_id="ret_0000000000000001")
+    store.upload_document("b.pdf", content=b"b", return_id="ret_0000000000000002")
+    assert [d["filename"] for d in store.list_documents(return_id="ret_0000000000000001")] == ["a.pdf"]
+    assert len(store.list_documents()) == 2
+
+
+def test_search_extracted_fields_and_text(store):
+    w2 = store.upload_document("w2_acme.pdf", content=b"w2")
+    store.save_extraction(
+        w2["document_id"],
+        {"form": "W-2", "fields": {"employer_ein": {"value": "12-3456789", "confidence": 0.95}}},
+        text="Form W-2\nEmployer: Acme Widgets Inc\n12-3456789",
+    )
+    receipt = store.upload_document("scan.jpg", content=b"receipt")
+    store.save_extraction(
+        receipt["document_id"],
+        {"form": "receipt", "fields": {"vendor": {"value": "City Pharmacy", "confidence": 0.6}}},
+        text="City Pharmacy\n" + "Aspirin 4.99\n" * 20 + "Total 99.80",
+    )
+
+    results = store.list_documents(query="12-3456789")
+    assert [d["document_id"] for d in results] == [w2["document_id"]]
+    assert {m["field"] for m in results[0]["matches"]} == {"fields.employer_ein", "ocr_text"}
+
+    snippet = next(m for m in store.list_documents(query="total")[0]["matches"] if m["field"] == "ocr_text")["snippet"]
+    assert snippet.startswith("...") and snippet.endswith("Total 99.80")
+
+    assert store.list_documents(query="acme")[0]["matches"][0]["field"] == "filename"
+    assert store.list_documents(query="no such vendor") == []
+    assert "matches" not in store.get_document(w2["document_id"])
+
+
+def test_archived_documents_hidden_but_still_deduplicated(store):
+    document = store.upload_document("old.pdf", content=b"old")
+    store.set_archived(document["document_id"])
+    assert store.list_documents() == []
+    assert len(store.list_documents(include_archived=True)) == 1
+    with pytest.raises(DuplicateDocumentError):
+        store.upload_document("again.pdf", content=b"old")
+
+    store.set_archived(document["document_id"], False)
+    assert len(store.list_documents()) == 1
+
+
+def test_purge_exports_and_indexes_expired_documents(store, tmp_path):
+    old = store.upload_document("w2_2015.pdf", content=b"2015", tax_year=2015)
+    kept = store.upload_document("w2_2023.pdf", content=b"2023", tax_year=2023)
+
+    preview = store.purge_expired(retention_years=7, as_of=date(2024, 6, 1), dry_run=True)
+    assert preview["cutoff_tax_year"] == 2016
+    assert [e["document_id"] for e in preview["purged"]] == [old["document_id"]]
+    assert store.get_document(old["document_id"]) is not None
+
+    export_dir = tmp_path / "export"
+    result = store.purge_expired(retention_years=7, export_dir=str(export_dir), as_of=date(2024, 6, 1))
+    exported = export_dir / "2015" / f"{old['document_id']}_w2_2015.pdf"
+    assert result["purged"][0]["exported_to"] == str(exported)
+    assert exported.read_bytes() == b"2015"
+    assert store.get_document(old["document_id"]) is None
+    assert not (store.storage_dir / old["file_path"]).exists()
+    assert store.get_document(kept["document_id"]) is not None
+    assert [e["filename"] for e in store.list_purged()] == ["w2_2015.pdf"]
+
+
+def test_tax_year_defaults_to_year_before_upload(store):
+    document = store.upload_document("scan.pdf", content=b"scan")
+    assert DocumentStore.document_tax_year(document) == datetime.utcnow().year - 1
+
+
+def test_notes_and_annotations(store):
+    document = store.upload_document("w2_corrected.pdf", content=b"w2c")
+    store.set_notes(document["document_id"], "  Employer reissued this in March  ")
+    annotation = store.add_annotation(document["document_id"], "Corrected W-2, ignore the other one", page=1)
+    assert annotation["id"].startswith("ann_")
+
+    store.update_annotation(document["document_id"], annotation["id"], text="Corrected W-2 (W-2c)")
+    record = store.get_document(document["document_id"])
+    assert record["notes"] == "Employer reissued this in March"
+    assert record["annotations"][0]["text"] == "Corrected W-2 (W-2c)"
+    assert store.list_documents(query="w-2c")[0]["matches"][0]["field"] == "annotation"
+
+    with pytest.raises(ValueError):
+        store.add_annotation(document["document_id"], "   ")
+    with pytest.raises(ValueError):
+        store.update_annotation(document["document_id"], annotation["id"], created_at="never")
+
+    store.delete_annotation(document["document_id"], annotation["id"])
+    assert store.get_document(document["document_id"])["annotations"] == []
+    with pytest.raises(KeyError):
+        store.delete_annotation(document["document_id"], annotation["id"])
+
+
+def test_filename_is_reduced_to_base_name(store):
+    record = store.upload_document("../../etc/passwd", content=b"x")
+    assert record["filename"] == "passwd"
+
+
+def test_invalid_document_id_rejected(store):
+    assert store.get_document("../../etc/passwd") is None
+    assert store.delete_document("../secrets") is False
+    with pytest.raises(KeyError):
+        store.read_document("doc_0000000000000000")
+
+
+def write_legacy_record(store, document_id, file_path, **extra):
+    record = {"document_id": document_id, "document_type": "W-2", "filename": "w2.pdf",
+              "file_path": file_path, "uploaded_at": "2024-01-01T00:00:00", **extra}
+    (store.storage_dir / f"{document_id}.json").write_text(json.dumps(record))
+
+
+def test_relink_copies_legacy_external_files(store, tmp_path):
+    external = tmp_path / "Downloads" / "w2.pdf"
+    external.parent.mkdir()
+    external.write_bytes(b"legacy w2")
+    write_legacy_record(store, "doc_00000000000000aa", str(external))
+
+    report = store.relink_documents()
+    assert report["relinked"] == ["doc_00000000000000aa"]
+
+    record = store.get_document("doc_00000000000000aa")
+    assert record["file_path"].startswith("blobs/")
+    external.unlink()
+    assert store.read_document("doc_00000000000000aa") == b"legacy w2"
+    assert store.relink_documents()["ok"] == ["doc_00000000000000aa"]
+
+
+def test_external_paths_are_not_read_until_relinked(store, tmp_path):
+    external = tmp_path / "secret.txt"
+    external.write_bytes(b"not a tax document")
+    write_legacy_record(store, "doc_00000000000000dd", str(external))
+    write_legacy_record(store, "doc_00000000000000ee", "../secret.txt")
+    for document_id in ("doc_00000000000000dd", "doc_00000000000000ee"):
+        with pytest.raises(FileNotFoundError, match="outside the store"):
+            store.read_document(document_id)
+
+
+def test_import_record_only_points_at_its_own_blob(store, tmp_path):
+    victim = tmp_path / "victim.txt"
+    victim.write_bytes(b"keep me")
+    kept = store.upload_document("kept.pdf", content=b"kept")
+
+    record = store.import_record({
+        "document_id": "doc_00000000000000ab", "filename": "w2.pdf",
+        "content_hash": kept["content_hash"], "file_path": str(victim),
+    })
+    assert record["file_path"] == kept["file_path"]
+    assert store.read_document("doc_00000000000000ab") == b"kept"
+    store.delete_document("doc_00000000000000ab")
+    store.delete_document(kept["document_id"])
+    assert victim.read_bytes() == b"keep me"
+
+    for bad in ("../../victim", "A" * 64, None):
+        with pytest.raises(ValueError):
+            store.import_record({"document_id": "doc_00000000000000ac", "content_hash": bad, "file_path": str(victim)})
+    with pytest.raises(ValueError, match="isn't in this store"):
+        store.import_record({"document_id": "doc_00000000000000ac", "content_hash": "0" * 64})
+    assert store.get_document("doc_00000000000000ac") is None
+
+
+def test_relink_repairs_stale_path_and_flags_missing(store, tmp_path):
+    kept = store.upload_document("kept.pdf", content=b"kept")
+    write_legacy_record(store, "doc_00000000000000bb", "/nowhere/w2.pdf", content_hash=kept["content_hash"])
+    write_legacy_record(store, "doc_00000000000000cc", "/nowhere/gone.pdf")
+
+    report = store.relink_documents()
+    assert report["repaired"] == ["doc_00000000000000bb"]
+    assert report["missing"] == ["doc_00000000000000cc"]
+    assert store.get_document("doc_00000000000000cc")["missing"] is True
+    assert store.read_document("doc_00000000000000bb") == b"kept"
@@ -0,0 +1,69 @@
+"""Tests for the noncash donation valuation guide."""
+from decimal import Decimal
+
+import pytest
+
+from app.tax_engine.donation_valuation import build_donation_batch, valuation_guide
+from app.tax_engine.schedule_a import ScheduleACalculator
+
+
+def test_guide_lists_categories():
+    guide = valuation_guide()
+    assert {entry["category"] for entry in guide} == {"clothing", "furniture", "electronics"}
+    assert all(entry["low"] <= entry["high"] for entry in guide)
+    assert {entry["category"] for entry in valuation_guide("furniture")} == {"furniture"}
+    with pytest.raises(ValueError):
+        valuation_guide("jewelry")
+
+
+def test_batch_values_by_condition():
+    batch = build_donation_batch([
+        {"item": "coat", "quantity": 2},                       # 15 each
+        {"item": "jeans", "quantity": 3, "condition": "like_new"},  # 20 each
+        {"item": "sofa", "condition": "very_good"},            # midpoint of 50-300
+    ])
+    assert [item["amount"] for item in batch["items"]] == [30.0, 60.0, 175.0]
+    assert batch["category_totals"] == {"clothing": 90.0, "furniture": 175.0}
+    assert batch["total"] == 265.0
+    assert batch["form_8283_required"] is False
+    assert batch["warnings"] == []
+
+
+def test_batch_warns_over_form_8283_threshold():
+    batch = build_donation_batch([
+        {"item": "dining_set", "condition": "like_new"},
+        {"item": "laptop", "value": 450},
+        {"description": "Antique lamp", "value": 40},
+    ])
+    assert batch["total"] == 890.0
+    assert batch["form_8283_required"] is True
+    assert batch["appraisal_required"] is False
+    assert batch["items"][2]["category"] == "other"
+    assert any("above the guide" in w for w in batch["warnings"])
+    assert any("Form 8283 Section A" in w for w in batch["warnings"])
+
+
+def test_batch_appraisal_for_similar_items_over_5000():
+    batch = build_donation_batch([{"item": "laptop", "quantity": 20, "condition": "like_new"}])
+    assert batch["total"] == 8000.0
+    assert batch["appraisal_required"] is True
+
+
+@pytest.mark.parametrize("items", [
+    [],
+    [{"item": "spaceship"}],
+    [{"item": "coat", "condition": "worn"}],
+    [{"item": "coat", "quantity": 0}],
+    [{"description": "Lamp"}],
+])
+def test_batch_rejects_invalid_items(items):
+    with pytest.raises(ValueError):
+        build_donation_batch(items)
+
+
+def test_batch_deduction_feeds_schedule_a():
+    batch = build_donation_batch([{"item": "television", "quantity": 3, "condition": "like_new"}])
+    deductions = [{"category": "charitable", "amount": batch["total"], "non_cash": True, "items": batch["items"]}]
+    result = ScheduleACalculator().calculate(deductions, Decimal("80000"), "single")
+    assert result["lines"]["12"] == 900.0
+    assert "Noncash contributions over $500 require Form 8283" in result["notes"]
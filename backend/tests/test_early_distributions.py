@@ -0,0 +1,106 @@
+"""Tests for Form 5329 and early distribution exceptions."""
+from decimal import Decimal
+
+import pytest
+
+from app.tax_engine.early_distributions import calculate_form_5329, evaluate_exception
+from app.tax_engine.return_calculation import calculate_return
+
+
+def make_return(*distributions, deductions=()):
+    return {
+        "return_id": "ret_0000000000000000",
+        "tax_year": 2024,
+        "filing_status": "single",
+        "dependents": [],
+        "income_sources": [
+            {"type": "wages", "amount": 50000, "withholding": 5000},
+            *[{"id": f"inc_{index}", "type": "retirement", "withholding": 0, **fields}
+              for index, fields in enumerate(distributions)],
+        ],
+        "deductions": list(deductions),
+    }
+
+
+def test_additional_tax_on_early_distributions():
+    tax_return = make_return(
+        {"description": "Broker IRA", "amount": 12000, "distribution_code": "1",
+         "exception_code": "09", "exception_amount": 10000},
+        {"description": "SIMPLE IRA", "amount": 2000, "distribution_code": "S"},
+        {"description": "Pension", "amount": 8000, "distribution_code": "7"},
+    )
+    form = calculate_form_5329(tax_return)
+    assert form["required"] is True
+    assert form["lines"] == {"1": 14000.0, "2": 10000.0, "3": 4000.0, "4": 700.0}
+    assert form["exception_code"] == "09"
+    # 10% of the $2,000 the first home exception doesn't cover, 25% of the SIMPLE IRA distribution
+    assert [d["additional_tax"] for d in form["distributions"]] == [200.0, 500.0]
+
+    without = calculate_return(make_return({"amount": 22000, "distribution_code": "7"}))
+    result = calculate_return(tax_return)
+    assert result["early_distribution_tax"] == 700.0
+    assert result["total_tax"] == without["total_tax"] + 700
+
+
+def test_more_than_one_exception_is_99():
+    tax_return = make_return(
+        {"amount": 3000, "distribution_code": "1", "exception_code": "05"},
+        {"amount": 3000, "distribution_code": "1", "exception_code": "13"},
+    )
+    form = calculate_form_5329(tax_return)
+    assert form["exception_code"] == "99"
+    assert form["lines"]["4"] == 0.0
+
+
+def test_no_form_without_early_distributions():
+    form = calculate_form_5329(make_return({"amount": 5000, "distribution_code": "2"}))
+    assert form["required"] is False
+    assert calculate_return(make_return({"amount": 5000}))["early_distribution_tax"] == 0.0
+
+
+def test_exception_for_the_wrong_plan_type():
+    tax_return = make_return({"amount": 20000, "distribution_code": "1", "plan_type": "ira", "exception_code": "01"})
+    result = evaluate_exception(tax_return, "inc_0", Decimal("70000"), age_at_separation=56)
+    assert result["status"] == "does_not_apply"
+    assert result["additional_tax"] == 2000.0
+
+    tax_return["income_sources"][1]["plan_type"] = "employer_plan"
+    assert evaluate_exception(tax_return, "inc_0", Decimal("70000"), age_at_separation=56)["status"] == "applies"
+    too_young = evaluate_exception(tax_return, "inc_0", Decimal("70000"), age_at_separation=54)
+    assert too_young["status"] == "does_not_apply"
+    public_safety = evaluate_exception(
+        tax_return, "inc_0", Decimal("70000"), age_at_separation=52, public_safety_employee=True
+    )
+    assert public_safety["status"] == "applies"
+
+
+def test_exception_limits():
+    tax_return = make_return(
+        {"amount": 15000, "distribution_code": "1", "plan_type": "ira", "exception_code": "09"},
+        deductions=[{"category": "medical", "amount": 9000}],
+    )
+    first_home = evaluate_exception(tax_return, "inc_0", Decimal("65000"), first_home_used_before=Decimal("4000"))
+    assert first_home["status"] == "partial"
+    assert first_home["allowed"] == 6000.0
+    assert first_home["additional_tax"] == 900.0
+
+    # $9,000 of medical expenses less 7.5% of $65,000
+    medical = evaluate_exception(tax_return, "inc_0", Decimal("65000"), exception_code="05")
+    assert medical["allowed"] == 4125.0
+
+    unemployed = evaluate_exception(
+        tax_return, "inc_0", Decimal("65000"), exception_code="07",
+        weeks_unemployed=8, health_insurance_premiums=Decimal("6000"),
+    )
+    assert unemployed["status"] == "does_not_apply"
+    assert evaluate_exception(tax_return, "inc_0", Decimal("65000"), exception_code="08")["status"] == "needs_review"
+
+
+def test_evaluate_errors():
+    tax_return = make_return({"amount": 1000, "distribution_code": "1"})
+    with pytest.raises(ValueError, match="No exception"):
+        evaluate_exception(tax_return, "inc_0", Decimal("50000"))
+    with pytest.raises(ValueError, match="Unknown exception"):
+        evaluate_exception(tax_return, "inc_0", Decimal("50000"), exception_code="42")
+    with pytest.raises(KeyError):
+        evaluate_exception(tax_return, "inc_missing", Decimal("50000"), exception_code="03")
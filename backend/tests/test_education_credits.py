@@ -0,0 +1,118 @@
+"""Tests for the 1098-T parser and the education credits."""
+from decimal import Decimal
+
+import pytest
+
+from app.documents.bulk_import import infer_document_type
+from app.documents.parsers import parse_document
+from app.documents.parsers.form_1098_t import Form1098T, parse_1098_t
+from app.tax_engine.education_credits import calculate_education_credits
+from app.tax_engine.return_calculation import calculate_return
+
+FORM_1098_T = """FILER'S name, street address, city or town, state, ZIP
+State University
+FILER'S employer identification no. 12-3456789
+STUDENT'S name
+Sam Doe
+Form 1098-T Tuition Statement
+1 Payments received for qualified tuition and related expenses $6,500.00
+4 Adjustments made for a prior year 0.00
+5 Scholarships or grants 2,000.00
+7 Check if the amount in box 1 includes amounts for an academic period beginning January-March 2025 [ ]
+8 Check if at least half-time student [X]
+9 Check if graduate student [ ]
+10 Ins. contract reimb./refund 150.00
+"""
+
+
+def student(id, expenses, **fields):
+    return {"id": id, "name": id.title(), "expenses": expenses, **fields}
+
+
+STUDENTS = [
+    student("sam", [
+        {"kind": "tuition", "amount": 6500},
+        {"kind": "scholarship", "amount": 2000},
+        {"kind": "course_materials", "amount": 600},
+    ]),
+    student("alex", [
+        {"kind": "tuition", "amount": 12000},
+        {"kind": "course_materials", "amount": 300},
+    ], graduate=True),
+]
+
+
+# ── 1098-T ────────────────────────────────────────────────────
+
+def test_1098_t():
+    result = parse_1098_t(FORM_1098_T)
+    record = result.record
+    assert isinstance(record, Form1098T)
+    assert (record.filer_name, record.filer_tin, record.student_name) == ("State University", "12-3456789", "Sam Doe")
+    assert record.payments_received == Decimal("6500.00")
+    assert record.scholarships_grants == Decimal("2000.00")
+    assert record.reimbursements_refunds == Decimal("150.00")
+    assert record.half_time_student is True and record.graduate_student is False
+    assert result.warnings == []
+
+    assert parse_document("1098t", FORM_1098_T).form == "1098-T"
+    assert infer_document_type("2024_1098-T.pdf") == ("1098-T", "filename")
+    assert infer_document_type("scan.txt", FORM_1098_T.encode()) == ("1098-T", "content")
+    assert infer_document_type("1098.pdf") == ("1098", "filename")
+
+
+def test_1098_t_warnings():
+    result = parse_1098_t(
+        "7 Check if the amount in box 1 includes amounts for an academic period beginning January-March 2025 [X]\n"
+        "4 Adjustments made for a prior year 800.00"
+    )
+    assert len(result.warnings) == 3
+    assert "box 1" in result.warnings[0].lower()
+
+
+# ── Credits ───────────────────────────────────────────────────
+
+def test_aotc_and_llc_by_student():
+    result = calculate_education_credits(STUDENTS, Decimal("60000"), "single", 2024)
+    sam, alex = result["students"]
+    # $7,100 paid less the $2,000 scholarship, capped at $4,000
+    assert (sam["credit"], sam["qualified_expenses"], sam["tentative_credit"]) == ("aotc", 4000.0, 2500.0)
+    # Graduate students get the lifetime learning credit; books bought elsewhere don't count for it
+    assert (alex["credit"], alex["qualified_expenses"]) == ("llc", 12000.0)
+    assert alex["aotc_ineligible_reasons"] == ["graduate student"]
+    assert (result["aotc"], result["llc"]) == (2500.0, 2000.0)
+    assert (result["refundable"], result["nonrefundable"]) == (1000.0, 3500.0)
+    assert any("$10,000" in note for note in result["notes"])
+
+
+def test_phaseout_and_married_separate():
+    result = calculate_education_credits(STUDENTS, Decimal("85000"), "single", 2024)
+    assert (result["phaseout_percent"], result["total"]) == (50.0, 2250.0)
+    assert calculate_education_credits(STUDENTS, Decimal("170000"), "married_joint", 2024)["total"] == 2250.0
+    assert calculate_education_credits(STUDENTS, Decimal("50000"), "married_separate", 2024)["total"] == 0.0
+
+    with pytest.raises(ValueError, match="2023"):
+        calculate_education_credits(STUDENTS, Decimal("50000"), "single", 2023)
+
+
+def test_aotc_years_limit():
+    result = calculate_education_credits(
+        [student("sam", [{"kind": "tuition", "amount": 3000}], aotc_years_claimed=4)], Decimal("0"), "single", 2024
+    )
+    assert result["students"][0]["credit"] == "llc"
+    assert result["llc"] == 600.0
+
+
+def test_credits_in_return_calculation():
+    tax_return = {
+        "tax_year": 2024,
+        "filing_status": "single",
+        "income_sources": [{"type": "wages", "amount": 30000, "withholding": 1000}],
+        "students": STUDENTS[:1],
+    }
+    result = calculate_return(tax_return)
+    # $1,500 of the $2,500 offsets tax; the refundable $1,000 is paid like withholding
+    assert result["income_tax"] == 1616.0
+    assert result["education_credits"]["applied"] == 1500.0
+    assert result["total_tax"] == 116.0
+    assert result["refund_or_owed"] == 1884.0
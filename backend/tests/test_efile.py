@@ -0,0 +1,126 @@
+"""Tests for the MeF XML export."""
+import xml.etree.ElementTree as ET
+from datetime import datetime
+
+from app.efile.mef import EFILE_NAMESPACE, build_return_xml, name_control, validate_return_xml
+from app.tax_engine.return_calculation import calculate_return
+
+NS = {"efile": EFILE_NAMESPACE}
+
+
+def make_return(**taxpayer):
+    return {
+        "return_id": "ret_0000000000000000",
+        "tax_year": 2024,
+        "filing_status": "single",
+        "taxpayer": {
+            "name": "Pat O'Doe", "ssn": "123-45-6789", "address": "12 Main St",
+            "city": "Austin", "state": "tx", "zip": "78701", **taxpayer,
+        },
+        "dependents": [],
+        "income_sources": [
+            {"type": "wages", "description": "Acme Corp", "amount": 85000.4, "withholding": 12000,
+             "employer_ein": "12-3456789"},
+            {"type": "interest", "description": "Bank", "amount": 120.5, "withholding": 0},
+        ],
+        "deductions": [],
+    }
+
+
+def build(tax_return):
+    return build_return_xml(tax_return, calculate_return(tax_return), timestamp=datetime(2025, 2, 1, 9, 30))
+
+
+def test_build_valid_return():
+    xml = build(make_return())
+    assert validate_return_xml(xml) == []
+
+    root = ET.fromstring(xml)
+    form = root.find("efile:ReturnData/efile:IRS1040", NS)
+    assert form.find("efile:IndividualReturnFilingStatusCd", NS).text == "1"
+    assert form.find("efile:WagesAmt", NS).text == "85000"
+    assert form.find("efile:TaxableInterestAmt", NS).text == "121"
+    assert form.find("efile:OverpaidAmt", NS) is not None
+    assert root.find("efile:ReturnHeader/efile:Filer/efile:PrimarySSN", NS).text == "123456789"
+    assert root.find("efile:ReturnHeader/efile:Filer/efile:PrimaryNameControlTxt", NS).text == "ODOE"
+    assert root.find("efile:ReturnHeader/efile:ReturnTs", NS).text == "2025-02-01T09:30:00Z"
+    assert root.find("efile:ReturnData/efile:IRSW2/efile:EmployerEIN", NS).text == "123456789"
+
+
+
+def test_qualified_dividends():
+    tax_return = make_return()
+    tax_return["income_sources"].append(
+        {"type": "dividends", "description": "Fund", "amount": 900, "withholding": 0, "qualified_dividends": 600}
+    )
+    xml = build(tax_return)
+    assert validate_return_xml(xml) == []
+    form = ET.fromstring(xml).find("efile:ReturnData/efile:IRS1040", NS)
+    assert form.find("efile:QualifiedDividendsAmt", NS).text == "600"
+    assert form.find("efile:OrdinaryDividendsAmt", NS).text == "900"
+    # Omitted when there are none
+    form = ET.fromstring(build(make_return())).find("efile:ReturnData/efile:IRS1040", NS)
+    assert form.find("efile:QualifiedDividendsAmt", NS) is None
+
+
+def test_tax_exempt_interest():
+    tax_return = make_return()
+    tax_return["income_sources"][1]["tax_exempt_interest"] = 250
+    form = ET.fromstring(build(tax_return)).find("efile:ReturnData/efile:IRS1040", NS)
+    assert form.find("efile:TaxExemptInterestAmt", NS).text == "250"
+    assert form.find("efile:TaxableInterestAmt", NS).text == "121"
+
+def test_validation_reports_missing_and_malformed_fields():
+    tax_return = make_return(ssn="", zip="787")
+    tax_return["income_sources"][0].pop("employer_ein")
+    errors = validate_return_xml(build(tax_return))
+    assert "ReturnHeader/Filer/PrimarySSN is not a valid ssn: (empty)" in errors
+    assert "ReturnHeader/Filer/USAddress/ZIPCd is not a valid zip: 787" in errors
+    assert "ReturnData/IRSW2[1]/EmployerEIN is not a valid ein: (empty)" in errors
+
+
+def test_validation_of_foreign_documents():
+    assert validate_return_xml(b"<Return>")[0].startswith("Not well-formed")
+    assert "namespace" in validate_return_xml(b"<Return/>")[0]
+    errors = validate_return_xml(f'<Return xmlns="{EFILE_NAMESPACE}"/>'.encode())
+    assert "ReturnHeader/TaxYr is required" in errors
+
+
+def test_name_control():
+    assert name_control("Mary Smith-Jones") == "SMIT"
+    assert name_control("Li") == "LI"
+    assert name_control("") == ""
+
+
+def test_refund_direct_deposit():
+    tax_return = make_return()
+    deposit = {"routing_number": "021000021", "account_number": "000123456789", "account_type": "savings"}
+    xml = build_return_xml(tax_return, calculate_return(tax_return), direct_deposit=deposit)
+    assert validate_return_xml(xml) == []
+
+    form = ET.fromstring(xml).find("efile:ReturnData/efile:IRS1040", NS)
+    assert form.find("efile:RefundAmt", NS).text == form.find("efile:OverpaidAmt", NS).text
+    assert form.find("efile:RoutingTransitNum", NS).text == "021000021"
+    assert form.find("efile:BankAccountTypeCd", NS).text == "2"
+    assert form.find("efile:DepositorAccountNum", NS).text == "000123456789"
+
+    # No refund, nothing to deposit
+    tax_return["income_sources"][0]["withholding"] = 0
+    xml = build_return_xml(tax_return, calculate_return(tax_return), direct_deposit=deposit)
+    assert ET.fromstring(xml).find("efile:ReturnData/efile:IRS1040/efile:RoutingTransitNum", NS) is None
+
+
+def test_ip_pins_in_header():
+    tax_return = make_return()
+    pins = {"taxpayer": {"pin": "482913", "valid_year": 2025}, "spouse": {"pin": "105577", "valid_year": 2025}}
+    xml = build_return_xml(tax_return, calculate_return(tax_return), ip_pins=pins)
+    assert validate_return_xml(xml) == []
+    header = ET.fromstring(xml).find("efile:ReturnHeader", NS)
+    assert header.find("efile:IdentityProtectionPIN", NS).text == "482913"
+    assert header.find("efile:SpouseIdentityProtectionPIN", NS).text == "105577"
+
+    xml = build_return_xml(tax_return, calculate_return(tax_return), ip_pins={"taxpayer": {"pin": "48291"}})
+    assert "ReturnHeader/IdentityProtectionPIN is not a valid ip pin: 48291" in validate_return_xml(xml)
+    assert ET.fromstring(build_return_xml(tax_return, calculate_return(tax_return))).find(
+        "efile:ReturnHeader/efile:IdentityProtectionPIN", NS
+    ) is None
@@ -0,0 +1,67 @@
+"""Tests for the embedding providers."""
+import json
+
+import httpx
+import pytest
+
+from app.utils.embeddings import EmbeddingError, HttpEmbeddingProvider, dense_cosine, normalize
+
+
+def provider(handler, **kwargs):
+    return HttpEmbeddingProvider(
+        "http://localhost:11434/v1/", "nomic-embed-text", transport=httpx.MockTransport(handler), **kwargs
+    )
+
+
+def test_embeds_in_input_order():
+    requests = []
+
+    def handler(request):
+        requests.append(request)
+        # Out of order, as some servers return them
+        return httpx.Response(200, json={"data": [
+            {"index": 1, "embedding": [0.0, 2.0]},
+            {"index": 0, "embedding": [3.0, 4.0]},
+        ]})
+
+    vectors = provider(handler, api_key="sk-test").embed(["home office", "mileage"])
+    assert vectors == [[0.6, 0.8], [0.0, 1.0]]
+    assert str(requests[0].url) == "http://localhost:11434/v1/embeddings"
+    assert json.loads(requests[0].content) == {"model": "nomic-embed-text", "input": ["home office", "mileage"]}
+    assert requests[0].headers["Authorization"] == "Bearer sk-test"
+    assert provider(handler).embed([]) == []
+
+
+def test_local_provider_sends_no_key():
+    def handler(request):
+        assert "Authorization" not in request.headers
+        return httpx.Response(200, json={"data": [{"index": 0, "embedding": [1.0]}]})
+
+    assert provider(handler).embed(["hi"]) == [[1.0]]
+
+
+@pytest.mark.parametrize("response, message", [
+    (httpx.Response(500, text="model not loaded"), "failed \\(500\\)"),
+    (httpx.Response(200, text="not json"), "failed"),
+    (httpx.Response(200, json={"error": "unknown model"}), "no embeddings"),
+    (httpx.Response(200, json={"data": []}), "Expected 1 embeddings, got 0"),
+])
+def test_bad_responses(response, message):
+    with pytest.raises(EmbeddingError, match=message):
+        provider(lambda request: response).embed(["hi"])
+
+
+def test_unreachable_provider():
+    def handler(request):
+        raise httpx.ConnectError("connection refused")
+
+    with pytest.raises(EmbeddingError, match="connection refused"):
+        provider(handler).embed(["hi"])
+
+
+def test_cosine():
+    a, b = normalize([1.0, 1.0]), normalize([1.0, 0.0])
+    assert dense_cosine(a, a) == pytest.approx(1.0)
+    assert dense_cosine(a, b) == pytest.approx(0.7071, abs=1e-4)
+    assert dense_cosine(a, [1.0, 0.0, 0.0]) == 0.0
+    assert normalize([0.0, 0.0]) == [0.0, 0.0]
@@ -0,0 +1,48 @@
+"""Tests for 1040-ES voucher generation."""
+from datetime import date
+from decimal import Decimal
+
+import pytest
+
+from app.forms.estimated_tax import remaining_payments, render_vouchers
+from app.services.formatting import Formatter
+from app.tax_engine.tax_calculator import TaxCalculator
+
+
+@pytest.fixture
+def schedule():
+    return TaxCalculator(2024).estimate_quarterly_payments(Decimal("80000"), "single")
+
+
+def test_remaining_payments(schedule):
+    assert [p["voucher"] for p in remaining_payments(schedule, date(2024, 1, 1))] == [1, 2, 3, 4]
+    remaining = remaining_payments(schedule, date(2024, 6, 16))
+    assert [p["voucher"] for p in remaining] == [3, 4]
+    assert remaining[0]["due_date"] == "2024-09-15"
+    assert remaining_payments(schedule, date(2025, 2, 1)) == []
+
+
+def test_no_payments_when_withholding_covers_tax():
+    covered = TaxCalculator(2024).estimate_quarterly_payments(Decimal("50000"), "single", Decimal("20000"))
+    assert remaining_payments(covered, date(2024, 1, 1)) == []
+
+
+def test_render_vouchers(schedule):
+    payments = remaining_payments(schedule, date(2024, 6, 16))
+    pdf = render_vouchers(payments, 2024, {"name": "Pat Doe", "ssn": "123-45-6789", "city": "Austin", "state": "TX"})
+    assert pdf.startswith(b"%PDF-")
+    assert pdf.count(b"/Type /Page ") == 2
+    assert b"Payment Voucher 3" in pdf
+    assert b"Pat Doe" in pdf
+    assert b"Austin TX" in pdf
+    assert f"${payments[0]['amount']:,.2f}".encode() in pdf
+
+    with pytest.raises(ValueError):
+        render_vouchers([], 2024, {})
+
+
+def test_render_vouchers_with_formats(schedule):
+    payments = remaining_payments(schedule, date(2024, 6, 16))
+    pdf = render_vouchers(payments, 2024, {"name": "Pat Doe"}, formatter=Formatter("us", "eu"))
+    assert b"09/15/2024" in pdf
+    assert Formatter("us", "eu").currency(payments[0]["amount"]).encode() in pdf
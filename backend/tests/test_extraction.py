@@ -0,0 +1,68 @@
+"""Tests for extracting form fields from stored documents."""
+import asyncio
+
+import pytest
+
+from app.documents.extraction import TextUnavailableError, document_text, extract_document
+from app.documents.inbox import InboxWatcher
+from app.services.job_queue import JobQueue
+from app.utils.document_store import DocumentStore
+
+W2_TEXT = """
+Form W-2 Wage and Tax Statement 2024
+c Employer's name, address, and ZIP code
+Acme Widgets Inc
+1 Wages, tips, other compensation 85,000.00
+2 Federal income tax withheld 12,000.00
+"""
+
+
+@pytest.fixture
+def store(tmp_path):
+    return DocumentStore(storage_dir=str(tmp_path / "documents"))
+
+
+def test_text_needs_a_text_layer():
+    assert document_text(b"Total $12.50", "text/plain") == "Total $12.50"
+    with pytest.raises(TextUnavailableError, match="OCR"):
+        document_text(b"\x89PNG", "image/png")
+    with pytest.raises(TextUnavailableError, match="no text layer"):
+        document_text(b" \n", "text/plain")
+
+
+def test_extract_saves_fields(store):
+    document = store.upload_document("w2.txt", content=W2_TEXT.encode(), document_type="W-2")
+    result = extract_document(store, document["document_id"])
+
+    assert result == {"document_id": document["document_id"], "extraction_status": "extracted", "form": "W-2"}
+    record = store.get_document(document["document_id"])
+    assert record["extraction"]["fields"]["wages"]["value"] == 85000.0
+    assert record["ocr_text"] == W2_TEXT
+
+
+def test_unreadable_documents_are_marked_failed(store):
+    photo = store.upload_document("w2.jpg", content=b"jpeg", document_type="W-2")
+    unknown = store.upload_document("letter.txt", content=b"Dear taxpayer", document_type="unknown")
+
+    assert extract_document(store, photo["document_id"])["extraction_status"] == "failed"
+    assert "OCR" in store.get_document(photo["document_id"])["extraction_error"]
+    assert "No parser" in extract_document(store, unknown["document_id"])["error"]
+    with pytest.raises(KeyError):
+        extract_document(store, "doc_0000000000000000")
+
+
+def test_inbox_documents_are_extracted_by_the_queue(store, tmp_path):
+    queue = JobQueue(storage_dir=str(tmp_path / "jobs"))
+    queue.register("extract_document", lambda payload, progress: extract_document(store, payload["document_id"]))
+    inbox = tmp_path / "inbox"
+    inbox.mkdir()
+    (inbox / "w2_acme.txt").write_text(W2_TEXT)
+    watcher = InboxWatcher(
+        store, str(inbox),
+        queue_extraction=lambda document_id: queue.enqueue("extract_document", {"document_id": document_id}),
+    )
+
+    document_id = watcher.scan(require_stable=False)[0]["document_id"]
+    assert store.get_document(document_id)["extraction_status"] == "queued"
+    assert asyncio.run(queue.run_pending()) == 1
+    assert store.get_document(document_id)["extraction_status"] == "extracted"
@@ -0,0 +1,68 @@
+"""Tests for field encryption."""
+import os
+import stat
+
+import pytest
+from cryptography.fernet import Fernet
+
+from app.utils.field_encryption import FieldCipher, InvalidKeyError, mask, mask_ssns
+
+
+def test_round_trip_with_key_file(tmp_path):
+    key_path = tmp_path / "keys" / "field.key"
+    cipher = FieldCipher(key_path=str(key_path))
+    token = cipher.encrypt("021000021")
+    assert "021000021" not in token
+    assert cipher.decrypt(token) == "021000021"
+    assert stat.S_IMODE(os.stat(key_path).st_mode) == 0o600
+
+    # A new cipher reuses the stored key
+    assert FieldCipher(key_path=str(key_path)).decrypt(token) == "021000021"
+
+
+def test_key_from_environment(tmp_path, monkeypatch):
+    key = Fernet.generate_key()
+    monkeypatch.setenv("FIELD_ENCRYPTION_KEY", key.decode())
+    cipher = FieldCipher(key_path=str(tmp_path / "unused.key"))
+    assert FieldCipher(key=key).decrypt(cipher.encrypt("secret")) == "secret"
+    assert not (tmp_path / "unused.key").exists()
+
+
+def test_wrong_key_rejected(tmp_path):
+    token = FieldCipher(key=Fernet.generate_key()).encrypt("secret")
+    with pytest.raises(ValueError, match="encryption key"):
+        FieldCipher(key=Fernet.generate_key()).decrypt(token)
+
+
+def test_malformed_key_rejected(tmp_path, monkeypatch):
+    key = Fernet.generate_key()
+    monkeypatch.setenv("FIELD_ENCRYPTION_KEY", key.decode() + "\n")  # pasted with a newline
+    assert FieldCipher(key=key).decrypt(FieldCipher().encrypt("secret")) == "secret"
+
+    for bad in (key[:-4], key[:20] + b"'; --" + key[25:], "ключ".encode() * 11):
+        monkeypatch.setenv("FIELD_ENCRYPTION_KEY", bad.decode())
+        with pytest.raises(InvalidKeyError, match="FIELD_ENCRYPTION_KEY isn't valid") as error:
+            FieldCipher().encrypt("secret")
+        assert bad.decode() not in str(error.value)
+
+    monkeypatch.delenv("FIELD_ENCRYPTION_KEY")
+    key_path = tmp_path / "field.key"
+    key_path.write_bytes(b"not a key")
+    with pytest.raises(InvalidKeyError, match="field.key"):
+        FieldCipher(key_path=str(key_path)).decrypt("token")
+
+
+def test_mask():
+    assert mask("123456789") == "*****6789"
+    assert mask("123") == "***"
+
+
+def test_mask_ssns():
+    tax_return = {
+        "taxpayer": {"name": "Pat Doe", "ssn": "123456789", "spouse_ssn": "987654321"},
+        "dependents": [{"name": "Casey", "has_ssn": True, "ssn": "111223333"}],
+    }
+    masked = mask_ssns(tax_return)
+    assert masked["taxpayer"] == {"name": "Pat Doe", "ssn": "*****6789", "spouse_ssn": "*****4321"}
+    assert masked["dependents"] == [{"name": "Casey", "has_ssn": True, "ssn": "*****3333"}]
+    assert tax_return["taxpayer"]["ssn"] == "123456789"
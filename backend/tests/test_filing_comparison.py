@@ -0,0 +1,105 @@
+"""Tests for the joint vs. separate filing comparison."""
+import pytest
+
+from app.tax_engine.filing_comparison import compare_filing_separately, split_joint_return
+from app.tax_engine.return_calculation import calculate_return
+
+
+def joint_return(**fields):
+    return {
+        "return_id": "ret_0000000000000000",
+        "tax_year": 2024,
+        "filing_status": "married_joint",
+        "taxpayer": {"name": "Pat Doe", "ssn": "123456789", "spouse_name": "Sam Doe", "spouse_ssn": "987654321"},
+        "dependents": [],
+        "income_sources": [
+            {"id": "inc_1", "type": "wages", "amount": 90000, "withholding": 9000},
+            {"id": "inc_2", "type": "wages", "amount": 30000, "withholding": 2000, "owner": "spouse"},
+            {"id": "inc_3", "type": "interest", "amount": 1000, "withholding": 0, "owner": "joint"},
+        ],
+        "deductions": [],
+        "businesses": [],
+        **fields,
+    }
+
+
+def test_split_by_owner():
+    returns = split_joint_return(joint_return(carryforwards={"capital_loss": 5000}))
+    taxpayer, spouse = returns["taxpayer"], returns["spouse"]
+    assert taxpayer["filing_status"] == spouse["filing_status"] == "married_separate"
+    assert [(s["id"], s["amount"]) for s in taxpayer["income_sources"]] == [("inc_1", 90000), ("inc_3", 500.0)]
+    assert [(s["id"], s["amount"]) for s in spouse["income_sources"]] == [("inc_2", 30000), ("inc_3", 500.0)]
+    assert {s["owner"] for s in spouse["income_sources"]} == {"taxpayer"}
+    assert spouse["taxpayer"]["name"] == "Sam Doe"
+    assert spouse["carryforwards"] == {"capital_loss": 2500.0}
+
+
+def test_business_income_follows_business_owner():
+    tax_return = joint_return(businesses=[{"id": "biz_1", "name": "Studio", "owner": "spouse", "expenses": []}])
+    tax_return["income_sources"].append({"id": "inc_4", "type": "self_employment", "amount": 10000, "business_id": "biz_1"})
+    returns = split_joint_return(tax_return)
+    assert "inc_4" in [s["id"] for s in returns["spouse"]["income_sources"]]
+    assert calculate_return(returns["spouse"])["businesses"][0]["net_profit"] == 10000.0
+    assert calculate_return(returns["taxpayer"])["self_employment_tax"] == 0.0
+
+
+def test_rentals_follow_their_owner():
+    rental = {"placed_in_service": "2020-01-15", "building_basis": 275000, "rents": 30000, "expenses": 5000}
+    tax_return = joint_return(rental_properties=[
+        {"id": "rent_1", "address": "1 Elm St", **rental},
+        {"id": "rent_2", "address": "2 Oak Ave", **rental, "owner": "spouse"},
+        {"id": "rent_3", "address": "3 Pine Rd", **rental, "owner": "joint"},
+    ])
+    returns = split_joint_return(tax_return)
+    assert [(p["id"], p["rents"]) for p in returns["taxpayer"]["rental_properties"]] == [
+        ("rent_1", 30000), ("rent_3", 15000.0),
+    ]
+    assert [(p["id"], p["building_basis"]) for p in returns["spouse"]["rental_properties"]] == [
+        ("rent_2", 275000), ("rent_3", 137500.0),
+    ]
+    joint_agi = calculate_return(tax_return)["agi"]
+    assert joint_agi == pytest.approx(
+        calculate_return(returns["taxpayer"])["agi"] + calculate_return(returns["spouse"])["agi"]
+    )
+
+
+def test_comparison_totals():
+    result = compare_filing_separately(joint_return())
+    assert result["joint_tax"] == calculate_return(joint_return())["total_tax"]
+    separate = result["separate"]
+    assert result["combined_separate_tax"] == pytest.approx(
+        separate["taxpayer"]["total_tax"] + separate["spouse"]["total_tax"]
+    )
+    assert result["difference"] == pytest.approx(result["combined_separate_tax"] - result["joint_tax"])
+    # Brackets for separate filers are half of joint ones, so uneven incomes do better jointly
+    assert result["difference"] > 0
+    assert result["recommended_filing_status"] == "married_joint"
+
+
+def test_one_itemizer_forces_the_other():
+    tax_return = joint_return(deductions=[
+        {"id": "ded_1", "category": "mortgage_interest", "amount": 20000, "owner": "taxpayer"},
+    ])
+    result = compare_filing_separately(tax_return)
+    assert result["separate"]["taxpayer"]["deduction_type"] == "Itemized"
+    assert result["separate"]["spouse"]["deduction_type"] == "Itemized"
+    assert result["separate"]["spouse"]["deduction_amount"] == 0.0
+    assert any("must itemize" in note for note in result["notes"])
+
+
+def test_education_credits_are_lost_filing_separately():
+    tax_return = joint_return(students=[{"id": "stu_1", "name": "Pat Doe", "expenses": [
+        {"kind": "tuition", "amount": 4000},
+    ]}])
+    result = compare_filing_separately(tax_return)
+    assert calculate_return(tax_return)["education_credits"]["total"] > 0
+    assert all(
+        calculate_return(separate)["education_credits"]["total"] == 0
+        for separate in split_joint_return(tax_return).values()
+    )
+    assert any("education and adoption credits are already left off" in note for note in result["notes"])
+
+
+def test_only_joint_returns():
+    with pytest.raises(ValueError, match="married filing jointly"):
+        compare_filing_separately(joint_return(filing_status="single"))
@@ -0,0 +1,51 @@
+"""Tests for the FBAR and Form 8938 threshold checks."""
+from app.tax_engine.foreign_accounts import check_foreign_accounts
+
+
+def account(max_balance, year_end=None, owner=None):
+    record = {"institution": "Maple Bank", "country": "Canada", "max_balance": max_balance}
+    if year_end is not None:
+        record["year_end_balance"] = year_end
+    if owner:
+        record["owner"] = owner
+    return record
+
+
+def test_fbar_aggregates_across_accounts():
+    result = check_foreign_accounts([account(6000, 5000), account(4500, 4000)], "single")
+    assert result["fbar"]["required"] is True
+    assert result["fbar"]["people"] == [
+        {"person": "taxpayer", "accounts": 2, "aggregate_max_balance": 10500.0, "required": True},
+    ]
+    assert result["form_8938"]["required"] is False
+    assert any("FinCEN Form 114" in w for w in result["warnings"])
+
+    # Exactly $10,000 doesn't exceed the threshold
+    assert check_foreign_accounts([account(10000, 9000)], "single")["fbar"]["required"] is False
+
+
+def test_fbar_is_per_person_with_joint_accounts():
+    accounts = [account(7000, 7000, "taxpayer"), account(6000, 6000, "spouse"), account(3500, 3500, "joint")]
+    people = {p["person"]: p for p in check_foreign_accounts(accounts, "married_joint")["fbar"]["people"]}
+    assert people["taxpayer"]["aggregate_max_balance"] == 10500.0 and people["taxpayer"]["required"] is True
+    assert people["spouse"]["aggregate_max_balance"] == 9500.0 and people["spouse"]["required"] is False
+
+
+def test_form_8938_thresholds():
+    accounts = [account(80000, 40000)]
+    assert check_foreign_accounts(accounts, "single")["form_8938"]["required"] is True
+    assert check_foreign_accounts(accounts, "married_joint")["form_8938"]["required"] is False
+    assert check_foreign_accounts([account(250000, 210000)], "single", lives_abroad=True)["form_8938"]["required"] is True
+
+    # A missing year-end balance counts at the maximum
+    result = check_foreign_accounts([account(60000)], "single")
+    assert result["form_8938"]["year_end_total"] == 60000.0
+    assert result["form_8938"]["required"] is True
+    assert any("maximum balance" in w for w in result["warnings"])
+
+
+def test_no_accounts():
+    result = check_foreign_accounts([], "single")
+    assert result["fbar"]["required"] is False
+    assert result["form_8938"]["required"] is False
+    assert result["warnings"] == []
@@ -0,0 +1,58 @@
+"""Tests for number and date formatting."""
+from datetime import date, datetime
+
+import pytest
+
+from app.forms.ledger_csv import build_return_csv
+from app.services.formatting import Formatter
+
+
+@pytest.mark.parametrize("currency_format, number, currency", [
+    ("us", "1,234,567.89", "-$1,234,567.89"),
+    ("eu", "1.234.567,89", "-$1.234.567,89"),
+    ("space", "1 234 567,89", "-$1 234 567,89"),
+])
+def test_amounts(currency_format, number, currency):
+    formatter = Formatter(currency_format=currency_format)
+    assert formatter.number(1234567.891) == number
+    assert formatter.currency(-1234567.891) == currency
+    assert formatter.number(None) == ""
+
+
+@pytest.mark.parametrize("date_format, expected", [("iso", "2025-04-15"), ("us", "04/15/2025"), ("eu", "15/04/2025")])
+def test_dates(date_format, expected):
+    formatter = Formatter(date_format=date_format)
+    assert formatter.date(date(2025, 4, 15)) == expected
+    assert formatter.date(datetime(2025, 4, 15, 9, 30)) == expected
+    assert formatter.date("2025-04-15T09:30:00") == expected
+    assert formatter.date("sometime in April") == "sometime in April"
+
+
+def test_whole_dollars():
+    formatter = Formatter(currency_format="eu", rounding="whole_dollars")
+    assert formatter.number(1234.5) == "1.235"
+    assert formatter.number(2.5) == "3"
+    assert formatter.currency(-0.4) == "$0"
+    assert Formatter().number(0.125) == "0.13"
+
+
+def test_unknown_formats_rejected():
+    with pytest.raises(ValueError):
+        Formatter(date_format="julian")
+    with pytest.raises(ValueError):
+        Formatter(currency_format="roman")
+    with pytest.raises(ValueError):
+        Formatter(rounding="dimes")
+
+
+def test_ledger_csv_uses_formats():
+    tax_return = {
+        "income_sources": [{"id": "inc_1", "type": "wages", "description": "Acme", "amount": 85000, "withholding": 12000.5}],
+        "deductions": [{"id": "ded_1", "category": "charitable", "description": "Food bank", "amount": 1250, "date": "2024-12-01"}],
+    }
+    lines = build_return_csv(tax_return).splitlines()
+    assert lines[0] == "record,id,category,description,owner,date,amount,withholding"
+    assert lines[1] == 'income,inc_1,wages,Acme,,,"85,000.00","12,000.50"'
+
+    lines = build_return_csv(tax_return, Formatter(date_format="eu", currency_format="eu")).splitlines()
+    assert lines[2] == "deduction;ded_1;charitable;Food bank;;01/12/2024;1.250,00;"
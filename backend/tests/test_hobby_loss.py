@@ -0,0 +1,48 @@
+"""Tests for the hobby-or-business nine-factor test."""
+import pytest
+
+from app.tax_engine.hobby_loss import HOBBY_FACTORS, evaluate_hobby_factors
+
+FACTORS = [name for name, _, _ in HOBBY_FACTORS]
+
+
+def test_mostly_businesslike_is_schedule_c():
+    answers = {name: "yes" for name in FACTORS[:7]}
+    answers.update(financial_status="no", personal_pleasure="no")
+    result = evaluate_hobby_factors(answers, notes={"manner": "Separate bank account and QuickBooks"})
+    assert (result["recommendation"], result["strength"]) == ("schedule_c", "strong")
+    assert (result["favorable"], result["unfavorable"], result["unanswered"]) == (7, 2, 0)
+    assert result["rationale"][0] == (
+        "Manner in which the activity is carried on: points to a business (Separate bank account and QuickBooks)"
+    )
+    assert len(result["rationale"]) == 10
+
+
+def test_close_calls_and_ties_lean_hobby():
+    answers = {"manner": "yes", "expertise": "yes", "time_and_effort": "no", "personal_pleasure": "no"}
+    result = evaluate_hobby_factors(answers)
+    assert (result["recommendation"], result["strength"]) == ("hobby", "weak")
+    assert "Schedule 1 line 8j" in result["rationale"][9]
+    assert "5 factors are undetermined" in result["rationale"][-1]
+
+    weak_business = evaluate_hobby_factors({**answers, "income_history": "yes"})
+    assert (weak_business["recommendation"], weak_business["strength"]) == ("schedule_c", "weak")
+
+
+def test_profit_presumption():
+    no_answers = {name: "no" for name in FACTORS}
+    result = evaluate_hobby_factors(no_answers, profit_years=3)
+    assert result["presumption_met"] is True
+    assert (result["recommendation"], result["strength"]) == ("schedule_c", "strong")
+    assert evaluate_hobby_factors(no_answers, profit_years=2)["recommendation"] == "hobby"
+    # Horse activities need 2 profitable years out of 7
+    assert evaluate_hobby_factors(no_answers, profit_years=2, horse_activity=True)["presumption_met"] is True
+
+
+def test_invalid_answers():
+    with pytest.raises(ValueError, match="Unknown factor"):
+        evaluate_hobby_factors({"vibes": "yes"})
+    with pytest.raises(ValueError, match="Invalid answer"):
+        evaluate_hobby_factors({"manner": "maybe"})
+    with pytest.raises(ValueError, match="between 0 and 5"):
+        evaluate_hobby_factors({}, profit_years=6)
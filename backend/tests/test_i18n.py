@@ -0,0 +1,40 @@
+"""Tests for the message catalogs."""
+import json
+
+import pytest
+
+from app.i18n import LOCALES_DIR, SUPPORTED_LOCALES, display_name, display_names, normalize_locale, translate
+from app.utils.return_store import ReturnStore
+
+
+def test_every_locale_has_every_key():
+    english = set(json.loads((LOCALES_DIR / "en.json").read_text(encoding="utf-8")))
+    for locale in SUPPORTED_LOCALES:
+        assert set(json.loads((LOCALES_DIR / f"{locale}.json").read_text(encoding="utf-8"))) == english
+
+
+@pytest.mark.parametrize("group, codes", [
+    ("income_type", ReturnStore.INCOME_TYPES),
+    ("deduction_category", ReturnStore.DEDUCTION_CATEGORIES),
+    ("business_expense_category", ReturnStore.BUSINESS_EXPENSE_CATEGORIES),
+    ("return_status", list(ReturnStore.STATUS_TRANSITIONS)),
+])
+def test_stored_codes_have_display_names(group, codes):
+    names = display_names("es")[group]
+    assert set(codes) <= set(names)
+
+
+def test_display_names_and_fallbacks():
+    assert display_name("income_type", "self_employment") == "Self-employment income"
+    assert display_name("income_type", "self_employment", "es-MX") == "Ingresos de trabajo por cuenta propia"
+    assert display_name("form", "W-2", "es").startswith("Formulario W-2")
+    assert display_name("income_type", "crypto_staking", "es") == "Crypto staking"
+    assert translate("validation.dependent_residency", "es", name="Ana", months=3).startswith("Ana vivió con usted 3 meses")
+    assert translate("no.such.key", "es") == "no.such.key"
+
+
+def test_unsupported_locale():
+    assert normalize_locale(None) == "en"
+    assert normalize_locale("ES_us") == "es"
+    with pytest.raises(ValueError):
+        normalize_locale("fr")
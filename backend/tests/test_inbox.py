@@ -0,0 +1,105 @@
+"""Tests for the tax inbox watcher."""
+import asyncio
+
+import pytest
+
+from app.documents.inbox import InboxWatcher
+from app.utils.document_store import DocumentStore
+
+
+@pytest.fixture
+def store(tmp_path):
+    """Create a document store with a temp directory."""
+    return DocumentStore(storage_dir=str(tmp_path / "documents"))
+
+
+@pytest.fixture
+def inbox(tmp_path):
+    folder = tmp_path / "inbox"
+    folder.mkdir()
+    return folder
+
+
+def test_file_imported_once_stable(store, inbox):
+    events = []
+    watcher = InboxWatcher(store, str(inbox), on_event=events.append)
+    (inbox / "w2_acme.pdf").write_bytes(b"w2 bytes")
+
+    assert watcher.scan() == []  # first sighting: may still be downloading
+    emitted = watcher.scan()
+
+    assert [e["event"] for e in emitted] == ["inbox_document_imported"]
+    assert emitted[0]["document_type"] == "W-2"
+    assert events == emitted
+    assert (inbox / "imported" / "w2_acme.pdf").exists()
+    assert not (inbox / "w2_acme.pdf").exists()
+
+    record = store.get_document(emitted[0]["document_id"])
+    assert record["source"] == "inbox"
+    assert "extraction_status" not in record
+
+
+def test_imported_documents_are_queued_for_extraction(store, inbox):
+    queued = []
+    watcher = InboxWatcher(store, str(inbox), queue_extraction=queued.append)
+    (inbox / "w2_acme.pdf").write_bytes(b"w2 bytes")
+    event = watcher.scan(require_stable=False)[0]
+
+    assert queued == [event["document_id"]]
+    assert store.get_document(event["document_id"])["extraction_status"] == "queued"
+
+
+def test_changing_file_waits(store, inbox):
+    watcher = InboxWatcher(store, str(inbox))
+    path = inbox / "scan.pdf"
+    path.write_bytes(b"partial")
+    watcher.scan()
+    path.write_bytes(b"partial plus more")
+    assert watcher.scan() == []
+    assert len(watcher.scan()) == 1
+
+
+def test_forced_scan_imports_immediately(store, inbox):
+    watcher = InboxWatcher(store, str(inbox))
+    (inbox / "receipt.jpg").write_bytes(b"jpeg")
+    assert len(watcher.scan(require_stable=False)) == 1
+
+
+def test_duplicates_and_failures_are_filed_away(store, inbox):
+    store.upload_document("original.pdf", content=b"same")
+    watcher = InboxWatcher(store, str(inbox))
+    (inbox / "copy.pdf").write_bytes(b"same")
+    (inbox / "empty.pdf").write_bytes(b"")
+
+    events = {e["file"]: e for e in watcher.scan(require_stable=False)}
+    assert events["copy.pdf"]["event"] == "inbox_document_duplicate"
+    assert events["empty.pdf"]["event"] == "inbox_document_failed"
+    assert (inbox / "imported" / "copy.pdf").exists()
+    assert (inbox / "failed" / "empty.pdf").exists()
+    assert len(store.list_documents()) == 1
+
+
+def test_recent_events_since(store, inbox):
+    watcher = InboxWatcher(store, str(inbox))
+    (inbox / "a.pdf").write_bytes(b"a")
+    first = watcher.scan(require_stable=False)[0]
+    (inbox / "b.pdf").write_bytes(b"b")
+    watcher.scan(require_stable=False)
+    assert [e["file"] for e in watcher.recent_events(since=first["timestamp"])] == ["b.pdf"]
+
+
+def test_background_polling(store, inbox):
+    async def run():
+        watcher = InboxWatcher(store, str(inbox), poll_seconds=0.01)
+        (inbox / "w2.pdf").write_bytes(b"w2")
+        watcher.start()
+        for _ in range(200):
+            if watcher.events:
+                break
+            await asyncio.sleep(0.01)
+        await watcher.stop()
+        return watcher
+
+    watcher = asyncio.run(run())
+    assert not watcher.running
+    assert len(watcher.events) == 1
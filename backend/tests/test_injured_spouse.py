@@ -0,0 +1,106 @@
+"""Tests for the Form 8379 injured spouse allocation."""
+from decimal import Decimal
+
+import pytest
+
+from app.tax_engine.injured_spouse import injured_spouse_allocation
+from app.tax_engine.return_calculation import calculate_return
+
+
+def joint_return(**fields):
+    return {
+        "return_id": "ret_0000000000000000",
+        "tax_year": 2024,
+        "filing_status": "married_joint",
+        "taxpayer": {"name": "Pat Doe", "ssn": "123456789", "spouse_name": "Sam Doe", "spouse_ssn": "987654321"},
+        "dependents": [],
+        "income_sources": [
+            {"id": "inc_1", "type": "wages", "amount": 90000, "withholding": 9000},
+            {"id": "inc_2", "type": "wages", "amount": 30000, "withholding": 2000, "owner": "spouse"},
+            {"id": "inc_3", "type": "interest", "amount": 1000, "withholding": 0, "owner": "joint"},
+        ],
+        "deductions": [],
+        "businesses": [],
+        **fields,
+    }
+
+
+def lines(result):
+    return {line["key"]: line for line in result["lines"]}
+
+
+def test_income_and_withholding_follow_owner():
+    rows = lines(injured_spouse_allocation(joint_return()))
+    assert (rows["wages"]["taxpayer"], rows["wages"]["spouse"]) == (90000, 30000)
+    assert (rows["other_income"]["taxpayer"], rows["other_income"]["spouse"]) == (500, 500)
+    assert (rows["withholding"]["taxpayer"], rows["withholding"]["spouse"]) == (9000, 2000)
+    # The standard deduction is divided equally
+    assert rows["deduction"]["taxpayer"] == rows["deduction"]["spouse"] == 14600
+
+
+def test_columns_add_up_to_the_joint_return():
+    result = injured_spouse_allocation(joint_return())
+    for row in result["lines"]:
+        assert row["taxpayer"] + row["spouse"] == pytest.approx(row["joint"])
+    tax = sum(result["spouses"][spouse]["income_tax"] for spouse in ("taxpayer", "spouse"))
+    assert tax == pytest.approx(calculate_return(joint_return())["income_tax"])
+
+
+def test_injured_spouse_share_of_refund():
+    result = injured_spouse_allocation(joint_return(), "spouse")
+    spouse = result["spouses"]["spouse"]
+    assert spouse["refund_share"] == pytest.approx(spouse["total_payments"] - spouse["total_tax"])
+    # The spouse's withholding exceeds their share of the tax; the refund caps what comes back
+    assert spouse["refund_share"] > result["joint_refund"]
+    assert result["injured_spouse_refund"] == result["joint_refund"] == 448
+
+
+def test_over_withheld_debtor_gets_nothing_back():
+    result = injured_spouse_allocation(joint_return(), "taxpayer")
+    assert result["spouses"]["taxpayer"]["refund_share"] < 0
+    assert result["injured_spouse_refund"] == 0
+    assert any("wouldn't recover anything" in note for note in result["notes"])
+
+
+def test_offset_with_and_without_form():
+    result = injured_spouse_allocation(joint_return(), "spouse", past_due_debt=Decimal("300"))
+    offset = result["offset"]
+    assert offset["offset_without_form_8379"] == 300
+    assert offset["refund_without_form_8379"] == 148
+    assert offset["offset_with_form_8379"] == 0
+    assert offset["protected"] == 300
+    assert injured_spouse_allocation(joint_return())["offset"] is None
+
+
+def test_self_employment_tax_stays_with_the_business_owner():
+    tax_return = joint_return(businesses=[{"id": "biz_1", "name": "Studio", "owner": "spouse", "expenses": []}])
+    tax_return["income_sources"].append({"id": "inc_4", "type": "self_employment", "amount": 10000, "business_id": "biz_1"})
+    rows = lines(injured_spouse_allocation(tax_return))
+    assert rows["other_taxes"]["taxpayer"] == 0
+    assert rows["other_taxes"]["spouse"] == rows["other_taxes"]["joint"] > 0
+
+
+def test_itemized_deductions_by_who_paid():
+    tax_return = joint_return(deductions=[
+        {"id": "ded_1", "category": "mortgage_interest", "amount": 24000},
+        {"id": "ded_2", "category": "charitable", "amount": 8000, "owner": "spouse"},
+    ])
+    rows = lines(injured_spouse_allocation(tax_return))
+    deduction = rows["deduction"]
+    assert deduction["joint"] == 32000
+    assert (deduction["taxpayer"], deduction["spouse"]) == (24000, 8000)
+
+
+def test_community_property_note():
+    tax_return = joint_return(state_returns=[{"id": "st_1", "state": "CA", "residency": "resident"}])
+    notes = injured_spouse_allocation(tax_return)["notes"]
+    assert any("CA is a community property state" in note for note in notes)
+
+
+def test_rejects_bad_input():
+    with pytest.raises(ValueError, match="married filing jointly"):
+        injured_spouse_allocation(joint_return(filing_status="single"))
+    with pytest.raises(ValueError, match="Injured spouse"):
+        injured_spouse_allocation(joint_return(), "joint")
+    with pytest.raises(ValueError, match="negative"):
+        injured_spouse_allocation(joint_return(), past_due_debt=Decimal("-1"))
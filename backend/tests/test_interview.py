@@ -0,0 +1,70 @@
+"""Tests for the deterministic side of the interview agent (tool application)."""
+import asyncio
+
+import pytest
+
+from app.agents.interview_agent import InterviewAgent
+from app.utils.conversation_store import ConversationStore
+from app.utils.return_store import ReturnStore
+
+
+@pytest.fixture
+def agent(tmp_path):
+    return InterviewAgent(
+        return_store=ReturnStore(storage_dir=str(tmp_path / "returns")),
+        conversation_store=ConversationStore(storage_dir=str(tmp_path / "conversations")),
+    )
+
+
+@pytest.fixture
+def started(agent):
+    tax_return = agent.return_store.create_return()
+    agent.start_interview(tax_return["return_id"])
+    return agent.return_store.get_return(tax_return["return_id"])
+
+
+def test_start_interview_tracks_progress(agent, started):
+    progress = InterviewAgent.progress_summary(started)
+    assert progress["current_section"] == "filing_status"
+    assert progress["percent_complete"] == 0
+    messages = agent.conversation_store.get_messages(started["interview"]["session_id"])
+    assert messages[0]["role"] == "assistant"
+
+
+def test_set_filing_status_tool(agent, started):
+    agent.apply_tool_call(started, "set_filing_status", {"filing_status": "head_of_household"})
+    assert agent.return_store.get_return(started["return_id"])["filing_status"] == "head_of_household"
+
+
+def test_invalid_filing_status_tool(agent, started):
+    with pytest.raises(ValueError):
+        agent.apply_tool_call(started, "set_filing_status", {"filing_status": "divorced"})
+
+
+def test_income_tool_creates_record(agent, started):
+    result = agent.apply_tool_call(
+        started, "add_income_source", {"type": "wages", "amount": 72000, "withholding": 8000}
+    )
+    stored = agent.return_store.get_return(started["return_id"])
+    assert stored["income_sources"][0]["id"] == result["record"]["id"]
+    assert stored["income_sources"][0]["source"] == "interview"
+
+
+def test_life_event_tool(agent, started):
+    agent.apply_tool_call(started, "record_life_event", {"event": "moved", "details": "CA to TX"})
+    stored = agent.return_store.get_return(started["return_id"])
+    assert stored["interview"]["life_events"] == [{"event": "moved", "details": "CA to TX"}]
+
+
+def test_unknown_tool_rejected(agent, started):
+    with pytest.raises(ValueError, match="Unknown interview tool"):
+        agent.apply_tool_call(started, "file_return", {})
+
+
+def test_answer_outside_scope_needs_cpa(agent, started, tmp_path, monkeypatch):
+    monkeypatch.chdir(tmp_path)
+    (tmp_path / ".app_settings.json").write_text('{"ai_tax_years": [2023]}')
+    result = asyncio.run(agent.answer(started["return_id"], "I'm single."))
+    assert result["response"]["type"] == "needs_human_cpa"
+    assert result["question"] == result["response"]["text"]
+    assert result["records_created"] == [] and result["current_section"] == "filing_status"
@@ -0,0 +1,58 @@
+"""Property checks on the bracket math over seeded random incomes for every filing status."""
+import random
+from decimal import Decimal
+
+import pytest
+
+from app.tax_engine.invariants import (
+    bracket_tax,
+    check_bracket_invariants,
+    check_continuity_at_edges,
+    check_effective_within_marginal,
+    check_monotonic,
+    marginal_rate,
+)
+from app.tax_engine.tax_calculator import FilingStatus, TaxBrackets
+
+STATUSES = [status.value for status in FilingStatus]
+
+
+def random_incomes(seed, count=150, top=1_000_000):
+    """Incomes spread over every bracket, in cents; the seed keeps failures reproducible"""
+    rng = random.Random(seed)
+    return [Decimal(rng.randrange(0, top * 100)) / 100 for _ in range(count)]
+
+
+@pytest.mark.parametrize("filing_status", STATUSES)
+def test_bracket_invariants_hold(filing_status):
+    for seed in range(3):
+        assert check_bracket_invariants(filing_status, random_incomes(seed)) == []
+
+
+def test_marginal_rate_and_edges():
+    assert marginal_rate(Decimal("11599.99"), "single") == Decimal("0.10")
+    assert marginal_rate(Decimal("11600"), "single") == Decimal("0.12")
+    assert marginal_rate(Decimal("5000000"), "married_joint") == Decimal("0.37")
+    assert bracket_tax(Decimal("60400"), "single") == Decimal("8341.00")
+
+
+def test_checks_catch_broken_math(monkeypatch):
+    incomes = [Decimal(n) for n in (5000, 20000, 50000)]
+
+    def falls_back(income, filing_status):
+        return bracket_tax(income, filing_status) - (4000 if income > 40000 else 0)
+
+    assert len(check_monotonic("single", incomes, tax=falls_back)) == 1
+
+    def cliff(income, filing_status):
+        return bracket_tax(income, filing_status) + (100 if income >= Decimal("47150") else 0)
+
+    assert check_continuity_at_edges("single", tax=cliff) == [
+        "single: tax jumps $100.00 between $47,149.99 and $47,150.00"
+    ]
+
+    # Rates that go down: the effective rate ends up above the marginal one
+    monkeypatch.setitem(
+        TaxBrackets.BRACKETS_2024, FilingStatus.SINGLE, [(Decimal("10000"), Decimal("0.30")), (None, Decimal("0.10"))]
+    )
+    assert check_effective_within_marginal("single", incomes)
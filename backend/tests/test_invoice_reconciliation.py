@@ -0,0 +1,66 @@
+"""Tests for reconciling invoices with 1099-NEC forms."""
+from app.services.invoice_reconciliation import normalize_client, reconcile_invoices
+
+
+def invoice(invoice_id, client, amount, issued, paid=None, expect_1099=True):
+    return {"id": invoice_id, "client": client, "amount": amount, "issue_date": issued,
+            "paid_date": paid, "expect_1099": expect_1099}
+
+
+def form_1099_nec(document_id, payer, amount, tax_year=2024):
+    return {
+        "document_id": document_id,
+        "tax_year": tax_year,
+        "uploaded_at": "2025-02-01T00:00:00",
+        "extraction": {"form": "1099-NEC", "fields": {
+            "payer_name": {"value": payer}, "nonemployee_compensation": {"value": amount},
+        }},
+    }
+
+
+def test_normalize_client():
+    assert normalize_client("Acme, Inc.") == "acme"
+    assert normalize_client("ACME LLC") == "acme"
+    assert normalize_client("Blue Sky Design Co.") == "blue sky design"
+
+
+def test_reconcile_statuses():
+    invoices = [
+        invoice("inv_1", "Acme Inc", 2000, "2024-02-01", "2024-02-20"),
+        invoice("inv_2", "Acme Inc", 1500, "2023-12-10", "2024-01-08"),
+        invoice("inv_3", "Globex", 3000, "2024-05-01", "2024-05-15"),
+        invoice("inv_4", "Initech", 4000, "2024-06-01", "2024-06-30"),
+        invoice("inv_5", "Small Client", 300, "2024-07-01", "2024-07-10"),
+        invoice("inv_6", "Acme Inc", 900, "2024-12-20"),
+        invoice("inv_7", "Acme Inc", 700, "2024-12-01", "2025-01-10"),
+    ]
+    documents = [
+        form_1099_nec("doc_1", "ACME, INC.", 3500),
+        form_1099_nec("doc_2", "Globex Corporation", 2500),
+        form_1099_nec("doc_3", "Umbrella LLC", 1000),
+        form_1099_nec("doc_4", "Acme Inc", 9999, tax_year=2023),
+        {"document_id": "doc_5", "tax_year": 2024, "extraction": {"form": "W-2", "fields": {}}},
+    ]
+    report = reconcile_invoices(invoices, documents, 2024)
+    rows = {row["client"]: row for row in report["clients"]}
+
+    assert rows["Acme Inc"]["status"] == "matched"
+    assert rows["Acme Inc"]["invoice_ids"] == ["inv_1", "inv_2"]
+    assert rows["Acme Inc"]["document_ids"] == ["doc_1"]
+    assert rows["Globex"]["status"] == "mismatch"
+    assert rows["Globex"]["difference"] == 500.0
+    assert rows["Initech"]["status"] == "missing_1099"
+    assert rows["Small Client"]["status"] == "no_1099_expected"
+    assert rows["Umbrella LLC"]["status"] == "unmatched_1099"
+
+    assert report["total_invoiced"] == 10800.0
+    assert report["total_reported"] == 7000.0
+    assert [i["id"] for i in report["open_invoices"]] == ["inv_6"]
+    assert report["open_amount"] == 900.0
+    assert report["issues"] == 3
+
+
+def test_reconcile_not_expected_and_empty():
+    report = reconcile_invoices([invoice("inv_1", "Friend", 5000, "2024-03-01", "2024-03-02", expect_1099=False)], [], 2024)
+    assert report["clients"][0]["status"] == "no_1099_expected"
+    assert reconcile_invoices([], [], 2024)["clients"] == []
@@ -0,0 +1,56 @@
+"""Tests for invoice storage."""
+import pytest
+
+from app.utils.invoice_store import InvoiceStore
+
+
+@pytest.fixture
+def store(tmp_path):
+    return InvoiceStore(str(tmp_path / "invoices.json"))
+
+
+def test_create_and_list(store):
+    first = store.create_invoice(" Acme Inc ", 2500, "2024-03-01", paid_date="2024-03-20")
+    second = store.create_invoice("Globex", 800, "2024-12-15", expect_1099=False)
+    store.create_invoice("Acme Inc", 1200, "2023-11-01", paid_date="2024-01-05")
+
+    assert first["id"].startswith("inv_")
+    assert first["client"] == "Acme Inc"
+    assert store.get_invoice(second["id"])["expect_1099"] is False
+    assert [i["amount"] for i in store.list_invoices()] == [1200, 2500, 800]
+    assert len(store.list_invoices(year=2024)) == 3
+    assert len(store.list_invoices(year=2023)) == 1
+    assert [i["id"] for i in store.list_invoices(status="open")] == [second["id"]]
+    assert len(store.list_invoices(client="acme inc")) == 2
+    with pytest.raises(ValueError):
+        store.list_invoices(status="overdue")
+
+
+@pytest.mark.parametrize("args", [
+    ("", 100, "2024-01-01"),
+    ("Acme", -5, "2024-01-01"),
+    ("Acme", 100, "2024-13-01"),
+    ("Acme", 100, "2024-02-01", "2024-01-15"),
+])
+def test_create_rejects_invalid(store, args):
+    with pytest.raises(ValueError):
+        store.create_invoice(*args)
+    assert store.list_invoices() == []
+
+
+def test_update_and_delete(store):
+    invoice = store.create_invoice("Acme", 500, "2024-05-01")
+    updated = store.update_invoice(invoice["id"], paid_date="2024-05-30", amount=550)
+    assert (updated["paid_date"], updated["amount"]) == ("2024-05-30", 550)
+    assert store.update_invoice(invoice["id"], paid_date=None)["paid_date"] is None
+
+    with pytest.raises(ValueError):
+        store.update_invoice(invoice["id"], id="inv_other")
+    with pytest.raises(ValueError):
+        store.update_invoice(invoice["id"], paid_date="2024-04-01")
+    with pytest.raises(KeyError):
+        store.update_invoice("inv_missing", amount=1)
+
+    assert store.delete_invoice(invoice["id"]) is True
+    assert store.delete_invoice(invoice["id"]) is False
+    assert store.get_invoice(invoice["id"]) is None
@@ -0,0 +1,133 @@
+"""Tests for the persistent background job queue."""
+import asyncio
+import time
+
+import pytest
+
+from app.services import job_queue as job_queue_module
+from app.services.job_queue import JobQueue
+from app.services.progress import ProgressBus
+
+
+@pytest.fixture
+def queue(tmp_path):
+    queue = JobQueue(storage_dir=str(tmp_path / "jobs"), progress=ProgressBus())
+    queue.register("echo", lambda payload, progress: {"echo": payload["value"]})
+    return queue
+
+
+def test_enqueue_and_run(queue):
+    job = queue.enqueue("echo", {"value": 42}, description="Echo")
+    assert job["status"] == "queued"
+    assert job["job_id"].startswith("job_")
+    with pytest.raises(ValueError):
+        queue.enqueue("unknown")
+
+    assert asyncio.run(queue.run_pending()) == 1
+    done = queue.get_job(job["job_id"])
+    assert (done["status"], done["attempts"], done["result"], done["percent"]) == ("completed", 1, {"echo": 42}, 100)
+    assert queue.progress.get_job(job["job_id"])["status"] == "completed"
+
+
+def test_failed_job_retries_with_backoff(queue, monkeypatch):
+    calls = []
+
+    def flaky(payload, progress):
+        calls.append(1)
+        if len(calls) < 2:
+            raise RuntimeError("disk busy")
+        return "ok"
+
+    queue.register("flaky", flaky)
+    monkeypatch.setattr(job_queue_module, "RETRY_DELAY_SECONDS", 3600)
+    job = queue.enqueue("flaky", max_attempts=2)
+
+    asyncio.run(queue.run_pending())
+    waiting = queue.get_job(job["job_id"])
+    assert (waiting["status"], waiting["attempts"], waiting["last_error"]) == ("queued", 1, "disk busy")
+    assert waiting["run_after"] > waiting["updated_at"]
+    # Not due yet
+    assert asyncio.run(queue.run_pending()) == 0
+
+    stored = queue._load(job["job_id"])
+    stored["run_after"] = stored["created_at"]
+    queue._save(stored)
+    asyncio.run(queue.run_pending())
+    assert queue.get_job(job["job_id"])["status"] == "completed"
+
+
+def test_gives_up_after_max_attempts(queue):
+    def broken(payload, progress):
+        raise RuntimeError("corrupt file")
+
+    queue.register("broken", broken)
+    job = queue.enqueue("broken", max_attempts=1)
+    asyncio.run(queue.run_pending())
+    failed = queue.get_job(job["job_id"])
+    assert (failed["status"], failed["last_error"]) == ("failed", "corrupt file")
+
+    with pytest.raises(ValueError):
+        queue.retry_job(queue.enqueue("echo", {"value": 1})["job_id"])
+    assert queue.retry_job(job["job_id"])["status"] == "queued"
+    assert queue.get_job(job["job_id"])["attempts"] == 0
+
+
+def test_cancel_queued_and_running(queue):
+    job = queue.enqueue("echo", {"value": 1})
+    assert queue.cancel_job(job["job_id"])["status"] == "cancelled"
+    with pytest.raises(ValueError):
+        queue.cancel_job(job["job_id"])
+    assert asyncio.run(queue.run_pending()) == 0
+
+    def slow(payload, progress):
+        # Cancelled from inside the handler so the test doesn't need a second thread
+        queue.cancel_job(queue.list_jobs(status="running")[0]["job_id"])
+        progress({"percent": 50})
+        return "finished anyway"
+
+    queue.register("slow", slow)
+    running = queue.enqueue("slow")
+    asyncio.run(queue.run_pending())
+    assert queue.get_job(running["job_id"])["status"] == "cancelled"
+
+
+def test_jobs_survive_restart(queue, tmp_path):
+    job = queue.enqueue("echo", {"value": 7})
+    claimed = queue._claim_next()
+    assert claimed["status"] == "running"
+
+    # A new process finds the job still marked running and requeues it
+    restarted = JobQueue(storage_dir=str(tmp_path / "jobs"))
+    restarted.register("echo", lambda payload, progress: payload["value"])
+    assert restarted.recover() == 1
+    assert restarted.get_job(job["job_id"])["attempts"] == 0
+    asyncio.run(restarted.run_pending())
+    assert restarted.get_job(job["job_id"])["result"] == 7
+
+
+def test_workers_respect_concurrency_limit(tmp_path):
+    queue = JobQueue(storage_dir=str(tmp_path / "jobs"), max_concurrent=2, poll_seconds=0.01)
+    active = []
+    peak = []
+
+    def work(payload, progress):
+        active.append(1)
+        peak.append(len(active))
+        time.sleep(0.05)
+        active.pop()
+        return payload["n"]
+
+    queue.register("work", work)
+
+    async def run():
+        await queue.start()
+        jobs = [queue.enqueue("work", {"n": n}) for n in range(5)]
+        while any(queue.get_job(job["job_id"])["status"] != "completed" for job in jobs):
+            await asyncio.sleep(0.01)
+        await queue.stop()
+        return jobs
+
+    jobs = asyncio.run(run())
+    assert max(peak) <= 2
+    assert [queue.get_job(job["job_id"])["result"] for job in jobs] == list(range(5))
+    assert [job["kind"] for job in queue.list_jobs(kind="work", status="completed")] == ["work"] * 5
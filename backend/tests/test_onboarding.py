@@ -0,0 +1,146 @@
+"""Tests for the first-time setup wizard."""
+import pytest
+
+from app.services.onboarding import ONBOARDING_STEPS, Onboarding
+from app.utils.app_lock import AppLock
+from app.utils.return_store import ReturnStore
+from app.utils.secret_store import API_KEY_NAME, SecretStore
+from app.utils.settings_store import SettingsStore
+
+TXF = """V042
+ATurboTax Deluxe
+D02/01/2024
+^
+TD
+N460
+C1
+L1
+$85,000.00
+PAcme Corp
+^
+TD
+N461
+C1
+L1
+$12000.00
+PAcme Corp
+^
+"""
+
+
+def make_wizard(path):
+    return Onboarding(
+        state_path=str(path / "onboarding.json"),
+        app_lock=AppLock(lock_path=str(path / "lock.json")),
+        settings_store=SettingsStore(settings_path=str(path / "settings.json")),
+        secret_store=SecretStore(fallback_path=str(path / "secrets.json"), key_path=str(path / "secrets.key"), keyring=None),
+        return_store=ReturnStore(storage_dir=str(path / "returns")),
+    )
+
+
+@pytest.fixture
+def wizard(tmp_path):
+    return make_wizard(tmp_path)
+
+
+def statuses(progress):
+    return {step["step"]: step["status"] for step in progress["steps"]}
+
+
+def test_starts_at_the_passphrase(wizard):
+    progress = wizard.status()
+    assert [step["step"] for step in progress["steps"]] == ONBOARDING_STEPS
+    assert progress["current_step"] == "passphrase"
+    assert progress["complete"] is False
+    assert progress["started_at"] is None
+
+
+def test_steps_run_in_order(wizard):
+    with pytest.raises(ValueError, match="passphrase step first"):
+        wizard.set_profile("Pat Doe")
+    with pytest.raises(ValueError, match="at least 8"):
+        wizard.set_passphrase("short")
+    progress = wizard.set_passphrase("correct horse")
+    assert wizard.app_lock.enabled
+    assert progress["current_step"] == "profile"
+    with pytest.raises(ValueError, match="profile step first"):
+        wizard.set_defaults(2024, "NJ")
+
+
+def test_full_run_without_import(wizard):
+    wizard.set_passphrase("correct horse")
+    wizard.set_profile("Pat Doe", "married_joint", spouse_name="Sam Doe", city="Newark")
+    progress = wizard.set_defaults(2024, "nj")
+    settings = wizard.settings_store.get_settings()
+    assert (settings.default_tax_year, settings.default_state) == (2024, "NJ")
+    assert wizard.settings_store.get_settings_history()[0]["changed_by"] == "onboarding"
+    assert progress["current_step"] == "api_key"
+
+    wizard.set_api_key("sk-ant-test")
+    assert wizard.secret_store.get(API_KEY_NAME) == "sk-ant-test"
+    progress = wizard.skip("import_prior_year")
+    assert progress["complete"] is True
+    assert statuses(progress)["import_prior_year"] == "skipped"
+
+    # Finishing starts the default year's return from the profile
+    tax_return = wizard.return_store.get_return(progress["return_id"])
+    assert tax_return["tax_year"] == 2024
+    assert tax_return["filing_status"] == "married_joint"
+    assert tax_return["taxpayer"] == {"name": "Pat Doe", "spouse_name": "Sam Doe", "city": "Newark", "state": "NJ"}
+
+
+def test_resumes_after_restart(tmp_path):
+    wizard = make_wizard(tmp_path)
+    wizard.set_passphrase("correct horse")
+    wizard.set_profile("Pat Doe")
+
+    resumed = make_wizard(tmp_path)
+    progress = resumed.status()
+    assert progress["current_step"] == "defaults"
+    assert progress["profile"] == {"name": "Pat Doe", "filing_status": "single"}
+    # The passphrase already exists, so the step can be repeated without one
+    assert resumed.set_passphrase()["current_step"] == "defaults"
+
+
+def test_import_prior_year(wizard):
+    wizard.set_passphrase("correct horse")
+    wizard.set_profile("Pat Doe")
+    wizard.set_defaults(2024)
+    wizard.skip("api_key")
+    progress = wizard.import_prior_year(TXF)
+
+    assert progress["complete"] is True
+    prior = wizard.return_store.get_return(progress["prior_return_id"])
+    current = wizard.return_store.get_return(progress["return_id"])
+    assert prior["tax_year"] == 2023 and current["tax_year"] == 2024
+    assert current["cloned_from"] == prior["return_id"]
+    assert current["income_sources"][0]["prior_year_amount"] == 85000
+    assert progress["import_summary"]["imported"]["income_sources"] == 1
+
+
+def test_skip_and_validation(wizard):
+    wizard.set_passphrase("correct horse")
+    with pytest.raises(ValueError, match="can't be skipped"):
+        wizard.skip("profile")
+    with pytest.raises(ValueError, match="Unknown step"):
+        wizard.skip("welcome")
+    with pytest.raises(ValueError, match="filing status"):
+        wizard.set_profile("Pat Doe", "joint")
+    with pytest.raises(ValueError, match="spouse's name"):
+        wizard.set_profile("Pat Doe", "married_joint")
+    with pytest.raises(ValueError, match="Unknown profile fields"):
+        wizard.set_profile("Pat Doe", ssn="123456789")
+    wizard.set_profile("Pat Doe")
+    with pytest.raises(ValueError, match="Invalid state"):
+        wizard.set_defaults(2024, "New Jersey")
+    with pytest.raises(ValueError, match="Tax year"):
+        wizard.set_defaults(1999)
+
+
+def test_reset_keeps_what_was_saved(wizard):
+    wizard.set_passphrase("correct horse")
+    wizard.set_profile("Pat Doe")
+    progress = wizard.reset()
+    assert progress["current_step"] == "passphrase"
+    assert progress["profile"] is None
+    assert wizard.app_lock.enabled
@@ -0,0 +1,65 @@
+"""Tests for merging page images and splitting PDFs."""
+import pytest
+
+from app.documents.pages import PAGE_BREAK, merge_images, page_texts, split_pdf
+from app.utils.document_store import DocumentStore
+from tests.test_previews import make_pdf, make_png
+
+
+@pytest.fixture
+def store(tmp_path):
+    """Create a document store with a temp directory."""
+    return DocumentStore(storage_dir=str(tmp_path / "documents"))
+
+
+def test_page_texts_split_on_form_feeds():
+    assert page_texts({"ocr_text": f"page one{PAGE_BREAK}page two"}) == ["page one", "page two"]
+    assert page_texts({}) == []
+
+
+def test_merge_validates_inputs(store):
+    pdf = store.upload_document("stack.pdf", content=b"%PDF-1.4")
+    with pytest.raises(ValueError, match="at least two"):
+        merge_images(store, [pdf["document_id"]])
+    photo = store.upload_document("page1.jpg", content=b"jpeg")
+    with pytest.raises(ValueError, match="not an image"):
+        merge_images(store, [photo["document_id"], pdf["document_id"]])
+    with pytest.raises(KeyError):
+        merge_images(store, [photo["document_id"], "doc_0000000000000000"])
+
+
+def test_split_requires_pdf(store):
+    photo = store.upload_document("page1.jpg", content=b"jpeg")
+    with pytest.raises(ValueError, match="Only PDF"):
+        split_pdf(store, photo["document_id"])
+
+
+def test_merge_images_into_pdf(store):
+    pytest.importorskip("PIL.Image")
+    first = store.upload_document("w2_page1.png", content=make_png(), document_type="W-2", tax_year=2024)
+    second = store.upload_document("w2_page2.png", content=make_png(400, 300), document_type="W-2")
+    store.save_extraction(first["document_id"], {"form": "W-2", "fields": {}}, text="front")
+    store.save_extraction(second["document_id"], {"form": "W-2", "fields": {}}, text="back")
+
+    merged = merge_images(store, [first["document_id"], second["document_id"]])
+    assert merged["mime_type"] == "application/pdf"
+    assert merged["filename"] == "w2_page1.pdf"
+    assert merged["document_type"] == "W-2"
+    assert page_texts(merged) == ["front", "back"]
+    assert store.read_document(merged["document_id"]).startswith(b"%PDF")
+    assert [d["document_id"] for d in store.list_documents()] == [merged["document_id"]]
+
+
+def test_split_pdf_keeps_page_text(store):
+    pytest.importorskip("pypdfium2")
+    stack = store.upload_document("scan.pdf", content=make_pdf(pages=3), return_id="ret_0123456789abcdef")
+    store.save_extraction(stack["document_id"], {"form": "W-2", "fields": {}}, text=PAGE_BREAK.join("abc"))
+
+    parts = split_pdf(store, stack["document_id"], ranges=[(1, 2), (3, 3)])
+    assert [p["filename"] for p in parts] == ["scan (pages 1-2).pdf", "scan (page 3).pdf"]
+    assert [page_texts(p) for p in parts] == [["a", "b"], ["c"]]
+    assert all(p["return_id"] == "ret_0123456789abcdef" for p in parts)
+    assert store.get_document(stack["document_id"])["archived"] is True
+
+    with pytest.raises(ValueError, match="Invalid page range"):
+        split_pdf(store, parts[0]["document_id"], ranges=[(2, 5)])
@@ -0,0 +1,108 @@
+"""Tests for comparing itemized deductions with SOI averages."""
+import json
+
+import pytest
+
+from app.tax_engine.peer_comparison import compare_with_peers, load_soi_dataset, peer_band
+from app.tax_engine.risk import assess_audit_risk
+
+DATASET = {
+    "source": "Test table",
+    "tax_year": 2021,
+    "bands": [
+        {"label": "Under $50,000", "min": None, "max": 50000, "averages": {}},
+        {
+            "label": "$50,000 under $100,000", "min": 50000, "max": 100000,
+            "averages": {"medical": 8000, "taxes": 6000, "interest": 7000, "charitable": 3000, "total": 20000},
+        },
+        {"label": "$100,000 or more", "min": 100000, "max": None, "averages": {"total": 40000}},
+    ],
+}
+
+
+def make_return(wages=80000, deductions=None):
+    return {
+        "return_id": "ret_0000000000000000",
+        "tax_year": 2024,
+        "filing_status": "single",
+        "income_sources": [{"id": "inc_1", "type": "wages", "amount": wages, "withholding": 0}],
+        "deductions": deductions or [],
+        "businesses": [],
+    }
+
+
+def itemizer():
+    return make_return(deductions=[
+        {"id": "ded_1", "category": "medical", "amount": 30000},
+        {"id": "ded_2", "category": "state_local_tax", "amount": 7000},
+        {"id": "ded_3", "category": "mortgage_interest", "amount": 9000},
+    ])
+
+
+def by_category(result):
+    return {c["category"]: c for c in result["categories"]}
+
+
+def test_flags_against_band_average():
+    result = compare_with_peers(itemizer(), DATASET)
+    assert result["band"]["label"] == "$50,000 under $100,000"
+    categories = by_category(result)
+    # Medical is compared after the 7.5% of AGI floor
+    assert categories["medical"]["amount"] == 24000
+    assert categories["medical"]["ratio"] == 3
+    assert {name: c["flag"] for name, c in categories.items()} == {
+        "medical": "unusually_high",
+        "taxes": "typical",
+        "interest": "above_average",
+        "charitable": "not_claimed",
+        "total": "unusually_high",
+    }
+    assert result["flagged"] == ["medical", "total"]
+    assert result["itemizes"] is True
+    assert any("Unusually high for this income: medical" in note for note in result["notes"])
+    assert any("tax year 2021" in note for note in result["notes"])
+
+
+def test_missing_averages_and_standard_deduction():
+    result = compare_with_peers(make_return(40000, [{"id": "ded_1", "category": "charitable", "amount": 2000}]), DATASET)
+    assert by_category(result)["charitable"]["flag"] == "no_data"
+    assert result["flagged"] == []
+    assert any("no averages for the Under $50,000 band" in note for note in result["notes"])
+    assert any("standard deduction is larger" in note for note in result["notes"])
+
+    result = compare_with_peers(make_return(), DATASET)
+    assert result["notes"][0] == "The return has no itemized deductions to compare"
+
+
+def test_bands():
+    assert peer_band(DATASET, 0)["label"] == "Under $50,000"
+    assert peer_band(DATASET, 100000)["label"] == "$100,000 or more"
+    with pytest.raises(ValueError, match="no band"):
+        peer_band({"bands": [{"label": "Low", "min": 0, "max": 10}]}, 50)
+
+
+def test_embedded_dataset_covers_every_agi():
+    dataset = load_soi_dataset()
+    bands = dataset["bands"]
+    assert bands[0]["min"] is None and bands[-1]["max"] is None
+    assert all(band["max"] == following["min"] for band, following in zip(bands, bands[1:]))
+    assert compare_with_peers(itemizer(), dataset)["band"]["label"] == "$75,000 under $100,000"
+
+
+def test_load_dataset_file(tmp_path):
+    path = tmp_path / "soi.json"
+    path.write_text(json.dumps(DATASET))
+    assert load_soi_dataset(str(path))["source"] == "Test table"
+    path.write_text(json.dumps({"source": "Empty"}))
+    with pytest.raises(ValueError, match="no AGI bands"):
+        load_soi_dataset(str(path))
+    with pytest.raises(ValueError, match="Can't read"):
+        load_soi_dataset(str(tmp_path / "missing.json"))
+
+
+def test_audit_risk_finding():
+    result = assess_audit_risk(itemizer(), peer_dataset=DATASET)
+    findings = [f for f in result["findings"] if f["code"] == "deductions_above_peers"]
+    # Only the medical category; the total isn't a finding of its own
+    assert [f["category"] for f in findings] == ["medical"]
+    assert findings[0]["title"] == "Medical and dental expenses are 3 times the average for AGI of $50,000 under $100,000"
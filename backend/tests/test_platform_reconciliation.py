@@ -0,0 +1,93 @@
+"""Tests for reconciling 1099-K forms with recorded business income."""
+from app.services.platform_reconciliation import reconcile_platform_income
+
+
+def form_1099_k(document_id, platform, gross, tax_year=2024):
+    return {
+        "document_id": document_id,
+        "tax_year": tax_year,
+        "uploaded_at": "2025-02-01T00:00:00",
+        "extraction": {"form": "1099-K", "fields": {
+            "payer_name": {"value": platform}, "gross_amount": {"value": gross},
+        }},
+    }
+
+
+def make_return(income_sources=(), businesses=(), allocations=()):
+    return {
+        "return_id": "ret_0000000000000000",
+        "tax_year": 2024,
+        "income_sources": list(income_sources),
+        "businesses": list(businesses),
+        "platform_allocations": list(allocations),
+    }
+
+
+def self_employment(source_id, description, amount, **fields):
+    return {"id": source_id, "type": "self_employment", "description": description, "amount": amount, **fields}
+
+
+def test_reconcile_statuses():
+    tax_return = make_return(
+        income_sources=[
+            self_employment("inc_1", "RideShare LLC", 14000),
+            self_employment("inc_2", "Etsy", 2500),
+            self_employment("inc_3", "TaskApp", 3000),
+            self_employment("inc_4", "TaskApp Inc.", 2000, form="1099-NEC"),
+            {"id": "inc_5", "type": "wages", "description": "Gig Market", "amount": 50000},
+        ],
+    )
+    documents = [
+        form_1099_k("doc_1", "RideShare, LLC", 14000),
+        form_1099_k("doc_2", "Etsy Inc", 3100),
+        form_1099_k("doc_3", "TaskApp", 3000),
+        form_1099_k("doc_4", "Gig Market", 1800),
+        form_1099_k("doc_5", "RideShare", 9999, tax_year=2023),
+        {"document_id": "doc_6", "tax_year": 2024, "extraction": {"form": "1099-NEC", "fields": {}}},
+    ]
+    report = reconcile_platform_income(tax_return, documents)
+    rows = {row["label"]: row for row in report["platforms"]}
+
+    assert rows["RideShare, LLC"]["status"] == "matched"
+    assert rows["RideShare, LLC"]["document_ids"] == ["doc_1"]
+    assert rows["Etsy Inc"]["status"] == "less_recorded"
+    assert rows["Etsy Inc"]["difference"] == -600.0
+    # The same gig paid through the app and again on a 1099-NEC
+    assert rows["TaskApp"]["status"] == "possible_double_count"
+    assert rows["TaskApp"]["source_ids"] == ["inc_3", "inc_4"]
+    assert rows["Gig Market"]["status"] == "unreported"
+
+    assert report["total_gross"] == 21900.0
+    assert report["total_recorded"] == 21500.0
+    assert report["issues"] == 3
+
+
+def test_personal_sales_and_business_allocation():
+    tax_return = make_return(
+        income_sources=[self_employment("inc_1", "Booth sales", 1200, business_id="biz_1")],
+        businesses=[{"id": "biz_1", "name": "Pottery Studio", "gross_receipts": 4000}],
+        allocations=[
+            {"document_id": "doc_1", "personal_sales": 900, "personal_cost": 1400, "business_id": None},
+            {"document_id": "doc_2", "personal_sales": 300, "personal_cost": 100, "business_id": "biz_1"},
+            {"document_id": "doc_3", "personal_sales": 0, "personal_cost": 0, "business_id": "biz_1"},
+        ],
+    )
+    documents = [
+        form_1099_k("doc_1", "PayApp", 900),
+        form_1099_k("doc_2", "Etsy", 3500),
+        form_1099_k("doc_3", "Square", 2000),
+    ]
+    report = reconcile_platform_income(tax_return, documents)
+    rows = {row["label"]: row for row in report["platforms"]}
+
+    # Sold a couch at a loss: not business income and nothing taxable
+    assert rows["PayApp"]["status"] == "personal"
+    # Both forms go to the studio: $5,200 of business share against $5,200 recorded
+    studio = rows["Pottery Studio"]
+    assert studio["document_ids"] == ["doc_2", "doc_3"]
+    assert (studio["business_share"], studio["recorded"], studio["status"]) == (5200.0, 5200.0, "matched")
+
+    personal = {row["document_id"]: row for row in report["personal_sales"]}
+    assert personal["doc_1"]["gain"] == 0 and "8z" in personal["doc_1"]["note"]
+    assert personal["doc_2"]["gain"] == 200.0 and "Form 8949" in personal["doc_2"]["note"]
+    assert report["issues"] == 0
@@ -0,0 +1,54 @@
+"""Tests for the practitioner dashboard."""
+from datetime import date
+
+from app.services.practice_dashboard import build_dashboard
+
+
+def make_return(return_id, client_id=None, tax_year=2024, status="draft", **fields):
+    tax_return = {"return_id": return_id, "tax_year": tax_year, "status": status, "state_returns": [], **fields}
+    if client_id:
+        tax_return["client_id"] = client_id
+    return tax_return
+
+
+CLIENTS = [
+    {"id": "cli_a", "name": "Adams", "engagement_status": "in_preparation"},
+    {"id": "cli_b", "name": "Baker", "engagement_status": "completed"},
+    {"id": "cli_c", "name": "Cole", "engagement_status": "in_preparation"},
+]
+
+
+def test_dashboard_rows_and_counts():
+    returns = [
+        make_return("ret_a24", "cli_a"),
+        make_return("ret_a23", "cli_a", tax_year=2023, status="filed"),
+        make_return("ret_b24", "cli_b", status="filed"),
+        make_return("ret_orphan"),
+    ]
+    documents = [
+        {"document_id": "doc_1", "return_id": "ret_a24"},
+        {"document_id": "doc_2", "client_id": "cli_a"},
+        {"document_id": "doc_3", "return_id": "ret_b24"},
+    ]
+    dashboard = build_dashboard(CLIENTS, returns, documents, as_of=date(2025, 4, 1), within_days=30)
+    rows = {row["client_id"]: row for row in dashboard["clients"]}
+
+    assert [r["return_id"] for r in rows["cli_a"]["returns"]] == ["ret_a24", "ret_a23"]
+    assert rows["cli_a"]["document_count"] == 2
+    assert rows["cli_a"]["next_deadline"]["due_date"] == "2025-04-15"
+    assert rows["cli_a"]["next_deadline"]["return_id"] == "ret_a24"
+    assert rows["cli_b"]["next_deadline"] is None
+    assert rows["cli_c"]["returns"] == []
+
+    assert dashboard["engagement_counts"] == {"in_preparation": 2, "completed": 1}
+    assert dashboard["return_status_counts"] == {"draft": 1, "filed": 2}
+    assert {d["client_name"] for d in dashboard["upcoming_deadlines"]} == {"Adams"}
+    assert dashboard["unassigned_returns"] == ["ret_orphan"]
+
+
+def test_dashboard_overdue():
+    returns = [make_return("ret_a24", "cli_a")]
+    dashboard = build_dashboard(CLIENTS[:1], returns, [], as_of=date(2025, 5, 1))
+    assert dashboard["clients"][0]["overdue_deadlines"] == 2
+    assert dashboard["overdue_count"] == 2
+    assert all(d["overdue"] for d in dashboard["upcoming_deadlines"])
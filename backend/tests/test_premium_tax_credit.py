@@ -0,0 +1,139 @@
+"""Tests for the 1095-A parser and the Form 8962 engine."""
+from decimal import Decimal
+
+import pytest
+
+from app.documents.parsers.form_1095_a import Form1095A, MonthlyCoverage, parse_1095_a
+from app.tax_engine.premium_tax_credit import PremiumTaxCreditCalculator
+
+MONTHS = ["January", "February", "March", "April", "May", "June",
+          "July", "August", "September", "October", "November", "December"]
+
+
+def make_1095a_text(premium="450.00", slcsp="520.00", advance="400.00", annual=True, covered=12):
+    lines = [
+        "Form 1095-A Health Insurance Marketplace Statement 2024",
+        "2 Marketplace-assigned policy number 99887766",
+        "3 Policy issuer's name Example Health Plan",
+    ]
+    for i, month in enumerate(MONTHS):
+        row = f"{21 + i} {month}"
+        if i < covered:
+            row += f" {premium} {slcsp} {advance}"
+        lines.append(row)
+    if annual:
+        total = lambda v: f"{Decimal(v) * covered:,.2f}"
+        lines.append(f"33 Annual Totals {total(premium)} {total(slcsp)} {total(advance)}")
+    return "\n".join(lines)
+
+
+def make_statement(premium, slcsp, advance, months=12):
+    return Form1095A(months=[
+        MonthlyCoverage(m, Decimal(premium), Decimal(slcsp), Decimal(advance)) if m <= months
+        else MonthlyCoverage(m)
+        for m in range(1, 13)
+    ])
+
+
+@pytest.fixture
+def calculator():
+    return PremiumTaxCreditCalculator(tax_year=2024)
+
+
+# ── 1095-A parser ─────────────────────────────────────────────
+
+def test_parses_monthly_columns():
+    result = parse_1095_a(make_1095a_text())
+    record = result.record
+    assert record.policy_number == "99887766"
+    assert record.issuer_name == "Example Health Plan"
+    assert len(record.months) == 12
+    assert record.months[0].enrollment_premium == Decimal("450.00")
+    assert record.months[0].slcsp_premium == Decimal("520.00")
+    assert record.months[11].advance_ptc == Decimal("400.00")
+    assert record.total_premium == Decimal("5400.00")
+    assert result.fields["month_01"].confidence > 0.95
+    assert result.warnings == []
+
+
+def test_uncovered_months_are_zero():
+    record = parse_1095_a(make_1095a_text(covered=6)).record
+    assert record.months[5].enrollment_premium == Decimal("450.00")
+    assert record.months[6].enrollment_premium == Decimal("0")
+    assert record.total_advance_ptc == Decimal("2400.00")
+
+
+def test_annual_total_mismatch_is_flagged():
+    text = make_1095a_text().replace("33 Annual Totals 5,400.00", "33 Annual Totals 9,999.00")
+    result = parse_1095_a(text)
+    assert result.fields["month_01"].confidence <= 0.6
+    assert any("annual totals" in w for w in result.warnings)
+
+
+def test_partial_row_is_flagged():
+    text = make_1095a_text(annual=False).replace("21 January 450.00 520.00 400.00", "21 January 450.00")
+    result = parse_1095_a(text)
+    assert result.record.months[0].enrollment_premium == Decimal("0")
+    assert any("January" in w for w in result.warnings)
+
+
+# ── Form 8962 engine ──────────────────────────────────────────
+
+def test_poverty_line_and_applicable_figure(calculator):
+    assert calculator.poverty_line(1) == Decimal("14580")
+    assert calculator.poverty_line(4) == Decimal("30000")
+    assert calculator.poverty_line(1, "AK") == Decimal("18210")
+    assert calculator.applicable_figure(140) == Decimal("0")
+    assert calculator.applicable_figure(200) == Decimal("0.0200")
+    assert calculator.applicable_figure(350) == Decimal("0.0725")
+    assert calculator.applicable_figure(450) == Decimal("0.085")
+
+
+def test_net_credit_when_advance_was_too_low(calculator):
+    result = calculator.calculate(Decimal("30000"), 1, "single", [make_statement("450", "520", "400")])
+    assert result["fpl_percent"] == 205
+    assert result["applicable_figure"] == pytest.approx(0.022)
+    assert result["monthly_contribution"] == 55
+    assert result["total_premium_tax_credit"] == pytest.approx(5400)
+    assert result["net_premium_tax_credit"] == pytest.approx(600)
+    assert result["excess_advance_repayment"] == 0
+
+
+def test_excess_advance_is_capped_below_400_percent(calculator):
+    result = calculator.calculate(Decimal("40000"), 1, "single", [make_statement("450", "520", "500")])
+    assert result["fpl_percent"] == 274
+    assert result["excess_advance_ptc"] == pytest.approx(1740)
+    assert result["repayment_limitation"] == 950
+    assert result["excess_advance_repayment"] == pytest.approx(950)
+
+
+def test_excess_advance_uncapped_above_400_percent(calculator):
+    result = calculator.calculate(Decimal("60000"), 1, "single", [make_statement("450", "520", "400")])
+    assert result["repayment_limitation"] is None
+    assert result["total_premium_tax_credit"] == pytest.approx(1140)
+    assert result["excess_advance_repayment"] == pytest.approx(3660)
+
+
+def test_married_separate_repays_advance(calculator):
+    result = calculator.calculate(Decimal("40000"), 2, "married_separate", [make_statement("450", "520", "100")])
+    assert result["eligible"] is False
+    assert result["total_premium_tax_credit"] == 0
+    assert result["excess_advance_repayment"] == pytest.approx(1200)
+
+
+def test_multiple_policies_share_one_slcsp(calculator):
+    statements = [make_statement("300", "600", "200"), make_statement("200", "600", "100")]
+    result = calculator.calculate(Decimal("30000"), 2, "married_joint", statements)
+    assert result["monthly"][0]["enrollment_premium"] == 500
+    assert result["monthly"][0]["slcsp_premium"] == 600
+
+
+def test_consumes_parsed_record(calculator):
+    record = parse_1095_a(make_1095a_text()).record
+    result = calculator.calculate(Decimal("30000"), 1, "single", [record])
+    assert result["net_premium_tax_credit"] == pytest.approx(600)
+
+
+def test_rejects_other_years():
+    with pytest.raises(ValueError, match="Only 2024"):
+        PremiumTaxCreditCalculator(tax_year=2023)
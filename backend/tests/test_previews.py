@@ -0,0 +1,67 @@
+"""Tests for document page previews and thumbnails."""
+import io
+
+import pytest
+
+from app.documents.previews import (
+    THUMBNAIL_SIZE,
+    PreviewUnavailableError,
+    page_count,
+    render_page,
+    render_thumbnail,
+)
+
+
+def make_png(width=800, height=600):
+    Image = pytest.importorskip("PIL.Image")
+    output = io.BytesIO()
+    Image.new("RGB", (width, height), "white").save(output, format="PNG")
+    return output.getvalue()
+
+
+def make_pdf(pages=2):
+    """Build a minimal valid PDF with blank pages."""
+    kids = " ".join(f"{3 + i} 0 R" for i in range(pages))
+    objects = ["<< /Type /Catalog /Pages 2 0 R >>", f"<< /Type /Pages /Kids [{kids}] /Count {pages} >>"]
+    objects += ["<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] >>"] * pages
+
+    body, offsets = b"%PDF-1.4\n", []
+    for number, obj in enumerate(objects, start=1):
+        offsets.append(len(body))
+        body += f"{number} 0 obj\n{obj}\nendobj\n".encode()
+    xref = len(body)
+    body += f"xref\n0 {len(objects) + 1}\n0000000000 65535 f \n".encode()
+    body += "".join(f"{o:010d} 00000 n \n" for o in offsets).encode()
+    body += f"trailer\n<< /Size {len(objects) + 1} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n".encode()
+    return body
+
+
+def test_unsupported_type():
+    with pytest.raises(PreviewUnavailableError, match="text/plain"):
+        render_page(b"hello", "text/plain")
+
+
+def test_page_numbers_start_at_one():
+    with pytest.raises(ValueError, match="start at 1"):
+        render_page(b"", "image/png", page=0)
+
+
+def test_image_thumbnail_fits_bounding_box():
+    Image = pytest.importorskip("PIL.Image")
+    thumbnail = Image.open(io.BytesIO(render_thumbnail(make_png(), "image/png")))
+    assert thumbnail.format == "PNG"
+    assert thumbnail.width <= THUMBNAIL_SIZE[0] and thumbnail.height <= THUMBNAIL_SIZE[1]
+
+
+def test_image_page_out_of_range():
+    with pytest.raises(ValueError, match="out of range"):
+        render_page(make_png(), "image/png", page=2)
+
+
+def test_pdf_pages():
+    pytest.importorskip("pypdfium2")
+    pdf = make_pdf(pages=2)
+    assert page_count(pdf, "application/pdf") == 2
+    assert render_page(pdf, "application/pdf", page=2).startswith(b"\x89PNG")
+    with pytest.raises(ValueError, match="out of range"):
+        render_page(pdf, "application/pdf", page=3)
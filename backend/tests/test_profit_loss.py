@@ -0,0 +1,111 @@
+"""Tests for the quarterly business profit and loss report."""
+import csv
+import io
+
+import pytest
+
+from app.forms.profit_loss import build_profit_loss_csv, render_profit_loss
+from app.services.formatting import Formatter
+from app.tax_engine.profit_loss import business_profit_loss
+
+
+def make_business(**overrides):
+    business = {
+        "id": "biz_1",
+        "name": "Studio",
+        "gross_receipts": 1000,
+        "expenses": [
+            {"category": "supplies", "amount": 300, "date": "2024-02-10"},
+            {"category": "supplies", "amount": 200, "date": "2024-08-01"},
+            {"category": "meals", "amount": 120, "date": "2024-05-15"},
+            {"category": "advertising", "amount": 50},
+        ],
+    }
+    business.update(overrides)
+    return business
+
+
+INCOME = [
+    {"type": "self_employment", "business_id": "biz_1", "amount": 5000, "date": "2024-01-20"},
+    {"type": "self_employment", "business_id": "biz_1", "amount": 7000, "date": "2024-10-05"},
+    {"type": "self_employment", "business_id": "biz_2", "amount": 9999, "date": "2024-01-20"},
+    {"type": "wages", "amount": 40000},
+]
+
+
+def test_quarters_and_ytd():
+    report = business_profit_loss(make_business(), INCOME, 2024)
+    assert report["periods"] == ["q1", "q2", "q3", "q4", "undated", "ytd"]
+    receipts = report["income"][0]
+    assert receipts["category"] == "gross_receipts"
+    assert [receipts[p] for p in report["periods"]] == [5000.0, 0.0, 0.0, 7000.0, 1000.0, 13000.0]
+
+    supplies = next(r for r in report["expenses"] if r["category"] == "supplies")
+    assert (supplies["q1"], supplies["q3"], supplies["ytd"]) == (300.0, 200.0, 500.0)
+    assert [r["category"] for r in report["expenses"]] == ["advertising", "supplies", "meals"]
+    assert report["total_expenses"]["ytd"] == 670.0
+    assert report["net_profit"]["q1"] == 4700.0
+    assert report["net_profit"]["ytd"] == 12330.0
+    assert any("without a date" in note for note in report["notes"])
+    assert any("Meals" in note for note in report["notes"])
+
+
+def test_through_quarter_leaves_out_later_entries():
+    report = business_profit_loss(make_business(gross_receipts=0), INCOME, 2024, through_quarter=2)
+    assert report["periods"] == ["q1", "q2", "undated", "ytd"]
+    assert report["gross_income"]["ytd"] == 5000.0
+    assert report["total_expenses"]["ytd"] == 470.0
+    assert "2 entries dated after Q2 were left out" in report["notes"]
+    with pytest.raises(ValueError):
+        business_profit_loss(make_business(), INCOME, 2024, through_quarter=5)
+
+
+def test_cost_of_goods_and_home_office():
+    business = make_business(expenses=[], cost_of_goods_sold=400, returns_allowances=100, home_office=1500)
+    report = business_profit_loss(business, [], 2024)
+    assert [r["category"] for r in report["income"]] == ["gross_receipts", "returns_allowances", "cost_of_goods_sold"]
+    assert report["gross_income"]["ytd"] == 500.0
+    assert report["expenses"][0]["category"] == "home_office"
+    assert report["net_profit"]["ytd"] == -1000.0
+
+
+def test_vehicle_costs_follow_the_chosen_method():
+    vehicle = {
+        "id": "veh_1", "placed_in_service": "2024-01-01", "method": "standard_mileage", "method_history": {},
+        "mileage_log": [
+            {"miles": 1000, "business": True, "date": "2024-03-01"},
+            {"miles": 500, "business": True, "date": "2024-11-01"},
+            {"miles": 400, "business": False, "date": "2024-11-02"},
+        ],
+        "expenses": [{"kind": "parking_tolls", "amount": 30, "date": "2024-11-01"}],
+    }
+    report = business_profit_loss(make_business(expenses=[], vehicles=[vehicle]), [], 2024)
+    car = report["expenses"][0]
+    assert car["category"] == "car_truck"
+    assert (car["q1"], car["q4"], car["ytd"]) == (670.0, 365.0, 1035.0)
+
+
+def test_dates_outside_the_year_are_left_out():
+    business = make_business(expenses=[{"category": "supplies", "amount": 80, "date": "2023-12-30"}])
+    report = business_profit_loss(business, [], 2024)
+    assert report["expenses"] == []
+    assert "1 entries dated outside 2024 were left out" in report["notes"]
+
+
+def test_csv_export():
+    report = business_profit_loss(make_business(), INCOME, 2024)
+    rows = list(csv.reader(io.StringIO(build_profit_loss_csv(report))))
+    assert rows[0] == ["section", "line", "Q1", "Q2", "Q3", "Q4", "Undated", "YTD"]
+    assert rows[1][:2] == ["Income", "Gross receipts"]
+    assert rows[-1] == ["", "Net profit (loss)", "4,700.00", "-120.00", "-200.00", "7,000.00", "950.00", "12,330.00"]
+
+    text = build_profit_loss_csv(report, Formatter(currency_format="eu", rounding="whole_dollars"))
+    assert text.splitlines()[-1] == ";Net profit (loss);4.700;-120;-200;7.000;950;12.330"
+
+
+def test_pdf_export():
+    report = business_profit_loss(make_business(), INCOME, 2024)
+    pdf = render_profit_loss(report)
+    assert pdf.startswith(b"%PDF")
+    assert b"Studio - Profit and Loss" in pdf
+    assert b"12,330.00" in pdf
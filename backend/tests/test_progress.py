@@ -0,0 +1,87 @@
+"""Tests for job progress reporting and cancellation."""
+import asyncio
+
+import pytest
+
+from app.documents.bulk_import import bulk_import
+from app.services import progress as progress_module
+from app.services.progress import PROGRESS_EVENT, JobCancelled, ProgressBus
+from app.utils.document_store import DocumentStore
+
+
+@pytest.fixture
+def bus():
+    return ProgressBus()
+
+
+def test_job_lifecycle(bus):
+    job = bus.start("backup", "Nightly backup")
+    assert job["job_id"].startswith("job_")
+    assert job["status"] == "running"
+
+    bus.update(job["job_id"], percent=42.4, message="Copying returns", files=3)
+    current = bus.get_job(job["job_id"])
+    assert (current["percent"], current["message"], current["details"]) == (42, "Copying returns", {"files": 3})
+
+    bus.finish(job["job_id"], {"files": 7})
+    done = bus.get_job(job["job_id"])
+    assert (done["status"], done["percent"], done["result"]) == ("completed", 100, {"files": 7})
+    with pytest.raises(ValueError):
+        bus.cancel_job(job["job_id"])
+    with pytest.raises(KeyError):
+        bus.get_job("job_0000000000000000")
+
+
+def test_cancel_stops_at_next_update(bus):
+    job = bus.start("ocr")
+    assert bus.cancel_job(job["job_id"])["status"] == "cancelling"
+    with pytest.raises(JobCancelled):
+        bus.update(job["job_id"], percent=50)
+    assert bus.finish(job["job_id"])["status"] == "cancelled"
+
+
+def test_cancel_bulk_import(bus, tmp_path):
+    folder = tmp_path / "inbox"
+    folder.mkdir()
+    for index in range(3):
+        (folder / f"w2_{index}.pdf").write_bytes(f"%PDF {index}".encode())
+    store = DocumentStore(storage_dir=str(tmp_path / "documents"))
+
+    job = bus.start("bulk_import")
+    bus.cancel_job(job["job_id"])
+    with pytest.raises(JobCancelled):
+        bulk_import(store, str(folder), progress=bus.reporter(job["job_id"]))
+    # The file in flight finishes; the rest are never started
+    assert len(store.list_documents()) == 1
+
+
+def test_subscribers_receive_events(bus):
+    async def collect():
+        queue = bus.subscribe()
+        job = bus.start("embeddings")
+        bus.update(job["job_id"], percent=10)
+        bus.fail(job["job_id"], "model missing")
+        await asyncio.sleep(0)
+        events = [queue.get_nowait() for _ in range(queue.qsize())]
+        bus.unsubscribe(queue)
+        return events
+
+    events = asyncio.run(collect())
+    assert [e["event"] for e in events] == [PROGRESS_EVENT] * 3
+    assert [e["status"] for e in events] == ["running", "running", "failed"]
+    assert events[1]["percent"] == 10
+
+
+def test_old_finished_jobs_are_dropped(bus, monkeypatch):
+    monkeypatch.setattr(progress_module, "MAX_FINISHED_JOBS", 2)
+    running = bus.start("backup")
+    finished = [bus.start("backup") for _ in range(3)]
+    for job in finished:
+        bus.finish(job["job_id"])
+    bus.start("backup")
+
+    ids = {job["job_id"] for job in bus.list_jobs()}
+    assert running["job_id"] in ids
+    assert finished[0]["job_id"] not in ids
+    assert {finished[1]["job_id"], finished[2]["job_id"]} <= ids
+    assert [job["job_id"] for job in bus.list_jobs(status="running")][-1] == running["job_id"]
@@ -0,0 +1,79 @@
+"""Tests for the after-tax raise calculator."""
+from decimal import Decimal
+
+import pytest
+
+from app.tax_engine.raise_calculator import raise_after_tax
+
+
+def make_return(wages=80000, filing_status="single", dependents=None, state="IL"):
+    return {
+        "return_id": "ret_0000000000000000",
+        "tax_year": 2024,
+        "filing_status": filing_status,
+        "taxpayer": {"name": "Pat Doe", "state": state},
+        "income_sources": [{"id": "inc_1", "type": "wages", "amount": wages, "withholding": 8000}],
+        "deductions": [],
+        "businesses": [],
+        "dependents": dependents or [],
+    }
+
+
+def children(count):
+    return [{"id": f"dep_{n}", "name": f"Kid {n}", "birth_date": "2015-03-01"} for n in range(count)]
+
+
+def test_plain_raise_in_one_bracket():
+    result = raise_after_tax(make_return(), Decimal("10000"))
+    changes = result["tax_changes"]
+    # 22% federal bracket, Illinois' flat 4.95%, and 7.65% FICA
+    assert changes["federal"] == 2200
+    assert changes["state"] == 495
+    assert changes["social_security"] == 620 and changes["medicare"] == 145
+    assert result["after_tax_raise"] == 10000 - 2200 - 495 - 765
+    assert result["marginal_rate"] == pytest.approx(0.346)
+    assert result["credits_lost"] == []
+    assert all(item["marginal_rate"] == pytest.approx(0.346) for item in result["slices"])
+
+
+def test_new_salary_and_wage_base():
+    result = raise_after_tax(make_return(160000), new_salary=Decimal("180000"))
+    assert result["raise"] == 20000 and result["new_wages"] == 180000
+    # Only the first $8,600 is under the social security wage base
+    assert result["tax_changes"]["social_security"] == pytest.approx(533.2)
+    assert any("wage base" in note for note in result["notes"])
+
+
+def test_credit_phase_out():
+    result = raise_after_tax(make_return(190000, dependents=children(2)), Decimal("20000"))
+    assert result["credits_lost"] == [{"credit": "child_tax_credit", "before": 4000, "after": 3500, "lost": 500}]
+    assert result["tax_changes"]["additional_medicare"] == 90
+    rates = [item["marginal_rate"] for item in result["slices"]]
+    # The slices past $200,000 AGI also lose $50 of credit per $1,000
+    assert rates[-1] > rates[0]
+    assert result["slices"][0]["credits_lost"] == []
+    assert any("child tax credit" in note for note in result["notes"])
+
+
+def test_spouse_and_no_state():
+    tax_return = make_return(filing_status="married_joint", state=None)
+    tax_return["income_sources"].append({"id": "inc_2", "type": "wages", "amount": 50000, "owner": "spouse"})
+    result = raise_after_tax(tax_return, new_salary=Decimal("60000"), owner="spouse")
+    assert result["current_wages"] == 50000 and result["raise"] == 10000
+    assert result["tax_changes"]["state"] == 0
+    assert any("No state of residence" in note for note in result["notes"])
+
+
+def test_rejects_bad_input():
+    with pytest.raises(ValueError, match="either"):
+        raise_after_tax(make_return())
+    with pytest.raises(ValueError, match="either"):
+        raise_after_tax(make_return(), Decimal("1"), Decimal("1"))
+    with pytest.raises(ValueError, match="same"):
+        raise_after_tax(make_return(), new_salary=Decimal("80000"))
+    with pytest.raises(ValueError, match="more than the current wages"):
+        raise_after_tax(make_return(), Decimal("-90000"))
+    with pytest.raises(ValueError, match="joint return"):
+        raise_after_tax(make_return(), Decimal("1000"), owner="spouse")
+    with pytest.raises(ValueError, match="Unsupported state"):
+        raise_after_tax(make_return(), Decimal("1000"), state="ZZ")
@@ -0,0 +1,48 @@
+"""Tests for the receipt parser."""
+from decimal import Decimal
+
+from app.documents.parsers import parse_document
+from app.documents.parsers.receipt import parse_receipt
+
+PHARMACY_RECEIPT = """
+CVS Pharmacy #4521
+123 Main St Springfield IL 62701
+03/14/2024 10:42 AM
+RX 88213 Amoxicillin        12.99
+Bandages                     4.50
+Subtotal                    17.49
+Tax                          0.51
+Total                       18.00
+"""
+
+
+def test_parses_vendor_total_date_and_category():
+    result = parse_receipt(PHARMACY_RECEIPT)
+    assert result.value("vendor") == "CVS Pharmacy #4521"
+    assert result.value("amount") == Decimal("18.00")
+    assert result.value("date") == "2024-03-14"
+    assert result.value("category") == "medical"
+    assert result.record.amount == Decimal("18.00")
+
+
+def test_total_preferred_over_subtotal():
+    result = parse_receipt("Shop\nSubtotal 10.00\nTotal Due: $10.80\n")
+    assert result.value("amount") == Decimal("10.80")
+    assert result.fields["amount"].confidence == 0.9
+
+
+def test_falls_back_to_largest_price():
+    result = parse_receipt("Salvation Army Donation Center\nStore 1234\nClothing 25.00\nHousewares 40.00\n")
+    assert result.value("amount") == Decimal("40.00")
+    assert result.fields["amount"].confidence == 0.5
+    assert result.value("category") == "charitable"
+
+
+def test_written_out_date():
+    assert parse_receipt("Shop\nJan 5, 2024\nTotal 3.00").value("date") == "2024-01-05"
+
+
+def test_unknown_category_warns():
+    result = parse_document("Receipt", "Hardware Store\nTotal 12.00")
+    assert "category" not in result.fields
+    assert any("category" in w for w in result.warnings)
@@ -0,0 +1,98 @@
+"""Tests for the PII redaction layer."""
+import pytest
+
+from app.agents.redaction import PIIRedactor
+
+
+@pytest.fixture
+def redactor():
+    return PIIRedactor()
+
+
+def test_ssn_masked(redactor):
+    text = redactor.redact("Taxpayer SSN is 123-45-6789.")
+    assert "123-45-6789" not in text
+    assert "[SSN_1]" in text
+
+
+def test_already_masked_ssn_untouched(redactor):
+    assert redactor.redact("SSN XXX-XX-1234") == "SSN XXX-XX-1234"
+
+
+def test_ein_masked(redactor):
+    text = redactor.redact("Employer EIN 98-7654321")
+    assert "98-7654321" not in text
+    assert "[EIN_1]" in text
+
+
+def test_account_number_masked(redactor):
+    text = redactor.redact("Deposit to account 000123456789")
+    assert "000123456789" not in text
+    assert "[ACCOUNT_1]" in text
+
+
+def test_dollar_amounts_not_masked(redactor):
+    text = redactor.redact("Wages of $12345678 and 85000.00 withheld 12000000.50")
+    assert "12345678" in text
+    assert "12000000.50" in text
+
+
+def test_same_value_same_token(redactor):
+    text = redactor.redact("123-45-6789 and again 123-45-6789, spouse 987-65-4321")
+    assert text.count("[SSN_1]") == 2
+    assert "[SSN_2]" in text
+
+
+def test_labelled_name_masked(redactor):
+    text = redactor.redact("Name: Jane Doe filed late. Jane Doe disagrees.")
+    assert "Jane Doe" not in text
+    assert text.count("[NAME_1]") == 2
+
+
+def test_redact_data_masks_name_fields(redactor):
+    data = {
+        "employee_name": "John Smith",
+        "ein": "12-3456789",
+        "wages": 85000,
+        "notes": "john smith worked remotely",
+    }
+    redacted = redactor.redact_data(data)
+    assert redacted["employee_name"] == "[NAME_1]"
+    assert redacted["ein"] == "[EIN_1]"
+    assert redacted["wages"] == 85000
+    assert "smith" not in redacted["notes"].lower()
+
+
+def test_redact_data_nested(redactor):
+    data = {"dependents": [{"name": "Amy Smith", "ssn": "111-22-3333"}]}
+    redacted = redactor.redact_data(data)
+    assert redacted["dependents"][0] == {"name": "[NAME_1]", "ssn": "[SSN_1]"}
+
+
+def test_restore_round_trip(redactor):
+    original = "Client John Smith (SSN 123-45-6789) works for EIN 12-3456789."
+    redactor.add_names(["John Smith"])
+    masked = redactor.redact(original)
+    assert redactor.restore(masked) == original
+
+
+def test_restore_many_tokens(redactor):
+    ssns = [f"123-45-{6700 + i}" for i in range(12)]
+    masked = redactor.redact(" ".join(ssns))
+    assert "[SSN_12]" in masked
+    assert redactor.restore(masked) == " ".join(ssns)
+
+
+def test_redact_messages_skips_images(redactor):
+    messages = [{
+        "role": "user",
+        "content": [
+            {"type": "image", "source": {"type": "base64", "data": "abc"}},
+            {"type": "text", "text": "SSN 123-45-6789"},
+        ],
+    }]
+    redacted = redactor.redact_messages(messages)
+    assert redacted[0]["content"][0] == messages[0]["content"][0]
+    assert redacted[0]["content"][1]["text"] == "SSN [SSN_1]"
+    # Original payload is not mutated
+    assert messages[0]["content"][1]["text"] == "SSN 123-45-6789"
@@ -0,0 +1,68 @@
+"""Tests for comparing taxes after a move to another state."""
+from decimal import Decimal
+
+import pytest
+
+from app.tax_engine.relocation import compare_relocation
+from app.tax_engine.state_tax import StateTaxCalculator
+
+
+def make_return(**overrides):
+    tax_return = {
+        "return_id": "ret_1",
+        "tax_year": 2024,
+        "filing_status": "single",
+        "income_sources": [{"id": "inc_1", "type": "wages", "amount": 150000, "withholding": 25000}],
+        "deductions": [
+            {"id": "ded_1", "category": "state_local_tax", "tax_type": "income", "amount": 8000},
+            {"id": "ded_2", "category": "state_local_tax", "tax_type": "real_estate", "amount": 6000},
+            {"id": "ded_3", "category": "mortgage_interest", "amount": 12000},
+        ],
+        "state_returns": [{"id": "st_1", "state": "NY", "residency": "resident"}],
+    }
+    tax_return.update(overrides)
+    return tax_return
+
+
+def test_move_to_no_income_tax_state():
+    result = compare_relocation(make_return(), "tx", new_property_tax=Decimal("12000"))
+    ny_tax = StateTaxCalculator().resident_tax("NY", Decimal("150000"), "single")["tax"]
+
+    assert result["current"]["state"] == "NY"
+    assert result["current"]["state_income_tax"] == float(ny_tax)
+    assert result["current"]["property_tax"] == 6000.0
+    assert result["new"]["state"] == "TX"
+    assert result["new"]["state_income_tax"] == 0.0
+    assert result["new"]["property_tax"] == 12000.0
+    # SALT is capped at $10,000 either way, so the federal tax doesn't change
+    assert result["new"]["federal_tax"] == result["current"]["federal_tax"]
+    assert result["annual_difference"] == pytest.approx(6000 - float(ny_tax))
+    assert any("TX has no state income tax" in note for note in result["notes"])
+
+
+def test_local_tax_and_federal_effect():
+    tax_return = make_return(
+        income_sources=[{"id": "inc_1", "type": "wages", "amount": 60000}],
+        deductions=[],
+    )
+    result = compare_relocation(
+        tax_return, "NY", from_state="FL", new_local_tax_rate=Decimal("3.876"),
+        property_tax=Decimal("0"), new_property_tax=Decimal("0"),
+    )
+    assert result["new"]["local_income_tax"] == 2325.6
+    # Standard deduction still wins, so only the state and local tax differ
+    assert result["annual_difference"] == pytest.approx(result["new"]["state_income_tax"] + 2325.6)
+
+    itemizer = make_return(deductions=[{"id": "ded_3", "category": "mortgage_interest", "amount": 14000}])
+    result = compare_relocation(itemizer, "IL", from_state="TX", new_property_tax=Decimal("4000"))
+    # Moving to IL adds SALT deductions, which lowers the federal tax
+    assert result["new"]["federal_tax"] < result["current"]["federal_tax"]
+
+
+def test_invalid_inputs():
+    with pytest.raises(ValueError, match="no resident state"):
+        compare_relocation(make_return(state_returns=[]), "TX")
+    with pytest.raises(ValueError, match="Unsupported state"):
+        compare_relocation(make_return(), "ZZ")
+    with pytest.raises(ValueError, match="negative"):
+        compare_relocation(make_return(), "TX", local_tax_rate=Decimal("-1"))
@@ -0,0 +1,98 @@
+"""Tests for rental depreciation schedules, Schedule E totals, and recapture on sale."""
+from decimal import Decimal
+
+import pytest
+
+from app.tax_engine.rental_depreciation import (
+    calculate_rentals, depreciation_schedule, rental_property_year, replacement_property,
+)
+
+
+def rental(**fields):
+    record = {"id": "rent_1", "address": "12 Elm St", "building_basis": 275000, "land_value": 50000,
+              "placed_in_service": "2020-01-15", "rents": 24000, "expenses": 8000}
+    record.update(fields)
+    return record
+
+
+def test_schedule_uses_mid_month_convention():
+    schedule = depreciation_schedule(Decimal("275000"), "2020-01-15")
+    assert schedule[0] == {"year": 2020, "months": 11.5, "depreciation": 9583.33,
+                           "accumulated": 9583.33, "remaining_basis": 265416.67}
+    assert schedule[1]["depreciation"] == 10000.0
+    # 27.5 years end with the half month left over from the first year
+    assert (schedule[-1]["year"], schedule[-1]["months"], schedule[-1]["remaining_basis"]) == (2047, 6.5, 0.0)
+    assert sum(Decimal(str(row["depreciation"])) for row in schedule) == Decimal("275000")
+
+
+def test_schedule_stops_at_sale():
+    schedule = depreciation_schedule(Decimal("275000"), "2020-01-15", "2024-06-10")
+    assert schedule[-1]["year"] == 2024 and schedule[-1]["months"] == 5.5
+    assert schedule[-1]["accumulated"] == 44166.67
+
+
+def test_year_figures():
+    result = rental_property_year(rental(), 2024)
+    assert result["depreciation"] == 10000.0
+    assert result["net_income"] == 6000.0
+    assert result["accumulated_depreciation"] == 49583.33
+    assert result["adjusted_basis"] == 275416.67
+    assert result["sale"] is None
+
+    # Placed in service in December: half a month
+    assert rental_property_year(rental(placed_in_service="2024-12-01"), 2024)["depreciation"] == 416.67
+
+
+def test_sale_recapture():
+    sold = rental(date_sold="2024-06-10", sale_price=400000, selling_expenses=24000)
+    result = rental_property_year(sold, 2024)
+    assert result["depreciation"] == 4583.34
+    sale = result["sale"]
+    assert sale["adjusted_basis"] == 280833.33
+    assert sale["gain_or_loss"] == 95166.67
+    assert sale["unrecaptured_section_1250_gain"] == 44166.67
+    assert sale["section_1231_gain"] == 51000.0
+
+    # A gain smaller than the depreciation is all recapture
+    small = rental_property_year(rental(date_sold="2024-06-10", sale_price=300000), 2024)["sale"]
+    assert small["unrecaptured_section_1250_gain"] == small["gain_or_loss"] == 19166.67
+
+
+def test_calculate_rentals_skips_properties_sold_earlier():
+    tax_return = {"tax_year": 2024, "rental_properties": [
+        rental(), rental(id="rent_2", date_sold="2023-03-01", sale_price=1), rental(id="rent_3", placed_in_service="2025-01-01"),
+    ]}
+    result = calculate_rentals(tax_return)
+    assert [p["property_id"] for p in result["properties"]] == ["rent_1"]
+    assert result["net_income"] == 6000.0 and result["sale_gain"] == 0.0
+
+
+def test_like_kind_exchange_defers_gain_into_replacement():
+    relinquished = rental(date_sold="2024-06-10", sale_price=510000,
+                          like_kind_exchange={"replacement_id": "rent_2", "boot_received": 10000})
+    sale = rental_property_year(relinquished, 2024)["sale"]
+    assert sale["realized_gain"] == 229166.67
+    # Only the boot is taxed, as depreciation recapture first
+    assert sale["gain_or_loss"] == 10000.0 and sale["unrecaptured_section_1250_gain"] == 10000.0
+    assert sale["deferred_gain"] == 219166.67
+
+    basis = replacement_property(relinquished, 2024, Decimal("500000"), Decimal("100000"))
+    assert basis == {"building_basis": 224666.66, "land_value": 56166.67,
+                     "prior_depreciation": 44166.67, "deferred_gain": 219166.67}
+
+    # Selling the replacement later picks up the deferred gain and all the depreciation taken
+    replacement = {"id": "rent_2", "building_basis": basis["building_basis"], "land_value": basis["land_value"],
+                   "placed_in_service": "2024-06-10", "prior_depreciation": basis["prior_depreciation"],
+                   "date_sold": "2025-06-10", "sale_price": 500000}
+    later = rental_property_year(replacement, 2025)["sale"]
+    assert later["gain_or_loss"] == 227336.37
+    assert later["unrecaptured_section_1250_gain"] == 52336.37
+    # Appreciation over the original $325,000 cost, as if the first property had been sold
+    assert later["section_1231_gain"] == 175000.0
+
+
+def test_replacement_value_must_exceed_land():
+    relinquished = rental(date_sold="2024-06-10", sale_price=300000,
+                          like_kind_exchange={"replacement_id": "rent_2", "boot_received": 0})
+    with pytest.raises(ValueError, match="land value"):
+        replacement_property(relinquished, 2024, Decimal("300000"), Decimal("300000"))
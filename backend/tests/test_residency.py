@@ -0,0 +1,64 @@
+"""Tests for counting state residency days."""
+from datetime import date
+
+from app.tax_engine.residency import count_state_days, return_residency_days
+
+
+def trip(state, start, end=None):
+    return {"state": state, "start_date": start, "end_date": end or start}
+
+
+def test_counts_days_and_travel_days_count_twice():
+    log = [trip("NY", "2024-01-01", "2024-01-10"), trip("NJ", "2024-01-10", "2024-01-12")]
+    result = count_state_days(log, 2024)
+    days = {row["state"]: row["days"] for row in result["states"]}
+    assert days == {"NY": 10, "NJ": 3}
+    assert result["unlogged_days"] == 366 - 12
+    assert result["warnings"] == []
+
+
+def test_unlogged_days_go_to_home_state():
+    result = count_state_days([trip("NY", "2024-02-01", "2024-02-29")], 2024, home_state="NJ")
+    rows = {row["state"]: row for row in result["states"]}
+    assert rows["NJ"]["days"] == 366 - 29
+    assert rows["NJ"]["home_state"] is True
+    assert rows["NY"]["status"] == "under"
+
+
+def test_dual_residency_warning():
+    log = [trip("NY", "2024-01-01", "2024-07-01")]
+    result = count_state_days(log, 2024, home_state="NJ")
+    rows = {row["state"]: row for row in result["states"]}
+    assert rows["NY"]["days"] == 183
+    assert rows["NY"]["status"] == "over"
+    assert any("183-day test" in w for w in result["warnings"])
+    assert any(w.startswith("Dual residency risk: NJ and NY") for w in result["warnings"])
+
+
+def test_approaching_and_state_thresholds():
+    log = [trip("CA", "2024-01-01", "2024-05-31"), trip("OR", "2024-06-01", "2024-12-31")]
+    result = count_state_days(log, 2024, as_of=date(2024, 12, 31))
+    rows = {row["state"]: row for row in result["states"]}
+    assert rows["OR"]["days"] == 214 and rows["OR"]["threshold"] == 200 and rows["OR"]["status"] == "over"
+    assert rows["CA"]["days"] == 152 and rows["CA"]["status"] == "under"
+
+    midyear = count_state_days([trip("CA", "2024-01-01", "2024-06-01")], 2024, as_of=date(2024, 6, 1))
+    assert midyear["states"][0]["status"] == "approaching"
+    assert midyear["states"][0]["days_until_threshold"] == 30
+    assert midyear["through"] == "2024-06-01"
+
+
+def test_no_income_tax_states_are_not_flagged():
+    result = count_state_days([trip("FL", "2024-01-01", "2024-09-30")], 2024, home_state="FL")
+    assert result["states"][0]["status"] == "no_income_tax"
+    assert result["warnings"] == []
+
+
+def test_home_state_defaults_to_resident_state_return():
+    tax_return = {
+        "tax_year": 2024,
+        "state_returns": [{"state": "NJ", "residency": "resident"}, {"state": "NY", "residency": "nonresident"}],
+        "travel_log": [trip("NY", "2024-03-04", "2024-03-08")],
+    }
+    assert return_residency_days(tax_return)["home_state"] == "NJ"
+    assert return_residency_days(tax_return, home_state="ct")["home_state"] == "CT"
@@ -0,0 +1,171 @@
+"""Tests for calculating stored returns."""
+import pytest
+
+from app.tax_engine.return_calculation import calculate_return
+from app.utils.return_store import ReturnStore
+
+
+def make_return(income_sources=(), **overrides):
+    tax_return = {
+        "return_id": "ret_0000000000000000",
+        "tax_year": 2024,
+        "filing_status": "single",
+        "dependents": [],
+        "income_sources": [{"type": t, "amount": a, "withholding": w} for t, a, w in income_sources],
+        "deductions": [],
+        "capital_transactions": [],
+        "businesses": [],
+        "carryforwards": {},
+    }
+    tax_return.update(overrides)
+    return tax_return
+
+
+def test_wages_refund():
+    result = calculate_return(make_return([("wages", 85000, 12000)]))
+    assert result["agi"] == 85000.0
+    assert result["taxable_income"] == 70400.0
+    assert result["income_tax"] == 10541.0
+    assert result["total_tax"] == 10541.0
+    assert result["refund_or_owed"] == 1459.0
+
+
+
+def test_estimated_payments_are_credited():
+    payments = [
+        {"id": "est_1", "tax_year": 2024, "quarter": "Q1", "amount": 1000, "paid_date": "2024-04-15"},
+        {"id": "est_2", "tax_year": 2024, "quarter": "Q4", "amount": 500, "paid_date": "2025-01-15"},
+        {"id": "est_3", "tax_year": 2025, "quarter": "Q1", "amount": 2000, "paid_date": "2025-04-15"},
+    ]
+    result = calculate_return(make_return([("wages", 85000, 12000)], estimated_payments=payments))
+    assert result["estimated_payments"] == 1500.0  # next year's payment isn't this year's
+    assert result["refund_or_owed"] == 2959.0
+
+
+def test_qualified_dividends_lower_the_tax():
+    ordinary = calculate_return(make_return([("wages", 64600, 0), ("dividends", 10000, 0)]))
+    tax_return = make_return([("wages", 64600, 0), ("dividends", 10000, 0)])
+    tax_return["income_sources"][1]["qualified_dividends"] = 10000
+    result = calculate_return(tax_return)
+    assert result["agi"] == ordinary["agi"]
+    assert result["qualified_dividends"] == 10000.0
+    assert result["income_tax"] == ordinary["income_tax"] - 700
+    assert result["qualified_dividends_worksheet"]["at_15_percent"] == 10000.0
+
+
+def test_qualified_dividends_capped_at_the_source_amount():
+    tax_return = make_return([("dividends", 5000, 0)])
+    tax_return["income_sources"][0]["qualified_dividends"] = 8000
+    assert calculate_return(tax_return)["qualified_dividends"] == 5000.0
+
+def test_self_employment_tax_and_adjustment():
+    result = calculate_return(make_return([("self_employment", 50000, 0)]))
+    assert result["self_employment_tax"] == 7064.78
+    assert result["adjustments"] == 3532.39
+    assert result["agi"] == 46467.61
+    assert result["total_tax"] == round(result["income_tax"] + 7064.78, 2)
+    assert result["refund_or_owed"] == -result["total_tax"]
+
+
+def test_whole_dollar_rounding():
+    result = calculate_return(make_return([("self_employment", 50000, 0)]), rounding="whole_dollars")
+    assert result["self_employment_tax"] == 7065.0
+    assert result["adjustments"] == 3532.0
+    assert result["self_employment"][0]["lines"]["13"] == 3532.0
+    assert all(value == int(value) for value in result["income"].values())
+    with pytest.raises(ValueError):
+        calculate_return(make_return([("wages", 1000, 0)]), rounding="dimes")
+
+
+def test_capital_loss_limited():
+    lots = [{"gain_or_loss": -10000}, {"description": "Old fund", "gain_or_loss": None}]
+    result = calculate_return(make_return([("wages", 60000, 0)], capital_transactions=lots))
+    assert result["income"]["capital_gains"] == -3000.0
+    assert result["agi"] == 57000.0
+    assert result["capital_loss_carryforward"] == 7000.0
+    assert any("Old fund" in note for note in result["notes"])
+
+
+def test_rental_income_and_sale():
+    rental = {"id": "rent_1", "building_basis": 275000, "placed_in_service": "2020-01-15", "rents": 24000,
+              "expenses": 20000}
+    result = calculate_return(make_return([("wages", 60000, 0)], rental_properties=[rental]))
+    assert result["income"]["rental"] == -6000.0
+    assert result["agi"] == 54000.0
+    assert result["rental_properties"][0]["depreciation"] == 10000.0
+    assert any("passive activity" in note for note in result["notes"])
+
+    rental.update(date_sold="2024-06-10", sale_price=300000)
+    result = calculate_return(make_return([("wages", 60000, 0)], rental_properties=[rental]))
+    assert result["income"]["capital_gains"] == 69166.67
+    assert any("section 1250" in note for note in result["notes"])
+
+
+def test_at_risk_limit_applies_to_income_tax_not_se_tax():
+    business = {"id": "biz_1", "name": "Studio", "gross_receipts": 10000, "at_risk_amount": 2000,
+                "expenses": [{"category": "supplies", "amount": 16000}]}
+    result = calculate_return(make_return([("wages", 60000, 0)], businesses=[business]))
+    assert result["income"]["business"] == -2000.0
+    assert result["agi"] == 58000.0
+    assert result["at_risk"][0]["carryforward"] == 4000.0
+    assert any("$4,000.00 of Studio's loss" in note for note in result["notes"])
+    assert result["businesses"][0]["net_profit"] == -6000.0
+
+
+def test_taxable_social_security():
+    sources = [("social_security", 30000, 0), ("retirement", 20000, 0)]
+    result = calculate_return(make_return(sources, filing_status="married_joint"))
+    assert result["income"]["social_security"] == 1500.0
+    assert calculate_return(make_return([("social_security", 20000, 0)]))["income"]["social_security"] == 0.0
+
+
+def test_tax_exempt_interest_counts_toward_social_security():
+    sources = [("social_security", 30000, 0), ("retirement", 20000, 0), ("interest", 0, 0)]
+    without = calculate_return(make_return(sources))
+    tax_return = make_return(sources)
+    tax_return["income_sources"][2]["tax_exempt_interest"] = 6000
+    result = calculate_return(tax_return)
+    assert result["tax_exempt_interest"] == 6000.0
+    assert result["income"]["interest"] == 0.0
+    # Provisional income of $41,000 instead of $35,000
+    assert without["income"]["social_security"] == 5350.0
+    assert result["income"]["social_security"] == 10450.0
+
+
+def test_dependent_credits_and_phaseout():
+    dependents = [{"name": "Alex", "birth_date": "2015-05-01"}, {"name": "Grandma", "birth_date": "1940-01-01"}]
+    result = calculate_return(make_return([("wages", 90000, 0)], dependents=dependents))
+    assert result["credits"]["child_tax_credit"] == 2000.0
+    assert result["credits"]["other_dependent_credit"] == 500.0
+    assert result["total_tax"] == round(result["income_tax"] - 2500, 2)
+
+    # $10,500 over the threshold rounds up to 11 x $50
+    phased = calculate_return(make_return([("wages", 210500, 0)], dependents=dependents[:1]))
+    assert phased["credits"]["child_tax_credit"] == 1450.0
+
+
+def test_itemized_deductions_from_schedule_a():
+    deductions = [
+        {"category": "mortgage_interest", "amount": 15000},
+        {"category": "state_local_tax", "amount": 12000},
+    ]
+    result = calculate_return(make_return([("wages", 150000, 0)], deductions=deductions))
+    assert result["deduction_type"] == "Itemized"
+    assert result["deduction_amount"] == 25000.0
+    assert result["schedule_a"]["lines"]["5e"] == 10000.0
+
+
+def test_unsupported_year():
+    with pytest.raises(ValueError):
+        calculate_return(make_return(tax_year=2023))
+
+
+def test_save_calculation(tmp_path):
+    store = ReturnStore(storage_dir=str(tmp_path / "returns"))
+    return_id = store.create_return()["return_id"]
+    store.add_income_source(return_id, "wages", 85000, withholding=12000)
+
+    saved = store.save_calculation(return_id, calculate_return(store.get_return(return_id)))
+    assert saved["calculated_tax"] == 10541.0
+    assert saved["refund_or_owed"] == 1459.0
+    assert store.get_return(return_id)["calculation"]["agi"] == 85000.0
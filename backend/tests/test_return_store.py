@@ -0,0 +1,832 @@
+"""Tests for the tax return store."""
+import pytest
+
+from app.tax_engine.return_calculation import calculate_return
+from app.utils.return_store import ReturnLockedError, ReturnStore
+
+
+@pytest.fixture
+def store(tmp_path):
+    """Create a return store with a temp directory."""
+    return ReturnStore(storage_dir=str(tmp_path / "returns"))
+
+
+def test_create_and_get_return(store):
+    created = store.create_return(tax_year=2024, filing_status="married_joint")
+    fetched = store.get_return(created["return_id"])
+    assert fetched["filing_status"] == "married_joint"
+    assert fetched["status"] == "draft"
+    assert fetched["income_sources"] == []
+
+
+def test_nonexistent_return(store):
+    assert store.get_return("ret_0000000000000000") is None
+
+
+def test_invalid_return_id_rejected(store):
+    assert store.get_return("../../etc/passwd") is None
+    assert store.delete_return("../secrets") is False
+
+
+def test_add_income_source(store):
+    tax_return = store.create_return()
+    source = store.add_income_source(
+        tax_return["return_id"], "wages", 85000, description="Acme Corp", withholding=12000
+    )
+    stored = store.get_return(tax_return["return_id"])
+    assert stored["income_sources"] == [source]
+    assert source["withholding"] == 12000
+
+
+def test_invalid_income_type(store):
+    tax_return = store.create_return()
+    with pytest.raises(ValueError, match="Invalid income type"):
+        store.add_income_source(tax_return["return_id"], "lottery", 100)
+
+
+def test_negative_deduction_rejected(store):
+    tax_return = store.create_return()
+    with pytest.raises(ValueError, match="negative"):
+        store.add_deduction(tax_return["return_id"], "charitable", -5)
+
+
+def test_add_to_missing_return(store):
+    with pytest.raises(KeyError):
+        store.add_deduction("ret_0000000000000000", "charitable", 500)
+
+
+def test_add_dependent(store):
+    tax_return = store.create_return()
+    dependent = store.add_dependent(tax_return["return_id"], "Amy", "daughter", months_lived_with=12)
+    assert store.get_return(tax_return["return_id"])["dependents"] == [dependent]
+
+
+def test_update_dependent(store):
+    return_id = store.create_return()["return_id"]
+    dependent = store.add_dependent(return_id, "Ruth", "mother")
+    updated = store.update_dependent(return_id, dependent["id"], support_percent=70, gross_income=4200)
+    assert store.get_return(return_id)["dependents"] == [updated]
+    assert updated["support_percent"] == 70
+    with pytest.raises(ValueError):
+        store.update_dependent(return_id, dependent["id"], months_lived_with=13)
+    with pytest.raises(ValueError):
+        store.add_dependent(return_id, "Amy", "daughter", support_percent=150)
+    with pytest.raises(ValueError):
+        store.update_dependent(return_id, dependent["id"], custodial_parent="grandparent")
+    with pytest.raises(KeyError):
+        store.update_dependent(return_id, "dep_missing", disabled=True)
+
+
+def test_list_and_delete(store):
+    first = store.create_return()
+    store.create_return(tax_year=2024)
+    assert len(store.list_returns()) == 2
+    assert store.delete_return(first["return_id"]) is True
+    assert len(store.list_returns()) == 1
+
+
+def test_client_links(store):
+    first = store.create_return(client_id="cli_a")
+    second = store.create_return()
+    assert [r["return_id"] for r in store.list_returns(client_id="cli_a")] == [first["return_id"]]
+
+    for status in ("in_progress", "review", "filed"):
+        store.set_status(second["return_id"], status)
+    assert store.assign_client(second["return_id"], "cli_a")["client_id"] == "cli_a"
+    assert len(store.list_returns(client_id="cli_a")) == 2
+    assert "client_id" not in store.assign_client(first["return_id"], None)
+    with pytest.raises(KeyError):
+        store.assign_client("ret_0000000000000000", "cli_a")
+
+
+def test_save_and_check_checklist(store):
+    tax_return = store.create_return()
+    checklist = store.save_checklist(tax_return["return_id"], [
+        {"title": "W-2 from Acme", "kind": "document", "form": "W-2", "reason": "Wages", "received": True},
+        {"title": "Form 1098", "kind": "document", "form": "1098", "reason": "Mortgage interest"},
+    ])
+    assert [item["checked"] for item in checklist["items"]] == [True, False]
+
+    item_id = checklist["items"][1]["id"]
+    store.set_checklist_item(tax_return["return_id"], item_id, True)
+    stored = store.get_return(tax_return["return_id"])
+    assert stored["checklist"]["items"][1]["checked"] is True
+
+
+def test_check_missing_checklist_item(store):
+    tax_return = store.create_return()
+    with pytest.raises(KeyError):
+        store.set_checklist_item(tax_return["return_id"], "chk_missing", True)
+
+
+def test_update_income_source(store):
+    tax_return = store.create_return()
+    source = store.add_income_source(tax_return["return_id"], "wages", 50000)
+    updated = store.update_income_source(tax_return["return_id"], source["id"], amount=52000, withholding=6000)
+    assert updated["amount"] == 52000
+    assert store.get_return(tax_return["return_id"])["income_sources"][0]["withholding"] == 6000
+    with pytest.raises(KeyError):
+        store.update_income_source(tax_return["return_id"], "inc_missing", amount=1)
+
+
+
+def test_qualified_dividends_validation(store):
+    tax_return = store.create_return()
+    return_id = tax_return["return_id"]
+    source = store.add_income_source(return_id, "dividends", 3000, qualified_dividends=2500)
+    assert source["qualified_dividends"] == 2500
+    with pytest.raises(ValueError, match="cannot exceed"):
+        store.update_income_source(return_id, source["id"], amount=2000)
+    with pytest.raises(ValueError, match="negative"):
+        store.add_income_source(return_id, "dividends", 3000, qualified_dividends=-1)
+    with pytest.raises(ValueError, match="Only dividend income"):
+        store.add_income_source(return_id, "interest", 3000, qualified_dividends=100)
+
+
+def test_schedule_b_flags_and_delete(store):
+    return_id = store.create_return()["return_id"]
+    source = store.add_income_source(return_id, "interest", 400, description="City Bonds", tax_exempt=True)
+    with pytest.raises(ValueError, match="Only interest and dividends"):
+        store.add_income_source(return_id, "wages", 1000, foreign_account=True)
+    with pytest.raises(ValueError, match="true or false"):
+        store.update_income_source(return_id, source["id"], tax_exempt="yes")
+    with pytest.raises(ValueError, match="Only dividend income"):
+        store.update_income_source(return_id, source["id"], exempt_interest_dividends=10)
+    with pytest.raises(ValueError, match="Invalid state code"):
+        store.update_income_source(return_id, source["id"], bond_state="ny")
+    store.update_income_source(return_id, source["id"], tax_exempt_interest=50, bond_state="NY")
+    store.delete_income_source(return_id, source["id"])
+    assert store.get_return(return_id)["income_sources"] == []
+    with pytest.raises(KeyError):
+        store.delete_income_source(return_id, source["id"])
+
+
+def test_early_distribution_validation(store):
+    return_id = store.create_return()["return_id"]
+    source = store.add_income_source(return_id, "retirement", 8000, distribution_code="1", plan_type="ira")
+    with pytest.raises(ValueError, match="Invalid distribution code"):
+        store.update_income_source(return_id, source["id"], distribution_code="Z")
+    with pytest.raises(ValueError, match="Only retirement distributions"):
+        store.add_income_source(return_id, "wages", 1000, distribution_code="1")
+    with pytest.raises(ValueError, match="Give the exception"):
+        store.update_income_source(return_id, source["id"], exception_amount=500)
+    with pytest.raises(ValueError, match="cannot exceed the distribution"):
+        store.update_income_source(return_id, source["id"], exception_code="09", exception_amount=9000)
+    updated = store.update_income_source(return_id, source["id"], exception_code="09", exception_amount=5000)
+    assert updated["exception_amount"] == 5000
+
+
+def test_add_capital_transactions(store):
+    tax_return = store.create_return()
+    lots = store.add_capital_transactions(
+        tax_return["return_id"],
+        [{"date_sold": "2024-03-01", "proceeds": 100.0, "cost_basis": 80.0}],
+        source="broker_csv",
+    )
+    assert lots[0]["id"].startswith("cap_")
+    assert store.get_return(tax_return["return_id"])["capital_transactions"][0]["source"] == "broker_csv"
+    with pytest.raises(ValueError):
+        store.add_capital_transactions(tax_return["return_id"], [{"proceeds": 1.0}])
+
+
+def test_clone_return(store):
+    prior = store.create_return(tax_year=2024, filing_status="married_joint", taxpayer={"name": "Pat Doe"})
+    return_id = prior["return_id"]
+    store.add_income_source(return_id, "wages", 85000, "Acme", withholding=9000, employer_ein="12-3456789",
+                            document_id="doc_0000000000000000")
+    store.add_deduction(return_id, "mortgage_interest", 12000, "Home loan")
+    store.add_deduction(return_id, "medical", 3000, "Surgery")
+    store.add_deduction(return_id, "charitable", 1200, "Monthly giving", recurring=True)
+    business = store.add_business(return_id, "Studio", gross_receipts=5000)
+    store.add_business_expense(return_id, business["id"], "supplies", 100)
+    store.add_dependent(return_id, "Alex Doe", "son")
+
+    cloned = store.clone_return(return_id, 2025, carryforwards={"capital_loss": 2000})
+    assert cloned["return_id"] != return_id
+    assert cloned["tax_year"] == 2025
+    assert cloned["status"] == "draft"
+    assert cloned["cloned_from"] == return_id
+    assert cloned["taxpayer"] == {"name": "Pat Doe"}
+    assert cloned["carryforwards"] == {"capital_loss": 2000}
+    assert [d["name"] for d in cloned["dependents"]] == ["Alex Doe"]
+
+    source = cloned["income_sources"][0]
+    assert (source["amount"], source["withholding"], source["prior_year_amount"]) == (0, 0, 85000)
+    assert source["employer_ein"] == "12-3456789"
+    assert "document_id" not in source
+    assert [d["description"] for d in cloned["deductions"]] == ["Home loan", "Monthly giving"]
+    assert cloned["businesses"] == [{"id": business["id"], "name": "Studio", "owner": "taxpayer", "expenses": []}]
+    assert store.get_return(cloned["return_id"]) == cloned
+
+    with pytest.raises(ValueError):
+        store.clone_return(return_id, 2024)
+
+
+def test_vehicle_method_carries_into_next_year(store):
+    tax_return = store.create_return(tax_year=2024)
+    business = store.add_business(tax_return["return_id"], "Studio")
+    vehicle = store.add_vehicle(tax_return["return_id"], business["id"], "2024 Hatchback", cost=30000)
+    store.add_mileage(tax_return["return_id"], vehicle["id"], 120, purpose="Client visit")
+    store.add_vehicle_expense(tax_return["return_id"], vehicle["id"], "fuel", 60)
+    with pytest.raises(ValueError):
+        store.add_vehicle_expense(tax_return["return_id"], vehicle["id"], "snacks", 5)
+    with pytest.raises(KeyError):
+        store.add_mileage(tax_return["return_id"], "veh_missing", 10)
+    assert store.set_vehicle_method(tax_return["return_id"], vehicle["id"], "actual")["method_history"] == {"2024": "actual"}
+
+    cloned = store.clone_return(tax_return["return_id"], 2025)
+    carried = cloned["businesses"][0]["vehicles"][0]
+    assert carried["method_history"] == {"2024": "actual"}
+    assert carried["mileage_log"] == [] and "method" not in carried
+    with pytest.raises(ValueError, match="first year"):
+        store.set_vehicle_method(cloned["return_id"], vehicle["id"], "standard_mileage")
+
+
+def test_personal_mileage(store):
+    return_id = store.create_return(tax_year=2024)["return_id"]
+    trip = store.add_personal_mileage(return_id, "medical", 42, purpose="Clinic", date="2024-03-02")
+    assert store.get_return(return_id)["mileage_log"] == [trip]
+    with pytest.raises(ValueError, match="Invalid mileage category"):
+        store.add_personal_mileage(return_id, "commuting", 10)
+    with pytest.raises(ValueError, match="positive"):
+        store.add_personal_mileage(return_id, "charitable", 0)
+
+    business = store.add_business(return_id, "Studio")
+    vehicle = store.add_vehicle(return_id, business["id"], "2024 Hatchback")
+    assert store.add_mileage(return_id, vehicle["id"], 30, business=False, category="charitable")["category"] == "charitable"
+    with pytest.raises(ValueError, match="isn't a business trip"):
+        store.add_mileage(return_id, vehicle["id"], 30, category="medical")
+
+    store.delete_personal_mileage(return_id, trip["id"])
+    assert store.get_return(return_id)["mileage_log"] == []
+    with pytest.raises(KeyError):
+        store.delete_personal_mileage(return_id, trip["id"])
+    assert "mileage_log" not in store.clone_return(return_id, 2025)
+
+
+def test_adoptions(store):
+    return_id = store.create_return()["return_id"]
+    adoption = store.add_adoption(return_id, " Mia Doe ", expenses=8000, final_year=2024)
+    assert adoption["id"].startswith("adopt_") and adoption["name"] == "Mia Doe"
+    with pytest.raises(ValueError, match="cannot be negative"):
+        store.update_adoption(return_id, adoption["id"], prior_year_expenses=-1)
+    with pytest.raises(ValueError, match="true or false"):
+        store.update_adoption(return_id, adoption["id"], special_needs="yes")
+    with pytest.raises(ValueError, match="Invalid final_year"):
+        store.add_adoption(return_id, "Leo", final_year="2024")
+    assert store.update_adoption(return_id, adoption["id"], special_needs=True)["special_needs"] is True
+    store.delete_adoption(return_id, adoption["id"])
+    with pytest.raises(KeyError):
+        store.update_adoption(return_id, adoption["id"], expenses=1)
+    assert store.set_carryforwards(return_id, adoption_credit=500) == {"adoption_credit": 500}
+
+
+def test_students_and_1098t(store):
+    return_id = store.create_return(tax_year=2024)["return_id"]
+    student = store.add_student(return_id, "Sam Doe", half_time=False)
+    with pytest.raises(ValueError):
+        store.add_student(return_id, "Alex", aotc_years_claimed=5)
+    with pytest.raises(ValueError, match="Invalid education expense kind"):
+        store.add_education_expense(return_id, student["id"], "rent", 900)
+    books = store.add_education_expense(return_id, student["id"], "course_materials", 400)
+
+    extraction = {"form": "1098-T", "fields": {
+        "filer_name": {"value": "State University"},
+        "payments_received": {"value": 6500.0},
+        "scholarships_grants": {"value": 2000.0},
+        "half_time_student": {"value": True},
+        "graduate_student": {"value": False},
+    }}
+    store.import_1098t(return_id, student["id"], extraction, document_id="doc_1")
+    student = store.import_1098t(return_id, student["id"], extraction, document_id="doc_1")
+    assert [(e["kind"], e["amount"]) for e in student["expenses"]] == [
+        ("course_materials", 400), ("tuition", 6500.0), ("scholarship", 2000.0),
+    ]
+    assert (student["half_time"], student["institution"]) == (True, "State University")
+    assert "graduate" not in student
+    with pytest.raises(ValueError, match="1098-T"):
+        store.import_1098t(return_id, student["id"], {"form": "W-2", "fields": {}})
+
+    store.delete_education_expense(return_id, student["id"], books["id"])
+    with pytest.raises(KeyError):
+        store.delete_education_expense(return_id, student["id"], books["id"])
+
+    # The year's American opportunity credit counts toward the four years
+    cloned = store.clone_return(return_id, 2025)["students"][0]
+    assert (cloned["id"], cloned["aotc_years_claimed"], cloned["expenses"]) == (student["id"], 1, [])
+
+    store.delete_student(return_id, student["id"])
+    with pytest.raises(KeyError):
+        store.update_student(return_id, student["id"], graduate=True)
+
+
+def test_divorce_and_alimony(store):
+    return_id = store.create_return(filing_status="married_joint")["return_id"]
+    divorce = store.set_divorce(return_id, decree_date="2024-10-01", separated_date="2024-02-01")
+    assert store.get_return(return_id)["divorce"] == divorce
+    with pytest.raises(ValueError):
+        store.set_divorce(return_id, decree_date="2024-10-01", separated_date="2024-12-01")
+    with pytest.raises(ValueError):
+        store.set_divorce(return_id, decree_type="annulment")
+
+    record = store.add_alimony(return_id, "paid", 12000, "2018-06-01", recipient_ssn="123456789")
+    # The recipient's SSN is encrypted on disk and masked unless revealed
+    assert record["recipient_ssn"] == "*****6789"
+    assert "123456789" not in (store.storage_dir / f"{return_id}.json").read_text()
+    assert store.get_alimony(return_id, reveal=True)[0]["recipient_ssn"] == "123456789"
+    with pytest.raises(ValueError, match="9 digits"):
+        store.add_alimony(return_id, "paid", 100, "2018-06-01", recipient_ssn="12345")
+    with pytest.raises(ValueError):
+        store.add_alimony(return_id, "sent", 100, "2018-06-01")
+    with pytest.raises(ValueError):
+        store.add_alimony(return_id, "paid", 100, "June 2018")
+    # Without an instrument date, the decree date is used
+    from_decree = store.add_alimony(return_id, "received", 3000)
+    assert from_decree["instrument_date"] == "2024-10-01" and from_decree["instrument_date_from_decree"] is True
+    store.delete_alimony(return_id, from_decree["id"])
+    pending = store.create_return(filing_status="single")["return_id"]
+    with pytest.raises(ValueError, match="no decree date"):
+        store.add_alimony(pending, "paid", 100)
+
+    clone = store.clone_return(return_id, 2025)
+    assert clone["filing_status"] == "single"
+    assert clone["alimony"][0]["amount"] == 0 and clone["alimony"][0]["prior_year_amount"] == 12000
+
+    store.delete_alimony(return_id, record["id"])
+    assert store.get_return(return_id)["alimony"] == []
+    with pytest.raises(KeyError):
+        store.delete_alimony(return_id, record["id"])
+
+
+def test_death_and_surviving_spouse_clones(store):
+    taxpayer = {"name": "Pat Doe", "ssn": "111223333", "spouse_name": "Sam Doe", "spouse_ssn": "444556666"}
+    tax_return = store.create_return(filing_status="married_joint", taxpayer=taxpayer)
+    return_id = tax_return["return_id"]
+    store.add_dependent(return_id, "Kid", "son", birth_date="2015-03-01", months_lived_with=12)
+    with pytest.raises(ValueError):
+        store.set_death(return_id, "spouse", "2025-01-10")
+    with pytest.raises(ValueError):
+        store.set_death(return_id, "spouse", "2024-08-12", remarried_date="2024-01-01")
+    death = store.set_death(return_id, "taxpayer", "2024-08-12", personal_representative="Sam Doe")
+    assert store.get_return(return_id)["death"] == death
+
+    # The surviving spouse's returns: two years as a qualifying surviving spouse, then single
+    first = store.clone_return(return_id, 2025)
+    assert first["filing_status"] == "qualifying_surviving_spouse"
+    assert first["taxpayer"]["name"] == "Sam Doe" and first["taxpayer"]["spouse_name"] == "Pat Doe"
+    assert first["death"]["deceased"] == "spouse" and first["death"]["personal_representative"] is None
+    second = store.clone_return(first["return_id"], 2026)
+    assert second["filing_status"] == "qualifying_surviving_spouse"
+    assert store.clone_return(second["return_id"], 2027)["filing_status"] == "single"
+
+    single = store.create_return(taxpayer={"name": "Lee"})
+    store.set_death(single["return_id"], "taxpayer", "2024-03-01")
+    with pytest.raises(ValueError):
+        store.set_death(single["return_id"], "spouse", "2024-03-01")
+    with pytest.raises(ValueError):
+        store.clone_return(single["return_id"], 2025)
+
+
+def test_set_carryforwards(store):
+    return_id = store.create_return()["return_id"]
+    assert store.set_carryforwards(return_id, nol=5000) == {"nol": 5000}
+    with pytest.raises(ValueError):
+        store.set_carryforwards(return_id, nol=-1)
+    with pytest.raises(ValueError):
+        store.set_carryforwards(return_id, casualty=10)
+
+
+def test_status_workflow(store):
+    return_id = store.create_return()["return_id"]
+    for status in ("in_progress", "review", "filed"):
+        tax_return = store.set_status(return_id, status)
+    assert tax_return["status"] == "filed"
+    assert tax_return["filed_at"]
+    assert [h["to"] for h in tax_return["status_history"]] == ["in_progress", "review", "filed"]
+
+    with pytest.raises(ValueError, match="can't move"):
+        store.set_status(return_id, "draft")
+    with pytest.raises(ValueError, match="Invalid status"):
+        store.set_status(return_id, "submitted")
+
+
+def test_filed_return_is_locked(store):
+    return_id = store.create_return()["return_id"]
+    store.add_income_source(return_id, "wages", 50000)
+    for status in ("in_progress", "review", "filed"):
+        store.set_status(return_id, status)
+
+    with pytest.raises(ReturnLockedError):
+        store.add_deduction(return_id, "medical", 100)
+    filed = store.get_return(return_id)
+    filed["filing_status"] = "married_joint"
+    with pytest.raises(ReturnLockedError):
+        store.save_return(filed)
+
+    amended = store.set_status(return_id, "amended")
+    assert amended["amendment"]["original"]["income_sources"][0]["amount"] == 50000
+    store.add_deduction(return_id, "medical", 100)
+    assert len(store.get_return(return_id)["deductions"]) == 1
+
+
+def test_direct_deposit_encrypted_and_masked(store):
+    return_id = store.create_return()["return_id"]
+    assert store.get_direct_deposit(return_id) is None
+
+    masked = store.set_direct_deposit(return_id, "021000021", "000123456789", "savings")
+    assert masked["routing_number"] == "*****0021"
+    assert masked["account_number"] == "********6789"
+    assert masked["account_type"] == "savings"
+
+    raw = (store.storage_dir / f"{return_id}.json").read_text()
+    assert "000123456789" not in raw and "021000021" not in raw
+    revealed = store.get_direct_deposit(return_id, reveal=True)
+    assert revealed["account_number"] == "000123456789"
+
+    assert store.clear_direct_deposit(return_id) is True
+    assert store.clear_direct_deposit(return_id) is False
+    assert store.get_direct_deposit(return_id) is None
+
+
+def test_direct_deposit_validation(store):
+    return_id = store.create_return()["return_id"]
+    with pytest.raises(ValueError, match="routing"):
+        store.set_direct_deposit(return_id, "021000022", "123456")
+    with pytest.raises(ValueError, match="Account number"):
+        store.set_direct_deposit(return_id, "021000021", "12")
+    with pytest.raises(ValueError, match="account_type"):
+        store.set_direct_deposit(return_id, "021000021", "123456", "brokerage")
+
+
+def test_ip_pins_encrypted_and_masked(store):
+    return_id = store.create_return(taxpayer={"name": "Pat Doe", "spouse_name": "Sam Doe"})["return_id"]
+    assert store.get_ip_pins(return_id) == {}
+
+    masked = store.set_ip_pin(return_id, "taxpayer", "482913")
+    assert masked["pin"] == "****13"
+    assert masked["valid_year"] == 2025
+    store.set_ip_pin(return_id, "spouse", "105577", valid_year=2026)
+
+    raw = (store.storage_dir / f"{return_id}.json").read_text()
+    assert "482913" not in raw and "105577" not in raw
+    revealed = store.get_ip_pins(return_id, reveal=True)
+    assert revealed["taxpayer"]["pin"] == "482913"
+    assert revealed["spouse"] == {**revealed["spouse"], "pin": "105577", "valid_year": 2026}
+
+    assert store.clear_ip_pin(return_id, "spouse") is True
+    assert store.clear_ip_pin(return_id, "spouse") is False
+    assert set(store.get_ip_pins(return_id)) == {"taxpayer"}
+    # Next year's return needs the PIN the IRS issues next January
+    assert "ip_pins" not in store.clone_return(return_id, 2025)
+
+
+def test_ip_pin_validation(store):
+    return_id = store.create_return()["return_id"]
+    with pytest.raises(ValueError, match="6 digits"):
+        store.set_ip_pin(return_id, "taxpayer", "12345")
+    with pytest.raises(ValueError, match="person"):
+        store.set_ip_pin(return_id, "dependent", "123456")
+    with pytest.raises(ValueError, match="no spouse"):
+        store.set_ip_pin(return_id, "spouse", "123456")
+    with pytest.raises(ValueError, match="can't be used on a 2024 return"):
+        store.set_ip_pin(return_id, "taxpayer", "123456", valid_year=2024)
+
+
+def test_seed_checklist_from_template_and_records(store):
+    return_id = store.create_return()["return_id"]
+    store.add_income_source(return_id, "wages", 50000, "Acme Corp")
+    store.add_deduction(return_id, "mortgage_interest", 9000)
+    store.add_deduction(return_id, "mortgage_interest", 1000)
+
+    checklist = store.seed_checklist(return_id)
+    titles = [item["title"] for item in checklist["items"]]
+    assert len(titles) == len(ReturnStore.CHECKLIST_TEMPLATE) + 2
+    assert "W-2 from Acme Corp" in titles
+    assert "Form 1098 mortgage interest statement" in titles
+    assert {item["source"] for item in checklist["items"]} == {"template"}
+
+    # Seeding again only adds items for new records
+    store.add_income_source(return_id, "interest", 120, "Credit Union")
+    assert len(store.seed_checklist(return_id)["items"]) == len(titles) + 1
+
+
+def test_foreign_accounts(store):
+    return_id = store.create_return()["return_id"]
+    account = store.add_foreign_account(return_id, " Maple Bank ", "Canada", 14200, year_end_balance=9800)
+    assert account["institution"] == "Maple Bank"
+    assert store.update_foreign_account(return_id, account["id"], owner="joint")["owner"] == "joint"
+    with pytest.raises(ValueError, match="cannot exceed"):
+        store.update_foreign_account(return_id, account["id"], max_balance=5000)
+
+    with pytest.raises(ValueError, match="cannot exceed"):
+        store.add_foreign_account(return_id, "Bank", "Mexico", 1000, year_end_balance=2000)
+    with pytest.raises(ValueError, match="account type"):
+        store.add_foreign_account(return_id, "Bank", "Mexico", 1000, account_type="crypto")
+    with pytest.raises(ValueError, match="required"):
+        store.add_foreign_account(return_id, "", "Mexico", 1000)
+
+    store.delete_foreign_account(return_id, account["id"])
+    with pytest.raises(KeyError):
+        store.update_foreign_account(return_id, account["id"], max_balance=1)
+
+
+def test_rental_properties(store):
+    return_id = store.create_return()["return_id"]
+    rental = store.add_rental_property(return_id, " 12 Elm St ", 275000, "2020-01-15", land_value=50000, rents=24000)
+    assert rental["address"] == "12 Elm St"
+    with pytest.raises(ValueError, match="placed in service by 2024"):
+        store.add_rental_property(return_id, "9 Oak Ave", 100000, "2025-02-01")
+    with pytest.raises(ValueError, match="sale price"):
+        store.update_rental_property(return_id, rental["id"], date_sold="2024-06-10")
+    with pytest.raises(ValueError, match="fall in 2024"):
+        store.update_rental_property(return_id, rental["id"], date_sold="2023-06-10", sale_price=1)
+
+    # Unsold rentals carry their basis into next year with the year's figures cleared
+    clone = store.clone_return(return_id, 2025)
+    assert clone["rental_properties"] == [{"id": rental["id"], "address": "12 Elm St", "building_basis": 275000,
+                                           "land_value": 50000, "placed_in_service": "2020-01-15",
+                                           "rents": 0, "expenses": 0}]
+
+    sold = store.update_rental_property(return_id, rental["id"], date_sold="2024-06-10", sale_price=400000)
+    assert sold["sale_price"] == 400000
+    assert store.clone_return(return_id, 2025)["rental_properties"] == []
+    store.delete_rental_property(return_id, rental["id"])
+    with pytest.raises(KeyError):
+        store.delete_rental_property(return_id, rental["id"])
+
+
+def test_like_kind_exchange(store):
+    return_id = store.create_return()["return_id"]
+    rental = store.add_rental_property(return_id, "12 Elm St", 275000, "2020-01-15", land_value=50000)
+    with pytest.raises(ValueError, match="180 days"):
+        store.record_like_kind_exchange(return_id, rental["id"], "2024-06-10", "8 Pine Rd", 500000,
+                                        replacement_date="2024-12-20")
+
+    result = store.record_like_kind_exchange(
+        return_id, rental["id"], "2024-06-10", "8 Pine Rd", 500000,
+        replacement_date="2024-08-01", replacement_land_value=100000, boot_received=10000,
+    )
+    relinquished, replacement = result["relinquished"], result["replacement"]
+    assert relinquished["date_sold"] == "2024-06-10" and relinquished["sale_price"] == 510000
+    assert relinquished["like_kind_exchange"]["replacement_id"] == replacement["id"]
+    assert replacement["placed_in_service"] == "2024-08-01"
+    assert replacement["acquired_by_exchange"] == {"relinquished_id": rental["id"], "exchange_date": "2024-06-10",
+                                                   "deferred_gain": 219166.67}
+    with pytest.raises(ValueError, match="already sold"):
+        store.record_like_kind_exchange(return_id, rental["id"], "2024-07-01", "1 Main St", 100000)
+    with pytest.raises(KeyError):
+        store.record_like_kind_exchange(return_id, "rent_missing", "2024-07-01", "1 Main St", 100000)
+
+    # Only the replacement carries into next year, with its exchange history
+    clone = store.clone_return(return_id, 2025)
+    assert [r["id"] for r in clone["rental_properties"]] == [replacement["id"]]
+    assert clone["rental_properties"][0]["prior_depreciation"] == 44166.67
+
+
+def test_at_risk_carries_to_next_year(store):
+    return_id = store.create_return()["return_id"]
+    business = store.add_business(return_id, "Studio")
+    rental = store.add_rental_property(return_id, "12 Elm St", 275000, "2020-01-15")
+    assert store.set_at_risk(return_id, business["id"], at_risk_amount=2000)["at_risk_amount"] == 2000
+    assert store.set_at_risk(return_id, rental["id"], carryforward=300)["at_risk_carryforward"] == 300
+    with pytest.raises(ValueError):
+        store.set_at_risk(return_id, business["id"], at_risk_amount=-1)
+    with pytest.raises(KeyError):
+        store.set_at_risk(return_id, "biz_missing", at_risk_amount=1)
+
+    at_risk = [
+        {"activity_id": business["id"], "ending_at_risk": 0.0, "carryforward": 4000.0},
+        {"activity_id": rental["id"], "ending_at_risk": None, "carryforward": 0.0},
+    ]
+    clone = store.clone_return(return_id, 2025, at_risk=at_risk)
+    assert clone["businesses"][0]["at_risk_amount"] == 0.0
+    assert clone["businesses"][0]["at_risk_carryforward"] == 4000.0
+    assert "at_risk_amount" not in clone["rental_properties"][0]
+    assert "at_risk_carryforward" not in clone["rental_properties"][0]
+
+
+def test_platform_allocation(store):
+    return_id = store.create_return()["return_id"]
+    business = store.add_business(return_id, "Studio")
+    store.set_platform_allocation(return_id, "doc_1", personal_sales=900, personal_cost=1400)
+    allocation = store.set_platform_allocation(return_id, "doc_1", business_id=business["id"])
+    assert store.get_return(return_id)["platform_allocations"] == [allocation]
+    assert allocation["personal_sales"] == 0
+    with pytest.raises(ValueError):
+        store.set_platform_allocation(return_id, "doc_1", personal_sales=-1)
+    with pytest.raises(KeyError):
+        store.set_platform_allocation(return_id, "doc_1", business_id="biz_missing")
+
+
+def test_estimated_payments(store):
+    return_id = store.create_return()["return_id"]
+    payment = store.add_estimated_payment(return_id, "Q1", 2400, "2025-04-10", "IRS Direct Pay")
+    assert payment["tax_year"] == 2025
+    with pytest.raises(ValueError):
+        store.add_estimated_payment(return_id, "Q5", 100, "2025-04-10")
+    with pytest.raises(ValueError):
+        store.add_estimated_payment(return_id, "Q2", 0, "2025-06-10")
+    with pytest.raises(ValueError):
+        store.add_estimated_payment(return_id, "Q2", 100, "June 10")
+
+    # Next year's payments can be logged after this return is filed
+    for status in ("in_progress", "review", "filed"):
+        store.set_status(return_id, status)
+    second = store.add_estimated_payment(return_id, "Q2", 2400, "2025-06-12")
+    store.delete_estimated_payment(return_id, payment["id"])
+    assert store.get_return(return_id)["estimated_payments"] == [second]
+    with pytest.raises(KeyError):
+        store.delete_estimated_payment(return_id, payment["id"])
+
+    # They're credited on next year's return, which gets them when this one is cloned
+    cloned = store.clone_return(return_id, 2025)
+    assert cloned["estimated_payments"] == [second]
+    assert store.clone_return(return_id, 2026)["estimated_payments"] == []
+
+
+def test_hobby_determinations(store):
+    return_id = store.create_return()["return_id"]
+    business = store.add_business(return_id, "Kennel")
+    first = store.save_hobby_determination(
+        return_id, "Dog breeding", {"manner": "no", "expertise": "no"}, notes={"manner": "No separate books"},
+    )
+    second = store.save_hobby_determination(return_id, "Dog breeding", {}, profit_years=3, business_id=business["id"])
+    assert first["recommendation"] == "hobby" and first["determined_at"]
+    assert second["recommendation"] == "schedule_c" and second["business_id"] == business["id"]
+    assert [d["id"] for d in store.get_return(return_id)["hobby_determinations"]] == [first["id"], second["id"]]
+    with pytest.raises(ValueError):
+        store.save_hobby_determination(return_id, " ", {})
+    with pytest.raises(ValueError):
+        store.save_hobby_determination(return_id, "Dog breeding", {"manner": "maybe"})
+    with pytest.raises(KeyError):
+        store.save_hobby_determination(return_id, "Dog breeding", {}, business_id="biz_missing")
+
+    store.delete_hobby_determination(return_id, first["id"])
+    with pytest.raises(KeyError):
+        store.delete_hobby_determination(return_id, first["id"])
+
+
+def test_holdings(store):
+    return_id = store.create_return()["return_id"]
+    holding = store.add_holding(return_id, " vti ", 40, 10800, "2023-03-14", market_value=9400)
+    assert holding["symbol"] == "VTI"
+    assert store.update_holding(return_id, holding["id"], market_value=9100)["market_value"] == 9100
+    with pytest.raises(ValueError, match="Quantity"):
+        store.add_holding(return_id, "VTI", 0, 100, "2023-03-14")
+    with pytest.raises(ValueError, match="YYYY-MM-DD"):
+        store.update_holding(return_id, holding["id"], date_acquired="03/14/2023")
+
+    # Open positions carry into next year
+    assert store.clone_return(return_id, 2025)["holdings"][0]["market_value"] == 9100
+    store.delete_holding(return_id, holding["id"])
+    with pytest.raises(KeyError):
+        store.update_holding(return_id, holding["id"], market_value=1)
+
+
+def test_crypto_receipts_and_disposals(store):
+    return_id = store.create_return()["return_id"]
+    receipt = store.add_crypto_receipt(return_id, "staking", "ETH", 0.5, 1500, "2024-03-01")
+    income, lot = receipt["income_source"], receipt["lot"]
+    assert income["type"] == "other" and income["amount"] == 1500.0
+    assert lot["income_source_id"] == income["id"]
+    store.add_crypto_receipt(return_id, "purchase", "ETH", 1, 2000, "2023-01-10")
+
+    sales = store.dispose_crypto(return_id, "ETH", 1.25, 5000, "2024-05-01")
+    assert [(s["cost_basis"], s["term"], s["source"]) for s in sales] == [(2000.0, "long", "crypto"), (750.0, "short", "crypto")]
+    tax_return = store.get_return(return_id)
+    assert [c["id"] for c in tax_return["capital_transactions"]] == [s["id"] for s in sales]
+    assert [lot["remaining"] for lot in tax_return["crypto_lots"]] == [0.25, 0.0]
+
+    with pytest.raises(ValueError, match="received in 2024"):
+        store.add_crypto_receipt(return_id, "airdrop", "UNI", 400, 2000, "2023-09-01")
+    with pytest.raises(ValueError, match="Business not found"):
+        store.add_crypto_receipt(return_id, "mining", "BTC", 0.01, 650, "2024-02-01", business_id="biz_missing")
+    with pytest.raises(ValueError, match="Sales must fall in 2024"):
+        store.dispose_crypto(return_id, "ETH", 0.1, 300, "2025-01-02")
+
+    # Unsold lots carry their basis into next year's return
+    clone = store.clone_return(return_id, 2025)
+    assert [(c["asset"], c["remaining"], c["cost_basis"]) for c in clone["crypto_lots"]] == [("ETH", 0.25, 1500.0)]
+    assert "income_source_id" not in clone["crypto_lots"][0]
+
+
+def test_travel_log(store):
+    return_id = store.create_return()["return_id"]
+    later = store.add_travel_days(return_id, "ny", "2024-06-03", "2024-06-07", note="Client visit")
+    earlier = store.add_travel_days(return_id, "CT", "2024-02-10")
+    assert later["state"] == "NY"
+    assert earlier["end_date"] == "2024-02-10"
+    assert [e["id"] for e in store.get_return(return_id)["travel_log"]] == [earlier["id"], later["id"]]
+
+    with pytest.raises(ValueError, match="before the start"):
+        store.add_travel_days(return_id, "NY", "2024-06-07", "2024-06-03")
+    with pytest.raises(ValueError, match="fall in 2024"):
+        store.add_travel_days(return_id, "NY", "2024-12-30", "2025-01-02")
+    with pytest.raises(ValueError, match="YYYY-MM-DD"):
+        store.add_travel_days(return_id, "NY", "June 3")
+
+    store.delete_travel_days(return_id, later["id"])
+    with pytest.raises(KeyError):
+        store.delete_travel_days(return_id, later["id"])
+
+
+def test_prior_returns_follow_clone_chain(store):
+    first = store.create_return(tax_year=2022)
+    second = store.clone_return(first["return_id"], 2023)
+    third = store.clone_return(second["return_id"], 2024)
+    assert [r["tax_year"] for r in store.prior_returns(third)] == [2023, 2022]
+    assert [r["tax_year"] for r in store.prior_returns(third, limit=1)] == [2023]
+    assert store.prior_returns(first) == []
+
+
+def test_load_prior_year_deductions(store):
+    prior = store.create_return(tax_year=2024)
+    store.add_income_source(prior["return_id"], "wages", 90000)
+    store.add_deduction(prior["return_id"], "state_local_tax", 6000, "State income tax")
+    store.add_deduction(prior["return_id"], "mortgage_interest", 11000, "Home loan")
+    current = store.clone_return(prior["return_id"], 2025)
+    assert "prior_year_deductions" not in current
+    with pytest.raises(ValueError, match="hasn't been calculated"):
+        store.load_prior_year_deductions(current["return_id"])
+
+    store.save_calculation(prior["return_id"], calculate_return(store.get_return(prior["return_id"])))
+    figures = store.load_prior_year_deductions(current["return_id"])
+    assert figures["itemized"] is True and figures["state_income_tax"] == 6000
+    assert store.get_return(current["return_id"])["prior_year_deductions"] == figures
+    # A clone made after the prior year was saved starts with the figures
+    assert store.clone_return(prior["return_id"], 2025)["prior_year_deductions"] == figures
+
+    unlinked = store.create_return(tax_year=2025)
+    with pytest.raises(KeyError):
+        store.load_prior_year_deductions(unlinked["return_id"])
+    with pytest.raises(ValueError, match="not 2024"):
+        store.load_prior_year_deductions(unlinked["return_id"], current["return_id"])
+
+
+def test_save_document_requests(store):
+    return_id = store.create_return()["return_id"]
+    requests = [
+        {"title": "W-2 from Acme Corp", "form": "W-2", "payer": "Acme Corp", "received": False, "document_id": None},
+        {"title": "1099-INT (interest)", "form": "1099-INT", "payer": None, "received": True, "document_id": "doc_1"},
+    ]
+    checklist = store.save_document_requests(return_id, requests)
+    items = {item["title"]: item for item in checklist["items"]}
+    assert items["W-2 from Acme Corp"]["checked"] is False
+    assert items["1099-INT (interest)"]["checked"] is True
+    assert items["1099-INT (interest)"]["document_id"] == "doc_1"
+    assert {item["source"] for item in items.values()} == {"request"}
+
+    # Running again checks off what arrived, without unchecking or duplicating
+    requests[0].update(received=True, document_id="doc_2")
+    requests[1].update(received=False, document_id=None)
+    checklist = store.save_document_requests(return_id, requests)
+    assert len(checklist["items"]) == 2
+    assert all(item["checked"] for item in checklist["items"])
+
+
+def test_manual_checklist_items_and_progress(store):
+    return_id = store.create_return()["return_id"]
+    store.save_checklist(return_id, [{"title": "W-2", "received": True}])
+    item = store.add_checklist_item(return_id, "K-1 from Maple Partners LP", form="K-1")
+    store.set_checklist_item(return_id, item["id"], notes="Still waiting; promised by March 15")
+
+    progress = ReturnStore.checklist_progress(store.get_return(return_id))
+    assert (progress["total"], progress["completed"], progress["percent_complete"]) == (2, 1, 50)
+    assert progress["outstanding"][0]["notes"] == "Still waiting; promised by March 15"
+
+    # Regenerating replaces generated items but keeps the ones added by hand
+    checklist = store.save_checklist(return_id, [{"title": "1099-INT"}])
+    assert [i["title"] for i in checklist["items"]] == ["1099-INT", "K-1 from Maple Partners LP"]
+
+    store.delete_checklist_item(return_id, item["id"])
+    with pytest.raises(KeyError):
+        store.delete_checklist_item(return_id, item["id"])
+    with pytest.raises(ValueError):
+        store.add_checklist_item(return_id, "Receipts", kind="errand")
+
+
+def test_return_notes(store):
+    return_id = store.create_return()["return_id"]
+    assert store.get_return(return_id)["notes"] == ""
+    assert store.set_notes(return_id, "  Ask about the home office  ")["notes"] == "Ask about the home office"
+
+
+def test_record_owner_validated(store):
+    return_id = store.create_return(filing_status="married_joint")["return_id"]
+    source = store.add_income_source(return_id, "wages", 30000, owner="spouse")
+    assert store.update_income_source(return_id, source["id"], owner="joint")["owner"] == "joint"
+    with pytest.raises(ValueError, match="owner"):
+        store.add_deduction(return_id, "medical", 100, owner="partner")
+
+
+def test_set_extension(store):
+    return_id = store.create_return()["return_id"]
+    store.add_state_return(return_id, "CA")
+    extension = store.set_extension(return_id, "2025-04-10", confirmation="4868-123", states=["ca"])
+    assert extension["states"] == ["CA"]
+    assert store.get_return(return_id)["extension"]["confirmation"] == "4868-123"
+
+    with pytest.raises(ValueError, match="NY"):
+        store.set_extension(return_id, "2025-04-10", states=["NY"])
+    with pytest.raises(ValueError, match="filed_on"):
+        store.set_extension(return_id, "April 10")
@@ -0,0 +1,103 @@
+"""Tests for the printable return review packet."""
+import io
+import re
+
+import pytest
+
+from app.forms.pdf import PdfDocument, render_form
+from app.forms.review_packet import build_review_packet
+from app.utils.document_store import DocumentStore
+from app.utils.return_store import ReturnStore
+
+
+@pytest.fixture
+def store(tmp_path):
+    return ReturnStore(storage_dir=str(tmp_path / "returns"))
+
+
+@pytest.fixture
+def documents(tmp_path):
+    return DocumentStore(storage_dir=str(tmp_path / "documents"))
+
+
+def page_count(pdf):
+    return int(re.search(rb"/Type /Pages /Kids \[[^\]]*\] /Count (\d+)", pdf).group(1))
+
+
+def test_render_form_unchanged_by_shared_layout():
+    pdf = render_form("Schedule X", "Test - 2024", [("Part I", [("1", "Line one", 10.0)])])
+    assert pdf.startswith(b"%PDF-1.4")
+    assert b"Page 1 of 1" in pdf
+    assert b"/XObject" not in pdf
+
+
+def test_document_embeds_jpeg():
+    document = PdfDocument(title="Images")
+    name = document.add_jpeg(b"\xff\xd8fake\xff\xd9", 4, 2)
+    document.add_page().image(50, 50, 40, 20, name)
+    pdf = document.to_bytes()
+    assert name == "Im1"
+    assert b"/Subtype /Image /Width 4 /Height 2" in pdf
+    assert b"/XObject << /Im1 6 0 R >>" in pdf
+    assert b"/Im1 Do" in pdf
+
+
+def test_packet_sections_and_prior_year(store, documents):
+    prior = store.create_return(tax_year=2023, taxpayer={"name": "Pat Doe"})
+    store.add_income_source(prior["return_id"], "wages", 50000, withholding=5000)
+    store.add_deduction(prior["return_id"], "mortgage_interest", 9000)
+    current = store.clone_return(prior["return_id"], 2024)
+    store.update_income_source(current["return_id"], current["income_sources"][0]["id"], amount=60000)
+    business = store.add_business(current["return_id"], "Design Studio", gross_receipts=20000)
+    store.add_deduction(current["return_id"], "charitable", 20000, description="Food bank")
+
+    pdf = build_review_packet(store.get_return(current["return_id"]), documents, store.get_return(prior["return_id"]))
+    assert b"Return Review Packet" in pdf
+    assert b"Two-Year Comparison" in pdf
+    assert b"Itemized Deduction Detail" in pdf
+    assert b"Food bank" in pdf
+    assert b"Schedule A \\(Form 1040\\)" in pdf
+    assert b"Schedule C \\(Form 1040\\)" in pdf
+    assert b"Schedule SE \\(Form 1040\\)" in pdf
+    assert business["name"].encode() in pdf
+    # Wages went from 50,000 to 60,000
+    assert b"(50,000.00)" in pdf and b"(60,000.00)" in pdf and b"(10,000.00)" in pdf
+    total = page_count(pdf)
+    assert f"Page {total} of {total}".encode() in pdf
+
+
+def test_packet_without_prior_return(store, documents):
+    tax_return = store.create_return()
+    store.add_income_source(tax_return["return_id"], "wages", 40000)
+    pdf = build_review_packet(store.get_return(tax_return["return_id"]), documents)
+    assert b"No prior-year return" in pdf
+    assert b"Itemized Deduction Detail" not in pdf
+
+
+def test_packet_shows_masked_ip_pin(store, documents):
+    tax_return = store.create_return(taxpayer={"name": "Pat Doe"})
+    store.add_income_source(tax_return["return_id"], "wages", 40000)
+    store.set_ip_pin(tax_return["return_id"], "taxpayer", "482913")
+    pdf = build_review_packet(
+        store.get_return(tax_return["return_id"]), documents, ip_pins=store.get_ip_pins(tax_return["return_id"])
+    )
+    assert b"IP PIN - Pat Doe" in pdf
+    assert b"****13 \\(for 2025\\)" in pdf
+    assert b"482913" not in pdf
+
+
+def test_packet_receipt_thumbnails(store, documents):
+    Image = pytest.importorskip("PIL.Image")
+    output = io.BytesIO()
+    Image.new("RGB", (200, 100), "white").save(output, format="PNG")
+    receipt = documents.upload_document("receipt.png", content=b"receipt", document_type="receipt")
+    documents.save_thumbnail(receipt["document_id"], output.getvalue())
+
+    tax_return = store.create_return()
+    store.add_deduction(tax_return["return_id"], "medical", 500, receipt_id=receipt["document_id"])
+    store.add_deduction(tax_return["return_id"], "medical", 80, receipt_id="doc_0000000000000000")
+
+    pdf = build_review_packet(store.get_return(tax_return["return_id"]), documents)
+    assert b"/Filter /DCTDecode" in pdf
+    assert pdf.count(b"/Subtype /Image") == 1
+    assert b"(no thumbnail)" in pdf
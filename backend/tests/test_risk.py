@@ -0,0 +1,85 @@
+"""Tests for rules-based audit risk scoring."""
+from app.tax_engine.risk import assess_audit_risk
+
+
+def make_return(tax_year=2024, income=None, deductions=None, businesses=None):
+    return {
+        "return_id": f"ret_{tax_year}",
+        "tax_year": tax_year,
+        "filing_status": "single",
+        "income_sources": income or [],
+        "deductions": deductions or [],
+        "businesses": businesses or [],
+    }
+
+
+def studio(gross_receipts, expenses, home_office=0):
+    return {
+        "id": "biz_1", "name": "Pottery Studio", "owner": "taxpayer",
+        "gross_receipts": gross_receipts, "home_office": home_office,
+        "expenses": [{"category": "supplies", "amount": amount} for amount in expenses],
+    }
+
+
+def codes(result):
+    return [finding["code"] for finding in result["findings"]]
+
+
+def test_clean_return_is_low_risk():
+    result = assess_audit_risk(make_return(income=[{"type": "wages", "amount": 72345.67}]))
+    assert result == {"score": 0, "level": "low", "findings": []}
+
+
+def test_repeated_schedule_c_losses():
+    wages = [{"type": "wages", "amount": 90000}]
+    current = make_return(income=wages, businesses=[studio(4000, [6123.45])])
+    assert codes(assess_audit_risk(current)) == ["schedule_c_loss"]
+
+    priors = [
+        make_return(2023, businesses=[studio(3000, [5000.10])]),
+        make_return(2022, businesses=[{**studio(2000, [4100.20]), "id": "biz_old"}]),
+        make_return(2021, businesses=[studio(9000, [1000.30])]),
+    ]
+    result = assess_audit_risk(current, priors)
+    finding = result["findings"][0]
+    assert finding["code"] == "schedule_c_repeated_losses"
+    assert finding["loss_years"] == [2022, 2023, 2024]
+    assert result["score"] == 25 and result["level"] == "moderate"
+
+
+def test_charitable_ratio_and_home_office_with_wages():
+    result = assess_audit_risk(make_return(
+        income=[{"type": "wages", "amount": 60000}],
+        deductions=[{"category": "charitable", "amount": 30000.55}],
+        businesses=[studio(30000, [2200.75], home_office=1500.25)],
+    ))
+    assert codes(result) == ["charitable_ratio_high", "home_office_with_wages"]
+    assert result["findings"][0]["title"] == "Charitable gifts are 35% of income"
+    assert result["score"] == 30
+
+    modest = assess_audit_risk(make_return(
+        income=[{"type": "wages", "amount": 60000}],
+        deductions=[{"category": "charitable", "amount": 7000.10}],
+    ))
+    assert codes(modest) == ["charitable_ratio"]
+
+
+def test_round_numbers():
+    business = studio(50000, [1200, 800, 2500, 300.17])
+    result = assess_audit_risk(make_return(
+        deductions=[{"category": "medical", "amount": 600}],
+        businesses=[business],
+    ))
+    assert codes(result) == ["round_numbers"]
+    assert result["findings"][0]["title"] == "5 of 6 amounts are round hundreds"
+
+
+def test_vehicle_full_business_use_and_high_income():
+    business = studio(1500000, [20000.40])
+    business["vehicles"] = [{
+        "id": "veh_1", "description": "2024 Pickup", "placed_in_service": "2024-01-10", "cost": 40000,
+        "mileage_log": [{"miles": 9000, "business": True}], "expenses": [], "method_history": {},
+    }]
+    result = assess_audit_risk(make_return(businesses=[business]))
+    assert sorted(codes(result)) == ["high_income", "vehicle_full_business_use"]
+    assert result["score"] == 20
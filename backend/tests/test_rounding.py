@@ -0,0 +1,33 @@
+"""Tests for the rounding policy."""
+from decimal import Decimal
+
+import pytest
+
+from app.tax_engine.rounding import round_amount, round_amounts
+
+
+def test_round_amount_half_up():
+    assert round_amount(2.49, "whole_dollars") == Decimal("2")
+    assert round_amount(2.5, "whole_dollars") == Decimal("3")
+    assert round_amount(-2.5, "whole_dollars") == Decimal("-3")
+    assert round_amount(0.125) == Decimal("0.13")
+    with pytest.raises(ValueError):
+        round_amount(1, "nearest_ten")
+
+
+def test_round_amounts_skips_rates():
+    result = {
+        "tax_year": 2024,
+        "tax_liability": 10541.5,
+        "effective_tax_rate": 12.4,
+        "bracket_breakdown": [{"rate": 12.0, "income_in_bracket": 47149.99, "upper_limit": None}],
+        "notes": ["kept"],
+    }
+    assert round_amounts(result) is result
+    assert round_amounts(result, "whole_dollars") == {
+        "tax_year": 2024,
+        "tax_liability": 10542.0,
+        "effective_tax_rate": 12.4,
+        "bracket_breakdown": [{"rate": 12.0, "income_in_bracket": 47150.0, "upper_limit": None}],
+        "notes": ["kept"],
+    }
@@ -0,0 +1,39 @@
+"""Golden tests: every scenario in app/tax_engine/data/scenarios must match its hand-verified results."""
+import json
+
+import pytest
+
+from app.tax_engine.scenarios import load_scenarios, run_scenario
+
+SCENARIOS = load_scenarios()
+
+
+@pytest.mark.parametrize("scenario", SCENARIOS, ids=[s["file"] for s in SCENARIOS])
+def test_scenario_matches_expected(scenario):
+    result = run_scenario(scenario)
+    assert result["mismatches"] == []
+
+
+def test_mismatches_are_reported():
+    scenario = {
+        "name": "Wrong on purpose",
+        "source": "n/a",
+        "return": {"income_sources": [{"type": "wages", "amount": 75000}]},
+        "expected": {"taxable_income": 60400, "total_tax": 8000, "deduction_type": "Itemized", "credits.nope": 1},
+    }
+    result = run_scenario(scenario)
+    assert result["passed"] is False
+    assert result["mismatches"] == [
+        {"field": "total_tax", "expected": 8000, "actual": 8341.0},
+        {"field": "deduction_type", "expected": "Itemized", "actual": "Standard"},
+        {"field": "credits.nope", "expected": 1, "actual": None},
+    ]
+
+
+def test_load_rejects_incomplete_files(tmp_path):
+    (tmp_path / "a.json").write_text(json.dumps({"name": "No expectations", "source": "n/a", "return": {}}))
+    with pytest.raises(ValueError, match="a.json: missing expected"):
+        load_scenarios(str(tmp_path))
+    (tmp_path / "a.json").write_text("{")
+    with pytest.raises(ValueError, match="a.json: invalid JSON"):
+        load_scenarios(str(tmp_path))
@@ -0,0 +1,122 @@
+"""Tests for the Schedule A engine and form."""
+from decimal import Decimal
+
+import pytest
+
+from app.forms.package import build_return_package, calculate_schedule_a
+from app.forms.pdf import render_form
+from app.forms.schedule_a import render_schedule_a
+from app.tax_engine.schedule_a import ScheduleACalculator
+from app.utils.return_store import ReturnStore
+
+
+@pytest.fixture
+def calculator():
+    return ScheduleACalculator(2024)
+
+
+def deduction(category, amount, **fields):
+    return {"category": category, "amount": amount, **fields}
+
+
+def test_medical_floor(calculator):
+    result = calculator.calculate([deduction("medical", 9000)], Decimal("100000"), "single")
+    assert result["lines"]["3"] == 7500.0
+    assert result["lines"]["4"] == 1500.0
+
+    below = calculator.calculate([deduction("medical", 5000)], Decimal("100000"), "single")
+    assert below["lines"]["4"] == 0.0
+
+
+def test_salt_cap(calculator):
+    deductions = [
+        deduction("state_local_tax", 8000),
+        deduction("state_local_tax", 6000, tax_type="real_estate"),
+        deduction("state_local_tax", 500, tax_type="personal_property"),
+    ]
+    result = calculator.calculate(deductions, Decimal("200000"), "married_joint")
+    assert (result["lines"]["5a"], result["lines"]["5b"], result["lines"]["5c"]) == (8000.0, 6000.0, 500.0)
+    assert result["lines"]["5d"] == 14500.0
+    assert result["lines"]["5e"] == 10000.0
+    assert any("capped" in note for note in result["notes"])
+
+    separate = calculator.calculate(deductions, Decimal("200000"), "married_separate")
+    assert separate["lines"]["5e"] == 5000.0
+
+
+def test_invalid_tax_type(calculator):
+    with pytest.raises(ValueError, match="tax_type"):
+        calculator.calculate([deduction("state_local_tax", 100, tax_type="car")], Decimal("50000"), "single")
+
+
+def test_charitable_limits_and_carryforward(calculator):
+    deductions = [deduction("charitable", 40000), deduction("charitable", 30000, non_cash=True)]
+    result = calculator.calculate(deductions, Decimal("100000"), "single")
+    # Cash limited to 60% of AGI; noncash shares what's left of the 60% ceiling
+    assert result["lines"]["11"] == 40000.0
+    assert result["lines"]["12"] == 20000.0
+    assert result["lines"]["14"] == 60000.0
+    assert result["charitable_carryforward"] == 10000.0
+    assert any("Form 8283" in note for note in result["notes"])
+
+
+def test_itemize_compared_to_standard(calculator):
+    small = calculator.calculate([deduction("mortgage_interest", 5000)], Decimal("80000"), "single")
+    assert small["itemize"] is False
+    assert small["standard_deduction"] == 14600.0
+
+    large = calculator.calculate(
+        [deduction("mortgage_interest", 12000), deduction("state_local_tax", 7000)], Decimal("80000"), "single"
+    )
+    assert large["itemize"] is True
+    assert large["total_itemized_deductions"] == 19000.0
+
+
+def test_validation(calculator):
+    with pytest.raises(ValueError):
+        ScheduleACalculator(2023)
+    with pytest.raises(ValueError, match="filing status"):
+        calculator.calculate([], Decimal("1000"), "unknown")
+    with pytest.raises(ValueError, match="negative"):
+        calculator.calculate([], Decimal("-1"), "single")
+
+
+def test_render_form_is_valid_pdf():
+    sections = [(f"Section {i}", [(str(n), f"Line {n}", n * 100.0) for n in range(10)]) for i in range(8)]
+    pdf = render_form("Test Form", "2024", sections, notes=["A note (with parentheses)"])
+    assert pdf.startswith(b"%PDF-1.4")
+    assert pdf.rstrip().endswith(b"%%EOF")
+    assert pdf.count(b"/Type /Page ") >= 2
+    assert b"A note \\(with parentheses\\)" in pdf
+
+    # Every xref entry points at the start of its object
+    xref = int(pdf.rsplit(b"startxref\n", 1)[1].split()[0])
+    entries = pdf[xref:].split(b"\n")[3:]
+    for object_id, entry in enumerate(entries, start=1):
+        if not entry[:10].isdigit():
+            break
+        assert pdf[int(entry[:10]):].startswith(b"%d 0 obj" % object_id)
+
+
+def test_stored_return_package(tmp_path):
+    import io
+    import json
+    import zipfile
+
+    store = ReturnStore(storage_dir=str(tmp_path / "returns"))
+    tax_return = store.create_return(taxpayer={"name": "Pat Doe"})
+    store.add_income_source(tax_return["return_id"], "wages", 60000)
+    store.add_deduction(tax_return["return_id"], "medical", 6000)
+    tax_return = store.get_return(tax_return["return_id"])
+
+    schedule = calculate_schedule_a(tax_return)
+    assert schedule["lines"]["2"] == 60000.0
+    assert schedule["lines"]["4"] == 1500.0
+    assert b"Pat Doe" in render_schedule_a(schedule, "Pat Doe")
+
+    archive = zipfile.ZipFile(io.BytesIO(build_return_package(tax_return)))
+    assert json.loads(archive.read("return.json"))["return_id"] == tax_return["return_id"]
+    assert archive.read("forms/schedule_a.pdf").startswith(b"%PDF-")
+
+    empty = store.create_return()
+    assert zipfile.ZipFile(io.BytesIO(build_return_package(empty))).namelist() == ["return.json"]
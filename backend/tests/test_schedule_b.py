@@ -0,0 +1,97 @@
+"""Tests for the Schedule B engine and form."""
+import io
+import zipfile
+
+from app.forms.package import build_return_package
+from app.forms.schedule_b import render_schedule_b
+from app.tax_engine.return_calculation import calculate_return
+from app.tax_engine.schedule_b import calculate_schedule_b
+
+
+def make_return(*sources, **overrides):
+    tax_return = {
+        "return_id": "ret_0000000000000000",
+        "tax_year": 2024,
+        "filing_status": "single",
+        "taxpayer": {"name": "Pat Doe"},
+        "dependents": [],
+        "income_sources": [
+            {"id": f"inc_{index}", "type": t, "description": payer, "amount": amount, "withholding": 0, **fields}
+            for index, (t, payer, amount, fields) in enumerate(sources)
+        ],
+        "deductions": [],
+    }
+    tax_return.update(overrides)
+    return tax_return
+
+
+def test_payer_lines_and_threshold():
+    tax_return = make_return(
+        ("wages", "Acme Corp", 60000, {}),
+        ("interest", "First Bank", 900, {}),
+        ("interest", "Credit Union", 700, {}),
+        ("dividends", "Index Fund", 1200, {"qualified_dividends": 1000}),
+    )
+    schedule = calculate_schedule_b(tax_return)
+    assert [line["payer"] for line in schedule["interest"]] == ["First Bank", "Credit Union"]
+    assert schedule["lines"] == {"2": 1600.0, "4": 1600.0, "6": 1200.0}
+    assert schedule["required"] is True
+    assert len(schedule["reasons"]) == 1 and "interest" in schedule["reasons"][0]
+
+
+def test_not_required_at_or_under_1500():
+    schedule = calculate_schedule_b(make_return(("interest", "First Bank", 1500, {}), ("dividends", "Fund", 300, {})))
+    assert schedule["required"] is False
+    assert any("isn't required" in note for note in schedule["notes"])
+
+
+def test_tax_exempt_interest_stays_off_the_schedule():
+    tax_return = make_return(
+        ("interest", "First Bank", 1000, {}),
+        ("interest", "City Water Bonds", 2500, {"tax_exempt": True}),
+    )
+    schedule = calculate_schedule_b(tax_return)
+    assert [line["payer"] for line in schedule["interest"]] == ["First Bank"]
+    assert schedule["tax_exempt_interest"] == 2500.0
+    assert schedule["required"] is False
+
+    calculation = calculate_return(tax_return)
+    assert calculation["income"]["interest"] == 1000.0
+    assert calculation["tax_exempt_interest"] == 2500.0
+
+
+def test_box_8_and_box_12_tax_exempt_amounts():
+    tax_return = make_return(
+        ("interest", "First Bank", 1000, {"tax_exempt_interest": 300}),
+        ("dividends", "Muni Fund", 200, {"exempt_interest_dividends": 800}),
+    )
+    schedule = calculate_schedule_b(tax_return)
+    assert schedule["lines"]["4"] == 1000.0 and schedule["lines"]["6"] == 200.0
+    assert schedule["tax_exempt_interest"] == 1100.0
+    assert calculate_return(tax_return)["income"]["interest"] == 1000.0
+
+
+def test_foreign_account_requires_part_iii():
+    flagged = make_return(("interest", "Maple Bank", 50, {"foreign_account": True}))
+    schedule = calculate_schedule_b(flagged)
+    assert schedule["required"] is True
+    assert schedule["part_iii"]["foreign_account"] is True
+    assert any("no foreign accounts are recorded" in note for note in schedule["notes"])
+
+    recorded = make_return(
+        ("interest", "Maple Bank", 50, {}),
+        foreign_accounts=[{"id": "fa_1", "institution": "Maple Bank", "country": "Canada", "max_balance": 14200}],
+    )
+    part_iii = calculate_schedule_b(recorded)["part_iii"]
+    assert part_iii == {"foreign_account": True, "countries": ["Canada"], "fbar_required": True, "foreign_trust": False}
+
+
+def test_form_and_package():
+    tax_return = make_return(("interest", "First Bank", 2000, {}))
+    assert render_schedule_b(calculate_schedule_b(tax_return), "Pat Doe").startswith(b"%PDF")
+    names = zipfile.ZipFile(io.BytesIO(build_return_package(tax_return))).namelist()
+    assert "forms/schedule_b.pdf" in names
+
+    small = make_return(("interest", "First Bank", 200, {}))
+    names = zipfile.ZipFile(io.BytesIO(build_return_package(small))).namelist()
+    assert "forms/schedule_b.pdf" not in names
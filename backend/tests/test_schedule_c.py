@@ -0,0 +1,115 @@
+"""Tests for the Schedule C and SE engines and forms."""
+import io
+import zipfile
+from decimal import Decimal
+
+import pytest
+
+from app.forms.package import build_return_package, return_agi
+from app.tax_engine.return_calculation import calculate_business_schedules
+from app.tax_engine.schedule_c import ScheduleCCalculator
+from app.tax_engine.schedule_se import ScheduleSECalculator
+from app.utils.return_store import ReturnStore
+
+
+@pytest.fixture
+def store(tmp_path):
+    return ReturnStore(storage_dir=str(tmp_path / "returns"))
+
+
+def business(expenses=(), **fields):
+    return {"id": "biz_1", "name": "Design Studio", "expenses": list(expenses), **fields}
+
+
+def test_expenses_map_to_lines():
+    result = ScheduleCCalculator().calculate(
+        business(
+            [
+                {"category": "advertising", "amount": 1200},
+                {"category": "supplies", "amount": 300},
+                {"category": "supplies", "amount": 200},
+                {"category": "meals", "amount": 800},
+            ],
+            gross_receipts=50000,
+            cost_of_goods_sold=5000,
+            home_office=1500,
+        ),
+        [],
+    )
+    lines = result["lines"]
+    assert lines["8"] == 1200.0
+    assert lines["22"] == 500.0
+    assert lines["24b"] == 400.0  # 50% of meals
+    assert lines["7"] == 45000.0
+    assert lines["28"] == 2100.0
+    assert lines["31"] == 41400.0
+    assert result["net_profit"] == 41400.0
+
+
+def test_linked_1099_income_counts_as_receipts():
+    sources = [
+        {"type": "self_employment", "amount": 20000, "business_id": "biz_1"},
+        {"type": "self_employment", "amount": 7000, "business_id": "biz_2"},
+        {"type": "wages", "amount": 60000},
+    ]
+    result = ScheduleCCalculator().calculate(business(gross_receipts=1000), sources)
+    assert result["lines"]["1"] == 21000.0
+
+
+def test_invalid_expense_category():
+    with pytest.raises(ValueError, match="expense category"):
+        ScheduleCCalculator().calculate(business([{"category": "vacation", "amount": 1}]), [])
+
+
+def test_self_employment_tax():
+    result = ScheduleSECalculator().calculate(Decimal("50000"))
+    assert result["lines"]["4a"] == 46175.0
+    assert result["lines"]["10"] == 5725.7
+    assert result["lines"]["11"] == 1339.08
+    assert result["self_employment_tax"] == 7064.78
+    assert result["deductible_half"] == 3532.39
+
+
+def test_self_employment_tax_wage_base_and_minimum():
+    # W-2 wages already past the wage base leave only the Medicare part
+    capped = ScheduleSECalculator().calculate(Decimal("50000"), Decimal("170000"))
+    assert capped["lines"]["10"] == 0.0
+    assert capped["self_employment_tax"] == 1339.08
+
+    small = ScheduleSECalculator().calculate(Decimal("400"))
+    assert small["required"] is False
+    assert small["self_employment_tax"] == 0.0
+
+
+def test_store_businesses(store):
+    tax_return = store.create_return()
+    return_id = tax_return["return_id"]
+    created = store.add_business(return_id, "Design Studio", principal_business_code="541430")
+    expense = store.add_business_expense(return_id, created["id"], "supplies", 250, "Paper")
+    assert store.get_return(return_id)["businesses"][0]["expenses"] == [expense]
+
+    with pytest.raises(ValueError):
+        store.add_business(return_id, "Shop", owner="partner")
+    with pytest.raises(ValueError):
+        store.add_business_expense(return_id, created["id"], "vacation", 10)
+    with pytest.raises(KeyError):
+        store.add_business_expense(return_id, "biz_0000000000000000", "supplies", 10)
+
+
+def test_package_includes_business_forms(store):
+    tax_return = store.create_return(filing_status="married_joint", taxpayer={"name": "Pat", "spouse_name": "Sam"})
+    return_id = tax_return["return_id"]
+    studio = store.add_business(return_id, "Design Studio")
+    store.add_business_expense(return_id, studio["id"], "office", 2000)
+    store.add_business(return_id, "Tutoring", owner="spouse", gross_receipts=300)
+    store.add_income_source(return_id, "self_employment", 30000, business_id=studio["id"])
+    tax_return = store.get_return(return_id)
+
+    schedules = calculate_business_schedules(tax_return)
+    assert [s["net_profit"] for s in schedules["businesses"]] == [28000.0, 300.0]
+    # The spouse's $300 is under the $400 threshold
+    assert [s["owner"] for s in schedules["self_employment"]] == ["taxpayer"]
+    assert return_agi(tax_return) == Decimal("28300") - Decimal(str(schedules["self_employment"][0]["deductible_half"]))
+
+    names = zipfile.ZipFile(io.BytesIO(build_return_package(tax_return))).namelist()
+    assert sorted(names) == ["forms/schedule_c_1.pdf", "forms/schedule_c_2.pdf", "forms/schedule_se.pdf", "return.json"]
@@ -0,0 +1,85 @@
+"""Tests for the keyring-backed secret store."""
+import json
+import os
+import stat
+
+from app.utils.secret_store import API_KEY_NAME, SERVICE_NAME, SecretStore, api_key_status, get_api_key
+
+
+class FakeKeyring:
+    """In-memory stand-in for a keyring backend."""
+
+    def __init__(self, fail=False):
+        self.passwords = {}
+        self.fail = fail
+
+    def get_password(self, service, name):
+        return self.passwords.get((service, name))
+
+    def set_password(self, service, name, value):
+        if self.fail:
+            raise RuntimeError("keyring is locked")
+        self.passwords[(service, name)] = value
+
+    def delete_password(self, service, name):
+        del self.passwords[(service, name)]
+
+
+def make_store(tmp_path, keyring=None):
+    return SecretStore(str(tmp_path / "secrets.json"), str(tmp_path / "secrets.key"), keyring=keyring)
+
+
+def test_keyring_storage(tmp_path):
+    keyring = FakeKeyring()
+    store = make_store(tmp_path, keyring)
+    assert store.set(API_KEY_NAME, "sk-ant-123") == "keyring"
+    assert keyring.passwords[(SERVICE_NAME, API_KEY_NAME)] == "sk-ant-123"
+    assert store.lookup(API_KEY_NAME) == ("sk-ant-123", "keyring")
+    assert not (tmp_path / "secrets.json").exists()
+
+    assert store.delete(API_KEY_NAME) is True
+    assert store.get(API_KEY_NAME) is None
+    assert store.delete(API_KEY_NAME) is False
+
+
+def test_file_fallback_is_encrypted(tmp_path):
+    store = make_store(tmp_path)
+    assert store.set(API_KEY_NAME, "sk-ant-123") == "file"
+    path = tmp_path / "secrets.json"
+    assert "sk-ant-123" not in path.read_text()
+    assert stat.S_IMODE(os.stat(path).st_mode) == 0o600
+    assert make_store(tmp_path).lookup(API_KEY_NAME) == ("sk-ant-123", "file")
+
+    # A keyring that refuses writes also falls back to the file
+    assert make_store(tmp_path, FakeKeyring(fail=True)).set(API_KEY_NAME, "sk-ant-456") == "file"
+    assert store.get(API_KEY_NAME) == "sk-ant-456"
+
+
+def test_migrate_to_keyring(tmp_path):
+    make_store(tmp_path).set(API_KEY_NAME, "sk-ant-123")
+    assert make_store(tmp_path).migrate_to_keyring() == []
+
+    keyring = FakeKeyring()
+    store = make_store(tmp_path, keyring)
+    assert store.migrate_to_keyring() == [API_KEY_NAME]
+    assert store.lookup(API_KEY_NAME) == ("sk-ant-123", "keyring")
+    assert not (tmp_path / "secrets.json").exists()
+    assert store.migrate_to_keyring() == []
+
+
+def test_unreadable_file_secret(tmp_path):
+    (tmp_path / "secrets.json").write_text(json.dumps({API_KEY_NAME: "not-a-token"}))
+    assert make_store(tmp_path).lookup(API_KEY_NAME) == (None, None)
+
+
+def test_environment_key_takes_precedence(tmp_path, monkeypatch):
+    monkeypatch.delenv("ANTHROPIC_API_KEY", raising=False)
+    store = make_store(tmp_path, FakeKeyring())
+    assert get_api_key(store) == ""
+    assert api_key_status(store) == {"configured": False, "source": None, "keyring_available": True}
+
+    store.set(API_KEY_NAME, "sk-ant-stored")
+    assert get_api_key(store) == "sk-ant-stored"
+    monkeypatch.setenv("ANTHROPIC_API_KEY", "sk-ant-env")
+    assert get_api_key(store) == "sk-ant-env"
+    assert api_key_status(store)["source"] == "environment"
@@ -0,0 +1,80 @@
+"""Tests for the state and local income tax refund worksheet."""
+import pytest
+
+from app.tax_engine.return_calculation import calculate_return
+from app.tax_engine.state_refund import prior_year_deductions, state_refund_worksheet
+
+
+def make_return(refund, prior=None):
+    return {
+        "return_id": "ret_0000000000000000",
+        "tax_year": 2024,
+        "filing_status": "single",
+        "dependents": [],
+        "income_sources": [
+            {"type": "wages", "amount": 80000, "withholding": 9000},
+            {"type": "state_refund", "description": "State of Ohio", "amount": refund, "withholding": 0},
+        ],
+        "deductions": [],
+        "prior_year_deductions": prior,
+    }
+
+
+def prior(**figures):
+    return {
+        "return_id": "ret_1111111111111111", "tax_year": 2023, "filing_status": "single", "itemized": True,
+        "itemized_deductions": 15000.0, "standard_deduction": 13850.0, "state_income_tax": 4000.0,
+        "salt_total": 7000.0, "salt_deducted": 7000.0, **figures,
+    }
+
+
+def test_taxable_up_to_the_excess_over_the_standard_deduction():
+    worksheet = state_refund_worksheet(make_return(1500, prior()))
+    assert worksheet["status"] == "taxable"
+    assert worksheet["lines"]["1"] == 1500.0
+    assert worksheet["lines"]["6"] == 1150.0
+    assert worksheet["taxable_amount"] == 1150.0
+    assert calculate_return(make_return(1500, prior()))["income"]["state_refund"] == 1150.0
+
+
+def test_standard_deduction_last_year_means_not_taxable():
+    worksheet = state_refund_worksheet(make_return(900, prior(itemized=False)))
+    assert worksheet["status"] == "not_taxable" and worksheet["taxable_amount"] == 0
+    assert worksheet["lines"] is None
+    # Sales tax was deducted instead of income tax
+    assert state_refund_worksheet(make_return(900, prior(state_income_tax=0)))["taxable_amount"] == 0
+
+
+def test_salt_cap_limits_the_taxable_refund():
+    capped = prior(itemized_deductions=30000, salt_total=12500, salt_deducted=10000, state_income_tax=9000)
+    assert state_refund_worksheet(make_return(2000, capped))["taxable_amount"] == 0
+    worksheet = state_refund_worksheet(make_return(3000, capped))
+    assert worksheet["lines"]["1"] == 500.0
+    assert any("capped" in note for note in worksheet["notes"])
+
+
+def test_without_last_years_figures_the_refund_is_taxable():
+    tax_return = make_return(700)
+    assert state_refund_worksheet(tax_return)["status"] == "needs_prior_year"
+    result = calculate_return(tax_return)
+    assert result["income"]["state_refund"] == 700.0
+    assert any("aren't loaded" in note for note in result["notes"])
+
+
+def test_prior_year_figures_from_a_saved_calculation():
+    prior_return = {
+        "return_id": "ret_1111111111111111", "tax_year": 2024, "filing_status": "single",
+        "income_sources": [{"type": "wages", "amount": 90000}],
+        "deductions": [
+            {"category": "state_local_tax", "amount": 6000},
+            {"category": "state_local_tax", "tax_type": "real_estate", "amount": 5000},
+            {"category": "mortgage_interest", "amount": 9000},
+        ],
+    }
+    with pytest.raises(ValueError, match="hasn't been calculated"):
+        prior_year_deductions(prior_return)
+    prior_return["calculation"] = calculate_return(prior_return)
+    figures = prior_year_deductions(prior_return)
+    assert figures["itemized"] is True
+    assert figures["itemized_deductions"] == 19000.0 and figures["standard_deduction"] == 14600.0
+    assert (figures["state_income_tax"], figures["salt_total"], figures["salt_deducted"]) == (6000.0, 11000.0, 10000.0)
@@ -0,0 +1,96 @@
+"""Tests for state returns and the state tax engine."""
+from decimal import Decimal
+
+import pytest
+
+from app.tax_engine.state_tax import StateTaxCalculator, tax_exempt_interest_add_back
+from app.utils.return_store import ReturnStore
+
+
+@pytest.fixture
+def calculator():
+    return StateTaxCalculator(2024)
+
+
+def test_progressive_resident_tax(calculator):
+    california = calculator.resident_tax("CA", Decimal("100000"), "single")
+    assert california["taxable_income"] == Decimal("94460")
+    assert california["tax"] == Decimal("5327.14")
+
+    # 8,500 x 4% + 3,200 x 4.5% + 2,200 x 5.25% + 66,750 x 5.5% + 11,350 x 6%
+    new_york = calculator.resident_tax("NY", Decimal("100000"), "single")
+    assert new_york["tax"] == Decimal("4951.75")
+
+
+def test_flat_and_no_tax_states(calculator):
+    assert calculator.resident_tax("IL", Decimal("100000"), "single")["tax"] == Decimal("4950.00")
+    assert calculator.resident_tax("TX", Decimal("100000"), "single")["tax"] == Decimal("0")
+    with pytest.raises(ValueError, match="Unsupported state"):
+        calculator.resident_tax("ZZ", Decimal("1"), "single")
+
+
+def test_nonresident_proration_and_resident_credit(calculator):
+    state_returns = [
+        {"id": "st_ca", "state": "CA", "residency": "resident"},
+        {"id": "st_ny", "state": "NY", "residency": "nonresident", "allocated_income": 30000},
+    ]
+    sources = [{"type": "wages", "state": "CA", "state_withholding": 4000}]
+    california, new_york = calculator.calculate(state_returns, Decimal("100000"), "single", sources)
+
+    assert new_york["income_ratio"] == 0.3
+    assert new_york["computed_tax"] == 1485.53  # 30% of the NY resident tax
+    assert new_york["balance"] == -1485.53
+
+    assert california["other_state_credit"] == 1485.53
+    assert california["computed_tax"] == 3841.61
+    assert california["withholding"] == 4000.0
+    assert california["balance"] == 158.39
+
+
+def test_allocated_income_from_state_wages(calculator):
+    state_returns = [{"id": "st_ny", "state": "NY", "residency": "part_year"}]
+    sources = [{"type": "wages", "amount": 40000, "state": "NY", "state_wages": 25000, "state_withholding": 900}]
+    (result,) = calculator.calculate(state_returns, Decimal("100000"), "single", sources)
+    assert result["allocated_income"] == 25000.0
+    assert result["withholding"] == 900.0
+
+
+def test_tax_exempt_interest_add_back(calculator):
+    state_returns = [{"id": "st_ny", "state": "NY", "residency": "resident"}]
+    sources = [
+        {"type": "interest", "amount": 0, "tax_exempt_interest": 2000, "bond_state": "NY"},
+        {"type": "dividends", "amount": 500, "exempt_interest_dividends": 1000, "bond_state": "CA"},
+        {"type": "interest", "amount": 3000, "tax_exempt": True},
+    ]
+    (result,) = calculator.calculate(state_returns, Decimal("100000"), "single", sources)
+    # New York bonds stay exempt; the California bonds and the unnamed issuer are added back
+    assert result["add_back"] == 4000.0
+    assert result["allocated_income"] == 104000.0
+    assert result["tax_before_credits"] == float(calculator.resident_tax("NY", Decimal("104000"), "single")["tax"])
+
+    assert tax_exempt_interest_add_back(sources, "IL") == Decimal("6000")
+    assert tax_exempt_interest_add_back(sources, "TX") == Decimal("0")
+
+
+def test_store_state_returns(tmp_path):
+    store = ReturnStore(storage_dir=str(tmp_path / "returns"))
+    return_id = store.create_return()["return_id"]
+    california = store.add_state_return(return_id, "ca")
+    new_york = store.add_state_return(return_id, "NY", "nonresident", allocated_income=30000)
+    assert california["state"] == "CA"
+
+    with pytest.raises(ValueError, match="already has a CA"):
+        store.add_state_return(return_id, "CA", "nonresident")
+    with pytest.raises(ValueError, match="resident state"):
+        store.add_state_return(return_id, "IL")
+    with pytest.raises(ValueError, match="residency"):
+        store.add_state_return(return_id, "IL", "visitor")
+
+    assert store.update_state_return(return_id, new_york["id"], allocated_income=35000)["allocated_income"] == 35000
+    store.save_state_calculations(return_id, [{"id": california["id"], "computed_tax": 100.0, "balance": -100.0}])
+    assert store.get_return(return_id)["state_returns"][0]["computed_tax"] == 100.0
+
+    store.delete_state_return(return_id, new_york["id"])
+    assert [r["state"] for r in store.get_return(return_id)["state_returns"]] == ["CA"]
+    with pytest.raises(KeyError):
+        store.delete_state_return(return_id, new_york["id"])
@@ -0,0 +1,129 @@
+"""Tests for the tax calendar and its reminders."""
+from datetime import date, timedelta
+
+import pytest
+
+from app.tax_engine.tax_calendar import due_reminders, return_events
+from app.utils.reminder_store import ReminderStore
+
+
+def make_return(**fields):
+    return {"return_id": "ret_0000000000000001", "tax_year": 2024, "status": "in_progress", **fields}
+
+
+def by_id(events):
+    return {e["event_id"].split(".", 1)[1]: e for e in events}
+
+
+def test_deadline_and_extension_events():
+    events = by_id(return_events(make_return()))
+    assert events["filing.federal"]["date"] == "2025-04-15"
+    assert events["payment.federal"]["date"] == "2025-04-15"
+    assert events["extension.federal"]["title"] == "Last day to request a 2024 extension (Form 4868)"
+    assert not any(key.startswith("estimated_payment") for key in events)
+
+    extended = by_id(return_events(make_return(extension={"filed_on": "2025-04-01", "states": []})))
+    assert extended["extension.federal"]["met"] is True
+    assert extended["filing.federal"]["date"] == "2025-10-15"
+
+
+def test_estimates_scheduled_for_business_income_or_balance_due():
+    events = by_id(return_events(make_return(income_sources=[{"type": "self_employment", "amount": 40000}])))
+    assert [events[f"estimated_payment.{q}-2025"]["date"] for q in ("Q1", "Q2", "Q3", "Q4")] == [
+        "2025-04-15", "2025-06-16", "2025-09-15", "2026-01-15",
+    ]
+    assert "estimated_payment.Q1-2025" in by_id(return_events(make_return(refund_or_owed=-1500)))
+    assert "estimated_payment.Q1-2025" not in by_id(return_events(make_return(refund_or_owed=-200)))
+
+
+def test_document_arrival_events():
+    tax_return = make_return(
+        income_sources=[
+            {"type": "wages", "description": "Acme Corp"},
+            {"type": "wages", "description": "Beta LLC"},
+            {"type": "capital_gains", "description": "Schwab"},
+        ],
+        deductions=[{"category": "mortgage_interest", "description": "Wells Fargo"}],
+    )
+    events = by_id(return_events(tax_return, documents=[{"document_type": "1098"}]))
+    assert events["document.W-2"]["title"] == "W-2 from Acme Corp, Beta LLC should have arrived"
+    assert events["document.W-2"]["date"] == "2025-01-31"
+    assert events["document.1099-B"]["date"] == "2025-02-15"
+    assert events["document.1098"]["met"] is True
+    assert events["document.W-2"]["met"] is False
+
+
+def test_ip_pin_expiry_events():
+    tax_return = make_return(
+        taxpayer={"name": "Pat Doe", "spouse_name": "Sam Doe"},
+        ip_pins={"spouse": {"pin": "encrypted", "valid_year": 2025}},
+    )
+    event = by_id(return_events(tax_return))["ip_pin.spouse-2025"]
+    assert event["title"] == "Sam Doe's 2025 IP PIN expires"
+    assert event["date"] == "2025-12-31"
+
+    assert due_reminders([event], as_of=date(2025, 11, 30)) == []
+    reminder = due_reminders([event], as_of=date(2025, 12, 1))[0]
+    assert reminder["message"].startswith("Sam Doe's 2025 IP PIN expires on 2025-12-31")
+
+
+def test_due_reminders_lead_times():
+    events = return_events(make_return(income_sources=[{"type": "wages", "description": "Acme Corp"}]))
+    # Documents are chased once late; filing reminders start two weeks out
+    assert [r["kind"] for r in due_reminders(events, as_of=date(2025, 1, 30))] == []
+    assert [r["kind"] for r in due_reminders(events, as_of=date(2025, 2, 1))] == ["document"]
+    reminders = {r["kind"]: r for r in due_reminders(events, as_of=date(2025, 4, 5))}
+    assert set(reminders) == {"document", "filing", "payment"}
+    assert reminders["filing"]["days_remaining"] == 10
+    assert reminders["filing"]["message"].endswith("(in 10 days)")
+    assert "extension" in {r["kind"] for r in due_reminders(events, as_of=date(2025, 4, 8))}
+
+
+def test_estimated_payment_reminders_escalate_until_paid():
+    tax_return = make_return(income_sources=[{"type": "self_employment", "amount": 40000}])
+    events = [e for e in return_events(tax_return) if e["event_id"].endswith("Q2-2025")]
+    q2 = events[0]["event_id"]
+    # Q2 is due Monday 2025-06-16
+    assert due_reminders(events, as_of=date(2025, 6, 1)) == []
+    stages = [due_reminders(events, as_of=date(2025, 6, day))[0]["stage"] for day in (2, 12, 13, 16, 17)]
+    assert stages == ["two_weeks", "two_weeks", "three_days", "due_today", "overdue"]
+
+    # Dismissing the two-week notice only silences it until the three-day one
+    states = {q2: {"snoozed_until": None, "dismissed_at": "2025-06-03T09:00:00"}}
+    assert due_reminders(events, states, as_of=date(2025, 6, 10)) == []
+    assert due_reminders(events, states, as_of=date(2025, 6, 13))[0]["stage"] == "three_days"
+    states = {q2: {"snoozed_until": None, "dismissed_at": "2025-06-16T09:00:00"}}
+    assert due_reminders(events, states, as_of=date(2025, 6, 16)) == []
+    assert due_reminders(events, states, as_of=date(2025, 6, 17))[0]["overdue"] is True
+
+    tax_return["estimated_payments"] = [{"id": "est_1", "tax_year": 2025, "quarter": "Q2", "amount": 2400}]
+    paid = by_id(return_events(tax_return))
+    assert paid["estimated_payment.Q2-2025"]["met"] is True
+    assert paid["estimated_payment.Q3-2025"]["met"] is False
+    # This year's payments, carried over from last year's return, don't meet next year's quarters
+    tax_return["estimated_payments"].append({"id": "est_0", "tax_year": 2024, "quarter": "Q3", "amount": 1000})
+    assert by_id(return_events(tax_return))["estimated_payment.Q3-2025"]["met"] is False
+    assert due_reminders([paid["estimated_payment.Q2-2025"]], as_of=date(2025, 6, 16)) == []
+    # Logging payments schedules the quarters even without business income
+    assert "estimated_payment.Q1-2025" in by_id(return_events(make_return(estimated_payments=[{"quarter": "Q1"}])))
+
+
+def test_snoozed_and_dismissed_reminders(tmp_path):
+    store = ReminderStore(state_path=str(tmp_path / "reminders.json"))
+    events = return_events(make_return())
+    filing = "ret_0000000000000001.filing.federal"
+    payment = "ret_0000000000000001.payment.federal"
+
+    with pytest.raises(ValueError):
+        store.snooze(filing, date.today())
+    store.snooze(filing, date.today() + timedelta(days=3))
+    store.dismiss(payment)
+    as_of = date(2025, 4, 10)
+    states = store.get_states()
+    states[filing]["snoozed_until"] = "2025-04-12"
+
+    assert {r["event_id"] for r in due_reminders(events, states, as_of=as_of)} == {"ret_0000000000000001.extension.federal"}
+    assert filing in {r["event_id"] for r in due_reminders(events, states, as_of=date(2025, 4, 12))}
+
+    store.restore(payment)
+    assert payment not in store.get_states()
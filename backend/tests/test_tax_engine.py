@@ -95,6 +95,53 @@ def test_quarterly_payments(calc):
     assert len(set(amounts)) == 1
 
 
+def test_quarterly_payments_include_self_employment_tax(calc):
+    """SE tax is added and half of it deducted before income tax."""
+    wages_only = calc.estimate_quarterly_payments(Decimal("60000"), "single")
+    result = calc.estimate_quarterly_payments(Decimal("60000"), "single", self_employment_income=Decimal("60000"))
+    assert result["self_employment_tax"] == 8477.73
+    assert result["self_employment_deduction"] == 4238.87
+    assert result["income_tax"] < wages_only["income_tax"]
+    assert result["estimated_annual_tax"] == round(result["income_tax"] + 8477.73, 2)
+    q1 = result["payment_schedule"][0]
+    assert q1["self_employment_tax"] == 2119.43
+    assert q1["amount"] == result["quarterly_payment"]
+
+    with pytest.raises(ValueError, match="exceed"):
+        calc.estimate_quarterly_payments(Decimal("10000"), "single", self_employment_income=Decimal("20000"))
+
+
+# ── Qualified dividends ────────────────────────────────────────
+
+def test_qualified_dividends_taxed_at_15_percent(calc):
+    """$60k taxable with $10k qualified: the dividends move from 22% to 15%."""
+    result = calc.calculate_individual_tax(Decimal("74600"), "single", qualified_dividends=Decimal("10000"))
+    worksheet = result["qualified_dividends_worksheet"]
+    assert worksheet["at_15_percent"] == 10000.0
+    assert worksheet["at_0_percent"] == 0.0
+    # Regular tax of $8,253 less 7% of $10,000
+    assert result["tax_liability"] == 7553.0
+
+
+def test_qualified_dividends_in_the_zero_bracket(calc):
+    result = calc.calculate_individual_tax(Decimal("54600"), "single", qualified_dividends=Decimal("5000"))
+    assert result["qualified_dividends_worksheet"]["at_0_percent"] == 5000.0
+    # Only the $35,000 of ordinary income is taxed
+    assert result["tax_liability"] == 3968.0
+
+
+def test_qualified_dividends_split_across_15_and_20_percent(calc):
+    result = calc.calculate_individual_tax(Decimal("614600"), "single", qualified_dividends=Decimal("100000"))
+    worksheet = result["qualified_dividends_worksheet"]
+    assert worksheet["at_15_percent"] == 18900.0
+    assert worksheet["at_20_percent"] == 81100.0
+
+
+def test_no_worksheet_without_qualified_dividends(calc):
+    result = calc.calculate_individual_tax(Decimal("50000"), "single")
+    assert result["qualified_dividends_worksheet"] is None
+
+
 # ── Input validation ───────────────────────────────────────────
 
 def test_negative_income_rejected(calc):
@@ -135,3 +182,8 @@ def test_disclaimer_in_individual(calc):
 def test_disclaimer_in_corporate(calc):
     result = calc.calculate_corporate_tax(Decimal("100000"))
     assert "disclaimer" in result
+
+
+def test_negative_qualified_dividends_rejected(calc):
+    with pytest.raises(ValueError, match="negative"):
+        calc.calculate_individual_tax(Decimal("50000"), "single", qualified_dividends=Decimal("-1"))
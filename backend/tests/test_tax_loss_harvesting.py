@@ -0,0 +1,60 @@
+"""Tests for tax-loss harvesting suggestions."""
+from datetime import date
+
+import pytest
+
+from app.tax_engine.tax_loss_harvesting import harvesting_suggestions
+
+
+def make_return(realized_gain=5000):
+    return {
+        "tax_year": 2024,
+        "filing_status": "single",
+        "income_sources": [{"type": "wages", "amount": 60000, "withholding": 0}],
+        "capital_transactions": [{"description": "AAPL", "gain_or_loss": realized_gain}],
+    }
+
+
+def holding(id, symbol, basis, value, acquired):
+    return {"id": id, "symbol": symbol, "quantity": 10, "cost_basis": basis, "market_value": value,
+            "date_acquired": acquired}
+
+
+HOLDINGS = [
+    holding("hold_1", "VTI", 10800, 9400, "2023-03-14"),
+    holding("hold_2", "VXUS", 4000, 3500, "2024-06-01"),
+    holding("hold_3", "VXUS", 5000, 3000, "2024-11-20"),
+    holding("hold_4", "BND", 2000, 2600, "2022-01-10"),
+]
+
+
+def test_candidates_and_savings():
+    result = harvesting_suggestions(make_return(), HOLDINGS, as_of=date(2024, 12, 2))
+    assert [c["holding_id"] for c in result["candidates"]] == ["hold_3", "hold_1", "hold_2"]
+    vti = result["candidates"][1]
+    assert (vti["unrealized_loss"], vti["term"], vti["wash_sale_risk"]) == (1400.0, "long", False)
+    assert vti["tax_savings"] == 308.0
+
+    # The June lot has shares bought on November 20 beside it
+    june = result["candidates"][2]
+    assert june["wash_sale_risk"] is True and june["wash_sale_clear_date"] == "2024-12-21"
+
+    assert result["realized_gain"] == 5000.0
+    assert result["total_harvestable_loss"] == 3900.0
+    # $3,400 harvested against the $5,000 gain: $3,250 at 22% and $150 at 12%
+    assert result["projected_tax_savings"] == 733.0
+    assert result["capital_loss_carryforward"] == 0.0
+    assert any("2025-01-02" in note for note in result["notes"])
+
+
+def test_losses_beyond_limit_carry_forward():
+    result = harvesting_suggestions(make_return(realized_gain=0), HOLDINGS[:1] + [
+        holding("hold_5", "ARKK", 20000, 12000, "2021-02-01"),
+    ], as_of=date(2024, 12, 2))
+    assert result["capital_loss_carryforward"] == 6400.0
+    assert any("carry forward" in note for note in result["notes"])
+
+
+def test_as_of_must_be_in_the_return_year():
+    with pytest.raises(ValueError, match="2024"):
+        harvesting_suggestions(make_return(), HOLDINGS, as_of=date(2025, 1, 3))
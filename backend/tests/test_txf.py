@@ -0,0 +1,95 @@
+"""Tests for the TXF importer."""
+import pytest
+
+from app.documents.txf import import_txf, parse_txf
+from app.utils.return_store import ReturnStore
+
+SAMPLE = """V042
+ATurboTax Deluxe
+D02/01/2025
+^
+TD
+N460
+C1
+L1
+$85,000.00
+PAcme Corp
+^
+TD
+N461
+C1
+L1
+$12000.00
+PAcme Corp
+^
+TD
+N287
+C1
+L1
+$120.50
+PFirst Bank
+^
+TD
+N283
+C1
+L1
+$-9500.00
+PHome Lender
+^
+TD
+N323
+C1
+L5
+PAPPLE INC 10 SH
+D01/15/2020
+D03/01/2024
+$1500.00
+$1800.00
+^
+TD
+N999
+C1
+L1
+$5.00
+^
+"""
+
+
+@pytest.fixture
+def store(tmp_path):
+    return ReturnStore(storage_dir=str(tmp_path / "returns"))
+
+
+def test_parse_txf():
+    parsed = parse_txf(SAMPLE)
+    assert parsed["header"] == {"version": "042", "program": "TurboTax Deluxe", "exported": "2025-02-01"}
+    assert [r["refnum"] for r in parsed["records"]] == ["460", "461", "287", "283", "323", "999"]
+    assert parsed["records"][4]["dates"] == ["2020-01-15", "2024-03-01"]
+
+    with pytest.raises(ValueError, match="Not a TXF"):
+        parse_txf("Date,Amount\n")
+
+
+def test_import_txf(store):
+    result = import_txf(store, SAMPLE, tax_year=2024, taxpayer={"name": "Pat Doe"})
+    tax_return = result["return"]
+    assert result["imported"] == {"income_sources": 2, "deductions": 1, "capital_transactions": 1}
+    assert result["warnings"] == ["Skipped 1 record(s) with unsupported reference number N999"]
+
+    wages, interest = tax_return["income_sources"]
+    assert (wages["type"], wages["amount"], wages["withholding"]) == ("wages", 85000.0, 12000.0)
+    assert wages["description"] == "Acme Corp"
+    assert wages["txf_program"] == "TurboTax Deluxe"
+    assert interest["amount"] == 120.5
+    assert tax_return["deductions"][0]["category"] == "mortgage_interest"
+    assert tax_return["deductions"][0]["amount"] == 9500.0
+
+    (lot,) = tax_return["capital_transactions"]
+    assert (lot["term"], lot["proceeds"], lot["cost_basis"], lot["gain_or_loss"]) == ("long", 1800.0, 1500.0, 300.0)
+    assert tax_return["taxpayer"] == {"name": "Pat Doe"}
+
+
+def test_import_without_usable_records(store):
+    with pytest.raises(ValueError, match="no income"):
+        import_txf(store, "V042\nATest\n^\nTD\nN999\n$1.00\n^\n")
+    assert store.list_returns() == []
@@ -0,0 +1,113 @@
+"""Tests for deterministic return validation."""
+import pytest
+
+from app.tax_engine.validation import summarize_return, validate_return
+
+
+def make_return(**overrides):
+    tax_return = {
+        "return_id": "ret_0000000000000000",
+        "tax_year": 2024,
+        "filing_status": "single",
+        "dependents": [],
+        "income_sources": [
+            {"type": "wages", "description": "Acme", "amount": 85000, "withholding": 12000},
+        ],
+        "deductions": [],
+    }
+    tax_return.update(overrides)
+    return tax_return
+
+
+def codes(findings):
+    return {f["code"] for f in findings}
+
+
+def test_clean_return_has_no_errors():
+    findings = validate_return(make_return())
+    assert not [f for f in findings if f["severity"] == "error"]
+
+
+def test_summary_totals():
+    summary = summarize_return(make_return(deductions=[
+        {"category": "charitable", "amount": 1000},
+        {"category": "charitable", "amount": 500},
+    ]))
+    assert summary["total_income"] == 85000
+    assert summary["total_withholding"] == 12000
+    assert summary["deductions_by_category"] == {"charitable": 1500}
+
+
+def test_no_income_flagged():
+    assert "no_income" in codes(validate_return(make_return(income_sources=[])))
+
+
+def test_withholding_exceeds_income():
+    findings = validate_return(make_return(income_sources=[
+        {"type": "wages", "amount": 1000, "withholding": 5000},
+    ]))
+    assert "withholding_exceeds_income" in codes(findings)
+
+
+def test_duplicate_income_flagged():
+    source = {"type": "wages", "description": "Acme", "amount": 85000, "withholding": 0}
+    findings = validate_return(make_return(income_sources=[source, dict(source)]))
+    assert "possible_duplicate_income" in codes(findings)
+
+
+def test_hoh_requires_dependent():
+    findings = validate_return(make_return(filing_status="head_of_household"))
+    assert "hoh_without_dependent" in codes(findings)
+
+
+@pytest.mark.parametrize("decree_date, flagged", [("2024-12-31", True), ("2025-01-15", False)])
+def test_married_after_divorce(decree_date, flagged):
+    findings = validate_return(make_return(filing_status="married_joint", divorce={"decree_date": decree_date}))
+    assert ("married_after_divorce" in codes(findings)) is flagged
+
+
+def test_dependent_residency_warning():
+    findings = validate_return(make_return(dependents=[
+        {"name": "Amy", "relationship": "daughter", "months_lived_with": 3},
+    ]))
+    assert "dependent_residency" in codes(findings)
+
+
+def test_itemized_below_standard_is_info():
+    findings = validate_return(make_return(deductions=[{"category": "charitable", "amount": 2000}]))
+    assert "standard_deduction_larger" in codes(findings)
+
+
+def test_unsupported_year():
+    assert "unsupported_tax_year" in codes(validate_return(make_return(tax_year=2022)))
+
+
+def test_messages_are_localized():
+    tax_return = make_return(income_sources=[
+        {"type": "wages", "description": "Acme", "amount": 1000, "withholding": 2000},
+    ])
+    english = {f["code"]: f["message"] for f in validate_return(tax_return)}
+    spanish = {f["code"]: f["message"] for f in validate_return(tax_return, locale="es")}
+    assert english["withholding_exceeds_income"] == (
+        "Withholding ($2,000.00) exceeds the amount reported for Acme ($1,000.00)"
+    )
+    assert spanish["withholding_exceeds_income"] == "La retención ($2,000.00) supera el monto declarado para Acme ($1,000.00)"
+
+
+def test_alimony_in_the_wrong_place():
+    findings = validate_return(make_return(
+        income_sources=[
+            {"type": "wages", "amount": 85000, "withholding": 12000},
+            {"type": "other", "description": "Spousal support from Sam", "amount": 6000, "withholding": 0},
+        ],
+        deductions=[{"category": "other", "description": "Alimony to Sam", "amount": 9000}],
+        alimony=[
+            {"direction": "paid", "amount": 4000, "instrument_date": "2021-03-01"},
+            {"direction": "received", "amount": 3000, "instrument_date": "2017-03-01", "description": "Child support"},
+            {"direction": "received", "amount": 2000, "instrument_date": "2017-03-01"},
+        ],
+    ))
+    assert {"alimony_as_other_income", "alimony_as_deduction", "child_support_as_alimony"} <= codes(findings)
+    ignored = [f for f in findings if f["code"] == "alimony_paid_not_deductible"]
+    assert len(ignored) == 1 and ignored[0]["severity"] == "info" and "2021-03-01" in ignored[0]["message"]
+    assert "alimony_received_not_taxable" not in codes(findings)
@@ -0,0 +1,108 @@
+"""Tests for the vehicle expense method comparison."""
+import pytest
+
+from app.tax_engine.return_calculation import calculate_return
+from app.tax_engine.schedule_c import ScheduleCCalculator
+from app.tax_engine.vehicle_expenses import allowed_methods, compare_vehicle_methods, mileage_deductions
+
+
+def make_vehicle(**overrides):
+    vehicle = {
+        "id": "veh_1",
+        "description": "2024 Hatchback",
+        "placed_in_service": "2024-03-01",
+        "cost": 30000,
+        "mileage_log": [{"miles": 12000, "business": True}, {"miles": 3000, "business": False}],
+        "expenses": [
+            {"kind": "fuel", "amount": 2400},
+            {"kind": "insurance", "amount": 1200},
+            {"kind": "repairs", "amount": 400},
+            {"kind": "parking_tolls", "amount": 150},
+        ],
+        "method_history": {},
+    }
+    vehicle.update(overrides)
+    return vehicle
+
+
+def test_compare_first_year():
+    result = compare_vehicle_methods(make_vehicle(), 2024)
+    assert result["business_use_percent"] == 80.0
+    assert result["standard_mileage"]["total"] == 8190.0
+    assert result["actual"]["business_share"] == 3200.0
+    assert result["actual"]["depreciation"] == 4800.0
+    assert result["actual"]["depreciation_method"] == "macrs"
+    assert result["actual"]["total"] == 8150.0
+    assert result["recommended_method"] == "standard_mileage"
+
+
+def test_switching_rules():
+    actual_first = make_vehicle(placed_in_service="2023-05-01", method_history={"2023": "actual"})
+    assert allowed_methods(actual_first, 2024)["methods"] == ["actual"]
+    result = compare_vehicle_methods(actual_first, 2024)
+    assert result["standard_mileage"]["allowed"] is False
+    assert result["actual"]["depreciation"] == 7680.0
+    assert result["recommended_method"] == "actual"
+
+    standard_first = make_vehicle(placed_in_service="2023-05-01", method_history={"2023": "standard_mileage"})
+    assert allowed_methods(standard_first, 2024)["methods"] == ["standard_mileage", "actual"]
+    result = compare_vehicle_methods(standard_first, 2024)
+    assert result["actual"]["depreciation_method"] == "straight_line"
+    assert result["actual"]["depreciation"] == 4800.0
+
+    leased = make_vehicle(placed_in_service="2023-05-01", leased=True, method_history={"2023": "standard_mileage"})
+    assert allowed_methods(leased, 2024)["methods"] == ["standard_mileage"]
+
+
+def test_low_business_use_and_caps():
+    result = compare_vehicle_methods(make_vehicle(cost=90000, total_miles=30000), 2024)
+    assert result["business_use_percent"] == 40.0
+    assert result["actual"]["depreciation_method"] == "straight_line"
+    assert result["actual"]["depreciation"] == 3600.0
+
+    result = compare_vehicle_methods(make_vehicle(cost=90000), 2024)
+    assert result["actual"]["depreciation"] == 9920.0
+    assert compare_vehicle_methods(make_vehicle(cost=90000, heavy_vehicle=True), 2024)["actual"]["depreciation"] == 14400.0
+
+    with pytest.raises(ValueError):
+        compare_vehicle_methods(make_vehicle(), 2023)
+
+
+def test_chosen_method_feeds_schedule_c():
+    business = {"id": "biz_1", "name": "Studio", "gross_receipts": 50000, "expenses": [], "vehicles": [make_vehicle()]}
+    calculator = ScheduleCCalculator(2024)
+    assert calculator.calculate(business, [])["lines"]["9"] == 0
+
+    business["vehicles"][0]["method"] = "actual"
+    lines = calculator.calculate(business, [])["lines"]
+    assert lines["9"] == 3350.0
+    assert lines["13"] == 4800.0
+
+
+def test_medical_and_charitable_mileage():
+    vehicle = make_vehicle(mileage_log=[
+        {"miles": 12000, "business": True},
+        {"miles": 300, "business": False, "category": "charitable"},
+    ])
+    tax_return = {
+        "tax_year": 2024,
+        "filing_status": "single",
+        "income_sources": [{"type": "wages", "amount": 60000}],
+        "deductions": [{"id": "ded_1", "category": "charitable", "amount": 15000}],
+        "businesses": [{"id": "biz_1", "name": "Studio", "vehicles": [vehicle]}],
+        "mileage_log": [
+            {"id": "trip_1", "category": "medical", "miles": 400, "parking_tolls": 16},
+            {"id": "trip_2", "category": "charitable", "miles": 200},
+        ],
+    }
+    medical, charitable = mileage_deductions(tax_return)
+    assert (medical["category"], medical["rate"], medical["amount"]) == ("medical", 0.21, 100.0)
+    assert (charitable["miles"], charitable["rate"], charitable["amount"]) == (500.0, 0.14, 70.0)
+    # Charitable trips are personal miles for the vehicle's business-use share
+    assert compare_vehicle_methods(vehicle, 2024)["business_use_percent"] == 97.56
+
+    # Medical mileage is under the 7.5% AGI floor; the charitable miles add to the gifts
+    assert calculate_return(tax_return)["deduction_amount"] == 15070.0
+
+    with pytest.raises(ValueError, match="No medical mileage rate for 2023"):
+        mileage_deductions({**tax_return, "tax_year": 2023})
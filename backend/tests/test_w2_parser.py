@@ -0,0 +1,109 @@
+"""Tests for the deterministic W-2 parser."""
+from decimal import Decimal
+
+import pytest
+
+from app.documents.parsers import parse_document
+from app.documents.parsers.w2 import parse_w2
+
+# Typical OCR output: labels and values on the same line, some boxes wrapped
+SAMPLE_W2 = """
+Form W-2 Wage and Tax Statement 2024
+b Employer identification number (EIN)
+12-3456789
+c Employer's name, address, and ZIP code
+Acme Widgets Inc
+100 Main St, Springfield, IL 62701
+1 Wages, tips, other compensation 85,000.00
+2 Federal income tax withheld 12,000.00
+3 Social security wages 90,000.00
+4 Social security tax withheld 5,580.00
+5 Medicare wages and tips
+90,000.00
+6 Medicare tax withheld 1,305.00
+10 Dependent care benefits 2,500.00
+12a D 5,000.00
+12b Code DD $7,200.00
+13 Statutory employee [ ] Retirement plan [X] Third-party sick pay [ ]
+14 Other SDI 1,200.00
+15 State Employer's state ID number CA 123-4567-8
+16 State wages, tips, etc. 85,000.00
+17 State income tax 4,100.00
+20 Locality name
+"""
+
+
+def test_parses_dollar_boxes():
+    result = parse_w2(SAMPLE_W2)
+    assert result.value("wages") == Decimal("85000.00")
+    assert result.value("federal_withholding") == Decimal("12000.00")
+    assert result.value("social_security_wages") == Decimal("90000.00")
+    assert result.value("medicare_wages") == Decimal("90000.00")
+    assert result.value("dependent_care_benefits") == Decimal("2500.00")
+    assert result.value("state_wages") == Decimal("85000.00")
+    assert result.value("state_income_tax") == Decimal("4100.00")
+    assert "allocated_tips" not in result.fields
+
+
+def test_parses_employer_and_state():
+    result = parse_w2(SAMPLE_W2)
+    assert result.value("employer_ein") == "12-3456789"
+    assert result.value("employer_name") == "Acme Widgets Inc"
+    assert result.value("state") == "CA"
+    assert result.value("state_employer_id") == "123-4567-8"
+    assert result.value("other") == "SDI 1,200.00"
+
+
+def test_parses_box_12_and_13():
+    result = parse_w2(SAMPLE_W2)
+    assert result.value("box_12_a") == {"code": "D", "amount": Decimal("5000.00")}
+    assert result.value("box_12_b") == {"code": "DD", "amount": Decimal("7200.00")}
+    assert result.value("retirement_plan") is True
+    assert result.value("statutory_employee") is False
+
+
+def test_consistent_withholding_raises_confidence():
+    result = parse_w2(SAMPLE_W2)
+    assert result.fields["social_security_tax"].confidence > 0.95
+    assert result.fields["medicare_tax"].confidence > 0.95
+    assert result.warnings == []
+
+
+def test_inconsistent_withholding_is_flagged():
+    text = SAMPLE_W2.replace("4 Social security tax withheld 5,580.00", "4 Social security tax withheld 8,580.00")
+    result = parse_w2(text)
+    assert result.fields["social_security_tax"].confidence <= 0.6
+    assert any("Social security tax" in w for w in result.warnings)
+    assert result.to_dict()["needs_review"] is True
+
+
+def test_label_without_box_number_has_lower_confidence():
+    result = parse_w2("Wages, tips, other compensation: $52,340.17")
+    assert result.value("wages") == Decimal("52340.17")
+    assert result.fields["wages"].confidence == pytest.approx(0.85)
+
+
+def test_adjacent_labels_do_not_bleed_into_amounts():
+    text = "1 Wages, tips, other compensation 2 Federal income tax withheld\n41,000.00 3,900.00"
+    result = parse_w2(text)
+    assert result.value("wages") != Decimal("2.00")
+
+
+def test_missing_wages_warns():
+    result = parse_w2("nothing useful here")
+    assert result.fields == {}
+    assert result.overall_confidence == 0.0
+    assert any("Box 1" in w for w in result.warnings)
+
+
+def test_to_dict_is_json_friendly():
+    data = parse_w2(SAMPLE_W2).to_dict()
+    assert data["form"] == "W-2"
+    assert data["fields"]["wages"]["value"] == 85000.0
+    assert 0 < data["overall_confidence"] <= 1
+
+
+def test_parse_document_dispatch():
+    assert parse_document("w2", SAMPLE_W2).form == "W-2"
+    with pytest.raises(ValueError, match="No parser"):
+        parse_document("1040", SAMPLE_W2)
@@ -0,0 +1,72 @@
+"""Tests for the mid-year withholding checkup."""
+from decimal import Decimal
+
+import pytest
+
+from app.tax_engine.return_calculation import calculate_return
+from app.tax_engine.withholding_checkup import withholding_checkup
+
+
+def make_return(**overrides):
+    tax_return = {
+        "return_id": "ret_1",
+        "tax_year": 2024,
+        "filing_status": "single",
+        "income_sources": [
+            {"id": "inc_1", "type": "wages", "amount": 1000, "withholding": 100},
+            {"id": "inc_2", "type": "interest", "amount": 500},
+        ],
+        "deductions": [],
+    }
+    tax_return.update(overrides)
+    return tax_return
+
+
+def test_projects_from_pay_stub_and_recommends_extra_withholding():
+    # 14 of 26 biweekly paychecks: $3,000 wages and $250 withheld each
+    result = withholding_checkup(make_return(), Decimal("42000"), Decimal("3500"), periods_remaining=12)
+    assert result["periods_paid"] == 14
+    assert result["projected_wages"] == 78000.0
+    assert result["projected_withholding"] == 6500.0
+
+    expected = calculate_return(make_return(income_sources=[
+        {"id": "inc_1", "type": "wages", "amount": 78000, "withholding": 6500},
+        {"id": "inc_2", "type": "interest", "amount": 500},
+    ]))
+    assert result["projected_total_tax"] == expected["total_tax"]
+    assert result["refund_or_owed"] == expected["refund_or_owed"] < 0
+
+    adjustment = result["recommended_adjustment_per_paycheck"]
+    assert adjustment * 12 >= -result["refund_or_owed"] > (adjustment - 1) * 12
+    assert "W-4 line 4(c)" in result["notes"][0]
+
+
+def test_overwithholding_and_target_refund():
+    result = withholding_checkup(make_return(), Decimal("30000"), Decimal("6000"), periods_remaining=6, pay_frequency="monthly")
+    assert result["refund_or_owed"] > 0
+    assert result["recommended_adjustment_per_paycheck"] < 0
+    assert "line 4(b)" in result["notes"][0]
+
+    aimed = withholding_checkup(
+        make_return(), Decimal("30000"), Decimal("6000"), periods_remaining=6,
+        pay_frequency="monthly", target_refund=Decimal("500"),
+    )
+    assert aimed["recommended_adjustment_per_paycheck"] > result["recommended_adjustment_per_paycheck"]
+
+
+def test_no_paychecks_left():
+    result = withholding_checkup(make_return(), Decimal("78000"), Decimal("2000"), periods_remaining=0)
+    assert result["recommended_adjustment_per_paycheck"] is None
+    assert any("estimated payment" in note for note in result["notes"])
+    assert any("underpayment" in note for note in result["notes"])
+
+
+def test_invalid_figures():
+    with pytest.raises(ValueError, match="pay frequency"):
+        withholding_checkup(make_return(), Decimal("1000"), Decimal("0"), 3, pay_frequency="daily")
+    with pytest.raises(ValueError, match="remaining"):
+        withholding_checkup(make_return(), Decimal("1000"), Decimal("0"), 26)
+    with pytest.raises(ValueError, match="withholding"):
+        withholding_checkup(make_return(), Decimal("1000"), Decimal("2000"), 3)
+    with pytest.raises(ValueError, match="Only 2024"):
+        withholding_checkup(make_return(tax_year=2025), Decimal("1000"), Decimal("0"), 3)
@@ -3,7 +3,12 @@
 import { useState } from 'react'
 import Link from 'next/link'
 
-type FilingStatus = 'single' | 'married_joint' | 'married_separate' | 'head_of_household'
+type FilingStatus =
+  | 'single'
+  | 'married_joint'
+  | 'married_separate'
+  | 'head_of_household'
+  | 'qualifying_surviving_spouse'
 
 interface BracketDetail {
   rate: number
@@ -30,6 +35,7 @@ const FILING_STATUSES: { value: FilingStatus; label: string }[] = [
   { value: 'married_joint', label: 'Married Filing Jointly' },
   { value: 'married_separate', label: 'Married Filing Separately' },
   { value: 'head_of_household', label: 'Head of Household' },
+  { value: 'qualifying_surviving_spouse', label: 'Qualifying Surviving Spouse' },
 ]
 
 const ENTITY_TYPES = [